@@ -23,7 +23,35 @@ $libraries = @(
     "library_http",
     "library_fs",
     "library_json",
-    "library_math"
+    "library_math",
+    "library_yaml",
+    "library_toml",
+    "library_csv",
+    "library_xml",
+    "library_regex",
+    "library_encoding",
+    "library_crypto",
+    "library_compress",
+    "library_sqlite",
+    "library_db",
+    "library_kv",
+    "library_net",
+    "library_ws",
+    "library_httpserver",
+    "library_mail",
+    "library_log",
+    "library_config",
+    "library_id",
+    "library_template",
+    "library_term",
+    "library_proc",
+    "library_clipboard",
+    "library_sched",
+    "library_testing",
+    "library_bench",
+    "library_i18n",
+    "library_geo",
+    "library_image"
 )
 
 # create the target directory for release
@@ -0,0 +1,216 @@
+use ::std::collections::HashMap;
+use serde_json::{json, Value as JsonValue};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+// 导入通用库
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::{callback_value, json_value, thrown_message};
+
+// 基准测试命名空间
+// bench 反复调用函数（函数名、函数指针或Lambda）并统计每次调用的耗时，
+// 返回 {"name", "iterations", "min", "mean", "p95", "max", "total"}，时间单位为毫秒；
+// 结果同时被记录下来，table 把记录的结果格式化为对比表格
+// 耗时包含解释器调用函数的开销，预热次数可以让JIT等优化在计时前生效
+mod bench {
+    use super::*;
+
+    // 每次调用返回后，解释器调用的续接函数
+    const RESUME_FUNCTION: &str = "bench::resume";
+    const DEFAULT_ITERATIONS: u64 = 100;
+
+    struct Run {
+        name: String,
+        function: String,
+        warmup: u64,
+        remaining: u64,
+        samples: Vec<Duration>,
+        started: Instant,
+    }
+
+    struct State {
+        // 正在进行的测试，被测函数中可以再调用 bench
+        runs: Vec<Run>,
+        results: Vec<JsonValue>,
+    }
+
+    static STATE: Mutex<State> = Mutex::new(State { runs: Vec::new(), results: Vec::new() });
+
+    fn lock_state() -> MutexGuard<'static, State> {
+        STATE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // 测量函数的耗时，返回统计结果
+    // 参数: name, function, [iterations]（默认为100）, [warmup]（不计时的预热次数，默认为0）
+    pub fn cn_bench(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供名称和要测量的函数".to_string();
+        }
+        let iterations = match args.get(2).map(|n| n.trim().parse::<u64>()) {
+            Some(Ok(n)) if n > 0 => n,
+            None => DEFAULT_ITERATIONS,
+            _ => return format!("错误: 次数必须是正整数: {}", args[2]),
+        };
+        let warmup = match args.get(3).map(|n| n.trim().parse::<u64>()) {
+            Some(Ok(n)) => n,
+            None => 0,
+            Some(Err(_)) => return format!("错误: 预热次数必须是非负整数: {}", args[3]),
+        };
+
+        let run = Run {
+            name: args[0].clone(),
+            function: args[1].clone(),
+            warmup,
+            remaining: iterations,
+            samples: Vec::with_capacity(iterations as usize),
+            started: Instant::now(),
+        };
+        let mut state = lock_state();
+        state.runs.push(run);
+        next_call(state.runs.last_mut().expect("测试存在"))
+    }
+
+    // 每次调用返回后由解释器调用，记录耗时并继续下一次调用；以续接函数注册，脚本无法直接调用
+    // 参数: returned
+    pub fn cn_resume(args: Vec<String>) -> String {
+        let elapsed_at = Instant::now();
+        let mut state = lock_state();
+        let mut run = match state.runs.pop() {
+            Some(run) => run,
+            None => return "错误: 没有正在进行的测试".to_string(),
+        };
+        if let Some(message) = args.first().and_then(|returned| thrown_message(returned)) {
+            return format!("错误: 测试 {} 的函数抛出异常: {}", run.name, message);
+        }
+
+        if run.warmup > 0 {
+            run.warmup -= 1;
+        } else {
+            run.samples.push(elapsed_at - run.started);
+            run.remaining -= 1;
+        }
+        if run.remaining > 0 {
+            state.runs.push(run);
+            return next_call(state.runs.last_mut().expect("测试存在"));
+        }
+
+        let result = statistics(&run.name, &run.samples);
+        state.results.push(result.clone());
+        json_value(&result.to_string())
+    }
+
+    // 把测试结果格式化为对比表格，按平均耗时从快到慢排列，并给出相对最快者的倍数
+    // 参数: [results]（bench 返回的结果数组，默认为已记录的全部结果）
+    pub fn cn_table(args: Vec<String>) -> String {
+        let results = match args.first().filter(|r| !r.trim().is_empty()) {
+            Some(results) => match serde_json::from_str::<JsonValue>(results) {
+                Ok(JsonValue::Array(results)) => results,
+                Ok(result @ JsonValue::Object(_)) => vec![result],
+                _ => return "错误: 结果必须是 bench 返回的结果或其数组".to_string(),
+            },
+            None => lock_state().results.clone(),
+        };
+        format_table(results)
+    }
+
+    // 清空已记录的结果
+    // 参数: 无
+    pub fn cn_clear(_args: Vec<String>) -> String {
+        lock_state().results.clear();
+        "true".to_string()
+    }
+
+    // 开始计时并请求调用被测函数
+    fn next_call(run: &mut Run) -> String {
+        run.started = Instant::now();
+        callback_value(&run.function, "[]", RESUME_FUNCTION)
+    }
+
+    fn statistics(name: &str, samples: &[Duration]) -> JsonValue {
+        let mut millis: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        millis.sort_by(|a, b| a.total_cmp(b));
+        let total: f64 = millis.iter().sum();
+        // 最近秩法：不小于95%样本的最小值
+        let p95_index = ((millis.len() as f64 * 0.95).ceil() as usize).clamp(1, millis.len()) - 1;
+        json!({
+            "name": name,
+            "iterations": millis.len(),
+            "min": millis[0],
+            "mean": total / millis.len() as f64,
+            "p95": millis[p95_index],
+            "max": millis[millis.len() - 1],
+            "total": total,
+        })
+    }
+
+    fn format_table(mut results: Vec<JsonValue>) -> String {
+        let field = |result: &JsonValue, key: &str| result.get(key).and_then(|v| v.as_f64()).unwrap_or(f64::NAN);
+        results.sort_by(|a, b| field(a, "mean").total_cmp(&field(b, "mean")));
+        let fastest = results.first().map(|r| field(r, "mean")).unwrap_or(f64::NAN);
+
+        let header = ["名称", "次数", "平均", "最小", "P95", "相对"].map(String::from).to_vec();
+        let mut rows = vec![header];
+        for result in &results {
+            let name = match result.get("name") {
+                Some(JsonValue::String(name)) => name.clone(),
+                Some(other) => other.to_string(),
+                None => String::new(),
+            };
+            let relative = if fastest > 0.0 { format!("{:.2}x", field(result, "mean") / fastest) } else { "-".to_string() };
+            rows.push(vec![
+                name,
+                result.get("iterations").map(|v| v.to_string()).unwrap_or_default(),
+                format_duration(field(result, "mean")),
+                format_duration(field(result, "min")),
+                format_duration(field(result, "p95")),
+                relative,
+            ]);
+        }
+
+        // 按显示宽度对齐，中文字符占两列
+        let width = |text: &str| text.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum::<usize>();
+        let widths: Vec<usize> = (0..rows[0].len())
+            .map(|col| rows.iter().map(|row| width(&row[col])).max().unwrap_or(0))
+            .collect();
+        let lines: Vec<String> = rows.iter().map(|row| {
+            row.iter().enumerate().map(|(col, cell)| {
+                let padding = " ".repeat(widths[col] - width(cell));
+                // 名称左对齐，数字右对齐
+                if col == 0 { format!("{}{}", cell, padding) } else { format!("{}{}", padding, cell) }
+            }).collect::<Vec<_>>().join("  ").trim_end().to_string()
+        }).collect();
+        lines.join("\n")
+    }
+
+    // 毫秒数按大小选择合适的单位
+    fn format_duration(ms: f64) -> String {
+        if ms.is_nan() {
+            "-".to_string()
+        } else if ms >= 1000.0 {
+            format!("{:.2}s", ms / 1000.0)
+        } else if ms >= 1.0 {
+            format!("{:.2}ms", ms)
+        } else if ms >= 0.001 {
+            format!("{:.2}µs", ms * 1000.0)
+        } else {
+            format!("{:.0}ns", ms * 1_000_000.0)
+        }
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册基准测试命名空间下的函数
+    let bench_ns = registry.namespace("bench");
+    bench_ns.add_function("bench", bench::cn_bench)
+            .add_continuation("resume", bench::cn_resume)
+            .add_function("table", bench::cn_table)
+            .add_function("clear", bench::cn_clear);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
@@ -0,0 +1,128 @@
+use ::std::collections::HashMap;
+use arboard::{Clipboard, ImageData};
+use image::RgbaImage;
+use std::borrow::Cow;
+use std::sync::{Mutex, MutexGuard};
+
+// 导入通用库
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+
+// 剪贴板命名空间
+// 在Linux上剪贴板内容由设置它的进程提供，没有剪贴板管理器时脚本退出后内容会消失
+mod clipboard {
+    use super::*;
+
+    // 打开后一直保留，使脚本运行期间设置的内容保持可用
+    static CLIPBOARD: Mutex<Option<Clipboard>> = Mutex::new(None);
+
+    fn lock_clipboard() -> MutexGuard<'static, Option<Clipboard>> {
+        CLIPBOARD.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn with_clipboard<F>(f: F) -> String
+    where
+        F: FnOnce(&mut Clipboard) -> Result<String, String>,
+    {
+        let mut clipboard = lock_clipboard();
+        if clipboard.is_none() {
+            match Clipboard::new() {
+                Ok(opened) => *clipboard = Some(opened),
+                Err(e) => return format!("错误: 无法访问剪贴板: {}", e),
+            }
+        }
+        match clipboard.as_mut() {
+            Some(clipboard) => f(clipboard).unwrap_or_else(|e| e),
+            None => "错误: 无法访问剪贴板".to_string(),
+        }
+    }
+
+    // 获取剪贴板中的文本，剪贴板中没有文本时返回空字符串
+    // 参数: 无
+    pub fn cn_get_text(_args: Vec<String>) -> String {
+        with_clipboard(|clipboard| match clipboard.get_text() {
+            Ok(text) => Ok(text),
+            Err(arboard::Error::ContentNotAvailable) => Ok(String::new()),
+            Err(e) => Err(format!("错误: 无法读取剪贴板: {}", e)),
+        })
+    }
+
+    // 把文本放入剪贴板
+    // 参数: text
+    pub fn cn_set_text(args: Vec<String>) -> String {
+        let text = args.first().cloned().unwrap_or_default();
+        with_clipboard(|clipboard| {
+            clipboard.set_text(text).map_err(|e| format!("错误: 无法写入剪贴板: {}", e))?;
+            Ok("true".to_string())
+        })
+    }
+
+    // 把剪贴板中的图片保存为PNG文件，剪贴板中没有图片时返回 false
+    // 参数: path
+    pub fn cn_get_image(args: Vec<String>) -> String {
+        let path = match args.first() {
+            Some(path) => path.clone(),
+            None => return "错误: 请提供保存图片的路径".to_string(),
+        };
+        with_clipboard(|clipboard| {
+            let image = match clipboard.get_image() {
+                Ok(image) => image,
+                Err(arboard::Error::ContentNotAvailable) => return Ok("false".to_string()),
+                Err(e) => return Err(format!("错误: 无法读取剪贴板: {}", e)),
+            };
+            let buffer = RgbaImage::from_raw(image.width as u32, image.height as u32, image.bytes.into_owned())
+                .ok_or_else(|| "错误: 剪贴板中的图片数据无效".to_string())?;
+            buffer.save_with_format(&path, image::ImageFormat::Png)
+                .map_err(|e| format!("错误: 无法保存图片 {}: {}", path, e))?;
+            Ok("true".to_string())
+        })
+    }
+
+    // 把PNG图片文件放入剪贴板
+    // 参数: path
+    pub fn cn_set_image(args: Vec<String>) -> String {
+        let path = match args.first() {
+            Some(path) => path,
+            None => return "错误: 请提供图片路径".to_string(),
+        };
+        let image = match image::open(path) {
+            Ok(image) => image.into_rgba8(),
+            Err(e) => return format!("错误: 无法读取图片 {}: {}", path, e),
+        };
+        let data = ImageData {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            bytes: Cow::Owned(image.into_raw()),
+        };
+        with_clipboard(|clipboard| {
+            clipboard.set_image(data).map_err(|e| format!("错误: 无法写入剪贴板: {}", e))?;
+            Ok("true".to_string())
+        })
+    }
+
+    // 清空剪贴板
+    // 参数: 无
+    pub fn cn_clear(_args: Vec<String>) -> String {
+        with_clipboard(|clipboard| {
+            clipboard.clear().map_err(|e| format!("错误: 无法清空剪贴板: {}", e))?;
+            Ok("true".to_string())
+        })
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册剪贴板命名空间下的函数
+    let clipboard_ns = registry.namespace("clipboard");
+    clipboard_ns.add_function("get_text", clipboard::cn_get_text)
+                .add_function("set_text", clipboard::cn_set_text)
+                .add_function("get_image", clipboard::cn_get_image)
+                .add_function("set_image", clipboard::cn_set_image)
+                .add_function("clear", clipboard::cn_clear);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
@@ -0,0 +1,68 @@
+use chrono::{DateTime, TimeZone};
+
+pub use croner::Cron;
+
+/// 一次最多计算的运行次数，避免按脚本传入的次数分配过大的数组
+pub const MAX_COUNT: usize = 10000;
+
+/// 解析cron表达式
+///
+/// 表达式有5个字段（分 时 日 月 周），也可以在最前面加上秒成为6个字段，
+/// 支持 * , - / 以及 L、W、# 和 @daily、@hourly 等别名。
+///
+/// # 参数
+/// * `expr` - cron表达式
+///
+/// # 返回
+/// 解析后的表达式，无效时返回错误信息
+pub fn parse(expr: &str) -> Result<Cron, String> {
+    Cron::new(expr.trim())
+        .with_seconds_optional()
+        .parse()
+        .map_err(|e| format!("错误: 无效的cron表达式 {}: {}", expr, e))
+}
+
+/// 检查cron表达式是否有效，可直接注册为库函数
+///
+/// # 参数
+/// * `args` - expr
+///
+/// # 返回
+/// "true" 或 "false"
+pub fn cn_validate(args: Vec<String>) -> String {
+    args.first().map(|expr| parse(expr).is_ok()).unwrap_or(false).to_string()
+}
+
+/// 解析要计算的运行次数
+///
+/// # 参数
+/// * `arg` - 脚本传入的次数参数
+///
+/// # 返回
+/// 0 到 `MAX_COUNT` 之间的次数，无效时返回错误信息
+pub fn parse_count(arg: Option<&String>) -> Result<usize, String> {
+    match arg.map(|n| n.trim().parse::<usize>()) {
+        Some(Ok(count)) if count <= MAX_COUNT => Ok(count),
+        _ => Err(format!("错误: 次数必须是 0 到 {} 之间的整数", MAX_COUNT)),
+    }
+}
+
+/// 计算 from 之后（不包括 from）的运行时间
+///
+/// # 参数
+/// * `cron` - cron表达式
+/// * `from` - 起始时间，按它的时区计算
+/// * `count` - 次数，超过 `MAX_COUNT` 时只计算 `MAX_COUNT` 次
+///
+/// # 返回
+/// 依次的运行时间，无法计算时返回错误信息
+pub fn next_times<Z: TimeZone>(cron: &Cron, from: &DateTime<Z>, count: usize) -> Result<Vec<DateTime<Z>>, String> {
+    let count = count.min(MAX_COUNT);
+    let mut times: Vec<DateTime<Z>> = Vec::with_capacity(count);
+    for _ in 0..count {
+        let after = times.last().unwrap_or(from);
+        let next = cron.find_next_occurrence(after, false).map_err(|e| format!("错误: 无法计算下一次运行时间: {}", e))?;
+        times.push(next);
+    }
+    Ok(times)
+}
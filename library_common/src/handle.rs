@@ -0,0 +1,91 @@
+use ::std::collections::BTreeMap;
+
+/// 句柄表，用于在多次库函数调用之间保存状态（如打开的文件、迭代器、连接等）
+///
+/// 库函数只能传递字符串，因此有状态的对象保存在句柄表中，
+/// 脚本持有返回的整数句柄，并在后续调用中传回。
+/// 通常与 `static` 的 `Mutex` 一起使用。
+pub struct HandleTable<T> {
+    next_id: u64,
+    items: BTreeMap<u64, T>,
+}
+
+impl<T> HandleTable<T> {
+    /// 创建一个空的句柄表
+    pub const fn new() -> Self {
+        HandleTable {
+            next_id: 1,
+            items: BTreeMap::new(),
+        }
+    }
+
+    /// 保存对象并返回新分配的句柄
+    pub fn insert(&mut self, item: T) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.items.insert(id, item);
+        id
+    }
+
+    /// 根据句柄获取对象的引用
+    pub fn get(&self, id: u64) -> Option<&T> {
+        self.items.get(&id)
+    }
+
+    /// 根据句柄获取对象的可变引用
+    pub fn get_mut(&mut self, id: u64) -> Option<&mut T> {
+        self.items.get_mut(&id)
+    }
+
+    /// 移除句柄并返回对应的对象
+    pub fn remove(&mut self, id: u64) -> Option<T> {
+        self.items.remove(&id)
+    }
+
+    /// 只保留满足条件的对象，移除其余句柄
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(u64, &mut T) -> bool,
+    {
+        self.items.retain(|id, item| keep(*id, item));
+    }
+
+    /// 按句柄顺序遍历所有对象
+    pub fn iter(&self) -> impl Iterator<Item = (u64, &T)> {
+        self.items.iter().map(|(id, item)| (*id, item))
+    }
+
+    /// 按句柄顺序遍历所有对象的可变引用
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (u64, &mut T)> {
+        self.items.iter_mut().map(|(id, item)| (*id, item))
+    }
+
+    /// 检查句柄是否存在
+    pub fn contains(&self, id: u64) -> bool {
+        self.items.contains_key(&id)
+    }
+
+    /// 获取当前保存的对象数量
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// 检查句柄表是否为空
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// 解析脚本传入的句柄参数
+    ///
+    /// # 返回
+    /// 句柄格式正确且存在时返回句柄值
+    pub fn parse_handle(&self, arg: &str) -> Option<u64> {
+        arg.trim().parse::<u64>().ok().filter(|id| self.items.contains_key(id))
+    }
+}
+
+impl<T> Default for HandleTable<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
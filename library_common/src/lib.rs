@@ -1,6 +1,27 @@
 // 导出命名空间模块
 pub mod namespace;
 
+// 导出句柄表模块
+pub mod handle;
+
+// 导出结构化返回值模块
+pub mod value;
+
+// 导出终端颜色模块
+pub mod term;
+
+// 导出用户和用户组查询模块（仅Unix）
+#[cfg(unix)]
+pub mod users;
+
+// 导出进度条模块（需要启用 progress 特性）
+#[cfg(feature = "progress")]
+pub mod progress;
+
+// 导出cron表达式模块（需要启用 cron 特性）
+#[cfg(feature = "cron")]
+pub mod cron;
+
 // 通用字符串处理函数
 pub mod string {
     /// 处理转义字符，将\n, \t等转换为对应的字符
@@ -1,4 +1,5 @@
 use ::std::collections::HashMap;
+use crate::value::{CONTINUATION_PREFIX, RETAINS_CALLBACKS_PREFIX};
 
 // 定义库函数类型
 pub type LibraryFunction = fn(Vec<String>) -> String;
@@ -39,6 +40,49 @@ impl NamespaceBuilder {
         self
     }
     
+    /// 向命名空间中添加会保存回调函数的函数（如注册路由、定时任务）
+    /// 
+    /// 传给这类函数的Lambda在调用结束后不会被解释器释放，可以在之后的调用中回调。
+    /// 
+    /// # 参数
+    /// * `name` - 函数名称（不含命名空间前缀）
+    /// * `func` - 函数指针
+    /// 
+    /// # 返回
+    /// 返回自身引用，支持链式调用
+    pub fn add_retaining_function(&mut self, name: &str, func: LibraryFunction) -> &mut Self {
+        self.add_function(name, func);
+        let marker = format!("{}{}", RETAINS_CALLBACKS_PREFIX, self.full_name(name));
+        self.functions.insert(marker, func);
+        self
+    }
+    
+    /// 向命名空间中添加续接函数
+    /// 
+    /// 续接函数只由解释器在回调协议中调用，脚本无法直接调用；
+    /// 回调请求中的续接函数名仍使用完整名称（如 `server::resume`）。
+    /// 
+    /// # 参数
+    /// * `name` - 函数名称（不含命名空间前缀）
+    /// * `func` - 函数指针
+    /// 
+    /// # 返回
+    /// 返回自身引用，支持链式调用
+    pub fn add_continuation(&mut self, name: &str, func: LibraryFunction) -> &mut Self {
+        let key = format!("{}{}", CONTINUATION_PREFIX, self.full_name(name));
+        self.functions.insert(key, func);
+        self
+    }
+    
+    // 拼接带命名空间前缀的完整函数名
+    fn full_name(&self, name: &str) -> String {
+        if self.namespace.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}::{}", self.namespace, name)
+        }
+    }
+    
     /// 将命名空间中的所有函数注册到目标HashMap
     /// 
     /// # 参数
@@ -127,6 +171,12 @@ pub struct LibraryRegistry {
     direct_functions: HashMap<String, LibraryFunction>,
 }
 
+impl Default for LibraryRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl LibraryRegistry {
     /// 创建一个新的库函数注册器
     pub fn new() -> Self {
@@ -171,7 +221,7 @@ impl LibraryRegistry {
         let mut all_functions = HashMap::new();
         
         // 添加所有命名空间函数
-        for (_, ns_builder) in &self.namespaces {
+        for ns_builder in self.namespaces.values() {
             ns_builder.register_all(&mut all_functions);
         }
         
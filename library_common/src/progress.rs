@@ -0,0 +1,168 @@
+use ::std::sync::atomic::{AtomicBool, Ordering};
+use ::std::sync::{Mutex, MutexGuard, OnceLock};
+use ::std::time::Duration;
+use indicatif::{MultiProgress, ProgressDrawTarget, ProgressStyle};
+
+use crate::handle::HandleTable;
+
+pub use indicatif::ProgressBar;
+
+const SPINNER_INTERVAL: Duration = Duration::from_millis(80);
+const PROGRESS_TEMPLATE: &str = "{prefix}[{bar:40.cyan/blue}] {pos}/{len} ({percent}%) {msg}";
+
+static BARS: Mutex<HandleTable<ProgressBar>> = Mutex::new(HandleTable::new());
+
+/// 进度条暂时隐藏：最近的输出没有以换行结尾（如输入提示），光标停在行中间
+static HIDDEN: Mutex<bool> = Mutex::new(false);
+
+/// 有结束后保留在屏幕上的行，全部进度条结束时需要换行
+static KEPT: AtomicBool = AtomicBool::new(false);
+
+/// 所有进度条显示在同一个 MultiProgress 中，各占一行，写入标准错误，标准错误不是终端时不显示
+static MULTI: OnceLock<MultiProgress> = OnceLock::new();
+
+fn lock_bars() -> MutexGuard<'static, HandleTable<ProgressBar>> {
+    BARS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn lock_hidden() -> MutexGuard<'static, bool> {
+    HIDDEN.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn multi() -> &'static MultiProgress {
+    MULTI.get_or_init(MultiProgress::new)
+}
+
+/// 启动旋转指示器，在后台持续转动直到 `finish`
+///
+/// # 参数
+/// * `message` - 指示器后面显示的消息
+///
+/// # 返回
+/// 新的句柄
+pub fn spinner(message: String) -> u64 {
+    let bar = multi().add(ProgressBar::new_spinner());
+    bar.set_message(message);
+    bar.enable_steady_tick(SPINNER_INTERVAL);
+    lock_bars().insert(bar)
+}
+
+/// 创建进度条
+///
+/// # 参数
+/// * `total` - 总数
+/// * `label` - 显示在进度条前面的标签，可以为空
+/// * `message` - 显示在进度条后面的消息
+///
+/// # 返回
+/// 新的句柄，模板无效时返回错误信息
+pub fn bar(total: u64, label: Option<&str>, message: String) -> Result<u64, String> {
+    let style = match ProgressStyle::with_template(PROGRESS_TEMPLATE) {
+        Ok(style) => style.progress_chars("=> "),
+        Err(e) => return Err(format!("错误: 无效的进度条模板: {}", e)),
+    };
+    let bar = multi().add(ProgressBar::new(total).with_style(style));
+    if let Some(label) = label.filter(|l| !l.is_empty()) {
+        bar.set_prefix(format!("{} ", label));
+    }
+    bar.set_message(message);
+    Ok(lock_bars().insert(bar))
+}
+
+/// 对句柄对应的旋转指示器或进度条执行操作
+///
+/// # 参数
+/// * `handle` - 脚本传入的句柄参数
+/// * `f` - 要执行的操作
+///
+/// # 返回
+/// 操作的结果，句柄无效时返回 None
+pub fn with_bar<R, F: FnOnce(&ProgressBar) -> R>(handle: Option<&String>, f: F) -> Option<R> {
+    let bars = lock_bars();
+    handle.and_then(|arg| bars.parse_handle(arg)).and_then(|id| bars.get(id)).map(f)
+}
+
+/// 结束旋转指示器或进度条，句柄随后失效
+///
+/// # 参数
+/// * `handle` - 脚本传入的句柄参数
+/// * `message` - 提供时保留最后一行并显示该消息
+/// * `keep` - 没有消息时是否保留最后一行，否则清除该行
+///
+/// # 返回
+/// 句柄有效时返回 true
+pub fn finish(handle: Option<&String>, message: Option<String>, keep: bool) -> bool {
+    let bar = {
+        let mut bars = lock_bars();
+        match handle.and_then(|arg| bars.parse_handle(arg)).and_then(|id| bars.remove(id)) {
+            Some(bar) => bar,
+            None => return false,
+        }
+    };
+    let kept = (message.is_some() || keep) && !bar.is_hidden();
+    match message {
+        Some(message) => bar.finish_with_message(message),
+        None if keep => bar.finish(),
+        None => bar.finish_and_clear(),
+    }
+    // 结束后的进度条被释放时，MultiProgress 会把保留的最后一行留在屏幕上
+    drop(bar);
+    if kept {
+        KEPT.store(true, Ordering::Relaxed);
+    }
+    // 全部结束后光标停在保留的最后一行末尾，换行后再输出其他内容
+    let hidden = *lock_hidden();
+    if !hidden && lock_bars().is_empty() && KEPT.swap(false, Ordering::Relaxed) {
+        eprintln!();
+    }
+    true
+}
+
+/// 写出内容时暂时擦除进度条，写完后重新显示
+///
+/// 内容不以换行结尾时（如输入提示），进度条保持隐藏，直到之后的输出以换行结尾或调用 `line_entered`，
+/// 避免进度条画在提示的同一行上。
+///
+/// # 参数
+/// * `bytes` - 要写出的内容，用于判断写完后光标是否在行首
+/// * `write` - 执行写出的函数
+///
+/// # 返回
+/// `write` 的返回值
+pub fn suspend<R, F: FnOnce() -> R>(bytes: &[u8], write: F) -> R {
+    let mut hidden = lock_hidden();
+    if !*hidden && lock_bars().is_empty() {
+        return write();
+    }
+    let ends_line = bytes.last().map(|last| *last == b'\n');
+    if *hidden {
+        let result = write();
+        if ends_line == Some(true) {
+            show(&mut hidden);
+        }
+        return result;
+    }
+    if ends_line == Some(false) {
+        let _ = multi().clear();
+        multi().set_draw_target(ProgressDrawTarget::hidden());
+        *hidden = true;
+        return write();
+    }
+    multi().suspend(write)
+}
+
+/// 用户输入以回车结束，光标回到行首，重新显示隐藏的进度条
+pub fn line_entered() {
+    let mut hidden = lock_hidden();
+    if *hidden {
+        show(&mut hidden);
+    }
+}
+
+fn show(hidden: &mut bool) {
+    multi().set_draw_target(ProgressDrawTarget::stderr());
+    *hidden = false;
+    for (_, bar) in lock_bars().iter() {
+        bar.tick();
+    }
+}
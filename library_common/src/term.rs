@@ -0,0 +1,96 @@
+use ::std::io::{self, IsTerminal};
+use ::std::sync::Mutex;
+
+/// 基本颜色名，依次对应SGR颜色编号的 0 到 7
+const COLORS: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+/// 是否输出颜色，None 表示自动（标准输出是终端且没有设置 NO_COLOR 时输出）
+static COLOR: Mutex<Option<bool>> = Mutex::new(None);
+
+/// 判断当前是否输出颜色
+///
+/// # 返回
+/// 按 `set_color` 的设置返回；自动模式下标准输出是终端且没有设置 NO_COLOR 时返回 true
+pub fn color_enabled() -> bool {
+    let setting = *COLOR.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    setting.unwrap_or_else(|| io::stdout().is_terminal() && ::std::env::var_os("NO_COLOR").is_none())
+}
+
+/// 设置是否输出颜色，可直接注册为库函数
+///
+/// # 参数
+/// * `args` - [mode]（true、false 或 auto，默认 auto）
+///
+/// # 返回
+/// 成功时返回 "true"，模式无效时返回错误信息
+pub fn cn_set_color(args: Vec<String>) -> String {
+    let setting = match args.first().map(|m| m.trim().to_lowercase()).as_deref() {
+        Some("true") => Some(true),
+        Some("false") => Some(false),
+        Some("auto") | Some("") | None => None,
+        Some(other) => return format!("错误: 无效的颜色模式: {}", other),
+    };
+    *COLOR.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = setting;
+    "true".to_string()
+}
+
+/// 检查标准流是否连接到终端，可直接注册为库函数；为 false 时通常是管道或重定向
+///
+/// # 参数
+/// * `args` - [stream]（stdout、stderr 或 stdin，默认 stdout）
+///
+/// # 返回
+/// "true" 或 "false"，标准流名称无效时返回错误信息
+pub fn cn_is_tty(args: Vec<String>) -> String {
+    match args.first().map(|s| s.trim().to_lowercase()).as_deref() {
+        Some("stdout") | Some("") | None => io::stdout().is_terminal().to_string(),
+        Some("stderr") => io::stderr().is_terminal().to_string(),
+        Some("stdin") => io::stdin().is_terminal().to_string(),
+        Some(other) => format!("错误: 未知的标准流: {}", other),
+    }
+}
+
+/// 把样式名转换为SGR参数
+///
+/// # 参数
+/// * `name` - 小写的样式名：颜色名（red、bright_red 等）、bg_颜色名、#rrggbb、bg#rrggbb，
+///   以及 bold、dim、italic、underline、blink、reverse、hidden、strikethrough
+///
+/// # 返回
+/// SGR参数（如 "31"、"38;2;255;0;0"），无法识别时返回 None
+pub fn style_code(name: &str) -> Option<String> {
+    let code = match name {
+        "bold" => "1",
+        "dim" => "2",
+        "italic" => "3",
+        "underline" => "4",
+        "blink" => "5",
+        "reverse" => "7",
+        "hidden" => "8",
+        "strikethrough" => "9",
+        _ => {
+            let (background, color) = match name.strip_prefix("bg_").or_else(|| name.strip_prefix("bg")) {
+                Some(color) => (true, color),
+                None => (false, name),
+            };
+            if let Some(hex) = color.strip_prefix('#') {
+                let rgb = u32::from_str_radix(hex, 16).ok().filter(|_| hex.len() == 6)?;
+                let base = if background { 48 } else { 38 };
+                return Some(format!("{};2;{};{};{}", base, rgb >> 16, (rgb >> 8) & 0xff, rgb & 0xff));
+            }
+            let (bright, color) = match color.strip_prefix("bright_") {
+                Some(color) => (true, color),
+                None => (false, color),
+            };
+            let index = COLORS.iter().position(|c| *c == color)?;
+            let base = match (background, bright) {
+                (false, false) => 30,
+                (false, true) => 90,
+                (true, false) => 40,
+                (true, true) => 100,
+            };
+            return Some((base + index).to_string());
+        },
+    };
+    Some(code.to_string())
+}
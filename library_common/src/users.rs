@@ -0,0 +1,104 @@
+use ::std::ffi::{CStr, CString};
+use ::std::os::raw::c_char;
+
+/// 缓冲区加倍重试的上限
+const MAX_BUFFER: usize = 1 << 20;
+
+/// 用户数据库（passwd）中的一条记录
+pub struct Passwd {
+    pub name: String,
+    pub uid: libc::uid_t,
+    pub gid: libc::gid_t,
+    pub gecos: String,
+    pub home: String,
+    pub shell: String,
+}
+
+/// 按用户名查询用户
+///
+/// # 参数
+/// * `name` - 用户名
+///
+/// # 返回
+/// 用户不存在时返回 None，查询失败时返回错误信息
+pub fn user_by_name(name: &str) -> Result<Option<Passwd>, String> {
+    let name = CString::new(name).map_err(|_| format!("错误: 无效的用户名: {}", name))?;
+    // SAFETY: name 是以 NUL 结尾的字符串，其余指针由 lookup_passwd 提供
+    lookup_passwd(|pwd, buf, len, result| unsafe { libc::getpwnam_r(name.as_ptr(), pwd, buf, len, result) })
+}
+
+/// 按用户ID查询用户
+///
+/// # 参数
+/// * `uid` - 用户ID
+///
+/// # 返回
+/// 用户不存在时返回 None，查询失败时返回错误信息
+pub fn user_by_uid(uid: libc::uid_t) -> Result<Option<Passwd>, String> {
+    // SAFETY: 指针由 lookup_passwd 提供
+    lookup_passwd(|pwd, buf, len, result| unsafe { libc::getpwuid_r(uid, pwd, buf, len, result) })
+}
+
+/// 按用户组ID查询用户组名
+///
+/// 成员很多的用户组需要较大的缓冲区，缓冲区不够时加倍重试。
+///
+/// # 参数
+/// * `gid` - 用户组ID
+///
+/// # 返回
+/// 用户组不存在时返回 None，查询失败时返回错误信息
+pub fn group_name(gid: libc::gid_t) -> Result<Option<String>, String> {
+    let mut buf: Vec<c_char> = vec![0; 1024];
+    loop {
+        // SAFETY: group 是只包含整数和指针的C结构体，全零是有效值
+        let mut grp: libc::group = unsafe { ::std::mem::zeroed() };
+        let mut result = ::std::ptr::null_mut();
+        // SAFETY: 缓冲区的长度与传入的长度一致
+        match unsafe { libc::getgrgid_r(gid, &mut grp, buf.as_mut_ptr(), buf.len(), &mut result) } {
+            libc::ERANGE if buf.len() < MAX_BUFFER => buf.resize(buf.len() * 2, 0),
+            0 if result.is_null() => return Ok(None),
+            // SAFETY: 查询成功时 gr_name 指向 buf 中以 NUL 结尾的字符串
+            0 => return Ok(Some(unsafe { c_string(grp.gr_name) })),
+            code => return Err(format!("错误: 无法查询用户组: {}", ::std::io::Error::from_raw_os_error(code))),
+        }
+    }
+}
+
+// 调用 getpw*_r，缓冲区不够时加倍重试
+fn lookup_passwd<F>(lookup: F) -> Result<Option<Passwd>, String>
+where
+    F: Fn(*mut libc::passwd, *mut c_char, usize, *mut *mut libc::passwd) -> libc::c_int,
+{
+    let mut buf: Vec<c_char> = vec![0; 1024];
+    loop {
+        // SAFETY: passwd 是只包含整数和指针的C结构体，全零是有效值
+        let mut pwd: libc::passwd = unsafe { ::std::mem::zeroed() };
+        let mut result = ::std::ptr::null_mut();
+        match lookup(&mut pwd, buf.as_mut_ptr(), buf.len(), &mut result) {
+            libc::ERANGE if buf.len() < MAX_BUFFER => buf.resize(buf.len() * 2, 0),
+            0 if result.is_null() => return Ok(None),
+            // SAFETY: 查询成功时各字段指向 buf 中以 NUL 结尾的字符串
+            0 => return Ok(Some(unsafe {
+                Passwd {
+                    name: c_string(pwd.pw_name),
+                    uid: pwd.pw_uid,
+                    gid: pwd.pw_gid,
+                    gecos: c_string(pwd.pw_gecos),
+                    home: c_string(pwd.pw_dir),
+                    shell: c_string(pwd.pw_shell),
+                }
+            })),
+            code => return Err(format!("错误: 无法查询用户: {}", ::std::io::Error::from_raw_os_error(code))),
+        }
+    }
+}
+
+// SAFETY: 调用者保证指针为空或指向以 NUL 结尾的字符串
+unsafe fn c_string(ptr: *const c_char) -> String {
+    if ptr.is_null() {
+        String::new()
+    } else {
+        CStr::from_ptr(ptr).to_string_lossy().into_owned()
+    }
+}
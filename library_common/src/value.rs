@@ -0,0 +1,141 @@
+use serde_json::Value as JsonValue;
+
+/// 结构化返回值前缀
+///
+/// 库函数的返回值以该前缀开头时，解释器会把前缀后面的内容按JSON解析，
+/// 转换为原生的值：对象转换为 Map，数组转换为 Array，null 转换为空值，
+/// 其中的整数在i32范围内转换为 Int，在i64范围内转换为 Long，超出i64范围时保留为字符串。
+/// 没有前缀的返回值仍按原规则转换为整数、浮点数、布尔值或字符串。
+/// 反方向上，脚本传给库函数的 Map 和 Array 参数是不带前缀的JSON文本。
+pub const JSON_VALUE_PREFIX: &str = "\u{1}cn-json:";
+
+/// 将JSON文本包装为结构化返回值
+///
+/// # 参数
+/// * `json` - 合法的JSON文本
+///
+/// # 返回
+/// 带有结构化前缀的返回值字符串
+pub fn json_value(json: &str) -> String {
+    format!("{}{}", JSON_VALUE_PREFIX, json)
+}
+
+/// 回调请求前缀
+///
+/// 库函数的返回值以该前缀开头时，解释器会调用脚本中的函数，
+/// 再把函数的返回值作为唯一参数传给同一个库中的续接函数；
+/// 续接函数的返回值按同样的规则处理，直到返回普通值为止。
+/// 这样库可以在不持有解释器的情况下让脚本处理事件（如HTTP请求）。
+/// 传给库的Lambda在最外层的库函数调用结束后释放，
+/// 需要在之后的调用中使用回调的函数（如注册路由）应以 `add_retaining_function` 注册。
+pub const CALLBACK_PREFIX: &str = "\u{1}cn-call:";
+
+/// 续接函数名前缀
+///
+/// 以 `add_continuation` 注册的续接函数以该前缀加完整名称登记，
+/// 解释器只在回调协议中调用它们，脚本无法直接调用。
+pub const CONTINUATION_PREFIX: &str = "\u{1}cn-resume:";
+
+/// 保存回调的函数的标记前缀
+///
+/// 以 `add_retaining_function` 注册的函数会额外以该前缀加完整名称登记，
+/// 解释器据此保留传给这些函数的Lambda，而不是在调用结束后释放。
+pub const RETAINS_CALLBACKS_PREFIX: &str = "\u{1}cn-retain:";
+
+/// 请求解释器调用脚本函数
+///
+/// # 参数
+/// * `function` - 脚本函数名，也可以是函数指针参数的字符串形式（如 `*fn(handler)`）
+/// * `args_json` - 传给函数的参数，合法的JSON数组文本，元素按结构化返回值的规则转换为原生值
+/// * `resume` - 续接函数的完整名称（含命名空间，如 `server::resume`）
+///
+/// # 返回
+/// 带有回调前缀的返回值字符串
+pub fn callback_value(function: &str, args_json: &str, resume: &str) -> String {
+    format!("{}{}\u{1}{}\u{1}{}", CALLBACK_PREFIX, resume, function, args_json)
+}
+
+/// 回调异常前缀
+///
+/// 回调的脚本函数抛出未捕获的异常时，续接函数收到的参数以该前缀开头，
+/// 前缀后面是异常信息；库可以据此区分正常返回值和异常。
+pub const CALLBACK_THROW_PREFIX: &str = "\u{1}cn-throw:";
+
+/// 取出续接函数参数中的异常信息
+///
+/// # 参数
+/// * `returned` - 续接函数收到的参数
+///
+/// # 返回
+/// 回调函数抛出异常时返回异常信息，正常返回时返回 None
+pub fn thrown_message(returned: &str) -> Option<&str> {
+    returned.strip_prefix(CALLBACK_THROW_PREFIX)
+}
+
+/// 取出续接函数参数中的结构化值
+///
+/// 回调函数返回 Map 或数组时，续接函数收到带有结构化前缀的JSON文本，
+/// 与内容恰好是JSON的字符串返回值区分开。
+///
+/// # 参数
+/// * `returned` - 续接函数收到的参数
+///
+/// # 返回
+/// 回调函数返回 Map 或数组时返回JSON文本，否则返回 None
+pub fn returned_json(returned: &str) -> Option<&str> {
+    returned.strip_prefix(JSON_VALUE_PREFIX)
+}
+
+/// 将JSON值序列化为紧凑的JSON文本
+///
+/// # 参数
+/// * `value` - 要序列化的值
+///
+/// # 返回
+/// JSON文本，失败时返回错误信息
+pub fn serialize(value: &JsonValue) -> String {
+    match serde_json::to_string(value) {
+        Ok(result) => result,
+        Err(e) => format!("错误: 序列化结果失败: {}", e),
+    }
+}
+
+/// 路径中的一段：对象的键或数组索引
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// 解析 get_value、set_value 等函数使用的路径
+///
+/// 支持 "a.b.[0].c" 和 "a.b[0].c" 两种写法，空路径表示根节点。
+///
+/// # 参数
+/// * `path` - 路径字符串
+///
+/// # 返回
+/// 依次的路径段，索引格式无效时返回错误信息
+pub fn parse_path(path: &str) -> Result<Vec<PathSegment>, String> {
+    let mut segments = Vec::new();
+    for part in path.split('.').filter(|p| !p.is_empty()) {
+        let (key, mut rest) = match part.find('[') {
+            Some(pos) => (&part[..pos], &part[pos..]),
+            None => (part, ""),
+        };
+        if !key.is_empty() {
+            segments.push(PathSegment::Key(key.to_string()));
+        }
+        while !rest.is_empty() {
+            let end = match (rest.starts_with('['), rest.find(']')) {
+                (true, Some(end)) => end,
+                _ => return Err(format!("错误: 无效的数组索引格式: {}", part)),
+            };
+            match rest[1..end].parse::<usize>() {
+                Ok(idx) => segments.push(PathSegment::Index(idx)),
+                Err(_) => return Err(format!("错误: 无效的数组索引格式: {}", part)),
+            }
+            rest = &rest[end + 1..];
+        }
+    }
+    Ok(segments)
+}
@@ -0,0 +1,644 @@
+use ::std::collections::HashMap;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use flate2::Compression;
+use serde_json::{Map, Value as JsonValue};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+// 导入通用库
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::json_value;
+
+// 压缩格式
+#[derive(Clone, Copy)]
+enum Format {
+    Gzip,
+    Deflate,
+    Zstd,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Result<Format, String> {
+        match name.trim().to_lowercase().as_str() {
+            "gzip" | "gz" => Ok(Format::Gzip),
+            "deflate" | "zlib" => Ok(Format::Deflate),
+            "zstd" | "zst" => Ok(Format::Zstd),
+            other => Err(format!("错误: 不支持的压缩格式: {}", other)),
+        }
+    }
+
+    // 根据扩展名推断格式，无法识别时使用 gzip
+    fn from_path(path: &str) -> Format {
+        match Path::new(path).extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref() {
+            Some("zst") | Some("zstd") => Format::Zstd,
+            Some("zz") | Some("deflate") => Format::Deflate,
+            _ => Format::Gzip,
+        }
+    }
+
+    fn compress(self, data: &[u8], level: Option<i32>) -> io::Result<Vec<u8>> {
+        match self {
+            Format::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate_level(level));
+                encoder.write_all(data)?;
+                encoder.finish()
+            },
+            Format::Deflate => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), flate_level(level));
+                encoder.write_all(data)?;
+                encoder.finish()
+            },
+            Format::Zstd => zstd::encode_all(data, level.unwrap_or(zstd::DEFAULT_COMPRESSION_LEVEL)),
+        }
+    }
+
+    fn decompress(self, data: &[u8]) -> io::Result<Vec<u8>> {
+        let mut output = Vec::new();
+        match self {
+            Format::Gzip => {
+                GzDecoder::new(data).read_to_end(&mut output)?;
+            },
+            Format::Deflate => {
+                ZlibDecoder::new(data).read_to_end(&mut output)?;
+            },
+            Format::Zstd => output = zstd::decode_all(data)?,
+        }
+        Ok(output)
+    }
+}
+
+fn flate_level(level: Option<i32>) -> Compression {
+    match level {
+        Some(level) => Compression::new(level.clamp(0, 9) as u32),
+        None => Compression::default(),
+    }
+}
+
+fn parse_level(arg: Option<&String>) -> Result<Option<i32>, String> {
+    match arg.map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        Some(level) => level.parse::<i32>().map(Some).map_err(|_| format!("错误: 无效的压缩级别: {}", level)),
+        None => Ok(None),
+    }
+}
+
+// 压缩命名空间
+// 压缩后的数据以 Base64 字符串表示
+mod compress {
+    use super::*;
+
+    fn compress_text(format: Format, args: &[String]) -> String {
+        if args.is_empty() {
+            return "错误: 请提供要压缩的文本".to_string();
+        }
+        let level = match parse_level(args.get(1)) {
+            Ok(level) => level,
+            Err(e) => return e,
+        };
+        match format.compress(args[0].as_bytes(), level) {
+            Ok(data) => STANDARD.encode(data),
+            Err(e) => format!("错误: 压缩失败: {}", e),
+        }
+    }
+
+    fn decompress_text(format: Format, args: &[String]) -> String {
+        if args.is_empty() {
+            return "错误: 请提供要解压的数据".to_string();
+        }
+        let data = match STANDARD.decode(args[0].trim()) {
+            Ok(data) => data,
+            Err(e) => return format!("错误: 无效的Base64数据: {}", e),
+        };
+        match format.decompress(&data).map(String::from_utf8) {
+            Ok(Ok(text)) => text,
+            Ok(Err(_)) => "错误: 解压结果不是有效的UTF-8文本，二进制数据请使用 decompress_file".to_string(),
+            Err(e) => format!("错误: 解压失败: {}", e),
+        }
+    }
+
+    // gzip 压缩文本，返回 Base64 字符串
+    // 参数: text, [level]（0到9，默认6）
+    pub fn cn_gzip(args: Vec<String>) -> String {
+        compress_text(Format::Gzip, &args)
+    }
+
+    // 解压 gzip 数据
+    // 参数: data（Base64）
+    pub fn cn_gunzip(args: Vec<String>) -> String {
+        decompress_text(Format::Gzip, &args)
+    }
+
+    // deflate（zlib 格式，与 HTTP 的 deflate 编码相同）压缩文本，返回 Base64 字符串
+    // 参数: text, [level]（0到9，默认6）
+    pub fn cn_deflate(args: Vec<String>) -> String {
+        compress_text(Format::Deflate, &args)
+    }
+
+    // 解压 deflate 数据
+    // 参数: data（Base64）
+    pub fn cn_inflate(args: Vec<String>) -> String {
+        decompress_text(Format::Deflate, &args)
+    }
+
+    // zstd 压缩文本，返回 Base64 字符串
+    // 参数: text, [level]（1到22，默认3）
+    pub fn cn_zstd(args: Vec<String>) -> String {
+        compress_text(Format::Zstd, &args)
+    }
+
+    // 解压 zstd 数据
+    // 参数: data（Base64）
+    pub fn cn_unzstd(args: Vec<String>) -> String {
+        decompress_text(Format::Zstd, &args)
+    }
+
+    // 压缩文件，返回压缩后的字节数
+    // 参数: source, target, [format]（gzip、deflate、zstd，默认根据目标文件的扩展名推断）, [level]
+    pub fn cn_compress_file(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供源文件和目标文件".to_string();
+        }
+        let format = match args.get(2).filter(|f| !f.trim().is_empty()) {
+            Some(name) => match Format::from_name(name) {
+                Ok(format) => format,
+                Err(e) => return e,
+            },
+            None => Format::from_path(&args[1]),
+        };
+        let level = match parse_level(args.get(3)) {
+            Ok(level) => level,
+            Err(e) => return e,
+        };
+        let data = match fs::read(&args[0]) {
+            Ok(data) => data,
+            Err(e) => return format!("错误: 无法读取文件 {}: {}", args[0], e),
+        };
+        let compressed = match format.compress(&data, level) {
+            Ok(compressed) => compressed,
+            Err(e) => return format!("错误: 压缩失败: {}", e),
+        };
+        match fs::write(&args[1], &compressed) {
+            Ok(()) => compressed.len().to_string(),
+            Err(e) => format!("错误: 无法写入文件 {}: {}", args[1], e),
+        }
+    }
+
+    // 解压文件，返回解压后的字节数
+    // 参数: source, target, [format]（默认根据源文件的扩展名推断）
+    pub fn cn_decompress_file(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供源文件和目标文件".to_string();
+        }
+        let format = match args.get(2).filter(|f| !f.trim().is_empty()) {
+            Some(name) => match Format::from_name(name) {
+                Ok(format) => format,
+                Err(e) => return e,
+            },
+            None => Format::from_path(&args[0]),
+        };
+        let data = match fs::read(&args[0]) {
+            Ok(data) => data,
+            Err(e) => return format!("错误: 无法读取文件 {}: {}", args[0], e),
+        };
+        let decompressed = match format.decompress(&data) {
+            Ok(decompressed) => decompressed,
+            Err(e) => return format!("错误: 解压失败: {}", e),
+        };
+        match fs::write(&args[1], &decompressed) {
+            Ok(()) => decompressed.len().to_string(),
+            Err(e) => format!("错误: 无法写入文件 {}: {}", args[1], e),
+        }
+    }
+}
+
+// ZIP归档命名空间
+mod zip_ns {
+    use super::*;
+    use zip::write::FileOptions;
+    use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+    // 创建ZIP归档，目录会被递归添加，条目名以源路径的最后一级开始
+    // 返回添加的文件数
+    // 参数: archive, sources（路径或路径数组）, [level]（0到9，0表示只存储不压缩）
+    pub fn cn_create(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供归档路径和要添加的文件".to_string();
+        }
+        let sources: Vec<String> = match serde_json::from_str::<Vec<String>>(&args[1]) {
+            Ok(sources) => sources,
+            Err(_) => vec![args[1].clone()],
+        };
+        let level = match parse_level(args.get(2)) {
+            Ok(level) => level,
+            Err(e) => return e,
+        };
+        let options = match level {
+            Some(0) => FileOptions::default().compression_method(CompressionMethod::Stored),
+            Some(level) => FileOptions::default().compression_level(Some(level.clamp(1, 9))),
+            None => FileOptions::default(),
+        };
+
+        let file = match File::create(&args[0]) {
+            Ok(file) => file,
+            Err(e) => return format!("错误: 无法创建文件 {}: {}", args[0], e),
+        };
+        let mut writer = ZipWriter::new(file);
+        let mut count = 0;
+        for source in &sources {
+            let path = Path::new(source);
+            let name = match path.file_name() {
+                Some(name) => PathBuf::from(name),
+                None => return format!("错误: 无效的路径: {}", source),
+            };
+            if let Err(e) = add_path(&mut writer, path, &name, options, &mut count) {
+                return format!("错误: 添加 {} 失败: {}", source, e);
+            }
+        }
+        match writer.finish() {
+            Ok(_) => count.to_string(),
+            Err(e) => format!("错误: 写入归档失败: {}", e),
+        }
+    }
+
+    fn add_path(writer: &mut ZipWriter<File>, path: &Path, name: &Path, options: FileOptions, count: &mut usize) -> Result<(), String> {
+        // ZIP 中的路径始终使用 / 分隔
+        let entry_name = name.components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect::<Vec<String>>()
+            .join("/");
+        if path.is_dir() {
+            writer.add_directory(format!("{}/", entry_name), options).map_err(|e| e.to_string())?;
+            let mut entries: Vec<PathBuf> = fs::read_dir(path)
+                .map_err(|e| e.to_string())?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .collect();
+            entries.sort();
+            for entry in entries {
+                if let Some(file_name) = entry.file_name() {
+                    add_path(writer, &entry, &name.join(file_name), options, count)?;
+                }
+            }
+        } else {
+            writer.start_file(entry_name, options).map_err(|e| e.to_string())?;
+            let mut file = File::open(path).map_err(|e| e.to_string())?;
+            io::copy(&mut file, writer).map_err(|e| e.to_string())?;
+            *count += 1;
+        }
+        Ok(())
+    }
+
+    fn open_archive(path: &str) -> Result<ZipArchive<File>, String> {
+        let file = File::open(path).map_err(|e| format!("错误: 无法打开文件 {}: {}", path, e))?;
+        ZipArchive::new(file).map_err(|e| format!("错误: 无效的ZIP归档: {}", e))
+    }
+
+    // 解压ZIP归档到目录，返回解压的文件数；指向目标目录之外的条目会被拒绝
+    // 参数: archive, target_dir
+    pub fn cn_extract(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供归档路径和目标目录".to_string();
+        }
+        let mut archive = match open_archive(&args[0]) {
+            Ok(archive) => archive,
+            Err(e) => return e,
+        };
+        let target = Path::new(&args[1]);
+        let mut count = 0;
+        for i in 0..archive.len() {
+            let mut entry = match archive.by_index(i) {
+                Ok(entry) => entry,
+                Err(e) => return format!("错误: 读取归档失败: {}", e),
+            };
+            let relative = match entry.enclosed_name() {
+                Some(relative) => relative.to_path_buf(),
+                None => return format!("错误: 不安全的条目路径: {}", entry.name()),
+            };
+            let output = target.join(relative);
+            let result = if entry.is_dir() {
+                fs::create_dir_all(&output)
+            } else {
+                output.parent().map(fs::create_dir_all).unwrap_or(Ok(()))
+                    .and_then(|_| File::create(&output))
+                    .and_then(|mut file| io::copy(&mut entry, &mut file))
+                    .map(|_| count += 1)
+            };
+            if let Err(e) = result {
+                return format!("错误: 解压 {} 失败: {}", entry.name(), e);
+            }
+        }
+        count.to_string()
+    }
+
+    // 列出ZIP归档中的条目，返回 Map 数组：{"name", "size", "compressed_size", "is_dir"}
+    // 参数: archive
+    pub fn cn_list(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供归档路径".to_string();
+        }
+        let mut archive = match open_archive(&args[0]) {
+            Ok(archive) => archive,
+            Err(e) => return e,
+        };
+        let mut entries = Vec::new();
+        for i in 0..archive.len() {
+            let entry = match archive.by_index_raw(i) {
+                Ok(entry) => entry,
+                Err(e) => return format!("错误: 读取归档失败: {}", e),
+            };
+            let mut obj = Map::new();
+            obj.insert("name".to_string(), JsonValue::String(entry.name().to_string()));
+            obj.insert("size".to_string(), JsonValue::from(entry.size()));
+            obj.insert("compressed_size".to_string(), JsonValue::from(entry.compressed_size()));
+            obj.insert("is_dir".to_string(), JsonValue::Bool(entry.is_dir()));
+            entries.push(JsonValue::Object(obj));
+        }
+        json_value(&JsonValue::Array(entries).to_string())
+    }
+
+    // 读取ZIP归档中单个文件的文本内容
+    // 参数: archive, name
+    pub fn cn_read(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供归档路径和条目名称".to_string();
+        }
+        let mut archive = match open_archive(&args[0]) {
+            Ok(archive) => archive,
+            Err(e) => return e,
+        };
+        let mut entry = match archive.by_name(&args[1]) {
+            Ok(entry) => entry,
+            Err(_) => return format!("错误: 条目不存在: {}", args[1]),
+        };
+        let mut content = String::new();
+        match entry.read_to_string(&mut content) {
+            Ok(_) => content,
+            Err(e) => format!("错误: 读取条目失败: {}", e),
+        }
+    }
+}
+
+// TAR归档命名空间
+// 支持未压缩的 .tar、gzip 压缩的 .tar.gz / .tgz 和 zstd 压缩的 .tar.zst
+mod tar_ns {
+    use super::*;
+    use tar::{Archive, Builder, EntryType};
+
+    // 归档的压缩方式
+    #[derive(Clone, Copy)]
+    enum Compression {
+        None,
+        Gzip,
+        Zstd,
+    }
+
+    impl Compression {
+        fn from_name(name: &str) -> Result<Compression, String> {
+            match name.trim().to_lowercase().as_str() {
+                "none" | "tar" => Ok(Compression::None),
+                "gzip" | "gz" => Ok(Compression::Gzip),
+                "zstd" | "zst" => Ok(Compression::Zstd),
+                other => Err(format!("错误: 不支持的压缩格式: {}", other)),
+            }
+        }
+
+        // 根据扩展名推断压缩方式
+        fn from_path(path: &str) -> Compression {
+            let name = path.to_lowercase();
+            if name.ends_with(".gz") || name.ends_with(".tgz") {
+                Compression::Gzip
+            } else if name.ends_with(".zst") || name.ends_with(".tzst") {
+                Compression::Zstd
+            } else {
+                Compression::None
+            }
+        }
+    }
+
+    // 创建TAR归档，目录会被递归添加，条目名以源路径的最后一级开始
+    // 返回添加的文件数
+    // 参数: archive, sources（路径或路径数组）, [compression]（none、gzip、zstd，默认根据归档的扩展名推断）
+    pub fn cn_create(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供归档路径和要添加的文件".to_string();
+        }
+        let sources: Vec<String> = match serde_json::from_str::<Vec<String>>(&args[1]) {
+            Ok(sources) => sources,
+            Err(_) => vec![args[1].clone()],
+        };
+        let compression = match args.get(2).filter(|c| !c.trim().is_empty()) {
+            Some(name) => match Compression::from_name(name) {
+                Ok(compression) => compression,
+                Err(e) => return e,
+            },
+            None => Compression::from_path(&args[0]),
+        };
+
+        let file = match File::create(&args[0]) {
+            Ok(file) => file,
+            Err(e) => return format!("错误: 无法创建文件 {}: {}", args[0], e),
+        };
+        let result = match compression {
+            Compression::None => write_archive(file, &sources).map(|(_, count)| count),
+            Compression::Gzip => write_archive(GzEncoder::new(file, flate2::Compression::default()), &sources)
+                .and_then(|(encoder, count)| encoder.finish().map(|_| count).map_err(|e| format!("错误: 写入归档失败: {}", e))),
+            Compression::Zstd => zstd::Encoder::new(file, zstd::DEFAULT_COMPRESSION_LEVEL)
+                .map_err(|e| format!("错误: 写入归档失败: {}", e))
+                .and_then(|encoder| write_archive(encoder, &sources))
+                .and_then(|(encoder, count)| encoder.finish().map(|_| count).map_err(|e| format!("错误: 写入归档失败: {}", e))),
+        };
+        match result {
+            Ok(count) => count.to_string(),
+            Err(e) => e,
+        }
+    }
+
+    fn write_archive<W: Write>(writer: W, sources: &[String]) -> Result<(W, usize), String> {
+        let mut builder = Builder::new(writer);
+        let mut count = 0;
+        for source in sources {
+            let path = Path::new(source);
+            let name = match path.file_name() {
+                Some(name) => PathBuf::from(name),
+                None => return Err(format!("错误: 无效的路径: {}", source)),
+            };
+            add_path(&mut builder, path, &name, &mut count).map_err(|e| format!("错误: 添加 {} 失败: {}", source, e))?;
+        }
+        builder.into_inner().map(|writer| (writer, count)).map_err(|e| format!("错误: 写入归档失败: {}", e))
+    }
+
+    fn add_path<W: Write>(builder: &mut Builder<W>, path: &Path, name: &Path, count: &mut usize) -> io::Result<()> {
+        if path.is_dir() {
+            builder.append_dir(name, path)?;
+            let mut entries: Vec<PathBuf> = fs::read_dir(path)?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .collect();
+            entries.sort();
+            for entry in entries {
+                if let Some(file_name) = entry.file_name() {
+                    add_path(builder, &entry, &name.join(file_name), count)?;
+                }
+            }
+        } else {
+            builder.append_path_with_name(path, name)?;
+            *count += 1;
+        }
+        Ok(())
+    }
+
+    // 打开归档，按文件头判断压缩方式
+    fn open_archive(path: &str) -> Result<Archive<Box<dyn Read>>, String> {
+        let mut file = File::open(path).map_err(|e| format!("错误: 无法打开文件 {}: {}", path, e))?;
+        let mut magic = [0u8; 4];
+        let read = file.read(&mut magic).map_err(|e| format!("错误: 无法读取文件 {}: {}", path, e))?;
+        let file = io::Cursor::new(magic[..read].to_vec()).chain(file);
+        let reader: Box<dyn Read> = if magic[..read].starts_with(&[0x1f, 0x8b]) {
+            Box::new(GzDecoder::new(file))
+        } else if magic[..read] == [0x28, 0xb5, 0x2f, 0xfd] {
+            Box::new(zstd::Decoder::new(file).map_err(|e| format!("错误: 无效的zstd数据: {}", e))?)
+        } else {
+            Box::new(file)
+        };
+        Ok(Archive::new(reader))
+    }
+
+    // 解压TAR归档到目录，返回解压的文件数；指向目标目录之外的条目会被拒绝
+    // 参数: archive, target_dir
+    pub fn cn_extract(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供归档路径和目标目录".to_string();
+        }
+        let mut archive = match open_archive(&args[0]) {
+            Ok(archive) => archive,
+            Err(e) => return e,
+        };
+        let target = Path::new(&args[1]);
+        if let Err(e) = fs::create_dir_all(target) {
+            return format!("错误: 无法创建目录 {}: {}", args[1], e);
+        }
+        let entries = match archive.entries() {
+            Ok(entries) => entries,
+            Err(e) => return format!("错误: 无效的TAR归档: {}", e),
+        };
+        let mut count = 0;
+        for entry in entries {
+            let mut entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => return format!("错误: 读取归档失败: {}", e),
+            };
+            let name = entry.path().map(|p| p.to_string_lossy().into_owned()).unwrap_or_default();
+            let is_file = entry.header().entry_type().is_file();
+            match entry.unpack_in(target) {
+                Ok(true) => {
+                    if is_file {
+                        count += 1;
+                    }
+                },
+                Ok(false) => return format!("错误: 不安全的条目路径: {}", name),
+                Err(e) => return format!("错误: 解压 {} 失败: {}", name, e),
+            }
+        }
+        count.to_string()
+    }
+
+    // 列出TAR归档中的条目，返回 Map 数组：{"name", "size", "is_dir", "mode", "mtime"}
+    // 参数: archive
+    pub fn cn_list(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供归档路径".to_string();
+        }
+        let mut archive = match open_archive(&args[0]) {
+            Ok(archive) => archive,
+            Err(e) => return e,
+        };
+        let entries = match archive.entries() {
+            Ok(entries) => entries,
+            Err(e) => return format!("错误: 无效的TAR归档: {}", e),
+        };
+        let mut list = Vec::new();
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => return format!("错误: 读取归档失败: {}", e),
+            };
+            let header = entry.header();
+            let mut obj = Map::new();
+            obj.insert("name".to_string(), JsonValue::String(entry.path().map(|p| p.to_string_lossy().into_owned()).unwrap_or_default()));
+            obj.insert("size".to_string(), JsonValue::from(entry.size()));
+            obj.insert("is_dir".to_string(), JsonValue::Bool(header.entry_type() == EntryType::Directory));
+            obj.insert("mode".to_string(), JsonValue::String(format!("{:o}", header.mode().unwrap_or(0) & 0o7777)));
+            obj.insert("mtime".to_string(), JsonValue::from(header.mtime().unwrap_or(0)));
+            list.push(JsonValue::Object(obj));
+        }
+        json_value(&JsonValue::Array(list).to_string())
+    }
+
+    // 读取TAR归档中单个文件的文本内容
+    // 参数: archive, name
+    pub fn cn_read(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供归档路径和条目名称".to_string();
+        }
+        let mut archive = match open_archive(&args[0]) {
+            Ok(archive) => archive,
+            Err(e) => return e,
+        };
+        let entries = match archive.entries() {
+            Ok(entries) => entries,
+            Err(e) => return format!("错误: 无效的TAR归档: {}", e),
+        };
+        let wanted = args[1].trim_start_matches("./");
+        for entry in entries {
+            let mut entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => return format!("错误: 读取归档失败: {}", e),
+            };
+            let matches = entry.path().map(|p| p.to_string_lossy().trim_start_matches("./") == wanted).unwrap_or(false);
+            if matches && entry.header().entry_type().is_file() {
+                let mut content = String::new();
+                return match entry.read_to_string(&mut content) {
+                    Ok(_) => content,
+                    Err(e) => format!("错误: 读取条目失败: {}", e),
+                };
+            }
+        }
+        format!("错误: 条目不存在: {}", args[1])
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册压缩命名空间下的函数
+    let compress_ns = registry.namespace("compress");
+    compress_ns.add_function("gzip", compress::cn_gzip)
+               .add_function("gunzip", compress::cn_gunzip)
+               .add_function("deflate", compress::cn_deflate)
+               .add_function("inflate", compress::cn_inflate)
+               .add_function("zstd", compress::cn_zstd)
+               .add_function("unzstd", compress::cn_unzstd)
+               .add_function("compress_file", compress::cn_compress_file)
+               .add_function("decompress_file", compress::cn_decompress_file);
+
+    // 注册ZIP归档命名空间下的函数
+    let zip_ns = registry.namespace("zip");
+    zip_ns.add_function("create", zip_ns::cn_create)
+          .add_function("extract", zip_ns::cn_extract)
+          .add_function("list", zip_ns::cn_list)
+          .add_function("read", zip_ns::cn_read);
+
+    // 注册TAR归档命名空间下的函数
+    let tar_ns = registry.namespace("tar");
+    tar_ns.add_function("create", tar_ns::cn_create)
+          .add_function("extract", tar_ns::cn_extract)
+          .add_function("list", tar_ns::cn_list)
+          .add_function("read", tar_ns::cn_read);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
@@ -0,0 +1,458 @@
+use ::std::collections::HashMap;
+use serde_json::{Map, Value as JsonValue};
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// 导入通用库
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::json_value;
+
+// 配置命名空间
+mod config {
+    use super::*;
+
+    // 加载 .env 文件到环境变量，返回设置的变量数量；已存在的环境变量默认不覆盖
+    // 支持 export 前缀、# 注释、单引号（原样）、双引号（转义和多行）以及 ${VAR} 变量引用
+    // 参数: [path]（默认 .env）, [override]（为 true 时覆盖已存在的环境变量）
+    pub fn cn_load_dotenv(args: Vec<String>) -> String {
+        let path = args.first().filter(|p| !p.is_empty()).map(|p| p.as_str()).unwrap_or(".env");
+        let overwrite = args.get(1).map(|o| o.trim() == "true").unwrap_or(false);
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) => return format!("错误: 无法读取 {}: {}", path, e),
+        };
+        let vars = match parse_dotenv(&text) {
+            Ok(vars) => vars,
+            Err(e) => return e,
+        };
+
+        let mut count = 0;
+        for (key, value) in vars {
+            if overwrite || env::var_os(&key).is_none() {
+                env::set_var(&key, value);
+                count += 1;
+            }
+        }
+        count.to_string()
+    }
+
+    // 解析 .env 格式的文本，返回 Map，不修改环境变量
+    // 参数: text
+    pub fn cn_parse_dotenv(args: Vec<String>) -> String {
+        match parse_dotenv(args.first().map(|t| t.as_str()).unwrap_or("")) {
+            Ok(vars) => {
+                let map: Map<String, JsonValue> = vars.into_iter().map(|(k, v)| (k, JsonValue::String(v))).collect();
+                json_value(&JsonValue::Object(map).to_string())
+            },
+            Err(e) => e,
+        }
+    }
+
+    // 读取INI文件，返回 Map：每个节是一个 Map，节之前的键直接放在顶层
+    // 值中的 true/false 和数字转换为对应类型，其余为字符串
+    // 参数: path
+    pub fn cn_read_ini(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供文件路径".to_string();
+        }
+        match fs::read_to_string(&args[0]) {
+            Ok(text) => match parse_ini(&text) {
+                Ok(map) => json_value(&JsonValue::Object(map).to_string()),
+                Err(e) => e,
+            },
+            Err(e) => format!("错误: 无法读取 {}: {}", args[0], e),
+        }
+    }
+
+    // 解析INI格式的文本，返回 Map
+    // 参数: text
+    pub fn cn_parse_ini(args: Vec<String>) -> String {
+        match parse_ini(args.first().map(|t| t.as_str()).unwrap_or("")) {
+            Ok(map) => json_value(&JsonValue::Object(map).to_string()),
+            Err(e) => e,
+        }
+    }
+
+    // 按层次加载配置，后面的层覆盖前面的层：defaults < files < 环境变量 < 命令行参数
+    // options 是 Map：
+    //   defaults: 默认配置 Map,
+    //   files: 配置文件路径或路径数组，按扩展名解析（json、toml、yaml/yml、ini、env），不存在的文件被跳过,
+    //   env_prefix: 环境变量前缀，如 "APP_" 使 APP_DB__HOST 对应 db.host（省略时不读取环境变量）,
+    //   env_separator: 环境变量中表示层级的分隔符，默认 "__",
+    //   args: 为 true 时读取脚本的命令行参数，也可以是参数数组；支持 --key=value、--key value 和 --flag，键中的 . 表示层级
+    // 参数: options
+    pub fn cn_load(args: Vec<String>) -> String {
+        let options = match args.first().filter(|o| !o.trim().is_empty()) {
+            Some(options) => match serde_json::from_str::<Map<String, JsonValue>>(options) {
+                Ok(options) => options,
+                Err(_) => return "错误: 选项必须是 Map".to_string(),
+            },
+            None => Map::new(),
+        };
+        match load(&options) {
+            Ok(config) => json_value(&config.to_string()),
+            Err(e) => e,
+        }
+    }
+
+    // 按以 . 分隔的路径获取配置值，不存在时返回默认值或空值
+    // 参数: config, path, [default]
+    pub fn cn_get(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供配置和路径".to_string();
+        }
+        let config = match serde_json::from_str::<JsonValue>(&args[0]) {
+            Ok(config) => config,
+            Err(_) => return "错误: 配置必须是 Map".to_string(),
+        };
+        let found = args[1].split('.').filter(|s| !s.is_empty()).try_fold(&config, |value, key| match value {
+            JsonValue::Object(map) => map.get(key),
+            JsonValue::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        });
+        match found {
+            Some(JsonValue::String(s)) => s.clone(),
+            Some(value) => json_value(&value.to_string()),
+            None => args.get(2).cloned().unwrap_or_else(|| json_value("null")),
+        }
+    }
+
+    fn load(options: &Map<String, JsonValue>) -> Result<JsonValue, String> {
+        let mut config = match options.get("defaults") {
+            Some(JsonValue::Object(defaults)) => JsonValue::Object(defaults.clone()),
+            Some(JsonValue::Null) | None => JsonValue::Object(Map::new()),
+            Some(_) => return Err("错误: defaults 必须是 Map".to_string()),
+        };
+
+        let files: Vec<&str> = match options.get("files") {
+            Some(JsonValue::String(path)) => vec![path.as_str()],
+            Some(JsonValue::Array(paths)) => paths.iter().filter_map(|p| p.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        for path in files {
+            if Path::new(path).exists() {
+                merge(&mut config, read_file(path)?);
+            }
+        }
+
+        if let Some(prefix) = options.get("env_prefix").and_then(|p| p.as_str()) {
+            let separator = options.get("env_separator").and_then(|s| s.as_str()).unwrap_or("__");
+            let mut layer = JsonValue::Object(Map::new());
+            for (key, value) in env::vars() {
+                if let Some(rest) = key.strip_prefix(prefix).filter(|rest| !rest.is_empty()) {
+                    let path: Vec<String> = rest.split(separator).map(|s| s.to_lowercase()).collect();
+                    set_path(&mut layer, &path, infer_scalar(&value));
+                }
+            }
+            merge(&mut config, layer);
+        }
+
+        let cli_args: Vec<String> = match options.get("args") {
+            Some(JsonValue::Bool(true)) => script_args(),
+            Some(JsonValue::Array(items)) => items.iter().map(|a| a.as_str().map(|s| s.to_string()).unwrap_or_else(|| a.to_string())).collect(),
+            _ => Vec::new(),
+        };
+        merge(&mut config, parse_cli_args(&cli_args));
+
+        Ok(config)
+    }
+
+    // 解释器的命令行为 CodeNothing <脚本> [参数]，跳过前两项和解释器自身的 --cn- 选项
+    fn script_args() -> Vec<String> {
+        env::args().skip(2).filter(|a| !a.starts_with("--cn-")).collect()
+    }
+
+    fn parse_cli_args(args: &[String]) -> JsonValue {
+        let mut layer = JsonValue::Object(Map::new());
+        let mut i = 0;
+        while i < args.len() {
+            if let Some(option) = args[i].strip_prefix("--").filter(|o| !o.is_empty()) {
+                let (key, value) = match option.split_once('=') {
+                    Some((key, value)) => (key, infer_scalar(value)),
+                    None => match args.get(i + 1).filter(|next| !next.starts_with("--")) {
+                        Some(next) => {
+                            i += 1;
+                            (option, infer_scalar(next))
+                        },
+                        None => (option, JsonValue::Bool(true)),
+                    },
+                };
+                let path: Vec<String> = key.split('.').map(|s| s.to_string()).collect();
+                set_path(&mut layer, &path, value);
+            }
+            i += 1;
+        }
+        layer
+    }
+
+    fn read_file(path: &str) -> Result<JsonValue, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("错误: 无法读取 {}: {}", path, e))?;
+        let name = Path::new(path).file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+        let extension = Path::new(&name).extension().map(|e| e.to_string_lossy().into_owned()).unwrap_or_default();
+        let parse_error = |e: &dyn std::fmt::Display| format!("错误: 无法解析 {}: {}", path, e);
+
+        match extension.as_str() {
+            "json" => serde_json::from_str(&text).map_err(|e| parse_error(&e)),
+            "toml" => toml::from_str::<toml::Value>(&text).map(toml_to_json).map_err(|e| parse_error(&e)),
+            "yaml" | "yml" => serde_yaml::from_str(&text).map_err(|e| parse_error(&e)),
+            "ini" | "cfg" | "conf" => parse_ini(&text).map(JsonValue::Object),
+            // .env 和 xxx.env 文件
+            _ if name == ".env" || extension == "env" => {
+                let vars = parse_dotenv(&text)?;
+                Ok(JsonValue::Object(vars.into_iter().map(|(k, v)| (k, infer_scalar(&v))).collect()))
+            },
+            _ => Err(format!("错误: 不支持的配置文件格式: {}", path)),
+        }
+    }
+
+    fn toml_to_json(value: toml::Value) -> JsonValue {
+        match value {
+            toml::Value::String(s) => JsonValue::String(s),
+            toml::Value::Integer(i) => JsonValue::from(i),
+            toml::Value::Float(f) => JsonValue::from(f),
+            toml::Value::Boolean(b) => JsonValue::Bool(b),
+            toml::Value::Datetime(dt) => JsonValue::String(dt.to_string()),
+            toml::Value::Array(items) => JsonValue::Array(items.into_iter().map(toml_to_json).collect()),
+            toml::Value::Table(table) => JsonValue::Object(table.into_iter().map(|(k, v)| (k, toml_to_json(v))).collect()),
+        }
+    }
+
+    // 深度合并：两边都是 Map 时逐键合并，否则用 overlay 替换
+    fn merge(base: &mut JsonValue, overlay: JsonValue) {
+        match (base, overlay) {
+            (JsonValue::Object(base), JsonValue::Object(overlay)) => {
+                for (key, value) in overlay {
+                    match base.get_mut(&key) {
+                        Some(existing) => merge(existing, value),
+                        None => {
+                            base.insert(key, value);
+                        },
+                    }
+                }
+            },
+            (base, overlay) => *base = overlay,
+        }
+    }
+
+    fn set_path(target: &mut JsonValue, path: &[String], value: JsonValue) {
+        let (last, parents) = match path.split_last() {
+            Some(split) => split,
+            None => return,
+        };
+        let mut current = target;
+        for key in parents {
+            if !current.is_object() {
+                *current = JsonValue::Object(Map::new());
+            }
+            current = current.as_object_mut().expect("已确保是 Map")
+                .entry(key.clone())
+                .or_insert_with(|| JsonValue::Object(Map::new()));
+        }
+        if !current.is_object() {
+            *current = JsonValue::Object(Map::new());
+        }
+        if let Some(map) = current.as_object_mut() {
+            map.insert(last.clone(), value);
+        }
+    }
+
+    // 把文本形式的值转换为布尔值或数字，其他保持为字符串
+    fn infer_scalar(text: &str) -> JsonValue {
+        match text {
+            "true" => JsonValue::Bool(true),
+            "false" => JsonValue::Bool(false),
+            _ => {
+                if let Ok(i) = text.parse::<i64>() {
+                    JsonValue::from(i)
+                } else if let Some(f) = text.parse::<f64>().ok().filter(|f| f.is_finite() && text.contains('.')) {
+                    JsonValue::from(f)
+                } else {
+                    JsonValue::String(text.to_string())
+                }
+            },
+        }
+    }
+
+    fn parse_ini(text: &str) -> Result<Map<String, JsonValue>, String> {
+        let mut root = Map::new();
+        let mut section: Option<String> = None;
+        for (number, raw) in text.lines().enumerate() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[') {
+                let name = name.strip_suffix(']').ok_or_else(|| format!("错误: 第{}行的节名缺少 ]", number + 1))?.trim();
+                root.entry(name.to_string()).or_insert_with(|| JsonValue::Object(Map::new()));
+                section = Some(name.to_string());
+                continue;
+            }
+
+            let (key, value) = line.split_once(['=', ':'])
+                .ok_or_else(|| format!("错误: 第{}行不是 键=值 的格式: {}", number + 1, line))?;
+            let value = value.trim();
+            let value = match unquote(value) {
+                Some(inner) => JsonValue::String(inner.to_string()),
+                None => infer_scalar(strip_inline_comment(value, &[";", "#"])),
+            };
+            let target = match &section {
+                Some(name) => match root.get_mut(name) {
+                    Some(JsonValue::Object(map)) => map,
+                    _ => unreachable!("节在解析节名时已创建"),
+                },
+                None => &mut root,
+            };
+            target.insert(key.trim().to_string(), value);
+        }
+        Ok(root)
+    }
+
+    fn unquote(value: &str) -> Option<&str> {
+        if value.len() >= 2 && ((value.starts_with('"') && value.ends_with('"')) || (value.starts_with('\'') && value.ends_with('\''))) {
+            Some(&value[1..value.len() - 1])
+        } else {
+            None
+        }
+    }
+
+    // 去掉空白之后的行内注释
+    fn strip_inline_comment<'t>(value: &'t str, markers: &[&str]) -> &'t str {
+        let cut = markers.iter()
+            .filter_map(|m| value.find(&format!(" {}", m)).or_else(|| value.find(&format!("\t{}", m))))
+            .min();
+        match cut {
+            Some(pos) => value[..pos].trim_end(),
+            None => value,
+        }
+    }
+
+    fn parse_dotenv(text: &str) -> Result<Vec<(String, String)>, String> {
+        let mut vars: Vec<(String, String)> = Vec::new();
+        let mut lines = text.lines().enumerate();
+        while let Some((number, raw)) = lines.next() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let line = line.strip_prefix("export ").map(|l| l.trim_start()).unwrap_or(line);
+            let (key, rest) = line.split_once('=')
+                .ok_or_else(|| format!("错误: 第{}行不是 KEY=VALUE 的格式: {}", number + 1, line))?;
+            let key = key.trim();
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.') {
+                return Err(format!("错误: 第{}行的变量名无效: {}", number + 1, key));
+            }
+            let rest = rest.trim_start();
+
+            let value = if let Some(quoted) = rest.strip_prefix('\'') {
+                let end = quoted.find('\'').ok_or_else(|| format!("错误: 第{}行的单引号没有闭合", number + 1))?;
+                quoted[..end].to_string()
+            } else if let Some(quoted) = rest.strip_prefix('"') {
+                // 双引号中的值可以跨越多行
+                let mut content = quoted.to_string();
+                while find_closing_quote(&content).is_none() {
+                    match lines.next() {
+                        Some((_, next)) => {
+                            content.push('\n');
+                            content.push_str(next);
+                        },
+                        None => return Err(format!("错误: 第{}行的双引号没有闭合", number + 1)),
+                    }
+                }
+                let end = find_closing_quote(&content).unwrap_or(content.len());
+                expand(&unescape(&content[..end]), &vars)
+            } else {
+                expand(strip_inline_comment(rest, &["#"]).trim_end(), &vars)
+            };
+            vars.retain(|(k, _)| k != key);
+            vars.push((key.to_string(), value));
+        }
+        Ok(vars)
+    }
+
+    fn find_closing_quote(text: &str) -> Option<usize> {
+        let mut escaped = false;
+        for (i, c) in text.char_indices() {
+            match c {
+                '\\' if !escaped => escaped = true,
+                '"' if !escaped => return Some(i),
+                _ => escaped = false,
+            }
+        }
+        None
+    }
+
+    fn unescape(text: &str) -> String {
+        let mut result = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                result.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some('r') => result.push('\r'),
+                // 保留 \$，由 expand 处理，避免被当作变量引用
+                Some('$') => result.push_str("\\$"),
+                Some(other) => result.push(other),
+                None => result.push('\\'),
+            }
+        }
+        result
+    }
+
+    // 展开 ${VAR} 和 $VAR，先查找同一文件中已定义的变量，再查找环境变量；\$ 表示 $ 本身
+    fn expand(text: &str, vars: &[(String, String)]) -> String {
+        let lookup = |name: &str| {
+            vars.iter().rev().find(|(k, _)| k == name).map(|(_, v)| v.clone())
+                .or_else(|| env::var(name).ok())
+                .unwrap_or_default()
+        };
+        let mut result = String::new();
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' if chars.peek() == Some(&'$') => {
+                    chars.next();
+                    result.push('$');
+                },
+                '$' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    let name: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                    result.push_str(&lookup(&name));
+                },
+                '$' if chars.peek().is_some_and(|c| c.is_ascii_alphabetic() || *c == '_') => {
+                    let mut name = String::new();
+                    while let Some(&c) = chars.peek().filter(|c| c.is_ascii_alphanumeric() || **c == '_') {
+                        name.push(c);
+                        chars.next();
+                    }
+                    result.push_str(&lookup(&name));
+                },
+                c => result.push(c),
+            }
+        }
+        result
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册配置命名空间下的函数
+    let config_ns = registry.namespace("config");
+    config_ns.add_function("load_dotenv", config::cn_load_dotenv)
+             .add_function("parse_dotenv", config::cn_parse_dotenv)
+             .add_function("read_ini", config::cn_read_ini)
+             .add_function("parse_ini", config::cn_parse_ini)
+             .add_function("load", config::cn_load)
+             .add_function("get", config::cn_get);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
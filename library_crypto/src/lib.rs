@@ -0,0 +1,346 @@
+use ::std::collections::HashMap;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes128Gcm, Aes256Gcm, Nonce};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Digest;
+use std::fs::File;
+use std::io::Read;
+
+// 导入通用库
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+
+// 加密命名空间
+// 摘要和随机数据以16进制字符串返回；密钥使用16进制字符串表示
+mod crypto {
+    use super::*;
+
+    // AES-GCM 随机数长度（字节）
+    const NONCE_LEN: usize = 12;
+
+    // bcrypt 默认计算成本
+    const DEFAULT_BCRYPT_COST: u32 = 12;
+
+    // MD5摘要（仅用于校验，不要用于安全场景）
+    // 参数: text
+    pub fn cn_md5(args: Vec<String>) -> String {
+        hash_text("md5", args.first())
+    }
+
+    // SHA-1摘要
+    // 参数: text
+    pub fn cn_sha1(args: Vec<String>) -> String {
+        hash_text("sha1", args.first())
+    }
+
+    // SHA-256摘要
+    // 参数: text
+    pub fn cn_sha256(args: Vec<String>) -> String {
+        hash_text("sha256", args.first())
+    }
+
+    // SHA-512摘要
+    // 参数: text
+    pub fn cn_sha512(args: Vec<String>) -> String {
+        hash_text("sha512", args.first())
+    }
+
+    // 计算文件的摘要，可以用于校验下载的文件
+    // 参数: path, [algorithm]（md5、sha1、sha256、sha512，默认 sha256）
+    pub fn cn_hash_file(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供文件路径".to_string();
+        }
+        let algorithm = args.get(1).map(|a| a.trim().to_lowercase()).unwrap_or_else(|| "sha256".to_string());
+        let mut file = match File::open(&args[0]) {
+            Ok(file) => file,
+            Err(e) => return format!("错误: 无法打开文件 {}: {}", args[0], e),
+        };
+        let result = match algorithm.as_str() {
+            "md5" => digest_reader::<md5::Md5>(&mut file),
+            "sha1" => digest_reader::<sha1::Sha1>(&mut file),
+            "sha256" => digest_reader::<sha2::Sha256>(&mut file),
+            "sha512" => digest_reader::<sha2::Sha512>(&mut file),
+            other => return format!("错误: 不支持的摘要算法: {}", other),
+        };
+        match result {
+            Ok(digest) => digest,
+            Err(e) => format!("错误: 读取文件失败: {}", e),
+        }
+    }
+
+    // 计算HMAC消息认证码
+    // 参数: algorithm（md5、sha1、sha256、sha512）, key, message
+    pub fn cn_hmac(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "错误: 请提供算法、密钥和消息".to_string();
+        }
+        let (key, message) = (args[1].as_bytes(), args[2].as_bytes());
+        match args[0].trim().to_lowercase().as_str() {
+            "md5" => hmac_hex::<Hmac<md5::Md5>>(key, message),
+            "sha1" => hmac_hex::<Hmac<sha1::Sha1>>(key, message),
+            "sha256" => hmac_hex::<Hmac<sha2::Sha256>>(key, message),
+            "sha512" => hmac_hex::<Hmac<sha2::Sha512>>(key, message),
+            other => format!("错误: 不支持的摘要算法: {}", other),
+        }
+    }
+
+    // 使用 bcrypt 计算密码哈希，每次结果不同（包含随机盐）
+    // 参数: password, [cost]（4到31，默认12）
+    pub fn cn_bcrypt_hash(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供密码".to_string();
+        }
+        let cost = match args.get(1).map(|c| c.trim().parse::<u32>()) {
+            Some(Ok(cost)) if (4..=31).contains(&cost) => cost,
+            Some(_) => return format!("错误: 无效的计算成本: {}", args[1]),
+            None => DEFAULT_BCRYPT_COST,
+        };
+        match bcrypt::hash(&args[0], cost) {
+            Ok(hash) => hash,
+            Err(e) => format!("错误: 计算密码哈希失败: {}", e),
+        }
+    }
+
+    // 验证密码是否与 bcrypt 哈希匹配
+    // 参数: password, hash
+    pub fn cn_bcrypt_verify(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供密码和哈希".to_string();
+        }
+        match bcrypt::verify(&args[0], &args[1]) {
+            Ok(matched) => matched.to_string(),
+            Err(e) => format!("错误: 无效的哈希: {}", e),
+        }
+    }
+
+    // 使用 Argon2id 计算密码哈希，返回PHC格式的字符串（包含参数和随机盐）
+    // 参数: password
+    pub fn cn_argon2_hash(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供密码".to_string();
+        }
+        let salt = SaltString::generate(&mut OsRng);
+        match Argon2::default().hash_password(args[0].as_bytes(), &salt) {
+            Ok(hash) => hash.to_string(),
+            Err(e) => format!("错误: 计算密码哈希失败: {}", e),
+        }
+    }
+
+    // 验证密码是否与 Argon2 哈希匹配
+    // 参数: password, hash
+    pub fn cn_argon2_verify(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供密码和哈希".to_string();
+        }
+        let hash = match PasswordHash::new(&args[1]) {
+            Ok(hash) => hash,
+            Err(e) => return format!("错误: 无效的哈希: {}", e),
+        };
+        Argon2::default().verify_password(args[0].as_bytes(), &hash).is_ok().to_string()
+    }
+
+    // 生成随机的AES密钥
+    // 参数: [bits]（128 或 256，默认256）
+    pub fn cn_generate_key(args: Vec<String>) -> String {
+        match args.first().map(|b| b.trim()) {
+            None | Some("256") => random_hex(32),
+            Some("128") => random_hex(16),
+            Some(other) => format!("错误: 不支持的密钥长度: {}", other),
+        }
+    }
+
+    // 从口令派生256位AES密钥（Argon2id），相同的口令和盐总是得到相同的密钥
+    // 参数: password, salt（至少8个字符）
+    pub fn cn_derive_key(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供口令和盐".to_string();
+        }
+        if args[1].len() < 8 {
+            return "错误: 盐至少需要8个字符".to_string();
+        }
+        let mut key = [0u8; 32];
+        match Argon2::default().hash_password_into(args[0].as_bytes(), args[1].as_bytes(), &mut key) {
+            Ok(()) => to_hex(&key),
+            Err(e) => format!("错误: 派生密钥失败: {}", e),
+        }
+    }
+
+    // 生成随机字节，返回16进制字符串
+    // 参数: [length]（字节数，默认16）
+    pub fn cn_random_bytes(args: Vec<String>) -> String {
+        match args.first().map(|n| n.trim().parse::<usize>()) {
+            Some(Ok(n)) if n <= 1024 * 1024 => random_hex(n),
+            Some(_) => format!("错误: 无效的长度: {}", args[0]),
+            None => random_hex(16),
+        }
+    }
+
+    // 使用 AES-GCM 加密文本，每次使用新的随机数
+    // 返回 Base64 字符串（随机数 + 密文 + 认证标签）
+    // 参数: key（16进制，16或32字节）, plaintext
+    pub fn cn_aes_encrypt(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供密钥和明文".to_string();
+        }
+        let key = match from_hex(&args[0]) {
+            Ok(key) => key,
+            Err(e) => return e,
+        };
+        let result = match key.len() {
+            16 => encrypt::<Aes128Gcm>(&key, args[1].as_bytes()),
+            32 => encrypt::<Aes256Gcm>(&key, args[1].as_bytes()),
+            n => return format!("错误: 密钥长度必须是16或32字节，实际为{}字节", n),
+        };
+        match result {
+            Ok(data) => STANDARD.encode(data),
+            Err(e) => e,
+        }
+    }
+
+    // 解密 aes_encrypt 的结果，密钥错误或数据被篡改时返回错误信息
+    // 参数: key（16进制）, ciphertext（Base64）
+    pub fn cn_aes_decrypt(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供密钥和密文".to_string();
+        }
+        let key = match from_hex(&args[0]) {
+            Ok(key) => key,
+            Err(e) => return e,
+        };
+        let data = match STANDARD.decode(args[1].trim()) {
+            Ok(data) if data.len() > NONCE_LEN => data,
+            Ok(_) => return "错误: 密文太短".to_string(),
+            Err(e) => return format!("错误: 无效的Base64数据: {}", e),
+        };
+        let result = match key.len() {
+            16 => decrypt::<Aes128Gcm>(&key, &data),
+            32 => decrypt::<Aes256Gcm>(&key, &data),
+            n => return format!("错误: 密钥长度必须是16或32字节，实际为{}字节", n),
+        };
+        match result.map(String::from_utf8) {
+            Ok(Ok(text)) => text,
+            Ok(Err(_)) => "错误: 解密结果不是有效的UTF-8文本".to_string(),
+            Err(e) => e,
+        }
+    }
+
+    // 以固定时间比较两个字符串，用于比较摘要和令牌，避免计时攻击
+    // 参数: a, b
+    pub fn cn_constant_time_eq(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "false".to_string();
+        }
+        let (a, b) = (args[0].as_bytes(), args[1].as_bytes());
+        if a.len() != b.len() {
+            return "false".to_string();
+        }
+        (a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0).to_string()
+    }
+
+    fn hash_text(algorithm: &str, text: Option<&String>) -> String {
+        let bytes = match text {
+            Some(text) => text.as_bytes(),
+            None => return "错误: 请提供要计算摘要的文本".to_string(),
+        };
+        match algorithm {
+            "md5" => to_hex(&md5::Md5::digest(bytes)),
+            "sha1" => to_hex(&sha1::Sha1::digest(bytes)),
+            "sha512" => to_hex(&sha2::Sha512::digest(bytes)),
+            _ => to_hex(&sha2::Sha256::digest(bytes)),
+        }
+    }
+
+    fn digest_reader<D: Digest>(reader: &mut impl Read) -> std::io::Result<String> {
+        let mut hasher = D::new();
+        let mut buffer = [0u8; 64 * 1024];
+        loop {
+            let n = reader.read(&mut buffer)?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buffer[..n]);
+        }
+        Ok(to_hex(&hasher.finalize()))
+    }
+
+    fn hmac_hex<M: Mac + KeyInit>(key: &[u8], message: &[u8]) -> String {
+        match <M as KeyInit>::new_from_slice(key) {
+            Ok(mut mac) => {
+                mac.update(message);
+                to_hex(&mac.finalize().into_bytes())
+            },
+            Err(e) => format!("错误: 无效的密钥: {}", e),
+        }
+    }
+
+    fn encrypt<C: Aead + AeadCore + KeyInit>(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+        let cipher = C::new_from_slice(key).map_err(|e| format!("错误: 无效的密钥: {}", e))?;
+        let nonce = C::generate_nonce(&mut OsRng);
+        let ciphertext = cipher.encrypt(&nonce, plaintext).map_err(|_| "错误: 加密失败".to_string())?;
+        let mut data = nonce.to_vec();
+        data.extend_from_slice(&ciphertext);
+        Ok(data)
+    }
+
+    fn decrypt<C: Aead + KeyInit>(key: &[u8], data: &[u8]) -> Result<Vec<u8>, String> {
+        let cipher = C::new_from_slice(key).map_err(|e| format!("错误: 无效的密钥: {}", e))?;
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        cipher.decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| "错误: 解密失败，密钥错误或数据已被篡改".to_string())
+    }
+
+    fn random_hex(len: usize) -> String {
+        let mut bytes = vec![0u8; len];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        to_hex(&bytes)
+    }
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn from_hex(hex: &str) -> Result<Vec<u8>, String> {
+        let hex = hex.trim();
+        if hex.len() % 2 == 1 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err("错误: 密钥必须是16进制字符串".to_string());
+        }
+        Ok((0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0))
+            .collect())
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册加密命名空间下的函数
+    let crypto_ns = registry.namespace("crypto");
+    crypto_ns.add_function("md5", crypto::cn_md5)
+             .add_function("sha1", crypto::cn_sha1)
+             .add_function("sha256", crypto::cn_sha256)
+             .add_function("sha512", crypto::cn_sha512)
+             .add_function("hash_file", crypto::cn_hash_file)
+             .add_function("hmac", crypto::cn_hmac)
+             .add_function("bcrypt_hash", crypto::cn_bcrypt_hash)
+             .add_function("bcrypt_verify", crypto::cn_bcrypt_verify)
+             .add_function("argon2_hash", crypto::cn_argon2_hash)
+             .add_function("argon2_verify", crypto::cn_argon2_verify)
+             .add_function("generate_key", crypto::cn_generate_key)
+             .add_function("derive_key", crypto::cn_derive_key)
+             .add_function("random_bytes", crypto::cn_random_bytes)
+             .add_function("aes_encrypt", crypto::cn_aes_encrypt)
+             .add_function("aes_decrypt", crypto::cn_aes_decrypt)
+             .add_function("constant_time_eq", crypto::cn_constant_time_eq);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
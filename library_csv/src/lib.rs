@@ -0,0 +1,424 @@
+use ::std::collections::HashMap;
+use serde_json::{Map, Value as JsonValue};
+use std::fs::File;
+use std::sync::{Mutex, MutexGuard};
+
+// 导入通用库
+use cn_common::handle::HandleTable;
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::{json_value, serialize};
+
+// CSV命名空间
+mod csv_ns {
+    use super::*;
+
+    // 读写选项，所有函数最后一个参数都可以是选项 Map，或者直接传入分隔符字符串
+    //   delimiter   分隔符，默认 ","，可以写 "tab" 或 "\t"
+    //   header      第一行是否为表头，默认 true；为 true 时每行是以表头为键的 Map，否则每行是 Array
+    //   quote       引号字符，默认 "\""
+    //   quote_style 写入时的引用规则：necessary（默认）、always、never、non_numeric
+    //   trim        读取时是否去掉单元格首尾空白，默认 false
+    //   infer       读取时是否推断数字、布尔值和空值，默认 false（全部作为字符串）
+    struct Options {
+        delimiter: u8,
+        header: bool,
+        quote: u8,
+        quote_style: csv::QuoteStyle,
+        trim: bool,
+        infer: bool,
+    }
+
+    impl Options {
+        fn parse(arg: Option<&String>) -> Result<Options, String> {
+            let mut options = Options {
+                delimiter: b',',
+                header: true,
+                quote: b'"',
+                quote_style: csv::QuoteStyle::Necessary,
+                trim: false,
+                infer: false,
+            };
+            let arg = match arg.filter(|a| !a.is_empty()) {
+                Some(arg) => arg,
+                None => return Ok(options),
+            };
+
+            let obj = match serde_json::from_str::<JsonValue>(arg) {
+                Ok(JsonValue::Object(obj)) => obj,
+                _ => {
+                    options.delimiter = parse_char(arg, "分隔符")?;
+                    return Ok(options);
+                },
+            };
+            for (key, value) in &obj {
+                match key.as_str() {
+                    "delimiter" => options.delimiter = parse_char(value.as_str().unwrap_or(""), "分隔符")?,
+                    "quote" => options.quote = parse_char(value.as_str().unwrap_or(""), "引号字符")?,
+                    "header" => options.header = parse_bool(value, key)?,
+                    "trim" => options.trim = parse_bool(value, key)?,
+                    "infer" => options.infer = parse_bool(value, key)?,
+                    "quote_style" => {
+                        options.quote_style = match value.as_str().unwrap_or("") {
+                            "necessary" => csv::QuoteStyle::Necessary,
+                            "always" => csv::QuoteStyle::Always,
+                            "never" => csv::QuoteStyle::Never,
+                            "non_numeric" => csv::QuoteStyle::NonNumeric,
+                            other => return Err(format!("错误: 无效的引用规则: {}", other)),
+                        }
+                    },
+                    other => return Err(format!("错误: 未知的选项: {}", other)),
+                }
+            }
+            Ok(options)
+        }
+
+        fn reader_builder(&self) -> csv::ReaderBuilder {
+            let mut builder = csv::ReaderBuilder::new();
+            builder.delimiter(self.delimiter)
+                .quote(self.quote)
+                .has_headers(self.header)
+                .flexible(true)
+                .trim(if self.trim { csv::Trim::All } else { csv::Trim::None });
+            builder
+        }
+
+        fn writer_builder(&self) -> csv::WriterBuilder {
+            let mut builder = csv::WriterBuilder::new();
+            builder.delimiter(self.delimiter)
+                .quote(self.quote)
+                .quote_style(self.quote_style)
+                .flexible(true);
+            builder
+        }
+    }
+
+    fn parse_char(value: &str, name: &str) -> Result<u8, String> {
+        match value {
+            "tab" | "\\t" | "\t" => Ok(b'\t'),
+            _ if value.len() == 1 => Ok(value.as_bytes()[0]),
+            _ => Err(format!("错误: {}必须是单个ASCII字符: {}", name, value)),
+        }
+    }
+
+    fn parse_bool(value: &JsonValue, key: &str) -> Result<bool, String> {
+        match value {
+            JsonValue::Bool(b) => Ok(*b),
+            JsonValue::String(s) if s == "true" || s == "false" => Ok(s == "true"),
+            _ => Err(format!("错误: 选项 {} 必须是布尔值", key)),
+        }
+    }
+
+    // 解析CSV文本，返回行数组
+    // 参数: text, [options]
+    pub fn cn_parse(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供CSV文本".to_string();
+        }
+        let options = match Options::parse(args.get(1)) {
+            Ok(options) => options,
+            Err(e) => return e,
+        };
+        match read_rows(options.reader_builder().from_reader(args[0].as_bytes()), &options) {
+            Ok(rows) => json_value(&serialize(&JsonValue::Array(rows))),
+            Err(e) => e,
+        }
+    }
+
+    // 读取CSV文件，返回行数组
+    // 参数: path, [options]
+    pub fn cn_read(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供文件路径".to_string();
+        }
+        let options = match Options::parse(args.get(1)) {
+            Ok(options) => options,
+            Err(e) => return e,
+        };
+        let reader = match options.reader_builder().from_path(&args[0]) {
+            Ok(reader) => reader,
+            Err(e) => return format!("错误: 无法打开文件 {}: {}", args[0], e),
+        };
+        match read_rows(reader, &options) {
+            Ok(rows) => json_value(&serialize(&JsonValue::Array(rows))),
+            Err(e) => e,
+        }
+    }
+
+    // 将行数组格式化为CSV文本
+    // 行为 Map 时按表头写入（表头为所有键的并集，按出现顺序），行为 Array 时直接写入；header 为 false 时不写表头
+    // 参数: rows, [options]
+    pub fn cn_format(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供行数组".to_string();
+        }
+        let options = match Options::parse(args.get(1)) {
+            Ok(options) => options,
+            Err(e) => return e,
+        };
+        let writer = options.writer_builder().from_writer(Vec::new());
+        match write_rows(writer, &args[0], &options) {
+            Ok(writer) => match writer.into_inner() {
+                Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+                Err(e) => format!("错误: 生成CSV失败: {}", e),
+            },
+            Err(e) => e,
+        }
+    }
+
+    // 将行数组写入CSV文件，规则与 format 相同
+    // 参数: path, rows, [options]
+    pub fn cn_write(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供文件路径和行数组".to_string();
+        }
+        let options = match Options::parse(args.get(2)) {
+            Ok(options) => options,
+            Err(e) => return e,
+        };
+        let writer = match options.writer_builder().from_path(&args[0]) {
+            Ok(writer) => writer,
+            Err(e) => return format!("错误: 无法创建文件 {}: {}", args[0], e),
+        };
+        match write_rows(writer, &args[1], &options) {
+            Ok(_) => "true".to_string(),
+            Err(e) => e,
+        }
+    }
+
+    // 读取所有记录
+    fn read_rows<R: std::io::Read>(mut reader: csv::Reader<R>, options: &Options) -> Result<Vec<JsonValue>, String> {
+        let headers = if options.header {
+            Some(read_headers(&mut reader)?)
+        } else {
+            None
+        };
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            let record = record.map_err(|e| format!("错误: 解析CSV失败: {}", e))?;
+            rows.push(record_to_value(&record, headers.as_deref(), options.infer));
+        }
+        Ok(rows)
+    }
+
+    fn read_headers<R: std::io::Read>(reader: &mut csv::Reader<R>) -> Result<Vec<String>, String> {
+        match reader.headers() {
+            Ok(headers) => Ok(headers.iter().map(|h| h.to_string()).collect()),
+            Err(e) => Err(format!("错误: 解析CSV失败: {}", e)),
+        }
+    }
+
+    // 将一条记录转换为 Map（有表头时）或 Array
+    fn record_to_value(record: &csv::StringRecord, headers: Option<&[String]>, infer: bool) -> JsonValue {
+        let cell = |text: &str| if infer { infer_value(text) } else { JsonValue::String(text.to_string()) };
+        match headers {
+            Some(headers) => {
+                let mut obj = Map::new();
+                for (i, text) in record.iter().enumerate() {
+                    let key = headers.get(i).cloned().unwrap_or_else(|| format!("column{}", i + 1));
+                    obj.insert(key, cell(text));
+                }
+                JsonValue::Object(obj)
+            },
+            None => JsonValue::Array(record.iter().map(cell).collect()),
+        }
+    }
+
+    // 推断单元格类型：空单元格为 null，其次尝试整数、浮点数和布尔值，否则为字符串
+    fn infer_value(text: &str) -> JsonValue {
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            return JsonValue::Null;
+        }
+        // 带前导零的数字（如邮编 "007"）保持为字符串
+        let leading_zero = trimmed.len() > 1 && trimmed.starts_with('0') && !trimmed.starts_with("0.");
+        if !leading_zero {
+            if let Ok(n) = trimmed.parse::<i64>() {
+                return JsonValue::from(n);
+            }
+            if let Some(n) = trimmed.parse::<f64>().ok().filter(|n| n.is_finite()).and_then(serde_json::Number::from_f64) {
+                return JsonValue::Number(n);
+            }
+        }
+        match trimmed {
+            "true" => JsonValue::Bool(true),
+            "false" => JsonValue::Bool(false),
+            _ => JsonValue::String(text.to_string()),
+        }
+    }
+
+    // 写入所有行
+    fn write_rows<W: std::io::Write>(mut writer: csv::Writer<W>, rows: &str, options: &Options) -> Result<csv::Writer<W>, String> {
+        let rows = match serde_json::from_str::<JsonValue>(rows) {
+            Ok(JsonValue::Array(rows)) => rows,
+            _ => return Err("错误: 行数据必须是数组".to_string()),
+        };
+
+        let mut columns: Vec<String> = Vec::new();
+        for key in rows.iter().filter_map(|row| row.as_object()).flat_map(|obj| obj.keys()) {
+            if !columns.contains(key) {
+                columns.push(key.clone());
+            }
+        }
+
+        let mut write_all = || -> Result<(), csv::Error> {
+            if options.header && !columns.is_empty() {
+                writer.write_record(&columns)?;
+            }
+            for row in &rows {
+                let cells: Vec<String> = match row {
+                    JsonValue::Object(obj) => columns.iter().map(|c| cell_text(obj.get(c))).collect(),
+                    JsonValue::Array(items) => items.iter().map(|item| cell_text(Some(item))).collect(),
+                    other => vec![cell_text(Some(other))],
+                };
+                writer.write_record(&cells)?;
+            }
+            writer.flush()?;
+            Ok(())
+        };
+        match write_all() {
+            Ok(()) => Ok(writer),
+            Err(e) => Err(format!("错误: 写入CSV失败: {}", e)),
+        }
+    }
+
+    // 将值转换为单元格文本，嵌套的 Map 和 Array 以JSON字符串写入，空值写为空单元格
+    fn cell_text(value: Option<&JsonValue>) -> String {
+        match value {
+            None | Some(JsonValue::Null) => String::new(),
+            Some(JsonValue::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+        }
+    }
+
+    // 逐行读取的CSV文件
+    struct RowReader {
+        reader: csv::Reader<File>,
+        headers: Option<Vec<String>>,
+        infer: bool,
+        peeked: Option<csv::StringRecord>,
+    }
+
+    impl RowReader {
+        fn advance(&mut self) -> Result<Option<csv::StringRecord>, String> {
+            let mut record = csv::StringRecord::new();
+            match self.reader.read_record(&mut record) {
+                Ok(true) => Ok(Some(record)),
+                Ok(false) => Ok(None),
+                Err(e) => Err(format!("错误: 解析CSV失败: {}", e)),
+            }
+        }
+    }
+
+    static READERS: Mutex<HandleTable<RowReader>> = Mutex::new(HandleTable::new());
+
+    fn lock_readers() -> MutexGuard<'static, HandleTable<RowReader>> {
+        READERS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // 打开CSV文件逐行读取，返回迭代句柄，适合处理大文件
+    // 参数: path, [options]
+    pub fn cn_open(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供文件路径".to_string();
+        }
+        let options = match Options::parse(args.get(1)) {
+            Ok(options) => options,
+            Err(e) => return e,
+        };
+        let mut reader = match options.reader_builder().from_path(&args[0]) {
+            Ok(reader) => reader,
+            Err(e) => return format!("错误: 无法打开文件 {}: {}", args[0], e),
+        };
+        let headers = if options.header {
+            match read_headers(&mut reader) {
+                Ok(headers) => Some(headers),
+                Err(e) => return e,
+            }
+        } else {
+            None
+        };
+        let row_reader = RowReader { reader, headers, infer: options.infer, peeked: None };
+        lock_readers().insert(row_reader).to_string()
+    }
+
+    // 获取表头，没有表头时返回空数组
+    // 参数: handle
+    pub fn cn_headers(args: Vec<String>) -> String {
+        let readers = lock_readers();
+        match args.first().and_then(|arg| readers.parse_handle(arg)).and_then(|id| readers.get(id)) {
+            Some(reader) => json_value(&serialize(&JsonValue::from(reader.headers.clone().unwrap_or_default()))),
+            None => "错误: 无效的读取句柄".to_string(),
+        }
+    }
+
+    // 检查是否还有行
+    // 参数: handle
+    pub fn cn_has_next(args: Vec<String>) -> String {
+        let mut readers = lock_readers();
+        let reader = match args.first().and_then(|arg| readers.parse_handle(arg)).and_then(|id| readers.get_mut(id)) {
+            Some(reader) => reader,
+            None => return "错误: 无效的读取句柄".to_string(),
+        };
+        if reader.peeked.is_none() {
+            match reader.advance() {
+                Ok(next) => reader.peeked = next,
+                Err(e) => return e,
+            }
+        }
+        reader.peeked.is_some().to_string()
+    }
+
+    // 读取下一行，没有更多行时返回错误信息
+    // 参数: handle
+    pub fn cn_next(args: Vec<String>) -> String {
+        let mut readers = lock_readers();
+        let reader = match args.first().and_then(|arg| readers.parse_handle(arg)).and_then(|id| readers.get_mut(id)) {
+            Some(reader) => reader,
+            None => return "错误: 无效的读取句柄".to_string(),
+        };
+        let next = match reader.peeked.take() {
+            Some(record) => Ok(Some(record)),
+            None => reader.advance(),
+        };
+        match next {
+            Ok(Some(record)) => json_value(&serialize(&record_to_value(&record, reader.headers.as_deref(), reader.infer))),
+            Ok(None) => "错误: 没有更多行".to_string(),
+            Err(e) => e,
+        }
+    }
+
+    // 关闭读取句柄
+    // 参数: handle
+    pub fn cn_close(args: Vec<String>) -> String {
+        let mut readers = lock_readers();
+        match args.first().and_then(|arg| readers.parse_handle(arg)) {
+            Some(id) => {
+                readers.remove(id);
+                "true".to_string()
+            },
+            None => "false".to_string(),
+        }
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册CSV命名空间下的函数
+    let csv_ns = registry.namespace("csv");
+    csv_ns.add_function("parse", csv_ns::cn_parse)
+          .add_function("read", csv_ns::cn_read)
+          .add_function("format", csv_ns::cn_format)
+          .add_function("write", csv_ns::cn_write)
+          .add_function("open", csv_ns::cn_open)
+          .add_function("headers", csv_ns::cn_headers)
+          .add_function("has_next", csv_ns::cn_has_next)
+          .add_function("next", csv_ns::cn_next)
+          .add_function("close", csv_ns::cn_close);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
@@ -0,0 +1,778 @@
+use ::std::collections::HashMap;
+use serde_json::{Map, Value as JsonValue};
+use std::sync::{Mutex, MutexGuard};
+
+// 导入通用库
+use cn_common::handle::HandleTable;
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::json_value;
+
+// 数据库命名空间
+// 通过连接URL同时支持 PostgreSQL（postgres://）和 MySQL（mysql://），两者使用相同的函数
+// connect 返回连接池句柄，每次调用从池中取出连接；begin 返回形如 "tx#1" 的事务句柄，
+// 事务内的语句都在同一个连接上执行。需要句柄的函数同时接受连接池句柄和事务句柄
+mod db {
+    use super::*;
+
+    // 事务句柄前缀，避免与连接池句柄混淆
+    const TX_PREFIX: &str = "tx#";
+
+    // 默认的连接池大小
+    const DEFAULT_POOL_SIZE: usize = 10;
+
+    enum Pool {
+        Postgres(Box<pg_backend::Pool>),
+        Mysql(mysql::Pool),
+    }
+
+    enum Conn {
+        Postgres(Box<postgres::Client>),
+        Mysql(mysql::PooledConn),
+    }
+
+    // 事务持有一个从池中取出的连接，结束后归还
+    struct Transaction {
+        pool: u64,
+        conn: Conn,
+    }
+
+    static POOLS: Mutex<HandleTable<Pool>> = Mutex::new(HandleTable::new());
+    static TRANSACTIONS: Mutex<HandleTable<Transaction>> = Mutex::new(HandleTable::new());
+
+    fn lock_pools() -> MutexGuard<'static, HandleTable<Pool>> {
+        POOLS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_transactions() -> MutexGuard<'static, HandleTable<Transaction>> {
+        TRANSACTIONS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // 从连接池中取出一个连接
+    fn checkout(pool_id: u64) -> Result<Conn, String> {
+        let mut pools = lock_pools();
+        match pools.get_mut(pool_id) {
+            Some(Pool::Postgres(pool)) => pool.get().map(|client| Conn::Postgres(Box::new(client))),
+            Some(Pool::Mysql(pool)) => {
+                let pool = pool.clone();
+                drop(pools);
+                pool.get_conn().map(Conn::Mysql).map_err(|e| format!("错误: 无法获取数据库连接: {}", e))
+            },
+            None => Err("错误: 无效的数据库句柄".to_string()),
+        }
+    }
+
+    // 把连接归还给连接池；连接池已关闭时直接断开
+    fn checkin(pool_id: u64, conn: Conn) {
+        if let (Some(Pool::Postgres(pool)), Conn::Postgres(client)) = (lock_pools().get_mut(pool_id), conn) {
+            pool.put(*client);
+        }
+    }
+
+    // 在句柄对应的连接上执行操作：事务句柄使用事务的连接，连接池句柄临时取出一个连接
+    fn with_conn<F>(handle: Option<&String>, f: F) -> String
+    where
+        F: FnOnce(&mut Conn) -> Result<String, String>,
+    {
+        let handle = match handle {
+            Some(handle) => handle,
+            None => return "错误: 请提供数据库句柄".to_string(),
+        };
+
+        if let Some(id) = handle.strip_prefix(TX_PREFIX) {
+            let mut transactions = lock_transactions();
+            return match transactions.parse_handle(id).and_then(|id| transactions.get_mut(id)) {
+                Some(tx) => f(&mut tx.conn).unwrap_or_else(|e| e),
+                None => "错误: 无效的事务句柄".to_string(),
+            };
+        }
+
+        let pool_id = match lock_pools().parse_handle(handle) {
+            Some(id) => id,
+            None => return "错误: 无效的数据库句柄".to_string(),
+        };
+        let mut conn = match checkout(pool_id) {
+            Ok(conn) => conn,
+            Err(e) => return e,
+        };
+        let result = f(&mut conn).unwrap_or_else(|e| e);
+        checkin(pool_id, conn);
+        result
+    }
+
+    // 连接数据库，返回连接池句柄
+    // URL格式: postgres://用户:密码@主机:端口/数据库 或 mysql://用户:密码@主机:端口/数据库
+    // 参数: url, [pool_size]（连接池最多保留的连接数，默认10）
+    pub fn cn_connect(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供数据库连接URL".to_string();
+        }
+        let pool_size = match args.get(1).map(|s| s.trim().parse::<usize>()) {
+            Some(Ok(size)) if size > 0 => size,
+            Some(_) => return format!("错误: 无效的连接池大小: {}", args[1]),
+            None => DEFAULT_POOL_SIZE,
+        };
+
+        let url = args[0].trim();
+        let pool = if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+            pg_backend::Pool::connect(url, pool_size).map(|pool| Pool::Postgres(Box::new(pool)))
+        } else if url.starts_with("mysql://") {
+            mysql_backend::connect(url, pool_size).map(Pool::Mysql)
+        } else {
+            Err("错误: 不支持的数据库URL，只支持 postgres:// 和 mysql://".to_string())
+        };
+        match pool {
+            Ok(pool) => lock_pools().insert(pool).to_string(),
+            Err(e) => e,
+        }
+    }
+
+    // 关闭连接池，断开所有空闲连接；未结束的事务仍可以提交或回滚
+    // 参数: db
+    pub fn cn_close(args: Vec<String>) -> String {
+        let mut pools = lock_pools();
+        match args.first().and_then(|arg| pools.parse_handle(arg)) {
+            Some(id) => {
+                pools.remove(id);
+                "true".to_string()
+            },
+            None => "false".to_string(),
+        }
+    }
+
+    // 执行SQL，返回受影响的行数
+    // 参数占位符：PostgreSQL 使用 $1、$2，MySQL 使用 ? 或 :name
+    // 参数: db, sql, [params]（数组按位置绑定，Map 按名称绑定，其他值作为唯一的参数）
+    pub fn cn_exec(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供数据库句柄和SQL".to_string();
+        }
+        let params = parse_params(args.get(2));
+        with_conn(args.first(), |conn| match conn {
+            Conn::Postgres(client) => pg_backend::execute(client, &args[1], params),
+            Conn::Mysql(conn) => mysql_backend::execute(conn, &args[1], params),
+        })
+    }
+
+    // 执行查询，返回 Map 数组，每个 Map 以列名为键
+    // 参数: db, sql, [params]
+    pub fn cn_query(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供数据库句柄和SQL".to_string();
+        }
+        let params = parse_params(args.get(2));
+        with_conn(args.first(), |conn| {
+            let rows = match conn {
+                Conn::Postgres(client) => pg_backend::query(client, &args[1], params)?,
+                Conn::Mysql(conn) => mysql_backend::query(conn, &args[1], params)?,
+            };
+            Ok(json_value(&JsonValue::Array(rows).to_string()))
+        })
+    }
+
+    // 执行查询，只返回第一行，没有结果时返回空值
+    // 参数: db, sql, [params]
+    pub fn cn_query_one(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供数据库句柄和SQL".to_string();
+        }
+        let params = parse_params(args.get(2));
+        with_conn(args.first(), |conn| {
+            let rows = match conn {
+                Conn::Postgres(client) => pg_backend::query(client, &args[1], params)?,
+                Conn::Mysql(conn) => mysql_backend::query(conn, &args[1], params)?,
+            };
+            Ok(json_value(&rows.into_iter().next().unwrap_or(JsonValue::Null).to_string()))
+        })
+    }
+
+    // 开始事务，返回事务句柄
+    // 参数: db
+    pub fn cn_begin(args: Vec<String>) -> String {
+        let pool_id = match args.first().and_then(|arg| lock_pools().parse_handle(arg)) {
+            Some(id) => id,
+            None => return "错误: 无效的数据库句柄".to_string(),
+        };
+        let mut conn = match checkout(pool_id) {
+            Ok(conn) => conn,
+            Err(e) => return e,
+        };
+        if let Err(e) = run_batch(&mut conn, "BEGIN") {
+            return e;
+        }
+        format!("{}{}", TX_PREFIX, lock_transactions().insert(Transaction { pool: pool_id, conn }))
+    }
+
+    // 提交事务，事务句柄随后失效
+    // 参数: tx
+    pub fn cn_commit(args: Vec<String>) -> String {
+        finish_transaction(args.first(), "COMMIT")
+    }
+
+    // 回滚事务，事务句柄随后失效
+    // 参数: tx
+    pub fn cn_rollback(args: Vec<String>) -> String {
+        finish_transaction(args.first(), "ROLLBACK")
+    }
+
+    fn finish_transaction(handle: Option<&String>, sql: &str) -> String {
+        let tx = {
+            let mut transactions = lock_transactions();
+            match handle.and_then(|arg| arg.strip_prefix(TX_PREFIX)).and_then(|id| transactions.parse_handle(id)) {
+                Some(id) => transactions.remove(id),
+                None => None,
+            }
+        };
+        let mut tx = match tx {
+            Some(tx) => tx,
+            None => return "错误: 无效的事务句柄".to_string(),
+        };
+        let result = run_batch(&mut tx.conn, sql);
+        // 执行失败时连接状态未知，不再放回连接池
+        match result {
+            Ok(()) => {
+                checkin(tx.pool, tx.conn);
+                "true".to_string()
+            },
+            Err(e) => e,
+        }
+    }
+
+    fn run_batch(conn: &mut Conn, sql: &str) -> Result<(), String> {
+        match conn {
+            Conn::Postgres(client) => client.batch_execute(sql).map_err(|e| format!("错误: SQL执行失败: {}", e)),
+            Conn::Mysql(conn) => {
+                use mysql::prelude::Queryable;
+                conn.query_drop(sql).map_err(|e| format!("错误: SQL执行失败: {}", e))
+            },
+        }
+    }
+
+    // SQL参数
+    pub(crate) enum Params {
+        Positional(Vec<JsonValue>),
+        Named(Map<String, JsonValue>),
+    }
+
+    fn parse_params(arg: Option<&String>) -> Params {
+        let value = match arg {
+            Some(arg) => serde_json::from_str::<JsonValue>(arg).unwrap_or_else(|_| JsonValue::String(arg.clone())),
+            None => return Params::Positional(Vec::new()),
+        };
+        match value {
+            JsonValue::Array(values) => Params::Positional(values),
+            JsonValue::Object(named) => Params::Named(named),
+            other => Params::Positional(vec![other]),
+        }
+    }
+}
+
+// PostgreSQL 后端
+// 参数按语句中推断出的类型转换，结果按列类型转换为对应的 JSON 值
+mod pg_backend {
+    use super::*;
+    use crate::db::Params;
+    use postgres::types::{FromSql, IsNull, Kind, ToSql, Type};
+    use postgres::{Client, Config, NoTls, Row};
+    use std::error::Error;
+    use std::str::FromStr;
+
+    type BoxError = Box<dyn Error + Sync + Send>;
+
+    // 简单的连接池：保留空闲连接，需要时再新建
+    pub struct Pool {
+        config: Config,
+        idle: Vec<Client>,
+        max_idle: usize,
+    }
+
+    impl Pool {
+        pub fn connect(url: &str, max_idle: usize) -> Result<Pool, String> {
+            let config = Config::from_str(url).map_err(|e| format!("错误: 无效的数据库URL: {}", e))?;
+            // 立即建立一个连接，尽早报告连接错误
+            let client = config.connect(NoTls).map_err(|e| format!("错误: 无法连接数据库: {}", e))?;
+            Ok(Pool { config, idle: vec![client], max_idle })
+        }
+
+        pub fn get(&mut self) -> Result<Client, String> {
+            while let Some(client) = self.idle.pop() {
+                if !client.is_closed() {
+                    return Ok(client);
+                }
+            }
+            self.config.connect(NoTls).map_err(|e| format!("错误: 无法连接数据库: {}", e))
+        }
+
+        pub fn put(&mut self, client: Client) {
+            if !client.is_closed() && self.idle.len() < self.max_idle {
+                self.idle.push(client);
+            }
+        }
+    }
+
+    pub fn execute(client: &mut Client, sql: &str, params: Params) -> Result<String, String> {
+        let stmt = client.prepare(sql).map_err(sql_error)?;
+        let values = bind(stmt.params(), params)?;
+        let refs: Vec<&(dyn ToSql + Sync)> = values.iter().map(|v| v.as_ref()).collect();
+        client.execute(&stmt, &refs).map(|n| n.to_string()).map_err(sql_error)
+    }
+
+    pub fn query(client: &mut Client, sql: &str, params: Params) -> Result<Vec<JsonValue>, String> {
+        let stmt = client.prepare(sql).map_err(sql_error)?;
+        let values = bind(stmt.params(), params)?;
+        let refs: Vec<&(dyn ToSql + Sync)> = values.iter().map(|v| v.as_ref()).collect();
+        let rows = client.query(&stmt, &refs).map_err(sql_error)?;
+        rows.iter().map(row_to_json).collect()
+    }
+
+    fn sql_error(e: postgres::Error) -> String {
+        match e.as_db_error() {
+            Some(db) => format!("错误: SQL执行失败: {}", db.message()),
+            None => format!("错误: SQL执行失败: {}", e),
+        }
+    }
+
+    // 按语句参数的类型转换参数值
+    fn bind(types: &[Type], params: Params) -> Result<Vec<Box<dyn ToSql + Sync>>, String> {
+        let values = match params {
+            Params::Positional(values) => values,
+            Params::Named(_) => return Err("错误: PostgreSQL 只支持位置参数（$1、$2），请以数组传入".to_string()),
+        };
+        if values.len() != types.len() {
+            return Err(format!("错误: SQL需要{}个参数，实际提供了{}个", types.len(), values.len()));
+        }
+        values.into_iter()
+            .zip(types)
+            .enumerate()
+            .map(|(i, (value, ty))| {
+                to_sql(value, ty).map_err(|e| format!("错误: 第{}个参数无法转换为 {}: {}", i + 1, ty.name(), e))
+            })
+            .collect()
+    }
+
+    fn to_sql(value: JsonValue, ty: &Type) -> Result<Box<dyn ToSql + Sync>, String> {
+        if value.is_null() {
+            return Ok(Box::new(Null));
+        }
+        let text = match &value {
+            JsonValue::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        let parse_err = |e: &dyn std::fmt::Display| e.to_string();
+
+        Ok(match *ty {
+            Type::BOOL => Box::new(match &value {
+                JsonValue::Bool(b) => *b,
+                JsonValue::Number(n) => n.as_f64().unwrap_or(0.0) != 0.0,
+                _ => text.trim().parse::<bool>().map_err(|e| parse_err(&e))?,
+            }),
+            Type::INT2 => Box::new(text.trim().parse::<i16>().map_err(|e| parse_err(&e))?),
+            Type::INT4 => Box::new(text.trim().parse::<i32>().map_err(|e| parse_err(&e))?),
+            Type::INT8 => Box::new(text.trim().parse::<i64>().map_err(|e| parse_err(&e))?),
+            Type::OID => Box::new(text.trim().parse::<u32>().map_err(|e| parse_err(&e))?),
+            Type::FLOAT4 => Box::new(text.trim().parse::<f32>().map_err(|e| parse_err(&e))?),
+            Type::FLOAT8 => Box::new(text.trim().parse::<f64>().map_err(|e| parse_err(&e))?),
+            Type::NUMERIC => Box::new(Numeric(encode_numeric(&text)?)),
+            Type::JSON | Type::JSONB => Box::new(value),
+            Type::BYTEA => Box::new(decode_hex(&text)?),
+            Type::UUID => Box::new(uuid::Uuid::parse_str(text.trim()).map_err(|e| parse_err(&e))?),
+            Type::DATE => Box::new(chrono::NaiveDate::from_str(text.trim()).map_err(|e| parse_err(&e))?),
+            Type::TIME => Box::new(chrono::NaiveTime::from_str(text.trim()).map_err(|e| parse_err(&e))?),
+            Type::TIMESTAMP => Box::new(parse_timestamp(&text)?),
+            Type::TIMESTAMPTZ => Box::new(
+                chrono::DateTime::parse_from_rfc3339(text.trim())
+                    .map(|t| t.with_timezone(&chrono::Utc))
+                    .map_err(|e| parse_err(&e))?,
+            ),
+            Type::TEXT | Type::VARCHAR | Type::BPCHAR | Type::NAME | Type::UNKNOWN => Box::new(text),
+            _ if matches!(ty.kind(), Kind::Enum(_)) => Box::new(EnumLabel(text)),
+            _ => return Err(format!("不支持该类型，请在SQL中先转换为文本，如 $n::text::{}", ty.name())),
+        })
+    }
+
+    fn parse_timestamp(text: &str) -> Result<chrono::NaiveDateTime, String> {
+        let text = text.trim();
+        chrono::NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f")
+            .or_else(|_| chrono::NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f"))
+            .map_err(|e| e.to_string())
+    }
+
+    // 任意类型的空值
+    #[derive(Debug)]
+    struct Null;
+
+    impl ToSql for Null {
+        fn to_sql(&self, _: &Type, _: &mut postgres::types::private::BytesMut) -> Result<IsNull, BoxError> {
+            Ok(IsNull::Yes)
+        }
+
+        fn accepts(_: &Type) -> bool {
+            true
+        }
+
+        postgres::types::to_sql_checked!();
+    }
+
+    // 枚举类型的值以文本标签传输
+    #[derive(Debug)]
+    struct EnumLabel(String);
+
+    impl ToSql for EnumLabel {
+        fn to_sql(&self, _: &Type, out: &mut postgres::types::private::BytesMut) -> Result<IsNull, BoxError> {
+            out.extend_from_slice(self.0.as_bytes());
+            Ok(IsNull::No)
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            matches!(ty.kind(), Kind::Enum(_))
+        }
+
+        postgres::types::to_sql_checked!();
+    }
+
+    // NUMERIC 的二进制表示，由 encode_numeric 生成
+    #[derive(Debug)]
+    struct Numeric(Vec<u8>);
+
+    impl ToSql for Numeric {
+        fn to_sql(&self, _: &Type, out: &mut postgres::types::private::BytesMut) -> Result<IsNull, BoxError> {
+            out.extend_from_slice(&self.0);
+            Ok(IsNull::No)
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            *ty == Type::NUMERIC
+        }
+
+        postgres::types::to_sql_checked!();
+    }
+
+    // 未专门处理的类型，读取原始的二进制表示
+    struct Raw(Vec<u8>);
+
+    impl<'a> FromSql<'a> for Raw {
+        fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+            Ok(Raw(raw.to_vec()))
+        }
+
+        fn accepts(_: &Type) -> bool {
+            true
+        }
+    }
+
+    fn row_to_json(row: &Row) -> Result<JsonValue, String> {
+        let mut obj = Map::new();
+        for (i, column) in row.columns().iter().enumerate() {
+            let value = column_to_json(row, i, column.type_())
+                .map_err(|e| format!("错误: 无法读取列 {}: {}", column.name(), e))?;
+            obj.insert(column.name().to_string(), value);
+        }
+        Ok(JsonValue::Object(obj))
+    }
+
+    fn column_to_json(row: &Row, i: usize, ty: &Type) -> Result<JsonValue, postgres::Error> {
+        fn get<'a, T: FromSql<'a>>(row: &'a Row, i: usize) -> Result<Option<T>, postgres::Error> {
+            row.try_get::<_, Option<T>>(i)
+        }
+
+        let value = match *ty {
+            Type::BOOL => get::<bool>(row, i)?.map(JsonValue::from),
+            Type::INT2 => get::<i16>(row, i)?.map(JsonValue::from),
+            Type::INT4 => get::<i32>(row, i)?.map(JsonValue::from),
+            Type::INT8 => get::<i64>(row, i)?.map(JsonValue::from),
+            Type::OID => get::<u32>(row, i)?.map(JsonValue::from),
+            Type::FLOAT4 => get::<f32>(row, i)?.map(|f| JsonValue::from(f as f64)),
+            Type::FLOAT8 => get::<f64>(row, i)?.map(JsonValue::from),
+            Type::JSON | Type::JSONB => get::<JsonValue>(row, i)?,
+            Type::BYTEA => get::<Vec<u8>>(row, i)?.map(|bytes| JsonValue::String(encode_hex(&bytes))),
+            Type::UUID => get::<uuid::Uuid>(row, i)?.map(|u| JsonValue::String(u.to_string())),
+            Type::DATE => get::<chrono::NaiveDate>(row, i)?.map(|d| JsonValue::String(d.to_string())),
+            Type::TIME => get::<chrono::NaiveTime>(row, i)?.map(|t| JsonValue::String(t.to_string())),
+            Type::TIMESTAMP => get::<chrono::NaiveDateTime>(row, i)?
+                .map(|t| JsonValue::String(t.format("%Y-%m-%d %H:%M:%S%.f").to_string())),
+            Type::TIMESTAMPTZ => get::<chrono::DateTime<chrono::Utc>>(row, i)?
+                .map(|t| JsonValue::String(t.to_rfc3339())),
+            Type::TEXT | Type::VARCHAR | Type::BPCHAR | Type::NAME => get::<String>(row, i)?.map(JsonValue::String),
+            // NUMERIC 以字符串返回，避免丢失精度
+            Type::NUMERIC => get::<Raw>(row, i)?.map(|raw| JsonValue::String(decode_numeric(&raw.0))),
+            _ => get::<Raw>(row, i)?.map(|raw| match String::from_utf8(raw.0) {
+                Ok(text) => JsonValue::String(text),
+                Err(e) => JsonValue::String(encode_hex(e.as_bytes())),
+            }),
+        };
+        Ok(value.unwrap_or(JsonValue::Null))
+    }
+
+    // 解码 NUMERIC 的二进制表示：以10000为基数的数字组，weight 为第一组的指数
+    fn decode_numeric(raw: &[u8]) -> String {
+        let read = |pos: usize| raw.get(pos..pos + 2).map(|b| i16::from_be_bytes([b[0], b[1]])).unwrap_or(0);
+        let ndigits = read(0).max(0) as usize;
+        let weight = read(2) as i32;
+        let sign = read(4) as u16;
+        let dscale = read(6).max(0) as usize;
+        match sign {
+            0xC000 => return "NaN".to_string(),
+            0xD000 => return "Infinity".to_string(),
+            0xF000 => return "-Infinity".to_string(),
+            _ => {},
+        }
+        let digit = |index: i32| {
+            if index < 0 || index as usize >= ndigits { 0 } else { read(8 + index as usize * 2) }
+        };
+
+        let mut result = String::new();
+        if sign == 0x4000 {
+            result.push('-');
+        }
+        if weight < 0 {
+            result.push('0');
+        } else {
+            result.push_str(&digit(0).to_string());
+            for index in 1..=weight {
+                result.push_str(&format!("{:04}", digit(index)));
+            }
+        }
+        if dscale > 0 {
+            let mut fraction = String::new();
+            let mut index = weight + 1;
+            while fraction.len() < dscale {
+                fraction.push_str(&format!("{:04}", digit(index)));
+                index += 1;
+            }
+            fraction.truncate(dscale);
+            result.push('.');
+            result.push_str(&fraction);
+        }
+        result
+    }
+
+    // 把十进制文本编码为 NUMERIC 的二进制表示，与 decode_numeric 相反
+    fn encode_numeric(text: &str) -> Result<Vec<u8>, String> {
+        let text = text.trim();
+        let header = |ndigits: i16, weight: i16, sign: u16, dscale: u16| {
+            [ndigits.to_be_bytes(), weight.to_be_bytes(), sign.to_be_bytes(), dscale.to_be_bytes()].concat()
+        };
+        if text.eq_ignore_ascii_case("nan") {
+            return Ok(header(0, 0, 0xC000, 0));
+        }
+
+        let (negative, unsigned) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let (int_part, frac_part) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if int_part.is_empty() && frac_part.is_empty()
+            || !int_part.bytes().chain(frac_part.bytes()).all(|b| b.is_ascii_digit())
+        {
+            return Err(format!("无效的数字: {}", text));
+        }
+
+        // 以小数点为界按4位分组
+        let int_part = int_part.trim_start_matches('0');
+        let int_padded = format!("{}{}", "0".repeat((4 - int_part.len() % 4) % 4), int_part);
+        let frac_padded = format!("{}{}", frac_part, "0".repeat((4 - frac_part.len() % 4) % 4));
+        let mut groups: Vec<i16> = int_padded.as_bytes().chunks(4)
+            .chain(frac_padded.as_bytes().chunks(4))
+            .map(|chunk| std::str::from_utf8(chunk).unwrap_or("0").parse::<i16>().unwrap_or(0))
+            .collect();
+        let mut weight = (int_padded.len() / 4) as i32 - 1;
+
+        // 去掉首尾的0组
+        let leading = groups.iter().take_while(|&&g| g == 0).count();
+        groups.drain(..leading);
+        weight -= leading as i32;
+        while groups.last() == Some(&0) {
+            groups.pop();
+        }
+        if groups.is_empty() {
+            weight = 0;
+        }
+
+        let mut bytes = header(groups.len() as i16, weight as i16, if negative { 0x4000 } else { 0 }, frac_part.len() as u16);
+        for group in groups {
+            bytes.extend_from_slice(&group.to_be_bytes());
+        }
+        Ok(bytes)
+    }
+
+    fn encode_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn decode_hex(hex: &str) -> Result<Vec<u8>, String> {
+        let trimmed = hex.trim();
+        let digits = trimmed.strip_prefix("\\x").or_else(|| trimmed.strip_prefix("0x")).unwrap_or(trimmed).as_bytes();
+        if digits.len() % 2 == 1 {
+            return Err("16进制字符串的长度必须是偶数".to_string());
+        }
+        digits.chunks(2)
+            .map(|pair| {
+                std::str::from_utf8(pair).ok()
+                    .and_then(|s| u8::from_str_radix(s, 16).ok())
+                    .ok_or_else(|| format!("无效的16进制字符: {}", String::from_utf8_lossy(pair)))
+            })
+            .collect()
+    }
+}
+
+// MySQL 后端
+mod mysql_backend {
+    use super::*;
+    use crate::db::Params;
+    use mysql::consts::ColumnType;
+    use mysql::prelude::Queryable;
+    use mysql::{Column, Opts, OptsBuilder, PoolConstraints, PoolOpts, PooledConn, Value};
+
+    // BINARY、VARBINARY 和 BLOB 列使用的字符集编号
+    const BINARY_CHARSET: u16 = 63;
+
+    pub fn connect(url: &str, pool_size: usize) -> Result<mysql::Pool, String> {
+        let opts = Opts::from_url(url).map_err(|e| format!("错误: 无效的数据库URL: {}", e))?;
+        let constraints = PoolConstraints::new(1, pool_size).unwrap_or_default();
+        let opts = OptsBuilder::from_opts(opts).pool_opts(PoolOpts::default().with_constraints(constraints));
+        mysql::Pool::new(opts).map_err(|e| format!("错误: 无法连接数据库: {}", e))
+    }
+
+    pub fn execute(conn: &mut PooledConn, sql: &str, params: Params) -> Result<String, String> {
+        let params = to_params(params);
+        let result = match params {
+            // 没有参数时使用文本协议，可以执行不支持预处理的语句
+            mysql::Params::Empty => conn.query_drop(sql),
+            params => conn.exec_drop(sql, params),
+        };
+        result.map_err(sql_error)?;
+        Ok(conn.affected_rows().to_string())
+    }
+
+    pub fn query(conn: &mut PooledConn, sql: &str, params: Params) -> Result<Vec<JsonValue>, String> {
+        let result = conn.exec_iter(sql, to_params(params)).map_err(sql_error)?;
+        let mut rows = Vec::new();
+        for row in result {
+            let row = row.map_err(sql_error)?;
+            let mut obj = Map::new();
+            for (i, column) in row.columns_ref().iter().enumerate() {
+                let value = row.as_ref(i).map(|v| to_json(v, column)).unwrap_or(JsonValue::Null);
+                obj.insert(column.name_str().into_owned(), value);
+            }
+            rows.push(JsonValue::Object(obj));
+        }
+        Ok(rows)
+    }
+
+    fn sql_error(e: mysql::Error) -> String {
+        format!("错误: SQL执行失败: {}", e)
+    }
+
+    fn to_params(params: Params) -> mysql::Params {
+        match params {
+            Params::Positional(values) if values.is_empty() => mysql::Params::Empty,
+            Params::Positional(values) => mysql::Params::Positional(values.into_iter().map(to_value).collect()),
+            Params::Named(named) => mysql::Params::Named(
+                named.into_iter()
+                    .map(|(key, value)| (key.trim_start_matches(':').as_bytes().to_vec(), to_value(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn to_value(value: JsonValue) -> Value {
+        match value {
+            JsonValue::Null => Value::NULL,
+            JsonValue::Bool(b) => Value::Int(b as i64),
+            JsonValue::Number(n) => match (n.as_i64(), n.as_u64()) {
+                (Some(i), _) => Value::Int(i),
+                (None, Some(u)) => Value::UInt(u),
+                _ => Value::Double(n.as_f64().unwrap_or(0.0)),
+            },
+            JsonValue::String(s) => Value::Bytes(s.into_bytes()),
+            other => Value::Bytes(other.to_string().into_bytes()),
+        }
+    }
+
+    fn to_json(value: &Value, column: &Column) -> JsonValue {
+        match value {
+            Value::NULL => JsonValue::Null,
+            Value::Int(i) => JsonValue::from(*i),
+            Value::UInt(u) => JsonValue::from(*u),
+            Value::Float(f) => JsonValue::from(*f as f64),
+            Value::Double(f) => JsonValue::from(*f),
+            Value::Bytes(bytes) => bytes_to_json(bytes, column),
+            Value::Date(year, month, day, hour, minute, second, micros) => {
+                let mut text = format!("{:04}-{:02}-{:02}", year, month, day);
+                if column.column_type() != ColumnType::MYSQL_TYPE_DATE {
+                    text.push_str(&format!(" {:02}:{:02}:{:02}", hour, minute, second));
+                    if *micros > 0 {
+                        text.push_str(&format!(".{:06}", micros));
+                    }
+                }
+                JsonValue::String(text)
+            },
+            Value::Time(negative, days, hours, minutes, seconds, micros) => {
+                let mut text = format!("{}{:02}:{:02}:{:02}", if *negative { "-" } else { "" }, *days * 24 + *hours as u32, minutes, seconds);
+                if *micros > 0 {
+                    text.push_str(&format!(".{:06}", micros));
+                }
+                JsonValue::String(text)
+            },
+        }
+    }
+
+    // 文本协议下所有值都以字节返回，按列类型还原
+    fn bytes_to_json(bytes: &[u8], column: &Column) -> JsonValue {
+        let text = match std::str::from_utf8(bytes) {
+            Ok(text) if column.character_set() != BINARY_CHARSET || is_textual(column.column_type()) => text,
+            _ => return JsonValue::String(bytes.iter().map(|b| format!("{:02x}", b)).collect()),
+        };
+        match column.column_type() {
+            ColumnType::MYSQL_TYPE_TINY
+            | ColumnType::MYSQL_TYPE_SHORT
+            | ColumnType::MYSQL_TYPE_INT24
+            | ColumnType::MYSQL_TYPE_LONG
+            | ColumnType::MYSQL_TYPE_LONGLONG
+            | ColumnType::MYSQL_TYPE_YEAR => text.parse::<i64>().map(JsonValue::from)
+                .or_else(|_| text.parse::<u64>().map(JsonValue::from))
+                .unwrap_or_else(|_| JsonValue::String(text.to_string())),
+            ColumnType::MYSQL_TYPE_FLOAT | ColumnType::MYSQL_TYPE_DOUBLE => text.parse::<f64>().map(JsonValue::from)
+                .unwrap_or_else(|_| JsonValue::String(text.to_string())),
+            ColumnType::MYSQL_TYPE_JSON => serde_json::from_str(text).unwrap_or_else(|_| JsonValue::String(text.to_string())),
+            // DECIMAL 以字符串返回，避免丢失精度
+            _ => JsonValue::String(text.to_string()),
+        }
+    }
+
+    // 字符集为 binary 但内容是文本的列类型（数字、日期等在文本协议下也使用 binary 字符集）
+    fn is_textual(ty: ColumnType) -> bool {
+        !matches!(
+            ty,
+            ColumnType::MYSQL_TYPE_STRING
+                | ColumnType::MYSQL_TYPE_VAR_STRING
+                | ColumnType::MYSQL_TYPE_VARCHAR
+                | ColumnType::MYSQL_TYPE_BLOB
+                | ColumnType::MYSQL_TYPE_TINY_BLOB
+                | ColumnType::MYSQL_TYPE_MEDIUM_BLOB
+                | ColumnType::MYSQL_TYPE_LONG_BLOB
+                | ColumnType::MYSQL_TYPE_BIT
+                | ColumnType::MYSQL_TYPE_GEOMETRY
+        )
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册数据库命名空间下的函数
+    let db_ns = registry.namespace("db");
+    db_ns.add_function("connect", db::cn_connect)
+         .add_function("close", db::cn_close)
+         .add_function("exec", db::cn_exec)
+         .add_function("query", db::cn_query)
+         .add_function("query_one", db::cn_query_one)
+         .add_function("begin", db::cn_begin)
+         .add_function("commit", db::cn_commit)
+         .add_function("rollback", db::cn_rollback);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
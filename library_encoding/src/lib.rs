@@ -0,0 +1,279 @@
+use ::std::collections::HashMap;
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE, URL_SAFE_NO_PAD};
+use base64::Engine;
+use encoding_rs::Encoding;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+// 导入通用库
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+
+// 编码命名空间
+// 二进制数据统一使用16进制字符串表示，与 file::read_bytes 的返回值相同
+mod encoding {
+    use super::*;
+
+    // URL组件中不需要编码的字符（RFC 3986 非保留字符）
+    const COMPONENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+
+    // Base64编码（标准字母表，带填充）
+    // 参数: text
+    pub fn cn_base64_encode(args: Vec<String>) -> String {
+        match args.first() {
+            Some(text) => STANDARD.encode(text.as_bytes()),
+            None => "错误: 请提供要编码的文本".to_string(),
+        }
+    }
+
+    // Base64解码，结果必须是UTF-8文本；二进制数据请使用 base64_to_hex
+    // 参数: text
+    pub fn cn_base64_decode(args: Vec<String>) -> String {
+        match args.first() {
+            Some(text) => match decode_base64(text, false) {
+                Ok(bytes) => bytes_to_text(bytes, "base64_to_hex"),
+                Err(e) => e,
+            },
+            None => "错误: 请提供要解码的文本".to_string(),
+        }
+    }
+
+    // URL安全的Base64编码（使用 - 和 _，不带填充），适合用于URL和文件名
+    // 参数: text
+    pub fn cn_base64url_encode(args: Vec<String>) -> String {
+        match args.first() {
+            Some(text) => URL_SAFE_NO_PAD.encode(text.as_bytes()),
+            None => "错误: 请提供要编码的文本".to_string(),
+        }
+    }
+
+    // URL安全的Base64解码，有无填充均可
+    // 参数: text
+    pub fn cn_base64url_decode(args: Vec<String>) -> String {
+        match args.first() {
+            Some(text) => match decode_base64(text, true) {
+                Ok(bytes) => bytes_to_text(bytes, "base64_to_hex"),
+                Err(e) => e,
+            },
+            None => "错误: 请提供要解码的文本".to_string(),
+        }
+    }
+
+    // 将16进制表示的二进制数据编码为Base64
+    // 参数: hex, [url_safe]（默认 false）
+    pub fn cn_hex_to_base64(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供16进制字符串".to_string();
+        }
+        let bytes = match decode_hex(&args[0]) {
+            Ok(bytes) => bytes,
+            Err(e) => return e,
+        };
+        if args.get(1).map(|s| s.trim() == "true").unwrap_or(false) {
+            URL_SAFE_NO_PAD.encode(bytes)
+        } else {
+            STANDARD.encode(bytes)
+        }
+    }
+
+    // 将Base64解码为16进制表示的二进制数据，标准和URL安全的字母表均可
+    // 参数: text
+    pub fn cn_base64_to_hex(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供要解码的文本".to_string();
+        }
+        let url_safe = args[0].contains(['-', '_']);
+        match decode_base64(&args[0], url_safe) {
+            Ok(bytes) => encode_hex(&bytes),
+            Err(e) => e,
+        }
+    }
+
+    // 将文本的UTF-8字节编码为16进制字符串
+    // 参数: text, [uppercase]（默认 false）
+    pub fn cn_hex_encode(args: Vec<String>) -> String {
+        let text = match args.first() {
+            Some(text) => text,
+            None => return "错误: 请提供要编码的文本".to_string(),
+        };
+        let hex = encode_hex(text.as_bytes());
+        if args.get(1).map(|s| s.trim() == "true").unwrap_or(false) {
+            hex.to_uppercase()
+        } else {
+            hex
+        }
+    }
+
+    // 将16进制字符串解码为UTF-8文本，忽略空白和 0x 前缀
+    // 参数: hex
+    pub fn cn_hex_decode(args: Vec<String>) -> String {
+        match args.first() {
+            Some(hex) => match decode_hex(hex) {
+                Ok(bytes) => bytes_to_text(bytes, "decode_charset"),
+                Err(e) => e,
+            },
+            None => "错误: 请提供16进制字符串".to_string(),
+        }
+    }
+
+    // URL百分号编码，编码除字母、数字和 -_.~ 以外的所有字符
+    // 参数: text, [form]（为 true 时按表单格式把空格编码为 +）
+    pub fn cn_url_encode(args: Vec<String>) -> String {
+        let text = match args.first() {
+            Some(text) => text,
+            None => return "错误: 请提供要编码的文本".to_string(),
+        };
+        let form = args.get(1).map(|s| s.trim() == "true").unwrap_or(false);
+        if form {
+            text.split(' ')
+                .map(|part| utf8_percent_encode(part, COMPONENT).to_string())
+                .collect::<Vec<String>>()
+                .join("+")
+        } else {
+            utf8_percent_encode(text, COMPONENT).to_string()
+        }
+    }
+
+    // URL百分号解码
+    // 参数: text, [form]（为 true 时把 + 解码为空格）
+    pub fn cn_url_decode(args: Vec<String>) -> String {
+        let text = match args.first() {
+            Some(text) => text,
+            None => return "错误: 请提供要解码的文本".to_string(),
+        };
+        let form = args.get(1).map(|s| s.trim() == "true").unwrap_or(false);
+        let input = if form { text.replace('+', " ") } else { text.clone() };
+        match percent_decode_str(&input).decode_utf8() {
+            Ok(decoded) => decoded.into_owned(),
+            Err(_) => "错误: 解码结果不是有效的UTF-8文本".to_string(),
+        }
+    }
+
+    // 将文本编码为指定字符集的字节，返回16进制字符串
+    // 字符集名称遵循WHATWG标准，如 gbk、gb18030、big5、shift_jis、utf-16le、windows-1252
+    // 参数: text, charset
+    pub fn cn_encode_charset(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供文本和字符集".to_string();
+        }
+        let encoding = match lookup_charset(&args[1]) {
+            Ok(encoding) => encoding,
+            Err(e) => return e,
+        };
+        // encoding_rs 的编码器不支持输出 UTF-16，单独处理
+        let bytes: Vec<u8> = if encoding == encoding_rs::UTF_16LE {
+            args[0].encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect()
+        } else if encoding == encoding_rs::UTF_16BE {
+            args[0].encode_utf16().flat_map(|unit| unit.to_be_bytes()).collect()
+        } else {
+            let (bytes, _, had_errors) = encoding.encode(&args[0]);
+            if had_errors {
+                return format!("错误: 文本中包含无法用 {} 表示的字符", encoding.name());
+            }
+            bytes.into_owned()
+        };
+        encode_hex(&bytes)
+    }
+
+    // 按指定字符集解码16进制表示的字节，返回UTF-8文本，可以配合 file::read_bytes 读取非UTF-8文件
+    // 参数: hex, charset
+    pub fn cn_decode_charset(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供16进制字符串和字符集".to_string();
+        }
+        let encoding = match lookup_charset(&args[1]) {
+            Ok(encoding) => encoding,
+            Err(e) => return e,
+        };
+        let bytes = match decode_hex(&args[0]) {
+            Ok(bytes) => bytes,
+            Err(e) => return e,
+        };
+        let (text, had_errors) = encoding.decode_without_bom_handling(&bytes);
+        if had_errors {
+            return format!("错误: 数据不是有效的 {} 编码", encoding.name());
+        }
+        text.into_owned()
+    }
+
+    // 在字符集之间转换文本，等价于先 decode_charset 再 encode_charset
+    // 参数: hex, from_charset, to_charset
+    pub fn cn_convert_charset(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "错误: 请提供16进制字符串、源字符集和目标字符集".to_string();
+        }
+        let text = cn_decode_charset(vec![args[0].clone(), args[1].clone()]);
+        if text.starts_with("错误:") {
+            return text;
+        }
+        cn_encode_charset(vec![text, args[2].clone()])
+    }
+
+    fn lookup_charset(label: &str) -> Result<&'static Encoding, String> {
+        Encoding::for_label(label.trim().as_bytes()).ok_or_else(|| format!("错误: 不支持的字符集: {}", label))
+    }
+
+    fn decode_base64(text: &str, url_safe: bool) -> Result<Vec<u8>, String> {
+        let cleaned: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        let (padded, unpadded) = if url_safe { (&URL_SAFE, &URL_SAFE_NO_PAD) } else { (&STANDARD, &STANDARD_NO_PAD) };
+        let result = if cleaned.ends_with('=') {
+            padded.decode(&cleaned)
+        } else {
+            unpadded.decode(&cleaned)
+        };
+        result.map_err(|e| format!("错误: 无效的Base64数据: {}", e))
+    }
+
+    fn encode_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn decode_hex(hex: &str) -> Result<Vec<u8>, String> {
+        let trimmed = hex.trim();
+        let digits: Vec<u8> = trimmed.strip_prefix("0x").or_else(|| trimmed.strip_prefix("0X")).unwrap_or(trimmed)
+            .bytes()
+            .filter(|b| !b.is_ascii_whitespace())
+            .collect();
+        if digits.len() % 2 == 1 {
+            return Err("错误: 16进制字符串的长度必须是偶数".to_string());
+        }
+        digits.chunks(2)
+            .map(|pair| {
+                std::str::from_utf8(pair).ok()
+                    .and_then(|s| u8::from_str_radix(s, 16).ok())
+                    .ok_or_else(|| format!("错误: 无效的16进制字符: {}", String::from_utf8_lossy(pair)))
+            })
+            .collect()
+    }
+
+    fn bytes_to_text(bytes: Vec<u8>, hint: &str) -> String {
+        match String::from_utf8(bytes) {
+            Ok(text) => text,
+            Err(_) => format!("错误: 解码结果不是有效的UTF-8文本，二进制数据请使用 {}", hint),
+        }
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册编码命名空间下的函数
+    let encoding_ns = registry.namespace("encoding");
+    encoding_ns.add_function("base64_encode", encoding::cn_base64_encode)
+               .add_function("base64_decode", encoding::cn_base64_decode)
+               .add_function("base64url_encode", encoding::cn_base64url_encode)
+               .add_function("base64url_decode", encoding::cn_base64url_decode)
+               .add_function("hex_to_base64", encoding::cn_hex_to_base64)
+               .add_function("base64_to_hex", encoding::cn_base64_to_hex)
+               .add_function("hex_encode", encoding::cn_hex_encode)
+               .add_function("hex_decode", encoding::cn_hex_decode)
+               .add_function("url_encode", encoding::cn_url_encode)
+               .add_function("url_decode", encoding::cn_url_decode)
+               .add_function("encode_charset", encoding::cn_encode_charset)
+               .add_function("decode_charset", encoding::cn_decode_charset)
+               .add_function("convert_charset", encoding::cn_convert_charset);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
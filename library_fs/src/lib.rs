@@ -2,9 +2,15 @@ use ::std::collections::HashMap;
 use ::std::fs;
 use ::std::path::Path;
 use ::std::io::Write;
+use ::std::time::{Duration, SystemTime, UNIX_EPOCH};
+use globset::{GlobBuilder, GlobMatcher};
+use serde_json::{Map, Value as JsonValue};
+use sha2::Digest;
+use walkdir::WalkDir;
 
 // 导入通用库
-use cn_common::namespace::{LibraryFunction, create_library_pointer, register_namespaces};
+use cn_common::namespace::{LibraryFunction, NamespaceBuilder, create_library_pointer, register_namespaces};
+use cn_common::value::json_value;
 
 // 根命名空间函数
 // 判断路径是否存在
@@ -37,9 +43,85 @@ fn cn_is_dir(args: Vec<String>) -> String {
     Path::new(path).is_dir().to_string()
 }
 
+// 判断是否为符号链接（Windows上包括目录联接），不跟随链接
+fn cn_is_symlink(args: Vec<String>) -> String {
+    if args.is_empty() {
+        return "false".to_string();
+    }
+    
+    let path = &args[0];
+    Path::new(path).is_symlink().to_string()
+}
+
+// 编译glob模式，* 和 ? 不匹配 /，** 匹配任意层目录
+fn glob_matcher(pattern: &str) -> Result<GlobMatcher, String> {
+    GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .map(|glob| glob.compile_matcher())
+        .map_err(|err| format!("ERROR: 无效的glob模式 {}: {}", pattern, err))
+}
+
+// 相对于 root 的路径，以 / 分隔，供glob模式匹配
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
 // 文件操作命名空间
 mod file {
     use super::*;
+    use ::std::collections::BTreeMap;
+    use ::std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+    use ::std::path::PathBuf;
+    use ::std::sync::{Mutex, MutexGuard};
+    use cn_common::handle::HandleTable;
+    use filetime::FileTime;
+    use fs2::FileExt;
+    
+    // read_lines 打开的文件
+    struct LineReader {
+        reader: BufReader<fs::File>,
+        // has_next_line 预读的行
+        peeked: Option<String>,
+    }
+    
+    impl LineReader {
+        // 读取下一行（不含换行符），读完时返回 None；不是有效UTF-8的字节被替换
+        fn advance(&mut self) -> Result<Option<String>, String> {
+            let mut line = Vec::new();
+            match self.reader.read_until(b'\n', &mut line) {
+                Ok(0) => Ok(None),
+                Ok(_) => {
+                    if line.ends_with(b"\n") {
+                        line.pop();
+                        if line.ends_with(b"\r") {
+                            line.pop();
+                        }
+                    }
+                    Ok(Some(String::from_utf8_lossy(&line).into_owned()))
+                },
+                Err(err) => Err(format!("ERROR: {}", err))
+            }
+        }
+    }
+    
+    static LINE_READERS: Mutex<HandleTable<LineReader>> = Mutex::new(HandleTable::new());
+    
+    fn lock_line_readers() -> MutexGuard<'static, HandleTable<LineReader>> {
+        LINE_READERS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    
+    // lock 和 try_lock 持有的文件锁，键为规范化的路径；文件关闭时锁随之释放
+    static FILE_LOCKS: Mutex<BTreeMap<PathBuf, fs::File>> = Mutex::new(BTreeMap::new());
+    
+    fn lock_file_locks() -> MutexGuard<'static, BTreeMap<PathBuf, fs::File>> {
+        FILE_LOCKS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
     
     // 读取文件内容
     pub fn cn_read(args: Vec<String>) -> String {
@@ -73,6 +155,100 @@ mod file {
         }
     }
     
+    // 打开文件逐行读取，返回读取句柄；文件内容不会一次读入内存
+    // 参数: path
+    pub fn cn_read_lines(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "ERROR: 需要文件路径参数".to_string();
+        }
+        
+        match fs::File::open(&args[0]) {
+            Ok(file) => lock_line_readers().insert(LineReader { reader: BufReader::new(file), peeked: None }).to_string(),
+            Err(err) => format!("ERROR: {}", err)
+        }
+    }
+    
+    // 检查是否还有行
+    // 参数: handle
+    pub fn cn_has_next_line(args: Vec<String>) -> String {
+        let mut readers = lock_line_readers();
+        let reader = match args.first().and_then(|arg| readers.parse_handle(arg)).and_then(|id| readers.get_mut(id)) {
+            Some(reader) => reader,
+            None => return "ERROR: 无效的读取句柄".to_string(),
+        };
+        if reader.peeked.is_none() {
+            match reader.advance() {
+                Ok(next) => reader.peeked = next,
+                Err(err) => return err,
+            }
+        }
+        reader.peeked.is_some().to_string()
+    }
+    
+    // 读取下一行（不含换行符），不是有效UTF-8的字节被替换；没有更多行时返回错误信息
+    // 参数: handle
+    pub fn cn_next_line(args: Vec<String>) -> String {
+        let mut readers = lock_line_readers();
+        let reader = match args.first().and_then(|arg| readers.parse_handle(arg)).and_then(|id| readers.get_mut(id)) {
+            Some(reader) => reader,
+            None => return "ERROR: 无效的读取句柄".to_string(),
+        };
+        let next = match reader.peeked.take() {
+            Some(line) => Ok(Some(line)),
+            None => reader.advance(),
+        };
+        match next {
+            Ok(Some(line)) => line,
+            Ok(None) => "ERROR: 没有更多行".to_string(),
+            Err(err) => err,
+        }
+    }
+    
+    // 关闭读取句柄
+    // 参数: handle
+    pub fn cn_close_lines(args: Vec<String>) -> String {
+        let mut readers = lock_line_readers();
+        match args.first().and_then(|arg| readers.parse_handle(arg)) {
+            Some(id) => readers.remove(id).is_some().to_string(),
+            None => "false".to_string(),
+        }
+    }
+    
+    // 读取文件中从 offset 开始的最多 len 个字节，超出文件末尾的部分被忽略
+    // 默认作为文本返回（不是有效UTF-8的字节被替换），format 为 "hex" 时返回16进制字符串
+    // 参数: path, offset, len, [format]
+    pub fn cn_read_range(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "ERROR: 需要三个参数: 文件路径、偏移和长度".to_string();
+        }
+        
+        let (offset, len) = match (args[1].trim().parse::<u64>(), args[2].trim().parse::<u64>()) {
+            (Ok(offset), Ok(len)) => (offset, len),
+            _ => return "ERROR: 偏移和长度必须是非负整数".to_string(),
+        };
+        let hex = match args.get(3).map(|f| f.trim()) {
+            Some("hex") => true,
+            Some("text") | Some("") | None => false,
+            Some(other) => return format!("ERROR: 格式必须是 \"text\" 或 \"hex\": {}", other),
+        };
+        let mut file = match fs::File::open(&args[0]) {
+            Ok(file) => file,
+            Err(err) => return format!("ERROR: {}", err)
+        };
+        if let Err(err) = file.seek(SeekFrom::Start(offset)) {
+            return format!("ERROR: {}", err);
+        }
+        let mut bytes = Vec::new();
+        if let Err(err) = file.take(len).read_to_end(&mut bytes) {
+            return format!("ERROR: {}", err);
+        }
+        if hex {
+            bytes.iter().map(|b| format!("{:02x}", b)).collect()
+        } else {
+            String::from_utf8_lossy(&bytes).into_owned()
+        }
+    }
+    
     // 写入文件
     pub fn cn_write(args: Vec<String>) -> String {
         if args.len() < 2 {
@@ -98,7 +274,6 @@ mod file {
         let content = &args[1];
         
         let mut file = match fs::OpenOptions::new()
-            .write(true)
             .append(true)
             .create(true)
             .open(path) {
@@ -112,6 +287,82 @@ mod file {
         }
     }
     
+    // 原子地写入文件：先写入同一目录下的临时文件并同步到磁盘，再改名替换目标文件，
+    // 中途中断时目标文件保持原来的内容；替换已有文件时保留它的权限
+    // 参数: path, content
+    pub fn cn_write_atomic(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "ERROR: 需要两个参数: 文件路径和内容".to_string();
+        }
+        
+        let path = Path::new(&args[0]);
+        let name = match path.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => return format!("ERROR: 无效的文件路径: {}", args[0]),
+        };
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.subsec_nanos()).unwrap_or(0);
+        let temp = path.with_file_name(format!(".{}.{}.{}.tmp", name, ::std::process::id(), nanos));
+        
+        let written = (|| {
+            let mut file = fs::OpenOptions::new().write(true).create_new(true).open(&temp)?;
+            file.write_all(args[1].as_bytes())?;
+            if let Ok(metadata) = fs::metadata(path) {
+                file.set_permissions(metadata.permissions())?;
+            }
+            file.sync_all()?;
+            fs::rename(&temp, path)
+        })();
+        match written {
+            Ok(_) => {
+                sync_parent(path);
+                "true".to_string()
+            },
+            Err(err) => {
+                let _ = fs::remove_file(&temp);
+                format!("ERROR: {}", err)
+            }
+        }
+    }
+    
+    // 在文件末尾追加一行，自动补上换行符；一行内容一次写入，不会与其他进程追加的内容交错
+    // 参数: path, line
+    pub fn cn_append_line(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "ERROR: 需要两个参数: 文件路径和内容".to_string();
+        }
+        
+        let mut line = args[1].clone();
+        if !line.ends_with('\n') {
+            line.push('\n');
+        }
+        let mut file = match fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&args[0]) {
+                Ok(file) => file,
+                Err(err) => return format!("ERROR: {}", err)
+            };
+        match file.write_all(line.as_bytes()) {
+            Ok(_) => "true".to_string(),
+            Err(err) => format!("ERROR: {}", err)
+        }
+    }
+    
+    // 同步目录，使改名在断电后也能保留；只在类Unix系统上需要，失败时忽略
+    #[cfg(unix)]
+    fn sync_parent(path: &Path) {
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        if let Ok(dir) = fs::File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+    
+    #[cfg(not(unix))]
+    fn sync_parent(_path: &Path) {}
+    
     // 删除文件
     pub fn cn_delete(args: Vec<String>) -> String {
         if args.is_empty() {
@@ -167,11 +418,346 @@ mod file {
             Err(err) => format!("ERROR: {}", err)
         }
     }
+    
+    // 给文件加建议锁，文件不存在时创建；锁被其他进程持有时等待其释放
+    // 建议锁只约束同样加锁的进程，不阻止直接读写；进程退出时系统自动释放
+    // 参数: path, [shared]（true 表示共享锁，可以被多个进程同时持有，默认为排他锁）
+    pub fn cn_lock(args: Vec<String>) -> String {
+        acquire_lock(&args, true)
+    }
+    
+    // 尝试给文件加建议锁，不等待；成功返回 true，锁被其他进程持有时返回 false
+    // 参数: path, [shared]
+    pub fn cn_try_lock(args: Vec<String>) -> String {
+        acquire_lock(&args, false)
+    }
+    
+    // 释放 lock 或 try_lock 加的锁，没有持有锁时返回 false
+    // 参数: path
+    pub fn cn_unlock(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "ERROR: 需要文件路径参数".to_string();
+        }
+        
+        let key = match fs::canonicalize(&args[0]) {
+            Ok(key) => key,
+            Err(_) => return "false".to_string(),
+        };
+        match lock_file_locks().remove(&key) {
+            Some(file) => match FileExt::unlock(&file) {
+                Ok(_) => "true".to_string(),
+                Err(err) => format!("ERROR: {}", err)
+            },
+            None => "false".to_string(),
+        }
+    }
+    
+    fn acquire_lock(args: &[String], wait: bool) -> String {
+        if args.is_empty() {
+            return "ERROR: 需要文件路径参数".to_string();
+        }
+        
+        let shared = args.get(1).map(|s| s.trim() == "true").unwrap_or(false);
+        let file = match fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&args[0]) {
+                Ok(file) => file,
+                Err(err) => return format!("ERROR: {}", err)
+            };
+        let key = match fs::canonicalize(&args[0]) {
+            Ok(key) => key,
+            Err(err) => return format!("ERROR: {}", err)
+        };
+        // 同一进程再次加锁会等待自己释放，直接报错
+        if lock_file_locks().contains_key(&key) {
+            return format!("ERROR: 已经持有文件锁: {}", args[0]);
+        }
+        
+        let locked = match (wait, shared) {
+            (true, false) => file.lock_exclusive().map(|_| true),
+            (true, true) => FileExt::lock_shared(&file).map(|_| true),
+            (false, false) => try_result(file.try_lock_exclusive()),
+            (false, true) => try_result(FileExt::try_lock_shared(&file)),
+        };
+        match locked {
+            Ok(true) => {
+                lock_file_locks().insert(key, file);
+                "true".to_string()
+            },
+            Ok(false) => "false".to_string(),
+            Err(err) => format!("ERROR: {}", err)
+        }
+    }
+    
+    fn try_result(result: ::std::io::Result<()>) -> ::std::io::Result<bool> {
+        match result {
+            Ok(_) => Ok(true),
+            Err(err) if err.raw_os_error() == fs2::lock_contended_error().raw_os_error() => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+    
+    // 计算文件的 SHA-256 摘要，返回十六进制字符串；分块读取，不会把整个文件读入内存
+    // 参数: path
+    pub fn cn_sha256(args: Vec<String>) -> String {
+        file_digest::<sha2::Sha256>(&args)
+    }
+    
+    // 计算文件的 MD5 摘要，返回十六进制字符串；只适合校验，不适合安全用途
+    // 参数: path
+    pub fn cn_md5(args: Vec<String>) -> String {
+        file_digest::<md5::Md5>(&args)
+    }
+    
+    // 比较两个文件的内容是否相同；大小不同时不读取内容，否则分块比较，遇到第一个不同就停止
+    // 参数: a, b
+    pub fn cn_equal(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "ERROR: 需要两个参数: 两个文件路径".to_string();
+        }
+        
+        let compared = (|| -> ::std::io::Result<bool> {
+            let mut a = fs::File::open(&args[0])?;
+            let mut b = fs::File::open(&args[1])?;
+            if a.metadata()?.len() != b.metadata()?.len() {
+                return Ok(false);
+            }
+            let mut buffer_a = vec![0u8; DIGEST_BUFFER_SIZE];
+            let mut buffer_b = vec![0u8; DIGEST_BUFFER_SIZE];
+            loop {
+                let n = read_full(&mut a, &mut buffer_a)?;
+                let m = read_full(&mut b, &mut buffer_b)?;
+                if n != m || buffer_a[..n] != buffer_b[..m] {
+                    return Ok(false);
+                }
+                if n == 0 {
+                    return Ok(true);
+                }
+            }
+        })();
+        match compared {
+            Ok(equal) => equal.to_string(),
+            Err(err) => format!("ERROR: {}", err)
+        }
+    }
+    
+    const DIGEST_BUFFER_SIZE: usize = 64 * 1024;
+    
+    fn file_digest<D: Digest>(args: &[String]) -> String {
+        if args.is_empty() {
+            return "ERROR: 需要文件路径参数".to_string();
+        }
+        
+        let digest = (|| -> ::std::io::Result<String> {
+            let mut file = fs::File::open(&args[0])?;
+            let mut hasher = D::new();
+            let mut buffer = vec![0u8; DIGEST_BUFFER_SIZE];
+            loop {
+                let n = file.read(&mut buffer)?;
+                if n == 0 {
+                    break;
+                }
+                hasher.update(&buffer[..n]);
+            }
+            Ok(hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect())
+        })();
+        match digest {
+            Ok(digest) => digest,
+            Err(err) => format!("ERROR: {}", err)
+        }
+    }
+    
+    // 读满缓冲区或读到文件末尾，返回读取的字节数，使两个文件的分块一一对应
+    fn read_full(file: &mut fs::File, buffer: &mut [u8]) -> ::std::io::Result<usize> {
+        let mut filled = 0;
+        while filled < buffer.len() {
+            match file.read(&mut buffer[filled..])? {
+                0 => break,
+                n => filled += n,
+            }
+        }
+        Ok(filled)
+    }
+    
+    // 获取文件信息，返回 {"type"（"file"、"dir"、"symlink" 或 "other"）, "size", "modified", "accessed", "created",
+    // "readonly", "mode", "uid", "gid", "owner", "group"}
+    // 时间为秒级时间戳，系统不支持时为空值；mode 是八进制权限字符串（如 "644"），uid 等属主信息只在类Unix系统上提供
+    // 符号链接的 type 为 "symlink"，其他信息来自它指向的文件
+    // 参数: path
+    pub fn cn_metadata(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "ERROR: 需要文件路径参数".to_string();
+        }
+        
+        let path = Path::new(&args[0]);
+        let link_metadata = match fs::symlink_metadata(path) {
+            Ok(metadata) => metadata,
+            Err(err) => return format!("ERROR: {}", err)
+        };
+        let file_type = link_metadata.file_type();
+        let kind = if file_type.is_symlink() {
+            "symlink"
+        } else if file_type.is_dir() {
+            "dir"
+        } else if file_type.is_file() {
+            "file"
+        } else {
+            "other"
+        };
+        // 指向不存在的文件的符号链接使用链接本身的信息
+        let metadata = fs::metadata(path).unwrap_or(link_metadata);
+        let timestamp = |time: ::std::io::Result<SystemTime>| match time {
+            Ok(time) => JsonValue::from(unix_seconds(time)),
+            Err(_) => JsonValue::Null,
+        };
+        
+        let mut result = Map::new();
+        result.insert("type".to_string(), JsonValue::from(kind));
+        result.insert("size".to_string(), JsonValue::from(metadata.len()));
+        result.insert("modified".to_string(), timestamp(metadata.modified()));
+        result.insert("accessed".to_string(), timestamp(metadata.accessed()));
+        result.insert("created".to_string(), timestamp(metadata.created()));
+        result.insert("readonly".to_string(), JsonValue::Bool(metadata.permissions().readonly()));
+        owner_info(&metadata, &mut result);
+        json_value(&JsonValue::Object(result).to_string())
+    }
+    
+    // 设置文件权限，mode 是八进制字符串（如 "755"、"0644"）
+    // 不支持Unix权限的系统上只根据属主写权限设置只读标志
+    // 参数: path, mode
+    pub fn cn_set_permissions(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "ERROR: 需要两个参数: 文件路径和权限".to_string();
+        }
+        
+        let mode = match u32::from_str_radix(args[1].trim().trim_start_matches("0o"), 8) {
+            Ok(mode) if mode <= 0o7777 => mode,
+            _ => return format!("ERROR: 无效的权限，需要八进制字符串: {}", args[1])
+        };
+        match set_mode(Path::new(&args[0]), mode) {
+            Ok(_) => "true".to_string(),
+            Err(err) => format!("ERROR: {}", err)
+        }
+    }
+    
+    // 更新文件的修改和访问时间，文件不存在时创建空文件
+    // 参数: path, [timestamp]（秒级时间戳，默认为现在）
+    pub fn cn_touch(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "ERROR: 需要文件路径参数".to_string();
+        }
+        
+        let time = match args.get(1).map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            Some(timestamp) => match timestamp.parse::<f64>() {
+                Ok(seconds) if seconds.is_finite() && seconds >= 0.0 => UNIX_EPOCH + Duration::from_secs_f64(seconds),
+                _ => return format!("ERROR: 无效的时间戳: {}", timestamp)
+            },
+            None => SystemTime::now(),
+        };
+        let file = match fs::OpenOptions::new().create(true).append(true).open(&args[0]) {
+            Ok(file) => file,
+            Err(err) => return format!("ERROR: {}", err)
+        };
+        let time = FileTime::from_system_time(time);
+        match filetime::set_file_handle_times(&file, Some(time), Some(time)) {
+            Ok(_) => "true".to_string(),
+            Err(err) => format!("ERROR: {}", err)
+        }
+    }
+    
+    // 早于1970年的时间为负数
+    fn unix_seconds(time: SystemTime) -> i64 {
+        match time.duration_since(UNIX_EPOCH) {
+            Ok(duration) => duration.as_secs() as i64,
+            Err(err) => -(err.duration().as_secs() as i64),
+        }
+    }
+    
+    #[cfg(unix)]
+    fn owner_info(metadata: &fs::Metadata, result: &mut Map<String, JsonValue>) {
+        use ::std::os::unix::fs::MetadataExt;
+        result.insert("mode".to_string(), JsonValue::String(format!("{:o}", metadata.mode() & 0o7777)));
+        result.insert("uid".to_string(), JsonValue::from(metadata.uid()));
+        result.insert("gid".to_string(), JsonValue::from(metadata.gid()));
+        let owner = cn_common::users::user_by_uid(metadata.uid()).ok().flatten().map(|user| user.name);
+        result.insert("owner".to_string(), owner.map(JsonValue::String).unwrap_or(JsonValue::Null));
+        let group = cn_common::users::group_name(metadata.gid()).ok().flatten();
+        result.insert("group".to_string(), group.map(JsonValue::String).unwrap_or(JsonValue::Null));
+    }
+    
+    #[cfg(not(unix))]
+    fn owner_info(_metadata: &fs::Metadata, result: &mut Map<String, JsonValue>) {
+        for key in ["mode", "uid", "gid", "owner", "group"] {
+            result.insert(key.to_string(), JsonValue::Null);
+        }
+    }
+    
+    #[cfg(unix)]
+    fn set_mode(path: &Path, mode: u32) -> ::std::io::Result<()> {
+        use ::std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(mode))
+    }
+    
+    #[cfg(not(unix))]
+    fn set_mode(path: &Path, mode: u32) -> ::std::io::Result<()> {
+        let mut permissions = fs::metadata(path)?.permissions();
+        permissions.set_readonly(mode & 0o200 == 0);
+        fs::set_permissions(path, permissions)
+    }
 }
 
 // 目录操作命名空间
 mod dir {
     use super::*;
+    use ::std::collections::VecDeque;
+    use ::std::path::PathBuf;
+    use ::std::sync::{Mutex, MutexGuard};
+    use cn_common::value::{callback_value, thrown_message};
+    
+    // 进度处理函数返回后，解释器调用的续接函数
+    const TRANSFER_RESUME_FUNCTION: &str = "dir::transfer_resume";
+    // 目标已存在时的处理方式
+    #[derive(Clone, Copy, PartialEq)]
+    enum Policy {
+        // 返回错误
+        Error,
+        // 删除整个目标目录后复制
+        Overwrite,
+        // 复制到目标目录中，替换同名文件
+        Merge,
+        // 复制到目标目录中，保留已有的同名文件
+        Skip,
+    }
+    
+    enum Step {
+        Dir(PathBuf),
+        File(PathBuf, PathBuf),
+        Link(PathBuf, PathBuf),
+    }
+    
+    struct Transfer {
+        src: PathBuf,
+        moving: bool,
+        policy: Policy,
+        steps: VecDeque<Step>,
+        handler: Option<String>,
+        total_files: u64,
+        files: u64,
+        dirs: u64,
+        skipped: u64,
+        bytes: u64,
+        current: String,
+    }
+    
+    // 正在报告进度的复制和移动，进度处理函数中可以再开始复制
+    static TRANSFERS: Mutex<Vec<Transfer>> = Mutex::new(Vec::new());
+    
+    fn lock_transfers() -> MutexGuard<'static, Vec<Transfer>> {
+        TRANSFERS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
     
     // 创建目录
     pub fn cn_create(args: Vec<String>) -> String {
@@ -212,7 +798,7 @@ mod dir {
         }
     }
     
-    // 列出目录内容
+    // 列出目录内容，按路径排序
     pub fn cn_list(args: Vec<String>) -> String {
         if args.is_empty() {
             return "ERROR: 需要目录路径参数".to_string();
@@ -222,17 +808,402 @@ mod dir {
         match fs::read_dir(path) {
             Ok(entries) => {
                 let mut result = Vec::new();
-                for entry in entries {
-                    if let Ok(entry) = entry {
-                        result.push(entry.path().to_string_lossy().to_string());
-                    }
+                for entry in entries.flatten() {
+                    result.push(entry.path().to_string_lossy().to_string());
                 }
+                result.sort();
                 result.join("\n")
             },
             Err(err) => format!("ERROR: {}", err)
         }
     }
     
+    // 递归遍历目录，返回按路径排序的数组，每个元素为
+    // {"path", "relative"（相对于 path，以 / 分隔）, "depth", "is_file", "is_dir", "is_symlink"}
+    // pattern 是匹配相对路径的glob模式，如 "**/*.cn"、"src/*.{rs,toml}"，省略时返回全部
+    // options 是可选的 Map: max_depth: 最大深度（1表示只列出直接包含的项）, follow_symlinks: 是否进入符号链接指向的目录（默认false）,
+    //   type: "file"、"dir" 或 "all"（默认）
+    // 参数: path, [pattern], [options]
+    pub fn cn_walk(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "ERROR: 需要目录路径参数".to_string();
+        }
+        let root = Path::new(&args[0]);
+        if !root.is_dir() {
+            return format!("ERROR: 不是目录: {}", args[0]);
+        }
+        let matcher = match args.get(1).map(|p| p.trim()).filter(|p| !p.is_empty()) {
+            Some(pattern) => match glob_matcher(pattern) {
+                Ok(matcher) => Some(matcher),
+                Err(err) => return err,
+            },
+            None => None,
+        };
+        let options = match args.get(2).map(|o| o.trim()).filter(|o| !o.is_empty()) {
+            Some(options) => match serde_json::from_str::<Map<String, JsonValue>>(options) {
+                Ok(options) => options,
+                Err(_) => return "ERROR: 选项必须是 Map".to_string(),
+            },
+            None => Map::new(),
+        };
+        let (want_files, want_dirs) = match options.get("type").and_then(|t| t.as_str()).unwrap_or("all") {
+            "all" => (true, true),
+            "file" => (true, false),
+            "dir" => (false, true),
+            other => return format!("ERROR: type 必须是 \"file\"、\"dir\" 或 \"all\": {}", other),
+        };
+
+        let mut walker = WalkDir::new(root)
+            .min_depth(1)
+            .follow_links(options.get("follow_symlinks").and_then(|f| f.as_bool()).unwrap_or(false))
+            .sort_by_file_name();
+        if let Some(depth) = options.get("max_depth") {
+            match depth.as_u64() {
+                Some(depth) => walker = walker.max_depth(depth as usize),
+                None => return format!("ERROR: max_depth 必须是非负整数: {}", depth),
+            }
+        }
+
+        let mut result = Vec::new();
+        // 无法读取的目录和符号链接循环被跳过
+        for entry in walker.into_iter().flatten() {
+            let file_type = entry.file_type();
+            // 没有跟随的符号链接按它指向的类型筛选
+            let is_dir = file_type.is_dir() || (file_type.is_symlink() && entry.path().is_dir());
+            if (is_dir && !want_dirs) || (!is_dir && !want_files) {
+                continue;
+            }
+            let relative = relative_path(root, entry.path());
+            if matcher.as_ref().is_some_and(|m| !m.is_match(&relative)) {
+                continue;
+            }
+            let mut item = Map::new();
+            item.insert("path".to_string(), JsonValue::String(entry.path().to_string_lossy().to_string()));
+            item.insert("relative".to_string(), JsonValue::String(relative));
+            item.insert("depth".to_string(), JsonValue::from(entry.depth()));
+            item.insert("is_file".to_string(), JsonValue::Bool(!is_dir && entry.path().is_file()));
+            item.insert("is_dir".to_string(), JsonValue::Bool(is_dir));
+            item.insert("is_symlink".to_string(), JsonValue::Bool(entry.path_is_symlink()));
+            result.push(JsonValue::Object(item));
+        }
+        json_value(&JsonValue::Array(result).to_string())
+    }
+    
+    // 递归复制目录，返回 {"files", "dirs", "skipped", "bytes"}
+    // options 是可选的 Map:
+    //   policy: 目标已存在时的处理方式，"error"（默认，返回错误）、"overwrite"（删除整个目标后复制）、
+    //           "merge"（复制到目标中并替换同名文件）或 "skip"（复制到目标中并保留已有的同名文件）,
+    //   progress: 进度处理函数 progress(已完成文件数, 总文件数, 当前文件)，返回 false 时取消
+    // 符号链接在类Unix系统上复制为符号链接
+    // 参数: src, dst, [options]
+    pub fn cn_copy(args: Vec<String>) -> String {
+        start_transfer(&args, false)
+    }
+    
+    // 移动目录，参数和返回值与 copy 相同；能直接改名时直接改名，否则逐个移动文件，
+    // 最后删除空的源目录；policy 为 "skip" 时被跳过的文件留在源目录中
+    // 参数: src, dst, [options]
+    pub fn cn_move(args: Vec<String>) -> String {
+        start_transfer(&args, true)
+    }
+    
+    // 进度处理函数返回后由解释器调用，继续复制或移动；以续接函数注册，脚本无法直接调用
+    // 参数: returned
+    pub fn cn_transfer_resume(args: Vec<String>) -> String {
+        let mut transfers = lock_transfers();
+        let returned = args.first().map(|r| r.as_str()).unwrap_or("");
+        if let Some(message) = thrown_message(returned) {
+            transfers.pop();
+            return format!("ERROR: 进度处理函数抛出异常: {}", message);
+        }
+        // 最后一个文件完成后再取消没有意义，照常返回结果
+        let finished = transfers.last().map(|t| t.steps.is_empty()).unwrap_or(true);
+        if returned == "false" && !finished {
+            transfers.pop();
+            return "ERROR: 已取消".to_string();
+        }
+        next_progress(transfers)
+    }
+    
+    fn start_transfer(args: &[String], moving: bool) -> String {
+        if args.len() < 2 {
+            return "ERROR: 需要两个参数: 源目录和目标目录".to_string();
+        }
+        let src = PathBuf::from(&args[0]);
+        let dst = PathBuf::from(&args[1]);
+        if !src.is_dir() {
+            return format!("ERROR: 不是目录: {}", args[0]);
+        }
+        let options = match args.get(2).map(|o| o.trim()).filter(|o| !o.is_empty()) {
+            Some(options) => match serde_json::from_str::<Map<String, JsonValue>>(options) {
+                Ok(options) => options,
+                Err(_) => return "ERROR: 选项必须是 Map".to_string(),
+            },
+            None => Map::new(),
+        };
+        let policy = match options.get("policy").and_then(|p| p.as_str()).unwrap_or("error") {
+            "error" => Policy::Error,
+            "overwrite" => Policy::Overwrite,
+            "merge" => Policy::Merge,
+            "skip" => Policy::Skip,
+            other => return format!("ERROR: policy 必须是 \"error\"、\"overwrite\"、\"merge\" 或 \"skip\": {}", other),
+        };
+        let handler = options.get("progress").and_then(|p| p.as_str()).filter(|p| !p.is_empty()).map(|p| p.to_string());
+        
+        // 不能复制到自身内部
+        if let (Ok(src_real), Some(dst_real)) = (fs::canonicalize(&src), resolve_target(&dst)) {
+            if dst_real.starts_with(&src_real) {
+                return "ERROR: 目标目录不能位于源目录内".to_string();
+            }
+        }
+        if dst.exists() {
+            match policy {
+                Policy::Error => return format!("ERROR: 目标已存在: {}", args[1]),
+                Policy::Overwrite => {
+                    let removed = if dst.is_dir() { fs::remove_dir_all(&dst) } else { fs::remove_file(&dst) };
+                    if let Err(err) = removed {
+                        return format!("ERROR: 无法删除目标 {}: {}", args[1], err);
+                    }
+                },
+                Policy::Merge | Policy::Skip => {
+                    if !dst.is_dir() {
+                        return format!("ERROR: 目标不是目录: {}", args[1]);
+                    }
+                },
+            }
+        }
+        
+        let mut transfer = Transfer {
+            src: src.clone(),
+            moving,
+            policy,
+            steps: VecDeque::new(),
+            handler,
+            total_files: 0,
+            files: 0,
+            dirs: 0,
+            skipped: 0,
+            bytes: 0,
+            current: String::new(),
+        };
+        if let Err(err) = plan(&mut transfer, &src, &dst) {
+            return err;
+        }
+        // 目标不存在时移动整个目录只需改名；跨文件系统时改名失败，改为逐个移动
+        if moving && !dst.exists() && fs::rename(&src, &dst).is_ok() {
+            for step in transfer.steps.drain(..) {
+                match step {
+                    Step::Dir(_) => transfer.dirs += 1,
+                    Step::File(_, to) => {
+                        transfer.files += 1;
+                        transfer.bytes += fs::metadata(to).map(|m| m.len()).unwrap_or(0);
+                    },
+                    Step::Link(..) => transfer.files += 1,
+                }
+            }
+            return finish(transfer);
+        }
+        
+        let mut transfers = lock_transfers();
+        transfers.push(transfer);
+        next_progress(transfers)
+    }
+    
+    // 目标路径的规范形式，目标还不存在时由存在的上级目录推算
+    fn resolve_target(dst: &Path) -> Option<PathBuf> {
+        let mut missing = Vec::new();
+        let mut current = dst;
+        loop {
+            if let Ok(real) = fs::canonicalize(current) {
+                return Some(missing.iter().rev().fold(real, |path, part| path.join(part)));
+            }
+            missing.push(current.file_name()?.to_owned());
+            current = current.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        }
+    }
+    
+    // 列出要创建的目录和要复制的文件，目录排在其中的文件之前
+    fn plan(transfer: &mut Transfer, src: &Path, dst: &Path) -> Result<(), String> {
+        for entry in WalkDir::new(src).sort_by_file_name() {
+            let entry = entry.map_err(|err| format!("ERROR: {}", err))?;
+            let target = dst.join(entry.path().strip_prefix(src).unwrap_or(entry.path()));
+            let file_type = entry.file_type();
+            if file_type.is_dir() {
+                transfer.steps.push_back(Step::Dir(target));
+            } else if file_type.is_symlink() {
+                transfer.total_files += 1;
+                transfer.steps.push_back(Step::Link(entry.into_path(), target));
+            } else {
+                transfer.total_files += 1;
+                transfer.steps.push_back(Step::File(entry.into_path(), target));
+            }
+        }
+        Ok(())
+    }
+    
+    // 执行步骤直到完成一个文件需要报告进度，或全部完成
+    fn next_progress(mut transfers: MutexGuard<'static, Vec<Transfer>>) -> String {
+        loop {
+            let transfer = match transfers.last_mut() {
+                Some(transfer) => transfer,
+                None => return "ERROR: 没有正在进行的复制".to_string(),
+            };
+            let step = match transfer.steps.pop_front() {
+                Some(step) => step,
+                None => {
+                    let transfer = transfers.pop().expect("复制存在");
+                    return finish(transfer);
+                },
+            };
+            let is_dir = matches!(step, Step::Dir(_));
+            if let Err(err) = run_step(transfer, step) {
+                transfers.pop();
+                return err;
+            }
+            if let Some(handler) = &transfer.handler {
+                if !is_dir {
+                    let done = transfer.files + transfer.skipped;
+                    let args_json = JsonValue::Array(vec![
+                        JsonValue::from(done),
+                        JsonValue::from(transfer.total_files),
+                        JsonValue::String(transfer.current.clone()),
+                    ]).to_string();
+                    return callback_value(handler, &args_json, TRANSFER_RESUME_FUNCTION);
+                }
+            }
+        }
+    }
+    
+    fn run_step(transfer: &mut Transfer, step: Step) -> Result<(), String> {
+        let error = |path: &Path, err: ::std::io::Error| format!("ERROR: {}: {}", path.display(), err);
+        match step {
+            Step::Dir(target) => {
+                if !target.is_dir() {
+                    fs::create_dir_all(&target).map_err(|err| error(&target, err))?;
+                    transfer.dirs += 1;
+                }
+            },
+            Step::File(from, to) | Step::Link(from, to) if to.exists() || to.is_symlink() => {
+                transfer.current = from.to_string_lossy().to_string();
+                if transfer.policy == Policy::Skip {
+                    transfer.skipped += 1;
+                    return Ok(());
+                }
+                fs::remove_file(&to).map_err(|err| error(&to, err))?;
+                transfer_file(transfer, &from, &to)?;
+            },
+            Step::File(from, to) | Step::Link(from, to) => {
+                transfer.current = from.to_string_lossy().to_string();
+                transfer_file(transfer, &from, &to)?;
+            },
+        }
+        Ok(())
+    }
+    
+    fn transfer_file(transfer: &mut Transfer, from: &Path, to: &Path) -> Result<(), String> {
+        let error = |path: &Path, err: ::std::io::Error| format!("ERROR: {}: {}", path.display(), err);
+        let is_link = fs::symlink_metadata(from).map(|m| m.file_type().is_symlink()).unwrap_or(false);
+        if transfer.moving && fs::rename(from, to).is_ok() {
+            transfer.bytes += if is_link { 0 } else { fs::metadata(to).map(|m| m.len()).unwrap_or(0) };
+        } else {
+            if is_link {
+                copy_link(from, to).map_err(|err| error(from, err))?;
+            } else {
+                transfer.bytes += fs::copy(from, to).map_err(|err| error(from, err))?;
+            }
+            if transfer.moving {
+                fs::remove_file(from).map_err(|err| error(from, err))?;
+            }
+        }
+        transfer.files += 1;
+        Ok(())
+    }
+    
+    #[cfg(unix)]
+    fn copy_link(from: &Path, to: &Path) -> ::std::io::Result<()> {
+        ::std::os::unix::fs::symlink(fs::read_link(from)?, to)
+    }
+    
+    // 不支持创建符号链接时复制链接指向的文件
+    #[cfg(not(unix))]
+    fn copy_link(from: &Path, to: &Path) -> ::std::io::Result<()> {
+        fs::copy(from, to).map(|_| ())
+    }
+    
+    fn finish(transfer: Transfer) -> String {
+        // 移动完成后删除空的源目录，由深到浅删除，仍有内容（被跳过的文件）的目录保留
+        if transfer.moving && transfer.src.exists() {
+            let dirs: Vec<PathBuf> = WalkDir::new(&transfer.src)
+                .contents_first(true)
+                .into_iter()
+                .flatten()
+                .filter(|entry| entry.file_type().is_dir())
+                .map(|entry| entry.into_path())
+                .collect();
+            for dir in dirs {
+                let _ = fs::remove_dir(dir);
+            }
+        }
+        let mut result = Map::new();
+        result.insert("files".to_string(), JsonValue::from(transfer.files));
+        result.insert("dirs".to_string(), JsonValue::from(transfer.dirs));
+        result.insert("skipped".to_string(), JsonValue::from(transfer.skipped));
+        result.insert("bytes".to_string(), JsonValue::from(transfer.bytes));
+        json_value(&JsonValue::Object(result).to_string())
+    }
+    
+    // 计算目录中所有文件的总字节数，包括子目录；不跟随符号链接，无法读取的项被忽略
+    // 参数: path
+    pub fn cn_size(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "ERROR: 需要目录路径参数".to_string();
+        }
+        
+        let path = Path::new(&args[0]);
+        if !path.is_dir() {
+            return format!("ERROR: 不是目录: {}", args[0]);
+        }
+        let total: u64 = WalkDir::new(path)
+            .into_iter()
+            .flatten()
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| entry.metadata().ok())
+            .map(|metadata| metadata.len())
+            .sum();
+        total.to_string()
+    }
+    
+    // 统计目录中的项数，包括子目录，不包括目录本身；返回 {"files", "dirs", "symlinks", "total"}
+    // 不跟随符号链接，无法读取的项被忽略
+    // 参数: path
+    pub fn cn_count(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "ERROR: 需要目录路径参数".to_string();
+        }
+        
+        let path = Path::new(&args[0]);
+        if !path.is_dir() {
+            return format!("ERROR: 不是目录: {}", args[0]);
+        }
+        let (mut files, mut dirs, mut symlinks) = (0u64, 0u64, 0u64);
+        for entry in WalkDir::new(path).min_depth(1).into_iter().flatten() {
+            let file_type = entry.file_type();
+            if file_type.is_symlink() {
+                symlinks += 1;
+            } else if file_type.is_dir() {
+                dirs += 1;
+            } else {
+                files += 1;
+            }
+        }
+        let mut result = Map::new();
+        result.insert("files".to_string(), JsonValue::from(files));
+        result.insert("dirs".to_string(), JsonValue::from(dirs));
+        result.insert("symlinks".to_string(), JsonValue::from(symlinks));
+        result.insert("total".to_string(), JsonValue::from(files + dirs + symlinks));
+        json_value(&JsonValue::Object(result).to_string())
+    }
+    
     // 获取当前工作目录
     pub fn cn_current(_args: Vec<String>) -> String {
         match ::std::env::current_dir() {
@@ -324,27 +1295,822 @@ mod path {
     }
 }
 
+// 文件监视命名空间（注册为 fs）
+// 通过定期扫描比较文件的修改时间和大小发现变化，事件为 {"type": "created"、"modified" 或 "deleted", "path", "is_dir"}
+// watch 阻塞并为每个事件调用处理函数；watch_changes 不阻塞，每次调用返回上次调用以来的事件
+mod watch {
+    use super::*;
+    use ::std::collections::{BTreeMap, VecDeque};
+    use ::std::path::PathBuf;
+    use ::std::sync::{Mutex, MutexGuard};
+    use ::std::thread;
+    use ::std::time::Instant;
+    use cn_common::handle::HandleTable;
+    use cn_common::value::{callback_value, thrown_message};
+    
+    // 处理函数返回后，解释器调用的续接函数
+    const RESUME_FUNCTION: &str = "fs::watch_resume";
+    const DEFAULT_INTERVAL_MS: u64 = 500;
+    
+    // 路径 -> (修改时间, 大小, 是否为目录)
+    type Snapshot = BTreeMap<PathBuf, (Option<SystemTime>, u64, bool)>;
+    
+    struct Scan {
+        root: PathBuf,
+        recursive: bool,
+        matcher: Option<GlobMatcher>,
+    }
+    
+    struct Watch {
+        scan: Scan,
+        snapshot: Snapshot,
+        handler: String,
+        interval: Duration,
+        deadline: Option<Instant>,
+        pending: VecDeque<JsonValue>,
+    }
+    
+    // watch_changes 的令牌对应的扫描结果
+    static SNAPSHOTS: Mutex<HandleTable<(Scan, Snapshot)>> = Mutex::new(HandleTable::new());
+    // 正在进行的 watch，处理函数中可以再开始 watch
+    static WATCHES: Mutex<Vec<Watch>> = Mutex::new(Vec::new());
+    
+    fn lock_snapshots() -> MutexGuard<'static, HandleTable<(Scan, Snapshot)>> {
+        SNAPSHOTS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    
+    fn lock_watches() -> MutexGuard<'static, Vec<Watch>> {
+        WATCHES.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    
+    // 监视文件或目录，每发生一个事件调用一次 handler(event)，直到处理函数返回 false 或超出时长；期间脚本的其他代码不会执行
+    // options 是可选的 Map: interval: 扫描间隔毫秒数（默认500）, duration: 最长监视毫秒数（默认不限）,
+    //   recursive: 是否监视子目录（默认true）, pattern: 只报告相对路径匹配这个glob模式的文件，如 "**/*.cn"
+    // 参数: path, handler, [options]
+    pub fn cn_watch(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "ERROR: 需要两个参数: 路径和处理函数".to_string();
+        }
+        let options = match parse_options(args.get(2)) {
+            Ok(options) => options,
+            Err(err) => return err,
+        };
+        let scan = match build_scan(&args[0], &options) {
+            Ok(scan) => scan,
+            Err(err) => return err,
+        };
+        let interval = match options.get("interval") {
+            Some(ms) => match ms.as_u64() {
+                Some(ms) if ms > 0 => Duration::from_millis(ms),
+                _ => return format!("ERROR: interval 必须是正整数毫秒数: {}", ms),
+            },
+            None => Duration::from_millis(DEFAULT_INTERVAL_MS),
+        };
+        let deadline = match options.get("duration") {
+            Some(ms) => match ms.as_u64() {
+                Some(0) => None,
+                Some(ms) => Some(Instant::now() + Duration::from_millis(ms)),
+                None => return format!("ERROR: duration 必须是非负整数毫秒数: {}", ms),
+            },
+            None => None,
+        };
+        
+        let watch = Watch {
+            snapshot: take_snapshot(&scan),
+            scan,
+            handler: args[1].clone(),
+            interval,
+            deadline,
+            pending: VecDeque::new(),
+        };
+        let mut watches = lock_watches();
+        watches.push(watch);
+        next_event(watches)
+    }
+    
+    // 处理函数返回后由解释器调用，等待下一个事件；以续接函数注册，脚本无法直接调用
+    // 参数: returned
+    pub fn cn_watch_resume(args: Vec<String>) -> String {
+        let mut watches = lock_watches();
+        let returned = args.first().map(|r| r.as_str()).unwrap_or("");
+        if let Some(message) = thrown_message(returned) {
+            watches.pop();
+            return format!("ERROR: 处理函数抛出异常: {}", message);
+        }
+        if returned == "false" {
+            watches.pop();
+            return "true".to_string();
+        }
+        next_event(watches)
+    }
+    
+    // 返回上次调用以来的变化 {"token", "events"}；不提供令牌时开始监视，events 为空
+    // 每次调用都应使用上一次返回的新令牌，旧令牌随之失效
+    // options 与 watch 的 recursive、pattern 相同，只在开始监视时使用
+    // 参数: path, [token], [options]
+    pub fn cn_watch_changes(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "ERROR: 需要路径参数".to_string();
+        }
+        let mut snapshots = lock_snapshots();
+        let token = args.get(1).map(|t| t.trim()).filter(|t| !t.is_empty());
+        let (scan, events, snapshot) = match token {
+            Some(token) => {
+                let (scan, old) = match snapshots.parse_handle(token).and_then(|id| snapshots.remove(id)) {
+                    Some(entry) => entry,
+                    None => return format!("ERROR: 无效的令牌: {}", token),
+                };
+                let snapshot = take_snapshot(&scan);
+                let events = diff(&old, &snapshot);
+                (scan, events, snapshot)
+            },
+            None => {
+                let options = match parse_options(args.get(2)) {
+                    Ok(options) => options,
+                    Err(err) => return err,
+                };
+                let scan = match build_scan(&args[0], &options) {
+                    Ok(scan) => scan,
+                    Err(err) => return err,
+                };
+                let snapshot = take_snapshot(&scan);
+                (scan, Vec::new(), snapshot)
+            },
+        };
+        
+        let mut result = Map::new();
+        result.insert("token".to_string(), JsonValue::from(snapshots.insert((scan, snapshot))));
+        result.insert("events".to_string(), JsonValue::Array(events));
+        json_value(&JsonValue::Object(result).to_string())
+    }
+    
+    // 放弃 watch_changes 的令牌
+    // 参数: token
+    pub fn cn_unwatch(args: Vec<String>) -> String {
+        let mut snapshots = lock_snapshots();
+        match args.first().and_then(|arg| snapshots.parse_handle(arg)) {
+            Some(id) => snapshots.remove(id).is_some().to_string(),
+            None => "false".to_string(),
+        }
+    }
+    
+    // 等待下一个事件，返回调用处理函数的回调请求；超出时长时返回 true
+    fn next_event(mut watches: MutexGuard<'static, Vec<Watch>>) -> String {
+        loop {
+            let watch = match watches.last_mut() {
+                Some(watch) => watch,
+                None => return "ERROR: 没有正在进行的监视".to_string(),
+            };
+            if let Some(event) = watch.pending.pop_front() {
+                let args_json = JsonValue::Array(vec![event]).to_string();
+                return callback_value(&watch.handler, &args_json, RESUME_FUNCTION);
+            }
+            if watch.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                watches.pop();
+                return "true".to_string();
+            }
+            let wait = match watch.deadline {
+                Some(deadline) => watch.interval.min(deadline.saturating_duration_since(Instant::now())),
+                None => watch.interval,
+            };
+            thread::sleep(wait);
+            let snapshot = take_snapshot(&watch.scan);
+            watch.pending.extend(diff(&watch.snapshot, &snapshot));
+            watch.snapshot = snapshot;
+        }
+    }
+    
+    fn parse_options(arg: Option<&String>) -> Result<Map<String, JsonValue>, String> {
+        match arg.map(|o| o.trim()).filter(|o| !o.is_empty()) {
+            Some(options) => serde_json::from_str::<Map<String, JsonValue>>(options).map_err(|_| "ERROR: 选项必须是 Map".to_string()),
+            None => Ok(Map::new()),
+        }
+    }
+    
+    fn build_scan(path: &str, options: &Map<String, JsonValue>) -> Result<Scan, String> {
+        let root = PathBuf::from(path);
+        if !root.exists() {
+            return Err(format!("ERROR: 路径不存在: {}", path));
+        }
+        let matcher = match options.get("pattern").and_then(|p| p.as_str()).filter(|p| !p.is_empty()) {
+            Some(pattern) => Some(glob_matcher(pattern)?),
+            None => None,
+        };
+        let recursive = options.get("recursive").and_then(|r| r.as_bool()).unwrap_or(true);
+        Ok(Scan { root, recursive, matcher })
+    }
+    
+    fn take_snapshot(scan: &Scan) -> Snapshot {
+        let mut snapshot = Snapshot::new();
+        // 监视单个文件时 root 本身也在扫描结果中；监视的目录被删除时扫描结果为空
+        let walker = WalkDir::new(&scan.root).max_depth(if scan.recursive { usize::MAX } else { 1 });
+        for entry in walker.into_iter().flatten() {
+            let is_root_dir = entry.depth() == 0 && entry.file_type().is_dir();
+            if is_root_dir {
+                continue;
+            }
+            if let Some(matcher) = &scan.matcher {
+                let relative = relative_path(&scan.root, entry.path());
+                if entry.depth() > 0 && !matcher.is_match(&relative) {
+                    continue;
+                }
+            }
+            if let Ok(metadata) = entry.metadata() {
+                snapshot.insert(entry.into_path(), (metadata.modified().ok(), metadata.len(), metadata.is_dir()));
+            }
+        }
+        snapshot
+    }
+    
+    fn diff(old: &Snapshot, new: &Snapshot) -> Vec<JsonValue> {
+        let event = |kind: &str, path: &Path, is_dir: bool| {
+            let mut event = Map::new();
+            event.insert("type".to_string(), JsonValue::from(kind));
+            event.insert("path".to_string(), JsonValue::String(path.to_string_lossy().to_string()));
+            event.insert("is_dir".to_string(), JsonValue::Bool(is_dir));
+            JsonValue::Object(event)
+        };
+        let mut events = Vec::new();
+        for (path, (modified, size, is_dir)) in new {
+            match old.get(path) {
+                None => events.push(event("created", path, *is_dir)),
+                // 目录的修改时间随其中的文件变化，已经由文件的事件反映
+                Some((old_modified, old_size, _)) if !is_dir && (old_modified != modified || old_size != size) => {
+                    events.push(event("modified", path, false));
+                },
+                Some(_) => {},
+            }
+        }
+        for (path, (_, _, is_dir)) in old {
+            if !new.contains_key(path) {
+                events.push(event("deleted", path, *is_dir));
+            }
+        }
+        events
+    }
+}
+
+// 临时文件命名空间（注册为 fs）
+// temp_file 和 temp_dir 在临时目录中创建名称唯一的文件或目录并返回路径，
+// 默认在解释器退出时删除，也可以用 cleanup 提前删除、用 keep 保留
+mod temp {
+    use super::*;
+    use ::std::collections::BTreeSet;
+    use ::std::collections::hash_map::DefaultHasher;
+    use ::std::hash::{Hash, Hasher};
+    use ::std::io::ErrorKind;
+    use ::std::path::PathBuf;
+    use ::std::sync::atomic::{AtomicU64, Ordering};
+    use ::std::sync::{Mutex, MutexGuard, Once};
+    
+    const DEFAULT_PREFIX: &str = "cn_";
+    // 名称冲突时的重试次数
+    const MAX_ATTEMPTS: u32 = 100;
+    
+    // 退出时要删除的临时文件和目录
+    static TRACKED: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+    static REGISTER_CLEANUP: Once = Once::new();
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    
+    fn lock_tracked() -> MutexGuard<'static, BTreeSet<PathBuf>> {
+        TRACKED.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    
+    // 创建临时文件，返回路径
+    // options 是可选的 Map: suffix: 文件名后缀（如 ".txt"）, dir: 创建在哪个目录（默认为系统临时目录）,
+    //   keep: 为 true 时退出时不删除
+    // 参数: [prefix]（默认为 "cn_"）, [options]
+    pub fn cn_temp_file(args: Vec<String>) -> String {
+        create(&args, |path| fs::OpenOptions::new().write(true).create_new(true).open(path).map(|_| ()))
+    }
+    
+    // 创建临时目录，返回路径；退出时连同其中的内容一起删除
+    // 参数: [prefix]（默认为 "cn_"）, [options]（与 temp_file 相同）
+    pub fn cn_temp_dir(args: Vec<String>) -> String {
+        create(&args, |path| fs::create_dir(path))
+    }
+    
+    // 立即删除 temp_file 或 temp_dir 创建的路径，省略参数时删除全部
+    // 参数: [path]
+    pub fn cn_cleanup(args: Vec<String>) -> String {
+        let paths: Vec<PathBuf> = {
+            let mut tracked = lock_tracked();
+            match args.first().filter(|p| !p.is_empty()) {
+                Some(path) => match tracked.take(Path::new(path)) {
+                    Some(path) => vec![path],
+                    None => return "false".to_string(),
+                },
+                None => ::std::mem::take(&mut *tracked).into_iter().collect(),
+            }
+        };
+        for path in &paths {
+            remove(path);
+        }
+        "true".to_string()
+    }
+    
+    // 保留临时文件或目录，退出时不再删除
+    // 参数: path
+    pub fn cn_keep(args: Vec<String>) -> String {
+        match args.first() {
+            Some(path) => lock_tracked().remove(Path::new(path)).to_string(),
+            None => "false".to_string(),
+        }
+    }
+    
+    fn create(args: &[String], make: impl Fn(&Path) -> ::std::io::Result<()>) -> String {
+        let prefix = args.first().map(|p| p.as_str()).filter(|p| !p.is_empty()).unwrap_or(DEFAULT_PREFIX);
+        if prefix.contains(['/', '\\']) {
+            return format!("ERROR: 前缀不能包含路径分隔符: {}", prefix);
+        }
+        let options = match args.get(1).map(|o| o.trim()).filter(|o| !o.is_empty()) {
+            Some(options) => match serde_json::from_str::<Map<String, JsonValue>>(options) {
+                Ok(options) => options,
+                Err(_) => return "ERROR: 选项必须是 Map".to_string(),
+            },
+            None => Map::new(),
+        };
+        let suffix = options.get("suffix").and_then(|s| s.as_str()).unwrap_or("");
+        let dir = options.get("dir").and_then(|d| d.as_str()).map(PathBuf::from).unwrap_or_else(::std::env::temp_dir);
+        let keep = options.get("keep").and_then(|k| k.as_bool()).unwrap_or(false);
+        
+        for _ in 0..MAX_ATTEMPTS {
+            let path = dir.join(format!("{}{}{}", prefix, unique_name(), suffix));
+            match make(&path) {
+                Ok(()) => {
+                    if !keep {
+                        track(path.clone());
+                    }
+                    return path.to_string_lossy().to_string();
+                },
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => continue,
+                Err(err) => return format!("ERROR: {}", err),
+            }
+        }
+        "ERROR: 无法生成唯一的临时文件名".to_string()
+    }
+    
+    // 由时间、进程号和计数器生成的12位16进制串
+    fn unique_name() -> String {
+        let mut hasher = DefaultHasher::new();
+        SystemTime::now().hash(&mut hasher);
+        ::std::process::id().hash(&mut hasher);
+        COUNTER.fetch_add(1, Ordering::Relaxed).hash(&mut hasher);
+        format!("{:012x}", hasher.finish() & 0xffff_ffff_ffff)
+    }
+    
+    fn track(path: PathBuf) {
+        REGISTER_CLEANUP.call_once(|| {
+            // SAFETY: atexit 只保存函数指针，cleanup_at_exit 在进程退出时调用，不会再被卸载
+            unsafe {
+                libc::atexit(cleanup_at_exit);
+            }
+        });
+        lock_tracked().insert(path);
+    }
+    
+    fn remove(path: &Path) {
+        if path.is_dir() {
+            let _ = fs::remove_dir_all(path);
+        } else {
+            let _ = fs::remove_file(path);
+        }
+    }
+    
+    extern "C" fn cleanup_at_exit() {
+        // 退出时不能让 panic 越过 C 调用边界
+        let _ = ::std::panic::catch_unwind(|| {
+            let paths = ::std::mem::take(&mut *lock_tracked());
+            for path in &paths {
+                remove(path);
+            }
+        });
+    }
+}
+
+// 链接命名空间（注册为 fs）
+// 相对的链接目标按链接所在的目录解析，与系统的规则相同
+mod link {
+    use super::*;
+    
+    // 创建指向 target 的符号链接 link；Windows上创建目录的符号链接需要权限，没有权限时改为创建目录联接
+    // 参数: target, link
+    pub fn cn_symlink(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "ERROR: 需要两个参数: 链接目标和链接路径".to_string();
+        }
+        
+        match create_symlink(Path::new(&args[0]), Path::new(&args[1])) {
+            Ok(_) => "true".to_string(),
+            Err(err) => format!("ERROR: {}", err)
+        }
+    }
+    
+    // 读取符号链接或目录联接指向的目标，原样返回，不解析为绝对路径
+    // 参数: link
+    pub fn cn_read_link(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "ERROR: 需要链接路径参数".to_string();
+        }
+        
+        match fs::read_link(&args[0]) {
+            Ok(target) => target.to_string_lossy().to_string(),
+            Err(err) => format!("ERROR: {}", err)
+        }
+    }
+    
+    // 创建指向文件 target 的硬链接 link，两者必须在同一文件系统上
+    // 参数: target, link
+    pub fn cn_hardlink(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "ERROR: 需要两个参数: 链接目标和链接路径".to_string();
+        }
+        
+        match fs::hard_link(&args[0], &args[1]) {
+            Ok(_) => "true".to_string(),
+            Err(err) => format!("ERROR: {}", err)
+        }
+    }
+    
+    #[cfg(unix)]
+    fn create_symlink(target: &Path, link: &Path) -> ::std::io::Result<()> {
+        ::std::os::unix::fs::symlink(target, link)
+    }
+    
+    #[cfg(windows)]
+    fn create_symlink(target: &Path, link: &Path) -> ::std::io::Result<()> {
+        use ::std::os::windows::fs::{symlink_dir, symlink_file};
+        // 链接目标相对于链接所在的目录
+        let resolved = match link.parent() {
+            Some(parent) if target.is_relative() => parent.join(target),
+            _ => target.to_path_buf(),
+        };
+        if !resolved.is_dir() {
+            return symlink_file(target, link);
+        }
+        match symlink_dir(target, link) {
+            Ok(_) => Ok(()),
+            // 目录联接不需要权限，但目标必须是绝对路径
+            Err(err) if err.kind() == ::std::io::ErrorKind::PermissionDenied || err.raw_os_error() == Some(1314) => {
+                let absolute = fs::canonicalize(&resolved)?;
+                // canonicalize 返回 \\?\ 开头的路径，mklink 不接受
+                let absolute = absolute.to_string_lossy().trim_start_matches(r"\\?\").to_string();
+                let output = ::std::process::Command::new("cmd")
+                    .args(["/C", "mklink", "/J"])
+                    .arg(link)
+                    .arg(&absolute)
+                    .output()?;
+                if output.status.success() {
+                    Ok(())
+                } else {
+                    let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                    Err(::std::io::Error::other(format!("无法创建目录联接: {}", message)))
+                }
+            },
+            Err(err) => Err(err),
+        }
+    }
+    
+    #[cfg(not(any(unix, windows)))]
+    fn create_symlink(_target: &Path, _link: &Path) -> ::std::io::Result<()> {
+        Err(::std::io::Error::new(::std::io::ErrorKind::Unsupported, "当前系统不支持符号链接"))
+    }
+}
+
+// 内存映射命名空间（注册为 file）
+// mmap 把文件映射到内存并返回句柄，按偏移读写不需要读入整个文件；映射的长度固定为打开时的文件大小，不能写到末尾之外
+// 字节数据默认作为文本（UTF-8），format 为 "hex" 时为16进制字符串
+// 其他进程在映射期间截断文件时，读取可能使进程崩溃
+mod mmap {
+    use super::*;
+    use ::std::sync::{Mutex, MutexGuard};
+    use cn_common::handle::HandleTable;
+    use memmap2::{Mmap, MmapMut};
+    
+    enum Mapping {
+        ReadOnly(Mmap),
+        Writable(MmapMut),
+    }
+    
+    impl Mapping {
+        fn bytes(&self) -> &[u8] {
+            match self {
+                Mapping::ReadOnly(map) => map,
+                Mapping::Writable(map) => map,
+            }
+        }
+        
+        fn bytes_mut(&mut self) -> Result<&mut [u8], String> {
+            match self {
+                Mapping::ReadOnly(_) => Err("ERROR: 映射是只读的，打开时 writable 参数需要为 true".to_string()),
+                Mapping::Writable(map) => Ok(map),
+            }
+        }
+    }
+    
+    static MAPPINGS: Mutex<HandleTable<Mapping>> = Mutex::new(HandleTable::new());
+    
+    fn lock_mappings() -> MutexGuard<'static, HandleTable<Mapping>> {
+        MAPPINGS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    
+    // 把文件映射到内存，返回映射句柄
+    // 参数: path, [writable]（true 时可以通过 mmap_set 和 mmap_write 修改文件，默认为只读）
+    pub fn cn_mmap(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "ERROR: 需要文件路径参数".to_string();
+        }
+        
+        let writable = args.get(1).map(|w| w.trim() == "true").unwrap_or(false);
+        let file = match fs::OpenOptions::new().read(true).write(writable).open(&args[0]) {
+            Ok(file) => file,
+            Err(err) => return format!("ERROR: {}", err)
+        };
+        // SAFETY: 映射期间文件被其他进程截断或修改时行为未定义，这一限制写在了命名空间的说明中
+        let mapping = if writable {
+            unsafe { MmapMut::map_mut(&file) }.map(Mapping::Writable)
+        } else {
+            unsafe { Mmap::map(&file) }.map(Mapping::ReadOnly)
+        };
+        match mapping {
+            Ok(mapping) => lock_mappings().insert(mapping).to_string(),
+            Err(err) => format!("ERROR: {}", err)
+        }
+    }
+    
+    // 获取映射的字节数
+    // 参数: handle
+    pub fn cn_mmap_len(args: Vec<String>) -> String {
+        with_mapping(&args, |mapping| Ok(mapping.bytes().len().to_string()))
+    }
+    
+    // 读取 index 处的字节，返回0到255的整数
+    // 参数: handle, index
+    pub fn cn_mmap_get(args: Vec<String>) -> String {
+        with_mapping(&args, |mapping| {
+            let index = parse_offset(args.get(1), "索引")?;
+            match mapping.bytes().get(index) {
+                Some(byte) => Ok(byte.to_string()),
+                None => Err(format!("ERROR: 索引超出范围: {}", index)),
+            }
+        })
+    }
+    
+    // 修改 index 处的字节
+    // 参数: handle, index, value（0到255的整数）
+    pub fn cn_mmap_set(args: Vec<String>) -> String {
+        with_mapping(&args, |mapping| {
+            let index = parse_offset(args.get(1), "索引")?;
+            let value = match args.get(2).map(|v| v.trim().parse::<u8>()) {
+                Some(Ok(value)) => value,
+                _ => return Err("ERROR: 字节值必须是0到255的整数".to_string()),
+            };
+            match mapping.bytes_mut()?.get_mut(index) {
+                Some(byte) => {
+                    *byte = value;
+                    Ok("true".to_string())
+                },
+                None => Err(format!("ERROR: 索引超出范围: {}", index)),
+            }
+        })
+    }
+    
+    // 读取从 offset 开始的最多 len 个字节，超出末尾的部分被忽略
+    // 参数: handle, offset, len, [format]
+    pub fn cn_mmap_read(args: Vec<String>) -> String {
+        with_mapping(&args, |mapping| {
+            let offset = parse_offset(args.get(1), "偏移")?;
+            let len = parse_offset(args.get(2), "长度")?;
+            let hex = parse_format(args.get(3))?;
+            let bytes = mapping.bytes();
+            let start = offset.min(bytes.len());
+            let end = start.saturating_add(len).min(bytes.len());
+            let bytes = &bytes[start..end];
+            if hex {
+                Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+            } else {
+                Ok(String::from_utf8_lossy(bytes).into_owned())
+            }
+        })
+    }
+    
+    // 从 offset 开始写入数据，返回写入的字节数；数据超出映射末尾时不写入并返回错误
+    // 参数: handle, offset, data, [format]
+    pub fn cn_mmap_write(args: Vec<String>) -> String {
+        with_mapping(&args, |mapping| {
+            let offset = parse_offset(args.get(1), "偏移")?;
+            let data = decode_data(args.get(2), args.get(3))?;
+            let bytes = mapping.bytes_mut()?;
+            match offset.checked_add(data.len()).filter(|end| *end <= bytes.len()) {
+                Some(end) => {
+                    bytes[offset..end].copy_from_slice(&data);
+                    Ok(data.len().to_string())
+                },
+                None => Err(format!("ERROR: 写入超出映射末尾，映射长度为 {}", bytes.len())),
+            }
+        })
+    }
+    
+    // 从 start 开始查找数据第一次出现的偏移，找不到时返回 -1
+    // 参数: handle, needle, [start]（默认为0）, [format]
+    pub fn cn_mmap_find(args: Vec<String>) -> String {
+        with_mapping(&args, |mapping| {
+            let needle = decode_data(args.get(1), args.get(3))?;
+            let start = match args.get(2).filter(|s| !s.trim().is_empty()) {
+                Some(start) => parse_offset(Some(start), "起始偏移")?,
+                None => 0,
+            };
+            let bytes = mapping.bytes();
+            if start > bytes.len() {
+                return Ok("-1".to_string());
+            }
+            match memchr::memmem::find(&bytes[start..], &needle) {
+                Some(position) => Ok((start + position).to_string()),
+                None => Ok("-1".to_string()),
+            }
+        })
+    }
+    
+    // 把修改写回磁盘；只读映射直接返回 true
+    // 参数: handle
+    pub fn cn_mmap_flush(args: Vec<String>) -> String {
+        with_mapping(&args, |mapping| match mapping {
+            Mapping::ReadOnly(_) => Ok("true".to_string()),
+            Mapping::Writable(map) => map.flush().map(|_| "true".to_string()).map_err(|err| format!("ERROR: {}", err)),
+        })
+    }
+    
+    // 关闭映射，可写映射在关闭前写回磁盘
+    // 参数: handle
+    pub fn cn_mmap_close(args: Vec<String>) -> String {
+        let mut mappings = lock_mappings();
+        let mapping = match args.first().and_then(|arg| mappings.parse_handle(arg)) {
+            Some(id) => mappings.remove(id),
+            None => None,
+        };
+        match mapping {
+            Some(Mapping::Writable(map)) => match map.flush() {
+                Ok(_) => "true".to_string(),
+                Err(err) => format!("ERROR: {}", err)
+            },
+            Some(Mapping::ReadOnly(_)) => "true".to_string(),
+            None => "false".to_string(),
+        }
+    }
+    
+    fn with_mapping<F>(args: &[String], f: F) -> String
+    where
+        F: FnOnce(&mut Mapping) -> Result<String, String>,
+    {
+        let mut mappings = lock_mappings();
+        let mapping = match args.first().and_then(|arg| mappings.parse_handle(arg)).and_then(|id| mappings.get_mut(id)) {
+            Some(mapping) => mapping,
+            None => return "ERROR: 无效的映射句柄".to_string(),
+        };
+        match f(mapping) {
+            Ok(result) => result,
+            Err(err) => err,
+        }
+    }
+    
+    fn parse_offset(arg: Option<&String>, name: &str) -> Result<usize, String> {
+        match arg.map(|a| a.trim().parse::<usize>()) {
+            Some(Ok(value)) => Ok(value),
+            _ => Err(format!("ERROR: {}必须是非负整数", name)),
+        }
+    }
+    
+    fn parse_format(arg: Option<&String>) -> Result<bool, String> {
+        match arg.map(|f| f.trim()) {
+            Some("hex") => Ok(true),
+            Some("text") | Some("") | None => Ok(false),
+            Some(other) => Err(format!("ERROR: 格式必须是 \"text\" 或 \"hex\": {}", other)),
+        }
+    }
+    
+    fn decode_data(data: Option<&String>, format: Option<&String>) -> Result<Vec<u8>, String> {
+        let data = data.ok_or_else(|| "ERROR: 需要数据参数".to_string())?;
+        if !parse_format(format)? {
+            return Ok(data.as_bytes().to_vec());
+        }
+        let hex = data.trim();
+        if hex.len() % 2 != 0 {
+            return Err("ERROR: 16进制字符串的长度必须是偶数".to_string());
+        }
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| hex.get(i..i + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(|| format!("ERROR: 无效的16进制字符串: {}", hex))
+    }
+}
+
+// 磁盘空间命名空间（注册为 fs）
+mod disk {
+    use super::*;
+    
+    // 获取路径所在文件系统中当前用户可用的字节数
+    // 参数: path
+    pub fn cn_free_space(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "ERROR: 需要路径参数".to_string();
+        }
+        
+        match available_space(Path::new(&args[0])) {
+            Ok(bytes) => bytes.to_string(),
+            Err(err) => format!("ERROR: {}", err)
+        }
+    }
+    
+    #[cfg(unix)]
+    fn available_space(path: &Path) -> ::std::io::Result<u64> {
+        use ::std::os::unix::ffi::OsStrExt;
+        let path = ::std::ffi::CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| ::std::io::Error::new(::std::io::ErrorKind::InvalidInput, "路径中包含空字符"))?;
+        // SAFETY: statvfs 是纯数据结构，全零是合法值
+        let mut stat: libc::statvfs = unsafe { ::std::mem::zeroed() };
+        // SAFETY: path 是以 NUL 结尾的字符串，stat 指向本函数中有效的结构
+        if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+            return Err(::std::io::Error::last_os_error());
+        }
+        // f_bavail 不包括只有root可用的保留块
+        Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+    }
+    
+    #[cfg(windows)]
+    fn available_space(path: &Path) -> ::std::io::Result<u64> {
+        use ::std::os::windows::ffi::OsStrExt;
+        
+        #[link(name = "kernel32")]
+        extern "system" {
+            fn GetDiskFreeSpaceExW(directory: *const u16, available: *mut u64, total: *mut u64, free: *mut u64) -> i32;
+        }
+        
+        let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+        let (mut available, mut total, mut free) = (0u64, 0u64, 0u64);
+        // SAFETY: wide 以 0 结尾，输出指针都指向本函数中的变量
+        if unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, &mut total, &mut free) } == 0 {
+            return Err(::std::io::Error::last_os_error());
+        }
+        Ok(available)
+    }
+    
+    #[cfg(not(any(unix, windows)))]
+    fn available_space(_path: &Path) -> ::std::io::Result<u64> {
+        Err(::std::io::Error::new(::std::io::ErrorKind::Unsupported, "当前系统不支持查询磁盘空间"))
+    }
+}
+
 // 初始化函数，返回函数映射
 #[no_mangle]
 pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
     // 使用register_namespaces函数一次性注册多个命名空间
-    let functions = register_namespaces(vec![
+    let mut functions = register_namespaces(vec![
         // 根命名空间函数
         ("", vec![
             ("exists", cn_exists),
             ("is_file", cn_is_file),
             ("is_dir", cn_is_dir),
+            ("is_symlink", cn_is_symlink),
         ]),
         // 文件操作命名空间
         ("file", vec![
             ("read", file::cn_read),
             ("read_bytes", file::cn_read_bytes),
+            ("read_lines", file::cn_read_lines),
+            ("has_next_line", file::cn_has_next_line),
+            ("next_line", file::cn_next_line),
+            ("close_lines", file::cn_close_lines),
+            ("read_range", file::cn_read_range),
             ("write", file::cn_write),
             ("append", file::cn_append),
+            ("write_atomic", file::cn_write_atomic),
+            ("append_line", file::cn_append_line),
+            ("sha256", file::cn_sha256),
+            ("md5", file::cn_md5),
+            ("equal", file::cn_equal),
             ("delete", file::cn_delete),
             ("copy", file::cn_copy),
             ("rename", file::cn_rename),
             ("size", file::cn_size),
+            ("metadata", file::cn_metadata),
+            ("set_permissions", file::cn_set_permissions),
+            ("touch", file::cn_touch),
+            ("lock", file::cn_lock),
+            ("try_lock", file::cn_try_lock),
+            ("unlock", file::cn_unlock),
+        ]),
+        // 文件操作命名空间中的内存映射函数
+        ("file", vec![
+            ("mmap", mmap::cn_mmap),
+            ("mmap_len", mmap::cn_mmap_len),
+            ("mmap_get", mmap::cn_mmap_get),
+            ("mmap_set", mmap::cn_mmap_set),
+            ("mmap_read", mmap::cn_mmap_read),
+            ("mmap_write", mmap::cn_mmap_write),
+            ("mmap_find", mmap::cn_mmap_find),
+            ("mmap_flush", mmap::cn_mmap_flush),
+            ("mmap_close", mmap::cn_mmap_close),
         ]),
         // 目录操作命名空间
         ("dir", vec![
@@ -352,6 +2118,11 @@ pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
             ("delete", dir::cn_delete),
             ("delete_all", dir::cn_delete_all),
             ("list", dir::cn_list),
+            ("walk", dir::cn_walk),
+            ("copy", dir::cn_copy),
+            ("move", dir::cn_move),
+            ("size", dir::cn_size),
+            ("count", dir::cn_count),
             ("current", dir::cn_current),
         ]),
         // 路径操作命名空间
@@ -363,8 +2134,26 @@ pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
             ("stem", path::cn_stem),
             ("is_absolute", path::cn_is_absolute),
         ]),
+        // 文件监视、临时文件、链接和磁盘空间命名空间
+        ("fs", vec![
+            ("watch", watch::cn_watch),
+            ("watch_changes", watch::cn_watch_changes),
+            ("unwatch", watch::cn_unwatch),
+            ("temp_file", temp::cn_temp_file),
+            ("temp_dir", temp::cn_temp_dir),
+            ("cleanup", temp::cn_cleanup),
+            ("keep", temp::cn_keep),
+            ("symlink", link::cn_symlink),
+            ("read_link", link::cn_read_link),
+            ("hardlink", link::cn_hardlink),
+            ("free_space", disk::cn_free_space),
+        ]),
     ]);
     
+    // 续接函数只由解释器在回调协议中调用
+    NamespaceBuilder::new("dir").add_continuation("transfer_resume", dir::cn_transfer_resume).register_all(&mut functions);
+    NamespaceBuilder::new("fs").add_continuation("watch_resume", watch::cn_watch_resume).register_all(&mut functions);
+    
     // 将HashMap装箱并转换为原始指针
     create_library_pointer(functions)
 } 
\ No newline at end of file
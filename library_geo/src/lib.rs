@@ -0,0 +1,536 @@
+use ::std::collections::HashMap;
+use serde_json::{json, Value as JsonValue};
+use std::f64::consts::PI;
+
+// 导入通用库
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::json_value;
+
+// 地理命名空间
+// 点可以写成 {"lat": 纬度, "lon": 经度}（也接受 lng）或 [纬度, 经度]，返回的点统一为 {"lat", "lon"}，单位为度
+// 距离单位: m、km（默认）、mi、nmi、ft；距离按球面（平均半径 6371008.8 米）计算
+// 多边形和点集是点的数组，边界框是 {"min_lat", "min_lon", "max_lat", "max_lon"}
+mod geo {
+    use super::*;
+
+    const EARTH_RADIUS: f64 = 6_371_008.8;
+    // Web墨卡托使用的赤道半径
+    const MERCATOR_RADIUS: f64 = 6_378_137.0;
+    const GEOHASH_ALPHABET: &[u8] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+    #[derive(Clone, Copy)]
+    struct Point {
+        lat: f64,
+        lon: f64,
+    }
+
+    impl Point {
+        fn to_json(self) -> JsonValue {
+            json!({ "lat": self.lat, "lon": self.lon })
+        }
+    }
+
+    struct BoundingBox {
+        min_lat: f64,
+        min_lon: f64,
+        max_lat: f64,
+        max_lon: f64,
+    }
+
+    impl BoundingBox {
+        fn to_json(&self) -> JsonValue {
+            json!({ "min_lat": self.min_lat, "min_lon": self.min_lon, "max_lat": self.max_lat, "max_lon": self.max_lon })
+        }
+    }
+
+    // 计算两点之间的大圆距离（haversine公式）
+    // 参数: a, b, [unit]
+    pub fn cn_distance(args: Vec<String>) -> String {
+        let result = (|| {
+            let a = point_arg(&args, 0)?;
+            let b = point_arg(&args, 1)?;
+            let unit = unit_arg(&args, 2)?;
+            Ok(number(haversine(a, b) / unit))
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 计算从a到b的初始方位角，正北为0，顺时针0到360度
+    // 参数: a, b
+    pub fn cn_bearing(args: Vec<String>) -> String {
+        let result = (|| {
+            let a = point_arg(&args, 0)?;
+            let b = point_arg(&args, 1)?;
+            Ok(number(bearing(a, b)))
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 计算从起点沿方位角前进一段距离后到达的点
+    // 参数: point, bearing, distance, [unit]
+    pub fn cn_destination(args: Vec<String>) -> String {
+        let result = (|| {
+            let start = point_arg(&args, 0)?;
+            let bearing = number_arg(&args, 1, "方位角")?;
+            let distance = number_arg(&args, 2, "距离")? * unit_arg(&args, 3)?;
+            Ok(json_value(&destination(start, bearing, distance).to_json().to_string()))
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 计算两点之间大圆弧的中点
+    // 参数: a, b
+    pub fn cn_midpoint(args: Vec<String>) -> String {
+        let result = (|| {
+            let a = point_arg(&args, 0)?;
+            let b = point_arg(&args, 1)?;
+            let (lat1, lon1, lat2) = (a.lat.to_radians(), a.lon.to_radians(), b.lat.to_radians());
+            let dlon = (b.lon - a.lon).to_radians();
+            let bx = lat2.cos() * dlon.cos();
+            let by = lat2.cos() * dlon.sin();
+            let lat = (lat1.sin() + lat2.sin()).atan2(((lat1.cos() + bx).powi(2) + by * by).sqrt());
+            let lon = lon1 + by.atan2(lat1.cos() + bx);
+            let mid = Point { lat: lat.to_degrees(), lon: normalize_lon(lon.to_degrees()) };
+            Ok(json_value(&mid.to_json().to_string()))
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 计算点集的边界框
+    // 参数: points
+    pub fn cn_bbox(args: Vec<String>) -> String {
+        let result = (|| {
+            let points = points_arg(&args, 0)?;
+            let first = points.first().ok_or_else(|| "错误: 点集不能为空".to_string())?;
+            let mut bbox = BoundingBox { min_lat: first.lat, min_lon: first.lon, max_lat: first.lat, max_lon: first.lon };
+            for p in &points[1..] {
+                bbox.min_lat = bbox.min_lat.min(p.lat);
+                bbox.min_lon = bbox.min_lon.min(p.lon);
+                bbox.max_lat = bbox.max_lat.max(p.lat);
+                bbox.max_lon = bbox.max_lon.max(p.lon);
+            }
+            Ok(json_value(&bbox.to_json().to_string()))
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 计算以点为中心、包含给定半径内所有点的边界框，可用于先粗筛再精确计算距离
+    // 参数: point, radius, [unit]
+    pub fn cn_bbox_around(args: Vec<String>) -> String {
+        let result = (|| {
+            let center = point_arg(&args, 0)?;
+            let radius = number_arg(&args, 1, "半径")? * unit_arg(&args, 2)?;
+            let dlat = (radius / EARTH_RADIUS).to_degrees();
+            let (min_lat, max_lat) = (center.lat - dlat, center.lat + dlat);
+            // 半径覆盖极点时经度不再受限
+            let bbox = if min_lat <= -90.0 || max_lat >= 90.0 {
+                BoundingBox { min_lat: min_lat.max(-90.0), min_lon: -180.0, max_lat: max_lat.min(90.0), max_lon: 180.0 }
+            } else {
+                let dlon = (radius / (EARTH_RADIUS * center.lat.to_radians().cos())).to_degrees().min(180.0);
+                BoundingBox { min_lat, min_lon: center.lon - dlon, max_lat, max_lon: center.lon + dlon }
+            };
+            Ok(json_value(&bbox.to_json().to_string()))
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 检查点是否在边界框内（包括边上）
+    // 参数: point, bbox
+    pub fn cn_in_bbox(args: Vec<String>) -> String {
+        let result = (|| {
+            let p = point_arg(&args, 0)?;
+            let bbox = bbox_arg(&args, 1)?;
+            let inside = p.lat >= bbox.min_lat && p.lat <= bbox.max_lat && p.lon >= bbox.min_lon && p.lon <= bbox.max_lon;
+            Ok(inside.to_string())
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 检查点是否在多边形内（射线法，经纬度按平面坐标处理），多边形首尾不必重复
+    // 参数: point, polygon
+    pub fn cn_in_polygon(args: Vec<String>) -> String {
+        let result = (|| {
+            let p = point_arg(&args, 0)?;
+            let polygon = points_arg(&args, 1)?;
+            if polygon.len() < 3 {
+                return Err("错误: 多边形至少需要3个点".to_string());
+            }
+            let mut inside = false;
+            let mut j = polygon.len() - 1;
+            for i in 0..polygon.len() {
+                let (a, b) = (polygon[i], polygon[j]);
+                if (a.lat > p.lat) != (b.lat > p.lat)
+                    && p.lon < (b.lon - a.lon) * (p.lat - a.lat) / (b.lat - a.lat) + a.lon
+                {
+                    inside = !inside;
+                }
+                j = i;
+            }
+            Ok(inside.to_string())
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 在坐标系之间转换点: wgs84（GPS）、gcj02（国测局，高德、腾讯地图）、bd09（百度地图）
+    // 中国境外的点在 wgs84 和 gcj02 之间转换时保持不变
+    // 参数: point, from, to
+    pub fn cn_convert(args: Vec<String>) -> String {
+        let result = (|| {
+            let p = point_arg(&args, 0)?;
+            let from = args.get(1).map(|s| s.trim().to_lowercase()).unwrap_or_default();
+            let to = args.get(2).map(|s| s.trim().to_lowercase()).unwrap_or_default();
+            // 先转换为 gcj02，再转换为目标坐标系
+            let gcj = match from.as_str() {
+                "wgs84" => wgs84_to_gcj02(p),
+                "gcj02" => p,
+                "bd09" => bd09_to_gcj02(p),
+                _ => return Err(format!("错误: 未知的坐标系: {}", from)),
+            };
+            let converted = match (from.as_str(), to.as_str()) {
+                (from, to) if from == to => p,
+                (_, "wgs84") => gcj02_to_wgs84(gcj),
+                (_, "gcj02") => gcj,
+                (_, "bd09") => gcj02_to_bd09(gcj),
+                _ => return Err(format!("错误: 未知的坐标系: {}", to)),
+            };
+            Ok(json_value(&converted.to_json().to_string()))
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 把点转换为Web墨卡托（EPSG:3857）坐标，返回 {"x", "y"}，单位为米
+    // 参数: point
+    pub fn cn_to_mercator(args: Vec<String>) -> String {
+        let result = (|| {
+            let p = point_arg(&args, 0)?;
+            // 超出该纬度的点在投影中无穷远
+            let lat = p.lat.clamp(-85.051_128_78, 85.051_128_78);
+            let x = MERCATOR_RADIUS * p.lon.to_radians();
+            let y = MERCATOR_RADIUS * (PI / 4.0 + lat.to_radians() / 2.0).tan().ln();
+            Ok(json_value(&json!({ "x": x, "y": y }).to_string()))
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 把Web墨卡托坐标转换为点
+    // 参数: xy（{"x", "y"} 或 [x, y]）
+    pub fn cn_from_mercator(args: Vec<String>) -> String {
+        let result = (|| {
+            let (x, y) = match args.first().and_then(|a| serde_json::from_str::<JsonValue>(a).ok()) {
+                Some(JsonValue::Object(obj)) => (obj.get("x").and_then(|v| v.as_f64()), obj.get("y").and_then(|v| v.as_f64())),
+                Some(JsonValue::Array(items)) if items.len() == 2 => (items[0].as_f64(), items[1].as_f64()),
+                _ => (None, None),
+            };
+            let (x, y) = x.zip(y).ok_or_else(|| "错误: 坐标必须是 {\"x\", \"y\"} 或 [x, y]".to_string())?;
+            let p = Point {
+                lat: (2.0 * (y / MERCATOR_RADIUS).exp().atan() - PI / 2.0).to_degrees(),
+                lon: (x / MERCATOR_RADIUS).to_degrees(),
+            };
+            Ok(json_value(&p.to_json().to_string()))
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 把十进制度数转换为度分秒字符串，如 39°54'26.40"N
+    // 参数: degrees, [axis]（lat 或 lon，默认为 lat）
+    pub fn cn_to_dms(args: Vec<String>) -> String {
+        let result = (|| {
+            let value = number_arg(&args, 0, "度数")?;
+            let (positive, negative) = match args.get(1).map(|a| a.trim().to_lowercase()).as_deref() {
+                None | Some("") | Some("lat") => ('N', 'S'),
+                Some("lon") | Some("lng") => ('E', 'W'),
+                Some(axis) => return Err(format!("错误: 未知的坐标轴: {}", axis)),
+            };
+            // 按百分之一秒取整后再拆分，避免出现 60 秒
+            let total = (value.abs() * 360_000.0).round() as u64;
+            let (degrees, minutes, hundredths) = (total / 360_000, total / 6_000 % 60, total % 6_000);
+            let hemisphere = if value < 0.0 { negative } else { positive };
+            Ok(format!("{}°{}'{}.{:02}\"{}", degrees, minutes, hundredths / 100, hundredths % 100, hemisphere))
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 把度分秒字符串转换为十进制度数，接受 39°54'26.4"N、39 54 26.4 N、-116.39 等写法
+    // 参数: text
+    pub fn cn_from_dms(args: Vec<String>) -> String {
+        let text = args.first().map(|s| s.trim()).unwrap_or("");
+        match parse_dms(text) {
+            Some(value) => number(value),
+            None => format!("错误: 无效的度分秒: {}", text),
+        }
+    }
+
+    // 计算点的Geohash编码
+    // 参数: point, [precision]（字符数，1到12，默认为9）
+    pub fn cn_geohash_encode(args: Vec<String>) -> String {
+        let result = (|| {
+            let p = point_arg(&args, 0)?;
+            let precision = match args.get(1).map(|n| n.trim().parse::<usize>()) {
+                None => 9,
+                Some(Ok(n)) if (1..=12).contains(&n) => n,
+                _ => return Err(format!("错误: 精度必须是1到12之间的整数: {}", args[1])),
+            };
+            Ok(geohash_encode(p, precision))
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 解码Geohash，返回单元格的中心点和边界框 {"lat", "lon", "bbox"}
+    // 参数: hash
+    pub fn cn_geohash_decode(args: Vec<String>) -> String {
+        let hash = args.first().map(|s| s.trim().to_lowercase()).unwrap_or_default();
+        match geohash_decode(&hash) {
+            Some(bbox) => {
+                let center = Point { lat: (bbox.min_lat + bbox.max_lat) / 2.0, lon: (bbox.min_lon + bbox.max_lon) / 2.0 };
+                let mut result = center.to_json();
+                result["bbox"] = bbox.to_json();
+                json_value(&result.to_string())
+            },
+            None => format!("错误: 无效的Geohash: {}", hash),
+        }
+    }
+
+    fn haversine(a: Point, b: Point) -> f64 {
+        let dlat = (b.lat - a.lat).to_radians();
+        let dlon = (b.lon - a.lon).to_radians();
+        let h = (dlat / 2.0).sin().powi(2) + a.lat.to_radians().cos() * b.lat.to_radians().cos() * (dlon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS * h.sqrt().min(1.0).asin()
+    }
+
+    fn bearing(a: Point, b: Point) -> f64 {
+        let (lat1, lat2) = (a.lat.to_radians(), b.lat.to_radians());
+        let dlon = (b.lon - a.lon).to_radians();
+        let y = dlon.sin() * lat2.cos();
+        let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
+        (y.atan2(x).to_degrees() + 360.0) % 360.0
+    }
+
+    fn destination(start: Point, bearing: f64, distance: f64) -> Point {
+        let angular = distance / EARTH_RADIUS;
+        let (lat1, lon1, theta) = (start.lat.to_radians(), start.lon.to_radians(), bearing.to_radians());
+        let lat2 = (lat1.sin() * angular.cos() + lat1.cos() * angular.sin() * theta.cos()).asin();
+        let lon2 = lon1 + (theta.sin() * angular.sin() * lat1.cos()).atan2(angular.cos() - lat1.sin() * lat2.sin());
+        Point { lat: lat2.to_degrees(), lon: normalize_lon(lon2.to_degrees()) }
+    }
+
+    fn normalize_lon(lon: f64) -> f64 {
+        (lon + 540.0) % 360.0 - 180.0
+    }
+
+    // GCJ-02 只在中国境内加偏移
+    fn out_of_china(p: Point) -> bool {
+        !(72.004..=137.8347).contains(&p.lon) || !(0.8293..=55.8271).contains(&p.lat)
+    }
+
+    fn wgs84_to_gcj02(p: Point) -> Point {
+        if out_of_china(p) {
+            return p;
+        }
+        const A: f64 = 6_378_245.0;
+        const EE: f64 = 0.006_693_421_622_965_943;
+        let (x, y) = (p.lon - 105.0, p.lat - 35.0);
+        let mut dlat = -100.0 + 2.0 * x + 3.0 * y + 0.2 * y * y + 0.1 * x * y + 0.2 * x.abs().sqrt()
+            + (20.0 * (6.0 * x * PI).sin() + 20.0 * (2.0 * x * PI).sin()) * 2.0 / 3.0
+            + (20.0 * (y * PI).sin() + 40.0 * (y / 3.0 * PI).sin()) * 2.0 / 3.0
+            + (160.0 * (y / 12.0 * PI).sin() + 320.0 * (y * PI / 30.0).sin()) * 2.0 / 3.0;
+        let mut dlon = 300.0 + x + 2.0 * y + 0.1 * x * x + 0.1 * x * y + 0.1 * x.abs().sqrt()
+            + (20.0 * (6.0 * x * PI).sin() + 20.0 * (2.0 * x * PI).sin()) * 2.0 / 3.0
+            + (20.0 * (x * PI).sin() + 40.0 * (x / 3.0 * PI).sin()) * 2.0 / 3.0
+            + (150.0 * (x / 12.0 * PI).sin() + 300.0 * (x / 30.0 * PI).sin()) * 2.0 / 3.0;
+        let rad_lat = p.lat.to_radians();
+        let magic = 1.0 - EE * rad_lat.sin().powi(2);
+        let sqrt_magic = magic.sqrt();
+        dlat = dlat * 180.0 / ((A * (1.0 - EE)) / (magic * sqrt_magic) * PI);
+        dlon = dlon * 180.0 / (A / sqrt_magic * rad_lat.cos() * PI);
+        Point { lat: p.lat + dlat, lon: p.lon + dlon }
+    }
+
+    // GCJ-02 没有解析逆变换，迭代逼近
+    fn gcj02_to_wgs84(gcj: Point) -> Point {
+        if out_of_china(gcj) {
+            return gcj;
+        }
+        let mut wgs = gcj;
+        for _ in 0..30 {
+            let shifted = wgs84_to_gcj02(wgs);
+            let (dlat, dlon) = (shifted.lat - gcj.lat, shifted.lon - gcj.lon);
+            wgs = Point { lat: wgs.lat - dlat, lon: wgs.lon - dlon };
+            if dlat.abs() < 1e-10 && dlon.abs() < 1e-10 {
+                break;
+            }
+        }
+        wgs
+    }
+
+    const BD_X_PI: f64 = PI * 3000.0 / 180.0;
+
+    fn gcj02_to_bd09(p: Point) -> Point {
+        let (x, y) = (p.lon, p.lat);
+        let z = (x * x + y * y).sqrt() + 0.00002 * (y * BD_X_PI).sin();
+        let theta = y.atan2(x) + 0.000003 * (x * BD_X_PI).cos();
+        Point { lat: z * theta.sin() + 0.006, lon: z * theta.cos() + 0.0065 }
+    }
+
+    fn bd09_to_gcj02(p: Point) -> Point {
+        let (x, y) = (p.lon - 0.0065, p.lat - 0.006);
+        let z = (x * x + y * y).sqrt() - 0.00002 * (y * BD_X_PI).sin();
+        let theta = y.atan2(x) - 0.000003 * (x * BD_X_PI).cos();
+        Point { lat: z * theta.sin(), lon: z * theta.cos() }
+    }
+
+    fn parse_dms(text: &str) -> Option<f64> {
+        let upper = text.to_uppercase();
+        let hemisphere = upper.chars().find(|c| matches!(c, 'N' | 'S' | 'E' | 'W'));
+        let negative = matches!(hemisphere, Some('S') | Some('W')) || upper.trim_start().starts_with('-');
+        let parts: Vec<f64> = upper
+            .split(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .filter(|part| !part.is_empty())
+            .map(|part| part.parse::<f64>())
+            .collect::<Result<_, _>>()
+            .ok()?;
+        if parts.is_empty() || parts.len() > 3 || parts[1..].iter().any(|part| *part >= 60.0) {
+            return None;
+        }
+        let value = parts.iter().zip([1.0, 60.0, 3600.0]).map(|(part, divisor)| part / divisor).sum::<f64>();
+        Some(if negative { -value } else { value })
+    }
+
+    fn geohash_encode(p: Point, precision: usize) -> String {
+        let (mut lat_range, mut lon_range) = ((-90.0, 90.0), (-180.0, 180.0));
+        let mut hash = String::with_capacity(precision);
+        let (mut bits, mut bit_count, mut even) = (0usize, 0, true);
+        while hash.len() < precision {
+            // 偶数位编码经度，奇数位编码纬度
+            let (range, value): (&mut (f64, f64), f64) = if even { (&mut lon_range, p.lon) } else { (&mut lat_range, p.lat) };
+            let mid = (range.0 + range.1) / 2.0;
+            bits <<= 1;
+            if value >= mid {
+                bits |= 1;
+                range.0 = mid;
+            } else {
+                range.1 = mid;
+            }
+            even = !even;
+            bit_count += 1;
+            if bit_count == 5 {
+                hash.push(GEOHASH_ALPHABET[bits] as char);
+                bits = 0;
+                bit_count = 0;
+            }
+        }
+        hash
+    }
+
+    fn geohash_decode(hash: &str) -> Option<BoundingBox> {
+        if hash.is_empty() {
+            return None;
+        }
+        let (mut lat_range, mut lon_range) = ((-90.0, 90.0), (-180.0, 180.0));
+        let mut even = true;
+        for c in hash.bytes() {
+            let bits = GEOHASH_ALPHABET.iter().position(|a| *a == c)?;
+            for shift in (0..5).rev() {
+                let range: &mut (f64, f64) = if even { &mut lon_range } else { &mut lat_range };
+                let mid = (range.0 + range.1) / 2.0;
+                if bits >> shift & 1 == 1 {
+                    range.0 = mid;
+                } else {
+                    range.1 = mid;
+                }
+                even = !even;
+            }
+        }
+        Some(BoundingBox { min_lat: lat_range.0, min_lon: lon_range.0, max_lat: lat_range.1, max_lon: lon_range.1 })
+    }
+
+    fn parse_point(value: &JsonValue) -> Option<Point> {
+        let (lat, lon) = match value {
+            JsonValue::Object(obj) => (
+                obj.get("lat").and_then(|v| v.as_f64()),
+                obj.get("lon").or_else(|| obj.get("lng")).and_then(|v| v.as_f64()),
+            ),
+            JsonValue::Array(items) if items.len() == 2 => (items[0].as_f64(), items[1].as_f64()),
+            _ => (None, None),
+        };
+        let (lat, lon) = lat.zip(lon)?;
+        ((-90.0..=90.0).contains(&lat) && lon.is_finite()).then_some(Point { lat, lon })
+    }
+
+    fn point_arg(args: &[String], index: usize) -> Result<Point, String> {
+        args.get(index)
+            .and_then(|arg| serde_json::from_str::<JsonValue>(arg).ok())
+            .as_ref()
+            .and_then(parse_point)
+            .ok_or_else(|| format!("错误: 第{}个参数必须是点（{{\"lat\", \"lon\"}} 或 [纬度, 经度]）", index + 1))
+    }
+
+    fn points_arg(args: &[String], index: usize) -> Result<Vec<Point>, String> {
+        match args.get(index).and_then(|arg| serde_json::from_str::<JsonValue>(arg).ok()) {
+            Some(JsonValue::Array(items)) => items.iter()
+                .map(|item| parse_point(item).ok_or_else(|| format!("错误: 无效的点: {}", item)))
+                .collect(),
+            _ => Err(format!("错误: 第{}个参数必须是点的数组", index + 1)),
+        }
+    }
+
+    fn bbox_arg(args: &[String], index: usize) -> Result<BoundingBox, String> {
+        let obj = match args.get(index).and_then(|arg| serde_json::from_str::<JsonValue>(arg).ok()) {
+            Some(JsonValue::Object(obj)) => obj,
+            _ => return Err(format!("错误: 第{}个参数必须是边界框", index + 1)),
+        };
+        let field = |name: &str| obj.get(name).and_then(|v| v.as_f64()).ok_or_else(|| format!("错误: 边界框缺少 {}", name));
+        Ok(BoundingBox { min_lat: field("min_lat")?, min_lon: field("min_lon")?, max_lat: field("max_lat")?, max_lon: field("max_lon")? })
+    }
+
+    fn number_arg(args: &[String], index: usize, name: &str) -> Result<f64, String> {
+        match args.get(index).map(|a| a.trim().parse::<f64>()) {
+            Some(Ok(value)) if value.is_finite() => Ok(value),
+            _ => Err(format!("错误: {}必须是数字", name)),
+        }
+    }
+
+    // 单位对应的米数
+    fn unit_arg(args: &[String], index: usize) -> Result<f64, String> {
+        match args.get(index).map(|u| u.trim().to_lowercase()).as_deref() {
+            None | Some("") | Some("km") => Ok(1000.0),
+            Some("m") => Ok(1.0),
+            Some("mi") => Ok(1609.344),
+            Some("nmi") => Ok(1852.0),
+            Some("ft") => Ok(0.3048),
+            Some(unit) => Err(format!("错误: 未知的距离单位: {}", unit)),
+        }
+    }
+
+    // 浮点数总是带小数点返回，使脚本中得到的值是浮点数
+    fn number(value: f64) -> String {
+        json_value(&JsonValue::from(value).to_string())
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册地理命名空间下的函数
+    let geo_ns = registry.namespace("geo");
+    geo_ns.add_function("distance", geo::cn_distance)
+          .add_function("bearing", geo::cn_bearing)
+          .add_function("destination", geo::cn_destination)
+          .add_function("midpoint", geo::cn_midpoint)
+          .add_function("bbox", geo::cn_bbox)
+          .add_function("bbox_around", geo::cn_bbox_around)
+          .add_function("in_bbox", geo::cn_in_bbox)
+          .add_function("in_polygon", geo::cn_in_polygon)
+          .add_function("convert", geo::cn_convert)
+          .add_function("to_mercator", geo::cn_to_mercator)
+          .add_function("from_mercator", geo::cn_from_mercator)
+          .add_function("to_dms", geo::cn_to_dms)
+          .add_function("from_dms", geo::cn_from_dms)
+          .add_function("geohash_encode", geo::cn_geohash_encode)
+          .add_function("geohash_decode", geo::cn_geohash_decode);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
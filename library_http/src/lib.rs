@@ -1,181 +1,1365 @@
+use ::std::collections::hash_map::DefaultHasher;
 use ::std::collections::HashMap;
-use reqwest::blocking::{Client, Response};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use encoding_rs::{Encoding, UTF_8};
+use reqwest::blocking::multipart::Form;
+use reqwest::blocking::{Body, Client, ClientBuilder, Response};
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Certificate, Identity, Method, NoProxy, Proxy};
+use serde_json::{Map, Value as JsonValue};
+use std::env;
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::time::Duration;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use ::url::form_urlencoded;
+use ::url::Url;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 
 // 导入通用库
-use cn_common::namespace::{LibraryFunction, NamespaceBuilder, create_library_pointer, LibraryRegistry};
+use cn_common::handle::HandleTable;
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::{callback_value, json_value, thrown_message};
 
 // HTTP命名空间
+// 请求函数返回响应 Map: {"status": 状态码, "headers": {小写头名: 值}, "body": 响应体}，
+// 也可以用 status、body、header 函数读取
+// 请求函数最后一个可选参数是 session_create 返回的会话句柄，或者包含超时、重试、代理、证书等设置的选项 Map
+// download、upload 和 multipart 直接在文件和网络之间传输数据，不把整个内容读入字符串
 mod http {
     use super::*;
 
+    const DEFAULT_BACKOFF_MS: u64 = 200;
+    const DEFAULT_RETRY_STATUSES: [u16; 4] = [429, 502, 503, 504];
+
+    // 下载进度处理函数返回后，解释器调用的续接函数
+    const DOWNLOAD_RESUME_FUNCTION: &str = "http::download_resume";
+    // 两次进度回调之间的最短间隔
+    const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+    const CHUNK_SIZE: usize = 64 * 1024;
+    const DEFAULT_CONCURRENCY: usize = 4;
+    // 缓存目录的环境变量，没有设置时使用系统临时目录下的 cn_http_cache
+    const CACHE_DIR_ENV: &str = "CN_HTTP_CACHE";
+    // 令牌在过期前多少秒重新获取
+    const TOKEN_REFRESH_MARGIN_SECS: u64 = 30;
+
+    // 共享的默认客户端，没有指定会话的请求复用它的连接
+    static DEFAULT_CLIENT: OnceLock<Client> = OnceLock::new();
+    static SESSIONS: Mutex<HandleTable<Session>> = Mutex::new(HandleTable::new());
+    // 正在报告进度的下载，进度处理函数中可以再开始下载
+    static DOWNLOADS: Mutex<Vec<Download>> = Mutex::new(Vec::new());
+
+    struct Session {
+        client: Client,
+        base_url: Option<Url>,
+        auth: Option<Auth>,
+    }
+
+    // 会话请求的 Authorization 头
+    enum Auth {
+        Header(String),
+        OAuth2(OAuth2),
+    }
+
+    // OAuth2 客户端凭据模式，令牌过期前自动重新获取
+    struct OAuth2 {
+        token_url: String,
+        client_id: String,
+        client_secret: String,
+        // 额外的表单参数，如 scope、audience
+        params: Vec<(String, String)>,
+        // 为 true 时把客户端凭据放在表单中，否则使用 Basic 认证
+        credentials_in_body: bool,
+        token: Option<(String, Option<Instant>)>,
+    }
+
+    impl OAuth2 {
+        // 返回有效的 Authorization 头，令牌不存在或即将过期时先获取新令牌
+        fn header(&mut self, client: &Client) -> Result<String, String> {
+            if let Some((header, expires)) = &self.token {
+                if expires.map_or(true, |expires| Instant::now() < expires) {
+                    return Ok(header.clone());
+                }
+            }
+            self.fetch(client)?;
+            Ok(self.token.as_ref().map(|(header, _)| header.clone()).unwrap_or_default())
+        }
+
+        // 向令牌端点请求新令牌，返回令牌端点的响应
+        fn fetch(&mut self, client: &Client) -> Result<Map<String, JsonValue>, String> {
+            let mut form = vec![("grant_type".to_string(), "client_credentials".to_string())];
+            form.extend(self.params.iter().cloned());
+            let mut request = client.post(&self.token_url).header(reqwest::header::ACCEPT, "application/json");
+            if self.credentials_in_body {
+                form.push(("client_id".to_string(), self.client_id.clone()));
+                form.push(("client_secret".to_string(), self.client_secret.clone()));
+            } else {
+                request = request.basic_auth(&self.client_id, Some(&self.client_secret));
+            }
+            let response = request.form(&form).send().map_err(|e| format!("错误: 获取令牌失败: {}", e))?;
+            let status = response.status();
+            let body = response.text().map_err(|e| format!("错误: 获取令牌失败: {}", e))?;
+            if !status.is_success() {
+                return Err(format!("错误: 获取令牌失败，状态码 {}: {}", status.as_u16(), body));
+            }
+            let token = match serde_json::from_str::<JsonValue>(&body) {
+                Ok(JsonValue::Object(token)) if token.get("access_token").is_some_and(|t| t.is_string()) => token,
+                _ => return Err(format!("错误: 令牌端点的响应中没有 access_token: {}", body)),
+            };
+            let access_token = token["access_token"].as_str().unwrap_or_default();
+            // token_type 大小写不敏感，统一使用 Bearer 的写法
+            let token_type = match token.get("token_type").and_then(|t| t.as_str()) {
+                Some(t) if !t.eq_ignore_ascii_case("bearer") => t.to_string(),
+                _ => "Bearer".to_string(),
+            };
+            let expires = token.get("expires_in")
+                .and_then(|e| e.as_u64().or_else(|| e.as_str().and_then(|e| e.parse().ok())))
+                .map(|secs| Instant::now() + Duration::from_secs(secs.saturating_sub(TOKEN_REFRESH_MARGIN_SECS)));
+            self.token = Some((format!("{} {}", token_type, access_token), expires));
+            Ok(token)
+        }
+    }
+
+    struct Download {
+        response: Response,
+        file: BufWriter<File>,
+        path: PathBuf,
+        handler: String,
+        status: u16,
+        downloaded: u64,
+        total: Option<u64>,
+        finished: bool,
+    }
+
+    fn lock_sessions() -> MutexGuard<'static, HandleTable<Session>> {
+        SESSIONS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_downloads() -> MutexGuard<'static, Vec<Download>> {
+        DOWNLOADS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     // 执行GET请求
+    // 参数: url, [options]（会话句柄或选项 Map）
     pub fn cn_get(args: Vec<String>) -> String {
         if args.is_empty() {
             return "错误: 未提供URL".to_string();
         }
-        
-        let url = &args[0];
-        let client = Client::new();
-        
-        match client.get(url).send() {
-            Ok(response) => format_response(response),
-            Err(err) => format!("错误: {}", err)
-        }
+        execute("GET", &args[0], HeaderMap::new(), None, args.get(1))
     }
     
     // 执行POST请求
+    // 参数: url, body, [options]（会话句柄或选项 Map）
     pub fn cn_post(args: Vec<String>) -> String {
         if args.len() < 2 {
             return "错误: 请提供URL和请求体".to_string();
         }
-        
-        let url = &args[0];
-        let body = &args[1];
-        let client = Client::new();
-        
-        match client.post(url).body(body.clone()).send() {
-            Ok(response) => format_response(response),
-            Err(err) => format!("错误: {}", err)
-        }
+        execute("POST", &args[0], HeaderMap::new(), Some(args[1].clone()), args.get(2))
     }
     
+    // 并发执行多个GET请求，返回与 urls 顺序相同的响应 Map 数组；
+    // 请求失败时对应的元素为 {"error": 错误信息}，不影响其他请求
+    // 参数: urls（URL数组）, [concurrency]（同时进行的请求数，默认为4）, [options]（所有请求共用的会话句柄或选项 Map）
+    pub fn cn_get_all(args: Vec<String>) -> String {
+        let urls: Vec<String> = match args.first().map(|u| serde_json::from_str::<JsonValue>(u)) {
+            Some(Ok(JsonValue::Array(urls))) => match urls.iter().map(|u| u.as_str().map(|u| u.to_string())).collect() {
+                Some(urls) => urls,
+                None => return "错误: URL数组的元素必须是字符串".to_string(),
+            },
+            _ => return "错误: 请提供URL数组".to_string(),
+        };
+        let concurrency = match args.get(1).map(|c| c.trim()).filter(|c| !c.is_empty()).map(|c| c.parse::<usize>()) {
+            Some(Ok(n)) if n > 0 => n,
+            None => DEFAULT_CONCURRENCY,
+            _ => return format!("错误: 并发数必须是正整数: {}", args[1]),
+        };
+        let options = match parse_request_options(args.get(2)) {
+            Ok(options) => options,
+            Err(e) => return e,
+        };
+
+        // 工作线程依次领取下一个URL
+        let next = AtomicUsize::new(0);
+        let mut results = vec![JsonValue::Null; urls.len()];
+        let finished: Vec<(usize, JsonValue)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..concurrency.min(urls.len()))
+                .map(|_| scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let url = match urls.get(index) {
+                            Some(url) => url,
+                            None => return done,
+                        };
+                        let result = match fetch("GET", url, HeaderMap::new(), &Payload::Empty, &options) {
+                            Ok(fetched) => JsonValue::Object(response_map(fetched, &options.body)),
+                            Err(e) => {
+                                let mut error = Map::new();
+                                error.insert("error".to_string(), JsonValue::String(e));
+                                JsonValue::Object(error)
+                            },
+                        };
+                        done.push((index, result));
+                    }
+                }))
+                .collect();
+            workers.into_iter().flat_map(|worker| worker.join().unwrap_or_default()).collect()
+        });
+        for (index, result) in finished {
+            results[index] = result;
+        }
+        json_value(&JsonValue::Array(results).to_string())
+    }
+
+    // 执行GET请求并把响应体解析为JSON返回
+    // 状态码不是2xx时返回 {"error": 错误信息, "status", "headers", "body"}
+    // 参数: url, [options]（会话句柄或选项 Map）
+    pub fn cn_get_json(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 未提供URL".to_string();
+        }
+        execute_json("GET", &args[0], None, args.get(1))
+    }
+
+    // 以JSON格式发送请求体执行POST请求，并把响应体解析为JSON返回
+    // body 是 Map、数组或JSON文本，其他字符串作为JSON字符串发送；状态码不是2xx时的返回值与 get_json 相同
+    // 参数: url, body, [options]（会话句柄或选项 Map）
+    pub fn cn_post_json(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供URL和请求体".to_string();
+        }
+        execute_json("POST", &args[0], Some(&args[1]), args.get(2))
+    }
+
     // 执行PUT请求
+    // 参数: url, body, [options]（会话句柄或选项 Map）
     pub fn cn_put(args: Vec<String>) -> String {
         if args.len() < 2 {
             return "错误: 请提供URL和请求体".to_string();
         }
-        
-        let url = &args[0];
-        let body = &args[1];
-        let client = Client::new();
-        
-        match client.put(url).body(body.clone()).send() {
-            Ok(response) => format_response(response),
-            Err(err) => format!("错误: {}", err)
-        }
+        execute("PUT", &args[0], HeaderMap::new(), Some(args[1].clone()), args.get(2))
     }
     
     // 执行DELETE请求
+    // 参数: url, [options]（会话句柄或选项 Map）
     pub fn cn_delete(args: Vec<String>) -> String {
         if args.is_empty() {
             return "错误: 未提供URL".to_string();
         }
-        
-        let url = &args[0];
-        let client = Client::new();
-        
-        match client.delete(url).send() {
-            Ok(response) => format_response(response),
-            Err(err) => format!("错误: {}", err)
-        }
+        execute("DELETE", &args[0], HeaderMap::new(), None, args.get(1))
     }
     
     // 带自定义头的请求
+    // 头信息可以是 Map，也可以是 "key1:value1;key2:value2" 形式的字符串
+    // 参数: method, url, headers, [body], [options]（会话句柄或选项 Map）
     pub fn cn_request(args: Vec<String>) -> String {
         if args.len() < 3 {
             return "错误: 请提供方法、URL和头信息".to_string();
         }
         
-        let method = &args[0];
-        let url = &args[1];
-        let headers_str = &args[2];
-        let body = args.get(3).cloned().unwrap_or_default();
-        
-        let client = Client::builder()
-            .timeout(Duration::from_secs(30))
-            .build()
-            .unwrap_or_default();
-            
-        // 解析头信息 (格式: "key1:value1;key2:value2")
+        let headers = parse_headers(&args[2]);
+        let body = args.get(3).filter(|body| !body.is_empty()).cloned();
+        execute(&args[0], &args[1], headers, body, args.get(4))
+    }
+
+    // 下载文件，响应体边接收边写入文件，返回 {"status", "path", "bytes"}；状态码不是2xx时返回错误
+    // 指定 progress 时在下载过程中和完成时调用 progress(已下载字节数, 总字节数)，总字节数未知时为空值，
+    // progress 返回 false 时取消下载
+    // 参数: url, path, [progress], [options]
+    pub fn cn_download(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供URL和保存路径".to_string();
+        }
+        let options = match parse_request_options(args.get(3)) {
+            Ok(options) => options,
+            Err(e) => return e,
+        };
+        let response = match send("GET", &args[0], HeaderMap::new(), &Payload::Empty, &options) {
+            Ok(response) => response,
+            Err(e) => return e,
+        };
+        let status = response.status().as_u16();
+        if !response.status().is_success() {
+            return format!("错误: 下载失败，状态码 {}", status);
+        }
+
+        let path = PathBuf::from(&args[1]);
+        let file = match File::create(&path) {
+            Ok(file) => BufWriter::new(file),
+            Err(e) => return format!("错误: 无法创建文件 {}: {}", path.display(), e),
+        };
+        let mut download = Download {
+            total: response.content_length(),
+            response,
+            file,
+            path,
+            handler: String::new(),
+            status,
+            downloaded: 0,
+            finished: false,
+        };
+
+        match args.get(2).filter(|h| !h.trim().is_empty()) {
+            Some(handler) => {
+                download.handler = handler.clone();
+                let mut downloads = lock_downloads();
+                downloads.push(download);
+                next_progress(downloads)
+            },
+            None => {
+                let copied = io::copy(&mut download.response, &mut download.file).map_err(|e| e.to_string());
+                match copied {
+                    Ok(bytes) => {
+                        download.downloaded = bytes;
+                        finish_download(download)
+                    },
+                    Err(e) => abort_download(download, &format!("错误: 下载中断: {}", e)),
+                }
+            },
+        }
+    }
+
+    // 进度处理函数返回后由解释器调用，继续下载；以续接函数注册，脚本无法直接调用
+    // 参数: returned
+    pub fn cn_download_resume(args: Vec<String>) -> String {
+        let mut downloads = lock_downloads();
+        let download = match downloads.pop() {
+            Some(download) => download,
+            None => return "错误: 没有正在进行的下载".to_string(),
+        };
+        let returned = args.first().map(|s| s.as_str()).unwrap_or("");
+        if let Some(message) = thrown_message(returned) {
+            return abort_download(download, &format!("错误: 进度处理函数抛出异常: {}", message));
+        }
+        if returned == "false" {
+            return abort_download(download, "错误: 下载已取消");
+        }
+        if download.finished {
+            return finish_download(download);
+        }
+        downloads.push(download);
+        next_progress(downloads)
+    }
+
+    // 上传文件作为请求体，文件内容边读边发送
+    // options 除请求选项外还可以包含 method（默认为 POST）和 content_type（默认为 application/octet-stream）
+    // 参数: url, path, [options]
+    pub fn cn_upload(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供URL和文件路径".to_string();
+        }
+        let extra = match parse_extra_options(args.get(2)) {
+            Ok(extra) => extra,
+            Err(e) => return e,
+        };
+        let options = match parse_request_options(args.get(2)) {
+            Ok(options) => options,
+            Err(e) => return e,
+        };
+        let path = PathBuf::from(&args[1]);
+        if !path.is_file() {
+            return format!("错误: 文件不存在: {}", path.display());
+        }
+        let method = extra.get("method").and_then(|m| m.as_str()).unwrap_or("POST");
+        let content_type = extra.get("content_type").and_then(|c| c.as_str()).unwrap_or("application/octet-stream");
         let mut headers = HeaderMap::new();
-        for header_pair in headers_str.split(';') {
-            if let Some((key, value)) = header_pair.split_once(':') {
-                if let (Ok(name), Ok(val)) = (
-                    HeaderName::from_str(key.trim()),
-                    HeaderValue::from_str(value.trim())
-                ) {
-                    headers.insert(name, val);
+        if let Ok(value) = HeaderValue::from_str(content_type) {
+            headers.insert(reqwest::header::CONTENT_TYPE, value);
+        }
+        match send(method, &args[0], headers, &Payload::File(path), &options) {
+            Ok(response) => format_response(response, &options.body),
+            Err(e) => e,
+        }
+    }
+
+    // 以 multipart/form-data 提交表单，文件部分边读边发送
+    // fields 是文本字段 Map，files 是 {字段名: 文件路径} Map
+    // 参数: url, fields, [files], [options]
+    pub fn cn_multipart(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供URL和表单字段".to_string();
+        }
+        let fields = match string_map(&args[1], "表单字段") {
+            Ok(fields) => fields,
+            Err(e) => return e,
+        };
+        let files = match args.get(2).filter(|f| !f.trim().is_empty()).map(|f| string_map(f, "文件")) {
+            Some(Ok(files)) => files.into_iter().map(|(name, path)| (name, PathBuf::from(path))).collect(),
+            Some(Err(e)) => return e,
+            None => Vec::new(),
+        };
+        let options = match parse_request_options(args.get(3)) {
+            Ok(options) => options,
+            Err(e) => return e,
+        };
+        match send("POST", &args[0], HeaderMap::new(), &Payload::Multipart(fields, files), &options) {
+            Ok(response) => format_response(response, &options.body),
+            Err(e) => e,
+        }
+    }
+
+    // 创建会话，会话复用连接、保存服务器设置的Cookie，返回会话句柄
+    // options 是可选的 Map:
+    //   base_url: 相对URL接在它后面, headers: 每个请求都带上的头信息（Map）,
+    //   cookies: 为 false 时不保存Cookie, user_agent: User-Agent 头, timeout: 请求超时毫秒数（默认30000，0表示不限），
+    //   auth: Authorization 头（如 basic_auth、bearer_auth 的结果）,
+    //   以及 connect_timeout、proxy、ca_cert 等客户端选项（见 client_builder）
+    // 参数: [options]
+    pub fn cn_session_create(args: Vec<String>) -> String {
+        let options = match args.first().filter(|o| !o.trim().is_empty()) {
+            Some(options) => match serde_json::from_str::<Map<String, JsonValue>>(options) {
+                Ok(options) => options,
+                Err(_) => return "错误: 选项必须是 Map".to_string(),
+            },
+            None => Map::new(),
+        };
+        match build_session(&options) {
+            Ok(session) => lock_sessions().insert(session).to_string(),
+            Err(e) => e,
+        }
+    }
+
+    // 删除缓存的响应
+    // 参数: [dir]（缓存目录，默认为 cache 为 true 时使用的目录）
+    pub fn cn_cache_clear(args: Vec<String>) -> String {
+        let dir = args.first().filter(|d| !d.trim().is_empty()).map(PathBuf::from).unwrap_or_else(default_cache_dir);
+        match fs::remove_dir_all(&dir) {
+            Ok(()) => "true".to_string(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => "true".to_string(),
+            Err(e) => format!("错误: 无法删除缓存目录 {}: {}", dir.display(), e),
+        }
+    }
+
+    // 生成 HTTP Basic 认证的 Authorization 头，用作 auth 选项或请求头
+    // 参数: user, password
+    pub fn cn_basic_auth(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供用户名".to_string();
+        }
+        let password = args.get(1).map(|p| p.as_str()).unwrap_or("");
+        format!("Basic {}", STANDARD.encode(format!("{}:{}", args[0], password)))
+    }
+
+    // 生成 Bearer 令牌的 Authorization 头
+    // 参数: token
+    pub fn cn_bearer_auth(args: Vec<String>) -> String {
+        match args.first().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            Some(token) => format!("Bearer {}", token),
+            None => "错误: 请提供令牌".to_string(),
+        }
+    }
+
+    // 设置会话请求的 Authorization 头，替换之前的设置（包括 oauth2）；值为空时清除
+    // 参数: session, auth
+    pub fn cn_session_auth(args: Vec<String>) -> String {
+        let mut sessions = lock_sessions();
+        let session = match args.first().and_then(|arg| sessions.parse_handle(arg)).and_then(|id| sessions.get_mut(id)) {
+            Some(session) => session,
+            None => return "false".to_string(),
+        };
+        session.auth = args.get(1).map(|a| a.trim()).filter(|a| !a.is_empty()).map(|a| Auth::Header(a.to_string()));
+        "true".to_string()
+    }
+
+    // 为会话使用 OAuth2 客户端凭据模式：立即获取令牌，之后会话的请求自动带上令牌，过期前自动重新获取
+    // config: {"token_url", "client_id", "client_secret", [scope], [audience], [params]（其他表单参数 Map）,
+    //          [credentials]（"basic"（默认）或 "body"，客户端凭据的发送方式）}
+    // 返回令牌端点的响应 Map（包含 access_token、expires_in 等）
+    // 参数: session, config
+    pub fn cn_oauth2(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供会话句柄和配置".to_string();
+        }
+        let config = match serde_json::from_str::<JsonValue>(&args[1]) {
+            Ok(JsonValue::Object(config)) => config,
+            _ => return "错误: 配置必须是 Map".to_string(),
+        };
+        let text = |name: &str| config.get(name).and_then(|v| v.as_str()).map(|v| v.to_string());
+        let (token_url, client_id, client_secret) = match (text("token_url"), text("client_id"), text("client_secret")) {
+            (Some(url), Some(id), Some(secret)) => (url, id, secret),
+            _ => return "错误: 配置必须包含 token_url、client_id 和 client_secret".to_string(),
+        };
+        let mut params = Vec::new();
+        for name in ["scope", "audience"] {
+            if let Some(value) = text(name) {
+                params.push((name.to_string(), value));
+            }
+        }
+        if let Some(JsonValue::Object(extra)) = config.get("params") {
+            params.extend(extra.iter().map(|(key, value)| match value {
+                JsonValue::String(value) => (key.clone(), value.clone()),
+                other => (key.clone(), other.to_string()),
+            }));
+        }
+        let credentials_in_body = match text("credentials").as_deref() {
+            Some("body") => true,
+            Some("basic") | None => false,
+            Some(other) => return format!("错误: credentials 必须是 \"basic\" 或 \"body\": {}", other),
+        };
+
+        let mut sessions = lock_sessions();
+        let session = match sessions.parse_handle(&args[0]).and_then(|id| sessions.get_mut(id)) {
+            Some(session) => session,
+            None => return "错误: 无效的会话句柄".to_string(),
+        };
+        let mut oauth = OAuth2 { token_url, client_id, client_secret, params, credentials_in_body, token: None };
+        match oauth.fetch(&session.client) {
+            Ok(token) => {
+                session.auth = Some(Auth::OAuth2(oauth));
+                json_value(&JsonValue::Object(token).to_string())
+            },
+            Err(e) => e,
+        }
+    }
+
+    // 关闭会话
+    // 参数: session
+    pub fn cn_session_close(args: Vec<String>) -> String {
+        let mut sessions = lock_sessions();
+        match args.first().and_then(|arg| sessions.parse_handle(arg)) {
+            Some(id) => sessions.remove(id).is_some().to_string(),
+            None => "false".to_string(),
+        }
+    }
+
+    fn build_session(options: &Map<String, JsonValue>) -> Result<Session, String> {
+        let base_url = match options.get("base_url").and_then(|b| b.as_str()) {
+            Some(base) => {
+                // 确保以 / 结尾，使相对URL接在路径后面而不是替换最后一段
+                let base = if base.ends_with('/') { base.to_string() } else { format!("{}/", base) };
+                Some(Url::parse(&base).map_err(|e| format!("错误: 无效的 base_url {}: {}", base, e))?)
+            },
+            None => None,
+        };
+
+        let mut builder = client_builder(options)?
+            .cookie_store(options.get("cookies").and_then(|c| c.as_bool()).unwrap_or(true));
+        if let Some(headers) = options.get("headers") {
+            builder = builder.default_headers(parse_headers(&headers.to_string()));
+        }
+        if let Some(user_agent) = options.get("user_agent").and_then(|u| u.as_str()) {
+            builder = builder.user_agent(user_agent);
+        }
+        if let Some(timeout) = options.get("timeout") {
+            let ms = timeout.as_u64().ok_or_else(|| format!("错误: 无效的超时毫秒数: {}", timeout))?;
+            builder = builder.timeout((ms > 0).then(|| Duration::from_millis(ms)));
+        }
+        let client = builder.build().map_err(|e| format!("错误: 无法创建会话: {}", e))?;
+        let auth = options.get("auth").and_then(|a| a.as_str()).map(|a| Auth::Header(a.to_string()));
+        Ok(Session { client, base_url, auth })
+    }
+
+    // 只能在创建客户端时设置的选项
+    const CLIENT_OPTIONS: [&str; 8] = ["connect_timeout", "proxy", "no_proxy", "ca_cert", "client_cert", "client_key", "insecure", "decompress"];
+
+    // 按客户端选项创建客户端构造器:
+    //   connect_timeout: 建立连接的超时毫秒数,
+    //   proxy: 代理URL，支持 http://、https://、socks5:// 和 socks5h://，用户名和密码写在URL中；
+    //          为 false 时不使用环境变量 HTTP_PROXY 等设置的代理,
+    //   no_proxy: 不使用代理的主机，逗号分隔（如 "localhost,.internal"）,
+    //   ca_cert: 额外信任的CA证书文件（PEM，可以包含多个证书）,
+    //   client_cert: 客户端证书文件，PEM（私钥在同一文件或 client_key 中）或 PKCS#12（.p12/.pfx，密码为 client_key）,
+    //   insecure: 为 true 时不验证服务器证书和主机名，只应用于内部测试服务器,
+    //   decompress: 为 false 时不请求压缩，也不自动解压 gzip、deflate、brotli 压缩的响应
+    fn client_builder(options: &Map<String, JsonValue>) -> Result<ClientBuilder, String> {
+        let mut builder = Client::builder();
+        if let Some(timeout) = options.get("connect_timeout") {
+            let ms = timeout.as_u64().ok_or_else(|| "错误: connect_timeout 必须是非负整数毫秒数".to_string())?;
+            if ms > 0 {
+                builder = builder.connect_timeout(Duration::from_millis(ms));
+            }
+        }
+
+        match options.get("proxy") {
+            Some(JsonValue::String(url)) => {
+                let mut proxy = Proxy::all(url.as_str()).map_err(|e| format!("错误: 无效的代理 {}: {}", url, e))?;
+                if let Some(no_proxy) = options.get("no_proxy").and_then(|n| n.as_str()) {
+                    proxy = proxy.no_proxy(NoProxy::from_string(no_proxy));
                 }
+                builder = builder.proxy(proxy);
+            },
+            Some(JsonValue::Bool(false)) => builder = builder.no_proxy(),
+            Some(JsonValue::Null) | None => {},
+            Some(other) => return Err(format!("错误: 无效的代理: {}", other)),
+        }
+
+        if let Some(path) = options.get("ca_cert").and_then(|c| c.as_str()) {
+            let pem = fs::read(path).map_err(|e| format!("错误: 无法读取CA证书 {}: {}", path, e))?;
+            let certs = Certificate::from_pem_bundle(&pem)
+                .ok()
+                .filter(|certs| !certs.is_empty())
+                .map(Ok)
+                // 不是PEM时按DER格式读取单个证书
+                .unwrap_or_else(|| Certificate::from_der(&pem).map(|cert| vec![cert]))
+                .map_err(|e| format!("错误: 无效的CA证书 {}: {}", path, e))?;
+            for cert in certs {
+                builder = builder.add_root_certificate(cert);
             }
         }
-        
-        let request_builder = match method.to_uppercase().as_str() {
-            "GET" => client.get(url),
-            "POST" => client.post(url),
-            "PUT" => client.put(url),
-            "DELETE" => client.delete(url),
-            "HEAD" => client.head(url),
-            "PATCH" => client.patch(url),
-            _ => return format!("错误: 不支持的HTTP方法 '{}'", method)
+
+        if let Some(path) = options.get("client_cert").and_then(|c| c.as_str()) {
+            let cert = fs::read(path).map_err(|e| format!("错误: 无法读取客户端证书 {}: {}", path, e))?;
+            let key = options.get("client_key").and_then(|k| k.as_str());
+            let identity = if path.ends_with(".p12") || path.ends_with(".pfx") {
+                Identity::from_pkcs12_der(&cert, key.unwrap_or(""))
+            } else {
+                let key = match key {
+                    Some(key_path) => fs::read(key_path).map_err(|e| format!("错误: 无法读取私钥 {}: {}", key_path, e))?,
+                    None => cert.clone(),
+                };
+                Identity::from_pkcs8_pem(&cert, &key)
+            };
+            builder = builder.identity(identity.map_err(|e| format!("错误: 无效的客户端证书 {}: {}", path, e))?);
+        }
+
+        if options.get("insecure").and_then(|i| i.as_bool()).unwrap_or(false) {
+            builder = builder.danger_accept_invalid_certs(true).danger_accept_invalid_hostnames(true);
+        }
+        if options.get("decompress").and_then(|d| d.as_bool()) == Some(false) {
+            builder = builder.no_gzip().no_deflate().no_brotli();
+        }
+        Ok(builder)
+    }
+
+    // 单个请求的选项
+    struct RequestOptions {
+        session: Option<String>,
+        timeout: Option<Duration>,
+        // 指定了客户端选项时为这个请求单独创建的客户端
+        client: Option<Client>,
+        retries: u32,
+        backoff: Duration,
+        retry_on: Vec<u16>,
+        body: BodyMode,
+        auth: Option<String>,
+        cache: Option<PathBuf>,
+    }
+
+    // 响应体的返回方式
+    enum BodyMode {
+        // 解码为文本，指定编码时忽略响应声明的编码
+        Text(Option<&'static Encoding>),
+        Hex,
+        Base64,
+    }
+
+    // 请求函数的最后一个参数可以是会话句柄，也可以是选项 Map:
+    //   session: 会话句柄, timeout: 整个请求的超时毫秒数（0表示不限）,
+    //   connect_timeout、proxy、ca_cert 等客户端选项（见 client_builder；使用会话时在 session_create 中设置）,
+    //   retries: 失败后的重试次数（默认0）, backoff: 第一次重试前等待的毫秒数（默认200，之后每次加倍）,
+    //   retry_on: 需要重试的状态码数组（默认 [429, 502, 503, 504]）；连接失败和超时总是重试,
+    //   charset: 按指定编码（如 "gbk"）解码响应体，默认按响应头或HTML中声明的编码,
+    //   raw: "hex" 或 "base64"，用于二进制内容，响应体不解码为文本而是返回编码后的字节,
+    //   auth: Authorization 头（如 basic_auth、bearer_auth 的结果），优先于会话的设置,
+    //   cache: 为 true 时缓存 GET 请求的响应（见 fetch），也可以是缓存目录的路径
+    fn parse_request_options(arg: Option<&String>) -> Result<RequestOptions, String> {
+        let mut options = RequestOptions {
+            session: None,
+            timeout: None,
+            client: None,
+            retries: 0,
+            backoff: Duration::from_millis(DEFAULT_BACKOFF_MS),
+            retry_on: DEFAULT_RETRY_STATUSES.to_vec(),
+            body: BodyMode::Text(None),
+            auth: None,
+            cache: None,
         };
-        
-        let request_with_headers = request_builder.headers(headers);
-        
-        // 添加请求体（如果有）
-        let request_with_body = if !body.is_empty() && method != "GET" && method != "HEAD" {
-            request_with_headers.body(body)
-        } else {
-            request_with_headers
+        let arg = match arg.map(|a| a.trim()).filter(|a| !a.is_empty()) {
+            Some(arg) => arg,
+            None => return Ok(options),
         };
-        
-        match request_with_body.send() {
-            Ok(response) => format_response(response),
-            Err(err) => format!("错误: {}", err)
+        let map = match serde_json::from_str::<JsonValue>(arg) {
+            Ok(JsonValue::Object(map)) => map,
+            // 不是 Map 时作为会话句柄
+            _ => {
+                options.session = Some(arg.to_string());
+                return Ok(options);
+            },
+        };
+
+        let millis = |name: &str| -> Result<Option<u64>, String> {
+            match map.get(name) {
+                Some(value) => value.as_u64().map(Some).ok_or_else(|| format!("错误: {} 必须是非负整数毫秒数", name)),
+                None => Ok(None),
+            }
+        };
+        options.session = map.get("session").map(|s| match s {
+            JsonValue::String(s) => s.clone(),
+            other => other.to_string(),
+        });
+        // 请求超时为0表示不限，这里用一个足够长的时间代替，以覆盖客户端的默认超时
+        options.timeout = millis("timeout")?.map(|ms| if ms == 0 { Duration::from_secs(u32::MAX as u64) } else { Duration::from_millis(ms) });
+        if CLIENT_OPTIONS.iter().any(|name| map.contains_key(*name)) {
+            if options.session.is_some() {
+                return Err("错误: 使用会话时请在 session_create 中设置代理、证书等客户端选项".to_string());
+            }
+            let client = client_builder(&map)?.build().map_err(|e| format!("错误: 无法创建客户端: {}", e))?;
+            options.client = Some(client);
+        }
+        if let Some(retries) = map.get("retries") {
+            options.retries = retries.as_u64().and_then(|r| u32::try_from(r).ok()).ok_or_else(|| "错误: retries 必须是非负整数".to_string())?;
         }
+        if let Some(backoff) = millis("backoff")? {
+            options.backoff = Duration::from_millis(backoff);
+        }
+        if let Some(retry_on) = map.get("retry_on") {
+            options.retry_on = retry_on.as_array()
+                .and_then(|codes| codes.iter().map(|c| c.as_u64().and_then(|c| u16::try_from(c).ok())).collect::<Option<Vec<_>>>())
+                .ok_or_else(|| "错误: retry_on 必须是状态码数组".to_string())?;
+        }
+        options.auth = map.get("auth").and_then(|a| a.as_str()).map(|a| a.to_string());
+        options.cache = match map.get("cache") {
+            Some(JsonValue::Bool(true)) => Some(default_cache_dir()),
+            Some(JsonValue::String(dir)) if !dir.is_empty() => Some(PathBuf::from(dir)),
+            Some(JsonValue::Bool(false)) | Some(JsonValue::Null) | None => None,
+            Some(other) => return Err(format!("错误: cache 必须是布尔值或目录路径: {}", other)),
+        };
+        if let Some(charset) = map.get("charset") {
+            let encoding = charset.as_str().and_then(|c| Encoding::for_label(c.trim().as_bytes()))
+                .ok_or_else(|| format!("错误: 不支持的编码: {}", charset))?;
+            options.body = BodyMode::Text(Some(encoding));
+        }
+        match map.get("raw").and_then(|r| r.as_str()) {
+            Some("hex") => options.body = BodyMode::Hex,
+            Some("base64") => options.body = BodyMode::Base64,
+            Some(other) => return Err(format!("错误: raw 必须是 \"hex\" 或 \"base64\": {}", other)),
+            None => {},
+        }
+        Ok(options)
     }
-    
-    // 编码URL
-    pub fn cn_encode_url(args: Vec<String>) -> String {
-        if args.is_empty() {
-            return String::new();
+
+    // 请求体，重试时按它重新构造
+    enum Payload {
+        Empty,
+        Text(String),
+        File(PathBuf),
+        Multipart(Vec<(String, String)>, Vec<(String, PathBuf)>),
+    }
+
+    // 发送请求并把响应转换为响应 Map
+    fn execute(method: &str, url: &str, headers: HeaderMap, body: Option<String>, options: Option<&String>) -> String {
+        let options = match parse_request_options(options) {
+            Ok(options) => options,
+            Err(e) => return e,
+        };
+        let payload = body.map(Payload::Text).unwrap_or(Payload::Empty);
+        match fetch(method, url, headers, &payload, &options) {
+            Ok(fetched) => json_value(&JsonValue::Object(response_map(fetched, &options.body)).to_string()),
+            Err(e) => e,
         }
-        
-        url::form_urlencoded::byte_serialize(args[0].as_bytes())
-            .collect::<String>()
     }
-    
-    // 解码URL
-    pub fn cn_decode_url(args: Vec<String>) -> String {
-        if args.is_empty() {
-            return String::new();
+
+    // 读取完的响应
+    struct Fetched {
+        status: u16,
+        // 小写头名到值的 Map，同名的头信息以 ", " 连接
+        headers: Map<String, JsonValue>,
+        body: Vec<u8>,
+    }
+
+    // 发送请求并读取响应；指定 cache 时 GET 请求使用缓存:
+    // 缓存过带 ETag 或 Last-Modified 的响应后，再次请求时带上 If-None-Match / If-Modified-Since，
+    // 服务器返回 304 时使用缓存的响应；Cache-Control 为 no-store 的响应不缓存
+    fn fetch(method: &str, url: &str, mut headers: HeaderMap, payload: &Payload, options: &RequestOptions) -> Result<Fetched, String> {
+        let cache_file = match &options.cache {
+            Some(dir) if method.eq_ignore_ascii_case("GET") => Some(cache_path(dir, &resolve_url(url, options)?)),
+            _ => None,
+        };
+        let cache_file = match cache_file {
+            Some(cache_file) => cache_file,
+            None => return read_response(send(method, url, headers, payload, options)?),
+        };
+
+        let cached = load_cached(&cache_file);
+        if let Some(cached) = &cached {
+            let validators = [
+                ("etag", reqwest::header::IF_NONE_MATCH),
+                ("last-modified", reqwest::header::IF_MODIFIED_SINCE),
+            ];
+            for (name, condition) in validators {
+                let value = cached.headers.get(name).and_then(|v| v.as_str()).and_then(|v| HeaderValue::from_str(v).ok());
+                if let Some(value) = value {
+                    headers.entry(condition).or_insert(value);
+                }
+            }
         }
-        
-        match url::form_urlencoded::parse(args[0].as_bytes())
-            .map(|(key, val)| format!("{}{}", key, val))
-            .collect::<String>() {
-                s if s.is_empty() => args[0].clone(),
-                s => s
+        let fetched = read_response(send(method, url, headers, payload, options)?)?;
+        if fetched.status == 304 {
+            if let Some(cached) = cached {
+                return Ok(cached);
             }
+        }
+        if fetched.status == 200 {
+            store_cached(&cache_file, &fetched);
+        }
+        Ok(fetched)
     }
-}
 
-// 格式化HTTP响应
-fn format_response(response: Response) -> String {
-    let status = response.status();
-    let headers = response.headers().clone();
+    // 请求的完整URL，用作缓存的键
+    fn resolve_url(url: &str, options: &RequestOptions) -> Result<String, String> {
+        match &options.session {
+            Some(session) => {
+                let sessions = lock_sessions();
+                let session = sessions.parse_handle(session)
+                    .and_then(|id| sessions.get(id))
+                    .ok_or_else(|| "错误: 无效的会话句柄".to_string())?;
+                join_base(session.base_url.as_ref(), url)
+            },
+            None => Ok(url.to_string()),
+        }
+    }
+
+    // 相对URL接在 base_url 后面
+    fn join_base(base: Option<&Url>, url: &str) -> Result<String, String> {
+        match base {
+            Some(base) if Url::parse(url).is_err() => base.join(url.trim_start_matches('/'))
+                .map(|url| url.to_string())
+                .map_err(|e| format!("错误: 无效的URL {}: {}", url, e)),
+            _ => Ok(url.to_string()),
+        }
+    }
+
+    // 发送请求；使用会话时用会话的客户端，并把相对URL接在会话的 base_url 后面
+    fn send(method: &str, url: &str, mut headers: HeaderMap, payload: &Payload, options: &RequestOptions) -> Result<Response, String> {
+        let method = Method::from_str(&method.to_uppercase()).map_err(|_| format!("错误: 不支持的HTTP方法 '{}'", method))?;
+        let mut auth = options.auth.clone();
+        let (client, url) = match &options.session {
+            Some(session) => {
+                let mut sessions = lock_sessions();
+                let session = sessions.parse_handle(session)
+                    .and_then(|id| sessions.get_mut(id))
+                    .ok_or_else(|| "错误: 无效的会话句柄".to_string())?;
+                if auth.is_none() {
+                    auth = match &mut session.auth {
+                        Some(Auth::Header(header)) => Some(header.clone()),
+                        Some(Auth::OAuth2(oauth)) => Some(oauth.header(&session.client)?),
+                        None => None,
+                    };
+                }
+                (session.client.clone(), join_base(session.base_url.as_ref(), url)?)
+            },
+            None => match &options.client {
+                Some(client) => (client.clone(), url.to_string()),
+                None => (DEFAULT_CLIENT.get_or_init(Client::new).clone(), url.to_string()),
+            },
+        };
+        // 请求头中已经有 Authorization 时不覆盖
+        if let Some(auth) = auth {
+            if !headers.contains_key(reqwest::header::AUTHORIZATION) {
+                let value = HeaderValue::from_str(&auth).map_err(|_| "错误: 无效的 Authorization 头".to_string())?;
+                headers.insert(reqwest::header::AUTHORIZATION, value);
+            }
+        }
+        // GET 和 HEAD 请求不带请求体
+        let with_body = method != Method::GET && method != Method::HEAD;
+
+        let mut backoff = options.backoff;
+        let mut attempt = 0;
+        loop {
+            let mut request = client.request(method.clone(), &url).headers(headers.clone());
+            if with_body {
+                request = match payload {
+                    Payload::Empty => request,
+                    Payload::Text(body) => request.body(body.clone()),
+                    // 文件内容边读边发送，不整个读入内存
+                    Payload::File(path) => {
+                        let file = File::open(path).map_err(|e| format!("错误: 无法打开文件 {}: {}", path.display(), e))?;
+                        request.body(Body::from(file))
+                    },
+                    Payload::Multipart(fields, files) => {
+                        let mut form = Form::new();
+                        for (name, value) in fields {
+                            form = form.text(name.clone(), value.clone());
+                        }
+                        for (name, path) in files {
+                            form = form.file(name.clone(), path).map_err(|e| format!("错误: 无法打开文件 {}: {}", path.display(), e))?;
+                        }
+                        request.multipart(form)
+                    },
+                };
+            }
+            if let Some(timeout) = options.timeout {
+                request = request.timeout(timeout);
+            }
+
+            let result = request.send();
+            let retry = match &result {
+                Ok(response) => options.retry_on.contains(&response.status().as_u16()),
+                Err(err) => err.is_connect() || err.is_timeout(),
+            };
+            if !retry || attempt >= options.retries {
+                return result.map_err(|err| format!("错误: {}", err));
+            }
+            attempt += 1;
+            thread::sleep(backoff);
+            backoff = backoff.saturating_mul(2);
+        }
+    }
+
+    // 读取响应体直到需要报告进度或下载结束，返回调用进度处理函数的回调请求
+    fn next_progress(mut downloads: MutexGuard<'static, Vec<Download>>) -> String {
+        let download = match downloads.last_mut() {
+            Some(download) => download,
+            None => return "错误: 没有正在进行的下载".to_string(),
+        };
+        let started = Instant::now();
+        let mut buffer = vec![0; CHUNK_SIZE];
+        while started.elapsed() < PROGRESS_INTERVAL {
+            match download.response.read(&mut buffer) {
+                Ok(0) => {
+                    download.finished = true;
+                    break;
+                },
+                Ok(n) => {
+                    if let Err(e) = download.file.write_all(&buffer[..n]) {
+                        let download = downloads.pop().expect("下载存在");
+                        return abort_download(download, &format!("错误: 无法写入文件: {}", e));
+                    }
+                    download.downloaded += n as u64;
+                },
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+                Err(e) => {
+                    let download = downloads.pop().expect("下载存在");
+                    return abort_download(download, &format!("错误: 下载中断: {}", e));
+                },
+            }
+        }
+        let args_json = JsonValue::Array(vec![JsonValue::from(download.downloaded), JsonValue::from(download.total)]).to_string();
+        callback_value(&download.handler, &args_json, DOWNLOAD_RESUME_FUNCTION)
+    }
+
+    fn finish_download(mut download: Download) -> String {
+        if let Err(e) = download.file.flush() {
+            return abort_download(download, &format!("错误: 无法写入文件: {}", e));
+        }
+        let mut result = Map::new();
+        result.insert("status".to_string(), JsonValue::from(download.status));
+        result.insert("path".to_string(), JsonValue::String(download.path.to_string_lossy().into_owned()));
+        result.insert("bytes".to_string(), JsonValue::from(download.downloaded));
+        json_value(&JsonValue::Object(result).to_string())
+    }
+
+    // 放弃下载并删除不完整的文件
+    fn abort_download(download: Download, error: &str) -> String {
+        let path = download.path.clone();
+        drop(download);
+        let _ = fs::remove_file(&path);
+        error.to_string()
+    }
+
+    // 选项 Map 中请求选项以外的设置
+    fn parse_extra_options(arg: Option<&String>) -> Result<Map<String, JsonValue>, String> {
+        match arg.map(|a| a.trim()).filter(|a| !a.is_empty()) {
+            Some(arg) => match serde_json::from_str::<JsonValue>(arg) {
+                Ok(JsonValue::Object(map)) => Ok(map),
+                // 会话句柄
+                Ok(JsonValue::Number(_)) => Ok(Map::new()),
+                _ => Err("错误: 选项必须是 Map 或会话句柄".to_string()),
+            },
+            None => Ok(Map::new()),
+        }
+    }
+
+    fn string_map(text: &str, name: &str) -> Result<Vec<(String, String)>, String> {
+        match serde_json::from_str::<JsonValue>(text) {
+            Ok(JsonValue::Object(map)) => Ok(map.into_iter()
+                .map(|(key, value)| match value {
+                    JsonValue::String(value) => (key, value),
+                    other => (key, other.to_string()),
+                })
+                .collect()),
+            _ => Err(format!("错误: {}必须是 Map", name)),
+        }
+    }
+
+    // 解析头信息，可以是JSON对象，也可以是 "key1:value1;key2:value2" 形式的字符串
+    fn parse_headers(text: &str) -> HeaderMap {
+        let pairs: Vec<(String, String)> = match serde_json::from_str::<JsonValue>(text) {
+            Ok(JsonValue::Object(map)) => map.into_iter()
+                .map(|(name, value)| match value {
+                    JsonValue::String(value) => (name, value),
+                    other => (name, other.to_string()),
+                })
+                .collect(),
+            _ => text.split(';')
+                .filter_map(|pair| pair.split_once(':'))
+                .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                .collect(),
+        };
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            if let (Ok(name), Ok(val)) = (HeaderName::from_str(&name), HeaderValue::from_str(&value)) {
+                headers.insert(name, val);
+            }
+        }
+        headers
+    }
     
-    match response.text() {
-        Ok(body) => {
-            let mut result = format!("状态码: {}\n", status);
-            
-            // 添加头信息
-            result.push_str("头信息:\n");
-            for (name, value) in headers.iter() {
-                if let Ok(val_str) = value.to_str() {
-                    result.push_str(&format!("{}: {}\n", name, val_str));
+    // 获取响应的状态码
+    // 参数: response
+    pub fn cn_status(args: Vec<String>) -> String {
+        match response_arg(&args) {
+            Ok(response) => response.get("status").map(|s| s.to_string()).unwrap_or_else(|| "0".to_string()),
+            Err(e) => e,
+        }
+    }
+
+    // 获取响应体
+    // 参数: response
+    pub fn cn_body(args: Vec<String>) -> String {
+        match response_arg(&args) {
+            Ok(response) => response.get("body").and_then(|b| b.as_str()).unwrap_or_default().to_string(),
+            Err(e) => e,
+        }
+    }
+
+    // 获取响应头，名称不区分大小写，不存在时返回空值
+    // 参数: response, name
+    pub fn cn_header(args: Vec<String>) -> String {
+        let response = match response_arg(&args) {
+            Ok(response) => response,
+            Err(e) => return e,
+        };
+        let name = match args.get(1) {
+            Some(name) => name.to_lowercase(),
+            None => return "错误: 请提供头名称".to_string(),
+        };
+        match response.get("headers").and_then(|h| h.get(&name)).and_then(|v| v.as_str()) {
+            Some(value) => value.to_string(),
+            None => json_value("null"),
+        }
+    }
+
+    // 把HTTP响应转换为响应 Map，同名的头信息以 ", " 连接
+    fn format_response(response: Response, mode: &BodyMode) -> String {
+        match read_response(response) {
+            Ok(fetched) => json_value(&JsonValue::Object(response_map(fetched, mode)).to_string()),
+            Err(e) => e,
+        }
+    }
+
+    fn read_response(response: Response) -> Result<Fetched, String> {
+        let status = response.status().as_u16();
+        let mut headers = Map::new();
+        for (name, value) in response.headers() {
+            let value = String::from_utf8_lossy(value.as_bytes()).into_owned();
+            match headers.get_mut(name.as_str()) {
+                Some(JsonValue::String(existing)) => {
+                    existing.push_str(", ");
+                    existing.push_str(&value);
+                },
+                _ => {
+                    headers.insert(name.as_str().to_string(), JsonValue::String(value));
+                },
+            }
+        }
+
+        let body = response.bytes().map_err(|err| format!("错误: 读取响应体时出错（状态码 {}）: {}", status, err))?;
+        Ok(Fetched { status, headers, body: body.to_vec() })
+    }
+
+    fn response_map(fetched: Fetched, mode: &BodyMode) -> Map<String, JsonValue> {
+        let Fetched { status, headers, body: bytes } = fetched;
+        let content_type = headers.get("content-type").and_then(|c| c.as_str()).unwrap_or("");
+        let body = match mode {
+            BodyMode::Hex => bytes.iter().map(|b| format!("{:02x}", b)).collect(),
+            BodyMode::Base64 => STANDARD.encode(&bytes),
+            BodyMode::Text(charset) => {
+                let encoding = charset.unwrap_or_else(|| detect_charset(content_type, &bytes));
+                // 以BOM开头时按BOM表示的编码解码
+                encoding.decode(&bytes).0.into_owned()
+            },
+        };
+        let mut result = Map::new();
+        result.insert("status".to_string(), JsonValue::from(status));
+        result.insert("headers".to_string(), JsonValue::Object(headers));
+        result.insert("body".to_string(), JsonValue::String(body));
+        result
+    }
+
+    fn default_cache_dir() -> PathBuf {
+        env::var_os(CACHE_DIR_ENV).map(PathBuf::from).unwrap_or_else(|| env::temp_dir().join("cn_http_cache"))
+    }
+
+    fn cache_path(dir: &Path, url: &str) -> PathBuf {
+        let mut hasher = DefaultHasher::new();
+        url.hash(&mut hasher);
+        dir.join(format!("{:016x}.json", hasher.finish()))
+    }
+
+    fn load_cached(path: &Path) -> Option<Fetched> {
+        let entry = serde_json::from_str::<JsonValue>(&fs::read_to_string(path).ok()?).ok()?;
+        Some(Fetched {
+            status: entry.get("status")?.as_u64().and_then(|s| u16::try_from(s).ok())?,
+            headers: entry.get("headers")?.as_object()?.clone(),
+            body: STANDARD.decode(entry.get("body")?.as_str()?).ok()?,
+        })
+    }
+
+    // 缓存带验证信息的响应；缓存只是优化，写入失败时忽略
+    fn store_cached(path: &Path, fetched: &Fetched) {
+        let header = |name: &str| fetched.headers.get(name).and_then(|v| v.as_str()).unwrap_or("");
+        if header("etag").is_empty() && header("last-modified").is_empty() {
+            return;
+        }
+        if header("cache-control").to_ascii_lowercase().contains("no-store") {
+            let _ = fs::remove_file(path);
+            return;
+        }
+        let mut entry = Map::new();
+        entry.insert("status".to_string(), JsonValue::from(fetched.status));
+        entry.insert("headers".to_string(), JsonValue::Object(fetched.headers.clone()));
+        entry.insert("body".to_string(), JsonValue::String(STANDARD.encode(&fetched.body)));
+        // 先写临时文件再改名，避免留下不完整的缓存
+        let temp = path.with_extension("tmp");
+        let written = path.parent().map(fs::create_dir_all).unwrap_or(Ok(()))
+            .and_then(|_| fs::write(&temp, JsonValue::Object(entry).to_string()))
+            .and_then(|_| fs::rename(&temp, path));
+        if written.is_err() {
+            let _ = fs::remove_file(&temp);
+        }
+    }
+
+    // 发送JSON请求并解析JSON响应
+    // 状态码不是2xx时返回错误 Map: {"error": 错误信息, "status", "headers", "body"}，body 能解析为JSON时为解析后的值
+    fn execute_json(method: &str, url: &str, body: Option<&String>, options: Option<&String>) -> String {
+        let options = match parse_request_options(options) {
+            Ok(options) => options,
+            Err(e) => return e,
+        };
+        let mut headers = HeaderMap::new();
+        headers.insert(reqwest::header::ACCEPT, HeaderValue::from_static("application/json"));
+        let payload = match body {
+            Some(body) => {
+                headers.insert(reqwest::header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+                // 不是有效JSON的字符串作为JSON字符串发送
+                match serde_json::from_str::<JsonValue>(body) {
+                    Ok(_) => Payload::Text(body.clone()),
+                    Err(_) => Payload::Text(JsonValue::String(body.clone()).to_string()),
                 }
+            },
+            None => Payload::Empty,
+        };
+        let fetched = match fetch(method, url, headers, &payload, &options) {
+            Ok(fetched) => fetched,
+            Err(e) => return e,
+        };
+        let status = reqwest::StatusCode::from_u16(fetched.status).ok();
+        let reason = status.and_then(|s| s.canonical_reason()).unwrap_or("");
+        let success = status.is_some_and(|s| s.is_success());
+        let mut result = response_map(fetched, &options.body);
+        let text = result.get("body").and_then(|b| b.as_str()).unwrap_or("").to_string();
+        let parsed = serde_json::from_str::<JsonValue>(&text);
+
+        if !success {
+            let status = result.get("status").cloned().unwrap_or(JsonValue::Null);
+            result.insert("error".to_string(), JsonValue::String(format!("错误: HTTP {} {}", status, reason).trim_end().to_string()));
+            if let Ok(parsed) = parsed {
+                result.insert("body".to_string(), parsed);
+            }
+            return json_value(&JsonValue::Object(result).to_string());
+        }
+        match parsed {
+            Ok(parsed) => json_value(&parsed.to_string()),
+            // 204 等没有响应体的成功响应返回空值
+            Err(_) if text.trim().is_empty() => json_value("null"),
+            Err(e) => format!("错误: 响应不是有效的JSON: {}", e),
+        }
+    }
+
+    // 按 Content-Type 头中的 charset 确定响应体的编码；HTML 没有指定时查找前1024字节中的 <meta charset>，默认为 UTF-8
+    fn detect_charset(content_type: &str, bytes: &[u8]) -> &'static Encoding {
+        let label_after = |text: &str| -> Option<&'static Encoding> {
+            let start = text.find("charset=")? + "charset=".len();
+            let label: String = text[start..].trim_start_matches(['"', '\'']).chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+                .collect();
+            Encoding::for_label(label.as_bytes())
+        };
+        let content_type = content_type.to_ascii_lowercase();
+        if let Some(encoding) = label_after(&content_type) {
+            return encoding;
+        }
+        if content_type.contains("html") {
+            let head = String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]).to_ascii_lowercase();
+            if let Some(encoding) = head.find("<meta").and_then(|meta| label_after(&head[meta..])) {
+                return encoding;
             }
-            
-            // 添加响应体
-            result.push_str("\n响应体:\n");
-            result.push_str(&body);
-            
-            result
-        },
-        Err(err) => format!("状态码: {}\n读取响应体时出错: {}", status, err)
+        }
+        UTF_8
+    }
+
+    fn response_arg(args: &[String]) -> Result<Map<String, JsonValue>, String> {
+        match args.first().map(|arg| serde_json::from_str::<JsonValue>(arg)) {
+            Some(Ok(JsonValue::Object(response))) if response.contains_key("status") => Ok(response),
+            _ => Err("错误: 参数必须是请求函数返回的响应".to_string()),
+        }
+    }
+}
+
+// URL命名空间
+// parse 把URL拆分为各部分，build 由各部分组成URL，查询参数与 Map 互相转换；
+// 查询参数中重复的键对应数组
+mod url {
+    use super::*;
+
+    // 编码URL组成部分时保留的字符（RFC 3986 的非保留字符）
+    const COMPONENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+
+    // 解析URL，返回 {"scheme", "username", "password", "host", "port", "path", "query", "params", "fragment", "origin"}
+    // port 没有写明时为协议的默认端口，params 是解析后的查询参数 Map
+    // 参数: url
+    pub fn cn_parse(args: Vec<String>) -> String {
+        let url = match args.first().map(|u| Url::parse(u.trim())) {
+            Some(Ok(url)) => url,
+            Some(Err(e)) => return format!("错误: 无效的URL {}: {}", args[0], e),
+            None => return "错误: 未提供URL".to_string(),
+        };
+        let optional = |value: Option<&str>| value.map(|v| JsonValue::String(v.to_string())).unwrap_or(JsonValue::Null);
+        let mut result = Map::new();
+        result.insert("scheme".to_string(), JsonValue::String(url.scheme().to_string()));
+        result.insert("username".to_string(), optional(Some(url.username()).filter(|u| !u.is_empty())));
+        result.insert("password".to_string(), optional(url.password()));
+        result.insert("host".to_string(), optional(url.host_str()));
+        result.insert("port".to_string(), url.port_or_known_default().map(JsonValue::from).unwrap_or(JsonValue::Null));
+        result.insert("path".to_string(), JsonValue::String(url.path().to_string()));
+        result.insert("query".to_string(), optional(url.query()));
+        result.insert("params".to_string(), JsonValue::Object(query_map(url.query().unwrap_or(""))));
+        result.insert("fragment".to_string(), optional(url.fragment()));
+        result.insert("origin".to_string(), JsonValue::String(url.origin().ascii_serialization()));
+        json_value(&JsonValue::Object(result).to_string())
+    }
+
+    // 由各部分组成URL，parts 的键与 parse 的结果相同，至少需要 host；
+    // scheme 默认为 https，query 可以是字符串或 Map，也可以用 params 指定查询参数 Map
+    // 参数: parts
+    pub fn cn_build(args: Vec<String>) -> String {
+        let parts = match args.first().map(|p| serde_json::from_str::<JsonValue>(p)) {
+            Some(Ok(JsonValue::Object(parts))) => parts,
+            _ => return "错误: 参数必须是URL各部分组成的 Map".to_string(),
+        };
+        let text = |name: &str| parts.get(name).and_then(|v| v.as_str()).filter(|v| !v.is_empty());
+        let host = match text("host") {
+            Some(host) => host,
+            None => return "错误: 缺少 host".to_string(),
+        };
+        let mut url = match Url::parse(&format!("{}://{}", text("scheme").unwrap_or("https"), host)) {
+            Ok(url) => url,
+            Err(e) => return format!("错误: 无效的URL: {}", e),
+        };
+
+        let result = (|| {
+            if let Some(port) = parts.get("port").filter(|p| !p.is_null()) {
+                let port = port.as_u64().and_then(|p| u16::try_from(p).ok()).ok_or_else(|| format!("错误: 无效的端口: {}", port))?;
+                url.set_port(Some(port)).map_err(|_| "错误: 这个URL不能设置端口".to_string())?;
+            }
+            if let Some(username) = text("username") {
+                url.set_username(username).map_err(|_| "错误: 这个URL不能设置用户名".to_string())?;
+            }
+            if let Some(password) = text("password") {
+                url.set_password(Some(password)).map_err(|_| "错误: 这个URL不能设置密码".to_string())?;
+            }
+            if let Some(path) = text("path") {
+                url.set_path(path);
+            }
+            match parts.get("params").or_else(|| parts.get("query")) {
+                Some(JsonValue::Object(params)) => {
+                    let query = build_query(params);
+                    url.set_query(Some(query.as_str()).filter(|q| !q.is_empty()));
+                },
+                Some(JsonValue::String(query)) if !query.is_empty() => url.set_query(Some(query.trim_start_matches('?'))),
+                _ => {},
+            }
+            if let Some(fragment) = text("fragment") {
+                url.set_fragment(Some(fragment.trim_start_matches('#')));
+            }
+            Ok(url.to_string())
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 把相对URL解析为相对于 base 的绝对URL
+    // 参数: base, relative
+    pub fn cn_join(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供基础URL和相对URL".to_string();
+        }
+        match Url::parse(args[0].trim()).and_then(|base| base.join(args[1].trim())) {
+            Ok(url) => url.to_string(),
+            Err(e) => format!("错误: 无法合并URL: {}", e),
+        }
+    }
+
+    // 把查询字符串解析为 Map，开头的 ? 可以省略
+    // 参数: query
+    pub fn cn_query_parse(args: Vec<String>) -> String {
+        let query = args.first().map(|q| q.trim().trim_start_matches('?')).unwrap_or("");
+        json_value(&JsonValue::Object(query_map(query)).to_string())
+    }
+
+    // 由 Map 生成查询字符串（不带 ?），数组值生成重复的键
+    // 参数: params
+    pub fn cn_query_build(args: Vec<String>) -> String {
+        match args.first().map(|p| serde_json::from_str::<JsonValue>(p)) {
+            Some(Ok(JsonValue::Object(params))) => build_query(&params),
+            _ => "错误: 参数必须是 Map".to_string(),
+        }
+    }
+
+    // 对URL的组成部分进行百分号编码，只保留字母、数字和 - _ . ~
+    // 参数: text
+    pub fn cn_encode(args: Vec<String>) -> String {
+        args.first().map(|text| utf8_percent_encode(text, COMPONENT).to_string()).unwrap_or_default()
+    }
+
+    // 解码百分号编码，+ 保持不变（查询字符串请用 query_parse）
+    // 参数: text
+    pub fn cn_decode(args: Vec<String>) -> String {
+        args.first().map(|text| percent_decode_str(text).decode_utf8_lossy().into_owned()).unwrap_or_default()
+    }
+
+    fn query_map(query: &str) -> Map<String, JsonValue> {
+        let mut params = Map::new();
+        for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+            let value = JsonValue::String(value.into_owned());
+            match params.get_mut(key.as_ref()) {
+                Some(JsonValue::Array(values)) => values.push(value),
+                Some(existing) => *existing = JsonValue::Array(vec![existing.take(), value]),
+                None => {
+                    params.insert(key.into_owned(), value);
+                },
+            }
+        }
+        params
+    }
+
+    fn build_query(params: &Map<String, JsonValue>) -> String {
+        let text = |value: &JsonValue| match value {
+            JsonValue::String(s) => s.clone(),
+            JsonValue::Null => String::new(),
+            other => other.to_string(),
+        };
+        let mut serializer = form_urlencoded::Serializer::new(String::new());
+        for (key, value) in params {
+            match value {
+                JsonValue::Array(values) => {
+                    for value in values {
+                        serializer.append_pair(key, &text(value));
+                    }
+                },
+                value => {
+                    serializer.append_pair(key, &text(value));
+                },
+            }
+        }
+        serializer.finish()
     }
 }
 
@@ -188,13 +1372,38 @@ pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
     // 注册HTTP命名空间下的函数
     let http_ns = registry.namespace("http");
     http_ns.add_function("get", http::cn_get)
+           .add_function("get_all", http::cn_get_all)
            .add_function("post", http::cn_post)
+           .add_function("get_json", http::cn_get_json)
+           .add_function("post_json", http::cn_post_json)
            .add_function("put", http::cn_put)
            .add_function("delete", http::cn_delete)
            .add_function("request", http::cn_request)
-           .add_function("encode_url", http::cn_encode_url)
-           .add_function("decode_url", http::cn_decode_url);
-           
+           .add_function("status", http::cn_status)
+           .add_function("body", http::cn_body)
+           .add_function("header", http::cn_header)
+           .add_function("download", http::cn_download)
+           .add_continuation("download_resume", http::cn_download_resume)
+           .add_function("upload", http::cn_upload)
+           .add_function("multipart", http::cn_multipart)
+           .add_function("session_create", http::cn_session_create)
+           .add_function("session_close", http::cn_session_close)
+           .add_function("session_auth", http::cn_session_auth)
+           .add_function("basic_auth", http::cn_basic_auth)
+           .add_function("bearer_auth", http::cn_bearer_auth)
+           .add_function("oauth2", http::cn_oauth2)
+           .add_function("cache_clear", http::cn_cache_clear);
+
+    // 注册URL命名空间下的函数
+    let url_ns = registry.namespace("url");
+    url_ns.add_function("parse", url::cn_parse)
+          .add_function("build", url::cn_build)
+          .add_function("join", url::cn_join)
+          .add_function("query_parse", url::cn_query_parse)
+          .add_function("query_build", url::cn_query_build)
+          .add_function("encode", url::cn_encode)
+          .add_function("decode", url::cn_decode);
+
     // 构建并返回库指针
     registry.build_library_pointer()
 } 
\ No newline at end of file
@@ -0,0 +1,317 @@
+use ::std::collections::HashMap;
+use percent_encoding::percent_decode_str;
+use serde_json::{Map, Value as JsonValue};
+use std::io::Cursor;
+use std::sync::{Mutex, MutexGuard};
+use tiny_http::{Header, Request, Response, Server};
+
+// 导入通用库
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::{callback_value, returned_json, thrown_message};
+
+// HTTP服务器命名空间
+// route 注册处理函数，listen 开始处理请求，直到某个处理函数调用 stop 为止
+// 每个请求以 Map 传给处理函数：
+// {"method", "path", "query": {参数}, "params": {路径参数}, "headers": {小写头名}, "body", "remote_addr"}
+// 处理函数返回字符串时作为响应体；返回 Map 且包含 status、headers 或 body 时按字段构造响应，
+// status 不是 100 到 599 之间的整数时以 500 响应；返回其他 Map 或数组时以JSON响应；
+// 处理函数抛出未捕获的异常时以 500 响应
+mod server {
+    use super::*;
+
+    // 处理函数返回后，解释器调用的续接函数
+    const RESUME_FUNCTION: &str = "server::resume";
+
+    struct Route {
+        method: String,
+        segments: Vec<String>,
+        handler: String,
+    }
+
+    struct State {
+        server: Option<Server>,
+        // 正在由处理函数处理的请求
+        pending: Option<Request>,
+        stopping: bool,
+    }
+
+    static ROUTES: Mutex<Vec<Route>> = Mutex::new(Vec::new());
+    static STATE: Mutex<State> = Mutex::new(State { server: None, pending: None, stopping: false });
+
+    fn lock_routes() -> MutexGuard<'static, Vec<Route>> {
+        ROUTES.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_state() -> MutexGuard<'static, State> {
+        STATE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // 注册路由，同一方法和路径重复注册时替换处理函数
+    // 路径中 :name 匹配一段并保存到 params，末尾的 * 匹配剩余部分并保存到 params["*"]
+    // 参数: method（GET、POST 等，* 表示任意方法）, path, handler（接收请求 Map 的函数）
+    pub fn cn_route(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "错误: 请提供方法、路径和处理函数".to_string();
+        }
+        let method = args[0].trim().to_uppercase();
+        let segments = split_path(&args[1]).into_iter().map(|s| s.to_string()).collect::<Vec<String>>();
+        if let Some(pos) = segments.iter().position(|s| s == "*") {
+            if pos != segments.len() - 1 {
+                return "错误: 通配符 * 只能出现在路径末尾".to_string();
+            }
+        }
+
+        let mut routes = lock_routes();
+        routes.retain(|r| !(r.method == method && r.segments == segments));
+        routes.push(Route { method, segments, handler: args[2].clone() });
+        "true".to_string()
+    }
+
+    // 在指定端口上监听并处理请求，直到处理函数调用 stop；期间脚本的其他代码不会执行
+    // 参数: port, [host]（默认 0.0.0.0）
+    pub fn cn_listen(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供端口".to_string();
+        }
+        let port = match args[0].trim().parse::<u16>() {
+            Ok(port) => port,
+            Err(_) => return format!("错误: 无效的端口: {}", args[0]),
+        };
+        let host = args.get(1).map(|h| h.trim()).unwrap_or("0.0.0.0");
+
+        let mut state = lock_state();
+        if state.server.is_some() {
+            return "错误: 服务器已经在运行".to_string();
+        }
+        match Server::http((host, port)) {
+            Ok(server) => state.server = Some(server),
+            Err(e) => return format!("错误: 无法监听 {}:{}: {}", host, port, e),
+        }
+        state.stopping = false;
+        next_request(&mut state)
+    }
+
+    // 处理函数返回后由解释器调用，发送响应并等待下一个请求；以续接函数注册，脚本无法直接调用
+    // 参数: response
+    pub fn cn_resume(args: Vec<String>) -> String {
+        let mut state = lock_state();
+        if let Some(request) = state.pending.take() {
+            let _ = request.respond(build_response(args.first().map(|s| s.as_str()).unwrap_or("")));
+        }
+        if state.stopping {
+            state.server = None;
+            state.stopping = false;
+            return "true".to_string();
+        }
+        next_request(&mut state)
+    }
+
+    // 在当前请求的响应发送后停止服务器，listen 随后返回
+    // 参数: 无
+    pub fn cn_stop(_args: Vec<String>) -> String {
+        let mut state = lock_state();
+        if state.server.is_none() {
+            return "false".to_string();
+        }
+        state.stopping = true;
+        "true".to_string()
+    }
+
+    // 等待下一个匹配路由的请求，返回调用处理函数的回调请求；没有匹配的请求直接以 404 或 405 响应
+    fn next_request(state: &mut State) -> String {
+        loop {
+            let received = match &state.server {
+                Some(server) => server.recv(),
+                None => return "true".to_string(),
+            };
+            let mut request = match received {
+                Ok(request) => request,
+                Err(e) => {
+                    state.server = None;
+                    return format!("错误: 接收请求失败: {}", e);
+                },
+            };
+
+            let (path, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+            let path = path.to_string();
+            let query = query.to_string();
+            let method = request.method().as_str().to_uppercase();
+
+            let matched = match_route(&method, &path);
+            let (handler, params) = match matched {
+                RouteMatch::Found(handler, params) => (handler, params),
+                RouteMatch::MethodNotAllowed => {
+                    let _ = request.respond(Response::from_string("405 Method Not Allowed").with_status_code(405));
+                    continue;
+                },
+                RouteMatch::NotFound => {
+                    let _ = request.respond(Response::from_string("404 Not Found").with_status_code(404));
+                    continue;
+                },
+            };
+
+            let mut body = Vec::new();
+            let _ = request.as_reader().read_to_end(&mut body);
+
+            let mut headers = Map::new();
+            for header in request.headers() {
+                let name = header.field.as_str().as_str().to_lowercase();
+                headers.insert(name, JsonValue::String(header.value.as_str().to_string()));
+            }
+
+            let mut obj = Map::new();
+            obj.insert("method".to_string(), JsonValue::String(method));
+            obj.insert("path".to_string(), JsonValue::String(decode(&path)));
+            obj.insert("query".to_string(), JsonValue::Object(parse_query(&query)));
+            obj.insert("params".to_string(), JsonValue::Object(params));
+            obj.insert("headers".to_string(), JsonValue::Object(headers));
+            obj.insert("body".to_string(), JsonValue::String(String::from_utf8_lossy(&body).into_owned()));
+            obj.insert(
+                "remote_addr".to_string(),
+                JsonValue::String(request.remote_addr().map(|a| a.to_string()).unwrap_or_default()),
+            );
+
+            state.pending = Some(request);
+            let args_json = JsonValue::Array(vec![JsonValue::Object(obj)]).to_string();
+            return callback_value(&handler, &args_json, RESUME_FUNCTION);
+        }
+    }
+
+    enum RouteMatch {
+        Found(String, Map<String, JsonValue>),
+        MethodNotAllowed,
+        NotFound,
+    }
+
+    // 按注册顺序查找第一个匹配的路由
+    fn match_route(method: &str, path: &str) -> RouteMatch {
+        let parts = split_path(path);
+        let mut path_matched = false;
+        for route in lock_routes().iter() {
+            let params = match match_segments(&route.segments, &parts) {
+                Some(params) => params,
+                None => continue,
+            };
+            if route.method == "*" || route.method == method || (route.method == "GET" && method == "HEAD") {
+                return RouteMatch::Found(route.handler.clone(), params);
+            }
+            path_matched = true;
+        }
+        if path_matched { RouteMatch::MethodNotAllowed } else { RouteMatch::NotFound }
+    }
+
+    fn match_segments(pattern: &[String], parts: &[&str]) -> Option<Map<String, JsonValue>> {
+        let mut params = Map::new();
+        for (i, segment) in pattern.iter().enumerate() {
+            if segment == "*" {
+                params.insert("*".to_string(), JsonValue::String(decode(&parts[i.min(parts.len())..].join("/"))));
+                return Some(params);
+            }
+            let part = parts.get(i)?;
+            match segment.strip_prefix(':') {
+                Some(name) => {
+                    params.insert(name.to_string(), JsonValue::String(decode(part)));
+                },
+                None if segment == part => {},
+                None => return None,
+            }
+        }
+        if pattern.len() == parts.len() { Some(params) } else { None }
+    }
+
+    fn split_path(path: &str) -> Vec<&str> {
+        path.split('/').filter(|s| !s.is_empty()).collect()
+    }
+
+    fn decode(text: &str) -> String {
+        percent_decode_str(text).decode_utf8_lossy().into_owned()
+    }
+
+    fn parse_query(query: &str) -> Map<String, JsonValue> {
+        query.split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (decode(&key.replace('+', " ")), JsonValue::String(decode(&value.replace('+', " "))))
+            })
+            .collect()
+    }
+
+    // 把处理函数的返回值转换为响应
+    fn build_response(returned: &str) -> Response<Cursor<Vec<u8>>> {
+        if let Some(message) = thrown_message(returned) {
+            eprintln!("错误: 处理函数抛出异常: {}", message);
+            return Response::from_string("500 Internal Server Error").with_status_code(500);
+        }
+        let mut status = 200;
+        let mut headers: Vec<(String, String)> = Vec::new();
+        // 只有返回 Map 或数组时才按JSON处理，内容恰好是JSON的字符串仍作为响应体
+        let body = match returned_json(returned).map(serde_json::from_str::<JsonValue>) {
+            Some(Ok(JsonValue::Object(mut obj))) if ["status", "headers", "body"].iter().any(|k| obj.contains_key(*k)) => {
+                if let Some(code) = obj.get("status") {
+                    status = match code.as_u64().or_else(|| code.as_str()?.trim().parse().ok()) {
+                        Some(code @ 100..=599) => code as u16,
+                        _ => {
+                            eprintln!("错误: 处理函数返回了无效的状态码: {}", code);
+                            return Response::from_string("500 Internal Server Error").with_status_code(500);
+                        },
+                    };
+                }
+                if let Some(JsonValue::Object(map)) = obj.remove("headers") {
+                    for (name, value) in map {
+                        let value = match value {
+                            JsonValue::String(s) => s,
+                            other => other.to_string(),
+                        };
+                        headers.push((name, value));
+                    }
+                }
+                match obj.remove("body") {
+                    Some(JsonValue::String(text)) => text,
+                    Some(JsonValue::Null) | None => String::new(),
+                    Some(other) => {
+                        headers.push(("Content-Type".to_string(), "application/json; charset=utf-8".to_string()));
+                        other.to_string()
+                    },
+                }
+            },
+            Some(Ok(value)) => {
+                headers.push(("Content-Type".to_string(), "application/json; charset=utf-8".to_string()));
+                value.to_string()
+            },
+            Some(Err(_)) | None => returned.to_string(),
+        };
+
+        // 没有指定 Content-Type 时按内容推断
+        if !headers.iter().any(|(name, _)| name.eq_ignore_ascii_case("content-type")) {
+            let content_type = if body.trim_start().starts_with('<') { "text/html; charset=utf-8" } else { "text/plain; charset=utf-8" };
+            headers.push(("Content-Type".to_string(), content_type.to_string()));
+        }
+
+        let mut response = Response::from_string(body).with_status_code(status);
+        for (name, value) in headers {
+            // 忽略无效的头信息
+            if let Ok(header) = Header::from_bytes(name.as_bytes(), value.as_bytes()) {
+                response.add_header(header);
+            }
+        }
+        response
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册HTTP服务器命名空间下的函数
+    let server_ns = registry.namespace("server");
+    server_ns.add_retaining_function("route", server::cn_route)
+             .add_function("listen", server::cn_listen)
+             .add_continuation("resume", server::cn_resume)
+             .add_function("stop", server::cn_stop);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
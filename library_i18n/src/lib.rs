@@ -0,0 +1,573 @@
+use ::std::collections::HashMap;
+use chrono::{DateTime, Local, Locale, NaiveDate, NaiveDateTime, TimeZone};
+use pure_rust_locales::locale_match;
+use serde_json::{Map, Value as JsonValue};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+// 导入通用库
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::json_value;
+
+// 国际化命名空间
+// 消息目录是JSON对象，可以嵌套，嵌套的键以 . 连接（如 "menu.file"）；
+// 消息中的 {name} 由参数替换，值为 {"one": ..., "other": ...} 等复数形式的对象时按 count 参数选择，
+// 也可以用 "=0" 这样的键指定某个数量的专用消息
+// 语言标签使用 zh-CN、en 这样的形式，查找消息时依次尝试 当前语言、其基础语言、后备语言（默认为 en）
+mod i18n {
+    use super::*;
+
+    const PLURAL_CATEGORIES: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
+
+    struct State {
+        locale: Option<String>,
+        fallback: String,
+        catalogs: BTreeMap<String, HashMap<String, JsonValue>>,
+    }
+
+    static STATE: Mutex<State> = Mutex::new(State { locale: None, fallback: String::new(), catalogs: BTreeMap::new() });
+
+    fn lock_state() -> MutexGuard<'static, State> {
+        STATE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // 从JSON文件加载消息目录，返回消息数量；省略语言时使用文件名（如 zh-CN.json）
+    // 参数: path, [locale]
+    pub fn cn_load(args: Vec<String>) -> String {
+        let path = match args.first() {
+            Some(path) => Path::new(path),
+            None => return "错误: 请提供文件路径".to_string(),
+        };
+        let locale = match args.get(1).filter(|l| !l.trim().is_empty()) {
+            Some(locale) => locale.clone(),
+            None => path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default(),
+        };
+        match load_file(path, &locale) {
+            Ok(count) => count.to_string(),
+            Err(e) => e,
+        }
+    }
+
+    // 加载目录中所有 <语言>.json 文件，返回加载的语言数组
+    // 参数: dir
+    pub fn cn_load_dir(args: Vec<String>) -> String {
+        let dir = match args.first() {
+            Some(dir) => dir,
+            None => return "错误: 请提供目录路径".to_string(),
+        };
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => return format!("错误: 无法读取目录 {}: {}", dir, e),
+        };
+        let mut paths: Vec<_> = entries.flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")))
+            .collect();
+        paths.sort();
+
+        let mut locales = Vec::new();
+        for path in paths {
+            let locale = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+            if let Err(e) = load_file(&path, &locale) {
+                return e;
+            }
+            locales.push(JsonValue::String(normalize_tag(&locale)));
+        }
+        json_value(&JsonValue::Array(locales).to_string())
+    }
+
+    // 添加消息，已存在的同名消息被覆盖，返回添加的消息数量
+    // 参数: locale, messages（Map）
+    pub fn cn_add(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供语言和消息".to_string();
+        }
+        match serde_json::from_str::<JsonValue>(&args[1]) {
+            Ok(JsonValue::Object(messages)) => add_messages(&args[0], messages).to_string(),
+            _ => "错误: 消息必须是 Map".to_string(),
+        }
+    }
+
+    // 设置当前语言
+    // 参数: locale
+    pub fn cn_set_locale(args: Vec<String>) -> String {
+        match args.first().filter(|l| !l.trim().is_empty()) {
+            Some(locale) => {
+                lock_state().locale = Some(normalize_tag(locale));
+                "true".to_string()
+            },
+            None => "错误: 请提供语言".to_string(),
+        }
+    }
+
+    // 获取当前语言，没有设置时取自环境变量 LC_ALL、LC_MESSAGES 或 LANG
+    // 参数: 无
+    pub fn cn_get_locale(_args: Vec<String>) -> String {
+        current_locale(&lock_state())
+    }
+
+    // 设置找不到消息时使用的后备语言
+    // 参数: locale
+    pub fn cn_set_fallback(args: Vec<String>) -> String {
+        match args.first().filter(|l| !l.trim().is_empty()) {
+            Some(locale) => {
+                lock_state().fallback = normalize_tag(locale);
+                "true".to_string()
+            },
+            None => "错误: 请提供语言".to_string(),
+        }
+    }
+
+    // 获取已加载消息的语言数组
+    // 参数: 无
+    pub fn cn_locales(_args: Vec<String>) -> String {
+        let locales: Vec<String> = lock_state().catalogs.keys().cloned().collect();
+        json_value(&JsonValue::from(locales).to_string())
+    }
+
+    // 翻译消息，找不到时返回键本身
+    // args 是 Map 时替换消息中的 {name}，其中 count 同时用于选择复数形式；args 是数字时作为 count
+    // 参数: key, [args], [locale]
+    pub fn cn_t(args: Vec<String>) -> String {
+        let key = match args.first() {
+            Some(key) => key,
+            None => return "错误: 请提供消息键".to_string(),
+        };
+        let params = match args.get(1).map(|a| a.trim()).filter(|a| !a.is_empty()) {
+            Some(params) => match serde_json::from_str::<JsonValue>(params) {
+                Ok(JsonValue::Object(params)) => params,
+                Ok(count @ JsonValue::Number(_)) => Map::from_iter([("count".to_string(), count)]),
+                _ => return "错误: 参数必须是 Map 或数字".to_string(),
+            },
+            None => Map::new(),
+        };
+
+        let state = lock_state();
+        let locale = locale_arg(&state, args.get(2));
+        let (found_in, message) = match lookup(&state, &locale, key) {
+            Some((found_in, message)) => (found_in, message.clone()),
+            None => return key.clone(),
+        };
+        drop(state);
+
+        let text = match message {
+            JsonValue::String(text) => text,
+            JsonValue::Object(forms) => {
+                let count = params.get("count").and_then(number_of);
+                select_plural(&forms, count, language(&found_in)).unwrap_or_else(|| key.clone())
+            },
+            other => other.to_string(),
+        };
+        interpolate(&text, &params)
+    }
+
+    // 检查消息是否存在（包括后备语言）
+    // 参数: key, [locale]
+    pub fn cn_has(args: Vec<String>) -> String {
+        let key = match args.first() {
+            Some(key) => key,
+            None => return "false".to_string(),
+        };
+        let state = lock_state();
+        let locale = locale_arg(&state, args.get(1));
+        lookup(&state, &locale, key).is_some().to_string()
+    }
+
+    // 获取数量在语言中的复数类别: zero、one、two、few、many 或 other
+    // 参数: n, [locale]
+    pub fn cn_plural(args: Vec<String>) -> String {
+        let n = match args.first().map(|n| n.trim().parse::<f64>()) {
+            Some(Ok(n)) => n,
+            _ => return "错误: 数量必须是数字".to_string(),
+        };
+        let locale = locale_arg(&lock_state(), args.get(1));
+        plural_category(language(&locale), n).to_string()
+    }
+
+    // 按语言习惯格式化数字（千位分隔符和小数点）
+    // 参数: n, [decimals]（保留的小数位数，默认原样保留）, [locale]
+    pub fn cn_format_number(args: Vec<String>) -> String {
+        let n = match args.first().map(|n| n.trim().parse::<f64>()) {
+            Some(Ok(n)) if n.is_finite() => n,
+            _ => return "错误: 请提供有效的数字".to_string(),
+        };
+        let decimals = match args.get(1).map(|d| d.trim()).filter(|d| !d.is_empty()) {
+            Some(decimals) => match decimals.parse::<usize>() {
+                Ok(decimals) => Some(decimals),
+                Err(_) => return format!("错误: 小数位数必须是非负整数: {}", decimals),
+            },
+            None => None,
+        };
+        let locale = locale_arg(&lock_state(), args.get(2));
+        format_number(n, decimals, system_locale(&locale))
+    }
+
+    // 按语言习惯格式化日期
+    // date 可以是 "YYYY-MM-DD"、"YYYY-MM-DD HH:MM:SS"、RFC 3339 时间或Unix时间戳（秒），省略或为空时为现在
+    // style: short（默认）、medium、long、full、time、datetime，或者 strftime 格式（如 "%A %-d %B"）
+    // 参数: [date], [style], [locale]
+    pub fn cn_format_date(args: Vec<String>) -> String {
+        let time = match args.first().map(|d| d.trim()).filter(|d| !d.is_empty()) {
+            Some(date) => match parse_date(date) {
+                Some(time) => time,
+                None => return format!("错误: 无效的日期: {}", date),
+            },
+            None => Local::now(),
+        };
+        let style = args.get(1).map(|s| s.trim()).filter(|s| !s.is_empty()).unwrap_or("short");
+        let locale = locale_arg(&lock_state(), args.get(2));
+        let pattern = match date_pattern(style, language(&locale)) {
+            Some(pattern) => pattern,
+            None => return format!("错误: 未知的日期样式: {}", style),
+        };
+        time.format_localized(&pattern, system_locale(&locale)).to_string()
+    }
+
+    fn load_file(path: &Path, locale: &str) -> Result<usize, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("错误: 无法读取 {}: {}", path.display(), e))?;
+        match serde_json::from_str::<JsonValue>(&text) {
+            Ok(JsonValue::Object(messages)) => Ok(add_messages(locale, messages)),
+            Ok(_) => Err(format!("错误: 消息目录 {} 必须是JSON对象", path.display())),
+            Err(e) => Err(format!("错误: 无法解析 {}: {}", path.display(), e)),
+        }
+    }
+
+    fn add_messages(locale: &str, messages: Map<String, JsonValue>) -> usize {
+        let mut flat = Vec::new();
+        flatten("", messages, &mut flat);
+        let count = flat.len();
+        lock_state().catalogs.entry(normalize_tag(locale)).or_default().extend(flat);
+        count
+    }
+
+    // 展开嵌套的消息，复数形式的对象作为一条消息保留
+    fn flatten(prefix: &str, messages: Map<String, JsonValue>, out: &mut Vec<(String, JsonValue)>) {
+        for (key, value) in messages {
+            let key = if prefix.is_empty() { key } else { format!("{}.{}", prefix, key) };
+            match value {
+                JsonValue::Object(map) if !is_plural_forms(&map) => flatten(&key, map, out),
+                value => out.push((key, value)),
+            }
+        }
+    }
+
+    fn is_plural_forms(map: &Map<String, JsonValue>) -> bool {
+        map.contains_key("other") && map.keys().all(|k| PLURAL_CATEGORIES.contains(&k.as_str()) || k.starts_with('='))
+    }
+
+    fn lookup<'s>(state: &'s State, locale: &str, key: &str) -> Option<(String, &'s JsonValue)> {
+        let fallback = if state.fallback.is_empty() { "en" } else { state.fallback.as_str() };
+        let candidates = [locale, language(locale), fallback, language(fallback)];
+        candidates.iter().find_map(|candidate| {
+            state.catalogs.get(*candidate)?.get(key).map(|message| (candidate.to_string(), message))
+        })
+    }
+
+    fn select_plural(forms: &Map<String, JsonValue>, count: Option<f64>, lang: &str) -> Option<String> {
+        let text = |value: &JsonValue| value.as_str().map(|s| s.to_string());
+        if let Some(count) = count {
+            let exact = forms.iter().find(|(k, _)| k.strip_prefix('=').and_then(|n| n.parse::<f64>().ok()) == Some(count));
+            if let Some((_, message)) = exact {
+                return text(message);
+            }
+            if let Some(message) = forms.get(plural_category(lang, count)) {
+                return text(message);
+            }
+        }
+        forms.get("other").and_then(text)
+    }
+
+    // 替换消息中的 {name}，没有对应参数的占位符保持不变
+    fn interpolate(text: &str, params: &Map<String, JsonValue>) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find('{') {
+            result.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let value = after.find('}').and_then(|end| params.get(after[..end].trim()).map(|value| (end, value)));
+            match value {
+                Some((end, value)) => {
+                    match value {
+                        JsonValue::String(s) => result.push_str(s),
+                        other => result.push_str(&other.to_string()),
+                    }
+                    rest = &after[end + 1..];
+                },
+                None => {
+                    result.push('{');
+                    rest = after;
+                },
+            }
+        }
+        result.push_str(rest);
+        result
+    }
+
+    fn number_of(value: &JsonValue) -> Option<f64> {
+        match value {
+            JsonValue::Number(n) => n.as_f64(),
+            JsonValue::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    // CLDR 基数复数规则（常用语言，以整数为主）
+    fn plural_category(lang: &str, n: f64) -> &'static str {
+        let integer = n.fract() == 0.0;
+        let i = n.abs().trunc() as u64;
+        match lang {
+            "zh" | "ja" | "ko" | "vi" | "th" | "id" | "ms" | "my" | "lo" | "km" => "other",
+            "fr" | "hy" => {
+                if i <= 1 {
+                    "one"
+                } else if integer && i % 1_000_000 == 0 {
+                    "many"
+                } else {
+                    "other"
+                }
+            },
+            "pt" => if i <= 1 { "one" } else { "other" },
+            "ru" | "uk" | "be" => {
+                if !integer {
+                    "other"
+                } else if i % 10 == 1 && i % 100 != 11 {
+                    "one"
+                } else if (2..=4).contains(&(i % 10)) && !(12..=14).contains(&(i % 100)) {
+                    "few"
+                } else {
+                    "many"
+                }
+            },
+            "pl" => {
+                if !integer {
+                    "other"
+                } else if i == 1 {
+                    "one"
+                } else if (2..=4).contains(&(i % 10)) && !(12..=14).contains(&(i % 100)) {
+                    "few"
+                } else {
+                    "many"
+                }
+            },
+            "cs" | "sk" => {
+                if !integer {
+                    "many"
+                } else if i == 1 {
+                    "one"
+                } else if (2..=4).contains(&i) {
+                    "few"
+                } else {
+                    "other"
+                }
+            },
+            "ar" => {
+                if !integer {
+                    "other"
+                } else if i == 0 {
+                    "zero"
+                } else if i == 1 {
+                    "one"
+                } else if i == 2 {
+                    "two"
+                } else if (3..=10).contains(&(i % 100)) {
+                    "few"
+                } else if (11..=99).contains(&(i % 100)) {
+                    "many"
+                } else {
+                    "other"
+                }
+            },
+            "he" => {
+                if integer && i == 1 {
+                    "one"
+                } else if integer && i == 2 {
+                    "two"
+                } else {
+                    "other"
+                }
+            },
+            _ => if integer && i == 1 { "one" } else { "other" },
+        }
+    }
+
+    fn format_number(n: f64, decimals: Option<usize>, locale: Locale) -> String {
+        let text = match decimals {
+            Some(decimals) => format!("{:.*}", decimals, n.abs()),
+            None => n.abs().to_string(),
+        };
+        let (integer, fraction) = match text.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (text.as_str(), None),
+        };
+
+        let separator = locale_match!(locale => LC_NUMERIC::THOUSANDS_SEP);
+        let grouping = locale_match!(locale => LC_NUMERIC::GROUPING);
+        let mut result = group_digits(integer, separator, grouping);
+        if let Some(fraction) = fraction {
+            result.push_str(locale_match!(locale => LC_NUMERIC::DECIMAL_POINT));
+            result.push_str(fraction);
+        }
+        if n < 0.0 && result.chars().any(|c| c.is_ascii_digit() && c != '0') {
+            result.insert(0, '-');
+        }
+        result
+    }
+
+    // 按 POSIX 的 grouping 规则分组：依次为从右往左各组的位数，最后一个值重复使用，0 或负数表示不再分组
+    fn group_digits(digits: &str, separator: &str, grouping: &[i64]) -> String {
+        if separator.is_empty() || grouping.first().map_or(true, |size| *size <= 0) {
+            return digits.to_string();
+        }
+        let mut groups: Vec<&str> = Vec::new();
+        let mut end = digits.len();
+        let mut sizes = grouping.iter();
+        let mut size = 0;
+        while end > 0 {
+            match sizes.next() {
+                Some(next) if *next > 0 => size = *next as usize,
+                Some(_) => size = end,
+                None => {},
+            }
+            let start = end.saturating_sub(size);
+            groups.push(&digits[start..end]);
+            end = start;
+        }
+        groups.reverse();
+        groups.join(separator)
+    }
+
+    fn date_pattern(style: &str, lang: &str) -> Option<String> {
+        if style.contains('%') {
+            return Some(style.to_string());
+        }
+        let pattern = match (style, lang) {
+            ("short", _) => "%x",
+            ("time", _) => "%X",
+            ("datetime", _) => "%x %X",
+            ("medium" | "long", "zh" | "ja") => "%Y年%-m月%-d日",
+            ("full", "zh" | "ja") => "%Y年%-m月%-d日 %A",
+            ("medium" | "long", "ko") => "%Y년 %-m월 %-d일",
+            ("full", "ko") => "%Y년 %-m월 %-d일 %A",
+            ("medium", "en") => "%b %-d, %Y",
+            ("long", "en") => "%B %-d, %Y",
+            ("full", "en") => "%A, %B %-d, %Y",
+            ("medium", "de") => "%-d. %b %Y",
+            ("long", "de") => "%-d. %B %Y",
+            ("full", "de") => "%A, %-d. %B %Y",
+            ("medium" | "long", "es" | "pt") => "%-d de %B de %Y",
+            ("full", "es" | "pt") => "%A, %-d de %B de %Y",
+            ("medium", _) => "%-d %b %Y",
+            ("long", _) => "%-d %B %Y",
+            ("full", _) => "%A %-d %B %Y",
+            _ => return None,
+        };
+        Some(pattern.to_string())
+    }
+
+    fn parse_date(text: &str) -> Option<DateTime<Local>> {
+        if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+            return Some(time.with_timezone(&Local));
+        }
+        if let Ok(seconds) = text.parse::<i64>() {
+            return Local.timestamp_opt(seconds, 0).single();
+        }
+        let naive = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S")
+            .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S"))
+            .ok()
+            .or_else(|| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0))?;
+        Local.from_local_datetime(&naive).earliest()
+    }
+
+    fn current_locale(state: &State) -> String {
+        if let Some(locale) = &state.locale {
+            return locale.clone();
+        }
+        ["LC_ALL", "LC_MESSAGES", "LANG"].iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .map(|value| value.split(['.', '@']).next().unwrap_or_default().to_string())
+            .filter(|value| !value.is_empty() && value != "C" && value != "POSIX")
+            .map(|value| normalize_tag(&value))
+            .unwrap_or_else(|| "en".to_string())
+    }
+
+    fn locale_arg(state: &State, arg: Option<&String>) -> String {
+        match arg.map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            Some(locale) => normalize_tag(locale),
+            None => current_locale(state),
+        }
+    }
+
+    // zh_cn、ZH-CN 等写法统一为 zh-CN
+    fn normalize_tag(tag: &str) -> String {
+        let mut parts = tag.trim().split(['-', '_']);
+        let mut normalized = parts.next().unwrap_or_default().to_lowercase();
+        for part in parts {
+            normalized.push('-');
+            if part.len() == 2 {
+                normalized.push_str(&part.to_uppercase());
+            } else {
+                normalized.push_str(part);
+            }
+        }
+        normalized
+    }
+
+    fn language(tag: &str) -> &str {
+        tag.split('-').next().unwrap_or(tag)
+    }
+
+    // 语言标签对应的系统区域数据，只有语言时使用该语言最常用的地区，找不到时使用 POSIX
+    fn system_locale(tag: &str) -> Locale {
+        if let Ok(locale) = Locale::try_from(tag.replace('-', "_").as_str()) {
+            return locale;
+        }
+        let lang = language(tag);
+        let region = match lang {
+            "en" => "US",
+            "zh" => "CN",
+            "ja" => "JP",
+            "ko" => "KR",
+            "pt" => "BR",
+            "ar" => "SA",
+            "hi" => "IN",
+            "sv" => "SE",
+            "da" => "DK",
+            "cs" => "CZ",
+            "uk" => "UA",
+            "el" => "GR",
+            "he" => "IL",
+            "vi" => "VN",
+            _ => return Locale::try_from(format!("{}_{}", lang, lang.to_uppercase()).as_str()).unwrap_or(Locale::POSIX),
+        };
+        Locale::try_from(format!("{}_{}", lang, region).as_str()).unwrap_or(Locale::POSIX)
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册国际化命名空间下的函数
+    let i18n_ns = registry.namespace("i18n");
+    i18n_ns.add_function("load", i18n::cn_load)
+           .add_function("load_dir", i18n::cn_load_dir)
+           .add_function("add", i18n::cn_add)
+           .add_function("set_locale", i18n::cn_set_locale)
+           .add_function("get_locale", i18n::cn_get_locale)
+           .add_function("set_fallback", i18n::cn_set_fallback)
+           .add_function("locales", i18n::cn_locales)
+           .add_function("t", i18n::cn_t)
+           .add_function("has", i18n::cn_has)
+           .add_function("plural", i18n::cn_plural)
+           .add_function("format_number", i18n::cn_format_number)
+           .add_function("format_date", i18n::cn_format_date);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
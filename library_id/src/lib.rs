@@ -0,0 +1,149 @@
+use ::std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+// 导入通用库
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::json_value;
+
+// ID生成命名空间
+mod id {
+    use super::*;
+
+    // nanoid 的默认字母表，URL安全
+    const NANOID_ALPHABET: &str = "_-0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    const NANOID_LENGTH: usize = 21;
+
+    // 雪花ID: 41位毫秒时间戳 + 10位机器号 + 12位序列号，默认纪元与 Twitter 相同
+    const SNOWFLAKE_EPOCH: u64 = 1288834974657;
+    const WORKER_BITS: u32 = 10;
+    const SEQUENCE_BITS: u32 = 12;
+    const MAX_WORKER: u64 = (1 << WORKER_BITS) - 1;
+    const MAX_SEQUENCE: u64 = (1 << SEQUENCE_BITS) - 1;
+
+    struct Snowflake {
+        last_ms: u64,
+        sequence: u64,
+    }
+
+    static SNOWFLAKE: Mutex<Snowflake> = Mutex::new(Snowflake { last_ms: 0, sequence: 0 });
+
+    fn lock_snowflake() -> MutexGuard<'static, Snowflake> {
+        SNOWFLAKE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // 生成版本4的随机UUID
+    // 参数: 无
+    pub fn cn_uuid4(_args: Vec<String>) -> String {
+        Uuid::new_v4().to_string()
+    }
+
+    // 生成版本7的UUID，以毫秒时间戳开头，按生成顺序排序，适合作为数据库主键
+    // 参数: 无
+    pub fn cn_uuid7(_args: Vec<String>) -> String {
+        Uuid::now_v7().to_string()
+    }
+
+    // 生成 nanoid 随机字符串
+    // 参数: [len]（默认21）, [alphabet]（默认为 A-Za-z0-9_-）
+    pub fn cn_nanoid(args: Vec<String>) -> String {
+        let len = match args.first().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            Some(len) => match len.parse::<usize>() {
+                Ok(len) => len,
+                Err(_) => return format!("错误: 无效的长度: {}", len),
+            },
+            None => NANOID_LENGTH,
+        };
+        let alphabet: Vec<char> = args.get(1).filter(|a| !a.is_empty()).map(|a| a.as_str()).unwrap_or(NANOID_ALPHABET).chars().collect();
+        if alphabet.len() < 2 || alphabet.len() > 256 {
+            return "错误: 字母表必须包含2到256个字符".to_string();
+        }
+
+        // 用掩码取随机字节并拒绝超出范围的值，避免取模偏差
+        let mask = alphabet.len().next_power_of_two() - 1;
+        let mut result = String::with_capacity(len);
+        let mut count = 0;
+        let mut buf = [0u8; 64];
+        while count < len {
+            if getrandom::fill(&mut buf).is_err() {
+                return "错误: 无法读取系统随机源".to_string();
+            }
+            for byte in buf {
+                let index = byte as usize & mask;
+                if index < alphabet.len() {
+                    result.push(alphabet[index]);
+                    count += 1;
+                    if count == len {
+                        break;
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    // 生成64位雪花ID，同一进程内严格递增；结果超出 int 范围，以 long 返回
+    // 参数: [worker_id]（0~1023，默认0）, [epoch]（纪元的毫秒时间戳，默认为 Twitter 纪元）
+    pub fn cn_snowflake(args: Vec<String>) -> String {
+        let worker = match args.first().map(|w| w.trim()).filter(|w| !w.is_empty()) {
+            Some(worker) => match worker.parse::<u64>() {
+                Ok(worker) if worker <= MAX_WORKER => worker,
+                _ => return format!("错误: 机器号必须是0到{}之间的整数", MAX_WORKER),
+            },
+            None => 0,
+        };
+        let epoch = match args.get(1).map(|e| e.trim()).filter(|e| !e.is_empty()) {
+            Some(epoch) => match epoch.parse::<u64>() {
+                Ok(epoch) => epoch,
+                Err(_) => return format!("错误: 无效的纪元: {}", epoch),
+            },
+            None => SNOWFLAKE_EPOCH,
+        };
+
+        let mut state = lock_snowflake();
+        // 时钟回拨时沿用上一次的时间戳，保证ID递增
+        let mut now = now_ms().max(state.last_ms);
+        if now == state.last_ms {
+            state.sequence = (state.sequence + 1) & MAX_SEQUENCE;
+            if state.sequence == 0 {
+                // 同一毫秒内序列号用完，等待下一毫秒
+                while now <= state.last_ms {
+                    std::thread::yield_now();
+                    now = now_ms();
+                }
+            }
+        } else {
+            state.sequence = 0;
+        }
+        state.last_ms = now;
+
+        if now < epoch {
+            return "错误: 当前时间早于纪元".to_string();
+        }
+        let id = ((now - epoch) << (WORKER_BITS + SEQUENCE_BITS)) | (worker << SEQUENCE_BITS) | state.sequence;
+        // 只使用63位，保证结果是非负的整数；通过JSON值返回，避免解释器把大整数转换为浮点数
+        json_value(&(id & i64::MAX as u64).to_string())
+    }
+
+    fn now_ms() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册ID生成命名空间下的函数
+    let id_ns = registry.namespace("id");
+    id_ns.add_function("uuid4", id::cn_uuid4)
+         .add_function("uuid7", id::cn_uuid7)
+         .add_function("nanoid", id::cn_nanoid)
+         .add_function("snowflake", id::cn_snowflake);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
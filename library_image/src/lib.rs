@@ -0,0 +1,378 @@
+use ::std::collections::HashMap;
+use img::codecs::jpeg::JpegEncoder;
+use img::imageops::FilterType;
+use img::{DynamicImage, GenericImage, GenericImageView, ImageFormat, Rgba, RgbaImage};
+use serde_json::{json, Value as JsonValue};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+// 导入通用库
+use cn_common::handle::HandleTable;
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::json_value;
+
+// 图片命名空间
+// open 和 new 返回图片句柄，resize、crop 等操作直接修改句柄对应的图片，需要保留原图时先 copy
+// 坐标以左上角为原点，颜色可以写成 "#rgb"、"#rrggbb"、"#rrggbbaa" 或 [r, g, b] / [r, g, b, a]（0到255）
+// 支持读写 PNG 和 JPEG，保存的格式由扩展名决定
+mod image {
+    use super::*;
+
+    const DEFAULT_JPEG_QUALITY: u8 = 85;
+    // new、resize 和 thumbnail 允许的最大像素数（RGBA约400MB），避免按脚本传入的大小分配过多内存
+    const MAX_PIXELS: u64 = 100_000_000;
+
+    static IMAGES: Mutex<HandleTable<DynamicImage>> = Mutex::new(HandleTable::new());
+
+    fn lock_images() -> MutexGuard<'static, HandleTable<DynamicImage>> {
+        IMAGES.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn with_image<F>(args: &[String], f: F) -> String
+    where
+        F: FnOnce(&mut DynamicImage) -> Result<String, String>,
+    {
+        let mut images = lock_images();
+        match args.first().and_then(|arg| images.parse_handle(arg)).and_then(|id| images.get_mut(id)) {
+            Some(image) => f(image).unwrap_or_else(|e| e),
+            None => "错误: 无效的图片句柄".to_string(),
+        }
+    }
+
+    // 打开图片文件，返回图片句柄
+    // 参数: path
+    pub fn cn_open(args: Vec<String>) -> String {
+        let path = match args.first() {
+            Some(path) => path,
+            None => return "错误: 请提供图片路径".to_string(),
+        };
+        match img::open(path) {
+            Ok(image) => lock_images().insert(image).to_string(),
+            Err(e) => format!("错误: 无法打开图片 {}: {}", path, e),
+        }
+    }
+
+    // 创建指定大小的RGBA图片，返回图片句柄
+    // 参数: width, height, [color]（默认为透明）
+    pub fn cn_new(args: Vec<String>) -> String {
+        let result = (|| {
+            let (width, height) = size_args(&args, 0, "宽度", "高度")?;
+            let color = match args.get(2).filter(|c| !c.trim().is_empty()) {
+                Some(color) => parse_color(color)?,
+                None => Rgba([0, 0, 0, 0]),
+            };
+            let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(width, height, color));
+            Ok(lock_images().insert(image).to_string())
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 复制图片，返回新的图片句柄
+    // 参数: image
+    pub fn cn_copy(args: Vec<String>) -> String {
+        let mut images = lock_images();
+        match args.first().and_then(|arg| images.parse_handle(arg)).and_then(|id| images.get(id)) {
+            Some(image) => {
+                let copy = image.clone();
+                images.insert(copy).to_string()
+            },
+            None => "错误: 无效的图片句柄".to_string(),
+        }
+    }
+
+    // 获取图片大小，返回 {"width", "height"}
+    // 参数: image
+    pub fn cn_size(args: Vec<String>) -> String {
+        with_image(&args, |image| Ok(json_value(&json!({ "width": image.width(), "height": image.height() }).to_string())))
+    }
+
+    // 把图片缩放为指定大小，不保持宽高比
+    // 参数: image, width, height, [filter]（nearest、linear、cubic、lanczos，默认为 lanczos）
+    pub fn cn_resize(args: Vec<String>) -> String {
+        let result = (|| {
+            let (width, height) = size_args(&args, 1, "宽度", "高度")?;
+            let filter = filter_arg(args.get(3))?;
+            Ok(with_image(&args, |image| {
+                *image = image.resize_exact(width, height, filter);
+                Ok("true".to_string())
+            }))
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 按宽高比把图片缩小到不超过指定大小，比指定大小小的图片不放大
+    // 参数: image, max_width, max_height, [filter]
+    pub fn cn_thumbnail(args: Vec<String>) -> String {
+        let result = (|| {
+            let (max_width, max_height) = size_args(&args, 1, "最大宽度", "最大高度")?;
+            let filter = filter_arg(args.get(3))?;
+            Ok(with_image(&args, |image| {
+                if image.width() > max_width || image.height() > max_height {
+                    *image = image.resize(max_width, max_height, filter);
+                }
+                Ok(json_value(&json!({ "width": image.width(), "height": image.height() }).to_string()))
+            }))
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 裁剪图片，区域超出图片时只保留图片内的部分
+    // 参数: image, x, y, width, height
+    pub fn cn_crop(args: Vec<String>) -> String {
+        let result = (|| {
+            let x = coordinate_arg(&args, 1, "x")?;
+            let y = coordinate_arg(&args, 2, "y")?;
+            let width = dimension_arg(&args, 3, "宽度")?;
+            let height = dimension_arg(&args, 4, "高度")?;
+            Ok(with_image(&args, |image| {
+                if x >= image.width() || y >= image.height() {
+                    return Err(format!("错误: 裁剪区域在图片之外: ({}, {})", x, y));
+                }
+                *image = image.crop_imm(x, y, width, height);
+                Ok("true".to_string())
+            }))
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 顺时针旋转图片
+    // 参数: image, degrees（90、180 或 270）
+    pub fn cn_rotate(args: Vec<String>) -> String {
+        let degrees = args.get(1).map(|d| d.trim().to_string()).unwrap_or_default();
+        with_image(&args, |image| {
+            *image = match degrees.as_str() {
+                "90" => image.rotate90(),
+                "180" => image.rotate180(),
+                "270" => image.rotate270(),
+                _ => return Err(format!("错误: 只能旋转90、180或270度: {}", degrees)),
+            };
+            Ok("true".to_string())
+        })
+    }
+
+    // 翻转图片
+    // 参数: image, direction（horizontal 或 vertical）
+    pub fn cn_flip(args: Vec<String>) -> String {
+        let direction = args.get(1).map(|d| d.trim().to_lowercase()).unwrap_or_default();
+        with_image(&args, |image| {
+            *image = match direction.as_str() {
+                "horizontal" | "h" => image.fliph(),
+                "vertical" | "v" => image.flipv(),
+                _ => return Err(format!("错误: 未知的翻转方向: {}", direction)),
+            };
+            Ok("true".to_string())
+        })
+    }
+
+    // 转换图片的颜色模式
+    // 参数: image, mode（rgba、rgb、gray 或 gray_alpha）
+    pub fn cn_convert(args: Vec<String>) -> String {
+        let mode = args.get(1).map(|m| m.trim().to_lowercase()).unwrap_or_default();
+        with_image(&args, |image| {
+            *image = match mode.as_str() {
+                "rgba" => DynamicImage::ImageRgba8(image.to_rgba8()),
+                "rgb" => DynamicImage::ImageRgb8(image.to_rgb8()),
+                "gray" | "grey" | "l" => DynamicImage::ImageLuma8(image.to_luma8()),
+                "gray_alpha" | "la" => DynamicImage::ImageLumaA8(image.to_luma_alpha8()),
+                _ => return Err(format!("错误: 未知的颜色模式: {}", mode)),
+            };
+            Ok("true".to_string())
+        })
+    }
+
+    // 获取像素的颜色，返回 [r, g, b, a]
+    // 参数: image, x, y
+    pub fn cn_get_pixel(args: Vec<String>) -> String {
+        let result = (|| {
+            let x = coordinate_arg(&args, 1, "x")?;
+            let y = coordinate_arg(&args, 2, "y")?;
+            Ok(with_image(&args, |image| {
+                if !image.in_bounds(x, y) {
+                    return Err(format!("错误: 像素 ({}, {}) 在图片之外", x, y));
+                }
+                let Rgba(channels) = image.get_pixel(x, y);
+                Ok(json_value(&json!(channels).to_string()))
+            }))
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 设置像素的颜色
+    // 参数: image, x, y, color
+    pub fn cn_set_pixel(args: Vec<String>) -> String {
+        let result = (|| {
+            let x = coordinate_arg(&args, 1, "x")?;
+            let y = coordinate_arg(&args, 2, "y")?;
+            let color = parse_color(args.get(3).ok_or_else(|| "错误: 请提供颜色".to_string())?)?;
+            Ok(with_image(&args, |image| {
+                if !image.in_bounds(x, y) {
+                    return Err(format!("错误: 像素 ({}, {}) 在图片之外", x, y));
+                }
+                image.put_pixel(x, y, color);
+                Ok("true".to_string())
+            }))
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 用颜色填充整张图片或其中的矩形区域，颜色直接替换原有像素（不混合透明度）
+    // 参数: image, color, [x, y, width, height]
+    pub fn cn_fill(args: Vec<String>) -> String {
+        let result = (|| {
+            let color = parse_color(args.get(1).ok_or_else(|| "错误: 请提供颜色".to_string())?)?;
+            let rect = if args.len() > 2 {
+                Some((coordinate_arg(&args, 2, "x")?, coordinate_arg(&args, 3, "y")?, dimension_arg(&args, 4, "宽度")?, dimension_arg(&args, 5, "高度")?))
+            } else {
+                None
+            };
+            Ok(with_image(&args, |image| {
+                let (x, y, width, height) = rect.unwrap_or((0, 0, image.width(), image.height()));
+                let x_end = x.saturating_add(width).min(image.width());
+                let y_end = y.saturating_add(height).min(image.height());
+                for py in y..y_end {
+                    for px in x..x_end {
+                        image.put_pixel(px, py, color);
+                    }
+                }
+                Ok("true".to_string())
+            }))
+        })();
+        result.unwrap_or_else(|e: String| e)
+    }
+
+    // 保存图片，格式由扩展名决定（.png、.jpg、.jpeg），保存为JPEG时丢弃透明度
+    // 参数: image, path, [quality]（JPEG质量，1到100，默认为85）
+    pub fn cn_save(args: Vec<String>) -> String {
+        let path = match args.get(1) {
+            Some(path) => path.clone(),
+            None => return "错误: 请提供保存路径".to_string(),
+        };
+        let quality = match args.get(2).map(|q| q.trim().parse::<u8>()) {
+            None => DEFAULT_JPEG_QUALITY,
+            Some(Ok(q)) if (1..=100).contains(&q) => q,
+            _ => return format!("错误: 质量必须是1到100之间的整数: {}", args[2]),
+        };
+        with_image(&args, |image| {
+            let format = ImageFormat::from_path(&path).map_err(|_| format!("错误: 无法从扩展名确定图片格式: {}", path))?;
+            match format {
+                ImageFormat::Png => image.save_with_format(&path, ImageFormat::Png)
+                    .map_err(|e| format!("错误: 无法保存图片 {}: {}", path, e))?,
+                ImageFormat::Jpeg => save_jpeg(image, Path::new(&path), quality)?,
+                _ => return Err(format!("错误: 不支持的图片格式: {}", path)),
+            }
+            Ok("true".to_string())
+        })
+    }
+
+    // 释放图片
+    // 参数: image
+    pub fn cn_close(args: Vec<String>) -> String {
+        let mut images = lock_images();
+        match args.first().and_then(|arg| images.parse_handle(arg)) {
+            Some(id) => images.remove(id).is_some().to_string(),
+            None => "false".to_string(),
+        }
+    }
+
+    fn save_jpeg(image: &DynamicImage, path: &Path, quality: u8) -> Result<(), String> {
+        let file = File::create(path).map_err(|e| format!("错误: 无法创建文件 {}: {}", path.display(), e))?;
+        let mut encoder = JpegEncoder::new_with_quality(BufWriter::new(file), quality);
+        // JPEG 不支持透明度
+        let rgb = match image {
+            DynamicImage::ImageLuma8(_) | DynamicImage::ImageRgb8(_) => image.clone(),
+            DynamicImage::ImageLumaA8(_) => DynamicImage::ImageLuma8(image.to_luma8()),
+            _ => DynamicImage::ImageRgb8(image.to_rgb8()),
+        };
+        encoder.encode_image(&rgb).map_err(|e| format!("错误: 无法保存图片 {}: {}", path.display(), e))
+    }
+
+    fn parse_color(text: &str) -> Result<Rgba<u8>, String> {
+        let invalid = || format!("错误: 无效的颜色: {}", text);
+        let text = text.trim();
+        if let Some(hex) = text.strip_prefix('#') {
+            let digits: Vec<u8> = hex.chars().map(|c| c.to_digit(16).map(|d| d as u8)).collect::<Option<_>>().ok_or_else(invalid)?;
+            let channels: Vec<u8> = match digits.len() {
+                3 | 4 => digits.iter().map(|d| d * 17).collect(),
+                6 | 8 => digits.chunks(2).map(|pair| pair[0] * 16 + pair[1]).collect(),
+                _ => return Err(invalid()),
+            };
+            let alpha = channels.get(3).copied().unwrap_or(255);
+            return Ok(Rgba([channels[0], channels[1], channels[2], alpha]));
+        }
+        match serde_json::from_str::<JsonValue>(text) {
+            Ok(JsonValue::Array(items)) if items.len() == 3 || items.len() == 4 => {
+                let channels: Vec<u8> = items.iter()
+                    .map(|item| item.as_u64().filter(|v| *v <= 255).map(|v| v as u8))
+                    .collect::<Option<_>>()
+                    .ok_or_else(invalid)?;
+                Ok(Rgba([channels[0], channels[1], channels[2], channels.get(3).copied().unwrap_or(255)]))
+            },
+            _ => Err(invalid()),
+        }
+    }
+
+    fn filter_arg(arg: Option<&String>) -> Result<FilterType, String> {
+        match arg.map(|f| f.trim().to_lowercase()).as_deref() {
+            None | Some("") | Some("lanczos") => Ok(FilterType::Lanczos3),
+            Some("nearest") => Ok(FilterType::Nearest),
+            Some("linear") => Ok(FilterType::Triangle),
+            Some("cubic") => Ok(FilterType::CatmullRom),
+            Some("gaussian") => Ok(FilterType::Gaussian),
+            Some(filter) => Err(format!("错误: 未知的缩放算法: {}", filter)),
+        }
+    }
+
+    fn dimension_arg(args: &[String], index: usize, name: &str) -> Result<u32, String> {
+        match args.get(index).map(|a| a.trim().parse::<u32>()) {
+            Some(Ok(value)) if value > 0 => Ok(value),
+            _ => Err(format!("错误: {}必须是正整数", name)),
+        }
+    }
+
+    // 读取相邻的宽度和高度参数，像素数不能超过 MAX_PIXELS
+    fn size_args(args: &[String], index: usize, width_name: &str, height_name: &str) -> Result<(u32, u32), String> {
+        let width = dimension_arg(args, index, width_name)?;
+        let height = dimension_arg(args, index + 1, height_name)?;
+        if width as u64 * height as u64 > MAX_PIXELS {
+            return Err(format!("错误: 图片过大: {}x{}，最多 {} 像素", width, height, MAX_PIXELS));
+        }
+        Ok((width, height))
+    }
+
+    fn coordinate_arg(args: &[String], index: usize, name: &str) -> Result<u32, String> {
+        match args.get(index).map(|a| a.trim().parse::<u32>()) {
+            Some(Ok(value)) => Ok(value),
+            _ => Err(format!("错误: {}必须是非负整数", name)),
+        }
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册图片命名空间下的函数
+    let image_ns = registry.namespace("image");
+    image_ns.add_function("open", image::cn_open)
+            .add_function("new", image::cn_new)
+            .add_function("copy", image::cn_copy)
+            .add_function("size", image::cn_size)
+            .add_function("resize", image::cn_resize)
+            .add_function("thumbnail", image::cn_thumbnail)
+            .add_function("crop", image::cn_crop)
+            .add_function("rotate", image::cn_rotate)
+            .add_function("flip", image::cn_flip)
+            .add_function("convert", image::cn_convert)
+            .add_function("get_pixel", image::cn_get_pixel)
+            .add_function("set_pixel", image::cn_set_pixel)
+            .add_function("fill", image::cn_fill)
+            .add_function("save", image::cn_save)
+            .add_function("close", image::cn_close);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
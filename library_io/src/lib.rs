@@ -1,44 +1,489 @@
 use ::std::collections::HashMap;
-use ::std::io::{self, Write as IoWrite};
+use ::std::io::{self, BufRead, IsTerminal, Read, Write as IoWrite};
 use ::std::fmt::Write as FmtWrite;
+use ::std::sync::{Mutex, MutexGuard, Once};
+use serde_json::Value as JsonValue;
 
 // 导入通用库
-use cn_common::namespace::{LibraryFunction, NamespaceBuilder, create_library_pointer, LibraryRegistry};
+use cn_common::handle::HandleTable;
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
 use cn_common::string::process_escape_chars;
 
 // 命名空间函数
 mod std {
     use super::*;
+    use cn_common::term::{color_enabled, style_code};
+    pub use cn_common::term::{cn_is_tty, cn_set_color};
+    
+    // 缓冲模式下积累到这个大小时写出
+    const BUFFER_LIMIT: usize = 64 * 1024;
+    
+    struct Output {
+        buffered: bool,
+        buffer: Vec<u8>,
+    }
+    
+    // 本库写入标准输出的内容都经过这里；缓冲模式下先积累在 buffer 中，
+    // 在缓冲区满、调用 flush、读取标准输入、写标准错误和进程退出时写出
+    static OUTPUT: Mutex<Output> = Mutex::new(Output { buffered: false, buffer: Vec::new() });
+    static FLUSH_AT_EXIT: Once = Once::new();
+    
+    fn lock_output() -> MutexGuard<'static, Output> {
+        OUTPUT.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
     
     // 打印字符串到标准输出
     pub fn cn_print(args: Vec<String>) -> String {
         let mut output = String::new();
         for arg in args {
             let processed = process_escape_chars(&arg);
-            print!("{}", processed);
             output.push_str(&processed);
         }
-        io::stdout().flush().unwrap();
+        let _ = write_stdout(output.as_bytes());
         output
     }
     
     // 打印字符串到标准输出，并添加换行符
     pub fn cn_println(args: Vec<String>) -> String {
         let mut output = String::new();
+        let mut written = String::new();
         for arg in args {
             let processed = process_escape_chars(&arg);
-            println!("{}", processed);
+            written.push_str(&processed);
+            written.push('\n');
             output.push_str(&processed);
         }
+        let _ = write_stdout(written.as_bytes());
         output.push('\n');
         output
     }
     
+    // 设置标准输出是否缓冲；缓冲可以大幅减少逐行打印大量内容时的系统调用，
+    // 但其他库直接写入标准输出的内容可能出现在缓冲的内容之前。关闭缓冲时立即写出缓冲的内容
+    // 参数: enabled（true 或 false）
+    pub fn cn_set_buffered(args: Vec<String>) -> String {
+        let buffered = match args.first().map(|b| b.trim()) {
+            Some("true") => true,
+            Some("false") => false,
+            _ => return "错误: 参数必须是 true 或 false".to_string(),
+        };
+        if buffered {
+            // 脚本正常结束时写出剩余的内容
+            FLUSH_AT_EXIT.call_once(|| {
+                // SAFETY: 库在进程结束前不会被卸载，回调一直有效
+                unsafe {
+                    libc::atexit(flush_at_exit);
+                }
+            });
+        }
+        lock_output().buffered = buffered;
+        if !buffered {
+            if let Err(e) = flush_stdout() {
+                return format!("错误: 无法写入标准输出: {}", e);
+            }
+        }
+        "true".to_string()
+    }
+    
+    // 立即写出缓冲的标准输出内容
+    // 参数: 无
+    pub fn cn_flush(_args: Vec<String>) -> String {
+        match flush_stdout() {
+            Ok(()) => "true".to_string(),
+            Err(e) => format!("错误: 无法写入标准输出: {}", e),
+        }
+    }
+    
+    // 把十六进制表示的字节原样写入标准输出，不做转义和编码转换，返回写入的字节数
+    // 十六进制中可以包含空白
+    // 参数: hex
+    pub fn cn_write_bytes(args: Vec<String>) -> String {
+        let hex: Vec<u8> = args.first().map(|h| h.bytes().filter(|b| !b.is_ascii_whitespace()).collect()).unwrap_or_default();
+        if hex.len() % 2 == 1 {
+            return "错误: 十六进制字符串的长度必须是偶数".to_string();
+        }
+        let mut bytes = Vec::with_capacity(hex.len() / 2);
+        for pair in hex.chunks(2) {
+            let digit = |b: u8| (b as char).to_digit(16);
+            match (digit(pair[0]), digit(pair[1])) {
+                (Some(high), Some(low)) => bytes.push((high * 16 + low) as u8),
+                _ => return format!("错误: 无效的十六进制字符: {}", String::from_utf8_lossy(pair)),
+            }
+        }
+        match write_stdout(&bytes) {
+            Ok(()) => bytes.len().to_string(),
+            Err(e) => format!("错误: 无法写入标准输出: {}", e),
+        }
+    }
+    
+    // 写入时暂时擦除显示中的进度条，写完后重新显示
+    pub(super) fn write_stdout(bytes: &[u8]) -> io::Result<()> {
+        let mut output = lock_output();
+        if output.buffered {
+            output.buffer.extend_from_slice(bytes);
+            if output.buffer.len() >= BUFFER_LIMIT {
+                let buffer = ::std::mem::take(&mut output.buffer);
+                return progress::suspend(&buffer, || {
+                    let mut stdout = io::stdout().lock();
+                    stdout.write_all(&buffer)?;
+                    stdout.flush()
+                });
+            }
+            return Ok(());
+        }
+        progress::suspend(bytes, || {
+            let mut stdout = io::stdout().lock();
+            stdout.write_all(bytes)?;
+            stdout.flush()
+        })
+    }
+    
+    pub(super) fn flush_stdout() -> io::Result<()> {
+        let mut output = lock_output();
+        if output.buffer.is_empty() {
+            return io::stdout().flush();
+        }
+        // 写出失败时丢弃缓冲的内容，避免在之后的每次调用中重复报错
+        let buffer = ::std::mem::take(&mut output.buffer);
+        progress::suspend(&buffer, || {
+            let mut stdout = io::stdout().lock();
+            stdout.write_all(&buffer)?;
+            stdout.flush()
+        })
+    }
+    
+    fn write_stderr(bytes: &[u8]) -> io::Result<()> {
+        progress::suspend(bytes, || {
+            let mut stderr = io::stderr().lock();
+            stderr.write_all(bytes)?;
+            stderr.flush()
+        })
+    }
+    
+    extern "C" fn flush_at_exit() {
+        let _ = flush_stdout();
+    }
+    
+    // 打印字符串到标准错误
+    pub fn cn_eprint(args: Vec<String>) -> String {
+        // 先写出缓冲的标准输出，保持两者的先后顺序
+        let _ = flush_stdout();
+        let mut output = String::new();
+        for arg in args {
+            let processed = process_escape_chars(&arg);
+            output.push_str(&processed);
+        }
+        let _ = write_stderr(output.as_bytes());
+        output
+    }
+    
+    // 打印字符串到标准错误，并添加换行符
+    pub fn cn_eprintln(args: Vec<String>) -> String {
+        let _ = flush_stdout();
+        let mut output = String::new();
+        let mut written = String::new();
+        for arg in args {
+            let processed = process_escape_chars(&arg);
+            written.push_str(&processed);
+            written.push('\n');
+            output.push_str(&processed);
+        }
+        let _ = write_stderr(written.as_bytes());
+        output.push('\n');
+        output
+    }
+    
+    // 立即结束脚本，以指定的状态码退出进程，退出前刷新标准输出和标准错误
+    // 参数: [code]（整数，默认为0；Unix 上只保留低8位）
+    pub fn cn_exit(args: Vec<String>) -> String {
+        let code = match args.first().map(|c| c.trim().parse::<i32>()) {
+            Some(Ok(code)) => code,
+            None => 0,
+            Some(Err(_)) => return format!("错误: 退出码必须是整数: {}", args[0]),
+        };
+        let _ = flush_stdout();
+        let _ = io::stderr().flush();
+        ::std::process::exit(code)
+    }
+    
+    // 用颜色和样式打印文本，关闭颜色输出时打印原文
+    // color 为颜色名（red、bright_red 等）或 #rrggbb，可以为空；styles 用逗号或空格分隔，
+    // 可以是 bold、dim、italic、underline、blink、reverse、hidden、strikethrough 和 bg_颜色名
+    // 参数: text, [color], [styles]
+    pub fn cn_color_print(args: Vec<String>) -> String {
+        match colorize(&args) {
+            Ok(text) => {
+                let _ = write_stdout(text.as_bytes());
+                text
+            },
+            Err(e) => e,
+        }
+    }
+    
+    // 用颜色和样式打印文本，并添加换行符
+    // 参数: text, [color], [styles]
+    pub fn cn_color_println(args: Vec<String>) -> String {
+        match colorize(&args) {
+            Ok(text) => {
+                let text = text + "\n";
+                let _ = write_stdout(text.as_bytes());
+                text
+            },
+            Err(e) => e,
+        }
+    }
+    
+    // 把标记文本转换为带颜色的文本并返回，不打印；关闭颜色输出时去掉标记
+    // 标记形如 [red]错误[/red]、[bold green]完成[/]，方括号中是 color_print 支持的颜色和样式，
+    // [/名称] 或 [/] 结束最近的标记，可以嵌套；[[ 表示字面的 [，无法识别的方括号原样保留
+    // 参数: text
+    pub fn cn_markup(args: Vec<String>) -> String {
+        let text = args.first().map(|t| process_escape_chars(t)).unwrap_or_default();
+        render_markup(&text, color_enabled())
+    }
+    
+    // 打印标记文本
+    // 参数: text
+    pub fn cn_markup_print(args: Vec<String>) -> String {
+        let output = cn_markup(args);
+        let _ = write_stdout(output.as_bytes());
+        output
+    }
+    
+    // 打印标记文本，并添加换行符
+    // 参数: text
+    pub fn cn_markup_println(args: Vec<String>) -> String {
+        let output = cn_markup(args) + "\n";
+        let _ = write_stdout(output.as_bytes());
+        output
+    }
+    
+    fn colorize(args: &[String]) -> Result<String, String> {
+        let text = args.first().map(|t| process_escape_chars(t)).unwrap_or_default();
+        let mut codes = Vec::new();
+        for name in args.iter().skip(1).flat_map(|s| s.split([',', ' '])).filter(|s| !s.is_empty()) {
+            match style_code(&name.to_lowercase()) {
+                Some(code) => codes.push(code),
+                None => return Err(format!("错误: 未知的颜色或样式: {}", name)),
+            }
+        }
+        if codes.is_empty() || !color_enabled() {
+            return Ok(text);
+        }
+        Ok(format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text))
+    }
+    
+    fn render_markup(text: &str, color: bool) -> String {
+        let mut result = String::with_capacity(text.len());
+        // 每层打开的标记对应的SGR参数
+        let mut stack: Vec<Vec<String>> = Vec::new();
+        let mut rest = text;
+        while let Some(open) = rest.find('[') {
+            result.push_str(&rest[..open]);
+            rest = &rest[open..];
+            if let Some(after) = rest.strip_prefix("[[") {
+                result.push('[');
+                rest = after;
+                continue;
+            }
+            let tag = match rest[1..].find([']', '[']) {
+                Some(end) if rest.as_bytes()[end + 1] == b']' => &rest[1..end + 1],
+                _ => {
+                    result.push('[');
+                    rest = &rest[1..];
+                    continue;
+                },
+            };
+            let consumed = tag.len() + 2;
+            if tag.starts_with('/') && !stack.is_empty() {
+                stack.pop();
+                if color {
+                    result.push_str("\x1b[0m");
+                    // 重新应用外层仍然有效的样式
+                    let codes: Vec<&str> = stack.iter().flatten().map(String::as_str).collect();
+                    if !codes.is_empty() {
+                        let _ = FmtWrite::write_fmt(&mut result, format_args!("\x1b[{}m", codes.join(";")));
+                    }
+                }
+                rest = &rest[consumed..];
+                continue;
+            }
+            let codes: Option<Vec<String>> = tag.split([',', ' '])
+                .filter(|s| !s.is_empty())
+                .map(|name| style_code(&name.to_lowercase()))
+                .collect();
+            match codes.filter(|codes| !codes.is_empty()) {
+                Some(codes) => {
+                    if color {
+                        let _ = FmtWrite::write_fmt(&mut result, format_args!("\x1b[{}m", codes.join(";")));
+                    }
+                    stack.push(codes);
+                    rest = &rest[consumed..];
+                },
+                // 不是标记，如 [1, 2]，原样保留
+                None => {
+                    result.push('[');
+                    rest = &rest[1..];
+                },
+            }
+        }
+        result.push_str(rest);
+        if color && !stack.is_empty() {
+            result.push_str("\x1b[0m");
+        }
+        result
+    }
+    
+    // 把数据打印为对齐的表格，返回打印的文本
+    // rows 是数组的数组或 Map 的数组；Map 的列默认为第一行的键，按键名排列
+    // options 是可选的 Map: headers: 表头数组（数组行默认没有表头，Map 行默认为键名）,
+    // columns: Map 行要显示的键及其顺序, border: ascii（默认）、unicode 或 none,
+    // max_width: 每列的最大显示宽度，超出部分用 … 截断, align: left、right、center 或它们的数组（默认数字右对齐、其余左对齐）
+    // 参数: rows, [options]
+    pub fn cn_print_table(args: Vec<String>) -> String {
+        match table::format(&args) {
+            // 没有行也没有表头时不打印
+            Ok(text) if text.is_empty() => text,
+            Ok(text) => {
+                let text = text + "\n";
+                let _ = write_stdout(text.as_bytes());
+                text
+            },
+            Err(e) => e,
+        }
+    }
+    
+    // 把数据格式化为表格文本，不打印；参数与 print_table 相同
+    // 参数: rows, [options]
+    pub fn cn_format_table(args: Vec<String>) -> String {
+        table::format(&args).unwrap_or_else(|e| e)
+    }
+    
     // 从标准输入读取一行
     pub fn cn_read_line(_args: Vec<String>) -> String {
+        read_input_line().unwrap_or_default()
+    }
+    
+    // 读取标准输入的全部内容直到结束，适合处理管道传入的数据；无效的UTF-8字节替换为 �
+    // 参数: 无
+    pub fn cn_read_all(_args: Vec<String>) -> String {
+        let _ = flush_stdout();
+        let mut bytes = Vec::new();
+        match io::stdin().lock().read_to_end(&mut bytes) {
+            Ok(_) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) => format!("错误: 读取标准输入失败: {}", e),
+        }
+    }
+    
+    // 逐行读取标准输入，返回迭代句柄，配合 lines::has_next、lines::next 和 lines::close 使用
+    // 参数: 无
+    pub fn cn_read_lines(_args: Vec<String>) -> String {
+        lines::open()
+    }
+    
+    // 读取整数，输入无效或超出范围时重新提示
+    // 参数: [prompt], [min], [max]
+    pub fn cn_read_int(args: Vec<String>) -> String {
+        let (min, max) = match (parse_bound::<i64>(args.get(1)), parse_bound::<i64>(args.get(2))) {
+            (Ok(min), Ok(max)) => (min, max),
+            (Err(e), _) | (_, Err(e)) => return e,
+        };
+        read_valid(args.first(), |input| match input.parse::<i64>() {
+            Ok(value) if min.is_some_and(|min| value < min) || max.is_some_and(|max| value > max) => Err(range_message(min, max)),
+            Ok(value) => Ok(value.to_string()),
+            Err(_) => Err("请输入整数".to_string()),
+        })
+    }
+    
+    // 读取数字，输入无效或超出范围时重新提示
+    // 参数: [prompt], [min], [max]
+    pub fn cn_read_float(args: Vec<String>) -> String {
+        let (min, max) = match (parse_bound::<f64>(args.get(1)), parse_bound::<f64>(args.get(2))) {
+            (Ok(min), Ok(max)) => (min, max),
+            (Err(e), _) | (_, Err(e)) => return e,
+        };
+        read_valid(args.first(), |input| match input.parse::<f64>() {
+            Ok(value) if !value.is_finite() => Err("请输入数字".to_string()),
+            Ok(value) if min.is_some_and(|min| value < min) || max.is_some_and(|max| value > max) => Err(range_message(min, max)),
+            Ok(value) => Ok(value.to_string()),
+            Err(_) => Err("请输入数字".to_string()),
+        })
+    }
+    
+    // 读取是或否，接受 y、yes、true、1、是 和 n、no、false、0、否（不区分大小写），返回 true 或 false
+    // 参数: [prompt], [default]（直接回车时的值，省略时必须输入）
+    pub fn cn_read_bool(args: Vec<String>) -> String {
+        let default = match args.get(1).map(|d| parse_bool(d.trim())) {
+            Some(Some(default)) => Some(default),
+            Some(None) => return format!("错误: 无效的默认值: {}", args[1]),
+            None => None,
+        };
+        read_valid(args.first(), |input| match (parse_bool(input), default) {
+            (Some(value), _) => Ok(value.to_string()),
+            (None, Some(default)) if input.is_empty() => Ok(default.to_string()),
+            (None, _) => Err("请输入 y 或 n".to_string()),
+        })
+    }
+    
+    // 从选项中选择一项，可以输入选项本身（不区分大小写）或它的序号，返回选中的选项
+    // 参数: [prompt], options（字符串数组）, [default]（直接回车时的选项）
+    pub fn cn_read_choice(args: Vec<String>) -> String {
+        let options = match args.get(1).map(|o| serde_json::from_str::<Vec<String>>(o)) {
+            Some(Ok(options)) if !options.is_empty() => options,
+            _ => return "错误: 选项必须是非空的字符串数组".to_string(),
+        };
+        let default = args.get(2).map(|d| d.trim()).filter(|d| !d.is_empty());
+        if default.is_some_and(|default| !options.iter().any(|option| option == default)) {
+            return format!("错误: 默认值不在选项中: {}", args[2]);
+        }
+        for (index, option) in options.iter().enumerate() {
+            let _ = write_stdout(format!("  {}) {}\n", index + 1, option).as_bytes());
+        }
+        read_valid(args.first(), |input| {
+            if input.is_empty() {
+                if let Some(default) = default {
+                    return Ok(default.to_string());
+                }
+            }
+            if let Some(option) = input.parse::<usize>().ok().and_then(|n| n.checked_sub(1)).and_then(|i| options.get(i)) {
+                return Ok(option.clone());
+            }
+            match options.iter().find(|option| option.to_lowercase() == input.to_lowercase()) {
+                Some(option) => Ok(option.clone()),
+                None => Err(format!("请输入 1 到 {} 之间的序号或选项名称", options.len())),
+            }
+        })
+    }
+    
+    // 读取密码，输入时不回显；标准输入不是终端时按普通的一行读取
+    // 参数: [prompt]
+    pub fn cn_read_password(args: Vec<String>) -> String {
+        if let Some(prompt) = args.first() {
+            let _ = write_stdout(process_escape_chars(prompt).as_bytes());
+        }
+        if !io::stdin().is_terminal() {
+            return read_input_line().unwrap_or_default();
+        }
+        let restore = match disable_echo() {
+            Ok(restore) => restore,
+            Err(e) => return format!("错误: 无法关闭回显: {}", e),
+        };
+        let input = read_input_line();
+        restore_echo(restore);
+        // 回车没有回显，补上换行
+        let _ = write_stdout(b"\n");
+        input.unwrap_or_default()
+    }
+    
+    // 读取一行并去掉末尾的换行符，输入结束或出错时返回 None
+    fn read_input_line() -> Option<String> {
+        // 提示等缓冲的内容要在等待输入前显示出来
+        let _ = flush_stdout();
         let mut input = String::new();
         match io::stdin().read_line(&mut input) {
+            Ok(0) | Err(_) => None,
             Ok(_) => {
+                progress::line_entered();
                 // 移除末尾的换行符
                 if input.ends_with('\n') {
                     input.pop();
@@ -46,91 +491,825 @@ mod std {
                         input.pop();
                     }
                 }
-                input
+                Some(input)
             },
-            Err(_) => String::new(),
         }
     }
     
-    // 格式化打印，类似C语言的printf
+    // 显示提示并读取，直到 validate 接受输入；validate 的错误信息会显示给用户
+    fn read_valid<F: FnMut(&str) -> Result<String, String>>(prompt: Option<&String>, mut validate: F) -> String {
+        let prompt = prompt.map(|p| process_escape_chars(p)).unwrap_or_default();
+        loop {
+            let _ = write_stdout(prompt.as_bytes());
+            let input = match read_input_line() {
+                Some(input) => input,
+                None => return "错误: 输入已结束".to_string(),
+            };
+            match validate(input.trim()) {
+                Ok(value) => return value,
+                Err(message) => {
+                    let _ = write_stdout(format!("{}\n", message).as_bytes());
+                },
+            }
+        }
+    }
+    
+    fn parse_bound<T: ::std::str::FromStr>(arg: Option<&String>) -> Result<Option<T>, String> {
+        match arg.map(|a| a.trim()).filter(|a| !a.is_empty()) {
+            Some(text) => text.parse().map(Some).map_err(|_| format!("错误: 无效的范围: {}", text)),
+            None => Ok(None),
+        }
+    }
+    
+    fn range_message<T: ::std::fmt::Display>(min: Option<T>, max: Option<T>) -> String {
+        match (min, max) {
+            (Some(min), Some(max)) => format!("请输入 {} 到 {} 之间的数", min, max),
+            (Some(min), None) => format!("请输入不小于 {} 的数", min),
+            (None, Some(max)) => format!("请输入不大于 {} 的数", max),
+            (None, None) => String::new(),
+        }
+    }
+    
+    fn parse_bool(input: &str) -> Option<bool> {
+        match input.to_lowercase().as_str() {
+            "y" | "yes" | "true" | "1" | "是" => Some(true),
+            "n" | "no" | "false" | "0" | "否" => Some(false),
+            _ => None,
+        }
+    }
+    
+    #[cfg(unix)]
+    type EchoState = libc::termios;
+    #[cfg(windows)]
+    type EchoState = u32;
+    #[cfg(not(any(unix, windows)))]
+    type EchoState = ();
+    
+    // 关闭终端回显，返回恢复用的原状态
+    #[cfg(unix)]
+    fn disable_echo() -> io::Result<EchoState> {
+        // SAFETY: termios 是只包含整数的C结构体，全零是有效值；tcgetattr 会填充它
+        unsafe {
+            let mut original: libc::termios = ::std::mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut original) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let mut silent = original;
+            silent.c_lflag &= !libc::ECHO;
+            if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &silent) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(original)
+        }
+    }
+    
+    #[cfg(unix)]
+    fn restore_echo(original: EchoState) {
+        // SAFETY: original 是 tcgetattr 返回的有效状态
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &original);
+        }
+    }
+    
+    #[cfg(windows)]
+    extern "system" {
+        fn GetStdHandle(handle: u32) -> *mut ::std::ffi::c_void;
+        fn GetConsoleMode(console: *mut ::std::ffi::c_void, mode: *mut u32) -> i32;
+        fn SetConsoleMode(console: *mut ::std::ffi::c_void, mode: u32) -> i32;
+    }
+    
+    #[cfg(windows)]
+    const STD_INPUT_HANDLE: u32 = -10i32 as u32;
+    #[cfg(windows)]
+    const ENABLE_ECHO_INPUT: u32 = 0x0004;
+    
+    #[cfg(windows)]
+    fn disable_echo() -> io::Result<EchoState> {
+        // SAFETY: 标准输入句柄在进程中一直有效，输出指针指向本函数中的变量
+        unsafe {
+            let console = GetStdHandle(STD_INPUT_HANDLE);
+            let mut mode = 0u32;
+            if GetConsoleMode(console, &mut mode) == 0 || SetConsoleMode(console, mode & !ENABLE_ECHO_INPUT) == 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(mode)
+        }
+    }
+    
+    #[cfg(windows)]
+    fn restore_echo(mode: EchoState) {
+        // SAFETY: 标准输入句柄在进程中一直有效
+        unsafe {
+            SetConsoleMode(GetStdHandle(STD_INPUT_HANDLE), mode);
+        }
+    }
+    
+    #[cfg(not(any(unix, windows)))]
+    fn disable_echo() -> io::Result<EchoState> {
+        Ok(())
+    }
+    
+    #[cfg(not(any(unix, windows)))]
+    fn restore_echo(_state: EchoState) {}
+    
+    // 格式化打印，与C语言的printf相同
+    // 格式说明为 %[标志][宽度][.精度]转换符，标志可以是 - + 空格 0 #，宽度和精度可以是 * 表示从参数中读取
+    // 转换符: d i（整数）、u（无符号整数）、x X o（十六进制和八进制）、f F e E g G（浮点数）、s（字符串）、c（字符）、%%
+    // 参数不足或类型不符时，格式说明原样输出
     pub fn cn_printf(args: Vec<String>) -> String {
         if args.is_empty() {
             return String::new();
         }
         
-        let format_str = process_escape_chars(&args[0]);
-        let mut result = String::new();
-        let mut format_args = args.iter().skip(1);
-        let mut chars = format_str.chars().peekable();
+        let result = format_printf(&process_escape_chars(&args[0]), &args[1..]);
         
-        while let Some(c) = chars.next() {
-            if c == '%' {
-                if let Some(&next_c) = chars.peek() {
-                    match next_c {
-                        's' => {
-                            // 字符串格式化
-                            if let Some(arg) = format_args.next() {
-                                let processed = process_escape_chars(arg);
-                                result.push_str(&processed);
-                            } else {
-                                result.push_str("%s");
-                            }
-                            chars.next(); // 消费's'
-                        },
-                        'd' | 'i' => {
-                            // 整数格式化
-                            if let Some(arg) = format_args.next() {
-                                if let Ok(num) = arg.parse::<i32>() {
-                                    let _ = FmtWrite::write_fmt(&mut result, format_args!("{}", num));
-                                } else {
-                                    result.push_str("%d");
-                                }
-                            } else {
-                                result.push_str("%d");
-                            }
-                            chars.next(); // 消费'd'或'i'
-                        },
-                        'f' => {
-                            // 浮点数格式化
-                            if let Some(arg) = format_args.next() {
-                                if let Ok(num) = arg.parse::<f64>() {
-                                    let _ = FmtWrite::write_fmt(&mut result, format_args!("{}", num));
-                                } else {
-                                    result.push_str("%f");
-                                }
-                            } else {
-                                result.push_str("%f");
-                            }
-                            chars.next(); // 消费'f'
-                        },
-                        '%' => {
-                            // 转义的百分号
-                            result.push('%');
-                            chars.next(); // 消费第二个'%'
-                        },
-                        _ => {
-                            // 未知格式说明符，保留原样
-                            result.push('%');
-                            result.push(next_c);
-                            chars.next(); // 消费下一个字符
-                        }
+        // 打印结果
+        let _ = write_stdout(result.as_bytes());
+        
+        result
+    }
+    
+    // 一个格式说明
+    #[derive(Default)]
+    struct Spec {
+        left: bool,
+        plus: bool,
+        space: bool,
+        zero: bool,
+        alternate: bool,
+        width: usize,
+        precision: Option<usize>,
+        conversion: char,
+    }
+    
+    fn format_printf(format: &str, args: &[String]) -> String {
+        let mut result = String::new();
+        let mut format_args = args.iter();
+        let mut rest = format;
+        while let Some(percent) = rest.find('%') {
+            result.push_str(&rest[..percent]);
+            rest = &rest[percent..];
+            let (spec, length) = match parse_spec(rest, &mut format_args) {
+                Some(parsed) => parsed,
+                None => {
+                    // 不完整或未知的格式说明，保留原样
+                    let length = rest[1..].chars().next().map(|c| 1 + c.len_utf8()).unwrap_or(1);
+                    result.push_str(&rest[..length]);
+                    rest = &rest[length..];
+                    continue;
+                },
+            };
+            let text = &rest[..length];
+            rest = &rest[length..];
+            if spec.conversion == '%' {
+                result.push('%');
+                continue;
+            }
+            match format_args.next().and_then(|arg| format_value(&spec, arg)) {
+                Some(formatted) => result.push_str(&formatted),
+                None => result.push_str(text),
+            }
+        }
+        result.push_str(rest);
+        result
+    }
+    
+    // 解析 % 开头的格式说明，返回格式说明和它占用的字节数
+    fn parse_spec<'a, I: Iterator<Item = &'a String>>(text: &str, args: &mut I) -> Option<(Spec, usize)> {
+        let bytes = text.as_bytes();
+        let mut spec = Spec::default();
+        let mut i = 1;
+        while let Some(flag) = bytes.get(i) {
+            match flag {
+                b'-' => spec.left = true,
+                b'+' => spec.plus = true,
+                b' ' => spec.space = true,
+                b'0' => spec.zero = true,
+                b'#' => spec.alternate = true,
+                _ => break,
+            }
+            i += 1;
+        }
+        if bytes.get(i) == Some(&b'*') {
+            // 负的宽度表示左对齐
+            let width: i64 = args.next()?.trim().parse().ok()?;
+            spec.left |= width < 0;
+            spec.width = width.unsigned_abs() as usize;
+            i += 1;
+        } else {
+            let start = i;
+            while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+                i += 1;
+            }
+            spec.width = text[start..i].parse().unwrap_or(0);
+        }
+        if bytes.get(i) == Some(&b'.') {
+            i += 1;
+            if bytes.get(i) == Some(&b'*') {
+                // 负的精度视为没有指定精度
+                let precision: i64 = args.next()?.trim().parse().ok()?;
+                spec.precision = usize::try_from(precision).ok();
+                i += 1;
+            } else {
+                let start = i;
+                while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+                    i += 1;
+                }
+                spec.precision = Some(text[start..i].parse().unwrap_or(0));
+            }
+        }
+        // 长度修饰符对结果没有影响
+        while matches!(bytes.get(i), Some(b'h' | b'l' | b'L' | b'q' | b'j' | b'z' | b't')) {
+            i += 1;
+        }
+        spec.conversion = *bytes.get(i)? as char;
+        if !"diuxXofFeEgGsc%".contains(spec.conversion) {
+            return None;
+        }
+        Some((spec, i + 1))
+    }
+    
+    fn format_value(spec: &Spec, arg: &str) -> Option<String> {
+        match spec.conversion {
+            'd' | 'i' => {
+                let value: i64 = arg.trim().parse().ok()?;
+                let digits = integer_digits(value.unsigned_abs().to_string(), spec.precision);
+                Some(pad_number(spec, sign(spec, value < 0), &digits, spec.precision.is_none()))
+            },
+            'u' | 'x' | 'X' | 'o' => {
+                // 负数按64位补码解释
+                let value = match arg.trim().parse::<u64>() {
+                    Ok(value) => value,
+                    Err(_) => arg.trim().parse::<i64>().ok()? as u64,
+                };
+                let digits = match spec.conversion {
+                    'x' => format!("{:x}", value),
+                    'X' => format!("{:X}", value),
+                    'o' => format!("{:o}", value),
+                    _ => value.to_string(),
+                };
+                let mut digits = integer_digits(digits, spec.precision);
+                let prefix = match spec.conversion {
+                    'x' if spec.alternate && value != 0 => "0x",
+                    'X' if spec.alternate && value != 0 => "0X",
+                    'o' if spec.alternate && !digits.starts_with('0') => {
+                        digits.insert(0, '0');
+                        ""
+                    },
+                    _ => "",
+                };
+                Some(pad_number(spec, prefix, &digits, spec.precision.is_none()))
+            },
+            'f' | 'F' | 'e' | 'E' | 'g' | 'G' => {
+                let value: f64 = arg.trim().parse().ok()?;
+                let uppercase = spec.conversion.is_ascii_uppercase();
+                if !value.is_finite() {
+                    let text = match (value.is_nan(), uppercase) {
+                        (true, false) => "nan",
+                        (true, true) => "NAN",
+                        (false, false) => "inf",
+                        (false, true) => "INF",
+                    };
+                    return Some(pad_number(spec, sign(spec, value < 0.0), text, false));
+                }
+                let precision = spec.precision.unwrap_or(6);
+                let magnitude = value.abs();
+                let body = match spec.conversion.to_ascii_lowercase() {
+                    'f' => format!("{:.*}", precision, magnitude),
+                    'e' => exponential(magnitude, precision),
+                    _ => general(magnitude, precision.max(1), spec.alternate),
+                };
+                let body = if uppercase { body.to_uppercase() } else { body };
+                let body = if spec.alternate && precision == 0 && !body.contains('.') && !spec.conversion.eq_ignore_ascii_case(&'g') {
+                    // # 使精度为0时也保留小数点
+                    match body.find(['e', 'E']) {
+                        Some(e) => format!("{}.{}", &body[..e], &body[e..]),
+                        None => body + ".",
                     }
                 } else {
-                    // 格式字符串以%结尾，保留原样
-                    result.push('%');
+                    body
+                };
+                Some(pad_number(spec, sign(spec, value.is_sign_negative()), &body, true))
+            },
+            's' => {
+                let text = process_escape_chars(arg);
+                let text: String = match spec.precision {
+                    Some(precision) => text.chars().take(precision).collect(),
+                    None => text,
+                };
+                Some(pad(spec, &text))
+            },
+            'c' => {
+                // 多位数字按字符编码处理（如 65 为 A），其他取第一个字符
+                let trimmed = arg.trim();
+                let c = if trimmed.len() > 1 && trimmed.bytes().all(|b| b.is_ascii_digit()) {
+                    char::from_u32(trimmed.parse().ok()?)?
+                } else {
+                    process_escape_chars(arg).chars().next()?
+                };
+                Some(pad(spec, &c.to_string()))
+            },
+            _ => None,
+        }
+    }
+    
+    fn sign(spec: &Spec, negative: bool) -> &'static str {
+        if negative {
+            "-"
+        } else if spec.plus {
+            "+"
+        } else if spec.space {
+            " "
+        } else {
+            ""
+        }
+    }
+    
+    // 精度是整数的最少位数，精度为0时数值0不输出数字
+    fn integer_digits(digits: String, precision: Option<usize>) -> String {
+        match precision {
+            Some(0) if digits == "0" => String::new(),
+            Some(precision) if digits.len() < precision => format!("{}{}", "0".repeat(precision - digits.len()), digits),
+            _ => digits,
+        }
+    }
+    
+    // 按宽度填充数字；0 标志在符号和前缀之后补零
+    fn pad_number(spec: &Spec, prefix: &str, digits: &str, zero_allowed: bool) -> String {
+        let length = prefix.chars().count() + digits.chars().count();
+        if spec.zero && zero_allowed && !spec.left && length < spec.width {
+            format!("{}{}{}", prefix, "0".repeat(spec.width - length), digits)
+        } else {
+            pad(spec, &format!("{}{}", prefix, digits))
+        }
+    }
+    
+    // 按宽度用空格填充，宽度按字符计算
+    fn pad(spec: &Spec, text: &str) -> String {
+        let length = text.chars().count();
+        if length >= spec.width {
+            text.to_string()
+        } else if spec.left {
+            format!("{}{}", text, " ".repeat(spec.width - length))
+        } else {
+            format!("{}{}", " ".repeat(spec.width - length), text)
+        }
+    }
+    
+    // %e 格式，指数至少两位并带符号，如 1.500000e+02
+    fn exponential(value: f64, precision: usize) -> String {
+        let text = format!("{:.*e}", precision, value);
+        let (mantissa, exponent) = text.split_once('e').unwrap_or((&text, "0"));
+        let exponent: i32 = exponent.parse().unwrap_or(0);
+        format!("{}e{}{:02}", mantissa, if exponent < 0 { '-' } else { '+' }, exponent.abs())
+    }
+    
+    // %g 格式：按精度取有效数字，指数小于-4或不小于精度时用 %e，否则用 %f，并去掉末尾的0
+    fn general(value: f64, precision: usize, alternate: bool) -> String {
+        // 按舍入后的结果确定指数
+        let rounded = format!("{:.*e}", precision - 1, value);
+        let exponent: i32 = rounded.split_once('e').and_then(|(_, e)| e.parse().ok()).unwrap_or(0);
+        let text = if exponent < -4 || exponent >= precision as i32 {
+            exponential(value, precision - 1)
+        } else {
+            format!("{:.*}", (precision as i32 - 1 - exponent) as usize, value)
+        };
+        if alternate {
+            return text;
+        }
+        let (number, exponent) = match text.find('e') {
+            Some(e) => (&text[..e], &text[e..]),
+            None => (text.as_str(), ""),
+        };
+        let number = if number.contains('.') { number.trim_end_matches('0').trim_end_matches('.') } else { number };
+        format!("{}{}", number, exponent)
+    }
+}
+
+// 标准输入逐行读取命名空间
+mod lines {
+    use super::*;
+    
+    // 逐行读取的迭代器状态，读取的行去掉末尾的换行符
+    struct LineReader {
+        peeked: Option<String>,
+        finished: bool,
+    }
+    
+    impl LineReader {
+        fn advance(&mut self) -> Result<Option<String>, String> {
+            if self.finished {
+                return Ok(None);
+            }
+            let _ = std::flush_stdout();
+            let mut bytes = Vec::new();
+            match io::stdin().lock().read_until(b'\n', &mut bytes) {
+                Ok(0) => {
+                    self.finished = true;
+                    Ok(None)
+                },
+                Ok(_) => {
+                    if bytes.ends_with(b"\n") {
+                        bytes.pop();
+                        if bytes.ends_with(b"\r") {
+                            bytes.pop();
+                        }
+                    }
+                    Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+                },
+                Err(e) => Err(format!("错误: 读取标准输入失败: {}", e)),
+            }
+        }
+    }
+    
+    static READERS: Mutex<HandleTable<LineReader>> = Mutex::new(HandleTable::new());
+    
+    fn lock_readers() -> MutexGuard<'static, HandleTable<LineReader>> {
+        READERS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    
+    pub(super) fn open() -> String {
+        lock_readers().insert(LineReader { peeked: None, finished: false }).to_string()
+    }
+    
+    // 检查是否还有下一行，需要时会等待输入
+    // 参数: handle
+    pub fn cn_has_next(args: Vec<String>) -> String {
+        let mut readers = lock_readers();
+        let reader = match args.first().and_then(|arg| readers.parse_handle(arg)).and_then(|id| readers.get_mut(id)) {
+            Some(reader) => reader,
+            None => return "错误: 无效的读取句柄".to_string(),
+        };
+        if reader.peeked.is_none() {
+            match reader.advance() {
+                Ok(next) => reader.peeked = next,
+                Err(e) => return e,
+            }
+        }
+        reader.peeked.is_some().to_string()
+    }
+    
+    // 读取下一行，没有更多行时返回错误信息
+    // 参数: handle
+    pub fn cn_next(args: Vec<String>) -> String {
+        let mut readers = lock_readers();
+        let reader = match args.first().and_then(|arg| readers.parse_handle(arg)).and_then(|id| readers.get_mut(id)) {
+            Some(reader) => reader,
+            None => return "错误: 无效的读取句柄".to_string(),
+        };
+        let next = match reader.peeked.take() {
+            Some(line) => Ok(Some(line)),
+            None => reader.advance(),
+        };
+        match next {
+            Ok(Some(line)) => line,
+            Ok(None) => "错误: 没有更多行".to_string(),
+            Err(e) => e,
+        }
+    }
+    
+    // 关闭读取句柄
+    // 参数: handle
+    pub fn cn_close(args: Vec<String>) -> String {
+        let mut readers = lock_readers();
+        match args.first().and_then(|arg| readers.parse_handle(arg)) {
+            Some(id) => {
+                readers.remove(id);
+                "true".to_string()
+            },
+            None => "false".to_string(),
+        }
+    }
+}
+
+// 表格格式化
+mod table {
+    use super::*;
+    
+    #[derive(Clone, Copy, PartialEq)]
+    enum Align {
+        Left,
+        Right,
+        Center,
+    }
+    
+    // 边框字符：横线、竖线，以及上、中、下三条分隔线的左、中、右交叉点
+    struct Border {
+        horizontal: &'static str,
+        vertical: &'static str,
+        top: [&'static str; 3],
+        middle: [&'static str; 3],
+        bottom: [&'static str; 3],
+    }
+    
+    const ASCII: Border = Border {
+        horizontal: "-",
+        vertical: "|",
+        top: ["+", "+", "+"],
+        middle: ["+", "+", "+"],
+        bottom: ["+", "+", "+"],
+    };
+    
+    const UNICODE: Border = Border {
+        horizontal: "─",
+        vertical: "│",
+        top: ["┌", "┬", "┐"],
+        middle: ["├", "┼", "┤"],
+        bottom: ["└", "┴", "┘"],
+    };
+    
+    pub(super) fn format(args: &[String]) -> Result<String, String> {
+        let rows = match args.first().map(|r| serde_json::from_str::<JsonValue>(r)) {
+            Some(Ok(JsonValue::Array(rows))) => rows,
+            _ => return Err("错误: 数据必须是数组".to_string()),
+        };
+        let options = match args.get(1).map(|o| o.trim()).filter(|o| !o.is_empty()) {
+            Some(options) => match serde_json::from_str::<JsonValue>(options) {
+                Ok(JsonValue::Object(options)) => options,
+                _ => return Err("错误: 选项必须是 Map".to_string()),
+            },
+            None => serde_json::Map::new(),
+        };
+        let string_list = |key: &str| -> Result<Option<Vec<String>>, String> {
+            match options.get(key) {
+                Some(JsonValue::Array(items)) => Ok(Some(items.iter().map(cell_text).collect())),
+                Some(_) => Err(format!("错误: 选项 {} 必须是数组", key)),
+                None => Ok(None),
+            }
+        };
+        let mut headers = string_list("headers")?;
+        let columns = string_list("columns")?;
+        let border = match options.get("border").and_then(|b| b.as_str()).unwrap_or("ascii") {
+            "ascii" => Some(&ASCII),
+            "unicode" => Some(&UNICODE),
+            "none" => None,
+            other => return Err(format!("错误: 未知的边框样式: {}", other)),
+        };
+        let max_width = match options.get("max_width") {
+            Some(width) => match width.as_u64() {
+                Some(width) if width > 0 => Some(width as usize),
+                _ => return Err("错误: max_width 必须是正整数".to_string()),
+            },
+            None => None,
+        };
+        
+        // 统一为单元格的二维数组，同时记录哪些单元格是数字
+        let mut cells: Vec<Vec<(String, bool)>> = Vec::with_capacity(rows.len());
+        if rows.iter().any(|row| row.is_object()) {
+            let keys = match columns {
+                Some(columns) => columns,
+                None => match rows.iter().find_map(|row| row.as_object()) {
+                    Some(first) => first.keys().cloned().collect(),
+                    None => Vec::new(),
+                },
+            };
+            for row in &rows {
+                let row = row.as_object().ok_or_else(|| "错误: 所有行必须都是 Map 或都是数组".to_string())?;
+                cells.push(keys.iter().map(|key| row.get(key).map(cell).unwrap_or_default()).collect());
+            }
+            headers.get_or_insert(keys);
+        } else {
+            for row in &rows {
+                match row {
+                    JsonValue::Array(items) => cells.push(items.iter().map(cell).collect()),
+                    other => cells.push(vec![cell(other)]),
                 }
-            } else {
-                // 普通字符，直接添加
-                result.push(c);
             }
         }
         
-        // 打印结果
-        print!("{}", result);
-        io::stdout().flush().unwrap();
+        let column_count = cells.iter().map(Vec::len).chain(headers.as_ref().map(Vec::len)).max().unwrap_or(0);
+        if column_count == 0 {
+            return Ok(String::new());
+        }
+        let aligns = match options.get("align") {
+            None => None,
+            Some(JsonValue::String(align)) => Some(vec![parse_align(align)?; column_count]),
+            Some(JsonValue::Array(items)) => Some(items.iter()
+                .map(|item| parse_align(item.as_str().unwrap_or("")))
+                .collect::<Result<Vec<_>, _>>()?),
+            Some(_) => return Err("错误: align 必须是字符串或数组".to_string()),
+        };
+        
+        let truncate_cell = |text: &str| match max_width {
+            Some(width) => truncate(text, width),
+            None => text.to_string(),
+        };
+        let headers: Option<Vec<String>> = headers.map(|headers| {
+            (0..column_count).map(|i| truncate_cell(headers.get(i).map(String::as_str).unwrap_or(""))).collect()
+        });
+        let cells: Vec<Vec<(String, bool)>> = cells.into_iter().map(|row| {
+            (0..column_count).map(|i| match row.get(i) {
+                Some((text, numeric)) => (truncate_cell(text), *numeric),
+                None => (String::new(), false),
+            }).collect()
+        }).collect();
         
+        let mut widths = vec![0; column_count];
+        let header_texts = headers.iter().flatten().enumerate();
+        let cell_texts = cells.iter().flat_map(|row| row.iter().map(|(text, _)| text).enumerate());
+        for (i, text) in header_texts.chain(cell_texts) {
+            widths[i] = widths[i].max(display_width(text));
+        }
+        
+        let render_row = |row: Vec<(&str, Align)>| -> String {
+            let padded: Vec<String> = row.iter().enumerate().map(|(i, (text, align))| pad(text, widths[i], *align)).collect();
+            match border {
+                Some(border) => format!("{} {} {}", border.vertical, padded.join(&format!(" {} ", border.vertical)), border.vertical),
+                None => padded.join("  ").trim_end().to_string(),
+            }
+        };
+        let rule = |corners: &[&str; 3]| -> Option<String> {
+            border.map(|border| {
+                let segments: Vec<String> = widths.iter().map(|width| border.horizontal.repeat(width + 2)).collect();
+                format!("{}{}{}", corners[0], segments.join(corners[1]), corners[2])
+            })
+        };
+        
+        let mut lines = Vec::new();
+        lines.extend(border.and_then(|border| rule(&border.top)));
+        if let Some(headers) = &headers {
+            let align = |i: usize| aligns.as_ref().and_then(|aligns| aligns.get(i).copied()).unwrap_or(Align::Left);
+            lines.push(render_row(headers.iter().enumerate().map(|(i, text)| (text.as_str(), align(i))).collect()));
+            match border {
+                Some(border) => lines.extend(rule(&border.middle)),
+                None => lines.push(widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<_>>().join("  ")),
+            }
+        }
+        for row in &cells {
+            let row = row.iter().enumerate().map(|(i, (text, numeric))| {
+                let default = if *numeric { Align::Right } else { Align::Left };
+                let align = aligns.as_ref().and_then(|aligns| aligns.get(i).copied()).unwrap_or(default);
+                (text.as_str(), align)
+            }).collect();
+            lines.push(render_row(row));
+        }
+        lines.extend(border.and_then(|border| rule(&border.bottom)));
+        Ok(lines.join("\n"))
+    }
+    
+    fn parse_align(name: &str) -> Result<Align, String> {
+        match name {
+            "left" => Ok(Align::Left),
+            "right" => Ok(Align::Right),
+            "center" => Ok(Align::Center),
+            other => Err(format!("错误: 未知的对齐方式: {}", other)),
+        }
+    }
+    
+    // 单元格文本和是否为数字；null 显示为空
+    fn cell(value: &JsonValue) -> (String, bool) {
+        (cell_text(value), value.is_number())
+    }
+    
+    fn cell_text(value: &JsonValue) -> String {
+        match value {
+            JsonValue::String(text) => text.replace(['\n', '\r', '\t'], " "),
+            JsonValue::Null => String::new(),
+            other => other.to_string(),
+        }
+    }
+    
+    fn pad(text: &str, width: usize, align: Align) -> String {
+        let space = width.saturating_sub(display_width(text));
+        match align {
+            Align::Left => format!("{}{}", text, " ".repeat(space)),
+            Align::Right => format!("{}{}", " ".repeat(space), text),
+            Align::Center => format!("{}{}{}", " ".repeat(space / 2), text, " ".repeat(space - space / 2)),
+        }
+    }
+    
+    // 截断到最大显示宽度，截断时以 … 结尾
+    fn truncate(text: &str, max: usize) -> String {
+        if display_width(text) <= max {
+            return text.to_string();
+        }
+        let mut result = String::new();
+        let mut width = 0;
+        for c in text.chars() {
+            let char_width = char_width(c);
+            if width + char_width + 1 > max {
+                break;
+            }
+            result.push(c);
+            width += char_width;
+        }
+        result.push('…');
         result
     }
+    
+    // 终端中的显示宽度：东亚宽字符占两列，ANSI转义序列不占宽度
+    fn display_width(text: &str) -> usize {
+        let mut width = 0;
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                // 跳过参数，直到终止字母
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            } else {
+                width += char_width(c);
+            }
+        }
+        width
+    }
+    
+    fn char_width(c: char) -> usize {
+        match c as u32 {
+            0x0300..=0x036F | 0x200B..=0x200F => 0,
+            0x1100..=0x115F | 0x2E80..=0x303E | 0x3041..=0x33FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF
+            | 0xA000..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF | 0xFE30..=0xFE4F | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6 | 0x1F300..=0x1F64F | 0x1F900..=0x1F9FF | 0x20000..=0x3FFFD => 2,
+            _ => 1,
+        }
+    }
+}
+
+// 进度条命名空间
+// 进度条和旋转指示器与 term 库共用 cn_common::progress 的实现，显示在标准错误中，标准错误不是终端时不显示；
+// 多个进度条各占一行。本库写标准输出和标准错误时会先擦除进度条、写完后重新显示，避免输出与进度条混在一起；
+// 输出不以换行结尾时（如输入提示）暂不显示进度条，直到下一次输出换行
+mod progress {
+    use super::*;
+    use cn_common::progress as display;
+    pub(super) use cn_common::progress::{line_entered, suspend};
+    
+    // 开始显示进度条，返回句柄；total 为0时显示不确定进度的旋转指示器
+    // 参数: total, [message]
+    pub fn cn_start(args: Vec<String>) -> String {
+        let total = match args.first().map(|t| t.trim().parse::<u64>()) {
+            Some(Ok(total)) => total,
+            _ => return "错误: 总数必须是非负整数".to_string(),
+        };
+        let message = args.get(1).map(|m| process_escape_chars(m)).unwrap_or_default();
+        if total == 0 {
+            return display::spinner(message).to_string();
+        }
+        match display::bar(total, None, message) {
+            Ok(handle) => handle.to_string(),
+            Err(e) => e,
+        }
+    }
+    
+    // 推进进度，返回当前进度；进度不会超过总数
+    // 参数: handle, [n]（默认为1）
+    pub fn cn_tick(args: Vec<String>) -> String {
+        let n = match args.get(1).map(|n| n.trim().parse::<u64>()) {
+            Some(Ok(n)) => n,
+            None => 1,
+            Some(Err(_)) => return format!("错误: 增量必须是非负整数: {}", args[1]),
+        };
+        update(args.first(), |bar| bar.position().saturating_add(n))
+    }
+    
+    // 设置进度，返回当前进度
+    // 参数: handle, position
+    pub fn cn_set(args: Vec<String>) -> String {
+        let position = match args.get(1).map(|n| n.trim().parse::<u64>()) {
+            Some(Ok(position)) => position,
+            _ => return "错误: 进度必须是非负整数".to_string(),
+        };
+        update(args.first(), |_| position)
+    }
+    
+    // 修改显示的说明文字
+    // 参数: handle, message
+    pub fn cn_set_message(args: Vec<String>) -> String {
+        let message = args.get(1).map(|m| process_escape_chars(m)).unwrap_or_default();
+        match display::with_bar(args.first(), |bar| bar.set_message(message)) {
+            Some(()) => "true".to_string(),
+            None => "错误: 无效的进度条句柄".to_string(),
+        }
+    }
+    
+    // 结束进度条，在原位置留下最终状态；message 替换最后显示的说明文字
+    // 参数: handle, [message]
+    pub fn cn_finish(args: Vec<String>) -> String {
+        let message = args.get(1).map(|m| process_escape_chars(m));
+        if display::finish(args.first(), message, true) {
+            "true".to_string()
+        } else {
+            "错误: 无效的进度条句柄".to_string()
+        }
+    }
+    
+    // 按 position 计算新的进度并设置，进度条的进度不超过总数，返回设置后的进度
+    fn update<F: FnOnce(&display::ProgressBar) -> u64>(handle: Option<&String>, position: F) -> String {
+        let result = display::with_bar(handle, |bar| {
+            let position = match bar.length() {
+                Some(total) if total > 0 => position(bar).min(total),
+                _ => position(bar),
+            };
+            bar.set_position(position);
+            position
+        });
+        match result {
+            Some(position) => position.to_string(),
+            None => "错误: 无效的进度条句柄".to_string(),
+        }
+    }
 }
 
 // 初始化函数，返回函数映射
@@ -146,7 +1325,43 @@ pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
          .add_function("echo", std::cn_println)
          .add_function("read_line", std::cn_read_line)
          .add_function("input", std::cn_read_line) //别名
-         .add_function("printf", std::cn_printf);
+         .add_function("read_all", std::cn_read_all)
+         .add_function("read_lines", std::cn_read_lines)
+         .add_function("is_tty", std::cn_is_tty)
+         .add_function("read_int", std::cn_read_int)
+         .add_function("read_float", std::cn_read_float)
+         .add_function("read_bool", std::cn_read_bool)
+         .add_function("read_choice", std::cn_read_choice)
+         .add_function("read_password", std::cn_read_password)
+         .add_function("printf", std::cn_printf)
+         .add_function("eprint", std::cn_eprint)
+         .add_function("eprintln", std::cn_eprintln)
+         .add_function("exit", std::cn_exit)
+         .add_function("set_buffered", std::cn_set_buffered)
+         .add_function("flush", std::cn_flush)
+         .add_function("write_bytes", std::cn_write_bytes)
+         .add_function("print_table", std::cn_print_table)
+         .add_function("format_table", std::cn_format_table)
+         .add_function("color_print", std::cn_color_print)
+         .add_function("color_println", std::cn_color_println)
+         .add_function("markup", std::cn_markup)
+         .add_function("markup_print", std::cn_markup_print)
+         .add_function("markup_println", std::cn_markup_println)
+         .add_function("set_color", std::cn_set_color);
+    
+    // 注册标准输入逐行读取命名空间下的函数
+    let lines_ns = registry.namespace("lines");
+    lines_ns.add_function("has_next", lines::cn_has_next)
+           .add_function("next", lines::cn_next)
+           .add_function("close", lines::cn_close);
+    
+    // 注册进度条命名空间下的函数
+    let progress_ns = registry.namespace("progress");
+    progress_ns.add_function("start", progress::cn_start)
+              .add_function("tick", progress::cn_tick)
+              .add_function("set", progress::cn_set)
+              .add_function("set_message", progress::cn_set_message)
+              .add_function("finish", progress::cn_finish);
     /*
     // 同时注册为直接函数，不需要命名空间前缀
     registry.add_direct_function("print", std::cn_print)
@@ -1,60 +1,65 @@
 use ::std::collections::HashMap;
-use serde_json::{Value as JsonValue, json, Map};
+use serde_json::{Value as JsonValue, Map};
+use serde_json_path::JsonPath;
 
 // 导入通用库
-use cn_common::namespace::{LibraryFunction, NamespaceBuilder, create_library_pointer, LibraryRegistry};
+use cn_common::handle::HandleTable;
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::{callback_value, json_value, parse_path, returned_json, serialize, thrown_message, PathSegment};
+use std::sync::{Mutex, MutexGuard};
 
 // JSON命名空间
 mod json {
     use super::*;
 
-    // 解析JSON字符串
+    // 解析JSON字符串，返回原生值：对象转换为 Map，数组转换为 Array，可以直接用脚本的索引和方法访问
     pub fn cn_parse(args: Vec<String>) -> String {
         if args.is_empty() {
             return "错误: 未提供JSON字符串".to_string();
         }
         
-        let json_str = &args[0];
-        
-        // 尝试处理可能的转义问题
-        let processed_str = preprocess_json_string(json_str);
-        
-        // 尝试从HTTP响应中提取JSON部分
-        let json_content = extract_json_from_http_response(&processed_str);
-        
-        // 尝试解析JSON
-        match serde_json::from_str::<JsonValue>(&json_content) {
-            Ok(value) => {
-                // 解析成功，返回格式化的JSON
-                match serde_json::to_string_pretty(&value) {
-                    Ok(pretty) => pretty,
-                    Err(e) => format!("错误: 格式化JSON失败: {}", e)
-                }
-            },
-            Err(e) => {
-                // 尝试修复常见的JSON格式问题
-                let fixed_json = fix_json_string(&json_content);
-                match serde_json::from_str::<JsonValue>(&fixed_json) {
-                    Ok(value) => {
-                        // 修复后解析成功
-                        match serde_json::to_string_pretty(&value) {
-                            Ok(pretty) => pretty,
-                            Err(e) => format!("错误: 格式化JSON失败: {}", e)
-                        }
-                    },
-                    Err(_) => format!("错误: 解析JSON失败: {}", e)
-                }
-            }
+        match parse_document(&args[0]) {
+            Ok(value) => json_value(&value.to_string()),
+            Err(e) => e,
+        }
+    }
+
+    // 将脚本中的值序列化为JSON字符串
+    // Map 和 Array 以JSON形式传入；无法按JSON解析的参数视为普通字符串
+    // 参数: value, [indent]（缩进空格数，省略时输出紧凑格式）
+    pub fn cn_stringify(args: Vec<String>) -> String {
+        let value = match args.first() {
+            Some(arg) => serde_json::from_str::<JsonValue>(arg).unwrap_or_else(|_| JsonValue::String(arg.clone())),
+            None => JsonValue::Null,
+        };
+        match args.get(1).map(|indent| indent.trim().parse::<usize>()) {
+            Some(Ok(width)) if width <= 16 => pretty_with_indent(&value, &" ".repeat(width)),
+            Some(_) => format!("错误: 无效的缩进: {}", args[1]),
+            None => serialize(&value),
         }
     }
     
     // 格式化JSON字符串
+    // 参数: json, [indent]（缩进空格数，默认2；"tab" 表示使用制表符缩进）
     pub fn cn_format(args: Vec<String>) -> String {
         if args.is_empty() {
             return "错误: 未提供JSON字符串".to_string();
         }
         
         let json_str = &args[0];
+        if let Some(indent) = args.get(1) {
+            let indent = match indent.as_str() {
+                "\t" | "\\t" | "tab" => "\t".to_string(),
+                width => match width.trim().parse::<usize>() {
+                    Ok(width) if width <= 16 => " ".repeat(width),
+                    _ => return format!("错误: 无效的缩进: {}", width),
+                },
+            };
+            return match parse_document(json_str) {
+                Ok(value) => pretty_with_indent(&value, &indent),
+                Err(e) => e,
+            };
+        }
         
         // 尝试处理可能的转义问题
         let processed_str = preprocess_json_string(json_str);
@@ -334,6 +339,593 @@ mod json {
         }
     }
     
+    // 设置指定路径的值，路径中不存在的对象属性会自动创建，返回修改后的JSON
+    // 参数: json, path, value
+    pub fn cn_set_value(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "错误: 请提供JSON字符串、路径和值".to_string();
+        }
+
+        let mut document = match parse_document(&args[0]) {
+            Ok(value) => value,
+            Err(e) => return e,
+        };
+        let segments = match parse_path(&args[1]) {
+            Ok(segments) => segments,
+            Err(e) => return e,
+        };
+
+        match set_at_path(&mut document, &segments, parse_value_arg(&args[2])) {
+            Ok(()) => serialize(&document),
+            Err(e) => e,
+        }
+    }
+
+    // 删除指定路径的属性或数组元素，返回修改后的JSON
+    // 参数: json, path
+    pub fn cn_delete(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供JSON字符串和路径".to_string();
+        }
+
+        let mut document = match parse_document(&args[0]) {
+            Ok(value) => value,
+            Err(e) => return e,
+        };
+        let segments = match parse_path(&args[1]) {
+            Ok(segments) => segments,
+            Err(e) => return e,
+        };
+        let (last, parents) = match segments.split_last() {
+            Some(split) => split,
+            None => return "错误: 不能删除根节点".to_string(),
+        };
+
+        let parent = match value_at_path_mut(&mut document, parents) {
+            Ok(parent) => parent,
+            Err(e) => return e,
+        };
+        let removed = match (last, parent) {
+            (PathSegment::Key(key), JsonValue::Object(obj)) => obj.remove(key).is_some(),
+            (PathSegment::Index(idx), JsonValue::Array(arr)) if *idx < arr.len() => {
+                arr.remove(*idx);
+                true
+            },
+            _ => false,
+        };
+
+        if removed {
+            serialize(&document)
+        } else {
+            format!("错误: 路径不存在: {}", args[1])
+        }
+    }
+
+    // 向指定路径的数组末尾追加值，返回修改后的JSON
+    // 参数: json, path, value（path 为空字符串时表示根节点）
+    pub fn cn_push(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "错误: 请提供JSON字符串、路径和值".to_string();
+        }
+
+        let mut document = match parse_document(&args[0]) {
+            Ok(value) => value,
+            Err(e) => return e,
+        };
+        let segments = match parse_path(&args[1]) {
+            Ok(segments) => segments,
+            Err(e) => return e,
+        };
+
+        match value_at_path_mut(&mut document, &segments) {
+            Ok(JsonValue::Array(arr)) => arr.push(parse_value_arg(&args[2])),
+            Ok(_) => return format!("错误: 路径指向的不是数组: {}", args[1]),
+            Err(e) => return e,
+        }
+        serialize(&document)
+    }
+
+    // 更新已存在路径的值：对象与对象浅合并，其他情况直接替换，返回修改后的JSON
+    // 参数: json, path, value
+    pub fn cn_update(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "错误: 请提供JSON字符串、路径和值".to_string();
+        }
+
+        let mut document = match parse_document(&args[0]) {
+            Ok(value) => value,
+            Err(e) => return e,
+        };
+        let segments = match parse_path(&args[1]) {
+            Ok(segments) => segments,
+            Err(e) => return e,
+        };
+
+        let target = match value_at_path_mut(&mut document, &segments) {
+            Ok(target) => target,
+            Err(e) => return e,
+        };
+        match (target, parse_value_arg(&args[2])) {
+            (JsonValue::Object(existing), JsonValue::Object(updates)) => existing.extend(updates),
+            (target, value) => *target = value,
+        }
+        serialize(&document)
+    }
+
+    // 使用JSONPath查询，返回所有匹配值组成的数组
+    // 支持通配符 [*]、递归下降 ..、切片 [start:end:step] 和过滤器 [?@.price < 10]
+    // 参数: json, path
+    pub fn cn_query(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供JSON字符串和JSONPath表达式".to_string();
+        }
+
+        let document = match parse_document(&args[0]) {
+            Ok(value) => value,
+            Err(e) => return e,
+        };
+        let path = match JsonPath::parse(args[1].trim()) {
+            Ok(path) => path,
+            Err(e) => return format!("错误: 无效的JSONPath表达式: {}", e),
+        };
+
+        let matches: Vec<&JsonValue> = path.query(&document).all();
+        json_value(&JsonValue::Array(matches.into_iter().cloned().collect()).to_string())
+    }
+
+    // 使用JSON Schema（常用子集）校验JSON
+    // 返回 {"valid": bool, "errors": [{"path": "$.a.b", "message": "..."}]}
+    // 参数: json, schema
+    pub fn cn_validate(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供JSON字符串和schema".to_string();
+        }
+
+        let document = match parse_document(&args[0]) {
+            Ok(value) => value,
+            Err(e) => return e,
+        };
+        let schema = match parse_document(&args[1]) {
+            Ok(value) => value,
+            Err(e) => return format!("错误: 无效的schema: {}", e.trim_start_matches("错误: ")),
+        };
+
+        let errors = super::schema::validate(&document, &schema);
+        let result = serde_json::json!({
+            "valid": errors.is_empty(),
+            "errors": errors.iter().map(|e| e.to_json()).collect::<Vec<_>>(),
+        });
+        serialize(&result)
+    }
+
+    // 比较两个JSON文档，返回把 a 变为 b 的 JSON Patch（RFC 6902）操作数组
+    // 参数: a, b
+    pub fn cn_diff(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供两个JSON文档".to_string();
+        }
+
+        let (left, right) = match (parse_document(&args[0]), parse_document(&args[1])) {
+            (Ok(left), Ok(right)) => (left, right),
+            (Err(e), _) | (_, Err(e)) => return e,
+        };
+        match serde_json::to_value(json_patch::diff(&left, &right)) {
+            Ok(patch) => json_value(&patch.to_string()),
+            Err(e) => format!("错误: 序列化结果失败: {}", e),
+        }
+    }
+
+    // 应用 JSON Patch（RFC 6902），任一操作失败时不修改文档并返回错误
+    // 参数: json, patch
+    pub fn cn_patch(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供JSON文档和补丁".to_string();
+        }
+
+        let mut document = match parse_document(&args[0]) {
+            Ok(value) => value,
+            Err(e) => return e,
+        };
+        let patch = match parse_document(&args[1]).and_then(|value| {
+            serde_json::from_value::<json_patch::Patch>(value).map_err(|e| format!("错误: 无效的JSON Patch: {}", e))
+        }) {
+            Ok(patch) => patch,
+            Err(e) => return e,
+        };
+
+        match json_patch::patch(&mut document, &patch) {
+            Ok(()) => serialize(&document),
+            Err(e) => format!("错误: 应用补丁失败: {}", e),
+        }
+    }
+
+    // 应用 JSON Merge Patch（RFC 7386）：递归合并对象，值为 null 的属性会被删除
+    // 参数: json, patch
+    pub fn cn_merge_patch(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供JSON文档和补丁".to_string();
+        }
+
+        let (mut document, patch) = match (parse_document(&args[0]), parse_document(&args[1])) {
+            (Ok(document), Ok(patch)) => (document, patch),
+            (Err(e), _) | (_, Err(e)) => return e,
+        };
+        json_patch::merge(&mut document, &patch);
+        serialize(&document)
+    }
+
+    // 打开大JSON文件进行流式读取，返回迭代句柄
+    // json_pointer 指向要逐个读取元素的数组或对象（如 "/items"，空字符串表示根节点，"*" 匹配任意段）
+    // 数组逐个返回元素，对象逐个返回 {"key": ..., "value": ...}
+    // 参数: path, json_pointer
+    pub fn cn_stream_file(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供文件路径".to_string();
+        }
+
+        let pointer = args.get(1).map(|p| p.as_str()).unwrap_or("");
+        match super::stream::JsonStream::open(&args[0], pointer) {
+            Ok(stream) => lock_streams().insert(stream).to_string(),
+            Err(e) => e,
+        }
+    }
+
+    // 检查流中是否还有元素
+    // 参数: handle
+    pub fn cn_stream_has_next(args: Vec<String>) -> String {
+        let mut streams = lock_streams();
+        let stream = match args.first().and_then(|arg| streams.parse_handle(arg)).and_then(|id| streams.get_mut(id)) {
+            Some(stream) => stream,
+            None => return "错误: 无效的流句柄".to_string(),
+        };
+        match stream.has_next() {
+            Ok(has_next) => has_next.to_string(),
+            Err(e) => e,
+        }
+    }
+
+    // 读取流中的下一个元素，没有更多元素时返回错误信息
+    // 参数: handle
+    pub fn cn_stream_next(args: Vec<String>) -> String {
+        let mut streams = lock_streams();
+        let stream = match args.first().and_then(|arg| streams.parse_handle(arg)).and_then(|id| streams.get_mut(id)) {
+            Some(stream) => stream,
+            None => return "错误: 无效的流句柄".to_string(),
+        };
+        match stream.next_value() {
+            Ok(Some(value)) => serialize(&value),
+            Ok(None) => "错误: 没有更多元素".to_string(),
+            Err(e) => e,
+        }
+    }
+
+    // 关闭流并释放文件
+    // 参数: handle
+    pub fn cn_stream_close(args: Vec<String>) -> String {
+        let mut streams = lock_streams();
+        match args.first().and_then(|arg| streams.parse_handle(arg)) {
+            Some(id) => {
+                streams.remove(id);
+                "true".to_string()
+            },
+            None => "false".to_string(),
+        }
+    }
+
+    static STREAMS: Mutex<HandleTable<super::stream::JsonStream>> = Mutex::new(HandleTable::new());
+
+    fn lock_streams() -> MutexGuard<'static, HandleTable<super::stream::JsonStream>> {
+        STREAMS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // 压缩JSON，去除所有空白
+    // 参数: json
+    pub fn cn_minify(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 未提供JSON字符串".to_string();
+        }
+        match parse_document(&args[0]) {
+            Ok(value) => serialize(&value),
+            Err(e) => e,
+        }
+    }
+
+    // 规范化JSON（参照 RFC 8785）：键按字典序排列、无空白、数字使用稳定格式，适合比较差异、计算哈希和签名
+    // 参数: json
+    pub fn cn_canonicalize(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 未提供JSON字符串".to_string();
+        }
+        match parse_document(&args[0]) {
+            Ok(value) => {
+                let mut output = String::new();
+                write_canonical(&value, &mut output);
+                output
+            },
+            Err(e) => e,
+        }
+    }
+
+    fn write_canonical(value: &JsonValue, output: &mut String) {
+        match value {
+            JsonValue::Number(n) => {
+                if n.is_i64() || n.is_u64() {
+                    output.push_str(&n.to_string());
+                } else {
+                    output.push_str(&canonical_number(n.as_f64().unwrap_or(0.0)));
+                }
+            },
+            JsonValue::Array(items) => {
+                output.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        output.push(',');
+                    }
+                    write_canonical(item, output);
+                }
+                output.push(']');
+            },
+            JsonValue::Object(obj) => {
+                // 按UTF-16编码单元排序键
+                let mut entries: Vec<(&String, &JsonValue)> = obj.iter().collect();
+                entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+                output.push('{');
+                for (i, (key, item)) in entries.into_iter().enumerate() {
+                    if i > 0 {
+                        output.push(',');
+                    }
+                    output.push_str(&JsonValue::String(key.clone()).to_string());
+                    output.push(':');
+                    write_canonical(item, output);
+                }
+                output.push('}');
+            },
+            other => output.push_str(&other.to_string()),
+        }
+    }
+
+    // 按 ECMAScript Number.prototype.toString 规则格式化浮点数，例如 1.0 -> "1"，1e21 -> "1e+21"
+    fn canonical_number(n: f64) -> String {
+        if n == 0.0 || !n.is_finite() {
+            return "0".to_string();
+        }
+
+        // 利用Rust的最短表示得到有效数字和指数
+        let scientific = format!("{:e}", n.abs());
+        let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+        let digits: String = mantissa.chars().filter(|c| c.is_ascii_digit()).collect();
+        let k = digits.len() as i32;
+        let point = exponent.parse::<i32>().unwrap_or(0) + 1;
+
+        let body = if k <= point && point <= 21 {
+            format!("{}{}", digits, "0".repeat((point - k) as usize))
+        } else if 0 < point && point <= 21 {
+            format!("{}.{}", &digits[..point as usize], &digits[point as usize..])
+        } else if -6 < point && point <= 0 {
+            format!("0.{}{}", "0".repeat((-point) as usize), digits)
+        } else {
+            let fraction = if k > 1 { format!(".{}", &digits[1..]) } else { String::new() };
+            let sign = if point > 0 { "+" } else { "-" };
+            format!("{}{}e{}{}", &digits[..1], fraction, sign, (point - 1).abs())
+        };
+
+        if n < 0.0 { format!("-{}", body) } else { body }
+    }
+
+    // 使用指定缩进格式化JSON
+    fn pretty_with_indent(value: &JsonValue, indent: &str) -> String {
+        if indent.is_empty() {
+            return serialize(value);
+        }
+        let mut buffer = Vec::new();
+        let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+        let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, formatter);
+        match serde::Serialize::serialize(value, &mut serializer) {
+            Ok(()) => String::from_utf8(buffer).unwrap_or_default(),
+            Err(e) => format!("错误: 格式化JSON失败: {}", e),
+        }
+    }
+
+    // 将JSON数组转换为CSV文本
+    // 元素为对象时每个对象一行，列名默认为所有对象键的并集（按出现顺序，同一对象内按键名排序）；
+    // 元素为数组时直接作为一行。嵌套的对象和数组以JSON字符串写入单元格，null 写为空单元格
+    // 参数: json_array, [columns]（JSON数组或逗号分隔的列名）
+    pub fn cn_to_csv(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供JSON数组".to_string();
+        }
+
+        let rows = match parse_document(&args[0]) {
+            Ok(JsonValue::Array(rows)) => rows,
+            Ok(_) => return "错误: 输入必须是JSON数组".to_string(),
+            Err(e) => return e,
+        };
+        let columns: Vec<String> = match args.get(1).map(|c| c.trim()).filter(|c| !c.is_empty()) {
+            Some(spec) => match serde_json::from_str::<Vec<String>>(spec) {
+                Ok(columns) => columns,
+                Err(_) => spec.split(',').map(|c| c.trim().to_string()).collect(),
+            },
+            None => {
+                let mut columns: Vec<String> = Vec::new();
+                for key in rows.iter().filter_map(|row| row.as_object()).flat_map(|obj| obj.keys()) {
+                    if !columns.contains(key) {
+                        columns.push(key.clone());
+                    }
+                }
+                columns
+            },
+        };
+
+        let mut writer = csv::WriterBuilder::new().flexible(true).from_writer(Vec::new());
+        let mut write_all = || -> Result<(), csv::Error> {
+            if !columns.is_empty() {
+                writer.write_record(&columns)?;
+            }
+            for row in &rows {
+                let cells: Vec<String> = match row {
+                    JsonValue::Object(obj) => columns.iter().map(|c| csv_cell(obj.get(c))).collect(),
+                    JsonValue::Array(items) => items.iter().map(|item| csv_cell(Some(item))).collect(),
+                    other => vec![csv_cell(Some(other))],
+                };
+                writer.write_record(&cells)?;
+            }
+            writer.flush()?;
+            Ok(())
+        };
+        if let Err(e) = write_all() {
+            return format!("错误: 生成CSV失败: {}", e);
+        }
+
+        match writer.into_inner() {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(e) => format!("错误: 生成CSV失败: {}", e),
+        }
+    }
+
+    // 将CSV文本转换为数组，并推断数字、布尔值和空值的类型
+    // has_header 为 true（默认）时每行转换为以表头为键的对象，否则每行转换为数组
+    // 参数: text, [has_header]
+    pub fn cn_from_csv(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供CSV文本".to_string();
+        }
+
+        let has_header = args.get(1).map(|h| h.trim() != "false").unwrap_or(true);
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(has_header)
+            .flexible(true)
+            .from_reader(args[0].as_bytes());
+
+        let headers: Vec<String> = if has_header {
+            match reader.headers() {
+                Ok(headers) => headers.iter().map(|h| h.to_string()).collect(),
+                Err(e) => return format!("错误: 解析CSV失败: {}", e),
+            }
+        } else {
+            Vec::new()
+        };
+
+        let mut rows = Vec::new();
+        for record in reader.records() {
+            let record = match record {
+                Ok(record) => record,
+                Err(e) => return format!("错误: 解析CSV失败: {}", e),
+            };
+            if has_header {
+                let mut obj = Map::new();
+                for (i, cell) in record.iter().enumerate() {
+                    let key = headers.get(i).cloned().unwrap_or_else(|| format!("column{}", i + 1));
+                    obj.insert(key, infer_csv_value(cell));
+                }
+                rows.push(JsonValue::Object(obj));
+            } else {
+                rows.push(JsonValue::Array(record.iter().map(infer_csv_value).collect()));
+            }
+        }
+        json_value(&JsonValue::Array(rows).to_string())
+    }
+
+    // 将JSON值转换为CSV单元格文本
+    fn csv_cell(value: Option<&JsonValue>) -> String {
+        match value {
+            None | Some(JsonValue::Null) => String::new(),
+            Some(JsonValue::String(s)) => s.clone(),
+            Some(other) => other.to_string(),
+        }
+    }
+
+    // 推断CSV单元格的类型：空单元格为 null，其次尝试整数、浮点数和布尔值，否则为字符串
+    fn infer_csv_value(cell: &str) -> JsonValue {
+        let trimmed = cell.trim();
+        if trimmed.is_empty() {
+            return JsonValue::Null;
+        }
+        // 带前导零的数字（如邮编 "007"）保持为字符串
+        let leading_zero = trimmed.len() > 1 && trimmed.starts_with('0') && !trimmed.starts_with("0.");
+        if !leading_zero {
+            if let Ok(n) = trimmed.parse::<i64>() {
+                return JsonValue::from(n);
+            }
+            if let Some(n) = trimmed.parse::<f64>().ok().filter(|n| n.is_finite()).and_then(serde_json::Number::from_f64) {
+                return JsonValue::Number(n);
+            }
+        }
+        match trimmed {
+            "true" => JsonValue::Bool(true),
+            "false" => JsonValue::Bool(false),
+            _ => JsonValue::String(cell.to_string()),
+        }
+    }
+
+    // 获取路径对应值的可变引用
+    fn value_at_path_mut<'a>(value: &'a mut JsonValue, segments: &[PathSegment]) -> Result<&'a mut JsonValue, String> {
+        let mut current = value;
+        for segment in segments {
+            current = match (segment, current) {
+                (PathSegment::Key(key), JsonValue::Object(obj)) => match obj.get_mut(key) {
+                    Some(v) => v,
+                    None => return Err(format!("错误: 属性不存在: {}", key)),
+                },
+                (PathSegment::Index(idx), JsonValue::Array(arr)) => match arr.get_mut(*idx) {
+                    Some(v) => v,
+                    None => return Err(format!("错误: 无效的数组索引: [{}]", idx)),
+                },
+                (PathSegment::Key(key), _) => return Err(format!("错误: 不是JSON对象，无法访问属性: {}", key)),
+                (PathSegment::Index(idx), _) => return Err(format!("错误: 不是JSON数组，无法访问索引: [{}]", idx)),
+            };
+        }
+        Ok(current)
+    }
+
+    // 在路径上设置值，缺失的对象属性会被创建，数组索引等于长度时追加
+    fn set_at_path(value: &mut JsonValue, segments: &[PathSegment], new_value: JsonValue) -> Result<(), String> {
+        let (first, rest) = match segments.split_first() {
+            Some(split) => split,
+            None => {
+                *value = new_value;
+                return Ok(());
+            }
+        };
+
+        // null 节点视为空容器，便于逐层创建
+        if value.is_null() {
+            *value = match first {
+                PathSegment::Key(_) => JsonValue::Object(Map::new()),
+                PathSegment::Index(_) => JsonValue::Array(Vec::new()),
+            };
+        }
+
+        let child = match (first, value) {
+            (PathSegment::Key(key), JsonValue::Object(obj)) => obj.entry(key.clone()).or_insert(JsonValue::Null),
+            (PathSegment::Index(idx), JsonValue::Array(arr)) => {
+                if *idx == arr.len() {
+                    arr.push(JsonValue::Null);
+                }
+                match arr.get_mut(*idx) {
+                    Some(v) => v,
+                    None => return Err(format!("错误: 无效的数组索引: [{}]", idx)),
+                }
+            },
+            (PathSegment::Key(key), _) => return Err(format!("错误: 不是JSON对象，无法设置属性: {}", key)),
+            (PathSegment::Index(idx), _) => return Err(format!("错误: 不是JSON数组，无法设置索引: [{}]", idx)),
+        };
+        set_at_path(child, rest, new_value)
+    }
+
+    // 解析JSON文档参数（处理转义、HTTP响应和常见格式问题）
+    fn parse_document(input: &str) -> Result<JsonValue, String> {
+        let processed_str = preprocess_json_string(input);
+        let json_content = extract_json_from_http_response(&processed_str);
+        serde_json::from_str::<JsonValue>(&json_content)
+            .or_else(|e| serde_json::from_str::<JsonValue>(&fix_json_string(&json_content)).map_err(|_| e))
+            .map_err(|e| format!("错误: 解析JSON失败: {}", e))
+    }
+
+    // 将参数解析为JSON值，如果失败则作为字符串处理
+    fn parse_value_arg(value: &str) -> JsonValue {
+        serde_json::from_str::<JsonValue>(value).unwrap_or_else(|_| JsonValue::String(value.to_string()))
+    }
+
     // 预处理JSON字符串，处理可能的转义问题
     fn preprocess_json_string(input: &str) -> String {
         // 如果输入已经是有效的JSON，直接返回
@@ -359,16 +951,16 @@ mod json {
     
     // 从HTTP响应中提取JSON部分
     fn extract_json_from_http_response(response: &str) -> String {
-        // 检查是否是HTTP响应
-        if response.contains("状态码:") && response.contains("头信息:") && response.contains("响应体:") {
-            // 尝试提取响应体部分
-            if let Some(body_start) = response.find("响应体:") {
-                let body_content = &response[body_start + "响应体:".len()..].trim();
-                return body_content.to_string();
+        // http 库返回的响应 Map {"status", "headers", "body"}，取响应体
+        if let Ok(JsonValue::Object(obj)) = serde_json::from_str::<JsonValue>(response) {
+            if obj.len() == 3 && obj.contains_key("status") && obj.contains_key("headers") {
+                if let Some(JsonValue::String(body)) = obj.get("body") {
+                    return body.clone();
+                }
             }
         }
-        
-        // 如果不是HTTP响应或无法提取响应体，返回原始字符串
+
+        // 如果不是HTTP响应，返回原始字符串
         response.to_string()
     }
     
@@ -478,6 +1070,718 @@ mod json {
     }
 }
 
+// JSON Lines 命名空间（每行一个JSON值）
+mod jsonl {
+    use super::*;
+    use std::fs::{File, OpenOptions};
+    use std::io::{BufRead, BufReader, Lines, Write};
+
+    // map 的函数返回后，解释器调用的续接函数
+    const MAP_RESUME_FUNCTION: &str = "jsonl::map_resume";
+
+    // 解析一行JSON，line_number 从1开始，用于错误信息
+    fn parse_line(line: &str, line_number: usize) -> Result<JsonValue, String> {
+        serde_json::from_str::<JsonValue>(line)
+            .map_err(|e| format!("错误: 第{}行不是有效的JSON: {}", line_number, e))
+    }
+
+    // 读取文件中的所有记录（跳过空行）
+    fn read_records(path: &str) -> Result<Vec<JsonValue>, String> {
+        let file = File::open(path).map_err(|e| format!("错误: 无法打开文件 {}: {}", path, e))?;
+        let mut records = Vec::new();
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(|e| format!("错误: 读取文件失败: {}", e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            records.push(parse_line(&line, i + 1)?);
+        }
+        Ok(records)
+    }
+
+    fn array_value(values: Vec<JsonValue>) -> String {
+        json_value(&JsonValue::Array(values).to_string())
+    }
+
+    // 读取整个JSON Lines文件，返回所有记录组成的数组
+    // 参数: path
+    pub fn cn_read(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供文件路径".to_string();
+        }
+        match read_records(&args[0]) {
+            Ok(records) => array_value(records),
+            Err(e) => e,
+        }
+    }
+
+    // 追加一条记录（压缩为单行），文件不存在时自动创建
+    // 参数: path, json
+    pub fn cn_append(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供文件路径和JSON值".to_string();
+        }
+
+        let value = match serde_json::from_str::<JsonValue>(&args[1]) {
+            Ok(value) => value,
+            Err(e) => return format!("错误: 无效的JSON: {}", e),
+        };
+        let line = format!("{}\n", value);
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&args[0])
+            .and_then(|mut file| file.write_all(line.as_bytes()));
+        match result {
+            Ok(()) => "true".to_string(),
+            Err(e) => format!("错误: 写入文件失败: {}", e),
+        }
+    }
+
+    // 将JSON数组写入文件，每个元素一行（覆盖原文件）
+    // 参数: path, json_array
+    pub fn cn_write(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供文件路径和JSON数组".to_string();
+        }
+
+        let items = match serde_json::from_str::<JsonValue>(&args[1]) {
+            Ok(JsonValue::Array(items)) => items,
+            Ok(_) => return "错误: 第二个参数必须是JSON数组".to_string(),
+            Err(e) => return format!("错误: 无效的JSON: {}", e),
+        };
+        let content: String = items.iter().map(|item| format!("{}\n", item)).collect();
+        match std::fs::write(&args[0], content) {
+            Ok(()) => "true".to_string(),
+            Err(e) => format!("错误: 写入文件失败: {}", e),
+        }
+    }
+
+    // 对每条记录调用函数，返回各次返回值组成的数组；函数可以是函数名、函数指针或Lambda，参数为记录
+    // 返回的 Map 和数组按原样保留，函数抛出异常时停止并返回错误
+    // 参数: path, function
+    pub fn cn_map(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供文件路径和处理函数".to_string();
+        }
+
+        let records = match read_records(&args[0]) {
+            Ok(records) => records,
+            Err(e) => return e,
+        };
+        let mut mapping = Mapping { function: args[1].clone(), pending: records.into_iter(), results: Vec::new() };
+        match mapping.pending.next() {
+            Some(record) => {
+                let call = map_call(&mapping.function, record);
+                lock_mappings().push(mapping);
+                call
+            },
+            None => array_value(Vec::new()),
+        }
+    }
+
+    // map 的函数每次返回后由解释器调用，记录返回值并继续处理下一条记录；以续接函数注册，脚本无法直接调用
+    // 参数: returned
+    pub fn cn_map_resume(args: Vec<String>) -> String {
+        let mut mappings = lock_mappings();
+        let mut mapping = match mappings.pop() {
+            Some(mapping) => mapping,
+            None => return "错误: 没有正在进行的 map".to_string(),
+        };
+        let returned = args.first().map(|s| s.as_str()).unwrap_or("");
+        if let Some(message) = thrown_message(returned) {
+            return format!("错误: map 的处理函数抛出异常: {}", message);
+        }
+
+        // Map 和数组带有结构化前缀；其他返回值中数字、布尔值和 null 按JSON解析，其余视为普通字符串
+        let value = match returned_json(returned) {
+            Some(json) => serde_json::from_str::<JsonValue>(json).unwrap_or(JsonValue::Null),
+            None => match serde_json::from_str::<JsonValue>(returned) {
+                Ok(value) if !value.is_object() && !value.is_array() && !value.is_string() => value,
+                _ => JsonValue::String(returned.to_string()),
+            },
+        };
+        mapping.results.push(value);
+        match mapping.pending.next() {
+            Some(record) => {
+                let call = map_call(&mapping.function, record);
+                mappings.push(mapping);
+                call
+            },
+            None => array_value(mapping.results),
+        }
+    }
+
+    // 正在进行的 map，处理函数中可以再调用 map
+    struct Mapping {
+        function: String,
+        pending: std::vec::IntoIter<JsonValue>,
+        results: Vec<JsonValue>,
+    }
+
+    static MAPPINGS: Mutex<Vec<Mapping>> = Mutex::new(Vec::new());
+
+    fn lock_mappings() -> MutexGuard<'static, Vec<Mapping>> {
+        MAPPINGS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn map_call(function: &str, record: JsonValue) -> String {
+        callback_value(function, &JsonValue::Array(vec![record]).to_string(), MAP_RESUME_FUNCTION)
+    }
+
+    // 逐行读取的迭代器状态
+    pub struct LineReader {
+        lines: Lines<BufReader<File>>,
+        line_number: usize,
+        peeked: Option<JsonValue>,
+    }
+
+    impl LineReader {
+        fn advance(&mut self) -> Result<Option<JsonValue>, String> {
+            for line in self.lines.by_ref() {
+                self.line_number += 1;
+                let line = line.map_err(|e| format!("错误: 读取文件失败: {}", e))?;
+                if !line.trim().is_empty() {
+                    return parse_line(&line, self.line_number).map(Some);
+                }
+            }
+            Ok(None)
+        }
+    }
+
+    static READERS: Mutex<HandleTable<LineReader>> = Mutex::new(HandleTable::new());
+
+    fn lock_readers() -> MutexGuard<'static, HandleTable<LineReader>> {
+        READERS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // 打开文件逐行读取，返回迭代句柄
+    // 参数: path
+    pub fn cn_open(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供文件路径".to_string();
+        }
+        match File::open(&args[0]) {
+            Ok(file) => {
+                let reader = LineReader { lines: BufReader::new(file).lines(), line_number: 0, peeked: None };
+                lock_readers().insert(reader).to_string()
+            },
+            Err(e) => format!("错误: 无法打开文件 {}: {}", args[0], e),
+        }
+    }
+
+    // 检查是否还有记录
+    // 参数: handle
+    pub fn cn_has_next(args: Vec<String>) -> String {
+        let mut readers = lock_readers();
+        let reader = match args.first().and_then(|arg| readers.parse_handle(arg)).and_then(|id| readers.get_mut(id)) {
+            Some(reader) => reader,
+            None => return "错误: 无效的读取句柄".to_string(),
+        };
+        if reader.peeked.is_none() {
+            match reader.advance() {
+                Ok(next) => reader.peeked = next,
+                Err(e) => return e,
+            }
+        }
+        reader.peeked.is_some().to_string()
+    }
+
+    // 读取下一条记录，没有更多记录时返回错误信息
+    // 参数: handle
+    pub fn cn_next(args: Vec<String>) -> String {
+        let mut readers = lock_readers();
+        let reader = match args.first().and_then(|arg| readers.parse_handle(arg)).and_then(|id| readers.get_mut(id)) {
+            Some(reader) => reader,
+            None => return "错误: 无效的读取句柄".to_string(),
+        };
+        let next = match reader.peeked.take() {
+            Some(value) => Ok(Some(value)),
+            None => reader.advance(),
+        };
+        match next {
+            Ok(Some(value)) => json_value(&value.to_string()),
+            Ok(None) => "错误: 没有更多记录".to_string(),
+            Err(e) => e,
+        }
+    }
+
+    // 关闭读取句柄
+    // 参数: handle
+    pub fn cn_close(args: Vec<String>) -> String {
+        let mut readers = lock_readers();
+        match args.first().and_then(|arg| readers.parse_handle(arg)) {
+            Some(id) => {
+                readers.remove(id);
+                "true".to_string()
+            },
+            None => "false".to_string(),
+        }
+    }
+}
+
+// JSON Schema 校验（实现常用子集）
+// 支持: type、enum、const、required、properties、additionalProperties、items、
+//       minimum、maximum、exclusiveMinimum、exclusiveMaximum、minLength、maxLength、pattern、
+//       minItems、maxItems、uniqueItems、allOf、anyOf、oneOf、not
+mod schema {
+    use regex::Regex;
+    use serde_json::{json, Value as JsonValue};
+
+    // 单条校验错误，path 为JSONPath形式，例如 "$.user.tags[0]"
+    pub struct ValidationError {
+        pub path: String,
+        pub message: String,
+    }
+
+    impl ValidationError {
+        pub fn to_json(&self) -> JsonValue {
+            json!({ "path": self.path, "message": self.message })
+        }
+    }
+
+    // 校验 value 是否符合 schema，返回全部错误
+    pub fn validate(value: &JsonValue, schema: &JsonValue) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        validate_at(value, schema, "$", &mut errors);
+        errors
+    }
+
+    fn error(errors: &mut Vec<ValidationError>, path: &str, message: String) {
+        errors.push(ValidationError { path: path.to_string(), message });
+    }
+
+    fn type_name(value: &JsonValue) -> &'static str {
+        match value {
+            JsonValue::Null => "null",
+            JsonValue::Bool(_) => "boolean",
+            JsonValue::Number(n) if n.is_i64() || n.is_u64() => "integer",
+            JsonValue::Number(_) => "number",
+            JsonValue::String(_) => "string",
+            JsonValue::Array(_) => "array",
+            JsonValue::Object(_) => "object",
+        }
+    }
+
+    fn matches_type(value: &JsonValue, expected: &str) -> bool {
+        match expected {
+            "number" => value.is_number(),
+            // 1.0 这类没有小数部分的浮点数也视为整数
+            "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+            other => type_name(value) == other,
+        }
+    }
+
+    fn child_key_path(path: &str, key: &str) -> String {
+        if key.chars().all(|c| c.is_alphanumeric() || c == '_') && !key.is_empty() {
+            format!("{}.{}", path, key)
+        } else {
+            format!("{}[{}]", path, JsonValue::String(key.to_string()))
+        }
+    }
+
+    fn validate_at(value: &JsonValue, schema: &JsonValue, path: &str, errors: &mut Vec<ValidationError>) {
+        // 布尔schema: true 接受任意值，false 拒绝任意值
+        let schema = match schema {
+            JsonValue::Bool(true) => return,
+            JsonValue::Bool(false) => return error(errors, path, "不允许出现该值".to_string()),
+            JsonValue::Object(obj) => obj,
+            _ => return,
+        };
+
+        if let Some(expected) = schema.get("type") {
+            let allowed: Vec<&str> = match expected {
+                JsonValue::String(t) => vec![t.as_str()],
+                JsonValue::Array(types) => types.iter().filter_map(|t| t.as_str()).collect(),
+                _ => Vec::new(),
+            };
+            if !allowed.is_empty() && !allowed.iter().any(|t| matches_type(value, t)) {
+                let message = format!("类型应为 {}，实际为 {}", allowed.join(" 或 "), type_name(value));
+                // 类型不符时不再检查其余约束，避免产生大量连带错误
+                return error(errors, path, message);
+            }
+        }
+
+        if let Some(JsonValue::Array(options)) = schema.get("enum") {
+            if !options.contains(value) {
+                error(errors, path, format!("值必须是 {} 之一", JsonValue::Array(options.clone())));
+            }
+        }
+        if let Some(expected) = schema.get("const") {
+            if expected != value {
+                error(errors, path, format!("值必须等于 {}", expected));
+            }
+        }
+
+        match value {
+            JsonValue::Number(n) => validate_number(n.as_f64().unwrap_or(f64::NAN), schema, path, errors),
+            JsonValue::String(s) => validate_string(s, schema, path, errors),
+            JsonValue::Array(items) => validate_array(items, schema, path, errors),
+            JsonValue::Object(obj) => validate_object(obj, schema, path, errors),
+            _ => {},
+        }
+
+        validate_combinators(value, schema, path, errors);
+    }
+
+    fn validate_number(n: f64, schema: &serde_json::Map<String, JsonValue>, path: &str, errors: &mut Vec<ValidationError>) {
+        let bound = |key: &str| schema.get(key).and_then(|v| v.as_f64());
+        if let Some(min) = bound("minimum") {
+            if n < min {
+                error(errors, path, format!("值 {} 小于最小值 {}", n, min));
+            }
+        }
+        if let Some(max) = bound("maximum") {
+            if n > max {
+                error(errors, path, format!("值 {} 大于最大值 {}", n, max));
+            }
+        }
+        if let Some(min) = bound("exclusiveMinimum") {
+            if n <= min {
+                error(errors, path, format!("值 {} 必须大于 {}", n, min));
+            }
+        }
+        if let Some(max) = bound("exclusiveMaximum") {
+            if n >= max {
+                error(errors, path, format!("值 {} 必须小于 {}", n, max));
+            }
+        }
+    }
+
+    fn validate_string(s: &str, schema: &serde_json::Map<String, JsonValue>, path: &str, errors: &mut Vec<ValidationError>) {
+        let length = s.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(|v| v.as_u64()) {
+            if length < min {
+                error(errors, path, format!("字符串长度 {} 小于 {}", length, min));
+            }
+        }
+        if let Some(max) = schema.get("maxLength").and_then(|v| v.as_u64()) {
+            if length > max {
+                error(errors, path, format!("字符串长度 {} 大于 {}", length, max));
+            }
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(|v| v.as_str()) {
+            match Regex::new(pattern) {
+                Ok(re) if !re.is_match(s) => error(errors, path, format!("字符串不匹配模式 {}", pattern)),
+                Ok(_) => {},
+                Err(e) => error(errors, path, format!("无效的正则表达式 {}: {}", pattern, e)),
+            }
+        }
+    }
+
+    fn validate_array(items: &[JsonValue], schema: &serde_json::Map<String, JsonValue>, path: &str, errors: &mut Vec<ValidationError>) {
+        let count = items.len() as u64;
+        if let Some(min) = schema.get("minItems").and_then(|v| v.as_u64()) {
+            if count < min {
+                error(errors, path, format!("数组元素个数 {} 小于 {}", count, min));
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(|v| v.as_u64()) {
+            if count > max {
+                error(errors, path, format!("数组元素个数 {} 大于 {}", count, max));
+            }
+        }
+        if schema.get("uniqueItems").and_then(|v| v.as_bool()) == Some(true) {
+            let duplicated = items.iter().enumerate().any(|(i, item)| items[..i].contains(item));
+            if duplicated {
+                error(errors, path, "数组元素必须唯一".to_string());
+            }
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (i, item) in items.iter().enumerate() {
+                validate_at(item, item_schema, &format!("{}[{}]", path, i), errors);
+            }
+        }
+    }
+
+    fn validate_object(obj: &serde_json::Map<String, JsonValue>, schema: &serde_json::Map<String, JsonValue>, path: &str, errors: &mut Vec<ValidationError>) {
+        if let Some(JsonValue::Array(required)) = schema.get("required") {
+            for key in required.iter().filter_map(|k| k.as_str()) {
+                if !obj.contains_key(key) {
+                    error(errors, &child_key_path(path, key), "缺少必需属性".to_string());
+                }
+            }
+        }
+
+        let properties = schema.get("properties").and_then(|p| p.as_object());
+        for (key, value) in obj {
+            let child_path = child_key_path(path, key);
+            match properties.and_then(|p| p.get(key)) {
+                Some(property_schema) => validate_at(value, property_schema, &child_path, errors),
+                None => match schema.get("additionalProperties") {
+                    Some(JsonValue::Bool(false)) => error(errors, &child_path, "不允许的额外属性".to_string()),
+                    Some(extra_schema) => validate_at(value, extra_schema, &child_path, errors),
+                    None => {},
+                },
+            }
+        }
+    }
+
+    fn validate_combinators(value: &JsonValue, schema: &serde_json::Map<String, JsonValue>, path: &str, errors: &mut Vec<ValidationError>) {
+        let passes = |sub: &JsonValue| validate(value, sub).is_empty();
+
+        if let Some(JsonValue::Array(all)) = schema.get("allOf") {
+            for sub in all {
+                validate_at(value, sub, path, errors);
+            }
+        }
+        if let Some(JsonValue::Array(any)) = schema.get("anyOf") {
+            if !any.iter().any(passes) {
+                error(errors, path, "不符合 anyOf 中的任何一个schema".to_string());
+            }
+        }
+        if let Some(JsonValue::Array(one)) = schema.get("oneOf") {
+            let matched = one.iter().filter(|sub| passes(sub)).count();
+            if matched != 1 {
+                error(errors, path, format!("必须恰好符合 oneOf 中的一个schema，实际符合 {} 个", matched));
+            }
+        }
+        if let Some(not) = schema.get("not") {
+            if passes(not) {
+                error(errors, path, "不能符合 not 中的schema".to_string());
+            }
+        }
+    }
+}
+
+// 大文件流式解析
+// 按字节扫描文件，只在目标容器内解析单个元素，内存占用与单个元素大小相关，而不是整个文件
+mod stream {
+    use serde_json::{json, Value as JsonValue};
+    use std::fs::File;
+    use std::io::{BufRead, BufReader};
+
+    // 当前所在的容器
+    struct Frame {
+        // 容器在父容器中的键或索引，根容器为 None
+        segment: Option<String>,
+        is_object: bool,
+        count: usize,
+    }
+
+    pub struct JsonStream {
+        reader: BufReader<File>,
+        pointer: Vec<String>,
+        stack: Vec<Frame>,
+        started: bool,
+        peeked: Option<JsonValue>,
+    }
+
+    // 解析 JSON Pointer（RFC 6901），"*" 段匹配任意键或索引
+    fn parse_pointer(pointer: &str) -> Result<Vec<String>, String> {
+        let pointer = pointer.trim();
+        if pointer.is_empty() {
+            return Ok(Vec::new());
+        }
+        match pointer.strip_prefix('/') {
+            Some(rest) => Ok(rest.split('/').map(|s| s.replace("~1", "/").replace("~0", "~")).collect()),
+            None => Err(format!("错误: 无效的JSON Pointer: {}", pointer)),
+        }
+    }
+
+    impl JsonStream {
+        pub fn open(path: &str, pointer: &str) -> Result<Self, String> {
+            let pointer = parse_pointer(pointer)?;
+            let file = File::open(path).map_err(|e| format!("错误: 无法打开文件 {}: {}", path, e))?;
+            Ok(JsonStream {
+                reader: BufReader::new(file),
+                pointer,
+                stack: Vec::new(),
+                started: false,
+                peeked: None,
+            })
+        }
+
+        // 是否还有下一个元素（会预读一个元素）
+        pub fn has_next(&mut self) -> Result<bool, String> {
+            if self.peeked.is_none() {
+                self.peeked = self.advance()?;
+            }
+            Ok(self.peeked.is_some())
+        }
+
+        // 读取下一个元素
+        pub fn next_value(&mut self) -> Result<Option<JsonValue>, String> {
+            match self.peeked.take() {
+                Some(value) => Ok(Some(value)),
+                None => self.advance(),
+            }
+        }
+
+        fn peek(&mut self) -> Result<Option<u8>, String> {
+            let buffer = self.reader.fill_buf().map_err(|e| format!("错误: 读取文件失败: {}", e))?;
+            Ok(buffer.first().copied())
+        }
+
+        fn bump(&mut self) {
+            self.reader.consume(1);
+        }
+
+        fn skip_whitespace(&mut self) -> Result<Option<u8>, String> {
+            while let Some(c) = self.peek()? {
+                if !c.is_ascii_whitespace() {
+                    return Ok(Some(c));
+                }
+                self.bump();
+            }
+            Ok(None)
+        }
+
+        fn expect(&mut self, expected: u8) -> Result<(), String> {
+            match self.skip_whitespace()? {
+                Some(c) if c == expected => {
+                    self.bump();
+                    Ok(())
+                },
+                Some(c) => Err(format!("错误: JSON格式错误，期望 '{}'，实际为 '{}'", expected as char, c as char)),
+                None => Err("错误: JSON意外结束".to_string()),
+            }
+        }
+
+        // 读取一个完整的值；out 为 Some 时保存原始字节，否则只跳过
+        fn read_raw_value(&mut self, mut out: Option<&mut Vec<u8>>) -> Result<(), String> {
+            let first = match self.skip_whitespace()? {
+                Some(c) => c,
+                None => return Err("错误: JSON意外结束".to_string()),
+            };
+
+            let mut depth = 0usize;
+            let mut in_string = false;
+            let mut escaped = false;
+            let is_scalar = !matches!(first, b'{' | b'[' | b'"');
+
+            while let Some(c) = self.peek()? {
+                if is_scalar && (c == b',' || c == b']' || c == b'}' || c.is_ascii_whitespace()) {
+                    return Ok(());
+                }
+                self.bump();
+                if let Some(buffer) = out.as_deref_mut() {
+                    buffer.push(c);
+                }
+                if is_scalar {
+                    continue;
+                }
+
+                if in_string {
+                    if escaped {
+                        escaped = false;
+                    } else if c == b'\\' {
+                        escaped = true;
+                    } else if c == b'"' {
+                        in_string = false;
+                        if depth == 0 {
+                            return Ok(());
+                        }
+                    }
+                    continue;
+                }
+                match c {
+                    b'"' => in_string = true,
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Ok(());
+                        }
+                    },
+                    _ => {},
+                }
+            }
+
+            if is_scalar {
+                Ok(())
+            } else {
+                Err("错误: JSON意外结束".to_string())
+            }
+        }
+
+        fn read_key(&mut self) -> Result<String, String> {
+            let mut raw = Vec::new();
+            self.read_raw_value(Some(&mut raw))?;
+            serde_json::from_slice::<String>(&raw).map_err(|e| format!("错误: 无效的对象键: {}", e))
+        }
+
+        // 当前容器路径（不含根）
+        fn container_path(&self) -> Vec<&str> {
+            self.stack.iter().filter_map(|frame| frame.segment.as_deref()).collect()
+        }
+
+        fn path_matches(&self, path: &[&str], exact: bool) -> bool {
+            if path.len() > self.pointer.len() || (exact && path.len() != self.pointer.len()) {
+                return false;
+            }
+            path.iter().zip(&self.pointer).all(|(segment, expected)| expected == "*" || segment == expected)
+        }
+
+        // 推进到下一个匹配元素
+        fn advance(&mut self) -> Result<Option<JsonValue>, String> {
+            if !self.started {
+                self.started = true;
+                match self.skip_whitespace()? {
+                    Some(c @ (b'{' | b'[')) => {
+                        self.bump();
+                        self.stack.push(Frame { segment: None, is_object: c == b'{', count: 0 });
+                    },
+                    // 根节点不是容器时没有可以逐个读取的元素
+                    _ => return Ok(None),
+                }
+            }
+
+            while let Some(frame) = self.stack.last() {
+                let is_object = frame.is_object;
+                let count = frame.count;
+
+                match self.skip_whitespace()? {
+                    Some(b']' | b'}') => {
+                        self.bump();
+                        self.stack.pop();
+                        continue;
+                    },
+                    None => return Err("错误: JSON意外结束".to_string()),
+                    _ => {},
+                }
+                if count > 0 {
+                    self.expect(b',')?;
+                }
+
+                let segment = if is_object {
+                    let key = self.read_key()?;
+                    self.expect(b':')?;
+                    key
+                } else {
+                    count.to_string()
+                };
+                if let Some(frame) = self.stack.last_mut() {
+                    frame.count += 1;
+                }
+
+                let container_path = self.container_path();
+                if self.path_matches(&container_path, true) {
+                    // 当前容器就是目标容器，解析并返回这个元素
+                    let mut raw = Vec::new();
+                    self.read_raw_value(Some(&mut raw))?;
+                    let value = serde_json::from_slice::<JsonValue>(&raw)
+                        .map_err(|e| format!("错误: 解析JSON失败: {}", e))?;
+                    return Ok(Some(if is_object { json!({ "key": segment, "value": value }) } else { value }));
+                }
+
+                let mut value_path = container_path;
+                value_path.push(&segment);
+                let descend = self.path_matches(&value_path, false);
+                match self.skip_whitespace()? {
+                    Some(c @ (b'{' | b'[')) if descend => {
+                        self.bump();
+                        self.stack.push(Frame { segment: Some(segment), is_object: c == b'{', count: 0 });
+                    },
+                    _ => self.read_raw_value(None)?,
+                }
+            }
+
+            Ok(None)
+        }
+    }
+}
+
 // 初始化函数，返回函数映射
 #[no_mangle]
 pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
@@ -487,12 +1791,42 @@ pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
     // 注册JSON命名空间下的函数
     let json_ns = registry.namespace("json");
     json_ns.add_function("parse", json::cn_parse)
+           .add_function("stringify", json::cn_stringify)
            .add_function("format", json::cn_format)
            .add_function("create_object", json::cn_create_object)
            .add_function("create_array", json::cn_create_array)
            .add_function("get_value", json::cn_get_value)
            .add_function("is_valid", json::cn_is_valid)
-           .add_function("merge", json::cn_merge);
+           .add_function("merge", json::cn_merge)
+           .add_function("set_value", json::cn_set_value)
+           .add_function("delete", json::cn_delete)
+           .add_function("push", json::cn_push)
+           .add_function("update", json::cn_update)
+           .add_function("query", json::cn_query)
+           .add_function("validate", json::cn_validate)
+           .add_function("diff", json::cn_diff)
+           .add_function("patch", json::cn_patch)
+           .add_function("merge_patch", json::cn_merge_patch)
+           .add_function("stream_file", json::cn_stream_file)
+           .add_function("stream_has_next", json::cn_stream_has_next)
+           .add_function("stream_next", json::cn_stream_next)
+           .add_function("stream_close", json::cn_stream_close)
+           .add_function("minify", json::cn_minify)
+           .add_function("canonicalize", json::cn_canonicalize)
+           .add_function("to_csv", json::cn_to_csv)
+           .add_function("from_csv", json::cn_from_csv);
+
+    // 注册JSON Lines命名空间下的函数
+    let jsonl_ns = registry.namespace("jsonl");
+    jsonl_ns.add_function("read", jsonl::cn_read)
+            .add_function("append", jsonl::cn_append)
+            .add_function("write", jsonl::cn_write)
+            .add_function("map", jsonl::cn_map)
+            .add_continuation("map_resume", jsonl::cn_map_resume)
+            .add_function("open", jsonl::cn_open)
+            .add_function("has_next", jsonl::cn_has_next)
+            .add_function("next", jsonl::cn_next)
+            .add_function("close", jsonl::cn_close);
            
     // 构建并返回库指针
     registry.build_library_pointer()
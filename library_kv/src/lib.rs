@@ -0,0 +1,287 @@
+use ::std::collections::HashMap;
+use serde_json::{Map, Value as JsonValue};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// 导入通用库
+use cn_common::handle::HandleTable;
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::json_value;
+
+// 键值存储命名空间
+// 数据保存在 open 指定的目录中，程序退出后仍然存在；键和值都是字符串
+// 每个值前保存8字节的过期时间（Unix毫秒，0表示永不过期），过期的键在读取时删除
+mod kv {
+    use super::*;
+
+    static STORES: Mutex<HandleTable<sled::Db>> = Mutex::new(HandleTable::new());
+
+    fn lock_stores() -> MutexGuard<'static, HandleTable<sled::Db>> {
+        STORES.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // 取出句柄对应的存储；sled::Db 内部是引用计数，克隆后可以在锁外使用
+    fn store(handle: Option<&String>) -> Result<sled::Db, String> {
+        let stores = lock_stores();
+        handle.and_then(|arg| stores.parse_handle(arg))
+            .and_then(|id| stores.get(id))
+            .cloned()
+            .ok_or_else(|| "错误: 无效的存储句柄".to_string())
+    }
+
+    // 打开存储目录，目录不存在时创建；同一目录同时只能被一个句柄打开
+    // 参数: path
+    pub fn cn_open(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供存储目录".to_string();
+        }
+        match sled::open(&args[0]) {
+            Ok(db) => lock_stores().insert(db).to_string(),
+            Err(e) => format!("错误: 无法打开存储 {}: {}", args[0], e),
+        }
+    }
+
+    // 关闭存储，关闭前把数据写入磁盘
+    // 参数: kv
+    pub fn cn_close(args: Vec<String>) -> String {
+        let db = {
+            let mut stores = lock_stores();
+            match args.first().and_then(|arg| stores.parse_handle(arg)) {
+                Some(id) => stores.remove(id),
+                None => None,
+            }
+        };
+        match db {
+            Some(db) => db.flush().is_ok().to_string(),
+            None => "false".to_string(),
+        }
+    }
+
+    // 获取值，键不存在或已过期时返回默认值，没有默认值时返回空值
+    // 参数: kv, key, [default]
+    pub fn cn_get(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供存储句柄和键".to_string();
+        }
+        let db = match store(args.first()) {
+            Ok(db) => db,
+            Err(e) => return e,
+        };
+        match read(&db, &args[1]) {
+            Ok(Some((value, _))) => value,
+            Ok(None) => args.get(2).cloned().unwrap_or_else(|| json_value("null")),
+            Err(e) => e,
+        }
+    }
+
+    // 设置值
+    // 参数: kv, key, value, [ttl]（过期秒数，默认永不过期）
+    pub fn cn_set(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "错误: 请提供存储句柄、键和值".to_string();
+        }
+        let db = match store(args.first()) {
+            Ok(db) => db,
+            Err(e) => return e,
+        };
+        let expires_at = match args.get(3).map(|t| parse_ttl(t)) {
+            Some(Ok(ttl)) => now_millis() + ttl,
+            Some(Err(e)) => return e,
+            None => 0,
+        };
+        match db.insert(args[1].as_bytes(), encode(expires_at, &args[2])) {
+            Ok(_) => "true".to_string(),
+            Err(e) => format!("错误: 写入失败: {}", e),
+        }
+    }
+
+    // 删除键，返回键删除前是否存在
+    // 参数: kv, key
+    pub fn cn_delete(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供存储句柄和键".to_string();
+        }
+        let db = match store(args.first()) {
+            Ok(db) => db,
+            Err(e) => return e,
+        };
+        let existed = match read(&db, &args[1]) {
+            Ok(found) => found.is_some(),
+            Err(e) => return e,
+        };
+        match db.remove(args[1].as_bytes()) {
+            Ok(_) => existed.to_string(),
+            Err(e) => format!("错误: 删除失败: {}", e),
+        }
+    }
+
+    // 检查键是否存在且未过期
+    // 参数: kv, key
+    pub fn cn_has(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供存储句柄和键".to_string();
+        }
+        match store(args.first()).and_then(|db| read(&db, &args[1])) {
+            Ok(found) => found.is_some().to_string(),
+            Err(e) => e,
+        }
+    }
+
+    // 设置已有键的过期时间，ttl 为0时取消过期；键不存在时返回 false
+    // 参数: kv, key, ttl
+    pub fn cn_expire(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "错误: 请提供存储句柄、键和过期秒数".to_string();
+        }
+        let db = match store(args.first()) {
+            Ok(db) => db,
+            Err(e) => return e,
+        };
+        let expires_at = match parse_ttl(&args[2]) {
+            Ok(0) => 0,
+            Ok(ttl) => now_millis() + ttl,
+            Err(e) => return e,
+        };
+        let value = match read(&db, &args[1]) {
+            Ok(Some((value, _))) => value,
+            Ok(None) => return "false".to_string(),
+            Err(e) => return e,
+        };
+        match db.insert(args[1].as_bytes(), encode(expires_at, &value)) {
+            Ok(_) => "true".to_string(),
+            Err(e) => format!("错误: 写入失败: {}", e),
+        }
+    }
+
+    // 获取键的剩余过期秒数（向上取整）；永不过期时返回 -1，键不存在时返回 -2
+    // 参数: kv, key
+    pub fn cn_ttl(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供存储句柄和键".to_string();
+        }
+        match store(args.first()).and_then(|db| read(&db, &args[1])) {
+            Ok(Some((_, 0))) => "-1".to_string(),
+            Ok(Some((_, expires_at))) => (expires_at.saturating_sub(now_millis()).saturating_add(999) / 1000).to_string(),
+            Ok(None) => "-2".to_string(),
+            Err(e) => e,
+        }
+    }
+
+    // 获取所有以指定前缀开头的键值对，返回 Map
+    // 参数: kv, prefix
+    pub fn cn_scan_prefix(args: Vec<String>) -> String {
+        let prefix = args.get(1).map(|p| p.as_str()).unwrap_or("");
+        match store(args.first()).and_then(|db| scan(&db, prefix)) {
+            Ok(entries) => {
+                let map: Map<String, JsonValue> = entries.into_iter().map(|(k, v)| (k, JsonValue::String(v))).collect();
+                json_value(&JsonValue::Object(map).to_string())
+            },
+            Err(e) => e,
+        }
+    }
+
+    // 获取所有键，按字典序排列
+    // 参数: kv, [prefix]
+    pub fn cn_keys(args: Vec<String>) -> String {
+        let prefix = args.get(1).map(|p| p.as_str()).unwrap_or("");
+        match store(args.first()).and_then(|db| scan(&db, prefix)) {
+            Ok(entries) => {
+                let keys: Vec<JsonValue> = entries.into_iter().map(|(k, _)| JsonValue::String(k)).collect();
+                json_value(&JsonValue::Array(keys).to_string())
+            },
+            Err(e) => e,
+        }
+    }
+
+    // 把缓冲的数据立即写入磁盘
+    // 参数: kv
+    pub fn cn_flush(args: Vec<String>) -> String {
+        match store(args.first()) {
+            Ok(db) => match db.flush() {
+                Ok(_) => "true".to_string(),
+                Err(e) => format!("错误: 写入磁盘失败: {}", e),
+            },
+            Err(e) => e,
+        }
+    }
+
+    fn now_millis() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+    }
+
+    // 解析过期秒数，支持小数，返回毫秒
+    fn parse_ttl(text: &str) -> Result<u64, String> {
+        match text.trim().parse::<f64>() {
+            Ok(seconds) if seconds >= 0.0 && seconds.is_finite() => Ok((seconds * 1000.0).round() as u64),
+            _ => Err(format!("错误: 无效的过期秒数: {}", text)),
+        }
+    }
+
+    fn encode(expires_at: u64, value: &str) -> Vec<u8> {
+        let mut bytes = expires_at.to_be_bytes().to_vec();
+        bytes.extend_from_slice(value.as_bytes());
+        bytes
+    }
+
+    // 解码存储的值，返回值和过期时间；已过期时返回 None
+    fn decode(bytes: &[u8]) -> Option<(String, u64)> {
+        if bytes.len() < 8 {
+            return None;
+        }
+        let (header, value) = bytes.split_at(8);
+        let expires_at = u64::from_be_bytes(header.try_into().ok()?);
+        if expires_at != 0 && expires_at <= now_millis() {
+            return None;
+        }
+        Some((String::from_utf8_lossy(value).into_owned(), expires_at))
+    }
+
+    fn read(db: &sled::Db, key: &str) -> Result<Option<(String, u64)>, String> {
+        let bytes = match db.get(key.as_bytes()) {
+            Ok(Some(bytes)) => bytes,
+            Ok(None) => return Ok(None),
+            Err(e) => return Err(format!("错误: 读取失败: {}", e)),
+        };
+        let decoded = decode(&bytes);
+        if decoded.is_none() {
+            // 顺便删除已过期的键；只在值未被其他调用修改时删除
+            let _ = db.compare_and_swap(key.as_bytes(), Some(bytes), None as Option<&[u8]>);
+        }
+        Ok(decoded)
+    }
+
+    fn scan(db: &sled::Db, prefix: &str) -> Result<Vec<(String, String)>, String> {
+        let mut entries = Vec::new();
+        for item in db.scan_prefix(prefix.as_bytes()) {
+            let (key, bytes) = item.map_err(|e| format!("错误: 读取失败: {}", e))?;
+            if let Some((value, _)) = decode(&bytes) {
+                entries.push((String::from_utf8_lossy(&key).into_owned(), value));
+            }
+        }
+        Ok(entries)
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册键值存储命名空间下的函数
+    let kv_ns = registry.namespace("kv");
+    kv_ns.add_function("open", kv::cn_open)
+         .add_function("close", kv::cn_close)
+         .add_function("get", kv::cn_get)
+         .add_function("set", kv::cn_set)
+         .add_function("delete", kv::cn_delete)
+         .add_function("has", kv::cn_has)
+         .add_function("expire", kv::cn_expire)
+         .add_function("ttl", kv::cn_ttl)
+         .add_function("scan_prefix", kv::cn_scan_prefix)
+         .add_function("keys", kv::cn_keys)
+         .add_function("flush", kv::cn_flush);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
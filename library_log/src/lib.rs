@@ -0,0 +1,306 @@
+use ::std::collections::HashMap;
+use chrono::format::{Item, StrftimeItems};
+use chrono::Local;
+use serde_json::{Map, Value as JsonValue};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::sync::{Mutex, MutexGuard};
+
+// 导入通用库
+use cn_common::handle::HandleTable;
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+
+// 日志命名空间
+// 没有添加任何输出目标时写入标准错误；添加输出目标后只写入已添加的目标
+mod log {
+    use super::*;
+
+    const LEVEL_NAMES: [&str; 5] = ["TRACE", "DEBUG", "INFO", "WARN", "ERROR"];
+
+    // 级别数值，OFF 高于所有级别，用于关闭日志
+    const TRACE: u8 = 0;
+    const DEBUG: u8 = 1;
+    const INFO: u8 = 2;
+    const WARN: u8 = 3;
+    const ERROR: u8 = 4;
+    const OFF: u8 = 5;
+
+    const DEFAULT_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f";
+
+    enum Target {
+        Stderr,
+        Stdout,
+        File {
+            path: String,
+            file: File,
+            size: u64,
+            // 超过该字节数时轮转，0表示不轮转
+            max_size: u64,
+            // 保留的历史文件数量
+            max_files: u32,
+        },
+    }
+
+    struct Sink {
+        target: Target,
+        level: u8,
+    }
+
+    struct Config {
+        level: u8,
+        format: Option<String>,
+        time_format: String,
+    }
+
+    static SINKS: Mutex<HandleTable<Sink>> = Mutex::new(HandleTable::new());
+    static CONFIG: Mutex<Config> = Mutex::new(Config { level: INFO, format: None, time_format: String::new() });
+
+    fn lock_sinks() -> MutexGuard<'static, HandleTable<Sink>> {
+        SINKS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_config() -> MutexGuard<'static, Config> {
+        CONFIG.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // 记录 TRACE 级别的日志
+    // 参数: message, [tag]
+    pub fn cn_trace(args: Vec<String>) -> String {
+        write_log(TRACE, &args)
+    }
+
+    // 记录 DEBUG 级别的日志
+    // 参数: message, [tag]
+    pub fn cn_debug(args: Vec<String>) -> String {
+        write_log(DEBUG, &args)
+    }
+
+    // 记录 INFO 级别的日志
+    // 参数: message, [tag]
+    pub fn cn_info(args: Vec<String>) -> String {
+        write_log(INFO, &args)
+    }
+
+    // 记录 WARN 级别的日志
+    // 参数: message, [tag]
+    pub fn cn_warn(args: Vec<String>) -> String {
+        write_log(WARN, &args)
+    }
+
+    // 记录 ERROR 级别的日志
+    // 参数: message, [tag]
+    pub fn cn_error(args: Vec<String>) -> String {
+        write_log(ERROR, &args)
+    }
+
+    // 设置全局最低级别，低于该级别的日志被忽略；默认为 info
+    // 参数: level（trace、debug、info、warn、error 或 off）
+    pub fn cn_set_level(args: Vec<String>) -> String {
+        match args.first().map(|l| parse_level(l)) {
+            Some(Ok(level)) => {
+                lock_config().level = level;
+                "true".to_string()
+            },
+            Some(Err(e)) => e,
+            None => "错误: 请提供日志级别".to_string(),
+        }
+    }
+
+    // 获取当前的全局最低级别
+    // 参数: 无
+    pub fn cn_get_level(_args: Vec<String>) -> String {
+        level_name(lock_config().level).to_lowercase()
+    }
+
+    // 设置日志格式，可用占位符: {time}、{level}、{tag}、{message}
+    // 省略参数时恢复默认格式 "{time} [{level}] {tag}: {message}"（没有标签时省略 "{tag}: "）
+    // 参数: [format]
+    pub fn cn_set_format(args: Vec<String>) -> String {
+        lock_config().format = args.first().filter(|f| !f.is_empty()).cloned();
+        "true".to_string()
+    }
+
+    // 设置时间格式，使用 strftime 语法，默认为 "%Y-%m-%d %H:%M:%S%.3f"
+    // 参数: [format]
+    pub fn cn_set_time_format(args: Vec<String>) -> String {
+        let format = args.first().cloned().unwrap_or_default();
+        if StrftimeItems::new(&format).any(|item| matches!(item, Item::Error)) {
+            return format!("错误: 无效的时间格式: {}", format);
+        }
+        lock_config().time_format = format;
+        "true".to_string()
+    }
+
+    // 添加输出目标，返回目标句柄
+    // kind 为 stderr、stdout 或 file；options 是可选的 Map:
+    //   level: 该目标的最低级别, path: 文件路径（file 必需）,
+    //   max_size: 文件超过该字节数时轮转（默认不轮转）, max_files: 保留的历史文件数（默认5，如 app.log.1 ~ app.log.5）
+    // 参数: kind, [options]
+    pub fn cn_add_sink(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供输出目标类型".to_string();
+        }
+        let options = match args.get(1).filter(|o| !o.trim().is_empty()) {
+            Some(options) => match serde_json::from_str::<Map<String, JsonValue>>(options) {
+                Ok(options) => options,
+                Err(_) => return "错误: 选项必须是 Map".to_string(),
+            },
+            None => Map::new(),
+        };
+        let level = match options.get("level").and_then(|l| l.as_str()).map(parse_level) {
+            Some(Ok(level)) => level,
+            Some(Err(e)) => return e,
+            None => TRACE,
+        };
+
+        let target = match args[0].trim().to_lowercase().as_str() {
+            "stderr" => Target::Stderr,
+            "stdout" => Target::Stdout,
+            "file" => {
+                let path = match options.get("path").and_then(|p| p.as_str()) {
+                    Some(path) => path.to_string(),
+                    None => return "错误: 文件输出目标需要 path 选项".to_string(),
+                };
+                let file = match open_append(&path) {
+                    Ok(file) => file,
+                    Err(e) => return e,
+                };
+                Target::File {
+                    size: file.metadata().map(|m| m.len()).unwrap_or(0),
+                    path,
+                    file,
+                    max_size: options.get("max_size").and_then(|s| s.as_u64()).unwrap_or(0),
+                    max_files: options.get("max_files").and_then(|s| s.as_u64()).unwrap_or(5) as u32,
+                }
+            },
+            other => return format!("错误: 未知的输出目标类型: {}", other),
+        };
+        lock_sinks().insert(Sink { target, level }).to_string()
+    }
+
+    // 移除输出目标
+    // 参数: sink
+    pub fn cn_remove_sink(args: Vec<String>) -> String {
+        let mut sinks = lock_sinks();
+        match args.first().and_then(|arg| sinks.parse_handle(arg)) {
+            Some(id) => {
+                sinks.remove(id);
+                "true".to_string()
+            },
+            None => "false".to_string(),
+        }
+    }
+
+    fn write_log(level: u8, args: &[String]) -> String {
+        let message = args.first().map(|m| m.as_str()).unwrap_or("");
+        let tag = args.get(1).map(|t| t.as_str()).unwrap_or("");
+
+        let line = {
+            let config = lock_config();
+            if level < config.level {
+                return "false".to_string();
+            }
+            let time_format = if config.time_format.is_empty() { DEFAULT_TIME_FORMAT } else { &config.time_format };
+            let time = Local::now().format(time_format).to_string();
+            match &config.format {
+                Some(format) => format.replace("{time}", &time)
+                    .replace("{level}", level_name(level))
+                    .replace("{tag}", tag)
+                    .replace("{message}", message),
+                None if tag.is_empty() => format!("{} [{}] {}", time, level_name(level), message),
+                None => format!("{} [{}] {}: {}", time, level_name(level), tag, message),
+            }
+        };
+
+        let mut sinks = lock_sinks();
+        if sinks.is_empty() {
+            eprintln!("{}", line);
+            return "true".to_string();
+        }
+        let mut error = None;
+        for (_, sink) in sinks.iter_mut().filter(|(_, sink)| level >= sink.level) {
+            if let Err(e) = write_line(&mut sink.target, &line) {
+                error = Some(e);
+            }
+        }
+        error.unwrap_or_else(|| "true".to_string())
+    }
+
+    fn write_line(target: &mut Target, line: &str) -> Result<(), String> {
+        match target {
+            Target::Stderr => eprintln!("{}", line),
+            Target::Stdout => println!("{}", line),
+            Target::File { path, file, size, max_size, max_files } => {
+                let len = line.len() as u64 + 1;
+                if *max_size > 0 && *size > 0 && *size + len > *max_size {
+                    *file = rotate(path, *max_files)?;
+                    *size = 0;
+                }
+                writeln!(file, "{}", line).map_err(|e| format!("错误: 无法写入日志文件 {}: {}", path, e))?;
+                *size += len;
+            },
+        }
+        Ok(())
+    }
+
+    // 轮转日志文件: app.log.(n-1) -> app.log.n, ..., app.log -> app.log.1，返回新的空文件
+    fn rotate(path: &str, max_files: u32) -> Result<File, String> {
+        if max_files == 0 {
+            let _ = fs::remove_file(path);
+        } else {
+            let _ = fs::remove_file(format!("{}.{}", path, max_files));
+            for i in (1..max_files).rev() {
+                let _ = fs::rename(format!("{}.{}", path, i), format!("{}.{}", path, i + 1));
+            }
+            fs::rename(path, format!("{}.1", path)).map_err(|e| format!("错误: 无法轮转日志文件 {}: {}", path, e))?;
+        }
+        open_append(path)
+    }
+
+    fn open_append(path: &str) -> Result<File, String> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| format!("错误: 无法打开日志文件 {}: {}", path, e))
+    }
+
+    fn parse_level(name: &str) -> Result<u8, String> {
+        let name = name.trim().to_uppercase();
+        if name == "OFF" {
+            return Ok(OFF);
+        }
+        LEVEL_NAMES.iter()
+            .position(|l| *l == name || (name == "WARNING" && *l == "WARN"))
+            .map(|i| i as u8)
+            .ok_or_else(|| format!("错误: 无效的日志级别: {}", name.to_lowercase()))
+    }
+
+    fn level_name(level: u8) -> &'static str {
+        LEVEL_NAMES.get(level as usize).copied().unwrap_or("OFF")
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册日志命名空间下的函数
+    let log_ns = registry.namespace("log");
+    log_ns.add_function("trace", log::cn_trace)
+          .add_function("debug", log::cn_debug)
+          .add_function("info", log::cn_info)
+          .add_function("warn", log::cn_warn)
+          .add_function("error", log::cn_error)
+          .add_function("set_level", log::cn_set_level)
+          .add_function("get_level", log::cn_get_level)
+          .add_function("set_format", log::cn_set_format)
+          .add_function("set_time_format", log::cn_set_time_format)
+          .add_function("add_sink", log::cn_add_sink)
+          .add_function("remove_sink", log::cn_remove_sink);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
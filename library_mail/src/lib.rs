@@ -0,0 +1,186 @@
+use ::std::collections::HashMap;
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MessageBuilder, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::transport::smtp::client::{Tls, TlsParameters};
+use lettre::{Message, SmtpTransport, Transport};
+use serde_json::{Map, Value as JsonValue};
+use std::path::Path;
+use std::time::Duration;
+
+// 导入通用库
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+
+// 邮件命名空间
+mod mail {
+    use super::*;
+
+    // SMTP连接和收发的超时时间
+    const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+    // 通过SMTP服务器发送邮件，成功时返回 true
+    // to 可以是逗号分隔的地址或地址数组，地址可以带名称，如 "张三 <zhang@example.com>"
+    // options 是可选的 Map：
+    //   from: 发件人（默认为 user）, cc / bcc / reply_to: 地址, html: HTML正文（与 body 一起作为备选正文）,
+    //   attachments: 附件路径数组, tls: tls（465端口默认）、starttls（587端口默认）、opportunistic（其他端口默认）或 none
+    // user 为空时不进行身份验证
+    // 参数: host, port, user, pass, to, subject, body, [options]
+    pub fn cn_send(args: Vec<String>) -> String {
+        if args.len() < 7 {
+            return "错误: 请提供服务器、端口、用户名、密码、收件人、主题和正文".to_string();
+        }
+        let port = match args[1].trim().parse::<u16>() {
+            Ok(port) => port,
+            Err(_) => return format!("错误: 无效的端口: {}", args[1]),
+        };
+        let options = match args.get(7).filter(|o| !o.trim().is_empty()) {
+            Some(options) => match serde_json::from_str::<Map<String, JsonValue>>(options) {
+                Ok(options) => options,
+                Err(_) => return "错误: 选项必须是 Map".to_string(),
+            },
+            None => Map::new(),
+        };
+
+        let message = match build_message(&args, &options) {
+            Ok(message) => message,
+            Err(e) => return e,
+        };
+        let transport = match build_transport(args[0].trim(), port, &args[2], &args[3], &options) {
+            Ok(transport) => transport,
+            Err(e) => return e,
+        };
+        match transport.send(&message) {
+            Ok(_) => "true".to_string(),
+            Err(e) => format!("错误: 发送邮件失败: {}", e),
+        }
+    }
+
+    fn build_message(args: &[String], options: &Map<String, JsonValue>) -> Result<Message, String> {
+        let from = match options.get("from").and_then(|f| f.as_str()) {
+            Some(from) => from,
+            None => args[2].as_str(),
+        };
+        let mut builder: MessageBuilder = Message::builder().from(parse_mailbox(from)?).subject(args[5].as_str());
+
+        let to = parse_mailboxes(&serde_json::from_str(&args[4]).unwrap_or_else(|_| JsonValue::String(args[4].clone())))?;
+        if to.is_empty() {
+            return Err("错误: 请提供收件人".to_string());
+        }
+        for mailbox in to {
+            builder = builder.to(mailbox);
+        }
+        for mailbox in parse_mailboxes(options.get("cc").unwrap_or(&JsonValue::Null))? {
+            builder = builder.cc(mailbox);
+        }
+        for mailbox in parse_mailboxes(options.get("bcc").unwrap_or(&JsonValue::Null))? {
+            builder = builder.bcc(mailbox);
+        }
+        for mailbox in parse_mailboxes(options.get("reply_to").unwrap_or(&JsonValue::Null))? {
+            builder = builder.reply_to(mailbox);
+        }
+
+        // 正文：纯文本，或纯文本加HTML的备选正文
+        let text = args[6].clone();
+        let content = match options.get("html").and_then(|h| h.as_str()) {
+            Some(html) => MultiPart::alternative_plain_html(text, html.to_string()),
+            None => MultiPart::mixed().singlepart(SinglePart::plain(text)),
+        };
+
+        let attachments = match options.get("attachments") {
+            Some(JsonValue::Array(paths)) => paths.iter().filter_map(|p| p.as_str()).map(attachment).collect::<Result<Vec<_>, _>>()?,
+            Some(JsonValue::String(path)) => vec![attachment(path)?],
+            _ => Vec::new(),
+        };
+        let result = if attachments.is_empty() {
+            builder.multipart(content)
+        } else {
+            let mut mixed = MultiPart::mixed().multipart(content);
+            for part in attachments {
+                mixed = mixed.singlepart(part);
+            }
+            builder.multipart(mixed)
+        };
+        result.map_err(|e| format!("错误: 无法生成邮件: {}", e))
+    }
+
+    fn build_transport(host: &str, port: u16, user: &str, pass: &str, options: &Map<String, JsonValue>) -> Result<SmtpTransport, String> {
+        let default_mode = match port {
+            465 => "tls",
+            587 => "starttls",
+            _ => "opportunistic",
+        };
+        let mode = options.get("tls").and_then(|t| t.as_str()).unwrap_or(default_mode).to_lowercase();
+        let tls_error = |e: lettre::transport::smtp::Error| format!("错误: 无法建立TLS连接: {}", e);
+
+        let builder = match mode.as_str() {
+            "tls" => SmtpTransport::relay(host).map_err(tls_error)?,
+            "starttls" => SmtpTransport::starttls_relay(host).map_err(tls_error)?,
+            "opportunistic" => {
+                let parameters = TlsParameters::new(host.to_string()).map_err(tls_error)?;
+                SmtpTransport::builder_dangerous(host).tls(Tls::Opportunistic(parameters))
+            },
+            "none" => SmtpTransport::builder_dangerous(host),
+            other => return Err(format!("错误: 无效的TLS模式: {}", other)),
+        };
+
+        let mut builder = builder.port(port).timeout(Some(SMTP_TIMEOUT));
+        if !user.is_empty() {
+            builder = builder.credentials(Credentials::new(user.to_string(), pass.to_string()));
+        }
+        Ok(builder.build())
+    }
+
+    fn parse_mailbox(address: &str) -> Result<Mailbox, String> {
+        address.trim().parse::<Mailbox>().map_err(|e| format!("错误: 无效的邮件地址 {}: {}", address, e))
+    }
+
+    // 地址可以是逗号分隔的字符串或字符串数组
+    fn parse_mailboxes(value: &JsonValue) -> Result<Vec<Mailbox>, String> {
+        match value {
+            JsonValue::String(list) => list.split(',').filter(|a| !a.trim().is_empty()).map(parse_mailbox).collect(),
+            JsonValue::Array(items) => items.iter().filter_map(|a| a.as_str()).map(parse_mailbox).collect(),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    fn attachment(path: &str) -> Result<SinglePart, String> {
+        let bytes = std::fs::read(path).map_err(|e| format!("错误: 无法读取附件 {}: {}", path, e))?;
+        let filename = Path::new(path).file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_else(|| path.to_string());
+        let content_type = ContentType::parse(guess_content_type(path)).unwrap_or(ContentType::TEXT_PLAIN);
+        Ok(Attachment::new(filename).body(bytes, content_type))
+    }
+
+    // 按扩展名推断附件类型
+    fn guess_content_type(path: &str) -> &'static str {
+        let extension = Path::new(path).extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+        match extension.as_str() {
+            "txt" | "log" => "text/plain",
+            "html" | "htm" => "text/html",
+            "csv" => "text/csv",
+            "json" => "application/json",
+            "xml" => "application/xml",
+            "pdf" => "application/pdf",
+            "zip" => "application/zip",
+            "gz" => "application/gzip",
+            "png" => "image/png",
+            "jpg" | "jpeg" => "image/jpeg",
+            "gif" => "image/gif",
+            "svg" => "image/svg+xml",
+            _ => "application/octet-stream",
+        }
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册邮件命名空间下的函数
+    let mail_ns = registry.namespace("mail");
+    mail_ns.add_function("send", mail::cn_send);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
@@ -1,7 +1,47 @@
 use ::std::collections::HashMap;
+use serde_json::Value as JsonValue;
 
 // 导入通用库
 use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::json_value;
+
+// 解析列表参数：支持单个数组参数（脚本中的数组以JSON传入，也可以是 "[1, 2, 3]" 形式的字符串），或多个独立参数
+fn parse_list(args: &[String]) -> Vec<JsonValue> {
+    if args.len() == 1 {
+        let trimmed = args[0].trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            if let Ok(JsonValue::Array(items)) = serde_json::from_str::<JsonValue>(trimmed) {
+                return items;
+            }
+
+            // 脚本中手写的字符串不一定是合法JSON（如 "[a, b]"），按逗号拆分
+            let inner = &trimmed[1..trimmed.len() - 1];
+            if inner.trim().is_empty() {
+                return Vec::new();
+            }
+            return inner.split(',').map(|item| parse_element(item.trim())).collect();
+        }
+    }
+
+    args.iter().map(|arg| parse_element(arg)).collect()
+}
+
+// 将单个参数解析为JSON值，无法解析时作为字符串
+fn parse_element(item: &str) -> JsonValue {
+    serde_json::from_str::<JsonValue>(item).unwrap_or_else(|_| JsonValue::String(item.to_string()))
+}
+
+// 解析数值列表参数，忽略无法解析为数值的元素
+fn parse_numbers(args: &[String]) -> Vec<f64> {
+    parse_list(args)
+        .iter()
+        .filter_map(|value| match value {
+            JsonValue::Number(n) => n.as_f64(),
+            JsonValue::String(s) => s.trim().parse::<f64>().ok(),
+            _ => None,
+        })
+        .collect()
+}
 
 // 根命名空间数学函数
 // 绝对值函数
@@ -200,7 +240,7 @@ mod trig {
         }
 
         if let Ok(num) = args[0].parse::<f64>() {
-            if num >= -1.0 && num <= 1.0 {
+            if (-1.0..=1.0).contains(&num) {
                 num.asin().to_string()
             } else {
                 "NaN".to_string()
@@ -217,7 +257,7 @@ mod trig {
         }
 
         if let Ok(num) = args[0].parse::<f64>() {
-            if num >= -1.0 && num <= 1.0 {
+            if (-1.0..=1.0).contains(&num) {
                 num.acos().to_string()
             } else {
                 "NaN".to_string()
@@ -429,47 +469,88 @@ mod hyperbolic {
 
 // 统计函数命名空间
 mod stats {
+    use super::{json_value, parse_numbers};
+    use serde_json::Value as JsonValue;
+
     // 计算平均值
-    pub fn cn_mean(args: Vec<String>) -> String {
-        if args.is_empty() {
-            return "0".to_string();
-        }
+    fn mean_of(numbers: &[f64]) -> f64 {
+        numbers.iter().sum::<f64>() / numbers.len() as f64
+    }
 
-        let mut sum = 0.0;
-        let mut count = 0;
+    // 计算样本方差（n-1）
+    fn sample_variance(numbers: &[f64]) -> f64 {
+        let mean = mean_of(numbers);
+        numbers.iter()
+            .map(|x| (x - mean).powi(2))
+            .sum::<f64>() / (numbers.len() - 1) as f64
+    }
 
-        for arg in args {
-            if let Ok(num) = arg.parse::<f64>() {
-                sum += num;
-                count += 1;
-            }
-        }
+    // 按升序排序后的副本
+    fn sorted(numbers: &[f64]) -> Vec<f64> {
+        let mut sorted = numbers.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        sorted
+    }
 
-        if count > 0 {
-            (sum / count as f64).to_string()
-        } else {
-            "0".to_string()
+    // 线性插值计算分位数，q 取值 0 到 1
+    fn quantile_of(sorted: &[f64], q: f64) -> f64 {
+        let position = q * (sorted.len() - 1) as f64;
+        let lower = position.floor() as usize;
+        let upper = position.ceil() as usize;
+        let fraction = position - lower as f64;
+        sorted[lower] + (sorted[upper] - sorted[lower]) * fraction
+    }
+
+    // 拆分"数据 + 末尾参数"形式的参数，例如 percentile("1", "2", "3", "50")
+    // 或 percentile("[1, 2, 3]", "50")
+    fn split_last(args: &[String]) -> Option<(Vec<f64>, f64)> {
+        let (last, data) = args.split_last()?;
+        let param = last.trim().parse::<f64>().ok()?;
+        Some((parse_numbers(data), param))
+    }
+
+    // 解析两个等长的数组参数
+    fn parse_pairs(args: &[String]) -> Option<(Vec<f64>, Vec<f64>)> {
+        if args.len() < 2 {
+            return None;
+        }
+        let xs = parse_numbers(&args[..1]);
+        let ys = parse_numbers(&args[1..2]);
+        if xs.len() != ys.len() || xs.len() < 2 {
+            return None;
         }
+        Some((xs, ys))
     }
 
-    // 计算中位数
-    pub fn cn_median(args: Vec<String>) -> String {
-        if args.is_empty() {
+    // 计算样本协方差
+    fn covariance_of(xs: &[f64], ys: &[f64]) -> f64 {
+        let mean_x = mean_of(xs);
+        let mean_y = mean_of(ys);
+        xs.iter().zip(ys)
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum::<f64>() / (xs.len() - 1) as f64
+    }
+
+    // 计算平均值
+    pub fn cn_mean(args: Vec<String>) -> String {
+        let numbers = parse_numbers(&args);
+
+        if numbers.is_empty() {
             return "0".to_string();
         }
 
-        let mut numbers: Vec<f64> = Vec::new();
-        for arg in args {
-            if let Ok(num) = arg.parse::<f64>() {
-                numbers.push(num);
-            }
-        }
+        mean_of(&numbers).to_string()
+    }
+
+    // 计算中位数
+    pub fn cn_median(args: Vec<String>) -> String {
+        let numbers = parse_numbers(&args);
 
         if numbers.is_empty() {
             return "0".to_string();
         }
 
-        numbers.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let numbers = sorted(&numbers);
         let len = numbers.len();
 
         if len % 2 == 0 {
@@ -481,52 +562,421 @@ mod stats {
 
     // 计算标准差
     pub fn cn_stddev(args: Vec<String>) -> String {
-        if args.len() < 2 {
+        let numbers = parse_numbers(&args);
+
+        if numbers.len() < 2 {
+            return "0".to_string();
+        }
+
+        sample_variance(&numbers).sqrt().to_string()
+    }
+
+    // 计算方差
+    pub fn cn_variance(args: Vec<String>) -> String {
+        let numbers = parse_numbers(&args);
+
+        if numbers.len() < 2 {
+            return "0".to_string();
+        }
+
+        sample_variance(&numbers).to_string()
+    }
+
+    // 计算众数（出现次数相同时返回较小的值）
+    pub fn cn_mode(args: Vec<String>) -> String {
+        let numbers = sorted(&parse_numbers(&args));
+
+        if numbers.is_empty() {
             return "0".to_string();
         }
 
-        let mut numbers: Vec<f64> = Vec::new();
-        for arg in args {
-            if let Ok(num) = arg.parse::<f64>() {
-                numbers.push(num);
+        let mut best = numbers[0];
+        let mut best_count = 0;
+        let mut i = 0;
+        while i < numbers.len() {
+            let mut j = i;
+            while j < numbers.len() && numbers[j] == numbers[i] {
+                j += 1;
             }
+            if j - i > best_count {
+                best = numbers[i];
+                best_count = j - i;
+            }
+            i = j;
         }
 
-        if numbers.len() < 2 {
+        best.to_string()
+    }
+
+    // 计算百分位数
+    // 参数: 数据..., p（0到100）
+    pub fn cn_percentile(args: Vec<String>) -> String {
+        match split_last(&args) {
+            Some((numbers, p)) if !numbers.is_empty() && (0.0..=100.0).contains(&p) => {
+                quantile_of(&sorted(&numbers), p / 100.0).to_string()
+            },
+            _ => "NaN".to_string(),
+        }
+    }
+
+    // 计算分位数
+    // 参数: 数据..., q（0到1）
+    pub fn cn_quantile(args: Vec<String>) -> String {
+        match split_last(&args) {
+            Some((numbers, q)) if !numbers.is_empty() && (0.0..=1.0).contains(&q) => {
+                quantile_of(&sorted(&numbers), q).to_string()
+            },
+            _ => "NaN".to_string(),
+        }
+    }
+
+    // 计算最小值
+    pub fn cn_min(args: Vec<String>) -> String {
+        let numbers = parse_numbers(&args);
+
+        if numbers.is_empty() {
             return "0".to_string();
         }
 
-        let mean = numbers.iter().sum::<f64>() / numbers.len() as f64;
-        let variance = numbers.iter()
-            .map(|x| (x - mean).powi(2))
-            .sum::<f64>() / (numbers.len() - 1) as f64;
+        numbers.iter().cloned().fold(f64::INFINITY, f64::min).to_string()
+    }
+
+    // 计算最大值
+    pub fn cn_max(args: Vec<String>) -> String {
+        let numbers = parse_numbers(&args);
+
+        if numbers.is_empty() {
+            return "0".to_string();
+        }
 
-        variance.sqrt().to_string()
+        numbers.iter().cloned().fold(f64::NEG_INFINITY, f64::max).to_string()
     }
 
-    // 计算方差
-    pub fn cn_variance(args: Vec<String>) -> String {
-        if args.len() < 2 {
+    // 计算极差（最大值 - 最小值）
+    pub fn cn_range(args: Vec<String>) -> String {
+        let numbers = parse_numbers(&args);
+
+        if numbers.is_empty() {
             return "0".to_string();
         }
 
-        let mut numbers: Vec<f64> = Vec::new();
-        for arg in args {
-            if let Ok(num) = arg.parse::<f64>() {
-                numbers.push(num);
-            }
+        let min = numbers.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = numbers.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        (max - min).to_string()
+    }
+
+    // 计算样本偏度（调整后的 Fisher-Pearson 系数）
+    pub fn cn_skewness(args: Vec<String>) -> String {
+        let numbers = parse_numbers(&args);
+        let n = numbers.len() as f64;
+
+        if numbers.len() < 3 {
+            return "NaN".to_string();
+        }
+
+        let mean = mean_of(&numbers);
+        let stddev = sample_variance(&numbers).sqrt();
+        if stddev == 0.0 {
+            return "NaN".to_string();
+        }
+
+        let sum_cubed = numbers.iter()
+            .map(|x| ((x - mean) / stddev).powi(3))
+            .sum::<f64>();
+        (n / ((n - 1.0) * (n - 2.0)) * sum_cubed).to_string()
+    }
+
+    // 计算样本超额峰度（正态分布为0）
+    pub fn cn_kurtosis(args: Vec<String>) -> String {
+        let numbers = parse_numbers(&args);
+        let n = numbers.len() as f64;
+
+        if numbers.len() < 4 {
+            return "NaN".to_string();
+        }
+
+        let mean = mean_of(&numbers);
+        let stddev = sample_variance(&numbers).sqrt();
+        if stddev == 0.0 {
+            return "NaN".to_string();
+        }
+
+        let sum_fourth = numbers.iter()
+            .map(|x| ((x - mean) / stddev).powi(4))
+            .sum::<f64>();
+        let kurtosis = n * (n + 1.0) / ((n - 1.0) * (n - 2.0) * (n - 3.0)) * sum_fourth
+            - 3.0 * (n - 1.0).powi(2) / ((n - 2.0) * (n - 3.0));
+        kurtosis.to_string()
+    }
+
+    // 计算样本协方差
+    // 参数: xs数组, ys数组
+    pub fn cn_covariance(args: Vec<String>) -> String {
+        match parse_pairs(&args) {
+            Some((xs, ys)) => covariance_of(&xs, &ys).to_string(),
+            None => "NaN".to_string(),
+        }
+    }
+
+    // 计算皮尔逊相关系数
+    // 参数: xs数组, ys数组
+    pub fn cn_correlation(args: Vec<String>) -> String {
+        match parse_pairs(&args) {
+            Some((xs, ys)) => {
+                let denominator = sample_variance(&xs).sqrt() * sample_variance(&ys).sqrt();
+                if denominator == 0.0 {
+                    return "NaN".to_string();
+                }
+                (covariance_of(&xs, &ys) / denominator).to_string()
+            },
+            None => "NaN".to_string(),
         }
+    }
+
+    // 计算每个值的标准分数，返回数组
+    pub fn cn_zscore(args: Vec<String>) -> String {
+        let numbers = parse_numbers(&args);
 
         if numbers.len() < 2 {
-            return "0".to_string();
+            return "[]".to_string();
+        }
+
+        let mean = mean_of(&numbers);
+        let stddev = sample_variance(&numbers).sqrt();
+        let scores: Vec<JsonValue> = numbers.iter()
+            .map(|x| {
+                if stddev == 0.0 {
+                    JsonValue::from(0.0)
+                } else {
+                    JsonValue::from((x - mean) / stddev)
+                }
+            })
+            .collect();
+
+        json_value(&JsonValue::Array(scores).to_string())
+    }
+
+    // 计算拟合结果的决定系数 R²
+    fn r_squared(ys: &[f64], predicted: &[f64]) -> f64 {
+        let mean = mean_of(ys);
+        let ss_tot = ys.iter().map(|y| (y - mean).powi(2)).sum::<f64>();
+        let ss_res = ys.iter().zip(predicted)
+            .map(|(y, p)| (y - p).powi(2))
+            .sum::<f64>();
+        if ss_tot == 0.0 {
+            1.0
+        } else {
+            1.0 - ss_res / ss_tot
         }
+    }
 
-        let mean = numbers.iter().sum::<f64>() / numbers.len() as f64;
-        let variance = numbers.iter()
-            .map(|x| (x - mean).powi(2))
-            .sum::<f64>() / (numbers.len() - 1) as f64;
+    // 使用部分主元高斯消元法求解线性方程组
+    fn solve_linear_system(mut matrix: Vec<Vec<f64>>, mut rhs: Vec<f64>) -> Option<Vec<f64>> {
+        let n = rhs.len();
+        for col in 0..n {
+            let pivot = (col..n).max_by(|&a, &b| {
+                matrix[a][col].abs().partial_cmp(&matrix[b][col].abs()).unwrap_or(std::cmp::Ordering::Equal)
+            })?;
+            if matrix[pivot][col].abs() < 1e-12 {
+                return None;
+            }
+            matrix.swap(col, pivot);
+            rhs.swap(col, pivot);
+
+            let pivot_row = matrix[col].clone();
+            for row in col + 1..n {
+                let factor = matrix[row][col] / pivot_row[col];
+                for (target, source) in matrix[row].iter_mut().zip(&pivot_row).skip(col) {
+                    *target -= factor * source;
+                }
+                rhs[row] -= factor * rhs[col];
+            }
+        }
+
+        let mut solution = vec![0.0; n];
+        for row in (0..n).rev() {
+            let sum = (row + 1..n).map(|k| matrix[row][k] * solution[k]).sum::<f64>();
+            solution[row] = (rhs[row] - sum) / matrix[row][row];
+        }
+        Some(solution)
+    }
+
+    // 计算多项式的值，系数按升幂排列
+    fn evaluate_polynomial(coefficients: &[f64], x: f64) -> f64 {
+        coefficients.iter().rev().fold(0.0, |acc, c| acc * x + c)
+    }
+
+    // 简单线性回归，返回 {"slope", "intercept", "r_squared"}
+    // 参数: xs数组, ys数组
+    pub fn cn_linregress(args: Vec<String>) -> String {
+        let (xs, ys) = match parse_pairs(&args) {
+            Some(pairs) => pairs,
+            None => return "错误: 需要两个长度相同且至少包含2个数值的数组".to_string(),
+        };
+
+        let variance_x = sample_variance(&xs);
+        if variance_x == 0.0 {
+            return "错误: x值不能全部相同".to_string();
+        }
+
+        let slope = covariance_of(&xs, &ys) / variance_x;
+        let intercept = mean_of(&ys) - slope * mean_of(&xs);
+        let predicted: Vec<f64> = xs.iter().map(|x| slope * x + intercept).collect();
 
-        variance.to_string()
+        json_value(&serde_json::json!({
+            "slope": slope,
+            "intercept": intercept,
+            "r_squared": r_squared(&ys, &predicted),
+        }).to_string())
+    }
+
+    // 最小二乘多项式拟合，返回 {"coefficients", "r_squared"}，系数按升幂排列
+    // 参数: xs数组, ys数组, degree
+    pub fn cn_polyfit(args: Vec<String>) -> String {
+        let (xs, ys) = match parse_pairs(&args) {
+            Some(pairs) => pairs,
+            None => return "错误: 需要两个长度相同且至少包含2个数值的数组".to_string(),
+        };
+
+        let degree = match args.get(2).and_then(|d| d.trim().parse::<usize>().ok()) {
+            Some(d) if d >= 1 && d < xs.len() => d,
+            _ => return "错误: 多项式次数必须至少为1且小于数据点数量".to_string(),
+        };
+
+        // 构造正规方程 (XᵀX)c = Xᵀy
+        let size = degree + 1;
+        let mut matrix = vec![vec![0.0; size]; size];
+        let mut rhs = vec![0.0; size];
+        for (x, y) in xs.iter().zip(&ys) {
+            let powers: Vec<f64> = (0..2 * size).map(|p| x.powi(p as i32)).collect();
+            for row in 0..size {
+                for col in 0..size {
+                    matrix[row][col] += powers[row + col];
+                }
+                rhs[row] += powers[row] * y;
+            }
+        }
+
+        let coefficients: Vec<f64> = match solve_linear_system(matrix, rhs) {
+            // 加0.0将-0.0规范为0.0
+            Some(c) => c.into_iter().map(|v| v + 0.0).collect(),
+            None => return "错误: 无法拟合，数据点不足以确定多项式".to_string(),
+        };
+        let predicted: Vec<f64> = xs.iter().map(|x| evaluate_polynomial(&coefficients, *x)).collect();
+
+        json_value(&serde_json::json!({
+            "coefficients": coefficients,
+            "r_squared": r_squared(&ys, &predicted),
+        }).to_string())
+    }
+
+    // 计算多项式在x处的值，系数按升幂排列
+    // 参数: 系数数组, x
+    pub fn cn_polyval(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "NaN".to_string();
+        }
+
+        let coefficients = parse_numbers(&args[..1]);
+        match args[1].trim().parse::<f64>() {
+            Ok(x) => evaluate_polynomial(&coefficients, x).to_string(),
+            Err(_) => "NaN".to_string(),
+        }
+    }
+
+    // 查找值所在的区间下标，区间为左闭右开，最后一个区间为闭区间
+    fn bin_index(edges: &[f64], value: f64) -> Option<usize> {
+        let last = edges.len() - 1;
+        if value < edges[0] || value > edges[last] || value.is_nan() {
+            return None;
+        }
+        if value == edges[last] {
+            return Some(last - 1);
+        }
+        // edges 已排序，二分查找第一个大于value的边界
+        let upper = edges.partition_point(|edge| *edge <= value);
+        Some(upper - 1)
+    }
+
+    // 等宽直方图，返回 {"edges", "counts"}
+    // 参数: 数值数组, bins（区间数量，默认为10）
+    pub fn cn_histogram(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 需要数值数组参数".to_string();
+        }
+
+        let numbers = parse_numbers(&args[..1]);
+        if numbers.is_empty() {
+            return "错误: 数组中没有有效数值".to_string();
+        }
+
+        let bins = match args.get(1) {
+            Some(b) => match b.trim().parse::<usize>() {
+                Ok(b) if b > 0 => b,
+                _ => return "错误: 区间数量必须是正整数".to_string(),
+            },
+            None => 10,
+        };
+
+        let min = numbers.iter().cloned().fold(f64::INFINITY, f64::min);
+        let mut max = numbers.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        // 所有值相同时，扩展为宽度为1的区间
+        if max == min {
+            max = min + 1.0;
+        }
+
+        let width = (max - min) / bins as f64;
+        let mut edges: Vec<f64> = (0..bins).map(|i| min + width * i as f64).collect();
+        edges.push(max);
+
+        let mut counts = vec![0u64; bins];
+        for value in &numbers {
+            if let Some(index) = bin_index(&edges, *value) {
+                counts[index] += 1;
+            }
+        }
+
+        json_value(&serde_json::json!({
+            "edges": edges,
+            "counts": counts,
+        }).to_string())
+    }
+
+    // 按给定边界分箱，返回 {"edges", "counts", "indices"}
+    // indices 为每个值所在区间的下标，超出范围的值为 -1
+    // 参数: 数值数组, 边界数组（升序）
+    pub fn cn_cut(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 需要数值数组和边界数组参数".to_string();
+        }
+
+        let numbers = parse_numbers(&args[..1]);
+        let edges = parse_numbers(&args[1..2]);
+
+        if edges.len() < 2 {
+            return "错误: 边界数组至少需要2个数值".to_string();
+        }
+        if edges.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return "错误: 边界数组必须严格递增".to_string();
+        }
+
+        let mut counts = vec![0u64; edges.len() - 1];
+        let mut indices = Vec::with_capacity(numbers.len());
+        for value in &numbers {
+            match bin_index(&edges, *value) {
+                Some(index) => {
+                    counts[index] += 1;
+                    indices.push(index as i64);
+                },
+                None => indices.push(-1),
+            }
+        }
+
+        json_value(&serde_json::json!({
+            "edges": edges,
+            "counts": counts,
+            "indices": indices,
+        }).to_string())
     }
 }
 
@@ -581,39 +1031,117 @@ mod constants {
 
 // 随机数生成命名空间
 mod random {
+    use super::{json_value, parse_list};
+    use serde_json::Value as JsonValue;
+    use std::sync::Mutex;
     use std::time::{SystemTime, UNIX_EPOCH};
 
-    // 简单的线性同余生成器状态
-    static mut RNG_STATE: u64 = 1;
+    // xoshiro256** 伪随机数生成器
+    pub struct Xoshiro256 {
+        state: [u64; 4],
+    }
+
+    impl Xoshiro256 {
+        // 使用 splitmix64 将单个种子扩展为完整状态
+        pub const fn new(seed: u64) -> Self {
+            let mut x = seed;
+            let mut state = [0u64; 4];
+            let mut i = 0;
+            while i < 4 {
+                x = x.wrapping_add(0x9E3779B97F4A7C15);
+                let mut z = x;
+                z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+                z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+                state[i] = z ^ (z >> 31);
+                i += 1;
+            }
+            Xoshiro256 { state }
+        }
+
+        pub fn next_u64(&mut self) -> u64 {
+            let s = &mut self.state;
+            let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+            let t = s[1] << 17;
+
+            s[2] ^= s[0];
+            s[3] ^= s[1];
+            s[1] ^= s[2];
+            s[0] ^= s[3];
+            s[2] ^= t;
+            s[3] = s[3].rotate_left(45);
+
+            result
+        }
+
+        // 生成 [0, 1) 之间的浮点数
+        pub fn next_f64(&mut self) -> f64 {
+            (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+        }
+
+        // 生成 [0, bound) 之间的整数（拒绝采样，避免取模偏差）
+        pub fn next_below(&mut self, bound: u64) -> u64 {
+            if bound == 0 {
+                return 0;
+            }
+            let zone = u64::MAX - (u64::MAX % bound);
+            loop {
+                let value = self.next_u64();
+                if value < zone {
+                    return value % bound;
+                }
+            }
+        }
+
+        // 标准正态分布（Box-Muller 变换）
+        pub fn next_gaussian(&mut self) -> f64 {
+            let u1 = 1.0 - self.next_f64();
+            let u2 = self.next_f64();
+            (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+        }
+    }
+
+    // 全局生成器，默认种子为1，保证未设置种子时结果可复现
+    static RNG: Mutex<Xoshiro256> = Mutex::new(Xoshiro256::new(1));
+
+    // 在锁内使用全局生成器
+    fn with_rng<T>(f: impl FnOnce(&mut Xoshiro256) -> T) -> T {
+        let mut rng = RNG.lock().unwrap_or_else(|e| e.into_inner());
+        f(&mut rng)
+    }
+
+    // 将 JSON 元素转换为返回给脚本的字符串
+    fn element_to_string(value: &JsonValue) -> String {
+        match value {
+            JsonValue::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
 
     // 设置随机数种子
     pub fn cn_seed(args: Vec<String>) -> String {
-        unsafe {
-            if args.is_empty() {
-                // 使用当前时间作为种子
-                let now = SystemTime::now()
-                    .duration_since(UNIX_EPOCH)
-                    .unwrap()
-                    .as_nanos() as u64;
-                RNG_STATE = now;
-            } else if let Ok(seed) = args[0].parse::<u64>() {
-                RNG_STATE = seed;
+        let seed = if args.is_empty() {
+            // 使用当前时间作为种子
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(1)
+        } else {
+            match args[0].parse::<u64>() {
+                Ok(seed) => seed,
+                Err(_) => return "0".to_string(),
             }
-            RNG_STATE.to_string()
-        }
+        };
+
+        with_rng(|rng| *rng = Xoshiro256::new(seed));
+        seed.to_string()
     }
 
     // 生成0到1之间的随机浮点数
     pub fn cn_random(_args: Vec<String>) -> String {
-        unsafe {
-            // 线性同余生成器: (a * x + c) mod m
-            RNG_STATE = RNG_STATE.wrapping_mul(1103515245).wrapping_add(12345);
-            let normalized = (RNG_STATE as f64) / (u64::MAX as f64);
-            normalized.to_string()
-        }
+        with_rng(|rng| rng.next_f64()).to_string()
     }
 
-    // 生成指定范围内的随机整数
+    // 生成指定范围内的随机整数 [min, max)
     pub fn cn_randint(args: Vec<String>) -> String {
         if args.len() < 2 {
             return "0".to_string();
@@ -626,12 +1154,9 @@ mod random {
             return min.to_string();
         }
 
-        unsafe {
-            RNG_STATE = RNG_STATE.wrapping_mul(1103515245).wrapping_add(12345);
-            let range = (max - min) as u64;
-            let result = min + ((RNG_STATE % range) as i32);
-            result.to_string()
-        }
+        let range = (max as i64 - min as i64) as u64;
+        let offset = with_rng(|rng| rng.next_below(range));
+        (min as i64 + offset as i64).to_string()
     }
 
     // 生成指定范围内的随机浮点数
@@ -643,130 +1168,2469 @@ mod random {
         let min = args[0].parse::<f64>().unwrap_or(0.0);
         let max = args[1].parse::<f64>().unwrap_or(1.0);
 
-        unsafe {
-            RNG_STATE = RNG_STATE.wrapping_mul(1103515245).wrapping_add(12345);
-            let normalized = (RNG_STATE as f64) / (u64::MAX as f64);
-            let result = min + normalized * (max - min);
-            result.to_string()
-        }
+        let normalized = with_rng(|rng| rng.next_f64());
+        (min + normalized * (max - min)).to_string()
     }
-}
 
-// 数值分析命名空间
-mod numeric {
-    // 计算阶乘
-    pub fn cn_factorial(args: Vec<String>) -> String {
-        if args.is_empty() {
-            return "1".to_string();
+    // 正态分布随机数
+    // 参数: [mean], [stddev]，默认为标准正态分布
+    pub fn cn_gaussian(args: Vec<String>) -> String {
+        let mean = args.first().and_then(|a| a.parse::<f64>().ok()).unwrap_or(0.0);
+        let stddev = args.get(1).and_then(|a| a.parse::<f64>().ok()).unwrap_or(1.0);
+
+        if stddev < 0.0 {
+            return "NaN".to_string();
         }
 
-        if let Ok(n) = args[0].parse::<u32>() {
-            if n > 20 {
-                return "Infinity".to_string(); // 防止溢出
-            }
+        (mean + stddev * with_rng(|rng| rng.next_gaussian())).to_string()
+    }
 
-            let mut result = 1u64;
-            for i in 1..=n {
-                result *= i as u64;
-            }
-            result.to_string()
-        } else {
-            "1".to_string()
+    // 指数分布随机数
+    // 参数: [lambda]，默认为1
+    pub fn cn_exponential(args: Vec<String>) -> String {
+        let lambda = args.first().and_then(|a| a.parse::<f64>().ok()).unwrap_or(1.0);
+
+        if lambda <= 0.0 {
+            return "NaN".to_string();
         }
+
+        let u = with_rng(|rng| rng.next_f64());
+        (-(1.0 - u).ln() / lambda).to_string()
     }
 
-    // 计算组合数 C(n, k)
-    pub fn cn_combination(args: Vec<String>) -> String {
-        if args.len() < 2 {
-            return "0".to_string();
-        }
+    // 泊松分布随机数
+    // 参数: lambda
+    pub fn cn_poisson(args: Vec<String>) -> String {
+        let lambda = match args.first().and_then(|a| a.parse::<f64>().ok()) {
+            Some(l) if l >= 0.0 && l.is_finite() => l,
+            _ => return "NaN".to_string(),
+        };
 
-        let n = args[0].parse::<u32>().unwrap_or(0);
-        let k = args[1].parse::<u32>().unwrap_or(0);
+        with_rng(|rng| poisson(rng, lambda)).to_string()
+    }
 
-        if k > n {
-            return "0".to_string();
+    fn poisson(rng: &mut Xoshiro256, lambda: f64) -> u64 {
+        if lambda == 0.0 {
+            return 0;
         }
 
-        if k == 0 || k == n {
-            return "1".to_string();
+        if lambda < 30.0 {
+            // Knuth 乘积法，适用于较小的 lambda
+            let limit = (-lambda).exp();
+            let mut k = 0u64;
+            let mut p = rng.next_f64();
+            while p > limit {
+                k += 1;
+                p *= rng.next_f64();
+            }
+            return k;
         }
 
-        // 使用更稳定的计算方法
-        let k = k.min(n - k); // 利用对称性
-        let mut result = 1u64;
+        // Hörmann 变换拒绝法 (PTRS)，适用于较大的 lambda
+        let slam = lambda.sqrt();
+        let loglam = lambda.ln();
+        let b = 0.931 + 2.53 * slam;
+        let a = -0.059 + 0.02483 * b;
+        let invalpha = 1.1239 + 1.1328 / (b - 3.4);
+        let vr = 0.9277 - 3.6224 / (b - 2.0);
 
-        for i in 0..k {
-            result = result * (n - i) as u64 / (i + 1) as u64;
-        }
+        loop {
+            let u = rng.next_f64() - 0.5;
+            let v = rng.next_f64();
+            let us = 0.5 - u.abs();
+            let k = ((2.0 * a / us + b) * u + lambda + 0.43).floor();
 
-        result.to_string()
+            if us >= 0.07 && v <= vr {
+                return k as u64;
+            }
+            if k < 0.0 || (us < 0.013 && v > us) {
+                continue;
+            }
+            if (v * invalpha / (a / (us * us) + b)).ln() <= -lambda + k * loglam - ln_factorial(k) {
+                return k as u64;
+            }
+        }
     }
 
-    // 计算排列数 P(n, k)
-    pub fn cn_permutation(args: Vec<String>) -> String {
-        if args.len() < 2 {
-            return "0".to_string();
+    // ln(k!)，使用 Stirling 级数近似
+    fn ln_factorial(k: f64) -> f64 {
+        if k < 10.0 {
+            let mut result = 0.0;
+            let mut i = 2.0;
+            while i <= k {
+                result += f64::ln(i);
+                i += 1.0;
+            }
+            return result;
         }
+        let n = k + 1.0;
+        (n - 0.5) * n.ln() - n + 0.5 * (2.0 * std::f64::consts::PI).ln() + 1.0 / (12.0 * n)
+            - 1.0 / (360.0 * n * n * n)
+    }
 
-        let n = args[0].parse::<u32>().unwrap_or(0);
-        let k = args[1].parse::<u32>().unwrap_or(0);
+    // 随机打乱列表，返回数组
+    // 参数: JSON数组，或多个元素
+    pub fn cn_shuffle(args: Vec<String>) -> String {
+        let mut items = parse_list(&args);
+
+        with_rng(|rng| {
+            // Fisher-Yates 洗牌
+            for i in (1..items.len()).rev() {
+                let j = rng.next_below(i as u64 + 1) as usize;
+                items.swap(i, j);
+            }
+        });
+
+        json_value(&JsonValue::Array(items).to_string())
+    }
+
+    // 从列表中随机选择一个元素
+    // 参数: JSON数组，或多个元素
+    pub fn cn_choice(args: Vec<String>) -> String {
+        let items = parse_list(&args);
+        if items.is_empty() {
+            return String::new();
+        }
+
+        let index = with_rng(|rng| rng.next_below(items.len() as u64)) as usize;
+        element_to_string(&items[index])
+    }
+
+    // 从列表中无放回地随机抽取k个元素，返回数组
+    // 参数: JSON数组, k
+    pub fn cn_sample(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "[]".to_string();
+        }
+
+        let mut items = parse_list(&args[..1]);
+        let k = args[1].parse::<usize>().unwrap_or(0).min(items.len());
+
+        with_rng(|rng| {
+            // 部分 Fisher-Yates，只打乱前k个位置
+            for i in 0..k {
+                let j = i + rng.next_below((items.len() - i) as u64) as usize;
+                items.swap(i, j);
+            }
+        });
+        items.truncate(k);
+
+        json_value(&JsonValue::Array(items).to_string())
+    }
+}
+
+// 安全随机数（使用操作系统随机源，不受 random::seed 影响，结果不可复现）
+mod secure_random {
+    // 从操作系统随机源填充缓冲区
+    fn fill(buf: &mut [u8]) -> bool {
+        getrandom::fill(buf).is_ok()
+    }
+
+    fn secure_u64() -> Option<u64> {
+        let mut buf = [0u8; 8];
+        if fill(&mut buf) {
+            Some(u64::from_le_bytes(buf))
+        } else {
+            None
+        }
+    }
+
+    // 生成n个安全随机字节，返回16进制字符串
+    pub fn cn_secure_bytes(args: Vec<String>) -> String {
+        let n = match args.first().and_then(|a| a.parse::<usize>().ok()) {
+            Some(n) if n <= 1024 * 1024 => n,
+            _ => return "错误: 字节数必须是0到1048576之间的整数".to_string(),
+        };
+
+        let mut buf = vec![0u8; n];
+        if !fill(&mut buf) {
+            return "错误: 无法读取系统随机源".to_string();
+        }
+
+        buf.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    // 生成指定范围内的安全随机整数 [min, max)
+    pub fn cn_secure_int(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "0".to_string();
+        }
+
+        let min = args[0].parse::<i64>().unwrap_or(0);
+        let max = args[1].parse::<i64>().unwrap_or(1);
+
+        if min >= max {
+            return min.to_string();
+        }
+
+        // 拒绝采样，避免取模偏差
+        let range = max.wrapping_sub(min) as u64;
+        let zone = u64::MAX - (u64::MAX % range);
+        loop {
+            let value = match secure_u64() {
+                Some(v) => v,
+                None => return "错误: 无法读取系统随机源".to_string(),
+            };
+            if value < zone {
+                return min.wrapping_add((value % range) as i64).to_string();
+            }
+        }
+    }
+
+    // 生成版本4的随机UUID
+    pub fn cn_uuid4(_args: Vec<String>) -> String {
+        let mut bytes = [0u8; 16];
+        if !fill(&mut bytes) {
+            return "错误: 无法读取系统随机源".to_string();
+        }
+
+        // 设置版本号(4)和变体(RFC 4122)
+        bytes[6] = (bytes[6] & 0x0f) | 0x40;
+        bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+        let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+    }
+}
+
+// 数值分析命名空间
+mod numeric {
+    use super::{expr, json_value, parse_numbers};
+    use num_bigint::BigInt;
+    use num_traits::{One, Signed, ToPrimitive, Zero};
+    use serde_json::Value as JsonValue;
+
+    // 计算阶乘
+    pub fn cn_factorial(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "1".to_string();
+        }
+
+        if let Ok(n) = args[0].parse::<u32>() {
+            if n > 20 {
+                return "Infinity".to_string(); // 防止溢出
+            }
+
+            let mut result = 1u64;
+            for i in 1..=n {
+                result *= i as u64;
+            }
+            result.to_string()
+        } else {
+            "1".to_string()
+        }
+    }
+
+    // 计算组合数 C(n, k)
+    pub fn cn_combination(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "0".to_string();
+        }
+
+        let n = args[0].parse::<u32>().unwrap_or(0);
+        let k = args[1].parse::<u32>().unwrap_or(0);
 
         if k > n {
             return "0".to_string();
         }
 
-        let mut result = 1u64;
-        for i in 0..k {
-            result *= (n - i) as u64;
+        if k == 0 || k == n {
+            return "1".to_string();
+        }
+
+        // 使用更稳定的计算方法
+        let k = k.min(n - k); // 利用对称性
+        let mut result = 1u64;
+
+        for i in 0..k {
+            result = result * (n - i) as u64 / (i + 1) as u64;
+        }
+
+        result.to_string()
+    }
+
+    // 计算排列数 P(n, k)
+    pub fn cn_permutation(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "0".to_string();
+        }
+
+        let n = args[0].parse::<u32>().unwrap_or(0);
+        let k = args[1].parse::<u32>().unwrap_or(0);
+
+        if k > n {
+            return "0".to_string();
+        }
+
+        let mut result = 1u64;
+        for i in 0..k {
+            result *= (n - i) as u64;
+        }
+
+        result.to_string()
+    }
+
+    // 计算最大公约数
+    pub fn cn_gcd(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "0".to_string();
+        }
+
+        let mut a = args[0].parse::<u64>().unwrap_or(0);
+        let mut b = args[1].parse::<u64>().unwrap_or(0);
+
+        while b != 0 {
+            let temp = b;
+            b = a % b;
+            a = temp;
+        }
+
+        a.to_string()
+    }
+
+    // 计算最小公倍数
+    pub fn cn_lcm(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "0".to_string();
+        }
+
+        let a = args[0].parse::<u64>().unwrap_or(0);
+        let b = args[1].parse::<u64>().unwrap_or(0);
+
+        if a == 0 || b == 0 {
+            return "0".to_string();
+        }
+
+        // 使用 lcm(a,b) = |a*b| / gcd(a,b)
+        let mut gcd_a = a;
+        let mut gcd_b = b;
+
+        while gcd_b != 0 {
+            let temp = gcd_b;
+            gcd_b = gcd_a % gcd_b;
+            gcd_a = temp;
+        }
+
+        let result = (a * b) / gcd_a;
+        result.to_string()
+    }
+
+    // 解析可选的容差和最大迭代次数参数
+    fn solver_options(tolerance: Option<&String>, max_iter: Option<&String>) -> (f64, usize) {
+        let tolerance = tolerance
+            .and_then(|t| t.trim().parse::<f64>().ok())
+            .filter(|t| *t > 0.0)
+            .unwrap_or(1e-10);
+        let max_iter = max_iter
+            .and_then(|m| m.trim().parse::<usize>().ok())
+            .filter(|m| *m > 0)
+            .unwrap_or(100);
+        (tolerance, max_iter)
+    }
+
+    // 将实根列表排序后作为数组返回
+    fn format_roots(mut roots: Vec<f64>) -> String {
+        roots.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        // 加0.0将-0.0规范为0.0
+        let roots: Vec<f64> = roots.into_iter().map(|r| r + 0.0).collect();
+        json_value(&JsonValue::from(roots).to_string())
+    }
+
+    // 二分法求根
+    // 参数: 表达式（变量为x）, a, b, [tolerance], [max_iter]
+    pub fn cn_bisect(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "错误: 需要参数 (表达式, a, b, [容差], [最大迭代次数])".to_string();
+        }
+
+        let f = match expr::parse(&args[0]) {
+            Ok(f) => f,
+            Err(e) => return format!("错误: {}", e),
+        };
+        let (mut a, mut b) = match (args[1].trim().parse::<f64>(), args[2].trim().parse::<f64>()) {
+            (Ok(a), Ok(b)) => (a, b),
+            _ => return "错误: 区间端点必须是数值".to_string(),
+        };
+        let (tolerance, max_iter) = solver_options(args.get(3), args.get(4));
+
+        let result = (|| {
+            let mut fa = expr::eval(&f, &[("x", a)])?;
+            let fb = expr::eval(&f, &[("x", b)])?;
+            if fa == 0.0 {
+                return Ok(a);
+            }
+            if fb == 0.0 {
+                return Ok(b);
+            }
+            if fa.signum() == fb.signum() {
+                return Err("区间两端的函数值必须异号".to_string());
+            }
+
+            for _ in 0..max_iter {
+                let mid = (a + b) / 2.0;
+                let fm = expr::eval(&f, &[("x", mid)])?;
+                if fm == 0.0 || (b - a).abs() / 2.0 < tolerance {
+                    return Ok(mid);
+                }
+                if fm.signum() == fa.signum() {
+                    a = mid;
+                    fa = fm;
+                } else {
+                    b = mid;
+                }
+            }
+            Ok((a + b) / 2.0)
+        })();
+
+        match result {
+            Ok(root) => root.to_string(),
+            Err(e) => format!("错误: {}", e),
+        }
+    }
+
+    // 牛顿迭代法求根（使用数值导数）
+    // 参数: 表达式（变量为x）, x0, [tolerance], [max_iter]
+    pub fn cn_newton(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 需要参数 (表达式, x0, [容差], [最大迭代次数])".to_string();
+        }
+
+        let f = match expr::parse(&args[0]) {
+            Ok(f) => f,
+            Err(e) => return format!("错误: {}", e),
+        };
+        let mut x = match args[1].trim().parse::<f64>() {
+            Ok(x) => x,
+            Err(_) => return "错误: 初始值必须是数值".to_string(),
+        };
+        let (tolerance, max_iter) = solver_options(args.get(2), args.get(3));
+
+        let result = (|| {
+            for _ in 0..max_iter {
+                let fx = expr::eval(&f, &[("x", x)])?;
+                let h = 1e-7 * x.abs().max(1.0);
+                let derivative = (expr::eval(&f, &[("x", x + h)])? - expr::eval(&f, &[("x", x - h)])?) / (2.0 * h);
+                if derivative == 0.0 || !derivative.is_finite() {
+                    return Err("导数为零，无法继续迭代".to_string());
+                }
+
+                let next = x - fx / derivative;
+                if (next - x).abs() < tolerance {
+                    return Ok(next);
+                }
+                x = next;
+            }
+            Err(format!("在 {} 次迭代内未收敛", max_iter))
+        })();
+
+        match result {
+            Ok(root) => root.to_string(),
+            Err(e) => format!("错误: {}", e),
+        }
+    }
+
+    // 求解一元二次方程 ax² + bx + c = 0 的实根，返回数组
+    pub fn cn_solve_quadratic(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "错误: 需要三个系数 (a, b, c)".to_string();
+        }
+
+        let coefficients: Vec<f64> = args[..3].iter().filter_map(|a| a.trim().parse::<f64>().ok()).collect();
+        if coefficients.len() < 3 {
+            return "错误: 系数必须是数值".to_string();
+        }
+
+        match quadratic_roots(coefficients[0], coefficients[1], coefficients[2]) {
+            Some(roots) => format_roots(roots),
+            None => "错误: 所有系数均为零，方程有无穷多解".to_string(),
+        }
+    }
+
+    fn quadratic_roots(a: f64, b: f64, c: f64) -> Option<Vec<f64>> {
+        if a == 0.0 {
+            // 退化为一次方程
+            if b == 0.0 {
+                return if c == 0.0 { None } else { Some(Vec::new()) };
+            }
+            return Some(vec![-c / b]);
+        }
+
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            Some(Vec::new())
+        } else if discriminant == 0.0 {
+            Some(vec![-b / (2.0 * a)])
+        } else {
+            // 使用数值稳定的求根公式，避免相近数相减
+            let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+            if q == 0.0 {
+                Some(vec![0.0])
+            } else {
+                Some(vec![q / a, c / q])
+            }
+        }
+    }
+
+    // 求解一元三次方程 ax³ + bx² + cx + d = 0 的实根，返回数组
+    pub fn cn_solve_cubic(args: Vec<String>) -> String {
+        if args.len() < 4 {
+            return "错误: 需要四个系数 (a, b, c, d)".to_string();
+        }
+
+        let coefficients: Vec<f64> = args[..4].iter().filter_map(|a| a.trim().parse::<f64>().ok()).collect();
+        if coefficients.len() < 4 {
+            return "错误: 系数必须是数值".to_string();
+        }
+        let (a, b, c, d) = (coefficients[0], coefficients[1], coefficients[2], coefficients[3]);
+
+        if a == 0.0 {
+            return match quadratic_roots(b, c, d) {
+                Some(roots) => format_roots(roots),
+                None => "错误: 所有系数均为零，方程有无穷多解".to_string(),
+            };
+        }
+
+        // 化为降次方程 t³ + pt + q = 0，其中 x = t - b/(3a)
+        let (b, c, d) = (b / a, c / a, d / a);
+        let p = c - b * b / 3.0;
+        let q = 2.0 * b * b * b / 27.0 - b * c / 3.0 + d;
+        let shift = -b / 3.0;
+        let discriminant = q * q / 4.0 + p * p * p / 27.0;
+        let epsilon = 1e-12;
+
+        let roots = if discriminant.abs() < epsilon {
+            if p.abs() < epsilon {
+                // 三重根
+                vec![shift]
+            } else {
+                // 一个单根和一个二重根
+                vec![3.0 * q / p + shift, -3.0 * q / (2.0 * p) + shift]
+            }
+        } else if discriminant > 0.0 {
+            // 一个实根（卡尔达诺公式）
+            let sqrt_disc = discriminant.sqrt();
+            vec![(-q / 2.0 + sqrt_disc).cbrt() + (-q / 2.0 - sqrt_disc).cbrt() + shift]
+        } else {
+            // 三个不同实根（三角函数解法）
+            let r = 2.0 * (-p / 3.0).sqrt();
+            let phi = (3.0 * q / (p * r)).clamp(-1.0, 1.0).acos() / 3.0;
+            (0..3)
+                .map(|k| r * (phi - 2.0 * std::f64::consts::PI * k as f64 / 3.0).cos() + shift)
+                .collect()
+        };
+
+        format_roots(roots)
+    }
+
+    // 以下为素数与数论函数
+    // u64 范围内使用确定性算法，超出范围的大整数使用 BigInt
+
+    // 模乘，使用 u128 避免溢出
+    fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+        ((a as u128 * b as u128) % m as u128) as u64
+    }
+
+    // 模幂
+    fn pow_mod(mut base: u64, mut exp: u64, m: u64) -> u64 {
+        let mut result = 1 % m;
+        base %= m;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = mul_mod(result, base, m);
+            }
+            base = mul_mod(base, base, m);
+            exp >>= 1;
+        }
+        result
+    }
+
+    // 米勒-拉宾测试使用的底数，对所有 u64 结果是确定的
+    const MILLER_RABIN_BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    fn is_prime_u64(n: u64) -> bool {
+        if n < 2 {
+            return false;
+        }
+        for &p in &MILLER_RABIN_BASES {
+            if n % p == 0 {
+                return n == p;
+            }
+        }
+
+        let mut d = n - 1;
+        let mut s = 0;
+        while d % 2 == 0 {
+            d /= 2;
+            s += 1;
+        }
+
+        'witness: for &a in &MILLER_RABIN_BASES {
+            let mut x = pow_mod(a, d, n);
+            if x == 1 || x == n - 1 {
+                continue;
+            }
+            for _ in 1..s {
+                x = mul_mod(x, x, n);
+                if x == n - 1 {
+                    continue 'witness;
+                }
+            }
+            return false;
+        }
+        true
+    }
+
+    // 大整数的米勒-拉宾测试（概率性，误判概率极低）
+    fn is_prime_big(n: &BigInt) -> bool {
+        if let Some(small) = n.to_u64() {
+            return is_prime_u64(small);
+        }
+        if n.is_negative() {
+            return false;
+        }
+
+        let one = BigInt::one();
+        let two = BigInt::from(2);
+        let n_minus_one = n - &one;
+        for &p in &MILLER_RABIN_BASES {
+            if (n % BigInt::from(p)).is_zero() {
+                return false;
+            }
+        }
+
+        let mut d = n_minus_one.clone();
+        let mut s = 0;
+        while (&d % &two).is_zero() {
+            d /= &two;
+            s += 1;
+        }
+
+        'witness: for &a in &MILLER_RABIN_BASES {
+            let mut x = BigInt::from(a).modpow(&d, n);
+            if x == one || x == n_minus_one {
+                continue;
+            }
+            for _ in 1..s {
+                x = x.modpow(&two, n);
+                if x == n_minus_one {
+                    continue 'witness;
+                }
+            }
+            return false;
+        }
+        true
+    }
+
+    fn gcd_u64(mut a: u64, mut b: u64) -> u64 {
+        while b != 0 {
+            let temp = b;
+            b = a % b;
+            a = temp;
+        }
+        a
+    }
+
+    // Pollard rho 算法寻找n的一个非平凡因子（n为合数）
+    fn pollard_rho(n: u64) -> u64 {
+        if n % 2 == 0 {
+            return 2;
+        }
+
+        let mut c = 1u64;
+        loop {
+            // 在u128中相加，避免 x² mod n + c 溢出u64
+            let f = |x: u64| ((u128::from(mul_mod(x, x, n)) + u128::from(c)) % u128::from(n)) as u64;
+            let (mut x, mut y, mut d) = (2u64, 2u64, 1u64);
+            while d == 1 {
+                x = f(x);
+                y = f(f(y));
+                d = gcd_u64(x.abs_diff(y), n);
+            }
+            if d != n {
+                return d;
+            }
+            c += 1;
+        }
+    }
+
+    // 分解质因数，结果按升序排列
+    fn factorize(n: u64) -> Vec<u64> {
+        let mut factors = Vec::new();
+        let mut stack = vec![n];
+        while let Some(m) = stack.pop() {
+            if m == 1 {
+                continue;
+            }
+            if is_prime_u64(m) {
+                factors.push(m);
+                continue;
+            }
+            let d = pollard_rho(m);
+            stack.push(d);
+            stack.push(m / d);
+        }
+        factors.sort_unstable();
+        factors
+    }
+
+    fn parse_bigint(arg: Option<&String>) -> Option<BigInt> {
+        arg.and_then(|a| a.trim().parse::<BigInt>().ok())
+    }
+
+    // 大整数结果：i64范围内作为整数返回，超出范围时返回十进制字符串，避免转为浮点数后丢失精度
+    // 参数: n - 结果
+    fn bigint_result(n: &BigInt) -> String {
+        match n.to_i64() {
+            Some(value) => json_value(&value.to_string()),
+            None => json_value(&JsonValue::String(n.to_string()).to_string()),
+        }
+    }
+
+    // 判断是否为素数
+    pub fn cn_is_prime(args: Vec<String>) -> String {
+        match parse_bigint(args.first()) {
+            Some(n) => is_prime_big(&n).to_string(),
+            None => "false".to_string(),
+        }
+    }
+
+    // 返回大于n的最小素数
+    pub fn cn_next_prime(args: Vec<String>) -> String {
+        let two = BigInt::from(2);
+        let n = match parse_bigint(args.first()) {
+            Some(n) if n >= two => n,
+            _ => return bigint_result(&two),
+        };
+
+        // 从下一个奇数开始检查
+        let mut candidate = if (&n % &two).is_zero() { n + 1 } else { n + 2 };
+        while !is_prime_big(&candidate) {
+            candidate += &two;
+        }
+        bigint_result(&candidate)
+    }
+
+    // 分解质因数，返回数组（支持 u64 范围）
+    pub fn cn_prime_factors(args: Vec<String>) -> String {
+        match args.first().and_then(|a| a.trim().parse::<u64>().ok()) {
+            Some(n) if n >= 1 => json_value(&JsonValue::from(factorize(n)).to_string()),
+            _ => "错误: 参数必须是1到18446744073709551615之间的整数".to_string(),
+        }
+    }
+
+    // 欧拉函数 φ(n)（支持 u64 范围）
+    pub fn cn_totient(args: Vec<String>) -> String {
+        let n = match args.first().and_then(|a| a.trim().parse::<u64>().ok()) {
+            Some(n) if n >= 1 => n,
+            _ => return "0".to_string(),
+        };
+
+        let mut factors = factorize(n);
+        factors.dedup();
+        let mut result = n;
+        for p in factors {
+            result = result / p * (p - 1);
+        }
+        bigint_result(&BigInt::from(result))
+    }
+
+    // 模幂 base^exp mod m（支持大整数）
+    pub fn cn_mod_pow(args: Vec<String>) -> String {
+        let (base, exp, modulus) = match (parse_bigint(args.first()), parse_bigint(args.get(1)), parse_bigint(args.get(2))) {
+            (Some(b), Some(e), Some(m)) => (b, e, m),
+            _ => return "错误: 需要三个整数参数 (base, exp, mod)".to_string(),
+        };
+
+        if !modulus.is_positive() {
+            return "错误: 模数必须是正整数".to_string();
+        }
+        if exp.is_negative() {
+            return "错误: 指数不能为负数".to_string();
+        }
+
+        // 结果规范为非负数
+        let result = base.modpow(&exp, &modulus);
+        let result = ((result % &modulus) + &modulus) % &modulus;
+        bigint_result(&result)
+    }
+
+    // 模逆元 a⁻¹ mod m（支持大整数）
+    pub fn cn_mod_inverse(args: Vec<String>) -> String {
+        let (a, modulus) = match (parse_bigint(args.first()), parse_bigint(args.get(1))) {
+            (Some(a), Some(m)) => (a, m),
+            _ => return "错误: 需要两个整数参数 (a, mod)".to_string(),
+        };
+
+        if !modulus.is_positive() {
+            return "错误: 模数必须是正整数".to_string();
+        }
+
+        // 扩展欧几里得算法
+        let (mut old_r, mut r) = (((a % &modulus) + &modulus) % &modulus, modulus.clone());
+        let (mut old_s, mut s) = (BigInt::one(), BigInt::zero());
+        while !r.is_zero() {
+            let quotient = &old_r / &r;
+            let next_r = &old_r - &quotient * &r;
+            old_r = std::mem::replace(&mut r, next_r);
+            let next_s = &old_s - &quotient * &s;
+            old_s = std::mem::replace(&mut s, next_s);
+        }
+
+        if !old_r.is_one() {
+            return "错误: 逆元不存在（a 与模数不互素）".to_string();
+        }
+        bigint_result(&(((old_s % &modulus) + &modulus) % &modulus))
+    }
+
+    // 以下为插值函数
+
+    fn parse_args_f64(args: &[String], count: usize) -> Option<Vec<f64>> {
+        if args.len() < count {
+            return None;
+        }
+        args[..count].iter().map(|a| a.trim().parse::<f64>().ok()).collect()
+    }
+
+    // 线性插值 a + (b - a) * t
+    pub fn cn_lerp(args: Vec<String>) -> String {
+        match parse_args_f64(&args, 3) {
+            Some(v) => (v[0] + (v[1] - v[0]) * v[2]).to_string(),
+            None => "NaN".to_string(),
+        }
+    }
+
+    // 将x限制在 [min, max] 范围内
+    pub fn cn_clamp(args: Vec<String>) -> String {
+        match parse_args_f64(&args, 3) {
+            Some(v) if v[1] <= v[2] => v[0].clamp(v[1], v[2]).to_string(),
+            _ => "NaN".to_string(),
+        }
+    }
+
+    // 将x从 [in_min, in_max] 线性映射到 [out_min, out_max]
+    pub fn cn_map_range(args: Vec<String>) -> String {
+        match parse_args_f64(&args, 5) {
+            Some(v) if v[1] != v[2] => {
+                let t = (v[0] - v[1]) / (v[2] - v[1]);
+                (v[3] + (v[4] - v[3]) * t).to_string()
+            },
+            _ => "NaN".to_string(),
+        }
+    }
+
+    // 平滑阶跃函数，参数: edge0, edge1, x
+    pub fn cn_smoothstep(args: Vec<String>) -> String {
+        match parse_args_f64(&args, 3) {
+            Some(v) if v[0] != v[1] => {
+                let t = ((v[2] - v[0]) / (v[1] - v[0])).clamp(0.0, 1.0);
+                (t * t * (3.0 - 2.0 * t)).to_string()
+            },
+            _ => "NaN".to_string(),
+        }
+    }
+
+    // 计算自然三次样条在各节点处的二阶导数
+    fn spline_second_derivatives(xs: &[f64], ys: &[f64]) -> Vec<f64> {
+        let n = xs.len();
+        let mut second = vec![0.0; n];
+        let mut u = vec![0.0; n];
+
+        // 追赶法求解三对角方程组
+        for i in 1..n - 1 {
+            let sig = (xs[i] - xs[i - 1]) / (xs[i + 1] - xs[i - 1]);
+            let p = sig * second[i - 1] + 2.0;
+            second[i] = (sig - 1.0) / p;
+            let slope_diff = (ys[i + 1] - ys[i]) / (xs[i + 1] - xs[i]) - (ys[i] - ys[i - 1]) / (xs[i] - xs[i - 1]);
+            u[i] = (6.0 * slope_diff / (xs[i + 1] - xs[i - 1]) - sig * u[i - 1]) / p;
+        }
+
+        second[n - 1] = 0.0;
+        for i in (0..n - 1).rev() {
+            second[i] = second[i] * second[i + 1] + u[i];
+        }
+        second
+    }
+
+    fn spline_eval(xs: &[f64], ys: &[f64], second: &[f64], x: f64) -> f64 {
+        // 查找x所在的区间，超出范围时使用端点区间外推
+        let upper = xs.partition_point(|v| *v < x).clamp(1, xs.len() - 1);
+        let lower = upper - 1;
+        let h = xs[upper] - xs[lower];
+        let a = (xs[upper] - x) / h;
+        let b = (x - xs[lower]) / h;
+        a * ys[lower] + b * ys[upper]
+            + ((a * a * a - a) * second[lower] + (b * b * b - b) * second[upper]) * h * h / 6.0
+    }
+
+    // 自然三次样条插值
+    // 参数: xs数组（严格递增）, ys数组, x（数值或数组）
+    pub fn cn_spline(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "错误: 需要参数 (xs, ys, x)".to_string();
+        }
+
+        let xs = parse_numbers(&args[..1]);
+        let ys = parse_numbers(&args[1..2]);
+        if xs.len() != ys.len() || xs.len() < 2 {
+            return "错误: xs 和 ys 必须长度相同且至少包含2个数值".to_string();
+        }
+        if xs.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return "错误: xs 必须严格递增".to_string();
+        }
+
+        let second = spline_second_derivatives(&xs, &ys);
+        let trimmed = args[2].trim();
+        if trimmed.starts_with('[') {
+            let points = parse_numbers(&args[2..3]);
+            let values: Vec<f64> = points.iter().map(|x| spline_eval(&xs, &ys, &second, *x)).collect();
+            return json_value(&JsonValue::from(values).to_string());
+        }
+
+        match trimmed.parse::<f64>() {
+            Ok(x) => spline_eval(&xs, &ys, &second, x).to_string(),
+            Err(_) => "NaN".to_string(),
+        }
+    }
+}
+
+// 分数（有理数）命名空间
+mod fraction {
+    use num_bigint::BigInt;
+    use num_rational::BigRational;
+    use num_traits::{Signed, ToPrimitive, Zero};
+    use std::cmp::Ordering;
+
+    // 解析分数字符串，支持 "3/4"、"-5"、"0.75" 三种形式
+    fn parse_fraction(input: &str) -> Option<BigRational> {
+        let input = input.trim();
+        if let Some((num, den)) = input.split_once('/') {
+            let num = num.trim().parse::<BigInt>().ok()?;
+            let den = den.trim().parse::<BigInt>().ok()?;
+            if den.is_zero() {
+                return None;
+            }
+            return Some(BigRational::new(num, den));
+        }
+
+        if let Ok(num) = input.parse::<BigInt>() {
+            return Some(BigRational::from_integer(num));
+        }
+
+        // 小数按十进制精确转换，例如 "0.75" -> 3/4
+        let (negative, digits) = match input.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, input.strip_prefix('+').unwrap_or(input)),
+        };
+        let (int_part, frac_part) = digits.split_once('.')?;
+        if int_part.is_empty() && frac_part.is_empty() {
+            return None;
+        }
+        if !int_part.chars().chain(frac_part.chars()).all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let num = format!("{}{}", int_part, frac_part).parse::<BigInt>().ok()?;
+        let den = BigInt::from(10u32).pow(frac_part.len() as u32);
+        let value = BigRational::new(num, den);
+        Some(if negative { -value } else { value })
+    }
+
+    // 将分数格式化为 "分子/分母"，分母始终为正
+    fn format_fraction(value: &BigRational) -> String {
+        format!("{}/{}", value.numer(), value.denom())
+    }
+
+    // 解析两个分数参数
+    fn parse_pair(args: &[String]) -> Option<(BigRational, BigRational)> {
+        if args.len() < 2 {
+            return None;
+        }
+        Some((parse_fraction(&args[0])?, parse_fraction(&args[1])?))
+    }
+
+    // 创建分数（不自动约分）
+    // 参数: numerator, [denominator]
+    pub fn cn_create(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "NaN".to_string();
+        }
+
+        let num = match args[0].trim().parse::<BigInt>() {
+            Ok(n) => n,
+            Err(_) => return "NaN".to_string(),
+        };
+        let den = match args.get(1) {
+            Some(d) => match d.trim().parse::<BigInt>() {
+                Ok(d) => d,
+                Err(_) => return "NaN".to_string(),
+            },
+            None => BigInt::from(1),
+        };
+
+        if den.is_zero() {
+            return "NaN".to_string();
+        }
+
+        // 将符号统一放到分子上
+        if den.is_negative() {
+            format!("{}/{}", -num, -den)
+        } else {
+            format!("{}/{}", num, den)
+        }
+    }
+
+    // 分数加法
+    pub fn cn_add(args: Vec<String>) -> String {
+        match parse_pair(&args) {
+            Some((a, b)) => format_fraction(&(a + b)),
+            None => "NaN".to_string(),
+        }
+    }
+
+    // 分数减法
+    pub fn cn_sub(args: Vec<String>) -> String {
+        match parse_pair(&args) {
+            Some((a, b)) => format_fraction(&(a - b)),
+            None => "NaN".to_string(),
+        }
+    }
+
+    // 分数乘法
+    pub fn cn_mul(args: Vec<String>) -> String {
+        match parse_pair(&args) {
+            Some((a, b)) => format_fraction(&(a * b)),
+            None => "NaN".to_string(),
+        }
+    }
+
+    // 分数除法
+    pub fn cn_div(args: Vec<String>) -> String {
+        match parse_pair(&args) {
+            Some((a, b)) if !b.is_zero() => format_fraction(&(a / b)),
+            _ => "NaN".to_string(),
+        }
+    }
+
+    // 约分
+    pub fn cn_simplify(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "NaN".to_string();
+        }
+
+        match parse_fraction(&args[0]) {
+            Some(value) => format_fraction(&value),
+            None => "NaN".to_string(),
+        }
+    }
+
+    // 比较两个分数，返回 -1、0 或 1
+    pub fn cn_compare(args: Vec<String>) -> String {
+        match parse_pair(&args) {
+            Some((a, b)) => match a.cmp(&b) {
+                Ordering::Less => "-1".to_string(),
+                Ordering::Equal => "0".to_string(),
+                Ordering::Greater => "1".to_string(),
+            },
+            None => "NaN".to_string(),
+        }
+    }
+
+    // 转换为浮点数
+    pub fn cn_to_float(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "NaN".to_string();
+        }
+
+        match parse_fraction(&args[0]).and_then(|value| value.to_f64()) {
+            Some(f) => f.to_string(),
+            None => "NaN".to_string(),
+        }
+    }
+}
+
+// 任意精度小数命名空间
+// 数值以字符串输入输出，内部使用按 10^位数 缩放的大整数（定点数）计算
+mod bigfloat {
+    use super::json_value;
+    use num_bigint::BigInt;
+    use num_integer::Integer;
+    use num_traits::{One, Signed, ToPrimitive, Zero};
+    use serde_json::Value as JsonValue;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // 默认保留的小数位数
+    static PRECISION: AtomicUsize = AtomicUsize::new(50);
+
+    // 计算时额外保留的保护位数，用于吸收中间结果的舍入误差
+    const GUARD_DIGITS: usize = 10;
+    const MAX_PRECISION: usize = 10000;
+    const MAX_POW_EXPONENT: u64 = 1_000_000;
+    const MAX_EXP_ARGUMENT: u64 = 100_000;
+    // 整数次幂结果（及中间结果）允许的最大整数位数
+    const MAX_RESULT_DIGITS: f64 = 100_000.0;
+
+    fn pow10(digits: usize) -> BigInt {
+        BigInt::from(10u32).pow(digits as u32)
+    }
+
+    // 四舍五入的整数除法（远离零方向）
+    fn div_round(numerator: &BigInt, denominator: &BigInt) -> BigInt {
+        let (quotient, remainder) = numerator.div_rem(denominator);
+        if (remainder.abs() * 2u32) >= denominator.abs() {
+            if numerator.is_negative() != denominator.is_negative() {
+                quotient - 1u32
+            } else {
+                quotient + 1u32
+            }
+        } else {
+            quotient
+        }
+    }
+
+    // 解析十进制字符串为定点数，支持 "-12.5"、"1e-30"、"3.2E10" 等形式
+    fn parse_fixed(input: &str, digits: usize) -> Option<BigInt> {
+        let input = input.trim();
+        let (mantissa, exponent) = match input.find(['e', 'E']) {
+            Some(pos) => (&input[..pos], input[pos + 1..].parse::<i64>().ok()?),
+            None => (input, 0),
+        };
+        let (negative, unsigned) = match mantissa.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, mantissa.strip_prefix('+').unwrap_or(mantissa)),
+        };
+        let (int_part, frac_part) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        if int_part.is_empty() && frac_part.is_empty() {
+            return None;
+        }
+        if !int_part.chars().chain(frac_part.chars()).all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let raw = format!("{}{}", int_part, frac_part).parse::<BigInt>().ok()?;
+        let shift = exponent + digits as i64 - frac_part.len() as i64;
+        if shift.abs() > (MAX_PRECISION * 10) as i64 {
+            return None;
+        }
+        let value = if shift >= 0 {
+            raw * pow10(shift as usize)
+        } else {
+            div_round(&raw, &pow10((-shift) as usize))
+        };
+        Some(if negative { -value } else { value })
+    }
+
+    // 将定点数舍入到 precision 位小数并格式化，去掉多余的尾随零但至少保留一位小数
+    // 结果总是以字符串值返回，避免解释器转换为浮点数后丢失精度
+    fn format_fixed(value: &BigInt, digits: usize, precision: usize) -> String {
+        let rounded = div_round(value, &pow10(digits - precision));
+        let negative = rounded.is_negative();
+        let mut text = rounded.abs().to_string();
+        if text.len() <= precision {
+            text = format!("{}{}", "0".repeat(precision + 1 - text.len()), text);
+        }
+
+        let (int_part, frac_part) = text.split_at(text.len() - precision);
+        let mut frac_part = frac_part.trim_end_matches('0');
+        if frac_part.is_empty() {
+            frac_part = "0";
+        }
+        let sign = if negative && (int_part != "0" || frac_part != "0") { "-" } else { "" };
+        let text = format!("{}{}.{}", sign, int_part, frac_part);
+        json_value(&JsonValue::String(text).to_string())
+    }
+
+    // 读取可选的精度参数，未提供时使用全局精度
+    fn precision_arg(arg: Option<&String>) -> Result<usize, String> {
+        match arg {
+            Some(arg) => match arg.trim().parse::<usize>() {
+                Ok(p) if p <= MAX_PRECISION => Ok(p),
+                _ => Err(format!("错误: 精度必须是 0 到 {} 之间的整数", MAX_PRECISION)),
+            },
+            None => Ok(PRECISION.load(Ordering::Relaxed)),
+        }
+    }
+
+    fn parse_arg(arg: &str, digits: usize) -> Result<BigInt, String> {
+        parse_fixed(arg, digits).ok_or_else(|| format!("错误: 无效的数值 '{}'", arg.trim()))
+    }
+
+    fn mul_fixed(a: &BigInt, b: &BigInt, scale: &BigInt) -> BigInt {
+        div_round(&(a * b), scale)
+    }
+
+    fn div_fixed(a: &BigInt, b: &BigInt, scale: &BigInt) -> BigInt {
+        div_round(&(a * scale), b)
+    }
+
+    // 定点数的自然对数，要求 x > 0
+    fn ln_fixed(x: &BigInt, scale: &BigInt) -> BigInt {
+        // 将 x 约化到 [1, 2) 区间: x = m * 2^k
+        let mut m = x.clone();
+        let mut k: i64 = 0;
+        let two_scale = scale * 2u32;
+        while m >= two_scale {
+            m = div_round(&m, &BigInt::from(2u32));
+            k += 1;
+        }
+        while &m < scale {
+            m *= 2u32;
+            k -= 1;
+        }
+
+        let mut result = atanh_series(&(&m - scale), &(&m + scale), scale);
+        if k != 0 {
+            let ln2 = atanh_series(scale, &(scale * 3u32), scale);
+            result += ln2 * k;
+        }
+        result
+    }
+
+    // 计算 2 * atanh(num / den)，即 ln((den + num) / (den - num))
+    fn atanh_series(num: &BigInt, den: &BigInt, scale: &BigInt) -> BigInt {
+        let t = div_round(&(num * scale), den);
+        let t_squared = mul_fixed(&t, &t, scale);
+        let mut power = t.clone();
+        let mut sum = t;
+        let mut n = 1u64;
+        loop {
+            power = mul_fixed(&power, &t_squared, scale);
+            n += 2;
+            let term = &power / n;
+            if term.is_zero() {
+                break;
+            }
+            sum += term;
+        }
+        sum * 2u32
+    }
+
+    // 大整数绝对值的常用对数（近似值），要求 n 不为零
+    fn log10_abs(n: &BigInt) -> f64 {
+        let shift = n.bits().saturating_sub(64);
+        let top = (n.abs() >> shift).to_f64().unwrap_or(f64::MAX);
+        top.log10() + shift as f64 * std::f64::consts::LOG10_2
+    }
+
+    // 定点数的指数函数
+    fn exp_fixed(x: &BigInt, digits: usize) -> Result<BigInt, String> {
+        // 用大整数比较整数部分，避免精度很高时 10^digits 超出 f64 范围导致检查失效
+        let whole = match (x / pow10(digits)).abs().to_u64() {
+            Some(w) if w <= MAX_EXP_ARGUMENT => w,
+            _ => return Err("错误: 指数过大".to_string()),
+        };
+
+        // exp(x) = exp(x / 2^k)^(2^k)，k 越大级数收敛越快，但平方会放大误差，因此增加保护位
+        let k = (((whole + 1) as f64).log2().ceil() as usize) + 8;
+        let extra = k / 3 + 1;
+        let work_digits = digits + extra;
+        let scale = pow10(work_digits);
+        let reduced = div_round(&(x * pow10(extra)), &(BigInt::one() << k));
+
+        let mut sum = scale.clone();
+        let mut term = scale.clone();
+        let mut n = 1u64;
+        loop {
+            term = mul_fixed(&term, &reduced, &scale) / n;
+            if term.is_zero() {
+                break;
+            }
+            sum += &term;
+            n += 1;
+        }
+        for _ in 0..k {
+            sum = mul_fixed(&sum, &sum, &scale);
         }
+        Ok(div_round(&sum, &pow10(extra)))
+    }
 
-        result.to_string()
+    // 整数次幂（指数可为负）
+    fn pow_integer(base: &BigInt, exponent: &BigInt, scale: &BigInt) -> Result<BigInt, String> {
+        let magnitude = match exponent.abs().to_u64() {
+            Some(e) if e <= MAX_POW_EXPONENT => e,
+            _ => return Err(format!("错误: 整数指数的绝对值不能超过 {}", MAX_POW_EXPONENT)),
+        };
+        if exponent.is_negative() && base.is_zero() {
+            return Err("错误: 零的负数次幂".to_string());
+        }
+        // 按结果的整数位数估算大小，避免构造超大的数（底数绝对值大于1时中间结果同样很大）
+        if !base.is_zero() && magnitude as f64 * (log10_abs(base) - log10_abs(scale)) > MAX_RESULT_DIGITS {
+            return Err(format!("错误: 结果过大（超过 {} 位整数）", MAX_RESULT_DIGITS));
+        }
+
+        let mut result = scale.clone();
+        let mut square = base.clone();
+        let mut remaining = magnitude;
+        while remaining > 0 {
+            if remaining & 1 == 1 {
+                result = mul_fixed(&result, &square, scale);
+            }
+            remaining >>= 1;
+            if remaining > 0 {
+                square = mul_fixed(&square, &square, scale);
+            }
+        }
+
+        if exponent.is_negative() {
+            if result.is_zero() {
+                return Err("错误: 结果过大".to_string());
+            }
+            Ok(div_fixed(scale, &result, scale))
+        } else {
+            Ok(result)
+        }
     }
 
-    // 计算最大公约数
-    pub fn cn_gcd(args: Vec<String>) -> String {
+    // 解析两个操作数和可选精度，返回 (a, b, 工作位数, 输出精度)
+    fn binary_args(args: &[String]) -> Result<(BigInt, BigInt, usize, usize), String> {
         if args.len() < 2 {
-            return "0".to_string();
+            return Err("错误: 需要两个参数".to_string());
         }
+        let precision = precision_arg(args.get(2))?;
+        let digits = precision + GUARD_DIGITS;
+        Ok((parse_arg(&args[0], digits)?, parse_arg(&args[1], digits)?, digits, precision))
+    }
 
-        let mut a = args[0].parse::<u64>().unwrap_or(0);
-        let mut b = args[1].parse::<u64>().unwrap_or(0);
+    // 设置全局精度（小数位数），返回设置后的精度
+    pub fn cn_set_precision(args: Vec<String>) -> String {
+        match precision_arg(args.first()) {
+            Ok(p) if !args.is_empty() => {
+                PRECISION.store(p, Ordering::Relaxed);
+                p.to_string()
+            },
+            Ok(_) => "错误: 需要精度参数".to_string(),
+            Err(e) => e,
+        }
+    }
 
-        while b != 0 {
-            let temp = b;
-            b = a % b;
-            a = temp;
+    // 获取当前全局精度
+    pub fn cn_get_precision(_args: Vec<String>) -> String {
+        PRECISION.load(Ordering::Relaxed).to_string()
+    }
+
+    // 加法
+    // 参数: a, b, [precision]
+    pub fn cn_add(args: Vec<String>) -> String {
+        match binary_args(&args) {
+            Ok((a, b, digits, precision)) => format_fixed(&(a + b), digits, precision),
+            Err(e) => e,
         }
+    }
 
-        a.to_string()
+    // 减法
+    // 参数: a, b, [precision]
+    pub fn cn_sub(args: Vec<String>) -> String {
+        match binary_args(&args) {
+            Ok((a, b, digits, precision)) => format_fixed(&(a - b), digits, precision),
+            Err(e) => e,
+        }
     }
 
-    // 计算最小公倍数
-    pub fn cn_lcm(args: Vec<String>) -> String {
-        if args.len() < 2 {
-            return "0".to_string();
+    // 乘法
+    // 参数: a, b, [precision]
+    pub fn cn_mul(args: Vec<String>) -> String {
+        match binary_args(&args) {
+            Ok((a, b, digits, precision)) => format_fixed(&mul_fixed(&a, &b, &pow10(digits)), digits, precision),
+            Err(e) => e,
         }
+    }
 
-        let a = args[0].parse::<u64>().unwrap_or(0);
-        let b = args[1].parse::<u64>().unwrap_or(0);
+    // 除法
+    // 参数: a, b, [precision]
+    pub fn cn_div(args: Vec<String>) -> String {
+        match binary_args(&args) {
+            Ok((_, b, _, _)) if b.is_zero() => "错误: 除数不能为零".to_string(),
+            Ok((a, b, digits, precision)) => format_fixed(&div_fixed(&a, &b, &pow10(digits)), digits, precision),
+            Err(e) => e,
+        }
+    }
 
-        if a == 0 || b == 0 {
-            return "0".to_string();
+    // 平方根
+    // 参数: x, [precision]
+    pub fn cn_sqrt(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 需要一个参数".to_string();
+        }
+        let precision = match precision_arg(args.get(1)) {
+            Ok(p) => p,
+            Err(e) => return e,
+        };
+        let digits = precision + GUARD_DIGITS;
+        let x = match parse_arg(&args[0], digits) {
+            Ok(x) => x,
+            Err(e) => return e,
+        };
+        if x.is_negative() {
+            return "错误: 负数没有实数平方根".to_string();
+        }
+        format_fixed(&(x * pow10(digits)).sqrt(), digits, precision)
+    }
+
+    // 幂运算，整数指数精确计算，非整数指数通过 exp(y * ln(x)) 计算（要求底数为正）
+    // 参数: x, y, [precision]
+    pub fn cn_pow(args: Vec<String>) -> String {
+        let (base, exponent, digits, precision) = match binary_args(&args) {
+            Ok(parsed) => parsed,
+            Err(e) => return e,
+        };
+        let scale = pow10(digits);
+
+        let (whole, fraction) = exponent.div_rem(&scale);
+        let result = if fraction.is_zero() {
+            pow_integer(&base, &whole, &scale)
+        } else if base.is_positive() {
+            let product = mul_fixed(&exponent, &ln_fixed(&base, &scale), &scale);
+            exp_fixed(&product, digits)
+        } else {
+            Err("错误: 非整数次幂要求底数为正数".to_string())
+        };
+
+        match result {
+            Ok(value) => format_fixed(&value, digits, precision),
+            Err(e) => e,
         }
+    }
 
-        // 使用 lcm(a,b) = |a*b| / gcd(a,b)
-        let mut gcd_a = a;
-        let mut gcd_b = b;
+    // 指数函数 e^x
+    // 参数: x, [precision]
+    pub fn cn_exp(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 需要一个参数".to_string();
+        }
+        let precision = match precision_arg(args.get(1)) {
+            Ok(p) => p,
+            Err(e) => return e,
+        };
+        let digits = precision + GUARD_DIGITS;
+        match parse_arg(&args[0], digits).and_then(|x| exp_fixed(&x, digits)) {
+            Ok(value) => format_fixed(&value, digits, precision),
+            Err(e) => e,
+        }
+    }
 
-        while gcd_b != 0 {
-            let temp = gcd_b;
-            gcd_b = gcd_a % gcd_b;
-            gcd_a = temp;
+    // 自然对数
+    // 参数: x, [precision]
+    pub fn cn_ln(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 需要一个参数".to_string();
+        }
+        let precision = match precision_arg(args.get(1)) {
+            Ok(p) => p,
+            Err(e) => return e,
+        };
+        let digits = precision + GUARD_DIGITS;
+        match parse_arg(&args[0], digits) {
+            Ok(x) if x.is_positive() => format_fixed(&ln_fixed(&x, &pow10(digits)), digits, precision),
+            Ok(_) => "错误: 对数的参数必须为正数".to_string(),
+            Err(e) => e,
         }
+    }
 
-        let result = (a * b) / gcd_a;
-        result.to_string()
+    // 比较两个数，返回 -1、0 或 1
+    // 参数: a, b, [precision]
+    pub fn cn_compare(args: Vec<String>) -> String {
+        match binary_args(&args) {
+            Ok((a, b, _, _)) => {
+                // 按输出精度舍入后再比较
+                let guard = pow10(GUARD_DIGITS);
+                match div_round(&a, &guard).cmp(&div_round(&b, &guard)) {
+                    std::cmp::Ordering::Less => "-1".to_string(),
+                    std::cmp::Ordering::Equal => "0".to_string(),
+                    std::cmp::Ordering::Greater => "1".to_string(),
+                }
+            },
+            Err(e) => e,
+        }
+    }
+}
+
+// 数学表达式解析与求值（供 calc 等命名空间将函数作为字符串传入）
+// 支持 + - * / % ^ 运算、括号、常数 pi/e 和常用数学函数，例如 "x^2 + sin(x)"
+mod expr {
+    #[derive(Debug, Clone)]
+    pub enum Expr {
+        Number(f64),
+        Variable(String),
+        Negate(Box<Expr>),
+        Binary(char, Box<Expr>, Box<Expr>),
+        Call(String, Vec<Expr>),
+    }
+
+    struct Parser<'a> {
+        chars: std::iter::Peekable<std::str::Chars<'a>>,
+    }
+
+    impl<'a> Parser<'a> {
+        fn skip_whitespace(&mut self) {
+            while matches!(self.chars.peek(), Some(c) if c.is_whitespace()) {
+                self.chars.next();
+            }
+        }
+
+        fn peek(&mut self) -> Option<char> {
+            self.skip_whitespace();
+            self.chars.peek().copied()
+        }
+
+        fn expect(&mut self, expected: char) -> Result<(), String> {
+            match self.peek() {
+                Some(c) if c == expected => {
+                    self.chars.next();
+                    Ok(())
+                },
+                Some(c) => Err(format!("期望 '{}'，实际为 '{}'", expected, c)),
+                None => Err(format!("期望 '{}'，但表达式已结束", expected)),
+            }
+        }
+
+        // expression := term (('+' | '-') term)*
+        fn parse_expression(&mut self) -> Result<Expr, String> {
+            let mut left = self.parse_term()?;
+            while let Some(op @ ('+' | '-')) = self.peek() {
+                self.chars.next();
+                let right = self.parse_term()?;
+                left = Expr::Binary(op, Box::new(left), Box::new(right));
+            }
+            Ok(left)
+        }
+
+        // term := unary (('*' | '/' | '%') unary)*
+        fn parse_term(&mut self) -> Result<Expr, String> {
+            let mut left = self.parse_unary()?;
+            loop {
+                match self.peek() {
+                    Some('*') => {
+                        self.chars.next();
+                        // "**" 视为幂运算
+                        if self.chars.peek() == Some(&'*') {
+                            self.chars.next();
+                            let right = self.parse_unary()?;
+                            left = Expr::Binary('^', Box::new(left), Box::new(right));
+                        } else {
+                            let right = self.parse_unary()?;
+                            left = Expr::Binary('*', Box::new(left), Box::new(right));
+                        }
+                    },
+                    Some(op @ ('/' | '%')) => {
+                        self.chars.next();
+                        let right = self.parse_unary()?;
+                        left = Expr::Binary(op, Box::new(left), Box::new(right));
+                    },
+                    _ => return Ok(left),
+                }
+            }
+        }
+
+        // unary := ('-' | '+') unary | power
+        fn parse_unary(&mut self) -> Result<Expr, String> {
+            match self.peek() {
+                Some('-') => {
+                    self.chars.next();
+                    Ok(Expr::Negate(Box::new(self.parse_unary()?)))
+                },
+                Some('+') => {
+                    self.chars.next();
+                    self.parse_unary()
+                },
+                _ => self.parse_power(),
+            }
+        }
+
+        // power := primary ('^' unary)?，右结合
+        fn parse_power(&mut self) -> Result<Expr, String> {
+            let base = self.parse_primary()?;
+            if self.peek() == Some('^') {
+                self.chars.next();
+                let exponent = self.parse_unary()?;
+                return Ok(Expr::Binary('^', Box::new(base), Box::new(exponent)));
+            }
+            Ok(base)
+        }
+
+        // primary := number | identifier | identifier '(' args ')' | '(' expression ')'
+        fn parse_primary(&mut self) -> Result<Expr, String> {
+            match self.peek() {
+                Some('(') => {
+                    self.chars.next();
+                    let inner = self.parse_expression()?;
+                    self.expect(')')?;
+                    Ok(inner)
+                },
+                Some(c) if c.is_ascii_digit() || c == '.' => {
+                    let mut text = String::new();
+                    while let Some(&c) = self.chars.peek() {
+                        if c.is_ascii_digit() || c == '.' {
+                            text.push(c);
+                            self.chars.next();
+                        } else if (c == 'e' || c == 'E') && !text.contains(['e', 'E']) {
+                            // 科学计数法，例如 1e-3
+                            text.push(c);
+                            self.chars.next();
+                            if let Some(&sign @ ('+' | '-')) = self.chars.peek() {
+                                text.push(sign);
+                                self.chars.next();
+                            }
+                        } else {
+                            break;
+                        }
+                    }
+                    text.parse::<f64>()
+                        .map(Expr::Number)
+                        .map_err(|_| format!("无效的数字: {}", text))
+                },
+                Some(c) if c.is_alphabetic() || c == '_' => {
+                    let mut name = String::new();
+                    while let Some(&c) = self.chars.peek() {
+                        if c.is_alphanumeric() || c == '_' {
+                            name.push(c);
+                            self.chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+
+                    if self.peek() == Some('(') {
+                        self.chars.next();
+                        let mut args = Vec::new();
+                        if self.peek() != Some(')') {
+                            loop {
+                                args.push(self.parse_expression()?);
+                                if self.peek() == Some(',') {
+                                    self.chars.next();
+                                } else {
+                                    break;
+                                }
+                            }
+                        }
+                        self.expect(')')?;
+                        return Ok(Expr::Call(name, args));
+                    }
+
+                    match name.as_str() {
+                        "pi" => Ok(Expr::Number(std::f64::consts::PI)),
+                        "e" => Ok(Expr::Number(std::f64::consts::E)),
+                        _ => Ok(Expr::Variable(name)),
+                    }
+                },
+                Some(c) => Err(format!("无法识别的字符: '{}'", c)),
+                None => Err("表达式意外结束".to_string()),
+            }
+        }
+    }
+
+    // 解析表达式
+    pub fn parse(source: &str) -> Result<Expr, String> {
+        let mut parser = Parser { chars: source.chars().peekable() };
+        let expr = parser.parse_expression()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(c) => Err(format!("表达式中存在多余的字符: '{}'", c)),
+        }
+    }
+
+    // 使用给定的变量值计算表达式
+    pub fn eval(expr: &Expr, vars: &[(&str, f64)]) -> Result<f64, String> {
+        match expr {
+            Expr::Number(n) => Ok(*n),
+            Expr::Variable(name) => vars.iter()
+                .find(|(var, _)| var == name)
+                .map(|(_, value)| *value)
+                .ok_or_else(|| format!("未定义的变量: {}", name)),
+            Expr::Negate(inner) => Ok(-eval(inner, vars)?),
+            Expr::Binary(op, left, right) => {
+                let a = eval(left, vars)?;
+                let b = eval(right, vars)?;
+                Ok(match op {
+                    '+' => a + b,
+                    '-' => a - b,
+                    '*' => a * b,
+                    '/' => a / b,
+                    '%' => a % b,
+                    _ => a.powf(b),
+                })
+            },
+            Expr::Call(name, args) => {
+                let values = args.iter()
+                    .map(|arg| eval(arg, vars))
+                    .collect::<Result<Vec<f64>, String>>()?;
+                call_function(name, &values)
+            },
+        }
+    }
+
+    fn call_function(name: &str, args: &[f64]) -> Result<f64, String> {
+        let unary = |f: fn(f64) -> f64| -> Result<f64, String> {
+            match args {
+                [x] => Ok(f(*x)),
+                _ => Err(format!("函数 {} 需要1个参数", name)),
+            }
+        };
+        let binary = |f: fn(f64, f64) -> f64| -> Result<f64, String> {
+            match args {
+                [a, b] => Ok(f(*a, *b)),
+                _ => Err(format!("函数 {} 需要2个参数", name)),
+            }
+        };
+
+        match name {
+            "sin" => unary(f64::sin),
+            "cos" => unary(f64::cos),
+            "tan" => unary(f64::tan),
+            "asin" => unary(f64::asin),
+            "acos" => unary(f64::acos),
+            "atan" => unary(f64::atan),
+            "sinh" => unary(f64::sinh),
+            "cosh" => unary(f64::cosh),
+            "tanh" => unary(f64::tanh),
+            "exp" => unary(f64::exp),
+            "ln" => unary(f64::ln),
+            "log10" => unary(f64::log10),
+            "log2" => unary(f64::log2),
+            "sqrt" => unary(f64::sqrt),
+            "cbrt" => unary(f64::cbrt),
+            "abs" => unary(f64::abs),
+            "floor" => unary(f64::floor),
+            "ceil" => unary(f64::ceil),
+            "round" => unary(f64::round),
+            "sign" => unary(f64::signum),
+            "atan2" => binary(f64::atan2),
+            "pow" => binary(f64::powf),
+            "min" => binary(f64::min),
+            "max" => binary(f64::max),
+            // log(x) 为自然对数，log(x, base) 为指定底数的对数
+            "log" => match args {
+                [x] => Ok(x.ln()),
+                [x, base] => Ok(x.ln() / base.ln()),
+                _ => Err("函数 log 需要1或2个参数".to_string()),
+            },
+            _ => Err(format!("未知的函数: {}", name)),
+        }
+    }
+}
+
+// 数值微积分命名空间
+mod calc {
+    use super::expr::{self, Expr};
+    use super::{json_value, parse_numbers};
+    use serde_json::Value as JsonValue;
+
+    // 判断参数是否为数组（采样数据或表达式数组）
+    fn is_array(arg: &str) -> bool {
+        let trimmed = arg.trim();
+        trimmed.starts_with('[') && trimmed.ends_with(']')
+    }
+
+    // 解析数值参数，也接受常量表达式，例如 "pi/2"
+    fn parse_f64(arg: Option<&String>) -> Option<f64> {
+        let arg = arg?.trim();
+        arg.parse::<f64>().ok().or_else(|| {
+            expr::parse(arg).ok().and_then(|e| expr::eval(&e, &[]).ok())
+        })
+    }
+
+    // 以 x 为自变量计算表达式
+    fn eval_at(f: &Expr, x: f64) -> Result<f64, String> {
+        expr::eval(f, &[("x", x)])
+    }
+
+    // 自适应辛普森积分的最大递归深度，不光滑的函数最多细分为 2^20 个小区间
+    const SIMPSON_MAX_DEPTH: u32 = 20;
+
+    // 自适应辛普森积分
+    // (a, b) 为区间，(fa, fm, fb) 为区间端点和中点的函数值；
+    // 到达最大深度仍未满足误差要求的小区间，其估计误差累加到 unconverged
+    fn adaptive_simpson(f: &Expr, (a, b): (f64, f64), (fa, fm, fb): (f64, f64, f64), whole: f64, tolerance: f64, depth: u32, unconverged: &mut f64) -> Result<f64, String> {
+        let m = (a + b) / 2.0;
+        let lm = (a + m) / 2.0;
+        let rm = (m + b) / 2.0;
+        let flm = eval_at(f, lm)?;
+        let frm = eval_at(f, rm)?;
+        let left = (m - a) / 6.0 * (fa + 4.0 * flm + fm);
+        let right = (b - m) / 6.0 * (fm + 4.0 * frm + fb);
+        let delta = left + right - whole;
+
+        if delta.abs() <= 15.0 * tolerance {
+            return Ok(left + right + delta / 15.0);
+        }
+        if depth == 0 {
+            *unconverged += delta.abs() / 15.0;
+            return Ok(left + right + delta / 15.0);
+        }
+
+        Ok(adaptive_simpson(f, (a, m), (fa, flm, fm), left, tolerance / 2.0, depth - 1, unconverged)?
+            + adaptive_simpson(f, (m, b), (fm, frm, fb), right, tolerance / 2.0, depth - 1, unconverged)?)
+    }
+
+    // 对等间距采样数据积分：奇数个点使用辛普森法，否则使用梯形法
+    fn integrate_samples(samples: &[f64], a: f64, b: f64) -> f64 {
+        let n = samples.len();
+        let h = (b - a) / (n - 1) as f64;
+
+        if n >= 3 && n % 2 == 1 {
+            let mut sum = samples[0] + samples[n - 1];
+            for (i, y) in samples.iter().enumerate().take(n - 1).skip(1) {
+                sum += if i % 2 == 1 { 4.0 * y } else { 2.0 * y };
+            }
+            sum * h / 3.0
+        } else {
+            let inner = samples[1..n - 1].iter().sum::<f64>();
+            h * ((samples[0] + samples[n - 1]) / 2.0 + inner)
+        }
+    }
+
+    // 定积分
+    // 参数: 表达式（变量为x）或等间距采样数组, a, b
+    pub fn cn_integrate(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "错误: 需要三个参数 (表达式或采样数组, a, b)".to_string();
+        }
+
+        let (a, b) = match (parse_f64(args.get(1)), parse_f64(args.get(2))) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return "错误: 积分上下限必须是数值".to_string(),
+        };
+
+        if is_array(&args[0]) {
+            let samples = parse_numbers(&args[..1]);
+            if samples.len() < 2 {
+                return "错误: 采样数组至少需要2个数值".to_string();
+            }
+            return integrate_samples(&samples, a, b).to_string();
+        }
+
+        let f = match expr::parse(&args[0]) {
+            Ok(f) => f,
+            Err(e) => return format!("错误: {}", e),
+        };
+
+        let result = (|| {
+            let fa = eval_at(&f, a)?;
+            let fb = eval_at(&f, b)?;
+            let fm = eval_at(&f, (a + b) / 2.0)?;
+            let whole = (b - a) / 6.0 * (fa + 4.0 * fm + fb);
+            let mut unconverged = 0.0;
+            let value = adaptive_simpson(&f, (a, b), (fa, fm, fb), whole, 1e-10, SIMPSON_MAX_DEPTH, &mut unconverged)?;
+            // 未收敛部分的估计误差相对结果可以忽略时仍返回结果
+            if unconverged > 1e-6 * value.abs().max(1.0) {
+                return Err(format!("积分未收敛，估计误差为 {:e}", unconverged));
+            }
+            Ok(value)
+        })();
+
+        match result {
+            Ok(value) => value.to_string(),
+            Err(e) => format!("错误: {}", e),
+        }
+    }
+
+    // 数值求导
+    // 参数: 表达式（变量为x）, x, [h]，返回该点的导数
+    //   或: 等间距采样数组, [dx]，返回每个采样点导数组成的数组
+    pub fn cn_derivative(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 需要表达式或采样数组参数".to_string();
+        }
+
+        if is_array(&args[0]) {
+            let samples = parse_numbers(&args[..1]);
+            let dx = parse_f64(args.get(1)).unwrap_or(1.0);
+            let n = samples.len();
+            if n < 2 || dx == 0.0 {
+                return "错误: 采样数组至少需要2个数值，且间距不能为0".to_string();
+            }
+
+            // 内部点使用中心差分，端点使用单侧差分
+            let gradient: Vec<f64> = (0..n)
+                .map(|i| {
+                    if i == 0 {
+                        (samples[1] - samples[0]) / dx
+                    } else if i == n - 1 {
+                        (samples[n - 1] - samples[n - 2]) / dx
+                    } else {
+                        (samples[i + 1] - samples[i - 1]) / (2.0 * dx)
+                    }
+                })
+                .collect();
+            return json_value(&JsonValue::from(gradient).to_string());
+        }
+
+        let f = match expr::parse(&args[0]) {
+            Ok(f) => f,
+            Err(e) => return format!("错误: {}", e),
+        };
+        let x = match parse_f64(args.get(1)) {
+            Some(x) => x,
+            None => return "错误: 需要求导点x".to_string(),
+        };
+        let h = parse_f64(args.get(2)).unwrap_or(1e-5 * x.abs().max(1.0));
+
+        // 五点中心差分
+        let result = (|| {
+            Ok::<f64, String>((-eval_at(&f, x + 2.0 * h)? + 8.0 * eval_at(&f, x + h)?
+                - 8.0 * eval_at(&f, x - h)? + eval_at(&f, x - 2.0 * h)?) / (12.0 * h))
+        })();
+
+        match result {
+            Ok(value) => value.to_string(),
+            Err(e) => format!("错误: {}", e),
+        }
+    }
+
+    // 按顶层逗号拆分表达式数组，例如 "[y1, -max(y0, 0)]"
+    fn split_expressions(arg: &str) -> Vec<String> {
+        if let Ok(JsonValue::Array(items)) = serde_json::from_str::<JsonValue>(arg) {
+            return items.iter()
+                .map(|item| match item {
+                    JsonValue::String(s) => s.clone(),
+                    other => other.to_string(),
+                })
+                .collect();
+        }
+
+        let trimmed = arg.trim();
+        let inner = &trimmed[1..trimmed.len() - 1];
+        let mut parts = Vec::new();
+        let mut depth = 0;
+        let mut current = String::new();
+        for c in inner.chars() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                ',' if depth == 0 => {
+                    parts.push(current.trim().to_string());
+                    current.clear();
+                    continue;
+                },
+                _ => {},
+            }
+            current.push(c);
+        }
+        if !current.trim().is_empty() {
+            parts.push(current.trim().to_string());
+        }
+        parts
+    }
+
+    // 常微分方程 dy/dt = f(t, y)，标量形式使用变量 t 和 y，
+    // 方程组形式使用变量 t 和 y0, y1, ...
+    struct OdeSystem {
+        equations: Vec<Expr>,
+        names: Vec<String>,
+    }
+
+    impl OdeSystem {
+        fn derivatives(&self, t: f64, y: &[f64]) -> Result<Vec<f64>, String> {
+            let mut vars: Vec<(&str, f64)> = vec![("t", t)];
+            vars.extend(self.names.iter().map(|n| n.as_str()).zip(y.iter().copied()));
+            self.equations.iter().map(|eq| expr::eval(eq, &vars)).collect()
+        }
+    }
+
+    fn add_scaled(y: &[f64], k: &[f64], scale: f64) -> Vec<f64> {
+        y.iter().zip(k).map(|(a, b)| a + b * scale).collect()
+    }
+
+    // 求解常微分方程的公共流程
+    // 参数: f, t0, y0, t1, steps
+    fn solve_ode(args: &[String], rk4: bool) -> String {
+        if args.len() < 4 {
+            return "错误: 需要参数 (f, t0, y0, t1, [steps])".to_string();
+        }
+
+        let is_system = is_array(&args[0]);
+        let sources = if is_system { split_expressions(&args[0]) } else { vec![args[0].clone()] };
+        let equations = match sources.iter().map(|s| expr::parse(s)).collect::<Result<Vec<Expr>, String>>() {
+            Ok(eqs) => eqs,
+            Err(e) => return format!("错误: {}", e),
+        };
+
+        let (t0, t1) = match (parse_f64(args.get(1)), parse_f64(args.get(3))) {
+            (Some(t0), Some(t1)) => (t0, t1),
+            _ => return "错误: t0 和 t1 必须是数值".to_string(),
+        };
+        let y0 = if is_system {
+            parse_numbers(&args[2..3])
+        } else {
+            match parse_f64(args.get(2)) {
+                Some(y) => vec![y],
+                None => return "错误: y0 必须是数值".to_string(),
+            }
+        };
+        if y0.len() != equations.len() {
+            return "错误: 初始值数量必须与方程数量相同".to_string();
+        }
+        let steps = match args.get(4) {
+            Some(s) => match s.trim().parse::<usize>() {
+                Ok(n) if n > 0 => n,
+                _ => return "错误: 步数必须是正整数".to_string(),
+            },
+            None => 100,
+        };
+
+        let names = if is_system {
+            (0..equations.len()).map(|i| format!("y{}", i)).collect()
+        } else {
+            vec!["y".to_string()]
+        };
+        let system = OdeSystem { equations, names };
+
+        let h = (t1 - t0) / steps as f64;
+        let mut ts = vec![t0];
+        let mut ys = vec![y0.clone()];
+        let mut y = y0;
+
+        for i in 0..steps {
+            let t = t0 + h * i as f64;
+            let step = (|| {
+                if rk4 {
+                    let k1 = system.derivatives(t, &y)?;
+                    let k2 = system.derivatives(t + h / 2.0, &add_scaled(&y, &k1, h / 2.0))?;
+                    let k3 = system.derivatives(t + h / 2.0, &add_scaled(&y, &k2, h / 2.0))?;
+                    let k4 = system.derivatives(t + h, &add_scaled(&y, &k3, h))?;
+                    Ok::<Vec<f64>, String>(y.iter().enumerate()
+                        .map(|(j, v)| v + h / 6.0 * (k1[j] + 2.0 * k2[j] + 2.0 * k3[j] + k4[j]))
+                        .collect())
+                } else {
+                    let k = system.derivatives(t, &y)?;
+                    Ok(add_scaled(&y, &k, h))
+                }
+            })();
+
+            y = match step {
+                Ok(next) => next,
+                Err(e) => return format!("错误: {}", e),
+            };
+            ts.push(t0 + h * (i + 1) as f64);
+            ys.push(y.clone());
+        }
+
+        let y_values: Vec<JsonValue> = if is_system {
+            ys.into_iter().map(JsonValue::from).collect()
+        } else {
+            ys.into_iter().map(|v| JsonValue::from(v[0])).collect()
+        };
+
+        json_value(&serde_json::json!({
+            "t": ts,
+            "y": y_values,
+        }).to_string())
+    }
+
+    // 欧拉法求解常微分方程，返回 {"t": [...], "y": [...]}
+    pub fn cn_solve_ode_euler(args: Vec<String>) -> String {
+        solve_ode(&args, false)
+    }
+
+    // 四阶龙格-库塔法求解常微分方程，返回 {"t": [...], "y": [...]}
+    pub fn cn_solve_ode_rk4(args: Vec<String>) -> String {
+        solve_ode(&args, true)
+    }
+}
+
+// 单位换算命名空间
+// 每个单位记录所属类别，以及换算到该类别基准单位的线性关系: 基准值 = 值 * factor + offset
+mod units {
+    use super::json_value;
+    use std::collections::HashMap;
+    use std::sync::{Mutex, OnceLock};
+
+    #[derive(Clone)]
+    struct Unit {
+        category: String,
+        factor: f64,
+        offset: f64,
+    }
+
+    // 内置单位表: (单位名, 类别, factor, offset)
+    // 基准单位: 长度为米(m)，质量为千克(kg)，温度为开尔文(K)，数据大小为字节(B)，角度为弧度(rad)
+    const BUILTIN_UNITS: &[(&str, &str, f64, f64)] = &[
+        // 长度
+        ("m", "length", 1.0, 0.0),
+        ("km", "length", 1000.0, 0.0),
+        ("dm", "length", 0.1, 0.0),
+        ("cm", "length", 0.01, 0.0),
+        ("mm", "length", 0.001, 0.0),
+        ("um", "length", 1e-6, 0.0),
+        ("nm", "length", 1e-9, 0.0),
+        ("mi", "length", 1609.344, 0.0),
+        ("yd", "length", 0.9144, 0.0),
+        ("ft", "length", 0.3048, 0.0),
+        ("in", "length", 0.0254, 0.0),
+        ("nmi", "length", 1852.0, 0.0),
+        // 质量
+        ("kg", "mass", 1.0, 0.0),
+        ("g", "mass", 0.001, 0.0),
+        ("mg", "mass", 1e-6, 0.0),
+        ("t", "mass", 1000.0, 0.0),
+        ("lb", "mass", 0.45359237, 0.0),
+        ("oz", "mass", 0.028349523125, 0.0),
+        ("st", "mass", 6.35029318, 0.0),
+        // 温度
+        ("K", "temperature", 1.0, 0.0),
+        ("C", "temperature", 1.0, 273.15),
+        ("F", "temperature", 5.0 / 9.0, 273.15 - 32.0 * 5.0 / 9.0),
+        // 数据大小
+        ("bit", "data", 0.125, 0.0),
+        ("B", "data", 1.0, 0.0),
+        ("KB", "data", 1e3, 0.0),
+        ("MB", "data", 1e6, 0.0),
+        ("GB", "data", 1e9, 0.0),
+        ("TB", "data", 1e12, 0.0),
+        ("KiB", "data", 1024.0, 0.0),
+        ("MiB", "data", 1048576.0, 0.0),
+        ("GiB", "data", 1073741824.0, 0.0),
+        ("TiB", "data", 1099511627776.0, 0.0),
+        // 角度
+        ("rad", "angle", 1.0, 0.0),
+        ("deg", "angle", std::f64::consts::PI / 180.0, 0.0),
+        ("grad", "angle", std::f64::consts::PI / 200.0, 0.0),
+        ("turn", "angle", std::f64::consts::TAU, 0.0),
+        ("arcmin", "angle", std::f64::consts::PI / 10800.0, 0.0),
+        ("arcsec", "angle", std::f64::consts::PI / 648000.0, 0.0),
+    ];
+
+    static REGISTRY: OnceLock<Mutex<HashMap<String, Unit>>> = OnceLock::new();
+
+    fn builtin_registry() -> Mutex<HashMap<String, Unit>> {
+        let units = BUILTIN_UNITS
+            .iter()
+            .map(|(name, category, factor, offset)| {
+                let unit = Unit { category: category.to_string(), factor: *factor, offset: *offset };
+                (name.to_string(), unit)
+            })
+            .collect();
+        Mutex::new(units)
+    }
+
+    fn with_registry<T>(f: impl FnOnce(&mut HashMap<String, Unit>) -> T) -> T {
+        let mut registry = REGISTRY.get_or_init(builtin_registry).lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        f(&mut registry)
+    }
+
+    // 单位换算
+    // 参数: 数值, 源单位, 目标单位
+    pub fn cn_convert(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "错误: 需要参数 (value, from, to)".to_string();
+        }
+
+        let value = match args[0].trim().parse::<f64>() {
+            Ok(v) => v,
+            Err(_) => return "NaN".to_string(),
+        };
+        let from_name = args[1].trim();
+        let to_name = args[2].trim();
+
+        with_registry(|registry| {
+            let from = match registry.get(from_name) {
+                Some(unit) => unit,
+                None => return format!("错误: 未知单位 '{}'", from_name),
+            };
+            let to = match registry.get(to_name) {
+                Some(unit) => unit,
+                None => return format!("错误: 未知单位 '{}'", to_name),
+            };
+            if from.category != to.category {
+                return format!("错误: 无法在 {} 和 {} 之间换算", from.category, to.category);
+            }
+
+            let base = value * from.factor + from.offset;
+            ((base - to.offset) / to.factor).to_string()
+        })
+    }
+
+    // 注册或覆盖单位
+    // 参数: 单位名, 类别, 相对类别基准单位的倍数, [偏移量]
+    // 类别不存在时会新建该类别，新类别的第一个单位通常应以 factor 1 作为基准单位
+    pub fn cn_register(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "错误: 需要参数 (name, category, factor, [offset])".to_string();
+        }
+
+        let name = args[0].trim();
+        let category = args[1].trim();
+        if name.is_empty() || category.is_empty() {
+            return "错误: 单位名和类别不能为空".to_string();
+        }
+
+        let factor = match args[2].trim().parse::<f64>() {
+            Ok(v) if v != 0.0 && v.is_finite() => v,
+            _ => return "错误: factor 必须是非零有限数值".to_string(),
+        };
+        let offset = match args.get(3) {
+            Some(arg) => match arg.trim().parse::<f64>() {
+                Ok(v) if v.is_finite() => v,
+                _ => return "错误: offset 必须是有限数值".to_string(),
+            },
+            None => 0.0,
+        };
+
+        with_registry(|registry| {
+            let unit = Unit { category: category.to_string(), factor, offset };
+            registry.insert(name.to_string(), unit);
+        });
+        "true".to_string()
+    }
+
+    // 列出单位，返回按名称排序的数组
+    // 参数: [类别]
+    pub fn cn_list(args: Vec<String>) -> String {
+        let category = args.first().map(|arg| arg.trim().to_string());
+        let mut names: Vec<String> = with_registry(|registry| {
+            registry
+                .iter()
+                .filter(|(_, unit)| category.as_ref().map_or(true, |c| &unit.category == c))
+                .map(|(name, _)| name.clone())
+                .collect()
+        });
+        names.sort();
+        json_value(&serde_json::Value::from(names).to_string())
+    }
+
+    // 列出所有单位类别，返回数组
+    pub fn cn_categories(_args: Vec<String>) -> String {
+        let mut categories: Vec<String> = with_registry(|registry| {
+            registry.values().map(|unit| unit.category.clone()).collect()
+        });
+        categories.sort();
+        categories.dedup();
+        json_value(&serde_json::Value::from(categories).to_string())
+    }
+
+    // 查询单位所属类别
+    pub fn cn_category_of(args: Vec<String>) -> String {
+        let name = match args.first() {
+            Some(arg) => arg.trim(),
+            None => return "错误: 需要单位名".to_string(),
+        };
+        with_registry(|registry| match registry.get(name) {
+            Some(unit) => unit.category.clone(),
+            None => format!("错误: 未知单位 '{}'", name),
+        })
+    }
+}
+
+// 位运算命名空间
+// 所有函数的最后一个可选参数为位宽（32 或 64，默认 32），结果按该位宽的有符号整数返回
+mod bits {
+    use super::json_value;
+
+    // 解析位宽参数
+    fn parse_width(arg: Option<&String>) -> Result<u32, String> {
+        match arg.map(|a| a.trim()) {
+            None | Some("32") => Ok(32),
+            Some("64") => Ok(64),
+            Some(other) => Err(format!("错误: 不支持的位宽 '{}'，只支持 32 或 64", other)),
+        }
+    }
+
+    // 解析整数，支持十进制（可为负数）、0x 十六进制和 0b 二进制
+    // 返回该位宽下的位模式（存放在 u64 的低位中）
+    fn parse_bits(input: &str, width: u32) -> Result<u64, String> {
+        let text = input.trim();
+        let (negative, digits) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text),
+        };
+        let magnitude = if let Some(hex) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+            u64::from_str_radix(hex, 16)
+        } else if let Some(bin) = digits.strip_prefix("0b").or_else(|| digits.strip_prefix("0B")) {
+            u64::from_str_radix(bin, 2)
+        } else {
+            digits.parse::<u64>()
+        }
+        .map_err(|_| format!("错误: 无效的整数 '{}'", text))?;
+
+        let out_of_range = || format!("错误: {} 超出 {} 位整数范围", text, width);
+        if width == 32 {
+            // 接受 i32 和 u32 两种范围的值
+            if negative {
+                if magnitude > 1 << 31 {
+                    return Err(out_of_range());
+                }
+                Ok((magnitude as u32).wrapping_neg() as u64)
+            } else if magnitude > u32::MAX as u64 {
+                Err(out_of_range())
+            } else {
+                Ok(magnitude)
+            }
+        } else if negative {
+            if magnitude > 1 << 63 {
+                return Err(out_of_range());
+            }
+            Ok(magnitude.wrapping_neg())
+        } else {
+            Ok(magnitude)
+        }
+    }
+
+    // 按位宽将位模式格式化为有符号整数，64位结果作为长整数返回，避免转为浮点数后丢失精度
+    fn format_bits(value: u64, width: u32) -> String {
+        if width == 32 {
+            (value as u32 as i32).to_string()
+        } else {
+            json_value(&(value as i64).to_string())
+        }
+    }
+
+    fn mask(width: u32) -> u64 {
+        if width == 32 { u32::MAX as u64 } else { u64::MAX }
+    }
+
+    // 解析移位或旋转的位数
+    fn parse_amount(input: &str) -> Result<i64, String> {
+        input.trim().parse::<i64>().map_err(|_| format!("错误: 无效的位数 '{}'", input.trim()))
+    }
+
+    // 二元位运算的公共实现
+    fn binary_op(args: &[String], op: fn(u64, u64) -> u64) -> String {
+        if args.len() < 2 {
+            return "错误: 需要两个参数".to_string();
+        }
+        let result = parse_width(args.get(2)).and_then(|width| {
+            let a = parse_bits(&args[0], width)?;
+            let b = parse_bits(&args[1], width)?;
+            Ok(format_bits(op(a, b) & mask(width), width))
+        });
+        result.unwrap_or_else(|e| e)
+    }
+
+    // 一元位运算的公共实现
+    fn unary_op(args: &[String], op: fn(u64, u32) -> String) -> String {
+        if args.is_empty() {
+            return "错误: 需要一个参数".to_string();
+        }
+        let result = parse_width(args.get(1)).and_then(|width| {
+            let a = parse_bits(&args[0], width)?;
+            Ok(op(a, width))
+        });
+        result.unwrap_or_else(|e| e)
+    }
+
+    // 移位和旋转的公共实现，op 接收 (位模式, 位数, 位宽)
+    fn shift_op(args: &[String], op: fn(u64, i64, u32) -> Result<u64, String>) -> String {
+        if args.len() < 2 {
+            return "错误: 需要参数 (value, n, [width])".to_string();
+        }
+        let result = parse_width(args.get(2)).and_then(|width| {
+            let a = parse_bits(&args[0], width)?;
+            let n = parse_amount(&args[1])?;
+            Ok(format_bits(op(a, n, width)? & mask(width), width))
+        });
+        result.unwrap_or_else(|e| e)
+    }
+
+    // 按位与
+    // 参数: a, b, [width]
+    pub fn cn_and(args: Vec<String>) -> String {
+        binary_op(&args, |a, b| a & b)
+    }
+
+    // 按位或
+    // 参数: a, b, [width]
+    pub fn cn_or(args: Vec<String>) -> String {
+        binary_op(&args, |a, b| a | b)
+    }
+
+    // 按位异或
+    // 参数: a, b, [width]
+    pub fn cn_xor(args: Vec<String>) -> String {
+        binary_op(&args, |a, b| a ^ b)
+    }
+
+    // 按位取反
+    // 参数: a, [width]
+    pub fn cn_not(args: Vec<String>) -> String {
+        unary_op(&args, |a, width| format_bits(!a & mask(width), width))
+    }
+
+    // 左移，移出位宽的位被丢弃，位数不小于位宽时结果为0
+    // 参数: a, n, [width]
+    pub fn cn_shl(args: Vec<String>) -> String {
+        shift_op(&args, |a, n, width| match n {
+            n if n < 0 => Err("错误: 移位位数不能为负数".to_string()),
+            n if n >= width as i64 => Ok(0),
+            n => Ok(a << n),
+        })
+    }
+
+    // 逻辑右移（高位补0）
+    // 参数: a, n, [width]
+    pub fn cn_shr(args: Vec<String>) -> String {
+        shift_op(&args, |a, n, width| match n {
+            n if n < 0 => Err("错误: 移位位数不能为负数".to_string()),
+            n if n >= width as i64 => Ok(0),
+            n => Ok(a >> n),
+        })
+    }
+
+    // 算术右移（高位补符号位）
+    // 参数: a, n, [width]
+    pub fn cn_sar(args: Vec<String>) -> String {
+        shift_op(&args, |a, n, width| {
+            if n < 0 {
+                return Err("错误: 移位位数不能为负数".to_string());
+            }
+            let n = n.min(width as i64 - 1) as u32;
+            Ok(if width == 32 {
+                ((a as u32 as i32) >> n) as u32 as u64
+            } else {
+                ((a as i64) >> n) as u64
+            })
+        })
+    }
+
+    // 循环移位，n 为正时向左旋转，为负时向右旋转
+    // 参数: a, n, [width]
+    pub fn cn_rotate(args: Vec<String>) -> String {
+        shift_op(&args, |a, n, width| {
+            let n = n.rem_euclid(width as i64) as u32;
+            Ok(if width == 32 {
+                (a as u32).rotate_left(n) as u64
+            } else {
+                a.rotate_left(n)
+            })
+        })
+    }
+
+    // 统计值为1的位数
+    // 参数: a, [width]
+    pub fn cn_popcount(args: Vec<String>) -> String {
+        unary_op(&args, |a, _| a.count_ones().to_string())
+    }
+
+    // 前导零的个数
+    // 参数: a, [width]
+    pub fn cn_leading_zeros(args: Vec<String>) -> String {
+        unary_op(&args, |a, width| {
+            if width == 32 {
+                (a as u32).leading_zeros().to_string()
+            } else {
+                a.leading_zeros().to_string()
+            }
+        })
+    }
+
+    // 末尾零的个数
+    // 参数: a, [width]
+    pub fn cn_trailing_zeros(args: Vec<String>) -> String {
+        unary_op(&args, |a, width| {
+            if width == 32 {
+                (a as u32).trailing_zeros().to_string()
+            } else {
+                a.trailing_zeros().to_string()
+            }
+        })
+    }
+
+    // 转换为补零到位宽的二进制字符串
+    // 参数: a, [width]
+    pub fn cn_to_binary(args: Vec<String>) -> String {
+        unary_op(&args, |a, width| format!("0b{:0width$b}", a, width = width as usize))
+    }
+
+    // 转换为补零到位宽的十六进制字符串
+    // 参数: a, [width]
+    pub fn cn_to_hex(args: Vec<String>) -> String {
+        unary_op(&args, |a, width| format!("0x{:0width$x}", a, width = (width / 4) as usize))
     }
 }
 
@@ -821,14 +3685,39 @@ pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
     stats_ns.add_function("mean", stats::cn_mean)
             .add_function("median", stats::cn_median)
             .add_function("stddev", stats::cn_stddev)
-            .add_function("variance", stats::cn_variance);
+            .add_function("variance", stats::cn_variance)
+            .add_function("mode", stats::cn_mode)
+            .add_function("percentile", stats::cn_percentile)
+            .add_function("quantile", stats::cn_quantile)
+            .add_function("min", stats::cn_min)
+            .add_function("max", stats::cn_max)
+            .add_function("range", stats::cn_range)
+            .add_function("skewness", stats::cn_skewness)
+            .add_function("kurtosis", stats::cn_kurtosis)
+            .add_function("covariance", stats::cn_covariance)
+            .add_function("correlation", stats::cn_correlation)
+            .add_function("zscore", stats::cn_zscore)
+            .add_function("linregress", stats::cn_linregress)
+            .add_function("polyfit", stats::cn_polyfit)
+            .add_function("polyval", stats::cn_polyval)
+            .add_function("histogram", stats::cn_histogram)
+            .add_function("cut", stats::cn_cut);
 
     // 注册随机数生成命名空间
     let random_ns = registry.namespace("random");
     random_ns.add_function("seed", random::cn_seed)
              .add_function("random", random::cn_random)
              .add_function("randint", random::cn_randint)
-             .add_function("uniform", random::cn_uniform);
+             .add_function("uniform", random::cn_uniform)
+             .add_function("gaussian", random::cn_gaussian)
+             .add_function("exponential", random::cn_exponential)
+             .add_function("poisson", random::cn_poisson)
+             .add_function("shuffle", random::cn_shuffle)
+             .add_function("choice", random::cn_choice)
+             .add_function("sample", random::cn_sample)
+             .add_function("secure_bytes", secure_random::cn_secure_bytes)
+             .add_function("secure_int", secure_random::cn_secure_int)
+             .add_function("uuid4", secure_random::cn_uuid4);
 
     // 注册数值分析命名空间
     let numeric_ns = registry.namespace("numeric");
@@ -836,7 +3725,78 @@ pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
               .add_function("combination", numeric::cn_combination)
               .add_function("permutation", numeric::cn_permutation)
               .add_function("gcd", numeric::cn_gcd)
-              .add_function("lcm", numeric::cn_lcm);
+              .add_function("lcm", numeric::cn_lcm)
+              .add_function("bisect", numeric::cn_bisect)
+              .add_function("newton", numeric::cn_newton)
+              .add_function("solve_quadratic", numeric::cn_solve_quadratic)
+              .add_function("solve_cubic", numeric::cn_solve_cubic)
+              .add_function("is_prime", numeric::cn_is_prime)
+              .add_function("next_prime", numeric::cn_next_prime)
+              .add_function("prime_factors", numeric::cn_prime_factors)
+              .add_function("totient", numeric::cn_totient)
+              .add_function("mod_pow", numeric::cn_mod_pow)
+              .add_function("mod_inverse", numeric::cn_mod_inverse)
+              .add_function("lerp", numeric::cn_lerp)
+              .add_function("clamp", numeric::cn_clamp)
+              .add_function("map_range", numeric::cn_map_range)
+              .add_function("smoothstep", numeric::cn_smoothstep)
+              .add_function("spline", numeric::cn_spline);
+
+    // 注册分数命名空间
+    let fraction_ns = registry.namespace("fraction");
+    fraction_ns.add_function("create", fraction::cn_create)
+               .add_function("add", fraction::cn_add)
+               .add_function("sub", fraction::cn_sub)
+               .add_function("mul", fraction::cn_mul)
+               .add_function("div", fraction::cn_div)
+               .add_function("simplify", fraction::cn_simplify)
+               .add_function("compare", fraction::cn_compare)
+               .add_function("to_float", fraction::cn_to_float);
+
+    // 注册任意精度小数命名空间
+    let bigfloat_ns = registry.namespace("bigfloat");
+    bigfloat_ns.add_function("set_precision", bigfloat::cn_set_precision)
+               .add_function("get_precision", bigfloat::cn_get_precision)
+               .add_function("add", bigfloat::cn_add)
+               .add_function("sub", bigfloat::cn_sub)
+               .add_function("mul", bigfloat::cn_mul)
+               .add_function("div", bigfloat::cn_div)
+               .add_function("sqrt", bigfloat::cn_sqrt)
+               .add_function("pow", bigfloat::cn_pow)
+               .add_function("exp", bigfloat::cn_exp)
+               .add_function("ln", bigfloat::cn_ln)
+               .add_function("compare", bigfloat::cn_compare);
+
+    // 注册数值微积分命名空间
+    let calc_ns = registry.namespace("calc");
+    calc_ns.add_function("integrate", calc::cn_integrate)
+           .add_function("derivative", calc::cn_derivative)
+           .add_function("solve_ode_euler", calc::cn_solve_ode_euler)
+           .add_function("solve_ode_rk4", calc::cn_solve_ode_rk4);
+
+    // 注册单位换算命名空间
+    let units_ns = registry.namespace("units");
+    units_ns.add_function("convert", units::cn_convert)
+            .add_function("register", units::cn_register)
+            .add_function("list", units::cn_list)
+            .add_function("categories", units::cn_categories)
+            .add_function("category_of", units::cn_category_of);
+
+    // 注册位运算命名空间
+    let bits_ns = registry.namespace("bits");
+    bits_ns.add_function("and", bits::cn_and)
+           .add_function("or", bits::cn_or)
+           .add_function("xor", bits::cn_xor)
+           .add_function("not", bits::cn_not)
+           .add_function("shl", bits::cn_shl)
+           .add_function("shr", bits::cn_shr)
+           .add_function("sar", bits::cn_sar)
+           .add_function("rotate", bits::cn_rotate)
+           .add_function("popcount", bits::cn_popcount)
+           .add_function("leading_zeros", bits::cn_leading_zeros)
+           .add_function("trailing_zeros", bits::cn_trailing_zeros)
+           .add_function("to_binary", bits::cn_to_binary)
+           .add_function("to_hex", bits::cn_to_hex);
 
     // 注册常数命名空间
     let const_ns = registry.namespace("constants");
@@ -865,6 +3825,11 @@ pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
  * - 随机数生成
  * - 数值分析函数
  * - 数学常数
+ * - 分数（有理数）运算
+ * - 数值微积分
+ * - 单位换算
+ * - 任意精度小数
+ * - 位运算
  *
  * 使用方法：
  *
@@ -905,12 +3870,15 @@ pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
  *    result : float = random();             // 0-1随机数
  *    result : int = randint("1", "10");     // 1-10随机整数
  *    result : float = uniform("0", "100");  // 0-100随机浮点数
+ *    result : float = gaussian("0", "1");   // 正态分布随机数
+ *    result : array<int> = shuffle("1", "2", "3"); // 打乱顺序，返回数组
  *
  * 7. 数值分析：
  *    using ns numeric;
  *    result : int = factorial("5");         // 阶乘: 120
  *    result : int = combination("5", "2");  // 组合数: 10
  *    result : int = gcd("12", "8");         // 最大公约数: 4
+ *    result : float = lerp("0", "10", "0.25"); // 线性插值: 2.5
  *
  * 8. 数学常数：
  *    using ns constants;
@@ -918,6 +3886,33 @@ pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
  *    e_val : float = e();                   // 自然常数e
  *    gamma_val : float = euler_gamma();     // 欧拉常数
  *
+ * 9. 分数运算：
+ *    using ns fraction;
+ *    result : string = add("1/3", "1/6");   // 分数加法: "1/2"
+ *    result : string = simplify("6/8");     // 约分: "3/4"
+ *    result : float = to_float("1/8");      // 转换为浮点数: 0.125
+ *
+ * 10. 数值微积分：
+ *    using ns calc;
+ *    result : float = integrate("x^2", "0", "3");   // 定积分: 9
+ *    result : float = derivative("x^3", "2");       // 导数: 12
+ *    result : string = solve_ode_rk4("-y", "0", "1", "1", "10"); // 常微分方程
+ *
+ * 11. 单位换算：
+ *    using ns units;
+ *    result : float = convert("10", "km", "mi");    // 6.2137...
+ *    register("h", "time", "3600");                  // 注册自定义单位
+ *
+ * 12. 任意精度小数：
+ *    using ns bigfloat;
+ *    result : string = sqrt("2");                    // 默认50位小数
+ *    result : string = div("1", "3", "30");          // 指定30位小数
+ *
+ * 13. 位运算：
+ *    using ns bits;
+ *    result : int = xor("0xff", "0x0f");             // 240
+ *    result : int = popcount("-1", "64");            // 64
+ *
  * 注意：
  * - 函数返回值会自动转换为适当的数值类型
  * - 无效输入会返回 "0" 或 "NaN"
@@ -0,0 +1,478 @@
+use ::std::collections::HashMap;
+use serde_json::{Map, Value as JsonValue};
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::net::{IpAddr, Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// 导入通用库
+use cn_common::handle::HandleTable;
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::json_value;
+
+// TCP命名空间
+// listen 返回监听句柄，connect 和 accept 返回连接句柄，两者都用 close 关闭
+// 数据以UTF-8文本收发；超时参数以毫秒为单位，0表示不限制
+mod tcp {
+    use super::*;
+
+    // 默认每次最多接收的字节数
+    const DEFAULT_RECV_SIZE: usize = 4096;
+
+    // accept 设置超时时轮询的间隔
+    const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    enum Socket {
+        Listener(TcpListener),
+        // 读取经过缓冲，以便按行接收；写入直接使用内部的 TcpStream
+        Stream(BufReader<TcpStream>),
+    }
+
+    static SOCKETS: Mutex<HandleTable<Socket>> = Mutex::new(HandleTable::new());
+
+    fn lock_sockets() -> MutexGuard<'static, HandleTable<Socket>> {
+        SOCKETS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // 在句柄对应的连接上执行操作
+    fn with_stream<F>(handle: Option<&String>, f: F) -> String
+    where
+        F: FnOnce(&mut BufReader<TcpStream>) -> Result<String, String>,
+    {
+        let mut sockets = lock_sockets();
+        let id = handle.and_then(|arg| sockets.parse_handle(arg));
+        match id.and_then(|id| sockets.get_mut(id)) {
+            Some(Socket::Stream(stream)) => f(stream).unwrap_or_else(|e| e),
+            Some(Socket::Listener(_)) => "错误: 监听句柄不能用于收发数据".to_string(),
+            None => "错误: 无效的连接句柄".to_string(),
+        }
+    }
+
+    // 连接到服务器，返回连接句柄
+    // 参数: host, port, [timeout]（连接超时毫秒数）
+    pub fn cn_connect(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供主机和端口".to_string();
+        }
+        let addrs = match resolve(&args[0], &args[1]) {
+            Ok(addrs) => addrs,
+            Err(e) => return e,
+        };
+        let timeout = match args.get(2).map(|t| parse_timeout(t)) {
+            Some(Ok(timeout)) => timeout,
+            Some(Err(e)) => return e,
+            None => None,
+        };
+
+        // 依次尝试解析出的每个地址
+        let mut last_error = None;
+        for addr in addrs {
+            let result = match timeout {
+                Some(timeout) => TcpStream::connect_timeout(&addr, timeout),
+                None => TcpStream::connect(addr),
+            };
+            match result {
+                Ok(stream) => return lock_sockets().insert(Socket::Stream(BufReader::new(stream))).to_string(),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        match last_error {
+            Some(e) if is_timeout(&e) => format!("错误: 连接 {}:{} 超时", args[0], args[1]),
+            Some(e) => format!("错误: 无法连接 {}:{}: {}", args[0], args[1], e),
+            None => format!("错误: 无法解析主机: {}", args[0]),
+        }
+    }
+
+    // 在指定地址和端口上监听，返回监听句柄；端口为0时由系统分配，可用 local_addr 查询
+    // 参数: host, port
+    pub fn cn_listen(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供主机和端口".to_string();
+        }
+        let addrs = match resolve(&args[0], &args[1]) {
+            Ok(addrs) => addrs,
+            Err(e) => return e,
+        };
+        match TcpListener::bind(&addrs[..]) {
+            Ok(listener) => lock_sockets().insert(Socket::Listener(listener)).to_string(),
+            Err(e) => format!("错误: 无法监听 {}:{}: {}", args[0], args[1], e),
+        }
+    }
+
+    // 等待并接受一个连接，返回连接句柄
+    // 参数: listener, [timeout]
+    pub fn cn_accept(args: Vec<String>) -> String {
+        let timeout = match args.get(1).map(|t| parse_timeout(t)) {
+            Some(Ok(timeout)) => timeout,
+            Some(Err(e)) => return e,
+            None => None,
+        };
+
+        let mut sockets = lock_sockets();
+        let id = args.first().and_then(|arg| sockets.parse_handle(arg));
+        let listener = match id.and_then(|id| sockets.get(id)) {
+            Some(Socket::Listener(listener)) => listener,
+            Some(Socket::Stream(_)) => return "错误: 连接句柄不能用于接受连接".to_string(),
+            None => return "错误: 无效的监听句柄".to_string(),
+        };
+
+        let result = match timeout {
+            Some(timeout) => accept_timeout(listener, timeout),
+            None => listener.accept().map(|(stream, _)| stream),
+        };
+        match result {
+            Ok(stream) => sockets.insert(Socket::Stream(BufReader::new(stream))).to_string(),
+            Err(e) if is_timeout(&e) => "错误: 等待连接超时".to_string(),
+            Err(e) => format!("错误: 接受连接失败: {}", e),
+        }
+    }
+
+    // 标准库的 accept 不支持超时，改为非阻塞模式轮询
+    fn accept_timeout(listener: &TcpListener, timeout: Duration) -> std::io::Result<TcpStream> {
+        listener.set_nonblocking(true)?;
+        let deadline = Instant::now() + timeout;
+        let result = loop {
+            match listener.accept() {
+                Ok((stream, _)) => break stream.set_nonblocking(false).map(|_| stream),
+                Err(e) if e.kind() == ErrorKind::WouldBlock && Instant::now() < deadline => {
+                    thread::sleep(ACCEPT_POLL_INTERVAL);
+                },
+                Err(e) => break Err(e),
+            }
+        };
+        listener.set_nonblocking(false)?;
+        result
+    }
+
+    // 发送文本，返回发送的字节数
+    // 参数: conn, text
+    pub fn cn_send(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供连接句柄和要发送的文本".to_string();
+        }
+        with_stream(args.first(), |stream| {
+            let socket = stream.get_mut();
+            socket.write_all(args[1].as_bytes()).and_then(|_| socket.flush()).map_err(|e| match e {
+                e if is_timeout(&e) => "错误: 发送超时".to_string(),
+                e => format!("错误: 发送失败: {}", e),
+            })?;
+            Ok(args[1].len().to_string())
+        })
+    }
+
+    // 接收数据，有数据到达时立即返回，最多返回 max_bytes 字节；对方关闭连接后返回空字符串
+    // 参数: conn, [max_bytes]（默认4096）
+    pub fn cn_recv(args: Vec<String>) -> String {
+        let max_bytes = match args.get(1).map(|m| m.trim().parse::<usize>()) {
+            Some(Ok(max)) if max > 0 => max,
+            Some(_) => return format!("错误: 无效的字节数: {}", args[1]),
+            None => DEFAULT_RECV_SIZE,
+        };
+        with_stream(args.first(), |stream| {
+            let buffer = stream.fill_buf().map_err(recv_error)?;
+            let mut len = buffer.len().min(max_bytes);
+            // 不在多字节字符中间截断，剩余的字节留给下一次接收
+            if let Err(e) = std::str::from_utf8(&buffer[..len]) {
+                if e.error_len().is_none() && e.valid_up_to() > 0 {
+                    len = e.valid_up_to();
+                }
+            }
+            let text = String::from_utf8_lossy(&buffer[..len]).into_owned();
+            stream.consume(len);
+            Ok(text)
+        })
+    }
+
+    // 接收一行，返回的文本包含行尾的换行符；对方关闭连接后返回空字符串
+    // 参数: conn
+    pub fn cn_recv_line(args: Vec<String>) -> String {
+        with_stream(args.first(), |stream| {
+            let mut line = Vec::new();
+            stream.read_until(b'\n', &mut line).map_err(recv_error)?;
+            Ok(String::from_utf8_lossy(&line).into_owned())
+        })
+    }
+
+    // 设置连接的收发超时，超时后 recv、recv_line 和 send 返回错误
+    // 参数: conn, timeout
+    pub fn cn_set_timeout(args: Vec<String>) -> String {
+        let timeout = match args.get(1).map(|t| parse_timeout(t)) {
+            Some(Ok(timeout)) => timeout,
+            Some(Err(e)) => return e,
+            None => return "错误: 请提供超时毫秒数".to_string(),
+        };
+        with_stream(args.first(), |stream| {
+            let socket = stream.get_ref();
+            socket.set_read_timeout(timeout)
+                .and_then(|_| socket.set_write_timeout(timeout))
+                .map(|_| "true".to_string())
+                .map_err(|e| format!("错误: 无法设置超时: {}", e))
+        })
+    }
+
+    // 获取本地地址，形如 127.0.0.1:8080
+    // 参数: handle
+    pub fn cn_local_addr(args: Vec<String>) -> String {
+        let sockets = lock_sockets();
+        let id = args.first().and_then(|arg| sockets.parse_handle(arg));
+        let addr = match id.and_then(|id| sockets.get(id)) {
+            Some(Socket::Listener(listener)) => listener.local_addr(),
+            Some(Socket::Stream(stream)) => stream.get_ref().local_addr(),
+            None => return "错误: 无效的句柄".to_string(),
+        };
+        addr.map(|a| a.to_string()).unwrap_or_else(|e| format!("错误: 无法获取地址: {}", e))
+    }
+
+    // 获取连接对方的地址
+    // 参数: conn
+    pub fn cn_peer_addr(args: Vec<String>) -> String {
+        with_stream(args.first(), |stream| {
+            stream.get_ref().peer_addr().map(|a| a.to_string()).map_err(|e| format!("错误: 无法获取地址: {}", e))
+        })
+    }
+
+    // 关闭连接或监听
+    // 参数: handle
+    pub fn cn_close(args: Vec<String>) -> String {
+        let mut sockets = lock_sockets();
+        let id = match args.first().and_then(|arg| sockets.parse_handle(arg)) {
+            Some(id) => id,
+            None => return "false".to_string(),
+        };
+        if let Some(Socket::Stream(stream)) = sockets.remove(id) {
+            let _ = stream.get_ref().shutdown(Shutdown::Both);
+        }
+        "true".to_string()
+    }
+
+    fn recv_error(e: std::io::Error) -> String {
+        if is_timeout(&e) {
+            "错误: 接收超时".to_string()
+        } else {
+            format!("错误: 接收失败: {}", e)
+        }
+    }
+}
+
+// UDP命名空间
+mod udp {
+    use super::*;
+
+    // 默认每次最多接收的字节数，足以容纳任何UDP数据报
+    const DEFAULT_RECV_SIZE: usize = 65536;
+
+    static SOCKETS: Mutex<HandleTable<UdpSocket>> = Mutex::new(HandleTable::new());
+
+    fn lock_sockets() -> MutexGuard<'static, HandleTable<UdpSocket>> {
+        SOCKETS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn with_socket<F>(handle: Option<&String>, f: F) -> String
+    where
+        F: FnOnce(&UdpSocket) -> Result<String, String>,
+    {
+        let sockets = lock_sockets();
+        match handle.and_then(|arg| sockets.parse_handle(arg)).and_then(|id| sockets.get(id)) {
+            Some(socket) => f(socket).unwrap_or_else(|e| e),
+            None => "错误: 无效的UDP句柄".to_string(),
+        }
+    }
+
+    // 绑定本地地址和端口，返回UDP句柄；端口为0时由系统分配
+    // 参数: host, port
+    pub fn cn_bind(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供主机和端口".to_string();
+        }
+        let addrs = match resolve(&args[0], &args[1]) {
+            Ok(addrs) => addrs,
+            Err(e) => return e,
+        };
+        match UdpSocket::bind(&addrs[..]) {
+            Ok(socket) => lock_sockets().insert(socket).to_string(),
+            Err(e) => format!("错误: 无法绑定 {}:{}: {}", args[0], args[1], e),
+        }
+    }
+
+    // 向指定地址发送一个数据报，返回发送的字节数
+    // 参数: socket, host, port, text
+    pub fn cn_send_to(args: Vec<String>) -> String {
+        if args.len() < 4 {
+            return "错误: 请提供UDP句柄、主机、端口和要发送的文本".to_string();
+        }
+        let addrs = match resolve(&args[1], &args[2]) {
+            Ok(addrs) => addrs,
+            Err(e) => return e,
+        };
+        with_socket(args.first(), |socket| {
+            // 优先选择与套接字地址族相同的地址
+            let local_v4 = socket.local_addr().map(|a| a.is_ipv4()).unwrap_or(true);
+            let addr = addrs.iter().find(|a| a.is_ipv4() == local_v4).unwrap_or(&addrs[0]);
+            socket.send_to(args[3].as_bytes(), addr)
+                .map(|n| n.to_string())
+                .map_err(|e| format!("错误: 发送失败: {}", e))
+        })
+    }
+
+    // 接收一个数据报，返回 Map: {"data": 文本, "host": 发送方地址, "port": 发送方端口}
+    // 参数: socket, [max_bytes]（默认65536，超出部分被丢弃）
+    pub fn cn_recv_from(args: Vec<String>) -> String {
+        let max_bytes = match args.get(1).map(|m| m.trim().parse::<usize>()) {
+            Some(Ok(max)) if max > 0 => max,
+            Some(_) => return format!("错误: 无效的字节数: {}", args[1]),
+            None => DEFAULT_RECV_SIZE,
+        };
+        with_socket(args.first(), |socket| {
+            let mut buffer = vec![0u8; max_bytes];
+            let (len, from) = socket.recv_from(&mut buffer).map_err(|e| {
+                if is_timeout(&e) { "错误: 接收超时".to_string() } else { format!("错误: 接收失败: {}", e) }
+            })?;
+            let mut obj = Map::new();
+            obj.insert("data".to_string(), JsonValue::String(String::from_utf8_lossy(&buffer[..len]).into_owned()));
+            obj.insert("host".to_string(), JsonValue::String(from.ip().to_string()));
+            obj.insert("port".to_string(), JsonValue::from(from.port()));
+            Ok(json_value(&JsonValue::Object(obj).to_string()))
+        })
+    }
+
+    // 设置收发超时，超时后 recv_from 返回错误
+    // 参数: socket, timeout（毫秒，0表示不限制）
+    pub fn cn_set_timeout(args: Vec<String>) -> String {
+        let timeout = match args.get(1).map(|t| parse_timeout(t)) {
+            Some(Ok(timeout)) => timeout,
+            Some(Err(e)) => return e,
+            None => return "错误: 请提供超时毫秒数".to_string(),
+        };
+        with_socket(args.first(), |socket| {
+            socket.set_read_timeout(timeout)
+                .and_then(|_| socket.set_write_timeout(timeout))
+                .map(|_| "true".to_string())
+                .map_err(|e| format!("错误: 无法设置超时: {}", e))
+        })
+    }
+
+    // 允许或禁止发送广播数据报
+    // 参数: socket, enabled
+    pub fn cn_set_broadcast(args: Vec<String>) -> String {
+        let enabled = args.get(1).map(|s| s.trim() == "true").unwrap_or(true);
+        with_socket(args.first(), |socket| {
+            socket.set_broadcast(enabled)
+                .map(|_| "true".to_string())
+                .map_err(|e| format!("错误: 无法设置广播: {}", e))
+        })
+    }
+
+    // 获取本地地址，形如 0.0.0.0:5353
+    // 参数: socket
+    pub fn cn_local_addr(args: Vec<String>) -> String {
+        with_socket(args.first(), |socket| {
+            socket.local_addr().map(|a| a.to_string()).map_err(|e| format!("错误: 无法获取地址: {}", e))
+        })
+    }
+
+    // 关闭UDP套接字
+    // 参数: socket
+    pub fn cn_close(args: Vec<String>) -> String {
+        let mut sockets = lock_sockets();
+        match args.first().and_then(|arg| sockets.parse_handle(arg)) {
+            Some(id) => {
+                sockets.remove(id);
+                "true".to_string()
+            },
+            None => "false".to_string(),
+        }
+    }
+}
+
+// DNS命名空间
+mod dns {
+    use super::*;
+
+    // 解析主机名，返回去重后的IP地址字符串数组
+    // 参数: hostname, [family]（ipv4 或 ipv6，默认两者都返回）
+    pub fn cn_resolve(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供主机名".to_string();
+        }
+        let filter: fn(&IpAddr) -> bool = match args.get(1).map(|f| f.trim().to_lowercase()).as_deref() {
+            None | Some("") | Some("any") => |_| true,
+            Some("ipv4") | Some("4") => IpAddr::is_ipv4,
+            Some("ipv6") | Some("6") => IpAddr::is_ipv6,
+            Some(other) => return format!("错误: 无效的地址族: {}", other),
+        };
+        let addrs = match resolve(&args[0], "0") {
+            Ok(addrs) => addrs,
+            Err(e) => return e,
+        };
+        let mut ips: Vec<IpAddr> = Vec::new();
+        for ip in addrs.iter().map(|a| a.ip()).filter(filter) {
+            if !ips.contains(&ip) {
+                ips.push(ip);
+            }
+        }
+        let ips: Vec<JsonValue> = ips.iter().map(|ip| JsonValue::String(ip.to_string())).collect();
+        json_value(&JsonValue::Array(ips).to_string())
+    }
+}
+
+fn resolve(host: &str, port: &str) -> Result<Vec<SocketAddr>, String> {
+    let port = port.trim().parse::<u16>().map_err(|_| format!("错误: 无效的端口: {}", port))?;
+    let addrs: Vec<SocketAddr> = (host.trim(), port).to_socket_addrs()
+        .map_err(|e| format!("错误: 无法解析主机 {}: {}", host, e))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(format!("错误: 无法解析主机: {}", host));
+    }
+    Ok(addrs)
+}
+
+fn parse_timeout(text: &str) -> Result<Option<Duration>, String> {
+    match text.trim().parse::<u64>() {
+        Ok(0) => Ok(None),
+        Ok(ms) => Ok(Some(Duration::from_millis(ms))),
+        Err(_) => Err(format!("错误: 无效的超时毫秒数: {}", text)),
+    }
+}
+
+// 设置了读写超时的套接字超时时，不同平台分别返回 WouldBlock 或 TimedOut
+fn is_timeout(e: &std::io::Error) -> bool {
+    matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册TCP命名空间下的函数
+    let tcp_ns = registry.namespace("tcp");
+    tcp_ns.add_function("connect", tcp::cn_connect)
+          .add_function("listen", tcp::cn_listen)
+          .add_function("accept", tcp::cn_accept)
+          .add_function("send", tcp::cn_send)
+          .add_function("recv", tcp::cn_recv)
+          .add_function("recv_line", tcp::cn_recv_line)
+          .add_function("set_timeout", tcp::cn_set_timeout)
+          .add_function("local_addr", tcp::cn_local_addr)
+          .add_function("peer_addr", tcp::cn_peer_addr)
+          .add_function("close", tcp::cn_close);
+
+    // 注册UDP命名空间下的函数
+    let udp_ns = registry.namespace("udp");
+    udp_ns.add_function("bind", udp::cn_bind)
+          .add_function("send_to", udp::cn_send_to)
+          .add_function("recv_from", udp::cn_recv_from)
+          .add_function("set_timeout", udp::cn_set_timeout)
+          .add_function("set_broadcast", udp::cn_set_broadcast)
+          .add_function("local_addr", udp::cn_local_addr)
+          .add_function("close", udp::cn_close);
+
+    // 注册DNS命名空间下的函数
+    let dns_ns = registry.namespace("dns");
+    dns_ns.add_function("resolve", dns::cn_resolve);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
@@ -1,10 +1,16 @@
 use ::std::collections::HashMap;
 use ::std::env;
-use ::std::process::Command;
-use sysinfo::{System, SystemExt, DiskExt, ProcessExt, CpuExt};
+use ::std::io::{Read, Write};
+use ::std::net::{IpAddr, TcpStream, ToSocketAddrs};
+use ::std::process::{Child, Command, ExitStatus, Stdio};
+use ::std::thread;
+use ::std::time::{Duration, Instant};
+use serde_json::{json, Value as JsonValue};
+use sysinfo::{System, SystemExt, DiskExt, NetworkExt, NetworksExt, ProcessExt, CpuExt, PidExt, UserExt};
 
 // 导入通用库
 use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::json_value;
 
 // 命名空间函数
 mod std {
@@ -47,6 +53,146 @@ mod std {
         result
     }
     
+    // 设置当前进程的环境变量，之后启动的子进程会继承
+    // 参数: key, value
+    pub fn cn_env_set(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 需要两个参数 (变量名, 值)".to_string();
+        }
+        if let Err(e) = check_env_key(&args[0]) {
+            return e;
+        }
+        if args[1].contains('\0') {
+            return "错误: 环境变量的值不能包含空字符".to_string();
+        }
+        env::set_var(&args[0], &args[1]);
+        "true".to_string()
+    }
+    
+    // 删除当前进程的环境变量，变量原本存在时返回 true
+    // 参数: key
+    pub fn cn_env_remove(args: Vec<String>) -> String {
+        let key = match args.first() {
+            Some(key) => key,
+            None => return "错误: 缺少环境变量名参数".to_string(),
+        };
+        if let Err(e) = check_env_key(key) {
+            return e;
+        }
+        let existed = env::var_os(key).is_some();
+        env::remove_var(key);
+        existed.to_string()
+    }
+    
+    // 展开字符串中的环境变量：$NAME、${NAME}、${NAME:-默认值}，开头的 ~（用户主目录），在Windows上还有 %NAME%
+    // 未设置的变量展开为空字符串，$$ 表示 $ 本身
+    // 参数: text
+    pub fn cn_env_expand(args: Vec<String>) -> String {
+        match args.first() {
+            Some(text) => expand_env(text),
+            None => "错误: 缺少字符串参数".to_string(),
+        }
+    }
+    
+    // 把 PATH 形式的字符串拆分为路径数组，分隔符在类Unix系统上为 :，在Windows上为 ;
+    // 参数: [value]（默认为 PATH 环境变量的值）
+    pub fn cn_path_split(args: Vec<String>) -> String {
+        let value = match args.first() {
+            Some(value) => value.into(),
+            None => env::var_os("PATH").unwrap_or_default(),
+        };
+        let paths: Vec<JsonValue> = env::split_paths(&value)
+            .map(|path| JsonValue::String(path.to_string_lossy().into_owned()))
+            .collect();
+        json_value(&JsonValue::Array(paths).to_string())
+    }
+    
+    // 把路径数组合并为 PATH 形式的字符串，可以再用 env_set 设置
+    // 参数: paths（字符串数组）
+    pub fn cn_path_join_env(args: Vec<String>) -> String {
+        let paths: Vec<String> = match args.first().map(|a| serde_json::from_str::<JsonValue>(a)) {
+            Some(Ok(JsonValue::Array(items))) => items.into_iter().map(|item| match item {
+                JsonValue::String(text) => text,
+                other => other.to_string(),
+            }).collect(),
+            _ => return "错误: 参数必须是路径数组".to_string(),
+        };
+        match env::join_paths(paths) {
+            Ok(joined) => joined.to_string_lossy().into_owned(),
+            Err(e) => format!("错误: 无法合并路径: {}", e),
+        }
+    }
+    
+    fn check_env_key(key: &str) -> Result<(), String> {
+        if key.is_empty() || key.contains('=') || key.contains('\0') {
+            return Err(format!("错误: 无效的环境变量名: {}", key));
+        }
+        Ok(())
+    }
+    
+    fn expand_env(text: &str) -> String {
+        let lookup = |name: &str| env::var(name).unwrap_or_default();
+        let mut result = String::new();
+        let mut rest = text;
+        if let Some(after) = rest.strip_prefix('~') {
+            if after.is_empty() || after.starts_with('/') || after.starts_with('\\') {
+                if let Some(home) = dirs::home_dir() {
+                    result.push_str(&home.to_string_lossy());
+                    rest = after;
+                }
+            }
+        }
+        let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+        let markers: &[char] = if cfg!(windows) { &['$', '%'] } else { &['$'] };
+        while let Some(position) = rest.find(markers) {
+            result.push_str(&rest[..position]);
+            let tail = &rest[position..];
+            if let Some(after) = tail.strip_prefix("$$") {
+                result.push('$');
+                rest = after;
+            } else if let Some(braced) = tail.strip_prefix("${") {
+                match braced.find('}') {
+                    Some(end) => {
+                        let inner = &braced[..end];
+                        let value = match inner.split_once(":-") {
+                            Some((name, default)) => Some(lookup(name)).filter(|v| !v.is_empty()).unwrap_or_else(|| expand_env(default)),
+                            None => lookup(inner),
+                        };
+                        result.push_str(&value);
+                        rest = &braced[end + 1..];
+                    },
+                    None => {
+                        result.push_str(tail);
+                        rest = "";
+                    },
+                }
+            } else if let Some(after) = tail.strip_prefix('$') {
+                let end = after.find(|c: char| !is_name_char(c)).unwrap_or(after.len());
+                if end == 0 {
+                    result.push('$');
+                } else {
+                    result.push_str(&lookup(&after[..end]));
+                }
+                rest = &after[end..];
+            } else {
+                // %NAME%，名称中只能有字母、数字和下划线，否则原样保留 %
+                let after = &tail[1..];
+                match after.find('%') {
+                    Some(end) if end > 0 && after[..end].chars().all(is_name_char) => {
+                        result.push_str(&lookup(&after[..end]));
+                        rest = &after[end + 1..];
+                    },
+                    _ => {
+                        result.push('%');
+                        rest = after;
+                    },
+                }
+            }
+        }
+        result.push_str(rest);
+        result
+    }
+    
     // 获取当前工作目录
     pub fn cn_cwd(_args: Vec<String>) -> String {
         match env::current_dir() {
@@ -90,6 +236,23 @@ mod std {
             total_mem, used_mem, total_swap, used_swap)
     }
     
+    // 获取内存信息，返回 {"total", "used", "free", "available", "swap_total", "swap_used", "swap_free"}，单位为字节
+    pub fn cn_memory_json(_args: Vec<String>) -> String {
+        let mut system = System::new();
+        system.refresh_memory();
+        
+        let result = json!({
+            "total": system.total_memory(),
+            "used": system.used_memory(),
+            "free": system.free_memory(),
+            "available": system.available_memory(),
+            "swap_total": system.total_swap(),
+            "swap_used": system.used_swap(),
+            "swap_free": system.free_swap(),
+        });
+        json_value(&result.to_string())
+    }
+    
     // 获取CPU信息
     pub fn cn_cpu_info(_args: Vec<String>) -> String {
         let mut system = System::new_all();
@@ -106,6 +269,32 @@ mod std {
         result
     }
     
+    // 获取CPU信息，返回 {"count", "physical_cores", "brand", "vendor", "usage", "cpus"}，
+    // cpus 的每个元素为 {"name", "usage", "frequency"}；使用率是百分比，频率单位为MHz
+    // 使用率需要两次采样计算，调用会等待约200毫秒
+    pub fn cn_cpu_info_json(_args: Vec<String>) -> String {
+        let mut system = System::new();
+        system.refresh_cpu();
+        ::std::thread::sleep(System::MINIMUM_CPU_UPDATE_INTERVAL);
+        system.refresh_cpu();
+        
+        let global = system.global_cpu_info();
+        let cpus: Vec<JsonValue> = system.cpus().iter().map(|cpu| json!({
+            "name": cpu.name(),
+            "usage": cpu.cpu_usage(),
+            "frequency": cpu.frequency(),
+        })).collect();
+        let result = json!({
+            "count": system.cpus().len(),
+            "physical_cores": system.physical_core_count(),
+            "brand": system.cpus().first().map(|cpu| cpu.brand()).unwrap_or(""),
+            "vendor": system.cpus().first().map(|cpu| cpu.vendor_id()).unwrap_or(""),
+            "usage": global.cpu_usage(),
+            "cpus": cpus,
+        });
+        json_value(&result.to_string())
+    }
+    
     // 获取磁盘信息
     pub fn cn_disk_info(_args: Vec<String>) -> String {
         let mut system = System::new_all();
@@ -124,6 +313,24 @@ mod std {
         result
     }
     
+    // 获取磁盘信息，返回数组，每个元素为 {"name", "mount_point", "file_system", "kind", "total", "available", "removable"}，
+    // 空间单位为字节，kind 为 "HDD"、"SSD" 或 "Unknown"
+    pub fn cn_disk_info_json(_args: Vec<String>) -> String {
+        let mut system = System::new();
+        system.refresh_disks_list();
+        
+        let disks: Vec<JsonValue> = system.disks().iter().map(|disk| json!({
+            "name": disk.name().to_string_lossy(),
+            "mount_point": disk.mount_point().to_string_lossy(),
+            "file_system": String::from_utf8_lossy(disk.file_system()),
+            "kind": format!("{:?}", disk.kind()),
+            "total": disk.total_space(),
+            "available": disk.available_space(),
+            "removable": disk.is_removable(),
+        })).collect();
+        json_value(&JsonValue::Array(disks).to_string())
+    }
+    
     // 执行系统命令
     // 参数: command, [arg1, arg2, ...]
     pub fn cn_exec(args: Vec<String>) -> String {
@@ -149,6 +356,504 @@ mod std {
         }
     }
     
+    // 执行系统命令，返回 {"code", "success", "stdout", "stderr", "timed_out", "signal"}
+    // code 是退出码，被信号终止或超时被杀死时为空值；signal 是终止进程的信号编号，只在类Unix系统上提供
+    // options 是可选的 Map:
+    //   stdin: 写入标准输入的字符串, cwd: 工作目录, env: 要设置的环境变量（值为 false 时删除该变量）,
+    //   clear_env: 是否不继承当前的环境变量（默认false）, timeout: 超时毫秒数，超时后杀死进程（设置后命令退出时也会杀死它在后台启动的进程）,
+    //   shell: 是否通过系统shell执行（默认false）；为 true 时 cmd 是命令行，args 成为 $1、$2 等参数（Windows上追加在命令行之后）
+    // 参数: cmd, [args]（字符串数组）, [options]
+    pub fn cn_exec_full(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 缺少命令参数".to_string();
+        }
+        let command_args: Vec<String> = match args.get(1).map(|a| a.trim()).filter(|a| !a.is_empty()) {
+            Some(list) => match serde_json::from_str::<JsonValue>(list) {
+                Ok(JsonValue::Array(items)) => items.into_iter().map(|item| match item {
+                    JsonValue::String(text) => text,
+                    other => other.to_string(),
+                }).collect(),
+                _ => return "错误: 参数列表必须是数组".to_string(),
+            },
+            None => Vec::new(),
+        };
+        let options = match args.get(2).map(|o| o.trim()).filter(|o| !o.is_empty()) {
+            Some(options) => match serde_json::from_str::<JsonValue>(options) {
+                Ok(JsonValue::Object(options)) => options,
+                _ => return "错误: 选项必须是 Map".to_string(),
+            },
+            None => serde_json::Map::new(),
+        };
+        match run_command(&args[0], &command_args, &options) {
+            Ok(result) => json_value(&result.to_string()),
+            Err(e) => e,
+        }
+    }
+    
+    fn run_command(program: &str, command_args: &[String], options: &serde_json::Map<String, JsonValue>) -> Result<JsonValue, String> {
+        let shell = options.get("shell").and_then(|s| s.as_bool()).unwrap_or(false);
+        let mut command = if shell {
+            shell_command(program, command_args)
+        } else {
+            let mut command = Command::new(program);
+            command.args(command_args);
+            command
+        };
+        if let Some(cwd) = options.get("cwd").and_then(|c| c.as_str()) {
+            command.current_dir(cwd);
+        }
+        if options.get("clear_env").and_then(|c| c.as_bool()).unwrap_or(false) {
+            command.env_clear();
+        }
+        match options.get("env") {
+            Some(JsonValue::Object(vars)) => {
+                for (key, value) in vars {
+                    match value {
+                        JsonValue::Bool(false) | JsonValue::Null => { command.env_remove(key); },
+                        JsonValue::String(text) => { command.env(key, text); },
+                        other => { command.env(key, other.to_string()); },
+                    }
+                }
+            },
+            Some(_) => return Err("错误: env 必须是 Map".to_string()),
+            None => {},
+        }
+        let timeout = match options.get("timeout") {
+            Some(ms) => match ms.as_u64() {
+                Some(ms) => Some(Duration::from_millis(ms)),
+                None => return Err("错误: timeout 必须是非负整数毫秒数".to_string()),
+            },
+            None => None,
+        };
+        let input = options.get("stdin").map(|input| match input {
+            JsonValue::String(text) => text.clone(),
+            other => other.to_string(),
+        });
+        
+        command.stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if timeout.is_some() {
+            new_process_group(&mut command);
+        }
+        let mut child = command.spawn().map_err(|e| format!("执行命令失败: {}", e))?;
+        
+        // 标准输入和输出在单独的线程中读写，避免管道缓冲区满时互相等待
+        let writer = match (child.stdin.take(), input) {
+            (Some(mut stdin), Some(input)) => Some(thread::spawn(move || {
+                // 进程不读取全部输入就退出时写入会失败，忽略即可
+                let _ = stdin.write_all(input.as_bytes());
+            })),
+            _ => None,
+        };
+        let stdout = child.stdout.take().map(read_pipe);
+        let stderr = child.stderr.take().map(read_pipe);
+        
+        let started = Instant::now();
+        let mut timed_out = false;
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) => {},
+                Err(e) => return Err(format!("等待命令失败: {}", e)),
+            }
+            if timeout.is_some_and(|timeout| started.elapsed() >= timeout) {
+                timed_out = true;
+                kill_tree(&mut child);
+                break child.wait().map_err(|e| format!("等待命令失败: {}", e))?;
+            }
+            thread::sleep(Duration::from_millis(5));
+        };
+        // 设置了超时时，命令在后台启动的进程（如 sh -c 'sleep 100 &'）也会被杀死，
+        // 脱离进程组的进程仍可能持有输出管道，读取最多再等待 PIPE_GRACE，之后返回已读到的输出
+        let deadline = timeout.map(|_| {
+            kill_tree(&mut child);
+            Instant::now() + PIPE_GRACE
+        });
+        if let Some(writer) = writer {
+            // 写入线程在进程退出后因管道关闭而结束，脱离进程组的进程仍持有标准输入时不再等待
+            if deadline.is_none() || writer.is_finished() {
+                let _ = writer.join();
+            }
+        }
+        let collect = |reader: Option<PipeReader>| {
+            reader.map(|reader| String::from_utf8_lossy(&reader.finish(deadline)).into_owned())
+                .unwrap_or_default()
+        };
+        
+        Ok(json!({
+            "code": status.code(),
+            "success": status.success(),
+            "stdout": collect(stdout),
+            "stderr": collect(stderr),
+            "timed_out": timed_out,
+            "signal": exit_signal(&status),
+        }))
+    }
+    
+    // 命令结束后等待输出管道关闭的最长时间
+    const PIPE_GRACE: Duration = Duration::from_millis(200);
+    
+    // 在单独的线程中读取输出管道，读到的内容随时可以取出
+    struct PipeReader {
+        bytes: ::std::sync::Arc<::std::sync::Mutex<Vec<u8>>>,
+        handle: thread::JoinHandle<()>,
+    }
+    
+    impl PipeReader {
+        // 等待管道关闭后返回全部输出；到达 deadline 时管道仍未关闭则返回已读到的部分，读取线程在管道关闭后自行结束
+        fn finish(self, deadline: Option<Instant>) -> Vec<u8> {
+            if let Some(deadline) = deadline {
+                while !self.handle.is_finished() && Instant::now() < deadline {
+                    thread::sleep(Duration::from_millis(5));
+                }
+            }
+            if deadline.is_none() || self.handle.is_finished() {
+                let _ = self.handle.join();
+            }
+            let mut bytes = self.bytes.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            ::std::mem::take(&mut *bytes)
+        }
+    }
+    
+    fn read_pipe<R: Read + Send + 'static>(mut pipe: R) -> PipeReader {
+        let bytes = ::std::sync::Arc::new(::std::sync::Mutex::new(Vec::new()));
+        let shared = ::std::sync::Arc::clone(&bytes);
+        let handle = thread::spawn(move || {
+            let mut chunk = [0u8; 8192];
+            loop {
+                match pipe.read(&mut chunk) {
+                    Ok(0) => break,
+                    Ok(n) => shared.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).extend_from_slice(&chunk[..n]),
+                    Err(e) if e.kind() == ::std::io::ErrorKind::Interrupted => {},
+                    Err(_) => break,
+                }
+            }
+        });
+        PipeReader { bytes, handle }
+    }
+    
+    #[cfg(windows)]
+    fn shell_command(line: &str, command_args: &[String]) -> Command {
+        let mut command = Command::new("cmd");
+        command.arg("/C").arg(line).args(command_args);
+        command
+    }
+    
+    #[cfg(not(windows))]
+    fn shell_command(line: &str, command_args: &[String]) -> Command {
+        let mut command = Command::new("sh");
+        // -c 之后的第一个参数成为 $0
+        command.arg("-c").arg(line).arg("sh").args(command_args);
+        command
+    }
+    
+    // 让超时时能杀死命令启动的所有子进程，否则它们继续持有输出管道，读取会一直等待
+    #[cfg(unix)]
+    fn new_process_group(command: &mut Command) {
+        use ::std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    
+    #[cfg(not(unix))]
+    fn new_process_group(_command: &mut Command) {}
+    
+    // 命令已退出时只杀死进程组中剩下的进程
+    #[cfg(unix)]
+    fn kill_tree(child: &mut Child) {
+        // SAFETY: kill 只接受整数参数；负的PID表示进程组，进程组号等于子进程的PID
+        if unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) } != 0 {
+            let _ = child.kill();
+        }
+    }
+    
+    #[cfg(not(unix))]
+    fn kill_tree(child: &mut Child) {
+        let _ = child.kill();
+    }
+    
+    #[cfg(unix)]
+    fn exit_signal(status: &ExitStatus) -> Option<i32> {
+        use ::std::os::unix::process::ExitStatusExt;
+        status.signal()
+    }
+    
+    #[cfg(not(unix))]
+    fn exit_signal(_status: &ExitStatus) -> Option<i32> {
+        None
+    }
+    
+    // 用系统默认的程序打开文件、目录或URL，打开器启动成功返回 true，不等待打开的程序退出
+    // 带协议的字符串（如 https://、mailto:）按URL处理，其余按路径处理，路径必须存在
+    // 参数: path_or_url
+    pub fn cn_open(args: Vec<String>) -> String {
+        let target = match args.first().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            Some(target) => target,
+            None => return "错误: 缺少路径或URL参数".to_string(),
+        };
+        let target = if is_url(target) {
+            target.to_string()
+        } else {
+            // 转为绝对路径，打开器的工作目录不一定与脚本相同
+            match ::std::fs::canonicalize(target) {
+                Ok(path) => path.to_string_lossy().into_owned(),
+                Err(e) => return format!("错误: 无法打开 {}: {}", target, e),
+            }
+        };
+        
+        #[cfg(windows)]
+        let mut command = {
+            // cmd 的 start 会把 URL 中的 & 当作命令分隔符，改用 FileProtocolHandler
+            let mut command = Command::new("rundll32.exe");
+            command.arg("url.dll,FileProtocolHandler").arg(&target);
+            command
+        };
+        #[cfg(target_os = "macos")]
+        let mut command = {
+            let mut command = Command::new("open");
+            command.arg(&target);
+            command
+        };
+        #[cfg(not(any(windows, target_os = "macos")))]
+        let mut command = {
+            let mut command = Command::new("xdg-open");
+            command.arg(&target);
+            command
+        };
+        
+        let mut child = match command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::piped()).spawn() {
+            Ok(child) => child,
+            Err(e) => return format!("错误: 无法启动打开器: {}", e),
+        };
+        let stderr = child.stderr.take().map(read_pipe);
+        // 打开器通常启动程序后立即退出；有的打开器会一直等待程序结束，此时视为成功
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            match child.try_wait() {
+                Ok(Some(status)) if status.success() => return "true".to_string(),
+                Ok(Some(status)) => {
+                    let stderr = stderr.map(|reader| reader.finish(Some(Instant::now() + PIPE_GRACE))).unwrap_or_default();
+                    let stderr = String::from_utf8_lossy(&stderr);
+                    return format!("错误: 无法打开 {} ({}): {}", target, status, stderr.trim());
+                },
+                Ok(None) if Instant::now() >= deadline => return "true".to_string(),
+                Ok(None) => thread::sleep(Duration::from_millis(50)),
+                Err(e) => return format!("错误: 无法打开 {}: {}", target, e),
+            }
+        }
+    }
+    
+    // 形如 scheme: 的前缀，scheme 至少两个字符，以排除 Windows 的盘符
+    fn is_url(target: &str) -> bool {
+        match target.split_once(':') {
+            Some((scheme, _)) => scheme.len() > 1
+                && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.')),
+            None => false,
+        }
+    }
+    
+    // 获取网络接口信息，返回数组，每个元素为 {"name", "index", "up", "loopback", "mac", "ipv4", "ipv6"}，
+    // ipv4 的元素为 {"address", "netmask", "prefix", "broadcast"}，ipv6 的元素为 {"address", "prefix"}；没有IP地址的接口不列出
+    pub fn cn_net_interfaces(_args: Vec<String>) -> String {
+        let interfaces = match if_addrs::get_if_addrs() {
+            Ok(interfaces) => interfaces,
+            Err(e) => return format!("错误: 无法获取网络接口: {}", e),
+        };
+        let mut system = System::new();
+        system.refresh_networks_list();
+        let macs: HashMap<&str, String> = system.networks()
+            .iter()
+            .map(|(name, data)| (name.as_str(), data.mac_address().to_string()))
+            .collect();
+        
+        // 同一接口的多个地址合并为一项，保持系统返回的顺序
+        let mut result: Vec<(String, JsonValue)> = Vec::new();
+        for interface in &interfaces {
+            let position = match result.iter().position(|(name, _)| *name == interface.name) {
+                Some(position) => position,
+                None => {
+                    result.push((interface.name.clone(), json!({
+                        "name": interface.name,
+                        "index": interface.index,
+                        "up": interface.is_oper_up(),
+                        "loopback": interface.is_loopback(),
+                        "mac": macs.get(interface.name.as_str()).filter(|mac| mac.as_str() != "00:00:00:00:00:00"),
+                        "ipv4": [],
+                        "ipv6": [],
+                    })));
+                    result.len() - 1
+                },
+            };
+            let entry = &mut result[position].1;
+            match &interface.addr {
+                if_addrs::IfAddr::V4(v4) => entry["ipv4"].as_array_mut().expect("ipv4 是数组").push(json!({
+                    "address": v4.ip.to_string(),
+                    "netmask": v4.netmask.to_string(),
+                    "prefix": v4.prefixlen,
+                    "broadcast": v4.broadcast.map(|b| b.to_string()),
+                })),
+                if_addrs::IfAddr::V6(v6) => entry["ipv6"].as_array_mut().expect("ipv6 是数组").push(json!({
+                    "address": v6.ip.to_string(),
+                    "prefix": v6.prefixlen,
+                })),
+            }
+        }
+        let result: Vec<JsonValue> = result.into_iter().map(|(_, entry)| entry).collect();
+        json_value(&JsonValue::Array(result).to_string())
+    }
+    
+    // 查询本机的公网IP地址，通过HTTP请求返回纯文本IP的服务
+    // 参数: [url]（只支持 http://，默认为 http://api.ipify.org）, [timeout]（毫秒，默认为5000）
+    pub fn cn_public_ip(args: Vec<String>) -> String {
+        let url = args.first().map(|u| u.trim()).filter(|u| !u.is_empty()).unwrap_or("http://api.ipify.org");
+        let timeout = match args.get(1).map(|t| t.trim().parse::<u64>()) {
+            Some(Ok(ms)) if ms > 0 => Duration::from_millis(ms),
+            None => Duration::from_millis(5000),
+            _ => return format!("错误: 无效的毫秒数: {}", args[1]),
+        };
+        let body = match http_get_text(url, timeout) {
+            Ok(body) => body,
+            Err(e) => return e,
+        };
+        match body.trim().parse::<IpAddr>() {
+            Ok(ip) => ip.to_string(),
+            Err(_) => format!("错误: 服务返回的不是IP地址: {}", body.trim()),
+        }
+    }
+    
+    // 测试主机是否可达，返回 {"host", "sent", "received", "loss"（丢包百分比）, "min", "avg", "max", "times"}，时间单位为毫秒
+    // 没有 port 时调用系统的 ping 命令（ICMP）；有 port 时测量TCP连接的建立时间，适用于禁止ICMP的网络
+    // options 是可选的 Map: count: 次数（默认为4）, timeout: 每次的超时毫秒数（默认为1000）, port: TCP端口
+    // 参数: host, [options]
+    pub fn cn_ping(args: Vec<String>) -> String {
+        let host = match args.first().map(|h| h.trim()).filter(|h| !h.is_empty()) {
+            Some(host) => host,
+            None => return "错误: 缺少主机参数".to_string(),
+        };
+        // 主机名会成为 ping 命令的参数，不能以 - 开头
+        if host.starts_with('-') {
+            return format!("错误: 无效的主机: {}", host);
+        }
+        let options = match args.get(1).map(|o| o.trim()).filter(|o| !o.is_empty()) {
+            Some(options) => match serde_json::from_str::<JsonValue>(options) {
+                Ok(JsonValue::Object(options)) => options,
+                _ => return "错误: 选项必须是 Map".to_string(),
+            },
+            None => serde_json::Map::new(),
+        };
+        let count = options.get("count").and_then(|c| c.as_u64()).filter(|c| *c > 0).unwrap_or(4);
+        let timeout = options.get("timeout").and_then(|t| t.as_u64()).filter(|t| *t > 0).unwrap_or(1000);
+        let times = match options.get("port").and_then(|p| p.as_u64()) {
+            Some(port) => match u16::try_from(port) {
+                Ok(port) => tcp_ping(host, port, count, Duration::from_millis(timeout)),
+                Err(_) => return format!("错误: 无效的端口: {}", port),
+            },
+            None => icmp_ping(host, count, timeout),
+        };
+        let times = match times {
+            Ok(times) => times,
+            Err(e) => return e,
+        };
+        
+        let received = times.len() as u64;
+        let min = times.iter().copied().reduce(f64::min);
+        let max = times.iter().copied().reduce(f64::max);
+        let avg = if times.is_empty() { None } else { Some(times.iter().sum::<f64>() / times.len() as f64) };
+        let result = json!({
+            "host": host,
+            "sent": count,
+            "received": received,
+            "loss": (count - received.min(count)) as f64 * 100.0 / count as f64,
+            "min": min,
+            "avg": avg,
+            "max": max,
+            "times": times,
+        });
+        json_value(&result.to_string())
+    }
+    
+    fn http_get_text(url: &str, timeout: Duration) -> Result<String, String> {
+        let rest = url.strip_prefix("http://").ok_or_else(|| format!("错误: 只支持 http:// 地址: {}", url))?;
+        let (authority, path) = match rest.find('/') {
+            Some(slash) => (&rest[..slash], &rest[slash..]),
+            None => (rest, "/"),
+        };
+        let (host, address) = match authority.rsplit_once(':') {
+            Some((host, port)) if port.parse::<u16>().is_ok() => (host, authority.to_string()),
+            _ => (authority, format!("{}:80", authority)),
+        };
+        let request_error = |e: ::std::io::Error| format!("错误: 请求 {} 失败: {}", url, e);
+        let socket_address = address.to_socket_addrs()
+            .map_err(request_error)?
+            .next()
+            .ok_or_else(|| format!("错误: 无法解析主机: {}", host))?;
+        let mut stream = TcpStream::connect_timeout(&socket_address, timeout).map_err(request_error)?;
+        stream.set_read_timeout(Some(timeout)).map_err(request_error)?;
+        stream.set_write_timeout(Some(timeout)).map_err(request_error)?;
+        // HTTP/1.0 的响应不会使用分块编码，读到连接关闭即可
+        let request = format!("GET {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: CodeNothing\r\nAccept: text/plain\r\n\r\n", path, host);
+        stream.write_all(request.as_bytes()).map_err(request_error)?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).map_err(request_error)?;
+        let response = String::from_utf8_lossy(&response);
+        let (head, body) = response.split_once("\r\n\r\n").ok_or_else(|| format!("错误: 无效的HTTP响应: {}", url))?;
+        let status = head.lines().next().unwrap_or("");
+        if status.split_whitespace().nth(1) != Some("200") {
+            return Err(format!("错误: 请求 {} 失败: {}", url, status));
+        }
+        Ok(body.to_string())
+    }
+    
+    fn tcp_ping(host: &str, port: u16, count: u64, timeout: Duration) -> Result<Vec<f64>, String> {
+        let address = (host, port).to_socket_addrs()
+            .map_err(|e| format!("错误: 无法解析主机 {}: {}", host, e))?
+            .next()
+            .ok_or_else(|| format!("错误: 无法解析主机: {}", host))?;
+        let mut times = Vec::new();
+        for attempt in 0..count {
+            if attempt > 0 {
+                thread::sleep(Duration::from_millis(200));
+            }
+            let started = Instant::now();
+            if TcpStream::connect_timeout(&address, timeout).is_ok() {
+                times.push(started.elapsed().as_secs_f64() * 1000.0);
+            }
+        }
+        Ok(times)
+    }
+    
+    fn icmp_ping(host: &str, count: u64, timeout_ms: u64) -> Result<Vec<f64>, String> {
+        let count_text = count.to_string();
+        #[cfg(windows)]
+        let ping_args = vec!["-n".to_string(), count_text, "-w".to_string(), timeout_ms.to_string(), host.to_string()];
+        // macOS 的 -W 单位是毫秒
+        #[cfg(target_os = "macos")]
+        let ping_args = vec!["-c".to_string(), count_text, "-W".to_string(), timeout_ms.to_string(), host.to_string()];
+        // Linux 的 -W 单位是秒
+        #[cfg(not(any(windows, target_os = "macos")))]
+        let ping_args = vec!["-c".to_string(), count_text, "-W".to_string(), ((timeout_ms + 999) / 1000).to_string(), host.to_string()];
+        
+        // 每次最多等待 timeout 再加上发送间隔，防止命令挂起
+        let mut options = serde_json::Map::new();
+        options.insert("timeout".to_string(), JsonValue::from(count * (timeout_ms + 1000) + 1000));
+        let result = run_command("ping", &ping_args, &options)?;
+        let output = result["stdout"].as_str().unwrap_or("");
+        let times: Vec<f64> = output.lines().filter_map(reply_time).collect();
+        if times.is_empty() && result["code"].as_i64().map_or(true, |code| code > 1) {
+            let stderr = result["stderr"].as_str().unwrap_or("").trim();
+            return Err(format!("错误: ping 失败: {}", if stderr.is_empty() { output.trim() } else { stderr }));
+        }
+        Ok(times)
+    }
+    
+    // 从 ping 的一行输出中取出响应时间，如 "time=12.3 ms"、"time<1ms"、"时间=5ms"
+    fn reply_time(line: &str) -> Option<f64> {
+        let start = ["time=", "time<", "时间=", "时间<"].iter().find_map(|marker| line.find(marker).map(|i| i + marker.len()))?;
+        let number: String = line[start..].chars().take_while(|c| c.is_ascii_digit() || *c == '.').collect();
+        number.parse().ok()
+    }
+    
     // 获取进程列表
     pub fn cn_processes(_args: Vec<String>) -> String {
         let mut system = System::new_all();
@@ -168,6 +873,150 @@ mod std {
         result
     }
     
+    // 获取进程列表，返回按PID排列的数组，每个元素为 {"pid", "parent", "name", "exe", "cmd", "status",
+    // "memory", "virtual_memory", "cpu_usage", "start_time", "run_time", "user"}
+    // 内存单位为字节，start_time 是时间戳（秒），run_time 是运行秒数；CPU使用率需要两次采样，调用会等待约200毫秒
+    pub fn cn_processes_json(_args: Vec<String>) -> String {
+        let mut system = System::new_all();
+        ::std::thread::sleep(System::MINIMUM_CPU_UPDATE_INTERVAL);
+        system.refresh_processes();
+        
+        let mut processes: Vec<_> = system.processes().values().collect();
+        processes.sort_by_key(|process| process.pid());
+        let processes: Vec<JsonValue> = processes.into_iter().map(|process| process_json(process, &system)).collect();
+        json_value(&JsonValue::Array(processes).to_string())
+    }
+    
+    // 获取单个进程的信息，字段与 processes_json 的元素相同；进程不存在时返回错误
+    // CPU使用率需要两次采样，调用会等待约200毫秒
+    // 参数: pid
+    pub fn cn_process_info(args: Vec<String>) -> String {
+        let pid = match parse_pid(args.first()) {
+            Ok(pid) => pid,
+            Err(e) => return e,
+        };
+        let mut system = System::new();
+        if !system.refresh_process(pid) {
+            return format!("错误: 进程不存在: {}", pid);
+        }
+        ::std::thread::sleep(System::MINIMUM_CPU_UPDATE_INTERVAL);
+        system.refresh_process(pid);
+        system.refresh_users_list();
+        match system.process(pid) {
+            Some(process) => json_value(&process_json(process, &system).to_string()),
+            None => format!("错误: 进程不存在: {}", pid),
+        }
+    }
+    
+    // 检查进程是否存在；已经退出但还没有被父进程回收的僵尸进程视为不存在
+    // 参数: pid
+    pub fn cn_process_exists(args: Vec<String>) -> String {
+        match parse_pid(args.first()) {
+            Ok(pid) => process_alive(&mut System::new(), pid).to_string(),
+            Err(e) => e,
+        }
+    }
+    
+    // 向进程发送信号，成功返回 true，进程不存在时返回 false
+    // 支持的信号: TERM、KILL、INT、HUP、QUIT、USR1、USR2、STOP、CONT、ALRM、ABRT（可以带 SIG 前缀）；
+    // Windows 上只支持 KILL，默认的 TERM 也按 KILL 处理
+    // 参数: pid, [signal]（默认为 TERM）
+    pub fn cn_kill(args: Vec<String>) -> String {
+        let pid = match parse_pid(args.first()) {
+            Ok(pid) => pid,
+            Err(e) => return e,
+        };
+        let name = args.get(1).map(|s| s.trim().to_uppercase()).filter(|s| !s.is_empty()).unwrap_or_else(|| "TERM".to_string());
+        let signal = match name.strip_prefix("SIG").unwrap_or(&name) {
+            "TERM" => sysinfo::Signal::Term,
+            "KILL" => sysinfo::Signal::Kill,
+            "INT" => sysinfo::Signal::Interrupt,
+            "HUP" => sysinfo::Signal::Hangup,
+            "QUIT" => sysinfo::Signal::Quit,
+            "USR1" => sysinfo::Signal::User1,
+            "USR2" => sysinfo::Signal::User2,
+            "STOP" => sysinfo::Signal::Stop,
+            "CONT" => sysinfo::Signal::Continue,
+            "ALRM" => sysinfo::Signal::Alarm,
+            "ABRT" => sysinfo::Signal::Abort,
+            _ => return format!("错误: 不支持的信号: {}", args[1]),
+        };
+        let mut system = System::new();
+        if !system.refresh_process(pid) {
+            return "false".to_string();
+        }
+        let process = match system.process(pid) {
+            Some(process) => process,
+            None => return "false".to_string(),
+        };
+        let sent = match process.kill_with(signal) {
+            Some(sent) => sent,
+            None if cfg!(windows) && signal == sysinfo::Signal::Term => process.kill(),
+            None => return format!("错误: 当前系统不支持信号: {}", name),
+        };
+        if sent {
+            "true".to_string()
+        } else {
+            format!("错误: 无法向进程 {} 发送信号 {}", pid, name)
+        }
+    }
+    
+    // 等待进程退出，退出返回 true，超时返回 false；可以等待任意进程，不要求是当前进程启动的
+    // 参数: pid, [timeout]（毫秒，默认一直等待）
+    pub fn cn_wait_for_exit(args: Vec<String>) -> String {
+        let pid = match parse_pid(args.first()) {
+            Ok(pid) => pid,
+            Err(e) => return e,
+        };
+        let deadline = match args.get(1).map(|t| t.trim().parse::<u64>()) {
+            Some(Ok(ms)) => Some(Instant::now() + Duration::from_millis(ms)),
+            None => None,
+            Some(Err(_)) => return format!("错误: 无效的毫秒数: {}", args[1]),
+        };
+        let mut system = System::new();
+        while process_alive(&mut system, pid) {
+            let remaining = match deadline {
+                Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+                None => Duration::from_millis(50),
+            };
+            if remaining.is_zero() {
+                return "false".to_string();
+            }
+            thread::sleep(remaining.min(Duration::from_millis(50)));
+        }
+        "true".to_string()
+    }
+    
+    fn parse_pid(arg: Option<&String>) -> Result<sysinfo::Pid, String> {
+        match arg.map(|pid| pid.trim().parse::<u32>()) {
+            Some(Ok(pid)) => Ok(sysinfo::Pid::from_u32(pid)),
+            Some(Err(_)) => Err(format!("错误: 无效的进程ID: {}", arg.map(String::as_str).unwrap_or(""))),
+            None => Err("错误: 缺少进程ID参数".to_string()),
+        }
+    }
+    
+    fn process_alive(system: &mut System, pid: sysinfo::Pid) -> bool {
+        system.refresh_process(pid)
+            && system.process(pid).is_some_and(|process| process.status() != sysinfo::ProcessStatus::Zombie)
+    }
+    
+    fn process_json(process: &sysinfo::Process, system: &System) -> JsonValue {
+        json!({
+            "pid": process.pid().as_u32(),
+            "parent": process.parent().map(|pid| pid.as_u32()),
+            "name": process.name(),
+            "exe": process.exe().to_string_lossy(),
+            "cmd": process.cmd(),
+            "status": process.status().to_string(),
+            "memory": process.memory(),
+            "virtual_memory": process.virtual_memory(),
+            "cpu_usage": process.cpu_usage(),
+            "start_time": process.start_time(),
+            "run_time": process.run_time(),
+            "user": process.user_id().and_then(|uid| system.get_user_by_id(uid)).map(|user| user.name()),
+        })
+    }
+    
     // 获取系统启动时间（秒）
     pub fn cn_uptime(_args: Vec<String>) -> String {
         let mut system = System::new_all();
@@ -212,9 +1061,672 @@ mod std {
     }
 }
 
+// 信号处理（注册为 std）
+// 收到信号时只记录下来，处理函数在脚本调用 dispatch_signals 或 wait_signal 时执行，
+// 长时间运行的脚本应在循环中定期调用 dispatch_signals；分发前多次收到的同一信号只执行一次处理函数
+// 注册了处理函数后信号不再终止进程，off_signal 之后恢复为终止进程（退出码为 128 加信号编号）
+mod signal {
+    use super::*;
+    use ::std::collections::{BTreeMap, VecDeque};
+    use ::std::sync::atomic::{AtomicU64, Ordering};
+    use ::std::sync::{Mutex, MutexGuard};
+    use cn_common::value::{callback_value, thrown_message};
+    use signal_hook::consts::signal::*;
+    use signal_hook::low_level;
+    
+    // 处理函数返回后，解释器调用的续接函数
+    const RESUME_FUNCTION: &str = "std::signal_resume";
+    
+    // 支持的信号：名称、编号、默认是否终止进程
+    #[cfg(not(windows))]
+    const SIGNALS: &[(&str, i32, bool)] = &[
+        ("SIGINT", SIGINT, true),
+        ("SIGTERM", SIGTERM, true),
+        ("SIGHUP", SIGHUP, true),
+        ("SIGQUIT", SIGQUIT, true),
+        ("SIGUSR1", SIGUSR1, true),
+        ("SIGUSR2", SIGUSR2, true),
+        ("SIGALRM", SIGALRM, true),
+        ("SIGWINCH", SIGWINCH, false),
+        ("SIGCHLD", SIGCHLD, false),
+        ("SIGCONT", SIGCONT, false),
+    ];
+    
+    #[cfg(windows)]
+    const SIGNALS: &[(&str, i32, bool)] = &[
+        ("SIGINT", SIGINT, true),
+        ("SIGTERM", SIGTERM, true),
+        ("SIGBREAK", SIGBREAK, true),
+    ];
+    
+    // 收到但还没有分发的信号，第n位对应编号为n的信号；信号处理程序中只能使用原子操作
+    static PENDING: AtomicU64 = AtomicU64::new(0);
+    // 脚本注册了处理函数的信号
+    static HANDLED: AtomicU64 = AtomicU64::new(0);
+    
+    struct Registry {
+        handlers: BTreeMap<i32, String>,
+        // 已经安装了系统信号处理程序的信号，安装后不再卸载
+        installed: u64,
+    }
+    
+    // 正在进行的分发，处理函数中可以再调用 dispatch_signals
+    struct Dispatch {
+        queue: VecDeque<(&'static str, String)>,
+        count: u64,
+    }
+    
+    static REGISTRY: Mutex<Registry> = Mutex::new(Registry { handlers: BTreeMap::new(), installed: 0 });
+    static DISPATCHES: Mutex<Vec<Dispatch>> = Mutex::new(Vec::new());
+    
+    fn lock_registry() -> MutexGuard<'static, Registry> {
+        REGISTRY.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    
+    fn lock_dispatches() -> MutexGuard<'static, Vec<Dispatch>> {
+        DISPATCHES.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    
+    // 注册信号处理函数 handler(name)，替换之前注册的处理函数
+    // 参数: name（如 "SIGINT"，也可以省略 SIG 前缀）, handler
+    pub fn cn_on_signal(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 需要两个参数 (信号名, 处理函数)".to_string();
+        }
+        let (_, signal, terminates) = match find_signal(&args[0]) {
+            Ok(found) => found,
+            Err(e) => return e,
+        };
+        let bit = 1u64 << signal;
+        let mut registry = lock_registry();
+        if registry.installed & bit == 0 {
+            let action = move || {
+                if HANDLED.load(Ordering::SeqCst) & bit != 0 {
+                    PENDING.fetch_or(bit, Ordering::SeqCst);
+                } else if terminates {
+                    low_level::exit(128 + signal);
+                }
+            };
+            // SAFETY: 处理程序只使用原子操作和 _exit，它们在信号处理程序中都是安全的
+            if let Err(e) = unsafe { low_level::register(signal, action) } {
+                return format!("错误: 无法注册信号 {}: {}", args[0], e);
+            }
+            registry.installed |= bit;
+        }
+        registry.handlers.insert(signal, args[1].clone());
+        HANDLED.fetch_or(bit, Ordering::SeqCst);
+        "true".to_string()
+    }
+    
+    // 取消信号处理函数，信号恢复默认行为；没有注册过时返回 false
+    // 参数: name
+    pub fn cn_off_signal(args: Vec<String>) -> String {
+        let (_, signal, _) = match args.first().map(|name| find_signal(name)) {
+            Some(Ok(found)) => found,
+            Some(Err(e)) => return e,
+            None => return "错误: 缺少信号名参数".to_string(),
+        };
+        let bit = 1u64 << signal;
+        HANDLED.fetch_and(!bit, Ordering::SeqCst);
+        PENDING.fetch_and(!bit, Ordering::SeqCst);
+        lock_registry().handlers.remove(&signal).is_some().to_string()
+    }
+    
+    // 向当前进程发送信号；没有注册处理函数时按信号的默认行为处理，可能终止进程
+    // 参数: name
+    pub fn cn_raise_signal(args: Vec<String>) -> String {
+        let (_, signal, _) = match args.first().map(|name| find_signal(name)) {
+            Some(Ok(found)) => found,
+            Some(Err(e)) => return e,
+            None => return "错误: 缺少信号名参数".to_string(),
+        };
+        match low_level::raise(signal) {
+            Ok(_) => "true".to_string(),
+            Err(e) => format!("错误: 无法发送信号: {}", e),
+        }
+    }
+    
+    // 执行已收到信号的处理函数，返回执行的处理函数个数；没有收到信号时立即返回0
+    // 参数: 无
+    pub fn cn_dispatch_signals(_args: Vec<String>) -> String {
+        start_dispatch()
+    }
+    
+    // 等待信号并执行处理函数，返回执行的处理函数个数，超时返回0
+    // 参数: [timeout]（毫秒，默认一直等待）
+    pub fn cn_wait_signal(args: Vec<String>) -> String {
+        let deadline = match args.first().map(|t| t.trim().parse::<u64>()) {
+            Some(Ok(ms)) => Some(Instant::now() + Duration::from_millis(ms)),
+            None => None,
+            Some(Err(_)) => return format!("错误: 无效的毫秒数: {}", args[0]),
+        };
+        while PENDING.load(Ordering::SeqCst) == 0 {
+            if HANDLED.load(Ordering::SeqCst) == 0 {
+                return "错误: 没有注册信号处理函数".to_string();
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return "0".to_string();
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        start_dispatch()
+    }
+    
+    // 处理函数返回后由解释器调用，继续执行下一个处理函数；以续接函数注册，脚本无法直接调用
+    // 参数: returned
+    pub fn cn_signal_resume(args: Vec<String>) -> String {
+        let mut dispatches = lock_dispatches();
+        if let Some(message) = args.first().and_then(|returned| thrown_message(returned)) {
+            dispatches.pop();
+            return format!("错误: 信号处理函数抛出异常: {}", message);
+        }
+        next_handler(dispatches)
+    }
+    
+    fn start_dispatch() -> String {
+        let pending = PENDING.swap(0, Ordering::SeqCst);
+        let queue: VecDeque<(&'static str, String)> = {
+            let registry = lock_registry();
+            SIGNALS.iter()
+                .filter(|(_, signal, _)| pending & (1u64 << signal) != 0)
+                .filter_map(|(name, signal, _)| registry.handlers.get(signal).map(|handler| (*name, handler.clone())))
+                .collect()
+        };
+        if queue.is_empty() {
+            return "0".to_string();
+        }
+        let mut dispatches = lock_dispatches();
+        dispatches.push(Dispatch { queue, count: 0 });
+        next_handler(dispatches)
+    }
+    
+    fn next_handler(mut dispatches: MutexGuard<'static, Vec<Dispatch>>) -> String {
+        let dispatch = match dispatches.last_mut() {
+            Some(dispatch) => dispatch,
+            None => return "错误: 没有正在进行的信号分发".to_string(),
+        };
+        match dispatch.queue.pop_front() {
+            Some((name, handler)) => {
+                dispatch.count += 1;
+                let args_json = JsonValue::Array(vec![JsonValue::from(name)]).to_string();
+                callback_value(&handler, &args_json, RESUME_FUNCTION)
+            },
+            None => {
+                let dispatch = dispatches.pop().expect("分发存在");
+                dispatch.count.to_string()
+            },
+        }
+    }
+    
+    fn find_signal(name: &str) -> Result<(&'static str, i32, bool), String> {
+        let upper = name.trim().to_uppercase();
+        let full = if upper.starts_with("SIG") { upper } else { format!("SIG{}", upper) };
+        SIGNALS.iter()
+            .find(|(signal_name, _, _)| *signal_name == full)
+            .copied()
+            .ok_or_else(|| {
+                let names: Vec<&str> = SIGNALS.iter().map(|(name, _, _)| *name).collect();
+                format!("错误: 不支持的信号: {}，支持 {}", name, names.join("、"))
+            })
+    }
+}
+
+// 用户、用户组和权限（注册为 std）
+// Unix 上直接查询系统的用户数据库（/etc/passwd、/etc/group 以及 NSS 配置的其他来源）；
+// Windows 上没有数字形式的 uid 和 gid，uid、gid 返回错误，其余函数只提供用户名和用户组名
+mod user {
+    use super::*;
+    
+    // 获取当前进程的实际用户ID
+    // 参数: 无
+    pub fn cn_uid(_args: Vec<String>) -> String {
+        #[cfg(unix)]
+        {
+            // SAFETY: getuid 总是成功，没有副作用
+            unsafe { libc::getuid() }.to_string()
+        }
+        #[cfg(not(unix))]
+        {
+            "错误: 当前系统不支持 uid".to_string()
+        }
+    }
+    
+    // 获取当前进程的实际用户组ID
+    // 参数: 无
+    pub fn cn_gid(_args: Vec<String>) -> String {
+        #[cfg(unix)]
+        {
+            // SAFETY: getgid 总是成功，没有副作用
+            unsafe { libc::getgid() }.to_string()
+        }
+        #[cfg(not(unix))]
+        {
+            "错误: 当前系统不支持 gid".to_string()
+        }
+    }
+    
+    // 获取当前进程所属的用户组，返回数组，每个元素为 {"gid", "name"}（Windows 上只有 "name"）
+    // 参数: 无
+    pub fn cn_groups(_args: Vec<String>) -> String {
+        match process_groups() {
+            Ok(groups) => json_value(&JsonValue::Array(groups).to_string()),
+            Err(e) => e,
+        }
+    }
+    
+    // 检查当前进程是否以 root 身份运行（有效用户ID为0）；Windows 上与 is_admin 相同
+    // 参数: 无
+    pub fn cn_is_root(_args: Vec<String>) -> String {
+        #[cfg(unix)]
+        {
+            // SAFETY: geteuid 总是成功，没有副作用
+            (unsafe { libc::geteuid() } == 0).to_string()
+        }
+        #[cfg(not(unix))]
+        {
+            cn_is_admin(Vec::new())
+        }
+    }
+    
+    // 检查当前进程是否拥有管理员权限：Unix 上为 root，Windows 上为提升权限运行的管理员
+    // 参数: 无
+    pub fn cn_is_admin(_args: Vec<String>) -> String {
+        #[cfg(unix)]
+        {
+            cn_is_root(Vec::new())
+        }
+        #[cfg(not(unix))]
+        {
+            // net session 只有在提升权限后才能成功
+            let status = Command::new("net")
+                .arg("session")
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+            status.map(|status| status.success()).unwrap_or(false).to_string()
+        }
+    }
+    
+    // 查询用户信息，返回 {"name", "uid", "gid", "full_name", "home", "shell", "groups"}，用户不存在时返回错误
+    // groups 的元素为 {"gid", "name"}；Windows 上只有 "name" 和 "groups"，groups 为用户组名数组
+    // 参数: [name]（用户名，Unix 上也可以是数字形式的uid，默认为当前有效用户）
+    pub fn cn_user_info(args: Vec<String>) -> String {
+        let name = args.first().map(|n| n.trim()).filter(|n| !n.is_empty());
+        match user_info(name) {
+            Ok(info) => json_value(&info.to_string()),
+            Err(e) => e,
+        }
+    }
+    
+    // 获取当前进程的有效用户，返回 {"name", "uid", "gid"}；使用 sudo 或 setuid 运行时与登录用户不同
+    // Windows 上只有 "name"
+    // 参数: 无
+    pub fn cn_effective_user(_args: Vec<String>) -> String {
+        #[cfg(unix)]
+        {
+            // SAFETY: geteuid 和 getegid 总是成功，没有副作用
+            let (uid, gid) = unsafe { (libc::geteuid(), libc::getegid()) };
+            let name = match unix::user_by_uid(uid) {
+                Ok(Some(user)) => JsonValue::String(user.name),
+                Ok(None) => JsonValue::Null,
+                Err(e) => return e,
+            };
+            json_value(&json!({ "name": name, "uid": uid, "gid": gid }).to_string())
+        }
+        #[cfg(not(unix))]
+        {
+            json_value(&json!({ "name": std::cn_username(Vec::new()) }).to_string())
+        }
+    }
+    
+    #[cfg(unix)]
+    fn process_groups() -> Result<Vec<JsonValue>, String> {
+        // SAFETY: 第一次调用只返回数量；第二次调用的缓冲区大小与传入的数量一致
+        let mut gids: Vec<libc::gid_t> = unsafe {
+            let count = libc::getgroups(0, ::std::ptr::null_mut());
+            if count < 0 {
+                return Err(format!("错误: 无法获取用户组: {}", ::std::io::Error::last_os_error()));
+            }
+            let mut gids = vec![0; count as usize];
+            let count = libc::getgroups(count, gids.as_mut_ptr());
+            if count < 0 {
+                return Err(format!("错误: 无法获取用户组: {}", ::std::io::Error::last_os_error()));
+            }
+            gids.truncate(count as usize);
+            gids
+        };
+        // 附加组列表不一定包含有效用户组
+        // SAFETY: getegid 总是成功，没有副作用
+        let egid = unsafe { libc::getegid() };
+        if !gids.contains(&egid) {
+            gids.insert(0, egid);
+        }
+        gids.into_iter().map(unix::group_json).collect()
+    }
+    
+    #[cfg(not(unix))]
+    fn process_groups() -> Result<Vec<JsonValue>, String> {
+        let name = std::cn_username(Vec::new());
+        let info = user_info(Some(&name))?;
+        let groups = info["groups"].as_array().cloned().unwrap_or_default();
+        Ok(groups.into_iter().map(|group| json!({ "name": group })).collect())
+    }
+    
+    #[cfg(unix)]
+    fn user_info(name: Option<&str>) -> Result<JsonValue, String> {
+        let user = match name {
+            Some(name) => match unix::user_by_name(name)? {
+                Some(user) => Some(user),
+                None => match name.parse::<libc::uid_t>() {
+                    Ok(uid) => unix::user_by_uid(uid)?,
+                    Err(_) => None,
+                },
+            },
+            // SAFETY: geteuid 总是成功，没有副作用
+            None => unix::user_by_uid(unsafe { libc::geteuid() })?,
+        };
+        let user = user.ok_or_else(|| format!("错误: 用户不存在: {}", name.unwrap_or("")))?;
+        let groups = unix::user_groups(&user.name, user.gid)?
+            .into_iter()
+            .map(unix::group_json)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(json!({
+            "name": user.name,
+            "uid": user.uid,
+            "gid": user.gid,
+            // GECOS 字段的第一项是全名，其余是电话等附加信息
+            "full_name": user.gecos.split(',').next().unwrap_or(""),
+            "home": user.home,
+            "shell": user.shell,
+            "groups": groups,
+        }))
+    }
+    
+    #[cfg(not(unix))]
+    fn user_info(name: Option<&str>) -> Result<JsonValue, String> {
+        let name = name.map(String::from).unwrap_or_else(|| std::cn_username(Vec::new()));
+        let mut system = System::new();
+        system.refresh_users_list();
+        let user = system.users()
+            .iter()
+            .find(|user| user.name().eq_ignore_ascii_case(&name))
+            .ok_or_else(|| format!("错误: 用户不存在: {}", name))?;
+        Ok(json!({ "name": user.name(), "groups": user.groups() }))
+    }
+    
+    #[cfg(unix)]
+    mod unix {
+        use super::*;
+        use ::std::ffi::CString;
+        
+        pub(super) use cn_common::users::{group_name, user_by_name, user_by_uid};
+        
+        pub(super) fn group_json(gid: libc::gid_t) -> Result<JsonValue, String> {
+            Ok(json!({ "gid": gid, "name": group_name(gid)? }))
+        }
+        
+        // 查询用户所属的全部用户组，包括主用户组
+        pub(super) fn user_groups(name: &str, gid: libc::gid_t) -> Result<Vec<libc::gid_t>, String> {
+            let name = CString::new(name).map_err(|_| format!("错误: 无效的用户名: {}", name))?;
+            // macOS 的 getgrouplist 使用 int 类型的组ID
+            #[cfg(target_os = "macos")]
+            type GroupId = libc::c_int;
+            #[cfg(not(target_os = "macos"))]
+            type GroupId = libc::gid_t;
+            
+            let mut groups: Vec<GroupId> = vec![0; 64];
+            loop {
+                let mut count = groups.len() as libc::c_int;
+                // SAFETY: count 是缓冲区的长度，返回后是实际的组数
+                let found = unsafe { libc::getgrouplist(name.as_ptr(), gid as GroupId, groups.as_mut_ptr(), &mut count) };
+                if found >= 0 {
+                    groups.truncate(count.max(0) as usize);
+                    return Ok(groups.into_iter().map(|g| g as libc::gid_t).collect());
+                }
+                // 缓冲区不够时 count 是需要的长度（部分系统不返回，此时加倍）
+                if groups.len() >= 1 << 16 {
+                    return Err("错误: 用户组过多".to_string());
+                }
+                let needed = (count.max(0) as usize).max(groups.len() * 2);
+                groups.resize(needed, 0);
+            }
+        }
+    }
+}
+
+// 电源和会话（注册为 std）
+// sleep_system 和 shutdown 会影响整台机器，只有在环境变量 CN_ALLOW_POWER 为 1 或 true 时才可用，
+// 开关在库加载时读取，脚本之后用 env_set 修改环境变量不会生效；执行时通常还需要管理员权限
+mod power {
+    use super::*;
+    use ::std::sync::OnceLock;
+    
+    const POWER_FLAG: &str = "CN_ALLOW_POWER";
+    static POWER_ALLOWED: OnceLock<bool> = OnceLock::new();
+    
+    // 读取电源操作开关，只在库加载时调用一次
+    pub fn load_power_flag() {
+        POWER_ALLOWED.get_or_init(|| match env::var(POWER_FLAG) {
+            Ok(flag) => flag == "1" || flag.eq_ignore_ascii_case("true"),
+            Err(_) => false,
+        });
+    }
+    
+    // 获取电池状态，返回 {"present", "percentage", "charging"}；没有电池时 present 为 false，其余为 null
+    // 参数: 无
+    pub fn cn_battery(_args: Vec<String>) -> String {
+        let (percentage, charging) = match battery_status() {
+            Ok(Some(status)) => status,
+            Ok(None) => return json_value(&json!({ "present": false, "percentage": null, "charging": null }).to_string()),
+            Err(e) => return e,
+        };
+        json_value(&json!({ "present": true, "percentage": percentage, "charging": charging }).to_string())
+    }
+    
+    // 获取系统启动信息，返回 {"boot_time"（启动时间戳，秒）, "uptime"（已运行秒数）}
+    // 参数: 无
+    pub fn cn_uptime_boot_time(_args: Vec<String>) -> String {
+        let system = System::new();
+        json_value(&json!({ "boot_time": system.boot_time(), "uptime": system.uptime() }).to_string())
+    }
+    
+    // 获取已登录的用户会话，返回数组，每个元素为 {"name", "terminal", "host", "login_time"（时间戳，秒）}；
+    // 同一用户的每个会话各占一项。Windows 上只有 "name" 和 "terminal"
+    // 参数: 无
+    pub fn cn_logged_in_users(_args: Vec<String>) -> String {
+        match sessions() {
+            Ok(sessions) => json_value(&JsonValue::Array(sessions).to_string()),
+            Err(e) => e,
+        }
+    }
+    
+    // 使系统进入睡眠状态，成功发出请求时返回 true
+    // 参数: 无
+    pub fn cn_sleep_system(_args: Vec<String>) -> String {
+        if let Err(e) = check_allowed() {
+            return e;
+        }
+        #[cfg(windows)]
+        let command = ("rundll32.exe", vec!["powrprof.dll,SetSuspendState", "0,1,0"]);
+        #[cfg(target_os = "macos")]
+        let command = ("pmset", vec!["sleepnow"]);
+        #[cfg(not(any(windows, target_os = "macos")))]
+        let command = ("systemctl", vec!["suspend"]);
+        run_power_command(command.0, &command.1)
+    }
+    
+    // 立即关闭系统；confirm 必须为 true，防止误调用
+    // 参数: confirm
+    pub fn cn_shutdown(args: Vec<String>) -> String {
+        if let Err(e) = check_allowed() {
+            return e;
+        }
+        if args.first().map(|c| c.trim()) != Some("true") {
+            return "错误: 关机需要传入 true 确认".to_string();
+        }
+        #[cfg(windows)]
+        let command = ("shutdown", vec!["/s", "/t", "0"]);
+        #[cfg(not(windows))]
+        let command = ("shutdown", vec!["-h", "now"]);
+        run_power_command(command.0, &command.1)
+    }
+    
+    fn check_allowed() -> Result<(), String> {
+        if POWER_ALLOWED.get().copied().unwrap_or(false) {
+            Ok(())
+        } else {
+            Err(format!("错误: 电源操作未启用，需要在启动前设置环境变量 {}=1", POWER_FLAG))
+        }
+    }
+    
+    fn run_power_command(program: &str, args: &[&str]) -> String {
+        let output = match Command::new(program).args(args).stdin(Stdio::null()).output() {
+            Ok(output) => output,
+            Err(e) => return format!("错误: 无法执行 {}: {}", program, e),
+        };
+        if output.status.success() {
+            "true".to_string()
+        } else {
+            format!("错误: {} 执行失败: {}", program, String::from_utf8_lossy(&output.stderr).trim())
+        }
+    }
+    
+    // (电量百分比, 是否正在充电)，无法获取的项为 None
+    type BatteryStatus = (Option<u64>, Option<bool>);
+    
+    // 没有电池时返回 None
+    #[cfg(target_os = "linux")]
+    fn battery_status() -> Result<Option<BatteryStatus>, String> {
+        let entries = match ::std::fs::read_dir("/sys/class/power_supply") {
+            Ok(entries) => entries,
+            Err(_) => return Ok(None),
+        };
+        let read = |path: &::std::path::Path, name: &str| {
+            ::std::fs::read_to_string(path.join(name)).ok().map(|value| value.trim().to_string())
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if read(&path, "type").as_deref() != Some("Battery") || read(&path, "present").as_deref() == Some("0") {
+                continue;
+            }
+            let percentage = read(&path, "capacity").and_then(|capacity| capacity.parse().ok());
+            let charging = read(&path, "status").map(|status| status == "Charging");
+            return Ok(Some((percentage, charging)));
+        }
+        Ok(None)
+    }
+    
+    #[cfg(target_os = "macos")]
+    fn battery_status() -> Result<Option<BatteryStatus>, String> {
+        // 输出形如 " -InternalBattery-0 (id=123)	85%; charging; 1:05 remaining present: true"
+        let output = Command::new("pmset").args(["-g", "batt"]).output()
+            .map_err(|e| format!("错误: 无法执行 pmset: {}", e))?;
+        let output = String::from_utf8_lossy(&output.stdout);
+        let line = match output.lines().find(|line| line.contains("InternalBattery")) {
+            Some(line) => line,
+            None => return Ok(None),
+        };
+        let mut fields = line.split('\t').nth(1).unwrap_or("").split(';').map(str::trim);
+        let percentage = fields.next().and_then(|p| p.trim_end_matches('%').parse().ok());
+        let charging = fields.next().map(|state| state == "charging");
+        Ok(Some((percentage, charging)))
+    }
+    
+    #[cfg(windows)]
+    fn battery_status() -> Result<Option<BatteryStatus>, String> {
+        #[repr(C)]
+        #[derive(Default)]
+        struct SystemPowerStatus {
+            ac_line_status: u8,
+            battery_flag: u8,
+            battery_life_percent: u8,
+            system_status_flag: u8,
+            battery_life_time: u32,
+            battery_full_life_time: u32,
+        }
+        
+        #[link(name = "kernel32")]
+        extern "system" {
+            fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+        }
+        
+        let mut status = SystemPowerStatus::default();
+        // SAFETY: 输出指针指向本函数中布局相同的结构体
+        if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+            return Err(format!("错误: 无法获取电池状态: {}", ::std::io::Error::last_os_error()));
+        }
+        // 128 表示没有电池，255 表示状态未知
+        if status.battery_flag & 128 != 0 {
+            return Ok(None);
+        }
+        let percentage = (status.battery_life_percent != 255).then_some(status.battery_life_percent as u64);
+        let charging = (status.battery_flag != 255).then_some(status.battery_flag & 8 != 0);
+        Ok(Some((percentage, charging)))
+    }
+    
+    #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+    fn battery_status() -> Result<Option<BatteryStatus>, String> {
+        Ok(None)
+    }
+    
+    #[cfg(unix)]
+    fn sessions() -> Result<Vec<JsonValue>, String> {
+        use ::std::os::raw::c_char;
+        
+        // utmpx 的字符串字段不一定以 NUL 结尾
+        fn field(chars: &[c_char]) -> String {
+            let bytes: Vec<u8> = chars.iter().take_while(|c| **c != 0).map(|c| *c as u8).collect();
+            String::from_utf8_lossy(&bytes).into_owned()
+        }
+        
+        let mut sessions = Vec::new();
+        // SAFETY: getutxent 返回的指针在下一次调用前有效，每条记录在下一次调用前就被复制出来
+        unsafe {
+            libc::setutxent();
+            loop {
+                let entry = libc::getutxent();
+                if entry.is_null() {
+                    break;
+                }
+                let entry = &*entry;
+                if entry.ut_type != libc::USER_PROCESS {
+                    continue;
+                }
+                let host = field(&entry.ut_host);
+                sessions.push(json!({
+                    "name": field(&entry.ut_user),
+                    "terminal": field(&entry.ut_line),
+                    "host": if host.is_empty() { None } else { Some(host) },
+                    "login_time": entry.ut_tv.tv_sec as i64,
+                }));
+            }
+            libc::endutxent();
+        }
+        Ok(sessions)
+    }
+    
+    #[cfg(not(unix))]
+    fn sessions() -> Result<Vec<JsonValue>, String> {
+        // query user 的输出第一行是表头，当前会话以 > 开头
+        let output = Command::new("query").arg("user").output()
+            .map_err(|e| format!("错误: 无法执行 query user: {}", e))?;
+        let output = String::from_utf8_lossy(&output.stdout);
+        let sessions = output.lines().skip(1).filter_map(|line| {
+            let mut columns = line.trim_start_matches(['>', ' ']).split_whitespace();
+            let name = columns.next()?;
+            Some(json!({ "name": name, "terminal": columns.next() }))
+        }).collect();
+        Ok(sessions)
+    }
+}
+
 // 初始化函数，返回函数映射
 #[no_mangle]
 pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 电源操作开关只在加载时读取，避免脚本自行打开
+    power::load_power_flag();
+    
     // 创建库函数注册器
     let mut registry = LibraryRegistry::new();
     
@@ -225,20 +1737,56 @@ pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
          .add_function("os_arch", std::cn_os_arch)
          .add_function("env", std::cn_env)
          .add_function("env_all", std::cn_env_all)
+         .add_function("env_set", std::cn_env_set)
+         .add_function("env_remove", std::cn_env_remove)
+         .add_function("env_expand", std::cn_env_expand)
+         .add_function("path_split", std::cn_path_split)
+         .add_function("path_join_env", std::cn_path_join_env)
          .add_function("cwd", std::cn_cwd)
          .add_function("home_dir", std::cn_home_dir)
          .add_function("temp_dir", std::cn_temp_dir)
          .add_function("hostname", std::cn_hostname)
          .add_function("memory", std::cn_memory)
+         .add_function("memory_json", std::cn_memory_json)
          .add_function("cpu_info", std::cn_cpu_info)
+         .add_function("cpu_info_json", std::cn_cpu_info_json)
          .add_function("disk_info", std::cn_disk_info)
+         .add_function("disk_info_json", std::cn_disk_info_json)
          .add_function("exec", std::cn_exec)
+         .add_function("exec_full", std::cn_exec_full)
+         .add_function("open", std::cn_open)
+         .add_function("net_interfaces", std::cn_net_interfaces)
+         .add_function("public_ip", std::cn_public_ip)
+         .add_function("ping", std::cn_ping)
          .add_function("processes", std::cn_processes)
+         .add_function("processes_json", std::cn_processes_json)
+         .add_function("process_info", std::cn_process_info)
+         .add_function("process_exists", std::cn_process_exists)
+         .add_function("kill", std::cn_kill)
+         .add_function("wait_for_exit", std::cn_wait_for_exit)
          .add_function("uptime", std::cn_uptime)
+         .add_function("uptime_boot_time", power::cn_uptime_boot_time)
+         .add_function("battery", power::cn_battery)
+         .add_function("logged_in_users", power::cn_logged_in_users)
+         .add_function("sleep_system", power::cn_sleep_system)
+         .add_function("shutdown", power::cn_shutdown)
          .add_function("username", std::cn_username)
+         .add_function("uid", user::cn_uid)
+         .add_function("gid", user::cn_gid)
+         .add_function("groups", user::cn_groups)
+         .add_function("is_root", user::cn_is_root)
+         .add_function("is_admin", user::cn_is_admin)
+         .add_function("user_info", user::cn_user_info)
+         .add_function("effective_user", user::cn_effective_user)
          .add_function("is_windows", std::cn_is_windows)
          .add_function("is_linux", std::cn_is_linux)
-         .add_function("is_macos", std::cn_is_macos);
+         .add_function("is_macos", std::cn_is_macos)
+         .add_retaining_function("on_signal", signal::cn_on_signal)
+         .add_function("off_signal", signal::cn_off_signal)
+         .add_function("raise_signal", signal::cn_raise_signal)
+         .add_function("dispatch_signals", signal::cn_dispatch_signals)
+         .add_function("wait_signal", signal::cn_wait_signal)
+         .add_continuation("signal_resume", signal::cn_signal_resume);
     
     // 同时注册为直接函数，不需要命名空间前缀
     registry.add_direct_function("os_name", std::cn_os_name)
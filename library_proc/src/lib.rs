@@ -0,0 +1,456 @@
+use ::std::collections::HashMap;
+use serde_json::{Map, Value as JsonValue};
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// 导入通用库
+use cn_common::handle::HandleTable;
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::{callback_value, json_value};
+
+// 进程命名空间
+// spawn 启动的进程在后台运行，标准输出和标准错误由后台线程按行读取并缓存，
+// 可以用 read_line 逐行读取，或用 stream 为每一行调用处理函数
+mod proc {
+    use super::*;
+
+    // stream 的处理函数返回后，解释器调用的续接函数
+    const RESUME_FUNCTION: &str = "proc::stream_resume";
+
+    // 等待进程退出时检查状态的间隔
+    const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    const STDOUT: usize = 0;
+    const STDERR: usize = 1;
+    const STREAM_NAMES: [&str; 2] = ["stdout", "stderr"];
+
+    // 读取线程发送的一行输出，None 表示该输出流已经结束
+    type Line = (usize, Option<String>);
+
+    struct Process {
+        child: Child,
+        stdin: Option<ChildStdin>,
+        lines: Receiver<Line>,
+        // 已经收到但还没有被读取的行
+        buffers: [VecDeque<String>; 2],
+        // 输出流是否可能还有数据
+        open: [bool; 2],
+        status: Option<ExitStatus>,
+    }
+
+    impl Process {
+        // 读取下一行，stream 为 None 时读取任意输出流；所有相关的输出流都结束时返回 None
+        fn next_line(&mut self, stream: Option<usize>, deadline: Option<Instant>) -> Result<Option<(usize, String)>, String> {
+            loop {
+                let wanted: Vec<usize> = match stream {
+                    Some(stream) => vec![stream],
+                    None => vec![STDOUT, STDERR],
+                };
+                for &i in &wanted {
+                    if let Some(line) = self.buffers[i].pop_front() {
+                        return Ok(Some((i, line)));
+                    }
+                }
+                if wanted.iter().all(|&i| !self.open[i]) {
+                    return Ok(None);
+                }
+
+                let received = match deadline {
+                    Some(deadline) => self.lines.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+                    None => self.lines.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                };
+                match received {
+                    Ok((i, Some(line))) => self.buffers[i].push_back(line),
+                    Ok((i, None)) => self.open[i] = false,
+                    Err(RecvTimeoutError::Timeout) => return Err("错误: 读取超时".to_string()),
+                    Err(RecvTimeoutError::Disconnected) => self.open = [false, false],
+                }
+            }
+        }
+
+        fn poll_status(&mut self) -> Result<Option<ExitStatus>, String> {
+            if self.status.is_none() {
+                self.status = self.child.try_wait().map_err(|e| format!("错误: 无法获取进程状态: {}", e))?;
+            }
+            Ok(self.status)
+        }
+    }
+
+    // 正在执行 stream 的进程和处理函数，处理函数中可以再对其他进程调用 stream
+    struct Streaming {
+        process: u64,
+        handler: String,
+    }
+
+    static PROCESSES: Mutex<HandleTable<Process>> = Mutex::new(HandleTable::new());
+    static STREAMING: Mutex<Vec<Streaming>> = Mutex::new(Vec::new());
+
+    fn lock_processes() -> MutexGuard<'static, HandleTable<Process>> {
+        PROCESSES.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_streaming() -> MutexGuard<'static, Vec<Streaming>> {
+        STREAMING.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn with_process<F>(handle: Option<&String>, f: F) -> String
+    where
+        F: FnOnce(&mut Process) -> Result<String, String>,
+    {
+        let mut processes = lock_processes();
+        let id = handle.and_then(|arg| processes.parse_handle(arg));
+        match id.and_then(|id| processes.get_mut(id)) {
+            Some(process) => f(process).unwrap_or_else(|e| e),
+            None => "错误: 无效的进程句柄".to_string(),
+        }
+    }
+
+    // 在后台启动进程，返回进程句柄
+    // args 是参数数组；options 是可选的 Map:
+    //   cwd: 工作目录, env: 要设置的环境变量 Map, clear_env: 为 true 时不继承当前环境变量,
+    //   stdin: null（默认）、pipe 或 inherit, stdout / stderr: pipe（默认）、null 或 inherit
+    // 参数: command, [args], [options]
+    pub fn cn_spawn(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供命令".to_string();
+        }
+        let command_args: Vec<String> = match args.get(1).filter(|a| !a.trim().is_empty()) {
+            Some(list) => match serde_json::from_str::<JsonValue>(list) {
+                Ok(JsonValue::Array(items)) => items.iter().map(|a| a.as_str().map(|s| s.to_string()).unwrap_or_else(|| a.to_string())).collect(),
+                _ => vec![list.clone()],
+            },
+            None => Vec::new(),
+        };
+        let options = match args.get(2).filter(|o| !o.trim().is_empty()) {
+            Some(options) => match serde_json::from_str::<Map<String, JsonValue>>(options) {
+                Ok(options) => options,
+                Err(_) => return "错误: 选项必须是 Map".to_string(),
+            },
+            None => Map::new(),
+        };
+
+        let mut command = Command::new(&args[0]);
+        command.args(&command_args);
+        if let Some(cwd) = options.get("cwd").and_then(|c| c.as_str()) {
+            command.current_dir(cwd);
+        }
+        if options.get("clear_env").and_then(|c| c.as_bool()).unwrap_or(false) {
+            command.env_clear();
+        }
+        if let Some(JsonValue::Object(env)) = options.get("env") {
+            for (key, value) in env {
+                match value {
+                    JsonValue::String(s) => command.env(key, s),
+                    other => command.env(key, other.to_string()),
+                };
+            }
+        }
+        for (name, default) in [("stdin", "null"), ("stdout", "pipe"), ("stderr", "pipe")] {
+            let mode = options.get(name).and_then(|m| m.as_str()).unwrap_or(default);
+            let stdio = match mode {
+                "pipe" => Stdio::piped(),
+                "null" => Stdio::null(),
+                "inherit" => Stdio::inherit(),
+                other => return format!("错误: {} 的模式无效: {}", name, other),
+            };
+            match name {
+                "stdin" => command.stdin(stdio),
+                "stdout" => command.stdout(stdio),
+                _ => command.stderr(stdio),
+            };
+        }
+
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) => return format!("错误: 无法启动 {}: {}", args[0], e),
+        };
+        let (sender, receiver) = mpsc::channel();
+        let open = [
+            child.stdout.take().map(|out| read_lines(out, STDOUT, sender.clone())).is_some(),
+            child.stderr.take().map(|err| read_lines(err, STDERR, sender)).is_some(),
+        ];
+        let stdin = child.stdin.take();
+        let process = Process {
+            child,
+            stdin,
+            lines: receiver,
+            buffers: [VecDeque::new(), VecDeque::new()],
+            open,
+            status: None,
+        };
+        lock_processes().insert(process).to_string()
+    }
+
+    // 获取进程的系统进程号
+    // 参数: proc
+    pub fn cn_pid(args: Vec<String>) -> String {
+        with_process(args.first(), |process| Ok(process.child.id().to_string()))
+    }
+
+    // 向进程的标准输入写入文本，需要以 stdin: "pipe" 启动
+    // 参数: proc, text
+    pub fn cn_write(args: Vec<String>) -> String {
+        let text = args.get(1).cloned().unwrap_or_default();
+        with_process(args.first(), |process| {
+            let stdin = process.stdin.as_mut().ok_or_else(|| "错误: 进程的标准输入不可写".to_string())?;
+            stdin.write_all(text.as_bytes())
+                .and_then(|_| stdin.flush())
+                .map_err(|e| format!("错误: 写入标准输入失败: {}", e))?;
+            Ok("true".to_string())
+        })
+    }
+
+    // 关闭进程的标准输入，使进程读到输入结束
+    // 参数: proc
+    pub fn cn_close_stdin(args: Vec<String>) -> String {
+        with_process(args.first(), |process| Ok(process.stdin.take().is_some().to_string()))
+    }
+
+    // 读取一行输出（不含换行符），输出已经结束时返回空值
+    // 参数: proc, [stream]（stdout 或 stderr，默认 stdout）, [timeout]（毫秒，0或省略表示一直等待）
+    pub fn cn_read_line(args: Vec<String>) -> String {
+        let stream = match args.get(1).map(|s| s.trim()).filter(|s| !s.is_empty()) {
+            Some(name) => match STREAM_NAMES.iter().position(|n| *n == name) {
+                Some(stream) => stream,
+                None => return format!("错误: 未知的输出流: {}", name),
+            },
+            None => STDOUT,
+        };
+        let deadline = match parse_timeout(args.get(2)) {
+            Ok(timeout) => timeout.map(|t| Instant::now() + t),
+            Err(e) => return e,
+        };
+        with_process(args.first(), |process| match process.next_line(Some(stream), deadline)? {
+            Some((_, line)) => Ok(line),
+            None => Ok(json_value("null")),
+        })
+    }
+
+    // 对进程的每一行输出调用处理函数 handler(line, stream)，stream 为 "stdout" 或 "stderr"，
+    // 输出结束后等待进程退出并返回退出码
+    // 参数: proc, handler
+    pub fn cn_stream(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供进程句柄和处理函数".to_string();
+        }
+        let id = match lock_processes().parse_handle(&args[0]) {
+            Some(id) => id,
+            None => return "错误: 无效的进程句柄".to_string(),
+        };
+        lock_streaming().push(Streaming { process: id, handler: args[1].clone() });
+        next_stream_line()
+    }
+
+    // 处理函数返回后由解释器调用，继续处理下一行；以续接函数注册，脚本无法直接调用
+    // 参数: returned
+    pub fn cn_stream_resume(_args: Vec<String>) -> String {
+        next_stream_line()
+    }
+
+    // 等待进程退出，返回退出码；在Unix上被信号终止时返回负的信号编号
+    // 参数: proc, [timeout]（毫秒，0或省略表示一直等待，超时返回错误）
+    pub fn cn_wait(args: Vec<String>) -> String {
+        let timeout = match parse_timeout(args.get(1)) {
+            Ok(timeout) => timeout,
+            Err(e) => return e,
+        };
+        with_process(args.first(), |process| wait(process, timeout.map(|t| Instant::now() + t)).map(|status| exit_code(status).to_string()))
+    }
+
+    // 获取退出码，进程仍在运行时返回空值
+    // 参数: proc
+    pub fn cn_exit_code(args: Vec<String>) -> String {
+        with_process(args.first(), |process| match process.poll_status()? {
+            Some(status) => Ok(exit_code(status).to_string()),
+            None => Ok(json_value("null")),
+        })
+    }
+
+    // 检查进程是否仍在运行
+    // 参数: proc
+    pub fn cn_is_running(args: Vec<String>) -> String {
+        with_process(args.first(), |process| Ok(process.poll_status()?.is_none().to_string()))
+    }
+
+    // 强制结束进程
+    // 参数: proc
+    pub fn cn_kill(args: Vec<String>) -> String {
+        with_process(args.first(), |process| {
+            if process.poll_status()?.is_some() {
+                return Ok("false".to_string());
+            }
+            process.child.kill().map_err(|e| format!("错误: 无法结束进程: {}", e))?;
+            Ok("true".to_string())
+        })
+    }
+
+    // 向进程发送信号，信号可以是名称（TERM、INT、HUP、KILL、QUIT、USR1、USR2、STOP、CONT）或编号
+    // 在Windows上只支持 KILL 和 TERM，两者都会强制结束进程
+    // 参数: proc, signal
+    pub fn cn_signal(args: Vec<String>) -> String {
+        let name = match args.get(1) {
+            Some(name) => name.trim().to_uppercase(),
+            None => return "错误: 请提供信号".to_string(),
+        };
+        with_process(args.first(), |process| {
+            if process.poll_status()?.is_some() {
+                return Ok("false".to_string());
+            }
+            send_signal(&mut process.child, name.strip_prefix("SIG").unwrap_or(&name))?;
+            Ok("true".to_string())
+        })
+    }
+
+    // 释放进程句柄，仍在运行的进程不会被结束
+    // 参数: proc
+    pub fn cn_close(args: Vec<String>) -> String {
+        let mut processes = lock_processes();
+        match args.first().and_then(|arg| processes.parse_handle(arg)) {
+            Some(id) => processes.remove(id).is_some().to_string(),
+            None => "false".to_string(),
+        }
+    }
+
+    // 取出当前 stream 的下一行并返回调用处理函数的回调请求；输出结束时等待进程退出并返回退出码
+    fn next_stream_line() -> String {
+        let (id, handler) = match lock_streaming().last() {
+            Some(streaming) => (streaming.process, streaming.handler.clone()),
+            None => return "错误: 没有正在处理输出的进程".to_string(),
+        };
+        let result = {
+            let mut processes = lock_processes();
+            match processes.get_mut(id) {
+                Some(process) => match process.next_line(None, None) {
+                    Ok(Some((stream, line))) => {
+                        let args_json = JsonValue::Array(vec![JsonValue::String(line), JsonValue::from(STREAM_NAMES[stream])]).to_string();
+                        return callback_value(&handler, &args_json, RESUME_FUNCTION);
+                    },
+                    Ok(None) => wait(process, None).map(|status| exit_code(status).to_string()).unwrap_or_else(|e| e),
+                    Err(e) => e,
+                },
+                None => "错误: 进程句柄已被释放".to_string(),
+            }
+        };
+        lock_streaming().pop();
+        result
+    }
+
+    // 后台线程按行读取输出流并发送到通道
+    fn read_lines<R: Read + Send + 'static>(output: R, stream: usize, sender: Sender<Line>) {
+        thread::spawn(move || {
+            let mut reader = BufReader::new(output);
+            let mut buf = Vec::new();
+            loop {
+                buf.clear();
+                match reader.read_until(b'\n', &mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {
+                        if buf.ends_with(b"\n") {
+                            buf.pop();
+                            if buf.ends_with(b"\r") {
+                                buf.pop();
+                            }
+                        }
+                        if sender.send((stream, Some(String::from_utf8_lossy(&buf).into_owned()))).is_err() {
+                            return;
+                        }
+                    },
+                }
+            }
+            let _ = sender.send((stream, None));
+        });
+    }
+
+    fn wait(process: &mut Process, deadline: Option<Instant>) -> Result<ExitStatus, String> {
+        // 没有人读取的输出由读取线程缓存，不会阻塞子进程
+        loop {
+            if let Some(status) = process.poll_status()? {
+                return Ok(status);
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Err("错误: 等待进程超时".to_string());
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+
+    #[cfg(unix)]
+    fn exit_code(status: ExitStatus) -> i32 {
+        use std::os::unix::process::ExitStatusExt;
+        status.code().or_else(|| status.signal().map(|s| -s)).unwrap_or(-1)
+    }
+
+    #[cfg(not(unix))]
+    fn exit_code(status: ExitStatus) -> i32 {
+        status.code().unwrap_or(-1)
+    }
+
+    #[cfg(unix)]
+    fn send_signal(child: &mut Child, name: &str) -> Result<(), String> {
+        let signal = match name {
+            "TERM" => libc::SIGTERM,
+            "INT" => libc::SIGINT,
+            "HUP" => libc::SIGHUP,
+            "KILL" => libc::SIGKILL,
+            "QUIT" => libc::SIGQUIT,
+            "USR1" => libc::SIGUSR1,
+            "USR2" => libc::SIGUSR2,
+            "STOP" => libc::SIGSTOP,
+            "CONT" => libc::SIGCONT,
+            other => other.parse::<i32>().map_err(|_| format!("错误: 未知的信号: {}", other))?,
+        };
+        // SAFETY: kill 只接收整数参数，进程号来自仍由本库持有且尚未回收的子进程
+        if unsafe { libc::kill(child.id() as libc::pid_t, signal) } == 0 {
+            Ok(())
+        } else {
+            Err(format!("错误: 无法发送信号: {}", std::io::Error::last_os_error()))
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn send_signal(child: &mut Child, name: &str) -> Result<(), String> {
+        match name {
+            "KILL" | "TERM" | "9" | "15" => child.kill().map_err(|e| format!("错误: 无法结束进程: {}", e)),
+            other => Err(format!("错误: 当前系统不支持信号: {}", other)),
+        }
+    }
+
+    fn parse_timeout(arg: Option<&String>) -> Result<Option<Duration>, String> {
+        match arg.map(|t| t.trim().parse::<u64>()) {
+            Some(Ok(0)) | None => Ok(None),
+            Some(Ok(ms)) => Ok(Some(Duration::from_millis(ms))),
+            Some(Err(_)) => Err(format!("错误: 无效的超时毫秒数: {}", arg.map(|t| t.as_str()).unwrap_or(""))),
+        }
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册进程命名空间下的函数
+    let proc_ns = registry.namespace("proc");
+    proc_ns.add_function("spawn", proc::cn_spawn)
+           .add_function("pid", proc::cn_pid)
+           .add_function("write", proc::cn_write)
+           .add_function("close_stdin", proc::cn_close_stdin)
+           .add_function("read_line", proc::cn_read_line)
+           .add_function("stream", proc::cn_stream)
+           .add_continuation("stream_resume", proc::cn_stream_resume)
+           .add_function("wait", proc::cn_wait)
+           .add_function("exit_code", proc::cn_exit_code)
+           .add_function("is_running", proc::cn_is_running)
+           .add_function("kill", proc::cn_kill)
+           .add_function("signal", proc::cn_signal)
+           .add_function("close", proc::cn_close);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
@@ -0,0 +1,291 @@
+use ::std::collections::HashMap;
+use regex::{Captures, Regex, RegexBuilder};
+use serde_json::{Map, Value as JsonValue};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+// 导入通用库
+use cn_common::handle::HandleTable;
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::json_value;
+
+// 正则表达式命名空间
+// 所有函数的 pattern 参数既可以是正则表达式，也可以是 compile 返回的句柄（形如 "regex#1"）
+// 直接传入的正则表达式会被缓存，重复调用时不会重新编译
+mod regex_ns {
+    use super::*;
+
+    // 句柄前缀，避免与纯数字的正则表达式混淆
+    const HANDLE_PREFIX: &str = "regex#";
+
+    // 缓存的正则表达式数量上限，超过后清空重建
+    const CACHE_LIMIT: usize = 256;
+
+    static COMPILED: Mutex<HandleTable<Regex>> = Mutex::new(HandleTable::new());
+    static CACHE: OnceLock<Mutex<HashMap<String, Regex>>> = OnceLock::new();
+
+    fn lock_compiled() -> MutexGuard<'static, HandleTable<Regex>> {
+        COMPILED.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_cache() -> MutexGuard<'static, HashMap<String, Regex>> {
+        CACHE.get_or_init(|| Mutex::new(HashMap::new())).lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // 获取正则表达式：句柄直接取出，其他字符串从缓存中取出或编译后放入缓存
+    fn resolve(pattern: &str) -> Result<Regex, String> {
+        if let Some(id) = pattern.strip_prefix(HANDLE_PREFIX) {
+            let compiled = lock_compiled();
+            if let Some(regex) = compiled.parse_handle(id).and_then(|id| compiled.get(id)) {
+                return Ok(regex.clone());
+            }
+        }
+
+        let mut cache = lock_cache();
+        if let Some(regex) = cache.get(pattern) {
+            return Ok(regex.clone());
+        }
+        let regex = Regex::new(pattern).map_err(|e| format!("错误: 无效的正则表达式: {}", e))?;
+        if cache.len() >= CACHE_LIMIT {
+            cache.clear();
+        }
+        cache.insert(pattern.to_string(), regex.clone());
+        Ok(regex)
+    }
+
+    // 预编译正则表达式，返回句柄
+    // 参数: pattern, [flags]（i 忽略大小写，m 多行，s 点号匹配换行，x 忽略空白和注释，U 非贪婪）
+    pub fn cn_compile(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供正则表达式".to_string();
+        }
+        let mut builder = RegexBuilder::new(&args[0]);
+        for flag in args.get(1).map(|f| f.as_str()).unwrap_or("").chars() {
+            match flag {
+                'i' => builder.case_insensitive(true),
+                'm' => builder.multi_line(true),
+                's' => builder.dot_matches_new_line(true),
+                'x' => builder.ignore_whitespace(true),
+                'U' => builder.swap_greed(true),
+                other => return format!("错误: 未知的标志: {}", other),
+            };
+        }
+        match builder.build() {
+            Ok(regex) => format!("{}{}", HANDLE_PREFIX, lock_compiled().insert(regex)),
+            Err(e) => format!("错误: 无效的正则表达式: {}", e),
+        }
+    }
+
+    // 释放预编译的正则表达式
+    // 参数: handle
+    pub fn cn_free(args: Vec<String>) -> String {
+        let mut compiled = lock_compiled();
+        match args.first().and_then(|arg| arg.strip_prefix(HANDLE_PREFIX)).and_then(|id| compiled.parse_handle(id)) {
+            Some(id) => {
+                compiled.remove(id);
+                "true".to_string()
+            },
+            None => "false".to_string(),
+        }
+    }
+
+    // 检查文本中是否存在匹配
+    // 参数: pattern, text
+    pub fn cn_test(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供正则表达式和文本".to_string();
+        }
+        match resolve(&args[0]) {
+            Ok(regex) => regex.is_match(&args[1]).to_string(),
+            Err(e) => e,
+        }
+    }
+
+    // 查找第一个匹配，返回 Map：
+    // {"match": 匹配文本, "start": 起始位置, "end": 结束位置, "groups": [分组], "named": {命名分组}}
+    // 位置按字符计算，未参与匹配的分组为空值；没有匹配时返回空值
+    // 参数: pattern, text
+    pub fn cn_match(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供正则表达式和文本".to_string();
+        }
+        let regex = match resolve(&args[0]) {
+            Ok(regex) => regex,
+            Err(e) => return e,
+        };
+        let result = match regex.captures(&args[1]) {
+            Some(caps) => match_to_json(&regex, &caps, &args[1]),
+            None => JsonValue::Null,
+        };
+        json_value(&result.to_string())
+    }
+
+    // 查找所有匹配，返回与 match 结构相同的 Map 数组
+    // 参数: pattern, text
+    pub fn cn_match_all(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供正则表达式和文本".to_string();
+        }
+        let regex = match resolve(&args[0]) {
+            Ok(regex) => regex,
+            Err(e) => return e,
+        };
+        let matches: Vec<JsonValue> = regex.captures_iter(&args[1])
+            .map(|caps| match_to_json(&regex, &caps, &args[1]))
+            .collect();
+        json_value(&JsonValue::Array(matches).to_string())
+    }
+
+    // 查找所有匹配的文本，返回字符串数组
+    // 参数: pattern, text, [group]（返回指定分组的内容，可以是序号或名称，默认为整个匹配）
+    pub fn cn_find_all(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供正则表达式和文本".to_string();
+        }
+        let regex = match resolve(&args[0]) {
+            Ok(regex) => regex,
+            Err(e) => return e,
+        };
+
+        let mut found = Vec::new();
+        match args.get(2) {
+            Some(group) => {
+                let index = group.parse::<usize>().ok();
+                if index.is_some_and(|i| i >= regex.captures_len()) || (index.is_none() && !regex.capture_names().any(|n| n == Some(group.as_str()))) {
+                    return format!("错误: 分组不存在: {}", group);
+                }
+                for caps in regex.captures_iter(&args[1]) {
+                    let m = match index {
+                        Some(i) => caps.get(i),
+                        None => caps.name(group),
+                    };
+                    if let Some(m) = m {
+                        found.push(JsonValue::String(m.as_str().to_string()));
+                    }
+                }
+            },
+            None => found.extend(regex.find_iter(&args[1]).map(|m| JsonValue::String(m.as_str().to_string()))),
+        }
+        json_value(&JsonValue::Array(found).to_string())
+    }
+
+    // 获取第一个匹配的分组，返回 Map：命名分组以名称为键，所有分组同时以序号为键（"0" 为整个匹配）
+    // 没有匹配时返回空值
+    // 参数: pattern, text
+    pub fn cn_captures(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供正则表达式和文本".to_string();
+        }
+        let regex = match resolve(&args[0]) {
+            Ok(regex) => regex,
+            Err(e) => return e,
+        };
+        let caps = match regex.captures(&args[1]) {
+            Some(caps) => caps,
+            None => return json_value("null"),
+        };
+
+        let mut groups = Map::new();
+        for (i, name) in regex.capture_names().enumerate() {
+            let value = caps.get(i).map(|m| JsonValue::String(m.as_str().to_string())).unwrap_or(JsonValue::Null);
+            if let Some(name) = name {
+                groups.insert(name.to_string(), value.clone());
+            }
+            groups.insert(i.to_string(), value);
+        }
+        json_value(&JsonValue::Object(groups).to_string())
+    }
+
+    // 替换匹配的文本，替换文本中可以用 $1、${name} 引用分组，$$ 表示 $ 本身
+    // 参数: pattern, text, replacement, [count]（最多替换的次数，默认0表示全部替换）
+    pub fn cn_replace(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "错误: 请提供正则表达式、文本和替换文本".to_string();
+        }
+        let regex = match resolve(&args[0]) {
+            Ok(regex) => regex,
+            Err(e) => return e,
+        };
+        let count = match args.get(3).map(|c| c.trim().parse::<usize>()) {
+            Some(Ok(count)) => count,
+            Some(Err(_)) => return format!("错误: 无效的替换次数: {}", args[3]),
+            None => 0,
+        };
+        regex.replacen(&args[1], count, args[2].as_str()).into_owned()
+    }
+
+    // 按匹配拆分文本，返回字符串数组
+    // 参数: pattern, text, [limit]（最多拆分出的段数，默认0表示不限制）
+    pub fn cn_split(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供正则表达式和文本".to_string();
+        }
+        let regex = match resolve(&args[0]) {
+            Ok(regex) => regex,
+            Err(e) => return e,
+        };
+        let parts: Vec<JsonValue> = match args.get(2).map(|l| l.trim().parse::<usize>()) {
+            Some(Ok(limit)) if limit > 0 => regex.splitn(&args[1], limit).map(|p| JsonValue::String(p.to_string())).collect(),
+            Some(Ok(_)) | None => regex.split(&args[1]).map(|p| JsonValue::String(p.to_string())).collect(),
+            Some(Err(_)) => return format!("错误: 无效的拆分数量: {}", args[2]),
+        };
+        json_value(&JsonValue::Array(parts).to_string())
+    }
+
+    // 转义文本中的正则表达式特殊字符
+    // 参数: text
+    pub fn cn_escape(args: Vec<String>) -> String {
+        match args.first() {
+            Some(text) => regex::escape(text),
+            None => String::new(),
+        }
+    }
+
+    // 将一次匹配转换为 Map
+    fn match_to_json(regex: &Regex, caps: &Captures, text: &str) -> JsonValue {
+        let whole = caps.get(0).expect("第0个分组总是存在");
+        let group_value = |i: usize| caps.get(i).map(|m| JsonValue::String(m.as_str().to_string())).unwrap_or(JsonValue::Null);
+
+        let mut named = Map::new();
+        for (i, name) in regex.capture_names().enumerate() {
+            if let Some(name) = name {
+                named.insert(name.to_string(), group_value(i));
+            }
+        }
+
+        let mut obj = Map::new();
+        obj.insert("match".to_string(), JsonValue::String(whole.as_str().to_string()));
+        obj.insert("start".to_string(), JsonValue::from(char_offset(text, whole.start())));
+        obj.insert("end".to_string(), JsonValue::from(char_offset(text, whole.end())));
+        obj.insert("groups".to_string(), JsonValue::Array((1..caps.len()).map(group_value).collect()));
+        obj.insert("named".to_string(), JsonValue::Object(named));
+        JsonValue::Object(obj)
+    }
+
+    // 字节位置转换为字符位置
+    fn char_offset(text: &str, byte: usize) -> usize {
+        text[..byte].chars().count()
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册正则表达式命名空间下的函数
+    let regex_ns = registry.namespace("regex");
+    regex_ns.add_function("compile", regex_ns::cn_compile)
+            .add_function("free", regex_ns::cn_free)
+            .add_function("test", regex_ns::cn_test)
+            .add_function("match", regex_ns::cn_match)
+            .add_function("match_all", regex_ns::cn_match_all)
+            .add_function("find_all", regex_ns::cn_find_all)
+            .add_function("captures", regex_ns::cn_captures)
+            .add_function("replace", regex_ns::cn_replace)
+            .add_function("split", regex_ns::cn_split)
+            .add_function("escape", regex_ns::cn_escape);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
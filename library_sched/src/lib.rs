@@ -0,0 +1,285 @@
+use ::std::collections::HashMap;
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use serde_json::Value as JsonValue;
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// 导入通用库
+use cn_common::cron::{parse, parse_count, Cron};
+use cn_common::handle::HandleTable;
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::{callback_value, json_value, thrown_message};
+
+// 调度命名空间
+// cron 表达式有5个字段（分 时 日 月 周），也可以在最前面加上秒成为6个字段，
+// 支持 * , - / 以及 L、W、# 和 @daily、@hourly 等别名，时间按本地时区计算，以秒级时间戳表示
+// add 和 every 注册任务，run 按计划调用任务的处理函数 handler(job)，直到没有任务、超出时长或处理函数调用 stop
+mod sched {
+    use super::*;
+
+    // 处理函数返回后，解释器调用的续接函数
+    const RESUME_FUNCTION: &str = "sched::resume";
+    const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+    enum Schedule {
+        Cron(Box<Cron>),
+        Every(chrono::Duration),
+    }
+
+    struct Job {
+        schedule: Schedule,
+        handler: String,
+        next: DateTime<Local>,
+    }
+
+    impl Job {
+        // 计算 after 之后的下一次运行时间，cron 表达式没有下一次时返回 None
+        fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+            match &self.schedule {
+                Schedule::Cron(cron) => cron.find_next_occurrence(&after, false).ok(),
+                // 固定间隔，落后时从现在重新计算，不补运行错过的次数
+                Schedule::Every(interval) => Some((self.next + *interval).max(Local::now())),
+            }
+        }
+    }
+
+    struct Runner {
+        running: bool,
+        stopping: bool,
+        deadline: Option<Instant>,
+    }
+
+    static JOBS: Mutex<HandleTable<Job>> = Mutex::new(HandleTable::new());
+    static RUNNER: Mutex<Runner> = Mutex::new(Runner { running: false, stopping: false, deadline: None });
+
+    fn lock_jobs() -> MutexGuard<'static, HandleTable<Job>> {
+        JOBS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_runner() -> MutexGuard<'static, Runner> {
+        RUNNER.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // 检查cron表达式是否有效
+    // 参数: expr
+    pub use cn_common::cron::cn_validate;
+
+    // 计算cron表达式在指定时间之后的下一次运行时间，返回时间戳
+    // 参数: expr, [from]（时间戳、"%Y-%m-%d %H:%M:%S" 或 RFC 3339 格式，默认为现在）
+    pub fn cn_next(args: Vec<String>) -> String {
+        match next_times(&args, 1) {
+            Ok(times) => times.first().map(|t| t.to_string()).unwrap_or_default(),
+            Err(e) => e,
+        }
+    }
+
+    // 计算cron表达式接下来的n次运行时间，返回时间戳数组；n 最多为 10000
+    // 参数: expr, n, [from]
+    pub fn cn_next_n(args: Vec<String>) -> String {
+        let count = match parse_count(args.get(1)) {
+            Ok(count) => count,
+            Err(e) => return e,
+        };
+        let mut rest = args.clone();
+        rest.remove(1);
+        match next_times(&rest, count) {
+            Ok(times) => json_value(&JsonValue::from(times).to_string()),
+            Err(e) => e,
+        }
+    }
+
+    // 注册按cron表达式运行的任务，返回任务句柄
+    // 参数: expr, handler
+    pub fn cn_add(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供cron表达式和处理函数".to_string();
+        }
+        let cron = match parse(&args[0]) {
+            Ok(cron) => cron,
+            Err(e) => return e,
+        };
+        let next = match cron.find_next_occurrence(&Local::now(), false) {
+            Ok(next) => next,
+            Err(e) => return format!("错误: 无法计算下一次运行时间: {}", e),
+        };
+        let job = Job { schedule: Schedule::Cron(Box::new(cron)), handler: args[1].clone(), next };
+        lock_jobs().insert(job).to_string()
+    }
+
+    // 注册按固定间隔运行的任务，第一次在一个间隔之后运行，返回任务句柄
+    // 参数: interval（毫秒）, handler
+    pub fn cn_every(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供间隔毫秒数和处理函数".to_string();
+        }
+        let interval = match args[0].trim().parse::<i64>() {
+            Ok(ms) if ms > 0 => chrono::Duration::milliseconds(ms),
+            _ => return format!("错误: 无效的间隔毫秒数: {}", args[0]),
+        };
+        let job = Job { schedule: Schedule::Every(interval), handler: args[1].clone(), next: Local::now() + interval };
+        lock_jobs().insert(job).to_string()
+    }
+
+    // 取消任务
+    // 参数: job
+    pub fn cn_cancel(args: Vec<String>) -> String {
+        let mut jobs = lock_jobs();
+        match args.first().and_then(|arg| jobs.parse_handle(arg)) {
+            Some(id) => jobs.remove(id).is_some().to_string(),
+            None => "false".to_string(),
+        }
+    }
+
+    // 获取任务的下一次运行时间，返回时间戳
+    // 参数: job
+    pub fn cn_next_run(args: Vec<String>) -> String {
+        let jobs = lock_jobs();
+        match args.first().and_then(|arg| jobs.parse_handle(arg)).and_then(|id| jobs.get(id)) {
+            Some(job) => job.next.timestamp().to_string(),
+            None => "错误: 无效的任务句柄".to_string(),
+        }
+    }
+
+    // 按计划运行任务，直到没有任务、超出时长或处理函数调用 stop；期间脚本的其他代码不会执行
+    // 处理函数抛出未捕获的异常时停止运行并返回错误
+    // 参数: [duration]（最长运行毫秒数，0或省略表示不限）
+    pub fn cn_run(args: Vec<String>) -> String {
+        let deadline = match args.first().map(|d| d.trim().parse::<u64>()) {
+            Some(Ok(0)) | None => None,
+            Some(Ok(ms)) => Some(Instant::now() + Duration::from_millis(ms)),
+            Some(Err(_)) => return format!("错误: 无效的毫秒数: {}", args[0]),
+        };
+        {
+            let mut runner = lock_runner();
+            if runner.running {
+                return "错误: 调度器已经在运行".to_string();
+            }
+            runner.running = true;
+            runner.stopping = false;
+            runner.deadline = deadline;
+        }
+        next_job()
+    }
+
+    // 处理函数返回后由解释器调用，等待下一个任务；以续接函数注册，脚本无法直接调用
+    // 参数: returned
+    pub fn cn_resume(args: Vec<String>) -> String {
+        if let Some(message) = args.first().and_then(|returned| thrown_message(returned)) {
+            lock_runner().running = false;
+            return format!("错误: 任务的处理函数抛出异常: {}", message);
+        }
+        next_job()
+    }
+
+    // 在当前处理函数返回后停止 run
+    // 参数: 无
+    pub fn cn_stop(_args: Vec<String>) -> String {
+        let mut runner = lock_runner();
+        if !runner.running {
+            return "false".to_string();
+        }
+        runner.stopping = true;
+        "true".to_string()
+    }
+
+    // 等待最早到期的任务，返回调用其处理函数的回调请求；结束时返回 true
+    fn next_job() -> String {
+        loop {
+            let deadline = {
+                let mut runner = lock_runner();
+                if runner.stopping {
+                    runner.running = false;
+                    return "true".to_string();
+                }
+                runner.deadline
+            };
+
+            let due = {
+                let mut jobs = lock_jobs();
+                let (id, next) = match jobs.iter().min_by_key(|(_, job)| job.next) {
+                    Some((id, job)) => (id, job.next),
+                    None => {
+                        lock_runner().running = false;
+                        return "true".to_string();
+                    },
+                };
+                let wait = (next - Local::now()).to_std().unwrap_or(Duration::ZERO);
+                if deadline.is_some_and(|deadline| Instant::now() + wait > deadline) {
+                    drop(jobs);
+                    thread::sleep(deadline.map(|d| d.saturating_duration_since(Instant::now())).unwrap_or_default());
+                    lock_runner().running = false;
+                    return "true".to_string();
+                }
+                if wait.is_zero() {
+                    let job = jobs.get_mut(id).expect("任务存在");
+                    let handler = job.handler.clone();
+                    match job.next_after(next.max(Local::now())) {
+                        Some(following) => job.next = following,
+                        // 没有下一次运行时间的任务在这次运行后结束
+                        None => {
+                            jobs.remove(id);
+                        },
+                    }
+                    Some((id, handler))
+                } else {
+                    // 分段等待，使等待期间新增或取消的任务能够生效
+                    drop(jobs);
+                    thread::sleep(wait.min(Duration::from_millis(500)));
+                    None
+                }
+            };
+
+            if let Some((id, handler)) = due {
+                let args_json = JsonValue::Array(vec![JsonValue::from(id)]).to_string();
+                return callback_value(&handler, &args_json, RESUME_FUNCTION);
+            }
+        }
+    }
+
+    fn next_times(args: &[String], count: usize) -> Result<Vec<i64>, String> {
+        let cron = parse(args.first().ok_or_else(|| "错误: 请提供cron表达式".to_string())?)?;
+        let from = match args.get(1).map(|f| f.trim()).filter(|f| !f.is_empty()) {
+            Some(from) => parse_time(from)?,
+            None => Local::now(),
+        };
+        let times = cn_common::cron::next_times(&cron, &from, count)?;
+        Ok(times.iter().map(|time| time.timestamp()).collect())
+    }
+
+    fn parse_time(text: &str) -> Result<DateTime<Local>, String> {
+        if let Ok(timestamp) = text.parse::<i64>() {
+            return Local.timestamp_opt(timestamp, 0).single().ok_or_else(|| format!("错误: 无效的时间: {}", text));
+        }
+        if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+            return Ok(time.with_timezone(&Local));
+        }
+        NaiveDateTime::parse_from_str(text, TIME_FORMAT)
+            .ok()
+            .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+            .ok_or_else(|| format!("错误: 无效的时间: {}", text))
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册调度命名空间下的函数
+    let sched_ns = registry.namespace("sched");
+    sched_ns.add_function("validate", sched::cn_validate)
+            .add_function("next", sched::cn_next)
+            .add_function("next_n", sched::cn_next_n)
+            .add_retaining_function("add", sched::cn_add)
+            .add_retaining_function("every", sched::cn_every)
+            .add_function("cancel", sched::cn_cancel)
+            .add_function("next_run", sched::cn_next_run)
+            .add_function("run", sched::cn_run)
+            .add_continuation("resume", sched::cn_resume)
+            .add_function("stop", sched::cn_stop);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
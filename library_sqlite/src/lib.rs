@@ -0,0 +1,342 @@
+use ::std::collections::HashMap;
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{Connection, Statement};
+use serde_json::{Map, Value as JsonValue};
+use std::sync::{Mutex, MutexGuard};
+
+// 导入通用库
+use cn_common::handle::HandleTable;
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::json_value;
+
+// SQLite命名空间
+// SQL参数以数组（对应 ? 或 ?1）或 Map（对应 :name，键可以省略冒号）传入；
+// 查询结果为 Map 数组，BLOB 列以16进制字符串表示
+mod sqlite {
+    use super::*;
+
+    // 预编译语句：保存所属连接和SQL，执行时使用连接的语句缓存，不会重复编译
+    struct Prepared {
+        db: u64,
+        sql: String,
+    }
+
+    static CONNECTIONS: Mutex<HandleTable<Connection>> = Mutex::new(HandleTable::new());
+    static STATEMENTS: Mutex<HandleTable<Prepared>> = Mutex::new(HandleTable::new());
+
+    fn lock_connections() -> MutexGuard<'static, HandleTable<Connection>> {
+        CONNECTIONS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_statements() -> MutexGuard<'static, HandleTable<Prepared>> {
+        STATEMENTS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // 在句柄对应的连接上执行操作
+    fn with_connection<F>(handle: Option<&String>, f: F) -> String
+    where
+        F: FnOnce(&Connection) -> Result<String, String>,
+    {
+        let connections = lock_connections();
+        match handle.and_then(|arg| connections.parse_handle(arg)).and_then(|id| connections.get(id)) {
+            Some(conn) => f(conn).unwrap_or_else(|e| e),
+            None => "错误: 无效的数据库句柄".to_string(),
+        }
+    }
+
+    // 打开数据库文件，文件不存在时创建；":memory:" 表示内存数据库
+    // 参数: path
+    pub fn cn_open(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供数据库路径".to_string();
+        }
+        match Connection::open(&args[0]) {
+            Ok(conn) => lock_connections().insert(conn).to_string(),
+            Err(e) => format!("错误: 无法打开数据库 {}: {}", args[0], e),
+        }
+    }
+
+    // 关闭数据库连接，同时释放该连接的预编译语句
+    // 参数: db
+    pub fn cn_close(args: Vec<String>) -> String {
+        let mut connections = lock_connections();
+        let id = match args.first().and_then(|arg| connections.parse_handle(arg)) {
+            Some(id) => id,
+            None => return "false".to_string(),
+        };
+        connections.remove(id);
+
+        lock_statements().retain(|_, stmt| stmt.db != id);
+        "true".to_string()
+    }
+
+    // 执行SQL，返回受影响的行数
+    // 不提供参数时可以一次执行多条以分号分隔的语句
+    // 参数: db, sql, [params]
+    pub fn cn_exec(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供数据库句柄和SQL".to_string();
+        }
+        with_connection(args.first(), |conn| {
+            match args.get(2) {
+                Some(params) => {
+                    let mut stmt = conn.prepare_cached(&args[1]).map_err(sql_error)?;
+                    execute(&mut stmt, params)
+                },
+                None => {
+                    conn.execute_batch(&args[1]).map_err(sql_error)?;
+                    Ok(conn.changes().to_string())
+                },
+            }
+        })
+    }
+
+    // 执行查询，返回 Map 数组，每个 Map 以列名为键
+    // 参数: db, sql, [params]
+    pub fn cn_query(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供数据库句柄和SQL".to_string();
+        }
+        with_connection(args.first(), |conn| {
+            let mut stmt = conn.prepare_cached(&args[1]).map_err(sql_error)?;
+            let rows = query(&mut stmt, args.get(2), None)?;
+            Ok(json_value(&JsonValue::Array(rows).to_string()))
+        })
+    }
+
+    // 执行查询，只返回第一行，没有结果时返回空值
+    // 参数: db, sql, [params]
+    pub fn cn_query_one(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供数据库句柄和SQL".to_string();
+        }
+        with_connection(args.first(), |conn| {
+            let mut stmt = conn.prepare_cached(&args[1]).map_err(sql_error)?;
+            let row = query(&mut stmt, args.get(2), Some(1))?.into_iter().next().unwrap_or(JsonValue::Null);
+            Ok(json_value(&row.to_string()))
+        })
+    }
+
+    // 获取最后插入行的 rowid
+    // 参数: db
+    pub fn cn_last_insert_id(args: Vec<String>) -> String {
+        with_connection(args.first(), |conn| Ok(conn.last_insert_rowid().to_string()))
+    }
+
+    // 开始事务
+    // 参数: db, [mode]（deferred、immediate、exclusive，默认 deferred）
+    pub fn cn_begin(args: Vec<String>) -> String {
+        let mode = match args.get(1).map(|m| m.trim().to_lowercase()).as_deref() {
+            None | Some("deferred") => "DEFERRED",
+            Some("immediate") => "IMMEDIATE",
+            Some("exclusive") => "EXCLUSIVE",
+            Some(other) => return format!("错误: 无效的事务模式: {}", other),
+        };
+        with_connection(args.first(), |conn| {
+            conn.execute_batch(&format!("BEGIN {}", mode)).map_err(sql_error)?;
+            Ok("true".to_string())
+        })
+    }
+
+    // 提交事务
+    // 参数: db
+    pub fn cn_commit(args: Vec<String>) -> String {
+        with_connection(args.first(), |conn| {
+            conn.execute_batch("COMMIT").map_err(sql_error)?;
+            Ok("true".to_string())
+        })
+    }
+
+    // 回滚事务
+    // 参数: db
+    pub fn cn_rollback(args: Vec<String>) -> String {
+        with_connection(args.first(), |conn| {
+            conn.execute_batch("ROLLBACK").map_err(sql_error)?;
+            Ok("true".to_string())
+        })
+    }
+
+    // 检查连接当前是否处于事务中
+    // 参数: db
+    pub fn cn_in_transaction(args: Vec<String>) -> String {
+        with_connection(args.first(), |conn| Ok((!conn.is_autocommit()).to_string()))
+    }
+
+    // 预编译SQL语句，返回语句句柄，可以用不同的参数多次执行
+    // 参数: db, sql
+    pub fn cn_prepare(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供数据库句柄和SQL".to_string();
+        }
+        let db = {
+            let connections = lock_connections();
+            let id = match connections.parse_handle(&args[0]) {
+                Some(id) => id,
+                None => return "错误: 无效的数据库句柄".to_string(),
+            };
+            // 立即编译一次，尽早报告语法错误
+            if let Some(conn) = connections.get(id) {
+                if let Err(e) = conn.prepare_cached(&args[1]) {
+                    return sql_error(e);
+                }
+            }
+            id
+        };
+        lock_statements().insert(Prepared { db, sql: args[1].clone() }).to_string()
+    }
+
+    // 查找预编译语句，返回所属连接和SQL
+    fn lookup_statement(handle: Option<&String>) -> Result<(String, String), String> {
+        let statements = lock_statements();
+        match handle.and_then(|arg| statements.parse_handle(arg)).and_then(|id| statements.get(id)) {
+            Some(stmt) => Ok((stmt.db.to_string(), stmt.sql.clone())),
+            None => Err("错误: 无效的语句句柄".to_string()),
+        }
+    }
+
+    // 执行预编译语句，返回受影响的行数
+    // 参数: stmt, [params]
+    pub fn cn_stmt_exec(args: Vec<String>) -> String {
+        let (db, sql) = match lookup_statement(args.first()) {
+            Ok(found) => found,
+            Err(e) => return e,
+        };
+        with_connection(Some(&db), |conn| {
+            let mut stmt = conn.prepare_cached(&sql).map_err(sql_error)?;
+            execute(&mut stmt, args.get(1).map(|p| p.as_str()).unwrap_or("[]"))
+        })
+    }
+
+    // 使用预编译语句查询，返回 Map 数组
+    // 参数: stmt, [params]
+    pub fn cn_stmt_query(args: Vec<String>) -> String {
+        let (db, sql) = match lookup_statement(args.first()) {
+            Ok(found) => found,
+            Err(e) => return e,
+        };
+        with_connection(Some(&db), |conn| {
+            let mut stmt = conn.prepare_cached(&sql).map_err(sql_error)?;
+            let rows = query(&mut stmt, args.get(1), None)?;
+            Ok(json_value(&JsonValue::Array(rows).to_string()))
+        })
+    }
+
+    // 释放预编译语句
+    // 参数: stmt
+    pub fn cn_finalize(args: Vec<String>) -> String {
+        let mut statements = lock_statements();
+        match args.first().and_then(|arg| statements.parse_handle(arg)) {
+            Some(id) => {
+                statements.remove(id);
+                "true".to_string()
+            },
+            None => "false".to_string(),
+        }
+    }
+
+    fn sql_error(e: rusqlite::Error) -> String {
+        format!("错误: SQL执行失败: {}", e)
+    }
+
+    fn execute(stmt: &mut Statement, params: &str) -> Result<String, String> {
+        bind(stmt, params)?;
+        stmt.raw_execute().map(|n| n.to_string()).map_err(sql_error)
+    }
+
+    fn query(stmt: &mut Statement, params: Option<&String>, limit: Option<usize>) -> Result<Vec<JsonValue>, String> {
+        bind(stmt, params.map(|p| p.as_str()).unwrap_or("[]"))?;
+        let columns: Vec<String> = stmt.column_names().into_iter().map(|c| c.to_string()).collect();
+        let mut rows = stmt.raw_query();
+        let mut result = Vec::new();
+        while let Some(row) = rows.next().map_err(sql_error)? {
+            let mut obj = Map::new();
+            for (i, name) in columns.iter().enumerate() {
+                let value = row.get_ref(i).map_err(sql_error)?;
+                obj.insert(name.clone(), to_json(value));
+            }
+            result.push(JsonValue::Object(obj));
+            if limit.is_some_and(|limit| result.len() >= limit) {
+                break;
+            }
+        }
+        Ok(result)
+    }
+
+    // 绑定参数：数组按位置绑定，Map 按名称绑定，其他值作为唯一的位置参数
+    fn bind(stmt: &mut Statement, params: &str) -> Result<(), String> {
+        let params = serde_json::from_str::<JsonValue>(params).unwrap_or_else(|_| JsonValue::String(params.to_string()));
+        match params {
+            JsonValue::Object(named) => {
+                for (key, value) in named {
+                    let name = if key.starts_with([':', '@', '$']) { key.clone() } else { format!(":{}", key) };
+                    let index = stmt.parameter_index(&name).map_err(sql_error)?
+                        .ok_or_else(|| format!("错误: SQL中没有参数: {}", name))?;
+                    stmt.raw_bind_parameter(index, to_sql(value)).map_err(sql_error)?;
+                }
+            },
+            params => {
+                let values = match params {
+                    JsonValue::Array(values) => values,
+                    other => vec![other],
+                };
+                if values.len() != stmt.parameter_count() {
+                    return Err(format!("错误: SQL需要{}个参数，实际提供了{}个", stmt.parameter_count(), values.len()));
+                }
+                for (i, value) in values.into_iter().enumerate() {
+                    stmt.raw_bind_parameter(i + 1, to_sql(value)).map_err(sql_error)?;
+                }
+            },
+        }
+        Ok(())
+    }
+
+    fn to_sql(value: JsonValue) -> SqlValue {
+        match value {
+            JsonValue::Null => SqlValue::Null,
+            JsonValue::Bool(b) => SqlValue::Integer(b as i64),
+            JsonValue::Number(n) => match n.as_i64() {
+                Some(i) => SqlValue::Integer(i),
+                None => SqlValue::Real(n.as_f64().unwrap_or(0.0)),
+            },
+            JsonValue::String(s) => SqlValue::Text(s),
+            other => SqlValue::Text(other.to_string()),
+        }
+    }
+
+    fn to_json(value: ValueRef) -> JsonValue {
+        match value {
+            ValueRef::Null => JsonValue::Null,
+            ValueRef::Integer(i) => JsonValue::from(i),
+            ValueRef::Real(f) => serde_json::Number::from_f64(f).map(JsonValue::Number).unwrap_or(JsonValue::Null),
+            ValueRef::Text(text) => JsonValue::String(String::from_utf8_lossy(text).into_owned()),
+            ValueRef::Blob(bytes) => JsonValue::String(bytes.iter().map(|b| format!("{:02x}", b)).collect()),
+        }
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册SQLite命名空间下的函数
+    let sqlite_ns = registry.namespace("sqlite");
+    sqlite_ns.add_function("open", sqlite::cn_open)
+             .add_function("close", sqlite::cn_close)
+             .add_function("exec", sqlite::cn_exec)
+             .add_function("query", sqlite::cn_query)
+             .add_function("query_one", sqlite::cn_query_one)
+             .add_function("last_insert_id", sqlite::cn_last_insert_id)
+             .add_function("begin", sqlite::cn_begin)
+             .add_function("commit", sqlite::cn_commit)
+             .add_function("rollback", sqlite::cn_rollback)
+             .add_function("in_transaction", sqlite::cn_in_transaction)
+             .add_function("prepare", sqlite::cn_prepare)
+             .add_function("stmt_exec", sqlite::cn_stmt_exec)
+             .add_function("stmt_query", sqlite::cn_stmt_query)
+             .add_function("finalize", sqlite::cn_finalize);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
@@ -0,0 +1,150 @@
+use ::std::collections::HashMap;
+use handlebars::Handlebars;
+use serde_json::{Map, Value as JsonValue};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// 导入通用库
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+
+// 模板命名空间
+// 使用 Handlebars 语法: {{name}} 输出变量（默认进行HTML转义），{{{name}}} 输出原始内容，
+// {{#if cond}}...{{else}}...{{/if}} 条件，{{#each items}}...{{/each}} 循环（{{this}}、{{@index}}、{{@key}}），
+// {{> header}} 包含其他模板，还可以使用 eq、ne、gt、lt、and、or、not 等辅助函数
+mod template {
+    use super::*;
+
+    // 包含的模板文件没有扩展名时依次尝试的扩展名
+    const PARTIAL_EXTENSIONS: [&str; 3] = ["hbs", "html", "txt"];
+
+    // 渲染模板字符串
+    // options 是可选的 Map:
+    //   partials: {名称: 模板} 形式的可包含模板,
+    //   dir: 查找 {{> name}} 所引用模板文件的目录（默认为当前目录）,
+    //   escape: 为 false 时不进行HTML转义, strict: 为 true 时引用不存在的变量报错
+    // 参数: template, context（Map）, [options]
+    pub fn cn_render(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供模板".to_string();
+        }
+        let options = match parse_options(args.get(2)) {
+            Ok(options) => options,
+            Err(e) => return e,
+        };
+        let dir = options.get("dir").and_then(|d| d.as_str()).map(PathBuf::from).unwrap_or_else(|| PathBuf::from("."));
+        render(&args[0], args.get(1), &dir, &options).unwrap_or_else(|e| e)
+    }
+
+    // 读取并渲染模板文件，{{> name}} 从模板文件所在目录查找被包含的模板
+    // 参数: path, context（Map）, [options]（同 render）
+    pub fn cn_render_file(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供模板文件路径".to_string();
+        }
+        let options = match parse_options(args.get(2)) {
+            Ok(options) => options,
+            Err(e) => return e,
+        };
+        let template = match fs::read_to_string(&args[0]) {
+            Ok(template) => template,
+            Err(e) => return format!("错误: 无法读取模板 {}: {}", args[0], e),
+        };
+        let dir = match options.get("dir").and_then(|d| d.as_str()) {
+            Some(dir) => PathBuf::from(dir),
+            None => Path::new(&args[0]).parent().map(|p| p.to_path_buf()).unwrap_or_else(|| PathBuf::from(".")),
+        };
+        render(&template, args.get(1), &dir, &options).unwrap_or_else(|e| e)
+    }
+
+    fn parse_options(arg: Option<&String>) -> Result<Map<String, JsonValue>, String> {
+        match arg.filter(|o| !o.trim().is_empty()) {
+            Some(options) => serde_json::from_str(options).map_err(|_| "错误: 选项必须是 Map".to_string()),
+            None => Ok(Map::new()),
+        }
+    }
+
+    fn render(template: &str, context: Option<&String>, dir: &Path, options: &Map<String, JsonValue>) -> Result<String, String> {
+        let context = match context.filter(|c| !c.trim().is_empty()) {
+            Some(context) => serde_json::from_str::<JsonValue>(context).map_err(|_| "错误: 上下文必须是 Map".to_string())?,
+            None => JsonValue::Object(Map::new()),
+        };
+
+        let mut engine = Handlebars::new();
+        engine.set_strict_mode(options.get("strict").and_then(|s| s.as_bool()).unwrap_or(false));
+        if options.get("escape").and_then(|e| e.as_bool()) == Some(false) {
+            engine.register_escape_fn(handlebars::no_escape);
+        }
+
+        if let Some(JsonValue::Object(partials)) = options.get("partials") {
+            for (name, partial) in partials {
+                let partial = partial.as_str().ok_or_else(|| format!("错误: 被包含的模板 {} 必须是字符串", name))?;
+                engine.register_partial(name, partial).map_err(|e| format!("错误: 模板 {} 有语法错误: {}", name, e))?;
+            }
+        }
+        register_file_partials(&mut engine, template, dir)?;
+
+        engine.render_template(template, &context).map_err(|e| format!("错误: 渲染失败: {}", e))
+    }
+
+    // 注册模板中引用、尚未注册且能在目录中找到的模板文件，包括被包含模板再引用的模板
+    fn register_file_partials(engine: &mut Handlebars, template: &str, dir: &Path) -> Result<(), String> {
+        let mut pending: Vec<String> = partial_names(template);
+        while let Some(name) = pending.pop() {
+            if engine.has_template(&name) {
+                continue;
+            }
+            let path = match find_partial(dir, &name) {
+                Some(path) => path,
+                // 找不到时由渲染过程报告错误
+                None => continue,
+            };
+            let partial = fs::read_to_string(&path).map_err(|e| format!("错误: 无法读取模板 {}: {}", path.display(), e))?;
+            engine.register_partial(&name, &partial).map_err(|e| format!("错误: 模板 {} 有语法错误: {}", name, e))?;
+            pending.extend(partial_names(&partial));
+        }
+        Ok(())
+    }
+
+    // 找出 {{> name}} 和 {{#> name}} 中的模板名称，忽略动态名称
+    fn partial_names(template: &str) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            rest = &rest[start + 2..];
+            let tag = rest.trim_start_matches(['~', '#']).trim_start();
+            if let Some(tag) = tag.strip_prefix('>') {
+                let name: String = tag.trim_start().chars().take_while(|c| !c.is_whitespace() && *c != '}' && *c != '~').collect();
+                let name = name.trim_matches(|c| c == '"' || c == '\'');
+                if !name.is_empty() && !name.starts_with('(') {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names
+    }
+
+    fn find_partial(dir: &Path, name: &str) -> Option<PathBuf> {
+        let path = dir.join(name);
+        if path.is_file() {
+            return Some(path);
+        }
+        PARTIAL_EXTENSIONS.iter()
+            .map(|ext| dir.join(format!("{}.{}", name, ext)))
+            .find(|p| p.is_file())
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册模板命名空间下的函数
+    let template_ns = registry.namespace("template");
+    template_ns.add_function("render", template::cn_render)
+               .add_function("render_file", template::cn_render_file);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
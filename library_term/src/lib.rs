@@ -0,0 +1,262 @@
+use ::std::collections::HashMap;
+use crossterm::cursor::{Hide, MoveDown, MoveLeft, MoveRight, MoveTo, MoveToColumn, MoveUp, Show};
+use crossterm::terminal::{self, Clear, ClearType};
+use crossterm::{execute, Command};
+use serde_json::{Map, Value as JsonValue};
+use std::io;
+
+// 导入通用库
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::progress::{self, ProgressBar};
+use cn_common::value::json_value;
+
+// 终端命名空间
+// 光标和清屏操作直接写入标准输出；旋转指示器和进度条写入标准错误，标准错误不是终端时不显示
+mod term {
+    use super::*;
+    use cn_common::term::{color_enabled, style_code};
+    pub use cn_common::term::{cn_is_tty, cn_set_color};
+
+    // 给文本加上颜色和样式，关闭颜色输出时原样返回
+    // styles 用逗号或空格分隔，可以是颜色名（red、bright_red 等）、bg_颜色名、#rrggbb、bg#rrggbb，
+    // 以及 bold、dim、italic、underline、blink、reverse、hidden、strikethrough
+    // 参数: text, styles
+    pub fn cn_style(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供文本".to_string();
+        }
+        let mut codes = Vec::new();
+        for name in args[1..].iter().flat_map(|s| s.split([',', ' '])).filter(|s| !s.is_empty()) {
+            match style_code(&name.to_lowercase()) {
+                Some(code) => codes.push(code),
+                None => return format!("错误: 未知的样式: {}", name),
+            }
+        }
+        if codes.is_empty() || !color_enabled() {
+            return args[0].clone();
+        }
+        format!("\x1b[{}m{}\x1b[0m", codes.join(";"), args[0])
+    }
+
+    // 去掉文本中的ANSI转义序列
+    // 参数: text
+    pub fn cn_strip(args: Vec<String>) -> String {
+        let text = args.first().map(|t| t.as_str()).unwrap_or("");
+        let mut result = String::with_capacity(text.len());
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' && chars.peek() == Some(&'[') {
+                chars.next();
+                // 跳过参数，直到终止字母
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            } else {
+                result.push(c);
+            }
+        }
+        result
+    }
+
+    // 清除整个屏幕并把光标移到左上角
+    // 参数: 无
+    pub fn cn_clear(_args: Vec<String>) -> String {
+        write_result(execute!(io::stdout(), Clear(ClearType::All), MoveTo(0, 0)))
+    }
+
+    // 清除光标所在的行并把光标移到行首
+    // 参数: 无
+    pub fn cn_clear_line(_args: Vec<String>) -> String {
+        write_result(execute!(io::stdout(), Clear(ClearType::CurrentLine), MoveToColumn(0)))
+    }
+
+    // 把光标移到指定位置，行和列从0开始
+    // 参数: row, col
+    pub fn cn_move_to(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供行和列".to_string();
+        }
+        match (args[0].trim().parse::<u16>(), args[1].trim().parse::<u16>()) {
+            (Ok(row), Ok(col)) => run(MoveTo(col, row)),
+            _ => "错误: 行和列必须是非负整数".to_string(),
+        }
+    }
+
+    // 把光标向上移动n行
+    // 参数: [n]（默认1）
+    pub fn cn_move_up(args: Vec<String>) -> String {
+        match parse_count(args.first()) {
+            Ok(n) => run(MoveUp(n)),
+            Err(e) => e,
+        }
+    }
+
+    // 把光标向下移动n行
+    // 参数: [n]（默认1）
+    pub fn cn_move_down(args: Vec<String>) -> String {
+        match parse_count(args.first()) {
+            Ok(n) => run(MoveDown(n)),
+            Err(e) => e,
+        }
+    }
+
+    // 把光标向左移动n列
+    // 参数: [n]（默认1）
+    pub fn cn_move_left(args: Vec<String>) -> String {
+        match parse_count(args.first()) {
+            Ok(n) => run(MoveLeft(n)),
+            Err(e) => e,
+        }
+    }
+
+    // 把光标向右移动n列
+    // 参数: [n]（默认1）
+    pub fn cn_move_right(args: Vec<String>) -> String {
+        match parse_count(args.first()) {
+            Ok(n) => run(MoveRight(n)),
+            Err(e) => e,
+        }
+    }
+
+    // 隐藏光标
+    // 参数: 无
+    pub fn cn_hide_cursor(_args: Vec<String>) -> String {
+        run(Hide)
+    }
+
+    // 显示光标
+    // 参数: 无
+    pub fn cn_show_cursor(_args: Vec<String>) -> String {
+        run(Show)
+    }
+
+    // 获取终端大小，返回 Map: {"cols": 列数, "rows": 行数}
+    // 参数: 无
+    pub fn cn_size(_args: Vec<String>) -> String {
+        match terminal::size() {
+            Ok((cols, rows)) => {
+                let mut obj = Map::new();
+                obj.insert("cols".to_string(), JsonValue::from(cols));
+                obj.insert("rows".to_string(), JsonValue::from(rows));
+                json_value(&JsonValue::Object(obj).to_string())
+            },
+            Err(e) => format!("错误: 无法获取终端大小: {}", e),
+        }
+    }
+
+    // 启动旋转指示器，在后台持续转动直到 finish，返回句柄
+    // 参数: [message]
+    pub fn cn_spinner(args: Vec<String>) -> String {
+        progress::spinner(args.first().cloned().unwrap_or_default()).to_string()
+    }
+
+    // 创建进度条，返回句柄
+    // 参数: total, [label]
+    pub fn cn_progress(args: Vec<String>) -> String {
+        let total = match args.first().map(|t| t.trim().parse::<u64>()) {
+            Some(Ok(total)) => total,
+            _ => return "错误: 总数必须是非负整数".to_string(),
+        };
+        match progress::bar(total, args.get(1).map(String::as_str), String::new()) {
+            Ok(handle) => handle.to_string(),
+            Err(e) => e,
+        }
+    }
+
+    // 设置进度条的当前值
+    // 参数: bar, value
+    pub fn cn_progress_set(args: Vec<String>) -> String {
+        let value = match args.get(1).map(|v| v.trim().parse::<u64>()) {
+            Some(Ok(value)) => value,
+            _ => return "错误: 进度值必须是非负整数".to_string(),
+        };
+        with_bar(args.first(), |bar| bar.set_position(value))
+    }
+
+    // 增加进度条的当前值
+    // 参数: bar, [delta]（默认1）
+    pub fn cn_progress_inc(args: Vec<String>) -> String {
+        let delta = match args.get(1).map(|d| d.trim().parse::<u64>()) {
+            Some(Ok(delta)) => delta,
+            Some(Err(_)) => return "错误: 增量必须是非负整数".to_string(),
+            None => 1,
+        };
+        with_bar(args.first(), |bar| bar.inc(delta))
+    }
+
+    // 设置旋转指示器或进度条后面显示的消息
+    // 参数: handle, message
+    pub fn cn_set_message(args: Vec<String>) -> String {
+        let message = args.get(1).cloned().unwrap_or_default();
+        with_bar(args.first(), |bar| bar.set_message(message))
+    }
+
+    // 结束旋转指示器或进度条，句柄随后失效；提供消息时保留最后一行并显示消息，否则清除该行
+    // 参数: handle, [message]
+    pub fn cn_finish(args: Vec<String>) -> String {
+        let message = args.get(1).filter(|m| !m.is_empty()).cloned();
+        progress::finish(args.first(), message, false).to_string()
+    }
+
+    fn with_bar<F: FnOnce(&ProgressBar)>(handle: Option<&String>, f: F) -> String {
+        match progress::with_bar(handle, f) {
+            Some(()) => "true".to_string(),
+            None => "错误: 无效的进度条句柄".to_string(),
+        }
+    }
+
+    // 向标准输出写入终端控制命令
+    fn run<C: Command>(command: C) -> String {
+        write_result(execute!(io::stdout(), command))
+    }
+
+    fn write_result(result: io::Result<()>) -> String {
+        match result {
+            Ok(()) => "true".to_string(),
+            Err(e) => format!("错误: 无法写入终端: {}", e),
+        }
+    }
+
+    fn parse_count(arg: Option<&String>) -> Result<u16, String> {
+        match arg.map(|n| n.trim().parse::<u16>()) {
+            Some(Ok(n)) => Ok(n),
+            Some(Err(_)) => Err("错误: 移动距离必须是非负整数".to_string()),
+            None => Ok(1),
+        }
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册终端命名空间下的函数
+    let term_ns = registry.namespace("term");
+    term_ns.add_function("style", term::cn_style)
+           .add_function("strip", term::cn_strip)
+           .add_function("set_color", term::cn_set_color)
+           .add_function("clear", term::cn_clear)
+           .add_function("clear_line", term::cn_clear_line)
+           .add_function("move_to", term::cn_move_to)
+           .add_function("move_up", term::cn_move_up)
+           .add_function("move_down", term::cn_move_down)
+           .add_function("move_left", term::cn_move_left)
+           .add_function("move_right", term::cn_move_right)
+           .add_function("hide_cursor", term::cn_hide_cursor)
+           .add_function("show_cursor", term::cn_show_cursor)
+           .add_function("size", term::cn_size)
+           .add_function("is_tty", term::cn_is_tty)
+           .add_function("spinner", term::cn_spinner)
+           .add_function("progress", term::cn_progress)
+           .add_function("progress_set", term::cn_progress_set)
+           .add_function("progress_inc", term::cn_progress_inc)
+           .add_function("set_message", term::cn_set_message)
+           .add_function("finish", term::cn_finish);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
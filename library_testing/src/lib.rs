@@ -0,0 +1,224 @@
+use ::std::collections::HashMap;
+use serde_json::{json, Value as JsonValue};
+use std::env;
+use std::io::Write;
+use std::process;
+use std::sync::{Mutex, MutexGuard};
+
+// 导入通用库
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::{callback_value, json_value, returned_json, thrown_message};
+
+// 测试命名空间
+// 断言函数通过时返回 true，失败时把失败信息输出到标准错误并返回 false，脚本可以继续运行；
+// 环境变量 CN_TEST_FORMAT=json 时失败信息和结果汇总按每行一个JSON对象输出，供测试运行器解析
+// 所有断言的结果都会被记录，summary 返回汇总，done 输出汇总并在有失败时以退出码1结束脚本
+mod testing {
+    use super::*;
+
+    // expect_error 的函数返回后，解释器调用的续接函数
+    const RESUME_FUNCTION: &str = "testing::resume";
+
+    struct Tally {
+        passed: u64,
+        failures: Vec<JsonValue>,
+        // 等待回调结果的 expect_error 的说明，回调中可以再调用 expect_error
+        expecting: Vec<String>,
+    }
+
+    static TALLY: Mutex<Tally> = Mutex::new(Tally { passed: 0, failures: Vec::new(), expecting: Vec::new() });
+
+    fn lock_tally() -> MutexGuard<'static, Tally> {
+        TALLY.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // 断言两个值相等；Map 和数组按结构比较（忽略键的顺序），其他值按字符串形式比较
+    // 参数: actual, expected, [message]
+    pub fn cn_assert_eq(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供实际值和期望值".to_string();
+        }
+        let passed = match (parse_structured(&args[0]), parse_structured(&args[1])) {
+            (Some(actual), Some(expected)) => actual == expected,
+            _ => args[0] == args[1],
+        };
+        record("assert_eq", passed, args.get(2), Some(&args[1]), Some(&args[0]))
+    }
+
+    // 断言值为 true
+    // 参数: value, [message]
+    pub fn cn_assert_true(args: Vec<String>) -> String {
+        let actual = args.first().cloned().unwrap_or_default();
+        record("assert_true", actual == "true", args.get(1), Some(&"true".to_string()), Some(&actual))
+    }
+
+    // 断言两个数的差的绝对值不超过 eps
+    // 参数: actual, expected, eps, [message]
+    pub fn cn_assert_close(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "错误: 请提供实际值、期望值和允许误差".to_string();
+        }
+        let numbers: Vec<f64> = match args[..3].iter().map(|a| a.trim().parse::<f64>()).collect() {
+            Ok(numbers) => numbers,
+            Err(_) => return format!("错误: 实际值、期望值和允许误差必须是数字: {}, {}, {}", args[0], args[1], args[2]),
+        };
+        let passed = (numbers[0] - numbers[1]).abs() <= numbers[2].abs();
+        let expected = format!("{} ± {}", args[1], args[2]);
+        record("assert_close", passed, args.get(3), Some(&expected), Some(&args[0]))
+    }
+
+    // 记录一次失败
+    // 参数: message
+    pub fn cn_fail(args: Vec<String>) -> String {
+        record("fail", false, args.first(), None, None)
+    }
+
+    // 调用函数并断言它抛出异常或返回以 "错误" 开头的字符串；函数可以是函数名、函数指针或Lambda
+    // 函数及其调用的函数中未被 try/catch 捕获的异常都会记录为抛出
+    // 参数: function, [args...]
+    pub fn cn_expect_error(args: Vec<String>) -> String {
+        let function = match args.first() {
+            Some(function) => function.clone(),
+            None => return "错误: 请提供要调用的函数".to_string(),
+        };
+        // 参数按原来的类型传回：数字、布尔值、Map 和数组以JSON形式传入
+        let args_json = JsonValue::Array(
+            args[1..].iter().map(|a| serde_json::from_str(a).unwrap_or_else(|_| JsonValue::String(a.clone()))).collect()
+        ).to_string();
+        lock_tally().expecting.push(function.clone());
+        callback_value(&function, &args_json, RESUME_FUNCTION)
+    }
+
+    // expect_error 的函数返回后由解释器调用，记录断言结果；以续接函数注册，脚本无法直接调用
+    // 参数: returned
+    pub fn cn_resume(args: Vec<String>) -> String {
+        let returned = args.first().map(|s| s.as_str()).unwrap_or("");
+        // 返回 Map 或数组时去掉结构化前缀，只显示JSON
+        let returned = returned_json(returned).unwrap_or(returned);
+        let function = lock_tally().expecting.pop().unwrap_or_default();
+        let passed = thrown_message(returned).is_some() || returned.starts_with("错误");
+        // 函数以 *fn(name) 或 function_ref(name) 的形式传入
+        let name = function.strip_prefix("*fn(")
+            .or_else(|| function.strip_prefix("function_ref("))
+            .and_then(|name| name.strip_suffix(')'))
+            .unwrap_or(&function);
+        let name = if name.starts_with("lambda#") { "lambda" } else { name };
+        let message = format!("{} 应当抛出异常或返回错误", name);
+        record("expect_error", passed, Some(&message), Some(&"异常或错误".to_string()), Some(&returned.to_string()))
+    }
+
+    // 获取结果汇总，返回 {"passed", "failed", "failures": [失败信息]}
+    // 参数: 无
+    pub fn cn_summary(_args: Vec<String>) -> String {
+        json_value(&summary(&lock_tally()).to_string())
+    }
+
+    // 清空已记录的结果
+    // 参数: 无
+    pub fn cn_reset(_args: Vec<String>) -> String {
+        let mut tally = lock_tally();
+        tally.passed = 0;
+        tally.failures.clear();
+        "true".to_string()
+    }
+
+    // 输出结果汇总，有失败时以退出码1结束脚本，全部通过时返回 true
+    // 参数: 无
+    pub fn cn_done(_args: Vec<String>) -> String {
+        let tally = lock_tally();
+        let failed = tally.failures.len();
+        if json_format() {
+            let mut report = summary(&tally);
+            report["event"] = JsonValue::from("summary");
+            eprintln!("{}", report);
+        } else {
+            eprintln!("测试结果: {} 通过, {} 失败", tally.passed, failed);
+        }
+        if failed > 0 {
+            let _ = std::io::stdout().flush();
+            process::exit(1);
+        }
+        "true".to_string()
+    }
+
+    fn record(assertion: &str, passed: bool, message: Option<&String>, expected: Option<&String>, actual: Option<&String>) -> String {
+        let mut tally = lock_tally();
+        if passed {
+            tally.passed += 1;
+            return "true".to_string();
+        }
+
+        let failure = json!({
+            "assertion": assertion,
+            "message": message.cloned().unwrap_or_default(),
+            "expected": expected,
+            "actual": actual.map(|a| display_value(a)),
+        });
+        if json_format() {
+            let mut event = failure.clone();
+            event["event"] = JsonValue::from("fail");
+            eprintln!("{}", event);
+        } else {
+            let mut line = format!("断言失败 [{}]", assertion);
+            if let Some(message) = message.filter(|m| !m.is_empty()) {
+                line.push_str(&format!(" {}", message));
+            }
+            if let (Some(expected), Some(actual)) = (expected, actual) {
+                line.push_str(&format!("\n  期望: {}\n  实际: {}", expected, display_value(actual)));
+            }
+            eprintln!("{}", line);
+        }
+        tally.failures.push(failure);
+        "false".to_string()
+    }
+
+    fn summary(tally: &Tally) -> JsonValue {
+        json!({
+            "passed": tally.passed,
+            "failed": tally.failures.len(),
+            "failures": tally.failures,
+        })
+    }
+
+    fn json_format() -> bool {
+        env::var("CN_TEST_FORMAT").is_ok_and(|format| format.eq_ignore_ascii_case("json"))
+    }
+
+    // Map 和数组以JSON形式传入，其他值不按结构比较
+    fn parse_structured(value: &str) -> Option<JsonValue> {
+        match serde_json::from_str::<JsonValue>(value) {
+            Ok(parsed @ (JsonValue::Object(_) | JsonValue::Array(_))) => Some(parsed),
+            _ => None,
+        }
+    }
+
+    // 异常以 "异常: 信息" 的形式显示
+    fn display_value(value: &str) -> String {
+        match thrown_message(value) {
+            Some(message) => format!("异常: {}", message),
+            None => value.to_string(),
+        }
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册测试命名空间下的函数
+    let testing_ns = registry.namespace("testing");
+    testing_ns.add_function("assert_eq", testing::cn_assert_eq)
+              .add_function("assert_true", testing::cn_assert_true)
+              .add_function("assert_close", testing::cn_assert_close)
+              .add_function("fail", testing::cn_fail)
+              .add_function("expect_error", testing::cn_expect_error)
+              .add_continuation("resume", testing::cn_resume)
+              .add_function("summary", testing::cn_summary)
+              .add_function("reset", testing::cn_reset)
+              .add_function("done", testing::cn_done);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
@@ -1,10 +1,16 @@
 use ::std::collections::HashMap;
+use ::std::sync::{Mutex, MutexGuard, OnceLock};
 use ::std::thread;
-use ::std::time::Duration as StdDuration;
-use chrono::{Local, Utc, DateTime, Datelike, Timelike, Duration};
+use ::std::time::{Duration as StdDuration, Instant};
+use chrono::{Local, Utc, DateTime, Datelike, Timelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use chrono::SecondsFormat;
+use chrono_tz::{OffsetComponents, OffsetName, Tz};
+use serde_json::{json, Value as JsonValue};
 
 // 导入通用库
-use cn_common::namespace::{LibraryFunction, NamespaceBuilder, create_library_pointer, LibraryRegistry};
+use cn_common::handle::HandleTable;
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::json_value;
 
 // 命名空间函数
 mod std {
@@ -44,6 +50,26 @@ mod std {
         millis.to_string()
     }
     
+    // 获取当前的微秒时间戳，作为长整数返回
+    pub fn cn_now_micros(_args: Vec<String>) -> String {
+        json_value(&Utc::now().timestamp_micros().to_string())
+    }
+    
+    // 获取当前的纳秒时间戳，作为长整数返回，实际精度取决于系统时钟
+    pub fn cn_now_nanos(_args: Vec<String>) -> String {
+        match Utc::now().timestamp_nanos_opt() {
+            Some(nanos) => json_value(&nanos.to_string()),
+            None => "错误: 时间超出纳秒时间戳的范围".to_string(),
+        }
+    }
+    
+    // 获取单调时钟的秒数（带小数），不受系统时间调整的影响；起点不确定，只用于计算两次调用之间的差值
+    pub fn cn_perf_counter(_args: Vec<String>) -> String {
+        static ORIGIN: OnceLock<Instant> = OnceLock::new();
+        let origin = *ORIGIN.get_or_init(Instant::now);
+        format!("{:.9}", origin.elapsed().as_secs_f64())
+    }
+    
     // 格式化当前本地时间
     // 参数: [format]，默认为 "%Y-%m-%d %H:%M:%S"
     pub fn cn_format_now(args: Vec<String>) -> String {
@@ -80,6 +106,164 @@ mod std {
         dt.format(format).to_string()
     }
     
+    // 按格式解析日期时间，返回时间戳（秒），是 format_timestamp 的逆操作
+    // 格式中没有时区偏移（%z）时按 zone 解释，默认为UTC；格式中只有日期时取当天0点
+    // 参数: datetime, format, [zone]（IANA时区名称或 "local"）
+    pub fn cn_parse(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 需要两个参数 (日期时间, 格式)".to_string();
+        }
+        
+        let text = args[0].trim();
+        let format = &args[1];
+        if let Ok(time) = DateTime::parse_from_str(text, format) {
+            return time.timestamp().to_string();
+        }
+        let naive = match NaiveDateTime::parse_from_str(text, format) {
+            Ok(naive) => naive,
+            Err(e) => match NaiveDate::parse_from_str(text, format) {
+                Ok(date) => date.and_time(NaiveTime::MIN),
+                Err(_) => return format!("错误: 无法按格式 {} 解析 {}: {}", format, text, e),
+            },
+        };
+        match args.get(2).filter(|z| !z.trim().is_empty()) {
+            Some(zone) => match tz::parse_zone(zone) {
+                Ok(zone) => match zone.from_local_datetime(&naive).earliest() {
+                    Some(time) => time.timestamp().to_string(),
+                    None => format!("错误: 时间在时区 {} 中不存在: {}", zone.name(), text),
+                },
+                Err(e) => e,
+            },
+            None => naive.and_utc().timestamp().to_string(),
+        }
+    }
+    
+    // 解析ISO 8601日期时间，返回时间戳（秒），有不足一秒的部分时为小数
+    // 支持 "2024-01-02T03:04:05Z"、"2024-01-02T03:04:05.123+08:00"、"2024-01-02 03:04:05"、"2024-01-02" 等形式，
+    // 没有时区偏移时按UTC解释
+    // 参数: datetime
+    pub fn cn_parse_iso(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 缺少日期时间参数".to_string();
+        }
+        
+        let text = args[0].trim();
+        if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+            return timestamp_value(&time);
+        }
+        // 基本格式和省略秒的偏移形式
+        for format in ["%Y-%m-%dT%H:%M:%S%.f%z", "%Y%m%dT%H%M%S%.f%z", "%Y-%m-%dT%H:%M%z"] {
+            if let Ok(time) = DateTime::parse_from_str(text, format) {
+                return timestamp_value(&time);
+            }
+        }
+        let text_utc = text.strip_suffix('Z').unwrap_or(text);
+        for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M", "%Y%m%dT%H%M%S"] {
+            if let Ok(naive) = NaiveDateTime::parse_from_str(text_utc, format) {
+                return timestamp_value(&naive.and_utc());
+            }
+        }
+        for format in ["%Y-%m-%d", "%Y%m%d"] {
+            if let Ok(date) = NaiveDate::parse_from_str(text_utc, format) {
+                return date.and_time(NaiveTime::MIN).and_utc().timestamp().to_string();
+            }
+        }
+        format!("错误: 无效的ISO 8601日期时间: {}", text)
+    }
+    
+    // 格式化为ISO 8601（RFC 3339）格式，如 "2024-07-01T12:00:00Z"、"2024-07-01T20:00:00.500+08:00"
+    // 参数: timestamp（秒，可以有小数）, [zone]（默认为UTC）, [precision]（秒的小数位数 0、3、6、9，默认按需要显示）
+    pub fn cn_format_iso(args: Vec<String>) -> String {
+        let time = match timestamp_arg(&args) {
+            Ok(time) => time,
+            Err(e) => return e,
+        };
+        let precision = match args.get(2).map(|p| p.trim()) {
+            Some("auto") | Some("") | None => SecondsFormat::AutoSi,
+            Some("0") => SecondsFormat::Secs,
+            Some("3") => SecondsFormat::Millis,
+            Some("6") => SecondsFormat::Micros,
+            Some("9") => SecondsFormat::Nanos,
+            Some(other) => return format!("错误: 小数位数必须是 0、3、6 或 9: {}", other),
+        };
+        time.to_rfc3339_opts(precision, true)
+    }
+    
+    // 格式化为RFC 2822格式（邮件头使用），如 "Mon, 1 Jul 2024 12:00:00 +0000"
+    // 参数: timestamp, [zone]（默认为UTC）
+    pub fn cn_format_rfc2822(args: Vec<String>) -> String {
+        match timestamp_arg(&args) {
+            Ok(time) => time.to_rfc2822(),
+            Err(e) => e,
+        }
+    }
+    
+    // 格式化为HTTP日期（RFC 9110 的 IMF-fixdate），如 "Mon, 01 Jul 2024 12:00:00 GMT"，总是使用UTC
+    // 参数: timestamp
+    pub fn cn_format_http_date(args: Vec<String>) -> String {
+        match timestamp_arg(&args[..args.len().min(1)]) {
+            Ok(time) => time.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+            Err(e) => e,
+        }
+    }
+    
+    // 解析RFC 3339格式，必须包含时区偏移，返回时间戳（秒），有不足一秒的部分时为小数
+    // 参数: datetime
+    pub fn cn_parse_rfc3339(args: Vec<String>) -> String {
+        let text = args.first().map(|t| t.trim()).unwrap_or("");
+        match DateTime::parse_from_rfc3339(text) {
+            Ok(time) => timestamp_value(&time),
+            Err(e) => format!("错误: 无效的RFC 3339日期时间 {}: {}", text, e),
+        }
+    }
+    
+    // 解析RFC 2822格式，也接受HTTP日期，返回时间戳（秒）
+    // 参数: datetime
+    pub fn cn_parse_rfc2822(args: Vec<String>) -> String {
+        let text = args.first().map(|t| t.trim()).unwrap_or("");
+        match DateTime::parse_from_rfc2822(text) {
+            Ok(time) => timestamp_value(&time),
+            Err(e) => format!("错误: 无效的RFC 2822日期时间 {}: {}", text, e),
+        }
+    }
+    
+    // 解析秒级时间戳（可以有小数）和可选的时区参数
+    fn timestamp_arg(args: &[String]) -> Result<DateTime<Tz>, String> {
+        let text = args.first().map(|t| t.trim()).unwrap_or("");
+        // 整数部分和小数部分分开解析，避免浮点数的精度损失
+        let (seconds, fraction) = text.split_once('.').unwrap_or((text, ""));
+        let seconds = seconds.parse::<i64>().map_err(|_| format!("错误: 无效的时间戳: {}", text))?;
+        if fraction.len() > 9 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("错误: 无效的时间戳: {}", text));
+        }
+        let mut nanos = format!("{:0<9}", fraction).parse::<u32>().unwrap_or(0);
+        // 负时间戳的小数部分向更早的方向取
+        let seconds = if text.starts_with('-') && nanos > 0 {
+            nanos = 1_000_000_000 - nanos;
+            seconds - 1
+        } else {
+            seconds
+        };
+        let zone = match args.get(1).filter(|z| !z.trim().is_empty()) {
+            Some(zone) => tz::parse_zone(zone)?,
+            None => Tz::UTC,
+        };
+        DateTime::from_timestamp(seconds, nanos)
+            .map(|time| time.with_timezone(&zone))
+            .ok_or_else(|| "错误: 无法创建日期时间对象".to_string())
+    }
+    
+    // 时间戳（秒），没有不足一秒的部分时为整数
+    fn timestamp_value<Z: TimeZone>(time: &DateTime<Z>) -> String {
+        match time.timestamp_subsec_nanos() {
+            0 => time.timestamp().to_string(),
+            nanos if time.timestamp() < 0 => {
+                format!("-{}.{:09}", -(time.timestamp() + 1), 1_000_000_000 - nanos).trim_end_matches('0').to_string()
+            },
+            nanos => format!("{}.{:09}", time.timestamp(), nanos).trim_end_matches('0').to_string(),
+        }
+    }
+    
     // 获取当前年份
     pub fn cn_year(_args: Vec<String>) -> String {
         Local::now().year().to_string()
@@ -250,6 +434,608 @@ mod std {
     }
 }
 
+// 时区命名空间
+// 时区使用IANA名称（如 "Asia/Shanghai"、"America/New_York"、"UTC"），"local" 表示系统时区
+// 时间参数可以是时间戳（秒），也可以是 "%Y-%m-%d %H:%M:%S" 格式的日期时间（按所在时区解释）或 RFC 3339 格式
+mod tz {
+    use super::*;
+    
+    const DEFAULT_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+    
+    // 获取时区的当前时间
+    // 参数: zone, [format]，默认为 "%Y-%m-%d %H:%M:%S"
+    pub fn cn_now(args: Vec<String>) -> String {
+        let zone = match args.first().map(|z| parse_zone(z)) {
+            Some(Ok(zone)) => zone,
+            Some(Err(e)) => return e,
+            None => return "错误: 缺少时区参数".to_string(),
+        };
+        let format = args.get(1).map(|f| f.as_str()).unwrap_or(DEFAULT_FORMAT);
+        Utc::now().with_timezone(&zone).format(format).to_string()
+    }
+    
+    // 把时间从一个时区转换到另一个时区，返回格式化的时间
+    // 参数: time, from, to, [format]
+    pub fn cn_convert(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "错误: 需要三个参数 (时间, 原时区, 目标时区)".to_string();
+        }
+        let (from, to) = match (parse_zone(&args[1]), parse_zone(&args[2])) {
+            (Ok(from), Ok(to)) => (from, to),
+            (Err(e), _) | (_, Err(e)) => return e,
+        };
+        let format = args.get(3).map(|f| f.as_str()).unwrap_or(DEFAULT_FORMAT);
+        match parse_time(&args[0], from) {
+            Ok(time) => time.with_timezone(&to).format(format).to_string(),
+            Err(e) => e,
+        }
+    }
+    
+    // 获取时间在时区中的各个部分，返回 {"year", "month", "day", "hour", "minute", "second",
+    // "weekday"（1-7，周一为1）, "timestamp", "offset"（相对UTC的秒数）, "dst", "abbreviation", "zone", "formatted"}
+    // 参数: time, zone
+    pub fn cn_components(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 需要两个参数 (时间, 时区)".to_string();
+        }
+        let zone = match parse_zone(&args[1]) {
+            Ok(zone) => zone,
+            Err(e) => return e,
+        };
+        let time = match parse_time(&args[0], zone) {
+            Ok(time) => time,
+            Err(e) => return e,
+        };
+        let offset = time.offset();
+        let result = json!({
+            "year": time.year(),
+            "month": time.month(),
+            "day": time.day(),
+            "hour": time.hour(),
+            "minute": time.minute(),
+            "second": time.second(),
+            "weekday": time.weekday().number_from_monday(),
+            "timestamp": time.timestamp(),
+            "offset": (offset.base_utc_offset() + offset.dst_offset()).num_seconds(),
+            "dst": !offset.dst_offset().is_zero(),
+            "abbreviation": offset.abbreviation().unwrap_or(""),
+            "zone": zone.name(),
+            "formatted": time.format(DEFAULT_FORMAT).to_string(),
+        });
+        json_value(&result.to_string())
+    }
+    
+    // 获取时区在指定时间相对UTC的偏移秒数（包括夏令时）
+    // 参数: zone, [time]（默认为现在）
+    pub fn cn_offset(args: Vec<String>) -> String {
+        match zone_time(&args) {
+            Ok(time) => {
+                let offset = time.offset();
+                (offset.base_utc_offset() + offset.dst_offset()).num_seconds().to_string()
+            },
+            Err(e) => e,
+        }
+    }
+    
+    // 判断时区在指定时间是否处于夏令时
+    // 参数: zone, [time]（默认为现在）
+    pub fn cn_is_dst(args: Vec<String>) -> String {
+        match zone_time(&args) {
+            Ok(time) => (!time.offset().dst_offset().is_zero()).to_string(),
+            Err(e) => e,
+        }
+    }
+    
+    // 获取系统时区的IANA名称
+    pub fn cn_local(_args: Vec<String>) -> String {
+        match iana_time_zone::get_timezone() {
+            Ok(name) => name,
+            Err(e) => format!("错误: 无法获取系统时区: {}", e),
+        }
+    }
+    
+    // 判断时区名称是否有效
+    // 参数: zone
+    pub fn cn_valid(args: Vec<String>) -> String {
+        args.first().map(|zone| parse_zone(zone).is_ok()).unwrap_or(false).to_string()
+    }
+    
+    // 列出所有时区名称
+    // 参数: [prefix]（只列出以此开头的时区，如 "Asia/"）
+    pub fn cn_list(args: Vec<String>) -> String {
+        let prefix = args.first().map(|p| p.as_str()).unwrap_or("");
+        let names: Vec<JsonValue> = chrono_tz::TZ_VARIANTS
+            .iter()
+            .map(|zone| zone.name())
+            .filter(|name| name.starts_with(prefix))
+            .map(JsonValue::from)
+            .collect();
+        json_value(&JsonValue::Array(names).to_string())
+    }
+    
+    pub(super) fn parse_zone(name: &str) -> Result<Tz, String> {
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("local") {
+            let local = iana_time_zone::get_timezone().map_err(|e| format!("错误: 无法获取系统时区: {}", e))?;
+            return local.parse::<Tz>().map_err(|_| format!("错误: 未知的系统时区: {}", local));
+        }
+        name.parse::<Tz>().map_err(|_| format!("错误: 未知的时区: {}", name))
+    }
+    
+    // 解析时间参数，日期时间按 zone 解释；夏令时切换时重复的时间取较早的一个，不存在的时间返回错误
+    fn parse_time(text: &str, zone: Tz) -> Result<DateTime<Tz>, String> {
+        let text = text.trim();
+        if let Ok(timestamp) = text.parse::<i64>() {
+            return DateTime::from_timestamp(timestamp, 0)
+                .map(|time| time.with_timezone(&zone))
+                .ok_or_else(|| "错误: 无效的时间戳".to_string());
+        }
+        if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+            return Ok(time.with_timezone(&zone));
+        }
+        let naive = NaiveDateTime::parse_from_str(text, DEFAULT_FORMAT)
+            .map_err(|_| format!("错误: 无效的时间: {}", text))?;
+        zone.from_local_datetime(&naive)
+            .earliest()
+            .ok_or_else(|| format!("错误: 时间在时区 {} 中不存在: {}", zone.name(), text))
+    }
+    
+    fn zone_time(args: &[String]) -> Result<DateTime<Tz>, String> {
+        let zone = parse_zone(args.first().ok_or_else(|| "错误: 缺少时区参数".to_string())?)?;
+        match args.get(1).filter(|t| !t.trim().is_empty()) {
+            Some(time) => parse_time(time, zone),
+            None => Ok(Utc::now().with_timezone(&zone)),
+        }
+    }
+}
+
+// 时长命名空间
+// 时长以秒为单位；humanize 的 locale 为 "zh"（默认，如 "2小时3分钟"）或 "en"（如 "2h 3m"）
+mod duration {
+    use super::*;
+    
+    // 单位的秒数和各语言的名称，从大到小排列
+    const UNITS: [(i64, &str, &str); 4] = [
+        (86400, "天", "d"),
+        (3600, "小时", "h"),
+        (60, "分钟", "m"),
+        (1, "秒", "s"),
+    ];
+    
+    // 计算从 ts1 到 ts2 的时长，返回 {"total_seconds", "negative", "days", "hours", "minutes", "seconds"}
+    // ts2 早于 ts1 时 total_seconds 为负数，negative 为 true，各部分仍为非负数
+    // 参数: ts1, ts2
+    pub fn cn_between(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 需要两个时间戳参数".to_string();
+        }
+        let (ts1, ts2) = match (args[0].trim().parse::<i64>(), args[1].trim().parse::<i64>()) {
+            (Ok(ts1), Ok(ts2)) => (ts1, ts2),
+            _ => return "错误: 时间戳必须是整数".to_string(),
+        };
+        let total = ts2 - ts1;
+        let abs = total.abs();
+        let result = json!({
+            "total_seconds": total,
+            "negative": total < 0,
+            "days": abs / 86400,
+            "hours": abs % 86400 / 3600,
+            "minutes": abs % 3600 / 60,
+            "seconds": abs % 60,
+        });
+        json_value(&result.to_string())
+    }
+    
+    // 把秒数转换为易读的时长，省略为0的部分，不足一秒的部分被舍去
+    // 参数: seconds, [locale], [units]（最多显示的部分数，如 2 时 "1天2小时3分钟" 显示为 "1天2小时"，默认全部显示）
+    pub fn cn_humanize(args: Vec<String>) -> String {
+        let seconds = match args.first().map(|s| s.trim().parse::<f64>()) {
+            Some(Ok(seconds)) if seconds.is_finite() => seconds.trunc() as i64,
+            _ => return "错误: 秒数必须是数字".to_string(),
+        };
+        let english = match args.get(1).map(|l| l.trim().to_lowercase()).as_deref() {
+            Some("zh") | Some("") | None => false,
+            Some("en") => true,
+            Some(other) => return format!("错误: 不支持的语言: {}，只支持 zh 和 en", other),
+        };
+        let max_units = match args.get(2).map(|u| u.trim().parse::<usize>()) {
+            Some(Ok(n)) if n > 0 => n,
+            None => UNITS.len(),
+            _ => return "错误: 部分数必须是正整数".to_string(),
+        };
+        
+        let mut rest = seconds.abs();
+        let mut parts = Vec::new();
+        for (unit, zh, en) in UNITS {
+            let count = rest / unit;
+            rest %= unit;
+            if count > 0 && parts.len() < max_units {
+                parts.push(if english { format!("{}{}", count, en) } else { format!("{}{}", count, zh) });
+            }
+        }
+        if parts.is_empty() {
+            parts.push(if english { "0s".to_string() } else { "0秒".to_string() });
+        }
+        let sign = if seconds < 0 { "-" } else { "" };
+        format!("{}{}", sign, parts.join(if english { " " } else { "" }))
+    }
+    
+    // 解析时长字符串，返回秒数；支持 "1h30m"、"1h 30m"、"1.5h"、"500ms"、"2天3小时"、"3分钟20秒" 等形式，纯数字表示秒
+    // 参数: text
+    pub fn cn_parse(args: Vec<String>) -> String {
+        let text = match args.first().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            Some(text) => text,
+            None => return "错误: 缺少时长参数".to_string(),
+        };
+        match parse_duration(text) {
+            Some(seconds) if seconds.fract() == 0.0 => (seconds as i64).to_string(),
+            Some(seconds) => seconds.to_string(),
+            None => format!("错误: 无效的时长: {}", text),
+        }
+    }
+    
+    fn unit_seconds(unit: &str) -> Option<f64> {
+        Some(match unit.to_lowercase().as_str() {
+            "w" | "周" | "星期" => 604800.0,
+            "d" | "day" | "days" | "天" | "日" => 86400.0,
+            "h" | "hr" | "hrs" | "hour" | "hours" | "时" | "小时" => 3600.0,
+            "m" | "min" | "mins" | "minute" | "minutes" | "分" | "分钟" => 60.0,
+            "" | "s" | "sec" | "secs" | "second" | "seconds" | "秒" => 1.0,
+            "ms" | "毫秒" => 0.001,
+            _ => return None,
+        })
+    }
+    
+    fn parse_duration(text: &str) -> Option<f64> {
+        let (sign, text) = match text.strip_prefix('-') {
+            Some(rest) => (-1.0, rest),
+            None => (1.0, text),
+        };
+        let mut total = 0.0;
+        let mut chars = text.chars().peekable();
+        let mut parsed_any = false;
+        loop {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            if chars.peek().is_none() {
+                break;
+            }
+            let mut number = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                number.push(c);
+            }
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            let mut unit = String::new();
+            while let Some(c) = chars.next_if(|c| !c.is_ascii_digit() && *c != '.' && !c.is_whitespace()) {
+                unit.push(c);
+            }
+            let value = number.parse::<f64>().ok()?;
+            total += value * unit_seconds(&unit)?;
+            parsed_any = true;
+        }
+        if parsed_any { Some(sign * total) } else { None }
+    }
+}
+
+// 秒表命名空间
+// 秒表基于单调时钟，不受系统时间调整的影响，适合测量耗时；时间单位为毫秒，保留小数
+mod stopwatch {
+    use super::*;
+    
+    struct Stopwatch {
+        started: Instant,
+        last_lap: Instant,
+    }
+    
+    static STOPWATCHES: Mutex<HandleTable<Stopwatch>> = Mutex::new(HandleTable::new());
+    
+    fn lock_stopwatches() -> MutexGuard<'static, HandleTable<Stopwatch>> {
+        STOPWATCHES.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+    
+    // 开始计时，返回秒表句柄
+    pub fn cn_start(_args: Vec<String>) -> String {
+        let now = Instant::now();
+        lock_stopwatches().insert(Stopwatch { started: now, last_lap: now }).to_string()
+    }
+    
+    // 获取开始计时以来的毫秒数
+    // 参数: handle
+    pub fn cn_elapsed_ms(args: Vec<String>) -> String {
+        with_stopwatch(&args, |stopwatch| millis(stopwatch.started.elapsed()))
+    }
+    
+    // 获取上一次 lap（或开始计时）以来的毫秒数，并从现在开始下一圈
+    // 参数: handle
+    pub fn cn_lap(args: Vec<String>) -> String {
+        with_stopwatch(&args, |stopwatch| {
+            let now = Instant::now();
+            let lap = now - stopwatch.last_lap;
+            stopwatch.last_lap = now;
+            millis(lap)
+        })
+    }
+    
+    // 从现在重新开始计时
+    // 参数: handle
+    pub fn cn_reset(args: Vec<String>) -> String {
+        with_stopwatch(&args, |stopwatch| {
+            let now = Instant::now();
+            stopwatch.started = now;
+            stopwatch.last_lap = now;
+            "true".to_string()
+        })
+    }
+    
+    // 停止计时并释放秒表，返回开始计时以来的毫秒数
+    // 参数: handle
+    pub fn cn_stop(args: Vec<String>) -> String {
+        let mut stopwatches = lock_stopwatches();
+        match args.first().and_then(|arg| stopwatches.parse_handle(arg)).and_then(|id| stopwatches.remove(id)) {
+            Some(stopwatch) => millis(stopwatch.started.elapsed()),
+            None => "错误: 无效的秒表句柄".to_string(),
+        }
+    }
+    
+    fn with_stopwatch<F>(args: &[String], f: F) -> String
+    where
+        F: FnOnce(&mut Stopwatch) -> String,
+    {
+        let mut stopwatches = lock_stopwatches();
+        match args.first().and_then(|arg| stopwatches.parse_handle(arg)).and_then(|id| stopwatches.get_mut(id)) {
+            Some(stopwatch) => f(stopwatch),
+            None => "错误: 无效的秒表句柄".to_string(),
+        }
+    }
+    
+    fn millis(duration: StdDuration) -> String {
+        format!("{:.3}", duration.as_secs_f64() * 1000.0)
+    }
+}
+
+// 日历命名空间
+// 以时间戳为参数的函数按 zone 计算日期，zone 为IANA时区名称或 "local"，默认为UTC（与 format_timestamp 相同）
+// 月份加减时日期超出目标月份的天数会取该月最后一天，如1月31日加一个月为2月28日或29日
+mod cal {
+    use super::*;
+    use chrono::{Days, Months};
+    
+    // 获取某年某月的天数
+    // 参数: year, month
+    pub fn cn_days_in_month(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 需要两个参数 (年, 月)".to_string();
+        }
+        let (year, month) = match (args[0].trim().parse::<i32>(), args[1].trim().parse::<u32>()) {
+            (Ok(year), Ok(month)) if (1..=12).contains(&month) => (year, month),
+            _ => return "错误: 年份必须是整数，月份必须是1到12的整数".to_string(),
+        };
+        match last_day_of_month(year, month) {
+            Some(day) => day.to_string(),
+            None => "错误: 年份超出范围".to_string(),
+        }
+    }
+    
+    // 判断是否为闰年
+    // 参数: year
+    pub fn cn_is_leap_year(args: Vec<String>) -> String {
+        match args.first().map(|y| y.trim().parse::<i32>()) {
+            Some(Ok(year)) => ((year % 4 == 0 && year % 100 != 0) || year % 400 == 0).to_string(),
+            _ => "错误: 年份必须是整数".to_string(),
+        }
+    }
+    
+    // 获取ISO 8601周数（1-53，每周从周一开始，包含1月4日的周为第1周）
+    // 参数: timestamp, [zone]
+    pub fn cn_week_of_year(args: Vec<String>) -> String {
+        match zoned_time(&args) {
+            Ok(time) => time.iso_week().week().to_string(),
+            Err(e) => e,
+        }
+    }
+    
+    // 获取当天0点的时间戳
+    // 参数: timestamp, [zone]
+    pub fn cn_start_of_day(args: Vec<String>) -> String {
+        start_of(&args, Some)
+    }
+    
+    // 获取所在周的周一0点的时间戳
+    // 参数: timestamp, [zone]
+    pub fn cn_start_of_week(args: Vec<String>) -> String {
+        start_of(&args, |date| date.checked_sub_days(Days::new(date.weekday().num_days_from_monday() as u64)))
+    }
+    
+    // 获取所在月的1日0点的时间戳
+    // 参数: timestamp, [zone]
+    pub fn cn_start_of_month(args: Vec<String>) -> String {
+        start_of(&args, |date| date.with_day(1))
+    }
+    
+    // 获取所在月最后一天 23:59:59 的时间戳
+    // 参数: timestamp, [zone]
+    pub fn cn_end_of_month(args: Vec<String>) -> String {
+        let time = match zoned_time(&args) {
+            Ok(time) => time,
+            Err(e) => return e,
+        };
+        let last = last_day_of_month(time.year(), time.month())
+            .and_then(|day| time.date_naive().with_day(day))
+            .and_then(|date| date.and_hms_opt(23, 59, 59));
+        match last.and_then(|naive| localize(time.timezone(), naive)) {
+            Some(end) => end.timestamp().to_string(),
+            None => "错误: 时间超出范围".to_string(),
+        }
+    }
+    
+    // 加上若干个月（可以为负数），时刻不变
+    // 参数: timestamp, months, [zone]
+    pub fn cn_add_months(args: Vec<String>) -> String {
+        let months = match args.get(1).map(|n| n.trim().parse::<i32>()) {
+            Some(Ok(months)) => months,
+            _ => return "错误: 月数必须是整数".to_string(),
+        };
+        add_months(&args, months)
+    }
+    
+    // 加上若干年（可以为负数），2月29日在非闰年变为2月28日
+    // 参数: timestamp, years, [zone]
+    pub fn cn_add_years(args: Vec<String>) -> String {
+        let months = match args.get(1).map(|n| n.trim().parse::<i32>()) {
+            Some(Ok(years)) => years.checked_mul(12),
+            _ => return "错误: 年数必须是整数".to_string(),
+        };
+        match months {
+            Some(months) => add_months(&args, months),
+            None => "错误: 年数超出范围".to_string(),
+        }
+    }
+    
+    fn add_months(args: &[String], months: i32) -> String {
+        // 时区参数在月数之后
+        let mut rest = vec![args[0].clone()];
+        rest.extend(args.get(2).cloned());
+        let time = match zoned_time(&rest) {
+            Ok(time) => time,
+            Err(e) => return e,
+        };
+        let naive = time.naive_local();
+        let shifted = if months >= 0 {
+            naive.checked_add_months(Months::new(months.unsigned_abs()))
+        } else {
+            naive.checked_sub_months(Months::new(months.unsigned_abs()))
+        };
+        match shifted.and_then(|naive| localize(time.timezone(), naive)) {
+            Some(result) => result.timestamp().to_string(),
+            None => "错误: 时间超出范围".to_string(),
+        }
+    }
+    
+    fn start_of<F>(args: &[String], date_of: F) -> String
+    where
+        F: FnOnce(NaiveDate) -> Option<NaiveDate>,
+    {
+        let time = match zoned_time(args) {
+            Ok(time) => time,
+            Err(e) => return e,
+        };
+        match date_of(time.date_naive()).and_then(|date| localize(time.timezone(), date.and_time(NaiveTime::MIN))) {
+            Some(start) => start.timestamp().to_string(),
+            None => "错误: 时间超出范围".to_string(),
+        }
+    }
+    
+    fn zoned_time(args: &[String]) -> Result<DateTime<Tz>, String> {
+        let timestamp = match args.first().map(|t| t.trim().parse::<i64>()) {
+            Some(Ok(timestamp)) => timestamp,
+            _ => return Err("错误: 时间戳必须是整数".to_string()),
+        };
+        let zone = match args.get(1).filter(|z| !z.trim().is_empty()) {
+            Some(zone) => tz::parse_zone(zone)?,
+            None => Tz::UTC,
+        };
+        DateTime::from_timestamp(timestamp, 0)
+            .map(|time| time.with_timezone(&zone))
+            .ok_or_else(|| "错误: 无效的时间戳".to_string())
+    }
+    
+    // 本地时间转为时区中的时刻；夏令时切换时重复的时间取较早的一个，跳过的时间顺延一小时
+    fn localize(zone: Tz, naive: NaiveDateTime) -> Option<DateTime<Tz>> {
+        zone.from_local_datetime(&naive)
+            .earliest()
+            .or_else(|| zone.from_local_datetime(&(naive + Duration::hours(1))).earliest())
+    }
+    
+    fn last_day_of_month(year: i32, month: u32) -> Option<u32> {
+        let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+        NaiveDate::from_ymd_opt(next_year, next_month, 1)
+            .and_then(|first| first.pred_opt())
+            .map(|last| last.day())
+    }
+}
+
+// cron命名空间
+// cron 表达式有5个字段（分 时 日 月 周），也可以在最前面加上秒成为6个字段，
+// 支持 * , - / 以及 L、W、# 和 @daily、@hourly 等别名（与 sched 库相同）
+// 时间参数和返回值都是时间戳（秒），表达式按 zone 中的时间计算，zone 为IANA时区名称或 "local"（默认）
+mod cron {
+    use super::*;
+    use cn_common::cron::{parse, parse_count};
+    
+    // 检查cron表达式是否有效
+    // 参数: expr
+    pub use cn_common::cron::cn_validate;
+    
+    // 计算 from 之后（不包括 from）的下一次运行时间
+    // 参数: expr, [from]（默认为现在）, [zone]
+    pub fn cn_next(args: Vec<String>) -> String {
+        match next_times(&args, 1) {
+            Ok(times) => times.first().map(|t| t.to_string()).unwrap_or_default(),
+            Err(e) => e,
+        }
+    }
+    
+    // 计算 from 之后的n次运行时间，返回时间戳数组；n 最多为 10000
+    // 参数: expr, n, [from], [zone]
+    pub fn cn_next_n(args: Vec<String>) -> String {
+        let count = match parse_count(args.get(1)) {
+            Ok(count) => count,
+            Err(e) => return e,
+        };
+        let mut rest = args.clone();
+        rest.remove(1);
+        match next_times(&rest, count) {
+            Ok(times) => json_value(&JsonValue::from(times).to_string()),
+            Err(e) => e,
+        }
+    }
+    
+    // 判断时间是否符合cron表达式；5个字段的表达式只比较到分钟
+    // 参数: expr, timestamp, [zone]
+    pub fn cn_matches(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 需要两个参数 (cron表达式, 时间戳)".to_string();
+        }
+        let cron = match parse(&args[0]) {
+            Ok(cron) => cron,
+            Err(e) => return e,
+        };
+        let mut time = match zoned_time(Some(&args[1]), args.get(2)) {
+            Ok(time) => time,
+            Err(e) => return e,
+        };
+        if !has_seconds(&args[0]) {
+            time = time.with_second(0).unwrap_or(time);
+        }
+        match cron.is_time_matching(&time) {
+            Ok(matching) => matching.to_string(),
+            Err(e) => format!("错误: {}", e),
+        }
+    }
+    
+    fn has_seconds(expr: &str) -> bool {
+        expr.split_whitespace().count() == 6
+    }
+    
+    fn zoned_time(timestamp: Option<&String>, zone: Option<&String>) -> Result<DateTime<Tz>, String> {
+        let zone = tz::parse_zone(zone.map(|z| z.as_str()).filter(|z| !z.trim().is_empty()).unwrap_or("local"))?;
+        let time = match timestamp.map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            Some(timestamp) => {
+                let timestamp = timestamp.parse::<i64>().map_err(|_| format!("错误: 无效的时间戳: {}", timestamp))?;
+                DateTime::from_timestamp(timestamp, 0).ok_or_else(|| "错误: 无效的时间戳".to_string())?
+            },
+            None => Utc::now(),
+        };
+        Ok(time.with_timezone(&zone))
+    }
+    
+    fn next_times(args: &[String], count: usize) -> Result<Vec<i64>, String> {
+        let cron = parse(args.first().ok_or_else(|| "错误: 请提供cron表达式".to_string())?)?;
+        let from = zoned_time(args.get(1), args.get(2))?;
+        let times = cn_common::cron::next_times(&cron, &from, count)?;
+        Ok(times.iter().map(|time| time.timestamp()).collect())
+    }
+}
+
 // 初始化函数，返回函数映射
 #[no_mangle]
 pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
@@ -262,9 +1048,19 @@ pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
           .add_function("utc_now", std::cn_utc_now)
           .add_function("now_millis", std::cn_now_millis)
           .add_function("utc_now_millis", std::cn_utc_now_millis)
+          .add_function("now_micros", std::cn_now_micros)
+          .add_function("now_nanos", std::cn_now_nanos)
+          .add_function("perf_counter", std::cn_perf_counter)
           .add_function("format_now", std::cn_format_now)
           .add_function("format_utc_now", std::cn_format_utc_now)
           .add_function("format_timestamp", std::cn_format_timestamp)
+          .add_function("parse", std::cn_parse)
+          .add_function("parse_iso", std::cn_parse_iso)
+          .add_function("format_iso", std::cn_format_iso)
+          .add_function("format_rfc2822", std::cn_format_rfc2822)
+          .add_function("format_http_date", std::cn_format_http_date)
+          .add_function("parse_rfc3339", std::cn_parse_rfc3339)
+          .add_function("parse_rfc2822", std::cn_parse_rfc2822)
           .add_function("year", std::cn_year)
           .add_function("month", std::cn_month)
           .add_function("day", std::cn_day)
@@ -280,9 +1076,56 @@ pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
           .add_function("current_year", std::cn_current_year)
           .add_function("get_null_value", std::cn_get_null_value);
     
+    // 注册时区命名空间下的函数
+    let tz_ns = registry.namespace("tz");
+    tz_ns.add_function("now", tz::cn_now)
+         .add_function("convert", tz::cn_convert)
+         .add_function("components", tz::cn_components)
+         .add_function("offset", tz::cn_offset)
+         .add_function("is_dst", tz::cn_is_dst)
+         .add_function("local", tz::cn_local)
+         .add_function("valid", tz::cn_valid)
+         .add_function("list", tz::cn_list);
+    
+    // 注册时长命名空间下的函数
+    let duration_ns = registry.namespace("duration");
+    duration_ns.add_function("between", duration::cn_between)
+               .add_function("humanize", duration::cn_humanize)
+               .add_function("parse", duration::cn_parse);
+    
+    // 注册秒表命名空间下的函数
+    let stopwatch_ns = registry.namespace("stopwatch");
+    stopwatch_ns.add_function("start", stopwatch::cn_start)
+                .add_function("elapsed_ms", stopwatch::cn_elapsed_ms)
+                .add_function("lap", stopwatch::cn_lap)
+                .add_function("reset", stopwatch::cn_reset)
+                .add_function("stop", stopwatch::cn_stop);
+    
+    // 注册日历命名空间下的函数
+    let cal_ns = registry.namespace("cal");
+    cal_ns.add_function("days_in_month", cal::cn_days_in_month)
+          .add_function("is_leap_year", cal::cn_is_leap_year)
+          .add_function("week_of_year", cal::cn_week_of_year)
+          .add_function("start_of_day", cal::cn_start_of_day)
+          .add_function("start_of_week", cal::cn_start_of_week)
+          .add_function("start_of_month", cal::cn_start_of_month)
+          .add_function("end_of_month", cal::cn_end_of_month)
+          .add_function("add_months", cal::cn_add_months)
+          .add_function("add_years", cal::cn_add_years);
+    
+    // 注册cron命名空间下的函数
+    let cron_ns = registry.namespace("cron");
+    cron_ns.add_function("validate", cron::cn_validate)
+           .add_function("next", cron::cn_next)
+           .add_function("next_n", cron::cn_next_n)
+           .add_function("matches", cron::cn_matches);
+    
     // 同时注册为直接函数，不需要命名空间前缀
     registry.add_direct_function("now", std::cn_now)
             .add_direct_function("now_millis", std::cn_now_millis)
+            .add_direct_function("now_micros", std::cn_now_micros)
+            .add_direct_function("now_nanos", std::cn_now_nanos)
+            .add_direct_function("perf_counter", std::cn_perf_counter)
             .add_direct_function("format_now", std::cn_format_now)
             .add_direct_function("sleep", std::cn_sleep)
             .add_direct_function("sleep_seconds", std::cn_sleep_seconds)
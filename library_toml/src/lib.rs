@@ -0,0 +1,365 @@
+use ::std::collections::HashMap;
+use serde_json::{Map, Value as JsonValue};
+use toml::Value as TomlValue;
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table};
+
+// 导入通用库
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::{json_value, parse_path, serialize, PathSegment};
+
+// TOML命名空间
+mod toml_ns {
+    use super::*;
+
+    // 解析TOML字符串，返回原生值：表转换为 Map，数组和表数组转换为 Array，日期时间转换为 RFC 3339 字符串
+    // 参数: toml
+    pub fn cn_parse(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 未提供TOML字符串".to_string();
+        }
+        match parse_document(&args[0]) {
+            Ok(value) => json_value(&serialize(&value)),
+            Err(e) => e,
+        }
+    }
+
+    // 将值格式化为TOML字符串，根节点必须是对象；对象数组输出为表数组（[[name]]），空值字段会被忽略
+    // Map 以JSON形式传入；其他字符串按TOML解析后重新输出
+    // 参数: value
+    pub fn cn_format(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 未提供要格式化的值".to_string();
+        }
+        let value = match serde_json::from_str::<JsonValue>(&args[0]) {
+            Ok(value) => value,
+            Err(_) => match parse_document(&args[0]) {
+                Ok(value) => value,
+                Err(e) => return e,
+            },
+        };
+        if !value.is_object() {
+            return "错误: TOML文档的根节点必须是对象".to_string();
+        }
+        let table = match json_to_toml(&value) {
+            Ok(Some(table)) => table,
+            Ok(None) => TomlValue::Table(toml::Table::new()),
+            Err(e) => return e,
+        };
+        match toml::to_string(&table) {
+            Ok(result) => result,
+            Err(e) => format!("错误: 生成TOML失败: {}", e),
+        }
+    }
+
+    // 从TOML中获取值，路径写法与 json::get_value 相同，如 "package.name" 或 "bin[0].path"
+    // 参数: toml, path
+    pub fn cn_get_value(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供TOML字符串和路径".to_string();
+        }
+        let document = match parse_document(&args[0]) {
+            Ok(document) => document,
+            Err(e) => return e,
+        };
+        let segments = match parse_path(&args[1]) {
+            Ok(segments) => segments,
+            Err(e) => return e,
+        };
+
+        let mut current = &document;
+        for segment in &segments {
+            current = match (segment, current) {
+                (PathSegment::Key(key), JsonValue::Object(obj)) => match obj.get(key) {
+                    Some(v) => v,
+                    None => return format!("错误: 属性不存在: {}", key),
+                },
+                (PathSegment::Index(idx), JsonValue::Array(arr)) => match arr.get(*idx) {
+                    Some(v) => v,
+                    None => return format!("错误: 无效的数组索引: [{}]", idx),
+                },
+                (PathSegment::Key(key), _) => return format!("错误: 不是表，无法访问属性: {}", key),
+                (PathSegment::Index(idx), _) => return format!("错误: 不是数组，无法访问索引: [{}]", idx),
+            };
+        }
+        json_value(&serialize(current))
+    }
+
+    // 在TOML文档中设置值并返回新的TOML字符串，原有的注释和格式会被保留
+    // 值先按JSON解析，其次按TOML值解析（可以写日期时间，如 1979-05-27T07:32:00Z），都失败时作为字符串
+    // 缺失的表会被创建
+    // 参数: toml, path, value
+    pub fn cn_set_value(args: Vec<String>) -> String {
+        if args.len() < 3 {
+            return "错误: 请提供TOML字符串、路径和值".to_string();
+        }
+        let mut document = match args[0].parse::<DocumentMut>() {
+            Ok(document) => document,
+            Err(e) => return format!("错误: 解析TOML失败: {}", e),
+        };
+        let segments = match parse_path(&args[1]) {
+            Ok(segments) => segments,
+            Err(e) => return e,
+        };
+        let mut new_item = match parse_item_arg(&args[2]) {
+            Ok(item) => item,
+            Err(e) => return e,
+        };
+
+        let (last, parents) = match segments.split_last() {
+            Some(split) => split,
+            None => return "错误: 路径不能为空".to_string(),
+        };
+        // 新建的表排在文档末尾
+        let mut next_position = last_position(document.as_table()) + 1;
+        let mut current = document.as_item_mut();
+        for segment in parents {
+            // 缺失的中间节点创建为隐式的标准表
+            if current.is_none() {
+                *current = Item::Table(implicit_table(&mut next_position));
+            }
+            current = match segment {
+                PathSegment::Key(key) => {
+                    if !current.is_table_like() {
+                        return format!("错误: 不是表，无法设置属性: {}", key);
+                    }
+                    if current.is_table() {
+                        if let Some(table) = current.as_table_mut() {
+                            table.entry(key).or_insert(Item::None);
+                        }
+                    } else if current.get(key.as_str()).is_none() {
+                        // 内联表中缺失的中间节点创建为内联表
+                        if let Some(table) = current.as_inline_table_mut() {
+                            table.insert(key, toml_edit::Value::InlineTable(toml_edit::InlineTable::new()));
+                        }
+                    }
+                    match current.get_mut(key.as_str()) {
+                        Some(item) => item,
+                        None => return format!("错误: 无法设置属性: {}", key),
+                    }
+                },
+                PathSegment::Index(idx) => match current.get_mut(*idx) {
+                    Some(item) => item,
+                    None => return format!("错误: 无效的数组索引: [{}]", idx),
+                },
+            };
+        }
+
+        if current.is_none() {
+            *current = Item::Table(implicit_table(&mut next_position));
+        }
+        // 只有标准表下可以放置表和表数组，其他位置转换为内联形式
+        if current.is_table() {
+            assign_positions(&mut new_item, &mut next_position);
+        } else {
+            new_item = into_inline(new_item);
+        }
+        match last {
+            PathSegment::Key(key) => match current.as_table_like_mut() {
+                Some(table) => {
+                    table.insert(key, new_item);
+                },
+                None => return format!("错误: 不是表，无法设置属性: {}", key),
+            },
+            PathSegment::Index(idx) => match current.get_mut(*idx) {
+                Some(item) => *item = new_item,
+                None => return format!("错误: 无效的数组索引: [{}]", idx),
+            },
+        }
+        document.to_string()
+    }
+
+    // 将TOML转换为JSON字符串
+    // 参数: toml
+    pub fn cn_to_json(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 未提供TOML字符串".to_string();
+        }
+        match parse_document(&args[0]) {
+            Ok(value) => serialize(&value),
+            Err(e) => e,
+        }
+    }
+
+    // 检查TOML是否有效
+    // 参数: toml
+    pub fn cn_is_valid(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "false".to_string();
+        }
+        args[0].parse::<toml::Table>().is_ok().to_string()
+    }
+
+    // 解析TOML文档并转换为JSON值
+    fn parse_document(input: &str) -> Result<JsonValue, String> {
+        match input.parse::<toml::Table>() {
+            Ok(table) => Ok(toml_to_json(TomlValue::Table(table))),
+            Err(e) => Err(format!("错误: 解析TOML失败: {}", e)),
+        }
+    }
+
+    // TOML值转换为JSON值
+    fn toml_to_json(value: TomlValue) -> JsonValue {
+        match value {
+            TomlValue::String(s) => JsonValue::String(s),
+            TomlValue::Integer(i) => JsonValue::from(i),
+            TomlValue::Float(f) => serde_json::Number::from_f64(f)
+                .map(JsonValue::Number)
+                .unwrap_or_else(|| JsonValue::String(f.to_string())),
+            TomlValue::Boolean(b) => JsonValue::Bool(b),
+            TomlValue::Datetime(dt) => JsonValue::String(dt.to_string()),
+            TomlValue::Array(items) => JsonValue::Array(items.into_iter().map(toml_to_json).collect()),
+            TomlValue::Table(table) => {
+                let mut obj = Map::new();
+                for (key, value) in table {
+                    obj.insert(key, toml_to_json(value));
+                }
+                JsonValue::Object(obj)
+            },
+        }
+    }
+
+    // JSON值转换为TOML值，空值返回 None（在表中被忽略）
+    fn json_to_toml(value: &JsonValue) -> Result<Option<TomlValue>, String> {
+        Ok(Some(match value {
+            JsonValue::Null => return Ok(None),
+            JsonValue::Bool(b) => TomlValue::Boolean(*b),
+            JsonValue::Number(n) => match n.as_i64() {
+                Some(i) => TomlValue::Integer(i),
+                None => TomlValue::Float(n.as_f64().unwrap_or(f64::NAN)),
+            },
+            JsonValue::String(s) => TomlValue::String(s.clone()),
+            JsonValue::Array(items) => {
+                let mut array = Vec::with_capacity(items.len());
+                for item in items {
+                    match json_to_toml(item)? {
+                        Some(v) => array.push(v),
+                        None => return Err("错误: TOML数组不支持空值".to_string()),
+                    }
+                }
+                TomlValue::Array(array)
+            },
+            JsonValue::Object(obj) => {
+                let mut table = toml::Table::new();
+                for (key, value) in obj {
+                    if let Some(v) = json_to_toml(value)? {
+                        table.insert(key.clone(), v);
+                    }
+                }
+                TomlValue::Table(table)
+            },
+        }))
+    }
+
+    // 将 set_value 的值参数解析为文档节点：对象成为标准表，对象数组成为表数组
+    fn parse_item_arg(input: &str) -> Result<Item, String> {
+        let value = match serde_json::from_str::<JsonValue>(input) {
+            Ok(value) => value,
+            Err(_) => {
+                return Ok(match input.parse::<toml_edit::Value>() {
+                    Ok(value) => Item::Value(value),
+                    Err(_) => toml_edit::value(input),
+                });
+            },
+        };
+        let toml_value = match json_to_toml(&value)? {
+            Some(v) => v,
+            None => return Err("错误: TOML不支持空值".to_string()),
+        };
+
+        // 借助 toml_edit 的解析得到带格式的节点
+        let mut wrapper = toml::Table::new();
+        wrapper.insert("value".to_string(), toml_value);
+        let text = toml::to_string(&wrapper).map_err(|e| format!("错误: 生成TOML失败: {}", e))?;
+        let mut document = text.parse::<DocumentMut>().map_err(|e| format!("错误: 生成TOML失败: {}", e))?;
+        let mut item = document.remove("value").unwrap_or(Item::None);
+        if let Item::Table(table) = &mut item {
+            table.set_implicit(false);
+        }
+        if let Item::ArrayOfTables(array) = &mut item {
+            for table in array.iter_mut() {
+                table.set_implicit(false);
+            }
+        }
+        Ok(item)
+    }
+
+    // 创建隐式的标准表（只包含子表时不输出表头）
+    fn implicit_table(next_position: &mut usize) -> Table {
+        let mut table = Table::new();
+        table.set_implicit(true);
+        table.set_position(*next_position);
+        table.decor_mut().set_prefix("\n");
+        *next_position += 1;
+        table
+    }
+
+    // 文档中最后一个表的位置
+    fn last_position(table: &Table) -> usize {
+        let mut last = table.position().unwrap_or(0);
+        for (_, item) in table.iter() {
+            match item {
+                Item::Table(child) => last = last.max(last_position(child)),
+                Item::ArrayOfTables(array) => {
+                    for child in array.iter() {
+                        last = last.max(last_position(child));
+                    }
+                },
+                _ => {},
+            }
+        }
+        last
+    }
+
+    // 为新插入的表和表数组依次分配位置
+    fn assign_positions(item: &mut Item, next_position: &mut usize) {
+        let visit = |table: &mut Table, next_position: &mut usize| {
+            table.set_position(*next_position);
+            table.decor_mut().set_prefix("\n");
+            *next_position += 1;
+            for (_, child) in table.iter_mut() {
+                assign_positions(child, next_position);
+            }
+        };
+        match item {
+            Item::Table(table) => visit(table, next_position),
+            Item::ArrayOfTables(array) => {
+                for table in array.iter_mut() {
+                    visit(table, next_position);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    // 内联表和内联数组中只能放置值，把表和表数组转换为内联形式
+    fn into_inline(item: Item) -> Item {
+        match item {
+            Item::Table(table) => Item::Value(toml_edit::Value::InlineTable(table.into_inline_table())),
+            Item::ArrayOfTables(array) => Item::Value(toml_edit::Value::Array(array_of_tables_into_array(array))),
+            other => other,
+        }
+    }
+
+    fn array_of_tables_into_array(array: ArrayOfTables) -> toml_edit::Array {
+        array.into_iter().map(|table| toml_edit::Value::InlineTable(table.into_inline_table())).collect()
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册TOML命名空间下的函数
+    let toml_ns = registry.namespace("toml");
+    toml_ns.add_function("parse", toml_ns::cn_parse)
+           .add_function("format", toml_ns::cn_format)
+           .add_function("get_value", toml_ns::cn_get_value)
+           .add_function("set_value", toml_ns::cn_set_value)
+           .add_function("to_json", toml_ns::cn_to_json)
+           .add_function("is_valid", toml_ns::cn_is_valid);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
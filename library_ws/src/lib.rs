@@ -0,0 +1,244 @@
+use ::std::collections::HashMap;
+use serde_json::{Map, Value as JsonValue};
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tungstenite::client::IntoClientRequest;
+use tungstenite::http::{HeaderName, HeaderValue};
+use tungstenite::protocol::frame::coding::CloseCode;
+use tungstenite::protocol::CloseFrame;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+// 导入通用库
+use cn_common::handle::HandleTable;
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::json_value;
+
+// WebSocket命名空间
+// 支持 ws:// 和 wss://；收到的 ping 会在下一次收发时自动回复 pong
+mod ws {
+    use super::*;
+
+    type Socket = WebSocket<MaybeTlsStream<TcpStream>>;
+
+    // close 等待对方确认关闭的最长时间
+    const CLOSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+    static SOCKETS: Mutex<HandleTable<Socket>> = Mutex::new(HandleTable::new());
+
+    fn lock_sockets() -> MutexGuard<'static, HandleTable<Socket>> {
+        SOCKETS.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn with_socket<F>(handle: Option<&String>, f: F) -> String
+    where
+        F: FnOnce(&mut Socket) -> Result<String, String>,
+    {
+        let mut sockets = lock_sockets();
+        let id = handle.and_then(|arg| sockets.parse_handle(arg));
+        match id.and_then(|id| sockets.get_mut(id)) {
+            Some(socket) => f(socket).unwrap_or_else(|e| e),
+            None => "错误: 无效的WebSocket句柄".to_string(),
+        }
+    }
+
+    // 连接WebSocket服务器，返回连接句柄
+    // 参数: url, [headers]（Map，附加到握手请求的头信息）
+    pub fn cn_connect(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供WebSocket URL".to_string();
+        }
+        let mut request = match args[0].trim().into_client_request() {
+            Ok(request) => request,
+            Err(e) => return format!("错误: 无效的WebSocket URL: {}", e),
+        };
+        if let Some(headers) = args.get(1) {
+            let headers = match serde_json::from_str::<Map<String, JsonValue>>(headers) {
+                Ok(headers) => headers,
+                Err(_) => return "错误: 头信息必须是 Map".to_string(),
+            };
+            for (name, value) in headers {
+                let value = match value {
+                    JsonValue::String(s) => s,
+                    other => other.to_string(),
+                };
+                match (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(&value)) {
+                    (Ok(name), Ok(value)) => {
+                        request.headers_mut().insert(name, value);
+                    },
+                    _ => return format!("错误: 无效的头信息: {}", name),
+                }
+            }
+        }
+
+        match tungstenite::connect(request) {
+            Ok((socket, _)) => lock_sockets().insert(socket).to_string(),
+            Err(e) => format!("错误: 无法连接 {}: {}", args[0], e),
+        }
+    }
+
+    // 发送文本消息
+    // 参数: ws, text
+    pub fn cn_send(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供WebSocket句柄和要发送的文本".to_string();
+        }
+        with_socket(args.first(), |socket| send(socket, Message::Text(args[1].clone())))
+    }
+
+    // 发送二进制消息，数据以16进制字符串表示
+    // 参数: ws, hex
+    pub fn cn_send_binary(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供WebSocket句柄和16进制数据".to_string();
+        }
+        let bytes = match decode_hex(&args[1]) {
+            Ok(bytes) => bytes,
+            Err(e) => return e,
+        };
+        with_socket(args.first(), |socket| send(socket, Message::Binary(bytes)))
+    }
+
+    // 接收下一条消息的内容，二进制消息按UTF-8解码；连接关闭后返回空字符串
+    // 参数: ws, [timeout]（毫秒，0或省略表示一直等待，超时返回错误）
+    pub fn cn_recv(args: Vec<String>) -> String {
+        let timeout = match parse_timeout(args.get(1)) {
+            Ok(timeout) => timeout,
+            Err(e) => return e,
+        };
+        with_socket(args.first(), |socket| match receive(socket, timeout)? {
+            Some(Message::Text(text)) => Ok(text),
+            Some(Message::Binary(bytes)) => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+            _ => Ok(String::new()),
+        })
+    }
+
+    // 接收下一条消息，返回 Map: {"type": "text" 或 "binary" 或 "close", "data": 内容}
+    // 二进制消息的内容为16进制字符串，关闭消息的内容为关闭原因
+    // 参数: ws, [timeout]
+    pub fn cn_recv_message(args: Vec<String>) -> String {
+        let timeout = match parse_timeout(args.get(1)) {
+            Ok(timeout) => timeout,
+            Err(e) => return e,
+        };
+        with_socket(args.first(), |socket| {
+            let (kind, data) = match receive(socket, timeout)? {
+                Some(Message::Text(text)) => ("text", text),
+                Some(Message::Binary(bytes)) => ("binary", bytes.iter().map(|b| format!("{:02x}", b)).collect()),
+                Some(Message::Close(frame)) => ("close", frame.map(|f| f.reason.into_owned()).unwrap_or_default()),
+                _ => ("close", String::new()),
+            };
+            let mut obj = Map::new();
+            obj.insert("type".to_string(), JsonValue::String(kind.to_string()));
+            obj.insert("data".to_string(), JsonValue::String(data));
+            Ok(json_value(&JsonValue::Object(obj).to_string()))
+        })
+    }
+
+    // 检查连接是否仍然可以收发消息
+    // 参数: ws
+    pub fn cn_is_open(args: Vec<String>) -> String {
+        let sockets = lock_sockets();
+        match args.first().and_then(|arg| sockets.parse_handle(arg)).and_then(|id| sockets.get(id)) {
+            Some(socket) => socket.can_write().to_string(),
+            None => "false".to_string(),
+        }
+    }
+
+    // 关闭连接，句柄随后失效
+    // 参数: ws, [reason]
+    pub fn cn_close(args: Vec<String>) -> String {
+        let mut socket = {
+            let mut sockets = lock_sockets();
+            match args.first().and_then(|arg| sockets.parse_handle(arg)).and_then(|id| sockets.remove(id)) {
+                Some(socket) => socket,
+                None => return "false".to_string(),
+            }
+        };
+        let frame = CloseFrame {
+            code: CloseCode::Normal,
+            reason: args.get(1).cloned().unwrap_or_default().into(),
+        };
+        // 发送关闭消息并等待对方确认，对方没有及时响应时直接断开
+        if socket.close(Some(frame)).is_ok() {
+            let _ = set_read_timeout(&socket, Some(CLOSE_TIMEOUT));
+            while socket.read().is_ok() {}
+        }
+        "true".to_string()
+    }
+
+    fn send(socket: &mut Socket, message: Message) -> Result<String, String> {
+        socket.send(message).map_err(|e| format!("错误: 发送失败: {}", e))?;
+        Ok("true".to_string())
+    }
+
+    // 读取下一条数据或关闭消息，跳过 ping 和 pong；连接已经关闭时返回 None
+    fn receive(socket: &mut Socket, timeout: Option<Duration>) -> Result<Option<Message>, String> {
+        set_read_timeout(socket, timeout).map_err(|e| format!("错误: 无法设置超时: {}", e))?;
+        let result = loop {
+            match socket.read() {
+                Ok(Message::Ping(_)) | Ok(Message::Pong(_)) | Ok(Message::Frame(_)) => continue,
+                Ok(message) => break Ok(Some(message)),
+                Err(tungstenite::Error::ConnectionClosed) | Err(tungstenite::Error::AlreadyClosed) => break Ok(None),
+                Err(tungstenite::Error::Io(e)) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    break Err("错误: 接收超时".to_string());
+                },
+                Err(e) => break Err(format!("错误: 接收失败: {}", e)),
+            }
+        };
+        let _ = set_read_timeout(socket, None);
+        result
+    }
+
+    fn set_read_timeout(socket: &Socket, timeout: Option<Duration>) -> std::io::Result<()> {
+        match socket.get_ref() {
+            MaybeTlsStream::Plain(stream) => stream.set_read_timeout(timeout),
+            MaybeTlsStream::NativeTls(stream) => stream.get_ref().set_read_timeout(timeout),
+            _ => Ok(()),
+        }
+    }
+
+    fn parse_timeout(arg: Option<&String>) -> Result<Option<Duration>, String> {
+        match arg.map(|t| t.trim().parse::<u64>()) {
+            Some(Ok(0)) | None => Ok(None),
+            Some(Ok(ms)) => Ok(Some(Duration::from_millis(ms))),
+            Some(Err(_)) => Err(format!("错误: 无效的超时毫秒数: {}", arg.map(|t| t.as_str()).unwrap_or(""))),
+        }
+    }
+
+    fn decode_hex(hex: &str) -> Result<Vec<u8>, String> {
+        let digits: Vec<u8> = hex.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+        if digits.len() % 2 == 1 {
+            return Err("错误: 16进制字符串的长度必须是偶数".to_string());
+        }
+        digits.chunks(2)
+            .map(|pair| {
+                std::str::from_utf8(pair).ok()
+                    .and_then(|s| u8::from_str_radix(s, 16).ok())
+                    .ok_or_else(|| format!("错误: 无效的16进制字符: {}", String::from_utf8_lossy(pair)))
+            })
+            .collect()
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册WebSocket命名空间下的函数
+    let ws_ns = registry.namespace("ws");
+    ws_ns.add_function("connect", ws::cn_connect)
+         .add_function("send", ws::cn_send)
+         .add_function("send_binary", ws::cn_send_binary)
+         .add_function("recv", ws::cn_recv)
+         .add_function("recv_message", ws::cn_recv_message)
+         .add_function("is_open", ws::cn_is_open)
+         .add_function("close", ws::cn_close);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
@@ -0,0 +1,855 @@
+use ::std::collections::HashMap;
+use serde_json::{Map, Value as JsonValue};
+
+// 导入通用库
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::json_value;
+
+use dom::Node;
+
+// XML命名空间
+mod xml {
+    use super::*;
+
+    // 解析XML字符串，返回根元素：{"name": 标签名, "attributes": {属性}, "children": [子元素或文本]}
+    // 注释、处理指令和只包含空白的文本会被忽略
+    // 参数: xml
+    pub fn cn_parse(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 未提供XML字符串".to_string();
+        }
+        match dom::parse(&args[0], false) {
+            Ok(document) => json_value(&document.root().to_json().to_string()),
+            Err(e) => e,
+        }
+    }
+
+    // 以宽松模式解析HTML：标签名转换为小写，自动补全未闭合的标签，支持无引号和无值的属性、空元素（br、img 等）以及常见的命名实体
+    // 返回值结构与 parse 相同
+    // 参数: html
+    pub fn cn_parse_html(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 未提供HTML字符串".to_string();
+        }
+        match dom::parse(&args[0], true) {
+            Ok(document) => json_value(&document.root().to_json().to_string()),
+            Err(e) => e,
+        }
+    }
+
+    // 使用类似XPath的表达式查询节点，返回数组：元素为 Map，属性和文本为字符串
+    // 支持 /、//、*、.、@属性、@*、text()，以及谓词 [n]、[last()]、[@属性]、[@属性='值']、[子元素='值']、[text()='值']、[contains(@属性,'值')]
+    // 以 / 开头的路径从文档开始，否则相对于传入的节点
+    // 参数: doc（XML字符串或 parse 返回的节点）, xpath
+    pub fn cn_select(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供文档和查询路径".to_string();
+        }
+        match select(&args[0], &args[1]) {
+            Ok(results) => json_value(&JsonValue::Array(results).to_string()),
+            Err(e) => e,
+        }
+    }
+
+    // 查询第一个匹配的结果，没有匹配时返回空值
+    // 参数: doc, xpath
+    pub fn cn_select_one(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供文档和查询路径".to_string();
+        }
+        match select(&args[0], &args[1]) {
+            Ok(results) => json_value(&results.into_iter().next().unwrap_or(JsonValue::Null).to_string()),
+            Err(e) => e,
+        }
+    }
+
+    // 获取节点的全部文本内容（包括所有后代元素中的文本）
+    // 参数: node
+    pub fn cn_text(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供节点".to_string();
+        }
+        match load_node(&args[0]) {
+            Ok(node) => node.text_content(),
+            Err(e) => e,
+        }
+    }
+
+    // 获取元素的属性值
+    // 参数: node, name
+    pub fn cn_attr(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供节点和属性名".to_string();
+        }
+        let node = match load_node(&args[0]) {
+            Ok(node) => node,
+            Err(e) => return e,
+        };
+        match node.attribute(&args[1]) {
+            Some(value) => value.to_string(),
+            None => format!("错误: 属性不存在: {}", args[1]),
+        }
+    }
+
+    // 将节点序列化为XML字符串
+    // 参数: node, [indent]（缩进空格数，省略时输出紧凑格式；包含文本的元素内部不缩进）
+    pub fn cn_to_string(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 请提供节点".to_string();
+        }
+        let indent = match args.get(1).map(|indent| indent.trim().parse::<usize>()) {
+            Some(Ok(width)) if width <= 16 => Some(" ".repeat(width)),
+            Some(_) => return format!("错误: 无效的缩进: {}", args[1]),
+            None => None,
+        };
+        match load_node(&args[0]) {
+            Ok(node) => node.to_xml(indent.as_deref()),
+            Err(e) => e,
+        }
+    }
+
+    // 检查XML是否有效
+    // 参数: xml
+    pub fn cn_is_valid(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "false".to_string();
+        }
+        dom::parse(&args[0], false).is_ok().to_string()
+    }
+
+    // 查询节点，绝对路径从包含该节点的文档开始
+    fn select(doc: &str, path: &str) -> Result<Vec<JsonValue>, String> {
+        let node = load_node(doc)?;
+        let document = if node.name() == Some(dom::DOCUMENT) {
+            node.clone()
+        } else {
+            Node::document(vec![node.clone()])
+        };
+        let results = xpath::evaluate(path, &node, &document)?;
+        Ok(results.into_iter().map(|item| item.to_json()).collect())
+    }
+
+    // 加载节点参数：parse 返回的 Map 以JSON形式传入，其他字符串按XML解析
+    fn load_node(input: &str) -> Result<Node, String> {
+        if let Ok(value @ JsonValue::Object(_)) = serde_json::from_str::<JsonValue>(input) {
+            return Node::from_json(&value);
+        }
+        dom::parse(input, false).map(|document| document.root().clone())
+    }
+}
+
+// 文档对象模型
+mod dom {
+    use super::*;
+    use quick_xml::events::attributes::Attributes;
+    use quick_xml::events::{BytesStart, BytesText, Event};
+    use quick_xml::Reader;
+
+    // 文档节点的名称，包含多个顶层节点时作为根节点返回
+    pub const DOCUMENT: &str = "#document";
+
+    // HTML空元素，不需要结束标签
+    const VOID_ELEMENTS: &[&str] = &[
+        "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr",
+    ];
+
+    // 遇到同名开始标签时自动闭合的HTML元素
+    const AUTO_CLOSE_ELEMENTS: &[&str] = &["p", "li", "tr", "td", "th", "option", "dt", "dd"];
+
+    // 常见的HTML命名实体
+    const HTML_ENTITIES: &[(&str, &str)] = &[
+        ("nbsp", "\u{a0}"), ("copy", "©"), ("reg", "®"), ("trade", "™"), ("hellip", "…"),
+        ("mdash", "—"), ("ndash", "–"), ("laquo", "«"), ("raquo", "»"), ("middot", "·"),
+        ("lsquo", "‘"), ("rsquo", "’"), ("ldquo", "“"), ("rdquo", "”"), ("bull", "•"),
+        ("times", "×"), ("divide", "÷"), ("deg", "°"), ("yen", "¥"), ("euro", "€"),
+    ];
+
+    #[derive(Clone)]
+    pub enum Node {
+        Element {
+            name: String,
+            attributes: Vec<(String, String)>,
+            children: Vec<Node>,
+        },
+        Text(String),
+    }
+
+    impl Node {
+        pub fn document(children: Vec<Node>) -> Node {
+            Node::Element { name: DOCUMENT.to_string(), attributes: Vec::new(), children }
+        }
+
+        pub fn name(&self) -> Option<&str> {
+            match self {
+                Node::Element { name, .. } => Some(name),
+                Node::Text(_) => None,
+            }
+        }
+
+        pub fn children(&self) -> &[Node] {
+            match self {
+                Node::Element { children, .. } => children,
+                Node::Text(_) => &[],
+            }
+        }
+
+        pub fn attributes(&self) -> &[(String, String)] {
+            match self {
+                Node::Element { attributes, .. } => attributes,
+                Node::Text(_) => &[],
+            }
+        }
+
+        pub fn attribute(&self, key: &str) -> Option<&str> {
+            self.attributes().iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+        }
+
+        // 文档的根元素：只有一个顶层元素时返回该元素，否则返回文档节点本身
+        pub fn root(&self) -> &Node {
+            let mut elements = self.children().iter().filter(|child| child.name().is_some());
+            match (elements.next(), elements.next()) {
+                (Some(root), None) if self.children().len() == 1 => root,
+                _ => self,
+            }
+        }
+
+        pub fn text_content(&self) -> String {
+            match self {
+                Node::Text(text) => text.clone(),
+                Node::Element { children, .. } => children.iter().map(|child| child.text_content()).collect(),
+            }
+        }
+
+        pub fn to_json(&self) -> JsonValue {
+            match self {
+                Node::Text(text) => JsonValue::String(text.clone()),
+                Node::Element { name, attributes, children } => {
+                    let mut attrs = Map::new();
+                    for (key, value) in attributes {
+                        attrs.insert(key.clone(), JsonValue::String(value.clone()));
+                    }
+                    let mut obj = Map::new();
+                    obj.insert("name".to_string(), JsonValue::String(name.clone()));
+                    obj.insert("attributes".to_string(), JsonValue::Object(attrs));
+                    obj.insert("children".to_string(), JsonValue::Array(children.iter().map(Node::to_json).collect()));
+                    JsonValue::Object(obj)
+                },
+            }
+        }
+
+        pub fn from_json(value: &JsonValue) -> Result<Node, String> {
+            match value {
+                JsonValue::String(text) => Ok(Node::Text(text.clone())),
+                JsonValue::Object(obj) => {
+                    let name = match obj.get("name").and_then(|n| n.as_str()) {
+                        Some(name) => name.to_string(),
+                        None => return Err("错误: 节点缺少 name 字段".to_string()),
+                    };
+                    let mut attributes = Vec::new();
+                    if let Some(attrs) = obj.get("attributes").and_then(|a| a.as_object()) {
+                        for (key, value) in attrs {
+                            let value = match value {
+                                JsonValue::String(s) => s.clone(),
+                                other => other.to_string(),
+                            };
+                            attributes.push((key.clone(), value));
+                        }
+                    }
+                    let mut children = Vec::new();
+                    if let Some(items) = obj.get("children").and_then(|c| c.as_array()) {
+                        for item in items {
+                            children.push(Node::from_json(item)?);
+                        }
+                    }
+                    Ok(Node::Element { name, attributes, children })
+                },
+                JsonValue::Null => Err("错误: 节点不能为空值".to_string()),
+                other => Ok(Node::Text(other.to_string())),
+            }
+        }
+
+        pub fn to_xml(&self, indent: Option<&str>) -> String {
+            let mut output = String::new();
+            if self.name() == Some(DOCUMENT) {
+                for child in self.children() {
+                    child.write_xml(&mut output, indent, 0);
+                    if indent.is_some() {
+                        output.push('\n');
+                    }
+                }
+            } else {
+                self.write_xml(&mut output, indent, 0);
+            }
+            output
+        }
+
+        fn write_xml(&self, output: &mut String, indent: Option<&str>, depth: usize) {
+            let (name, attributes, children) = match self {
+                Node::Text(text) => {
+                    output.push_str(&escape(text, false));
+                    return;
+                },
+                Node::Element { name, attributes, children } => (name, attributes, children),
+            };
+
+            output.push('<');
+            output.push_str(name);
+            for (key, value) in attributes {
+                output.push_str(&format!(" {}=\"{}\"", key, escape(value, true)));
+            }
+            if children.is_empty() {
+                output.push_str("/>");
+                return;
+            }
+            output.push('>');
+
+            // 混合内容（包含文本）的元素内部不缩进，避免改变文本
+            let child_indent = indent.filter(|_| children.iter().all(|child| child.name().is_some()));
+            for child in children {
+                if let Some(unit) = child_indent {
+                    output.push('\n');
+                    output.push_str(&unit.repeat(depth + 1));
+                }
+                child.write_xml(output, child_indent, depth + 1);
+            }
+            if let Some(unit) = child_indent {
+                output.push('\n');
+                output.push_str(&unit.repeat(depth));
+            }
+            output.push_str(&format!("</{}>", name));
+        }
+    }
+
+    fn escape(text: &str, attribute: bool) -> String {
+        let mut escaped = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' if attribute => escaped.push_str("&quot;"),
+                _ => escaped.push(c),
+            }
+        }
+        escaped
+    }
+
+    fn resolve_html_entity(entity: &str) -> Option<&'static str> {
+        HTML_ENTITIES.iter().find(|(name, _)| *name == entity).map(|(_, value)| *value)
+    }
+
+    // 解析文档，返回文档节点
+    pub fn parse(input: &str, html: bool) -> Result<Node, String> {
+        let mut reader = new_reader(input, html);
+        // 当前读取器在输入中的起始位置，跳过脚本内容后会重新创建读取器
+        let mut offset = 0;
+
+        // 栈底是文档节点
+        let mut stack: Vec<Node> = vec![Node::document(Vec::new())];
+        loop {
+            let event = reader.read_event().map_err(|e| format!("错误: 解析{}失败（位置 {}）: {}", kind(html), offset + reader.buffer_position(), e))?;
+            match event {
+                Event::Start(start) => {
+                    let (name, attributes) = read_start(&start, html)?;
+                    if html {
+                        close_implicit(&mut stack, &name);
+                        if VOID_ELEMENTS.contains(&name.as_str()) {
+                            append(&mut stack, Node::Element { name, attributes, children: Vec::new() });
+                            continue;
+                        }
+                        // 脚本和样式的内容按原样读取
+                        if name == "script" || name == "style" {
+                            let content_start = offset + reader.buffer_position();
+                            let (text, next) = raw_text(input, content_start, &name);
+                            offset = next;
+                            reader = new_reader(&input[next..], html);
+                            let children = if text.trim().is_empty() { Vec::new() } else { vec![Node::Text(text)] };
+                            append(&mut stack, Node::Element { name, attributes, children });
+                            continue;
+                        }
+                    }
+                    stack.push(Node::Element { name, attributes, children: Vec::new() });
+                },
+                Event::Empty(start) => {
+                    let (name, attributes) = read_start(&start, html)?;
+                    if html {
+                        close_implicit(&mut stack, &name);
+                    }
+                    append(&mut stack, Node::Element { name, attributes, children: Vec::new() });
+                },
+                Event::End(end) => {
+                    let name = String::from_utf8_lossy(end.name().as_ref()).into_owned();
+                    let name = if html { name.to_lowercase() } else { name };
+                    // HTML模式下闭合到最近的同名元素，找不到时忽略该结束标签
+                    if let Some(pos) = stack.iter().rposition(|node| node.name() == Some(name.as_str())) {
+                        while stack.len() > pos {
+                            close_top(&mut stack);
+                        }
+                    } else if !html {
+                        return Err(format!("错误: 解析XML失败: 多余的结束标签 </{}>", name));
+                    }
+                },
+                Event::Text(text) => {
+                    let text = read_text(&text, html)?;
+                    if !text.trim().is_empty() {
+                        append(&mut stack, Node::Text(text));
+                    }
+                },
+                Event::CData(data) => {
+                    let text = String::from_utf8_lossy(&data.into_inner()).into_owned();
+                    append(&mut stack, Node::Text(text));
+                },
+                Event::Eof => break,
+                _ => {},
+            }
+        }
+
+        if stack.len() > 1 {
+            if !html {
+                let name = stack.last().and_then(|node| node.name()).unwrap_or_default().to_string();
+                return Err(format!("错误: 解析XML失败: 元素 <{}> 未闭合", name));
+            }
+            while stack.len() > 1 {
+                close_top(&mut stack);
+            }
+        }
+        let document = stack.pop().unwrap_or_else(|| Node::document(Vec::new()));
+        if !html && document.children().iter().all(|child| child.name().is_none()) {
+            return Err("错误: 解析XML失败: 没有根元素".to_string());
+        }
+        Ok(document)
+    }
+
+    fn new_reader(input: &str, html: bool) -> Reader<&[u8]> {
+        let mut reader = Reader::from_str(input);
+        reader.check_end_names(!html);
+        reader
+    }
+
+    // 按原样读取脚本或样式的内容，直到对应的结束标签，返回内容和结束标签之后的位置
+    fn raw_text(input: &str, start: usize, name: &str) -> (String, usize) {
+        let rest = &input[start..];
+        let closing = format!("</{}", name);
+        let end = rest.to_ascii_lowercase().find(&closing);
+        match end {
+            Some(end) => {
+                let after = rest[end..].find('>').map(|gt| end + gt + 1).unwrap_or(rest.len());
+                (rest[..end].to_string(), start + after)
+            },
+            None => (rest.to_string(), input.len()),
+        }
+    }
+
+    fn kind(html: bool) -> &'static str {
+        if html { "HTML" } else { "XML" }
+    }
+
+    fn read_start(start: &BytesStart, html: bool) -> Result<(String, Vec<(String, String)>), String> {
+        let name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+        if !html {
+            return Ok((name, read_attributes(start.attributes(), false)?));
+        }
+        let mut attributes = start.html_attributes();
+        attributes.with_checks(false);
+        Ok((name.to_lowercase(), read_attributes(attributes, true)?))
+    }
+
+    fn read_attributes(attributes: Attributes, html: bool) -> Result<Vec<(String, String)>, String> {
+        let mut result = Vec::new();
+        for attribute in attributes {
+            let attribute = match attribute {
+                Ok(attribute) => attribute,
+                Err(_) if html => continue,
+                Err(e) => return Err(format!("错误: 解析XML失败: 无效的属性: {}", e)),
+            };
+            let key = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+            let value = if html {
+                attribute.unescape_value_with(resolve_html_entity)
+                    .map(|v| v.into_owned())
+                    .unwrap_or_else(|_| String::from_utf8_lossy(&attribute.value).into_owned())
+            } else {
+                attribute.unescape_value()
+                    .map_err(|e| format!("错误: 解析XML失败: 无效的属性值: {}", e))?
+                    .into_owned()
+            };
+            result.push((if html { key.to_lowercase() } else { key }, value));
+        }
+        Ok(result)
+    }
+
+    fn read_text(text: &BytesText, html: bool) -> Result<String, String> {
+        if html {
+            // 无法识别的实体保留原样
+            return Ok(text.unescape_with(resolve_html_entity)
+                .map(|t| t.into_owned())
+                .unwrap_or_else(|_| String::from_utf8_lossy(text).into_owned()));
+        }
+        text.unescape()
+            .map(|t| t.into_owned())
+            .map_err(|e| format!("错误: 解析XML失败: {}", e))
+    }
+
+    // 打开新的HTML元素前，自动闭合不能嵌套的同名元素（如连续的 <li>）
+    fn close_implicit(stack: &mut Vec<Node>, name: &str) {
+        if !AUTO_CLOSE_ELEMENTS.contains(&name) || stack.len() < 2 {
+            return;
+        }
+        let top = stack.last().and_then(|node| node.name()).unwrap_or_default();
+        let cells = ["td", "th"];
+        if top == name || (cells.contains(&top) && cells.contains(&name)) {
+            close_top(stack);
+        }
+    }
+
+    fn append(stack: &mut [Node], node: Node) {
+        if let Some(Node::Element { children, .. }) = stack.last_mut() {
+            children.push(node);
+        }
+    }
+
+    fn close_top(stack: &mut Vec<Node>) {
+        if let Some(node) = stack.pop() {
+            append(stack, node);
+        }
+    }
+}
+
+// 类似XPath的查询
+mod xpath {
+    use super::*;
+
+    enum Axis {
+        Child,
+        Descendant,
+    }
+
+    enum Test {
+        Name(String),
+        AnyElement,
+        SelfNode,
+        Attribute(String),
+        AnyAttribute,
+        Text,
+    }
+
+    enum Predicate {
+        Position(usize),
+        Last,
+        HasAttribute(String),
+        AttributeEquals(String, String),
+        TextEquals(String),
+        ChildEquals(String, String),
+        HasChild(String),
+        Contains(Operand, String),
+    }
+
+    enum Operand {
+        Attribute(String),
+        Text,
+    }
+
+    struct Step {
+        axis: Axis,
+        test: Test,
+        predicates: Vec<Predicate>,
+    }
+
+    // 查询结果：节点或字符串（属性值、文本）
+    pub enum Item<'a> {
+        Node(&'a Node),
+        Value(String),
+    }
+
+    impl Item<'_> {
+        pub fn to_json(&self) -> JsonValue {
+            match self {
+                Item::Node(node) => node.to_json(),
+                Item::Value(value) => JsonValue::String(value.clone()),
+            }
+        }
+    }
+
+    // 计算查询表达式，绝对路径从 document 开始，相对路径从 node 开始
+    pub fn evaluate<'a>(path: &str, node: &'a Node, document: &'a Node) -> Result<Vec<Item<'a>>, String> {
+        let path = path.trim();
+        if path.is_empty() {
+            return Err("错误: 查询路径不能为空".to_string());
+        }
+        let (steps, absolute) = parse_path(path)?;
+        let mut context: Vec<&Node> = vec![if absolute { document } else { node }];
+
+        for (i, step) in steps.iter().enumerate() {
+            let is_last = i + 1 == steps.len();
+            match step.test {
+                Test::Attribute(_) | Test::AnyAttribute | Test::Text => {
+                    if !is_last {
+                        return Err("错误: 属性和 text() 只能出现在路径的最后一步".to_string());
+                    }
+                    return Ok(collect_values(&context, step));
+                },
+                _ => {},
+            }
+
+            let mut next: Vec<&Node> = Vec::new();
+            for &ctx in &context {
+                let parents: Vec<&Node> = match step.axis {
+                    Axis::Child => vec![ctx],
+                    Axis::Descendant => descendants_or_self(ctx),
+                };
+                for parent in parents {
+                    let candidates: Vec<&Node> = match &step.test {
+                        Test::SelfNode => vec![parent],
+                        test => parent.children().iter().filter(|child| matches(child, test)).collect(),
+                    };
+                    for matched in apply_predicates(candidates, &step.predicates) {
+                        if !next.iter().any(|seen| std::ptr::eq(*seen, matched)) {
+                            next.push(matched);
+                        }
+                    }
+                }
+            }
+            context = next;
+        }
+        Ok(context.into_iter().map(Item::Node).collect())
+    }
+
+    fn collect_values<'a>(context: &[&'a Node], step: &Step) -> Vec<Item<'a>> {
+        let mut values = Vec::new();
+        for &ctx in context {
+            let nodes = match step.axis {
+                Axis::Child => vec![ctx],
+                Axis::Descendant => descendants_or_self(ctx),
+            };
+            for node in nodes {
+                match &step.test {
+                    Test::Attribute(name) => {
+                        if let Some(value) = node.attribute(name) {
+                            values.push(Item::Value(value.to_string()));
+                        }
+                    },
+                    Test::AnyAttribute => {
+                        values.extend(node.attributes().iter().map(|(_, value)| Item::Value(value.clone())));
+                    },
+                    _ => {
+                        for child in node.children() {
+                            if let Node::Text(text) = child {
+                                values.push(Item::Value(text.clone()));
+                            }
+                        }
+                    },
+                }
+            }
+        }
+        values
+    }
+
+    fn descendants_or_self(node: &Node) -> Vec<&Node> {
+        let mut result = vec![node];
+        let mut i = 0;
+        while i < result.len() {
+            let current = result[i];
+            result.extend(current.children().iter().filter(|child| child.name().is_some()));
+            i += 1;
+        }
+        result
+    }
+
+    fn matches(node: &Node, test: &Test) -> bool {
+        match (node.name(), test) {
+            (Some(name), Test::Name(expected)) => name == expected,
+            (Some(_), Test::AnyElement) => true,
+            _ => false,
+        }
+    }
+
+    fn apply_predicates<'a>(mut nodes: Vec<&'a Node>, predicates: &[Predicate]) -> Vec<&'a Node> {
+        for predicate in predicates {
+            nodes = match predicate {
+                Predicate::Position(n) => nodes.get(n - 1).map(|node| vec![*node]).unwrap_or_default(),
+                Predicate::Last => nodes.last().map(|node| vec![*node]).unwrap_or_default(),
+                _ => nodes.into_iter().filter(|node| check(node, predicate)).collect(),
+            };
+        }
+        nodes
+    }
+
+    fn check(node: &Node, predicate: &Predicate) -> bool {
+        match predicate {
+            Predicate::HasAttribute(name) => node.attribute(name).is_some(),
+            Predicate::AttributeEquals(name, value) => node.attribute(name) == Some(value.as_str()),
+            Predicate::TextEquals(value) => node.text_content().trim() == value,
+            Predicate::ChildEquals(name, value) => node.children().iter()
+                .any(|child| child.name() == Some(name.as_str()) && child.text_content().trim() == value),
+            Predicate::HasChild(name) => node.children().iter().any(|child| child.name() == Some(name.as_str())),
+            Predicate::Contains(Operand::Attribute(name), value) => node.attribute(name).is_some_and(|v| v.contains(value.as_str())),
+            Predicate::Contains(Operand::Text, value) => node.text_content().contains(value.as_str()),
+            Predicate::Position(_) | Predicate::Last => true,
+        }
+    }
+
+    // 解析路径，返回步骤列表和是否为绝对路径
+    fn parse_path(path: &str) -> Result<(Vec<Step>, bool), String> {
+        let absolute = path.starts_with('/');
+        let mut steps = Vec::new();
+        let mut rest = path;
+        while !rest.is_empty() {
+            let axis = if let Some(r) = rest.strip_prefix("//") {
+                rest = r;
+                Axis::Descendant
+            } else if let Some(r) = rest.strip_prefix('/') {
+                rest = r;
+                Axis::Child
+            } else if steps.is_empty() {
+                Axis::Child
+            } else {
+                return Err(format!("错误: 无效的查询路径: {}", path));
+            };
+            if rest.is_empty() {
+                // 单独的 "/" 表示文档本身
+                if matches!(axis, Axis::Child) && steps.is_empty() {
+                    steps.push(Step { axis, test: Test::SelfNode, predicates: Vec::new() });
+                    break;
+                }
+                return Err(format!("错误: 查询路径不完整: {}", path));
+            }
+            let end = step_end(rest);
+            steps.push(parse_step(&rest[..end], axis, path)?);
+            rest = &rest[end..];
+        }
+        Ok((steps, absolute))
+    }
+
+    // 找到当前步骤的结束位置（下一个不在方括号和引号内的 /）
+    fn step_end(input: &str) -> usize {
+        let mut depth = 0;
+        let mut quote: Option<char> = None;
+        for (i, c) in input.char_indices() {
+            match (quote, c) {
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), _) => {},
+                (None, '\'' | '"') => quote = Some(c),
+                (None, '[') => depth += 1,
+                (None, ']') => depth -= 1,
+                (None, '/') if depth == 0 => return i,
+                _ => {},
+            }
+        }
+        input.len()
+    }
+
+    fn parse_step(input: &str, axis: Axis, path: &str) -> Result<Step, String> {
+        let (test_part, mut rest) = match input.find('[') {
+            Some(pos) => (&input[..pos], &input[pos..]),
+            None => (input, ""),
+        };
+        let test = match test_part.trim() {
+            "" => return Err(format!("错误: 无效的查询路径: {}", path)),
+            "*" => Test::AnyElement,
+            "." => Test::SelfNode,
+            "@*" => Test::AnyAttribute,
+            "text()" => Test::Text,
+            name => match name.strip_prefix('@') {
+                Some(attr) => Test::Attribute(attr.to_string()),
+                None => Test::Name(name.to_string()),
+            },
+        };
+
+        let mut predicates = Vec::new();
+        while !rest.is_empty() {
+            let end = match closing_bracket(rest) {
+                Some(end) if rest.starts_with('[') => end,
+                _ => return Err(format!("错误: 无效的谓词: {}", rest)),
+            };
+            predicates.push(parse_predicate(rest[1..end].trim())?);
+            rest = &rest[end + 1..];
+        }
+        Ok(Step { axis, test, predicates })
+    }
+
+    fn closing_bracket(input: &str) -> Option<usize> {
+        let mut quote: Option<char> = None;
+        for (i, c) in input.char_indices() {
+            match (quote, c) {
+                (Some(q), c) if c == q => quote = None,
+                (Some(_), _) => {},
+                (None, '\'' | '"') => quote = Some(c),
+                (None, ']') => return Some(i),
+                _ => {},
+            }
+        }
+        None
+    }
+
+    fn parse_predicate(input: &str) -> Result<Predicate, String> {
+        if let Ok(n) = input.parse::<usize>() {
+            return if n == 0 {
+                Err("错误: 位置从1开始".to_string())
+            } else {
+                Ok(Predicate::Position(n))
+            };
+        }
+        if input == "last()" {
+            return Ok(Predicate::Last);
+        }
+        if let Some(args) = input.strip_prefix("contains(").and_then(|s| s.strip_suffix(')')) {
+            let (left, right) = match args.split_once(',') {
+                Some(split) => split,
+                None => return Err(format!("错误: 无效的谓词: {}", input)),
+            };
+            let operand = match left.trim() {
+                "text()" | "." => Operand::Text,
+                attr => match attr.strip_prefix('@') {
+                    Some(name) => Operand::Attribute(name.to_string()),
+                    None => return Err(format!("错误: 无效的谓词: {}", input)),
+                },
+            };
+            return Ok(Predicate::Contains(operand, unquote(right.trim(), input)?));
+        }
+        if let Some((left, right)) = input.split_once('=') {
+            let value = unquote(right.trim(), input)?;
+            return Ok(match left.trim() {
+                "text()" | "." => Predicate::TextEquals(value),
+                left => match left.strip_prefix('@') {
+                    Some(name) => Predicate::AttributeEquals(name.to_string(), value),
+                    None => Predicate::ChildEquals(left.to_string(), value),
+                },
+            });
+        }
+        match input.strip_prefix('@') {
+            Some(name) => Ok(Predicate::HasAttribute(name.to_string())),
+            None if !input.is_empty() => Ok(Predicate::HasChild(input.to_string())),
+            None => Err("错误: 谓词不能为空".to_string()),
+        }
+    }
+
+    fn unquote(value: &str, predicate: &str) -> Result<String, String> {
+        let quoted = value.len() >= 2
+            && ((value.starts_with('\'') && value.ends_with('\'')) || (value.starts_with('"') && value.ends_with('"')));
+        if quoted {
+            Ok(value[1..value.len() - 1].to_string())
+        } else {
+            Err(format!("错误: 谓词中的值需要用引号括起来: {}", predicate))
+        }
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册XML命名空间下的函数
+    let xml_ns = registry.namespace("xml");
+    xml_ns.add_function("parse", xml::cn_parse)
+          .add_function("parse_html", xml::cn_parse_html)
+          .add_function("select", xml::cn_select)
+          .add_function("select_one", xml::cn_select_one)
+          .add_function("text", xml::cn_text)
+          .add_function("attr", xml::cn_attr)
+          .add_function("to_string", xml::cn_to_string)
+          .add_function("is_valid", xml::cn_is_valid);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
@@ -0,0 +1,243 @@
+use ::std::collections::HashMap;
+use serde::Deserialize;
+use serde_json::{Map, Value as JsonValue};
+use serde_yaml::Value as YamlValue;
+
+// 导入通用库
+use cn_common::namespace::{LibraryFunction, LibraryRegistry};
+use cn_common::value::{json_value, parse_path, serialize, PathSegment};
+
+// YAML命名空间
+mod yaml {
+    use super::*;
+
+    // 解析YAML字符串，返回原生值：映射转换为 Map，序列转换为 Array
+    // 包含多个文档时只返回第一个文档，空文档返回空值
+    // 参数: yaml
+    pub fn cn_parse(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 未提供YAML字符串".to_string();
+        }
+        match parse_documents(&args[0]) {
+            Ok(documents) => json_value(&serialize(documents.first().unwrap_or(&JsonValue::Null))),
+            Err(e) => e,
+        }
+    }
+
+    // 解析包含多个文档（以 --- 分隔）的YAML字符串，返回文档数组
+    // 参数: yaml
+    pub fn cn_parse_all(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 未提供YAML字符串".to_string();
+        }
+        match parse_documents(&args[0]) {
+            Ok(documents) => json_value(&serialize(&JsonValue::Array(documents))),
+            Err(e) => e,
+        }
+    }
+
+    // 将值格式化为YAML字符串
+    // Map 和 Array 以JSON形式传入；其他字符串按YAML解析后重新输出，可用于规范化YAML
+    // 参数: value
+    pub fn cn_format(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 未提供要格式化的值".to_string();
+        }
+        match parse_value_arg(&args[0]) {
+            Ok(value) => emit(&value),
+            Err(e) => e,
+        }
+    }
+
+    // 将数组中的每个元素格式化为一个YAML文档，以 --- 分隔
+    // 参数: documents（数组）
+    pub fn cn_format_all(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 未提供文档数组".to_string();
+        }
+        let documents = match parse_value_arg(&args[0]) {
+            Ok(JsonValue::Array(documents)) => documents,
+            Ok(_) => return "错误: 参数必须是数组".to_string(),
+            Err(e) => return e,
+        };
+
+        let mut output = String::new();
+        for document in &documents {
+            let text = emit(document);
+            if text.starts_with("错误:") {
+                return text;
+            }
+            output.push_str("---\n");
+            output.push_str(&text);
+        }
+        output
+    }
+
+    // 从YAML中获取值，路径写法与 json::get_value 相同，如 "server.ports.[0]" 或 "server.ports[0]"
+    // 参数: yaml, path, [document]（文档序号，默认0）
+    pub fn cn_get_value(args: Vec<String>) -> String {
+        if args.len() < 2 {
+            return "错误: 请提供YAML字符串和路径".to_string();
+        }
+        let index = match args.get(2).map(|s| s.trim().parse::<usize>()) {
+            Some(Ok(index)) => index,
+            Some(Err(_)) => return format!("错误: 无效的文档序号: {}", args[2]),
+            None => 0,
+        };
+
+        let documents = match parse_documents(&args[0]) {
+            Ok(documents) => documents,
+            Err(e) => return e,
+        };
+        let document = match documents.get(index) {
+            Some(document) => document,
+            None => return format!("错误: 文档不存在: {}", index),
+        };
+        match value_at_path(document, &args[1]) {
+            Ok(value) => json_value(&serialize(value)),
+            Err(e) => e,
+        }
+    }
+
+    // 将YAML转换为JSON字符串
+    // 参数: yaml, [indent]（缩进空格数，省略时输出紧凑格式）
+    pub fn cn_to_json(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "错误: 未提供YAML字符串".to_string();
+        }
+        let documents = match parse_documents(&args[0]) {
+            Ok(documents) => documents,
+            Err(e) => return e,
+        };
+        let value = documents.into_iter().next().unwrap_or(JsonValue::Null);
+        match args.get(1).map(|indent| indent.trim().parse::<usize>()) {
+            Some(Ok(width)) if width <= 16 => {
+                let mut buf = Vec::new();
+                let indent = " ".repeat(width);
+                let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+                let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
+                match serde::Serialize::serialize(&value, &mut ser) {
+                    Ok(_) => String::from_utf8(buf).unwrap_or_default(),
+                    Err(e) => format!("错误: 序列化结果失败: {}", e),
+                }
+            },
+            Some(_) => format!("错误: 无效的缩进: {}", args[1]),
+            None => serialize(&value),
+        }
+    }
+
+    // 检查YAML是否有效
+    // 参数: yaml
+    pub fn cn_is_valid(args: Vec<String>) -> String {
+        if args.is_empty() {
+            return "false".to_string();
+        }
+        parse_documents(&args[0]).is_ok().to_string()
+    }
+
+    // 解析所有YAML文档并转换为JSON值，合并键（<<）会被展开
+    fn parse_documents(input: &str) -> Result<Vec<JsonValue>, String> {
+        let mut documents = Vec::new();
+        for document in serde_yaml::Deserializer::from_str(input) {
+            let mut value = YamlValue::deserialize(document).map_err(|e| format!("错误: 解析YAML失败: {}", e))?;
+            value.apply_merge().map_err(|e| format!("错误: 展开合并键失败: {}", e))?;
+            documents.push(yaml_to_json(value));
+        }
+        Ok(documents)
+    }
+
+    // 将参数解析为值：先按JSON解析（脚本中的 Map 和 Array），失败时按YAML解析
+    fn parse_value_arg(input: &str) -> Result<JsonValue, String> {
+        if let Ok(value) = serde_json::from_str::<JsonValue>(input) {
+            return Ok(value);
+        }
+        parse_documents(input).map(|documents| documents.into_iter().next().unwrap_or(JsonValue::Null))
+    }
+
+    // YAML值转换为JSON值：非字符串键转换为字符串，标签被忽略，NaN 和无穷大保留为YAML写法的字符串
+    fn yaml_to_json(value: YamlValue) -> JsonValue {
+        match value {
+            YamlValue::Null => JsonValue::Null,
+            YamlValue::Bool(b) => JsonValue::Bool(b),
+            YamlValue::Number(n) => {
+                if let Some(i) = n.as_i64() {
+                    JsonValue::from(i)
+                } else if let Some(u) = n.as_u64() {
+                    JsonValue::from(u)
+                } else {
+                    let f = n.as_f64().unwrap_or(f64::NAN);
+                    serde_json::Number::from_f64(f)
+                        .map(JsonValue::Number)
+                        .unwrap_or_else(|| JsonValue::String(n.to_string()))
+                }
+            },
+            YamlValue::String(s) => JsonValue::String(s),
+            YamlValue::Sequence(items) => JsonValue::Array(items.into_iter().map(yaml_to_json).collect()),
+            YamlValue::Mapping(mapping) => {
+                let mut obj = Map::new();
+                for (key, value) in mapping {
+                    obj.insert(key_to_string(key), yaml_to_json(value));
+                }
+                JsonValue::Object(obj)
+            },
+            YamlValue::Tagged(tagged) => yaml_to_json(tagged.value),
+        }
+    }
+
+    // 将映射的键转换为字符串
+    fn key_to_string(key: YamlValue) -> String {
+        match key {
+            YamlValue::String(s) => s,
+            YamlValue::Null => "null".to_string(),
+            YamlValue::Bool(b) => b.to_string(),
+            YamlValue::Number(n) => n.to_string(),
+            other => serde_yaml::to_string(&other).map(|s| s.trim_end().to_string()).unwrap_or_default(),
+        }
+    }
+
+    // 按路径查找值，支持 "a.b.[0].c" 和 "a.b[0].c" 两种写法，空路径表示根节点
+    fn value_at_path<'a>(value: &'a JsonValue, path: &str) -> Result<&'a JsonValue, String> {
+        let mut current = value;
+        for segment in parse_path(path)? {
+            current = match segment {
+                PathSegment::Key(key) => match current.as_object().and_then(|obj| obj.get(&key)) {
+                    Some(v) => v,
+                    None => return Err(format!("错误: 属性不存在: {}", key)),
+                },
+                PathSegment::Index(idx) => match current.as_array().and_then(|arr| arr.get(idx)) {
+                    Some(v) => v,
+                    None => return Err(format!("错误: 无效的数组索引: [{}]", idx)),
+                },
+            };
+        }
+        Ok(current)
+    }
+
+    // 输出YAML文本
+    fn emit(value: &JsonValue) -> String {
+        match serde_yaml::to_string(value) {
+            Ok(result) => result,
+            Err(e) => format!("错误: 生成YAML失败: {}", e),
+        }
+    }
+}
+
+// 初始化函数，返回函数映射
+#[no_mangle]
+pub extern "C" fn cn_init() -> *mut HashMap<String, LibraryFunction> {
+    // 创建库函数注册器
+    let mut registry = LibraryRegistry::new();
+
+    // 注册YAML命名空间下的函数
+    let yaml_ns = registry.namespace("yaml");
+    yaml_ns.add_function("parse", yaml::cn_parse)
+           .add_function("parse_all", yaml::cn_parse_all)
+           .add_function("format", yaml::cn_format)
+           .add_function("format_all", yaml::cn_format_all)
+           .add_function("get_value", yaml::cn_get_value)
+           .add_function("to_json", yaml::cn_to_json)
+           .add_function("is_valid", yaml::cn_is_valid);
+
+    // 构建并返回库指针
+    registry.build_library_pointer()
+}
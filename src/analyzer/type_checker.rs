@@ -498,16 +498,27 @@ impl TypeChecker {
                 let array_type = self.infer_expression_type(array_expr);
                 let index_type = self.infer_expression_type(index_expr);
 
-                // 索引必须是整数类型
-                if !matches!(index_type, Type::Int | Type::Long) {
-                    self.errors.push(TypeCheckError::new(
-                        format!("数组索引必须是整数类型，但得到 {:?}", index_type)
-                    ));
-                }
-
-                // 返回数组元素类型
                 match array_type {
-                    Type::Array(element_type) => *element_type,
+                    Type::Array(element_type) => {
+                        // 数组索引必须是整数类型
+                        if !matches!(index_type, Type::Int | Type::Long | Type::Auto) {
+                            self.errors.push(TypeCheckError::new(
+                                format!("数组索引必须是整数类型，但得到 {:?}", index_type)
+                            ));
+                        }
+                        *element_type
+                    },
+                    Type::Map(_, value_type) => {
+                        // 映射索引必须是字符串类型
+                        if !matches!(index_type, Type::String | Type::Auto) {
+                            self.errors.push(TypeCheckError::new(
+                                format!("映射索引必须是字符串类型，但得到 {:?}", index_type)
+                            ));
+                        }
+                        *value_type
+                    },
+                    // 自动类型（例如库函数返回的JSON数组或映射）在运行时才能确定
+                    Type::Auto => Type::Auto,
                     _ => {
                         self.errors.push(TypeCheckError::new(
                             format!("尝试对非数组类型进行索引访问: {:?}", array_type)
@@ -782,6 +793,23 @@ impl TypeChecker {
                     }
                 }
             },
+            Type::Map(key_type, value_type) => {
+                match method_name {
+                    "size" => Type::Int,
+                    "get" => (**value_type).clone(),
+                    "set" => Type::Map(key_type.clone(), value_type.clone()),
+                    "keys" => Type::Array(key_type.clone()),
+                    "contains" => Type::Bool,
+                    _ => {
+                        self.errors.push(TypeCheckError::new(
+                            format!("映射类型没有方法 '{}'", method_name)
+                        ));
+                        Type::Auto
+                    }
+                }
+            },
+            // 自动类型的实际类型在运行时才能确定，方法调用交给运行时检查
+            Type::Auto => Type::Auto,
             Type::Class(class_name) => {
                 // 检查类的方法
                 if let Some(class_methods) = self.class_methods.get(class_name).cloned() {
@@ -190,6 +190,11 @@ impl<'a> ExpressionEvaluator for Interpreter<'a> {
             return Value::None;
         }
 
+        // 同一语句中已有函数抛出异常，不再计算后面的表达式，由语句继续抛出
+        if self.pending_exception.is_some() {
+            return Value::None;
+        }
+
         // 启用常量表达式JIT优化
         if self.is_pure_constant_expression(expr) {
             if let Some(val) = jit::jit_eval_const_expr(expr) {
@@ -262,6 +267,9 @@ impl<'a> ExpressionEvaluator for Interpreter<'a> {
 
                 let array_value = self.evaluate_expression(array_expr);
                 let index_value = self.evaluate_expression(index_expr);
+                if self.pending_exception.is_some() {
+                    return Value::None;
+                }
 
                 match (array_value, index_value) {
                     (Value::Array(arr), Value::Int(index)) => {
@@ -273,8 +281,15 @@ impl<'a> ExpressionEvaluator for Interpreter<'a> {
                     (Value::Array(_), _) => {
                         panic!("数组索引必须是整数类型");
                     },
+                    (Value::Map(map), Value::String(key)) => {
+                        // 映射按键访问，键不存在时返回空值
+                        map.get(&key).cloned().unwrap_or(Value::None)
+                    },
+                    (Value::Map(_), _) => {
+                        panic!("映射索引必须是字符串类型");
+                    },
                     _ => {
-                        panic!("只能对数组进行索引访问");
+                        panic!("只能对数组或映射进行索引访问");
                     }
                 }
             },
@@ -283,6 +298,7 @@ impl<'a> ExpressionEvaluator for Interpreter<'a> {
                 for (key_expr, value_expr) in entries {
                     let key = match self.evaluate_expression(key_expr) {
                         Value::String(s) => s,
+                        _ if self.pending_exception.is_some() => return Value::None,
                         _ => panic!("映射键必须是字符串类型"),
                     };
                     let value = self.evaluate_expression(value_expr);
@@ -296,6 +312,9 @@ impl<'a> ExpressionEvaluator for Interpreter<'a> {
             Expression::FunctionPointerCall(func_expr, args) => {
                 let func_value = self.evaluate_expression(func_expr);
                 let arg_values: Vec<Value> = args.iter().map(|arg| self.evaluate_expression(arg)).collect();
+                if self.pending_exception.is_some() {
+                    return Value::None;
+                }
 
                 match func_value {
                     Value::FunctionPointer(func_ptr) => {
@@ -362,6 +381,9 @@ impl<'a> ExpressionEvaluator for Interpreter<'a> {
 
                 let left_val = self.evaluate_expression(left);
                 let right_val = self.evaluate_expression(right);
+                if self.pending_exception.is_some() {
+                    return Value::None;
+                }
 
                 // 内联简单的整数运算，避免函数调用开销
                 match (&left_val, op, &right_val) {
@@ -395,7 +417,10 @@ impl<'a> ExpressionEvaluator for Interpreter<'a> {
             Expression::CompareOp(left, op, right) => {
                 let left_val = self.evaluate_expression(left);
                 let right_val = self.evaluate_expression(right);
-                
+                if self.pending_exception.is_some() {
+                    return Value::None;
+                }
+
                 self.evaluate_compare_operation(&left_val, op, &right_val)
             },
             Expression::LogicalOp(left, op, right) => {
@@ -811,6 +836,7 @@ impl<'a> Interpreter<'a> {
                 let left_val = self.evaluate_expression(left);
                 let right_val = self.evaluate_expression(right);
                 match (left_val, right_val) {
+                    _ if self.pending_exception.is_some() => Value::None,
                     (Value::Bool(a), Value::Bool(b)) => Value::Bool(jit::jit_and_bool(a, b)),
                     _ => panic!("逻辑操作符的操作数必须是布尔类型"),
                 }
@@ -819,6 +845,7 @@ impl<'a> Interpreter<'a> {
                 let left_val = self.evaluate_expression(left);
                 let right_val = self.evaluate_expression(right);
                 match (left_val, right_val) {
+                    _ if self.pending_exception.is_some() => Value::None,
                     (Value::Bool(a), Value::Bool(b)) => Value::Bool(jit::jit_or_bool(a, b)),
                     _ => panic!("逻辑操作符的操作数必须是布尔类型"),
                 }
@@ -827,6 +854,7 @@ impl<'a> Interpreter<'a> {
                 // 对于否定操作，实际的表达式在右操作数位置
                 let val = self.evaluate_expression(right);
                 match val {
+                    _ if self.pending_exception.is_some() => Value::None,
                     Value::Bool(b) => Value::Bool(!b),
                     _ => panic!("逻辑否定操作符的操作数必须是布尔类型"),
                 }
@@ -877,6 +905,7 @@ impl<'a> Interpreter<'a> {
         match condition_val {
             Value::Bool(true) => self.evaluate_expression(true_expr),
             Value::Bool(false) => self.evaluate_expression(false_expr),
+            _ if self.pending_exception.is_some() => Value::None,
             _ => panic!("三元运算符的条件必须是布尔类型"),
         }
     }
@@ -1015,7 +1044,10 @@ impl<'a> Interpreter<'a> {
             let arg_value = self.evaluate_expression(arg);
             evaluated_args.push(arg_value.to_string());
         }
-        
+        if self.pending_exception.is_some() {
+            return Value::None;
+        }
+
         // 根据对象类型调用相应的方法
         match obj_value {
             Value::String(s) => {
@@ -1077,7 +1109,10 @@ impl<'a> Interpreter<'a> {
                 let arg_value = self.evaluate_expression(arg);
                 evaluated_args.push(arg_value.to_string());
             }
-            
+            if self.pending_exception.is_some() {
+                return Value::None;
+            }
+
             // 根据当前值类型调用相应的方法
             current_value = match &current_value {
                 Value::String(s) => {
@@ -1237,6 +1272,22 @@ impl<'a> Interpreter<'a> {
                     panic!("set方法需要两个参数")
                 }
             },
+            "keys" => {
+                if args.is_empty() {
+                    let mut keys: Vec<&String> = map.keys().collect();
+                    keys.sort();
+                    Value::Array(keys.into_iter().map(|k| Value::String(k.clone())).collect())
+                } else {
+                    panic!("keys方法不接受参数")
+                }
+            },
+            "contains" => {
+                if args.len() == 1 {
+                    Value::Bool(map.contains_key(&args[0]))
+                } else {
+                    panic!("contains方法需要一个参数")
+                }
+            },
             _ => {
                 panic!("未知的映射方法: {}", method_name)
             }
@@ -1373,6 +1424,9 @@ impl<'a> Interpreter<'a> {
     
     fn access_field(&mut self, obj_expr: &Expression, field_name: &str) -> Value {
         let obj_value = self.evaluate_expression(obj_expr);
+        if self.pending_exception.is_some() {
+            return Value::None;
+        }
 
         match obj_value {
             Value::Object(obj) => {
@@ -1883,17 +1937,7 @@ impl<'a> Interpreter<'a> {
                         match super::library_loader::call_library_function(lib_name, &full_path, string_args) {
                             Ok(result) => {
                                 // 尝试将结果转换为适当的值类型
-                                if let Ok(int_val) = result.parse::<i32>() {
-                                    return Value::Int(int_val);
-                                } else if let Ok(float_val) = result.parse::<f64>() {
-                                    return Value::Float(float_val);
-                                } else if result == "true" {
-                                    return Value::Bool(true);
-                                } else if result == "false" {
-                                    return Value::Bool(false);
-                                } else {
-                                    return Value::String(result);
-                                }
+                                return self.finish_library_call(&lib_name.clone(), result);
                             },
                             Err(_) => return Value::None,
                         }
@@ -3176,6 +3220,7 @@ impl<'a> Interpreter<'a> {
             lambda_body: Some(Box::new(lambda_body)),
             lambda_params: params.to_vec(), // 保存完整的参数信息
             closure_env,
+            lambda_id: super::value::next_lambda_id(),
         };
 
         debug_println("创建Lambda表达式函数指针成功");
@@ -3213,6 +3258,7 @@ impl<'a> Interpreter<'a> {
             lambda_body: Some(Box::new(lambda_body)),
             lambda_params: params.to_vec(), // 保存完整的参数信息
             closure_env,
+            lambda_id: super::value::next_lambda_id(),
         };
 
         debug_println("创建Lambda块函数指针成功");
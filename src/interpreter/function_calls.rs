@@ -1,7 +1,7 @@
 use crate::ast::{Expression, Function};
 use std::collections::HashMap;
 use super::value::Value;
-use super::library_loader::{call_library_function, convert_values_to_string_args};
+use super::library_loader::{call_library_function, call_library_continuation, convert_values_to_string_args, convert_value_to_string_arg, convert_callback_result_arg, convert_library_result, parse_library_callback, library_lambda, retain_library_callbacks, release_library_lambdas, CALLBACK_THROW_PREFIX};
 use super::interpreter_core::{Interpreter, debug_println};
 use super::expression_evaluator::ExpressionEvaluator;
 
@@ -38,17 +38,7 @@ impl<'a> FunctionCallHandler for Interpreter<'a> {
                     Ok(result) => {
                         debug_println(&format!("库函数调用成功: {} -> {}", name, result));
                         // 尝试将结果转换为适当的值类型
-                        if let Ok(int_val) = result.parse::<i32>() {
-                            return Value::Int(int_val);
-                        } else if let Ok(float_val) = result.parse::<f64>() {
-                            return Value::Float(float_val);
-                        } else if result == "true" {
-                            return Value::Bool(true);
-                        } else if result == "false" {
-                            return Value::Bool(false);
-                        } else {
-                            return Value::String(result);
-                        }
+                        return self.finish_library_call(&lib_name.clone(), result);
                     },
                     Err(err) => {
                         debug_println(&format!("调用库函数失败: {}", err));
@@ -66,22 +56,13 @@ impl<'a> FunctionCallHandler for Interpreter<'a> {
                     
                     // 将参数转换为字符串
                     let string_args = convert_values_to_string_args(&arg_values);
+                    retain_library_callbacks(lib_name, name, &string_args);
                     
                     let result = func(string_args);
                     debug_println(&format!("库函数调用成功: {} -> {}", name, result));
                     
                     // 尝试将结果转换为适当的值类型
-                    if let Ok(int_val) = result.parse::<i32>() {
-                        return Value::Int(int_val);
-                    } else if let Ok(float_val) = result.parse::<f64>() {
-                        return Value::Float(float_val);
-                    } else if result == "true" {
-                        return Value::Bool(true);
-                    } else if result == "false" {
-                        return Value::Bool(false);
-                    } else {
-                        return Value::String(result);
-                    }
+                    return self.finish_library_call(&lib_name.clone(), result);
                 }
             }
             
@@ -113,17 +94,7 @@ impl<'a> FunctionCallHandler for Interpreter<'a> {
             match call_library_function(lib_name, func_name, string_args) {
                 Ok(result) => {
                     // 尝试将结果转换为适当的值类型
-                    if let Ok(int_val) = result.parse::<i32>() {
-                        return Value::Int(int_val);
-                    } else if let Ok(float_val) = result.parse::<f64>() {
-                        return Value::Float(float_val);
-                    } else if result == "true" {
-                        return Value::Bool(true);
-                    } else if result == "false" {
-                        return Value::Bool(false);
-                    } else {
-                        return Value::String(result);
-                    }
+                    return self.finish_library_call(&lib_name.clone(), result);
                 },
                 Err(err) => {
                     panic!("调用库函数失败: {}", err);
@@ -151,17 +122,7 @@ impl<'a> FunctionCallHandler for Interpreter<'a> {
                     match call_library_function(lib_name, func_name, string_args) {
                         Ok(result) => {
                             // 尝试将结果转换为适当的值类型
-                            if let Ok(int_val) = result.parse::<i32>() {
-                                return Value::Int(int_val);
-                            } else if let Ok(float_val) = result.parse::<f64>() {
-                                return Value::Float(float_val);
-                            } else if result == "true" {
-                                return Value::Bool(true);
-                            } else if result == "false" {
-                                return Value::Bool(false);
-                            } else {
-                                return Value::String(result);
-                            }
+                            return self.finish_library_call(&lib_name.clone(), result);
                         },
                         Err(err) => {
                             debug_println(&format!("调用库函数失败: {}", err));
@@ -229,19 +190,10 @@ impl<'a> FunctionCallHandler for Interpreter<'a> {
             
             if let Some(func) = lib_functions.get(name) {
                 debug_println(&format!("在库 '{}' 中找到函数 '{}'", lib_name, name));
+                retain_library_callbacks(lib_name, name, &string_args);
                 let result = func(string_args.clone());
                 // 尝试将结果转换为适当的值类型
-                if let Ok(int_val) = result.parse::<i32>() {
-                    return Value::Int(int_val);
-                } else if let Ok(float_val) = result.parse::<f64>() {
-                    return Value::Float(float_val);
-                } else if result == "true" {
-                    return Value::Bool(true);
-                } else if result == "false" {
-                    return Value::Bool(false);
-                } else {
-                    return Value::String(result);
-                }
+                return self.finish_library_call(&lib_name.clone(), result);
             }
             
             // 尝试查找命名空间函数
@@ -251,19 +203,10 @@ impl<'a> FunctionCallHandler for Interpreter<'a> {
                 
                 if let Some(func) = lib_functions.get(&ns_func_name) {
                     debug_println(&format!("在库 '{}' 中找到命名空间函数 '{}'", lib_name, ns_func_name));
+                    retain_library_callbacks(lib_name, &ns_func_name, &string_args);
                     let result = func(string_args.clone());
                     // 尝试将结果转换为适当的值类型
-                    if let Ok(int_val) = result.parse::<i32>() {
-                        return Value::Int(int_val);
-                    } else if let Ok(float_val) = result.parse::<f64>() {
-                        return Value::Float(float_val);
-                    } else if result == "true" {
-                        return Value::Bool(true);
-                    } else if result == "false" {
-                        return Value::Bool(false);
-                    } else {
-                        return Value::String(result);
-                    }
+                    return self.finish_library_call(&lib_name.clone(), result);
                 }
             }
         }
@@ -366,17 +309,7 @@ impl<'a> FunctionCallHandler for Interpreter<'a> {
                     Ok(result) => {
                         debug_println(&format!("库函数调用成功: {} -> {}", full_path, result));
                         // 尝试将结果转换为适当的值类型
-                        if let Ok(int_val) = result.parse::<i32>() {
-                            return Value::Int(int_val);
-                        } else if let Ok(float_val) = result.parse::<f64>() {
-                            return Value::Float(float_val);
-                        } else if result == "true" {
-                            return Value::Bool(true);
-                        } else if result == "false" {
-                            return Value::Bool(false);
-                        } else {
-                            return Value::String(result);
-                        }
+                        return self.finish_library_call(&lib_name.clone(), result);
                     },
                     Err(err) => {
                         debug_println(&format!("调用库函数失败: {}", err));
@@ -426,21 +359,12 @@ impl<'a> FunctionCallHandler for Interpreter<'a> {
                     
                     if let Some(func) = lib_functions.get(&full_path) {
                         debug_println(&format!("在库 '{}' 中找到命名空间函数 '{}'", lib_name, full_path));
+                        retain_library_callbacks(lib_name, &full_path, &string_args);
                         let result = func(string_args.clone());
                         found = true;
                         
                         // 尝试将结果转换为适当的值类型
-                        if let Ok(int_val) = result.parse::<i32>() {
-                            return Value::Int(int_val);
-                        } else if let Ok(float_val) = result.parse::<f64>() {
-                            return Value::Float(float_val);
-                        } else if result == "true" {
-                            return Value::Bool(true);
-                        } else if result == "false" {
-                            return Value::Bool(false);
-                        } else {
-                            return Value::String(result);
-                        }
+                        return self.finish_library_call(&lib_name.clone(), result);
                     }
                 }
             }
@@ -579,17 +503,7 @@ impl<'a> FunctionCallHandler for Interpreter<'a> {
         match call_library_function(lib_name, func_name, arg_values) {
             Ok(result) => {
                 // 尝试将结果转换为适当的值类型
-                if let Ok(int_val) = result.parse::<i32>() {
-                    Value::Int(int_val)
-                } else if let Ok(float_val) = result.parse::<f64>() {
-                    Value::Float(float_val)
-                } else if result == "true" {
-                    Value::Bool(true)
-                } else if result == "false" {
-                    Value::Bool(false)
-                } else {
-                    Value::String(result)
-                }
+                self.finish_library_call(lib_name, result)
             },
             Err(err) => {
                 panic!("调用库函数失败: {}", err);
@@ -621,6 +535,11 @@ impl<'a> Interpreter<'a> {
     fn call_named_function_impl(&mut self, func_name: &str, args: Vec<Value>) -> Value {
         debug_println(&format!("通过函数指针调用函数: {}", func_name));
 
+        // 同一语句中之前调用的函数已经抛出异常，不再调用后面的函数
+        if self.pending_exception.is_some() {
+            return Value::None;
+        }
+
         // 检查函数是否存在
         if !self.functions.contains_key(func_name) {
             panic!("函数 '{}' 不存在", func_name);
@@ -780,6 +699,11 @@ impl<'a> Interpreter<'a> {
             panic!("尝试调用空Lambda函数指针");
         }
 
+        // 同一语句中之前调用的函数已经抛出异常，不再调用后面的函数
+        if self.pending_exception.is_some() {
+            return Value::None;
+        }
+
         if let Some(body) = &lambda_ptr.lambda_body {
             // 检查参数数量
             if args.len() != lambda_ptr.lambda_params.len() {
@@ -848,4 +772,60 @@ impl<'a> Interpreter<'a> {
             _ => true, // 其他类型默认为真
         }
     }
-}
\ No newline at end of file
+}
+
+// 库函数回调的辅助方法
+impl<'a> Interpreter<'a> {
+    // 处理库函数的返回值：库请求回调脚本函数时调用该函数，再把函数的返回值交给续接函数，
+    // 直到续接函数返回普通值为止；最外层的调用结束后释放没有被库保存的Lambda
+    pub fn finish_library_call(&mut self, lib_name: &str, result: String) -> Value {
+        let mut result = result;
+        while let Some(callback) = parse_library_callback(&result) {
+            debug_println(&format!("库 '{}' 请求回调函数: {} -> {}", lib_name, callback.function, callback.resume));
+            let resume_args = match self.call_library_callback(&callback.function, callback.args) {
+                Ok(returned) => vec![convert_callback_result_arg(&returned)],
+                // 未捕获的异常交给续接函数处理
+                Err(message) => vec![format!("{}{}", CALLBACK_THROW_PREFIX, message)],
+            };
+            result = match call_library_continuation(lib_name, &callback.resume, resume_args) {
+                Ok(result) => result,
+                Err(err) => panic!("调用库函数 {} 失败: {}", callback.resume, err),
+            };
+        }
+        if self.library_callback_depth == 0 {
+            release_library_lambdas();
+        }
+        convert_library_result(result)
+    }
+
+    // 调用库请求的脚本函数（Lambda、全局函数或命名空间函数），函数抛出未捕获的异常时返回异常信息
+    fn call_library_callback(&mut self, name: &str, args: Vec<Value>) -> Result<Value, String> {
+        let lambda = library_lambda(name);
+        let function: Option<&'a Function> = self.functions.get(name).or_else(|| self.namespaced_functions.get(name)).copied();
+
+        // 回调期间未捕获的异常沿语句逐层向上抛出，脚本中的 try/catch 仍然可以捕获，最终未捕获的保存在 pending_exception 中
+        self.library_callback_depth += 1;
+        let value = match (&lambda, function) {
+            (Some(lambda), _) => self.call_lambda_function_pointer_impl(lambda, args),
+            (None, Some(function)) => self.call_function_impl(function, args),
+            (None, None) => self.call_named_function_impl(name, args),
+        };
+        self.library_callback_depth -= 1;
+
+        match self.pending_exception.take() {
+            Some(exception) => Err(exception_message(&exception)),
+            None => Ok(value),
+        }
+    }
+}
+
+// 异常信息：对象取 message 字段，其他值直接转换为字符串
+fn exception_message(exception: &Value) -> String {
+    match exception {
+        Value::Object(obj) => match obj.fields.get("message") {
+            Some(message) => convert_value_to_string_arg(message),
+            None => convert_value_to_string_arg(exception),
+        },
+        _ => convert_value_to_string_arg(exception),
+    }
+}
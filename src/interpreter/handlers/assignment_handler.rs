@@ -15,6 +15,9 @@ pub fn handle_compound_assignment(interpreter: &mut Interpreter, name: String, o
     
     // 计算右侧表达式的值
     let right_value = interpreter.evaluate_expression(&expr);
+    if interpreter.pending_exception.is_some() {
+        return ExecutionResult::None;
+    }
     
     // 执行复合赋值操作
     let new_value = interpreter.perform_binary_operation(&current_value, &op, &right_value);
@@ -12,6 +12,10 @@ use std::time::Instant;
 pub fn handle_if_else(interpreter: &mut Interpreter, condition: Expression, if_block: Vec<Statement>, else_blocks: Vec<(Option<Expression>, Vec<Statement>)>) -> ExecutionResult {
     // 修复借用问题：不直接传递self，而是分别计算条件和执行语句块
     let condition_value = interpreter.evaluate_expression(&condition);
+    // 条件中调用的函数抛出了异常，由 execute_statement 继续抛出
+    if interpreter.pending_exception.is_some() {
+        return ExecutionResult::None;
+    }
     
     // 检查条件是否为真
     let is_true = match condition_value {
@@ -34,6 +38,9 @@ pub fn handle_if_else(interpreter: &mut Interpreter, condition: Expression, if_b
                 Some(else_if_condition) => {
                     // 这是 else-if 块，需要计算条件
                     let else_if_value = interpreter.evaluate_expression(&else_if_condition);
+                    if interpreter.pending_exception.is_some() {
+                        return ExecutionResult::None;
+                    }
                     let else_if_is_true = match else_if_value {
                         Value::Bool(b) => b,
                         _ => panic!("else-if 条件表达式必须是布尔类型"),
@@ -237,6 +244,8 @@ fn evaluate_for_loop_range(interpreter: &mut Interpreter, range_start: &Expressi
     // 获取起始和结束的整数值
     match (&start_value, &end_value) {
         (Value::Int(s), Value::Int(e)) => (*s, *e),
+        // 范围中调用的函数抛出了异常，返回空范围，由 execute_statement 继续抛出
+        _ if interpreter.pending_exception.is_some() => (1, 0),
         _ => panic!("for循环的范围必须是整数类型"),
     }
 }
@@ -267,6 +276,9 @@ fn execute_for_loop_optimized(interpreter: &mut Interpreter, var_name: &str, sta
 pub fn handle_foreach_loop(interpreter: &mut Interpreter, variable_name: String, collection_expr: Expression, loop_body: Vec<Statement>) -> ExecutionResult {
     // 计算集合表达式
     let collection = interpreter.evaluate_expression(&collection_expr);
+    if interpreter.pending_exception.is_some() {
+        return ExecutionResult::None;
+    }
 
     // 优化：预分配变量名，避免重复克隆
     let var_name_key = variable_name;
@@ -478,6 +490,8 @@ pub fn handle_while_loop(interpreter: &mut Interpreter, condition: Expression, l
             // 检查条件是否为真
             match condition_value {
                 Value::Bool(b) => b,
+                // 条件中调用的函数抛出了异常，结束循环，由 execute_statement 继续抛出
+                _ if interpreter.pending_exception.is_some() => false,
                 _ => panic!("while循环的条件必须是布尔类型"),
             }
         };
@@ -545,6 +559,7 @@ fn evaluate_simple_condition(interpreter: &mut Interpreter, condition: &Expressi
             let condition_value = interpreter.evaluate_expression(condition);
             match condition_value {
                 Value::Bool(b) => b,
+                _ if interpreter.pending_exception.is_some() => false,
                 _ => panic!("while循环的条件必须是布尔类型"),
             }
         }
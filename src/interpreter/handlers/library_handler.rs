@@ -32,8 +32,9 @@ pub fn handle_library_function_call_statement(interpreter: &mut Interpreter, lib
     // 调用库函数
     match call_library_function(&lib_name, &func_name, arg_values) {
         Ok(result) => {
-            // 库函数调用成功，但我们不需要返回值
+            // 库函数调用成功，但我们不需要返回值；库可能请求回调脚本函数
             debug_println(&format!("库函数调用成功: {}::{}", lib_name, func_name));
+            interpreter.finish_library_call(&lib_name, result);
         },
         Err(err) => {
             panic!("调用库函数 {}::{} 失败: {}", lib_name, func_name, err);
@@ -2,7 +2,7 @@ use crate::ast::{Expression, NamespaceType};
 use crate::interpreter::executor::ExecutionResult;
 use crate::interpreter::interpreter_core::{Interpreter, debug_println};
 use crate::interpreter::expression_evaluator::ExpressionEvaluator;
-use crate::interpreter::library_loader::{load_library, call_library_function, convert_values_to_string_args};
+use crate::interpreter::library_loader::{load_library, call_library_function, convert_values_to_string_args, retain_library_callbacks};
 use crate::interpreter::value::Value;
 
 pub fn handle_import_namespace(interpreter: &mut Interpreter, ns_type: NamespaceType, path: Vec<String>) -> ExecutionResult {
@@ -175,6 +175,8 @@ pub fn handle_namespaced_function_call_statement(interpreter: &mut Interpreter,
         match call_library_function(lib_name, &func_name, string_args) {
             Ok(result) => {
                 debug_println(&format!("库函数调用成功: {} -> {}", func_name, result));
+                // 返回值不需要，但库可能请求回调脚本函数
+                interpreter.finish_library_call(&lib_name.clone(), result);
                 return ExecutionResult::None;
             },
             Err(err) => {
@@ -200,6 +202,7 @@ pub fn handle_namespaced_function_call_statement(interpreter: &mut Interpreter,
         if let Some(func) = lib_functions.get(&full_path) {
             debug_println(&format!("在库 '{}' 中找到命名空间函数 '{}', 调用之", lib_name, full_path));
             let string_args = convert_values_to_string_args(&arg_values);
+            retain_library_callbacks(lib_name, &full_path, &string_args);
             let _ = func(string_args); // 忽略返回值（如有需要可处理）
             return ExecutionResult::None;
         }
@@ -150,6 +150,10 @@ pub struct Interpreter<'a> {
     pub timeout_duration: std::time::Duration,
     pub operation_count: usize,
     pub max_operations: usize,
+    // 正在执行的库回调层数，大于0时函数中未捕获的异常交给调用处继续抛出，而不是结束程序
+    pub library_callback_depth: usize,
+    // 函数中未捕获、等待调用处的语句继续抛出的异常
+    pub pending_exception: Option<Value>,
 }
 
 impl<'a> Interpreter<'a> {
@@ -196,6 +200,8 @@ impl<'a> Interpreter<'a> {
             timeout_duration: std::time::Duration::from_secs(30), // 默认30秒超时
             operation_count: 0,
             max_operations: 1_000_000, // 默认最大100万次操作
+            library_callback_depth: 0,
+            pending_exception: None,
         };
         
         // 初始化常量
@@ -346,6 +352,11 @@ impl<'a> Interpreter<'a> {
     
     // 辅助函数：调用函数并处理参数
     pub fn call_function_impl(&mut self, function: &'a crate::ast::Function, arg_values: Vec<Value>) -> Value {
+        // 同一语句中之前调用的函数已经抛出异常，不再调用后面的函数
+        if self.pending_exception.is_some() {
+            return Value::None;
+        }
+        
         // 保存当前的局部环境
         let old_local_env = self.local_env.clone();
         
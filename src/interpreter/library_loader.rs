@@ -1,6 +1,7 @@
 use std::path::{Path, PathBuf};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, RwLock};
+use std::cell::RefCell;
 use std::env;
 use std::fs;
 use std::io::Read;
@@ -8,7 +9,7 @@ use libloading::{Library, Symbol};
 use once_cell::sync::Lazy;
 use dashmap::DashMap;
 use crate::interpreter::debug_println;
-use crate::interpreter::value::Value;
+use crate::interpreter::value::{LambdaFunctionPointerInstance, Value};
 
 // 🚀 v0.6.0 LLL优化：使用无锁并发HashMap替代全局锁
 // DashMap提供了高性能的并发访问，无需全局锁
@@ -19,6 +20,14 @@ static LOADED_LIBRARIES: Lazy<DashMap<String, Arc<Library>>> =
 static FUNCTION_CACHE: Lazy<DashMap<String, Arc<HashMap<String, LibraryFunction>>>> =
     Lazy::new(|| DashMap::new());
 
+// 库中的续接函数，只在回调协议中调用，不作为脚本可见的函数；键为库名
+static CONTINUATIONS: Lazy<DashMap<String, Arc<HashMap<String, LibraryFunction>>>> =
+    Lazy::new(DashMap::new);
+
+// 库中会保存回调函数的函数，传给它们的Lambda不随调用结束而释放；键为库名
+static RETAINING_FUNCTIONS: Lazy<DashMap<String, HashSet<String>>> =
+    Lazy::new(DashMap::new);
+
 // 📊 性能统计（可选，用于监控优化效果）
 use std::sync::atomic::{AtomicU64, Ordering};
 static CACHE_HITS: AtomicU64 = AtomicU64::new(0);
@@ -247,7 +256,23 @@ fn extract_library_functions(lib: &Arc<Library>, lib_name: &str) -> Result<Arc<H
 
         // 将原始指针转换为HashMap，然后包装为Arc
         let boxed_functions = Box::from_raw(functions_ptr);
-        let functions = *boxed_functions; // 解引用Box<HashMap>为HashMap
+        let all_functions = *boxed_functions; // 解引用Box<HashMap>为HashMap
+
+        // 续接函数和保存回调的标记单独登记，不作为脚本可见的函数
+        let mut functions = HashMap::new();
+        let mut continuations = HashMap::new();
+        let mut retaining = HashSet::new();
+        for (func_name, func) in all_functions {
+            if let Some(name) = func_name.strip_prefix(CONTINUATION_PREFIX) {
+                continuations.insert(name.to_string(), func);
+            } else if let Some(name) = func_name.strip_prefix(RETAINS_CALLBACKS_PREFIX) {
+                retaining.insert(name.to_string());
+            } else {
+                functions.insert(func_name, func);
+            }
+        }
+        CONTINUATIONS.insert(lib_name.to_string(), Arc::new(continuations));
+        RETAINING_FUNCTIONS.insert(lib_name.to_string(), retaining);
 
         // 调试输出函数列表
         debug_println(&format!("📋 库 '{}' 中的函数:", lib_name));
@@ -262,6 +287,7 @@ fn extract_library_functions(lib: &Arc<Library>, lib_name: &str) -> Result<Arc<H
 // 🚀 v0.6.0 LLL优化：超高速库函数调用
 pub fn call_library_function(lib_name: &str, func_name: &str, args: Vec<String>) -> Result<String, String> {
     debug_println(&format!("🚀 快速调用: {}::{}", lib_name, func_name));
+    retain_library_callbacks(lib_name, func_name, &args);
 
     // 🔥 直接从函数缓存获取（最快路径）
     if let Some(functions) = FUNCTION_CACHE.get(lib_name) {
@@ -285,6 +311,16 @@ pub fn call_library_function(lib_name: &str, func_name: &str, args: Vec<String>)
     }
 }
 
+// 调用库的续接函数，回调协议专用
+// 早期的库把续接函数注册为普通函数，找不到续接函数时按普通函数调用
+pub fn call_library_continuation(lib_name: &str, func_name: &str, args: Vec<String>) -> Result<String, String> {
+    let continuation = CONTINUATIONS.get(lib_name).and_then(|continuations| continuations.get(func_name).copied());
+    match continuation {
+        Some(func) => Ok(func(args)),
+        None => call_library_function(lib_name, func_name, args),
+    }
+}
+
 // 🚀 v0.6.0 新增：性能统计和缓存管理函数
 
 /// 获取库加载性能统计
@@ -341,18 +377,8 @@ pub fn convert_value_to_string_arg(value: &Value) -> String {
         Value::Bool(b) => b.to_string(),
         Value::String(s) => s.clone(),
         Value::Long(l) => l.to_string(),
-        Value::Array(arr) => {
-            let elements: Vec<String> = arr.iter()
-                .map(|v| convert_value_to_string_arg(v))
-                .collect();
-            format!("[{}]", elements.join(", "))
-        },
-        Value::Map(map) => {
-            let entries: Vec<String> = map.iter()
-                .map(|(k, v)| format!("{}:{}", k, convert_value_to_string_arg(v)))
-                .collect();
-            format!("{{{}}}", entries.join(", "))
-        },
+        // 数组和映射以JSON形式传递，库可以直接按JSON解析（早期为 [a, b]、{k:v} 形式的非JSON文本）
+        Value::Array(_) | Value::Map(_) => value_to_json(value).to_string(),
         Value::Object(obj) => {
             format!("{}@{:p}", obj.class_name, obj)
         },
@@ -395,7 +421,8 @@ pub fn convert_value_to_string_arg(value: &Value) -> String {
             if lambda_ptr.is_null {
                 "null".to_string()
             } else {
-                "*fn(lambda)".to_string()
+                // 带上编号，使库可以把Lambda作为回调函数交还给解释器
+                format!("*fn(lambda#{})", register_library_lambda(lambda_ptr))
             }
         },
         Value::ArrayPointer(array_ptr) => {
@@ -415,4 +442,174 @@ pub fn convert_value_to_string_arg(value: &Value) -> String {
 // 从Vector<Value>转换为Vector<String>，用于库函数调用
 pub fn convert_values_to_string_args(values: &[Value]) -> Vec<String> {
     values.iter().map(|v| convert_value_to_string_arg(v)).collect()
-} 
\ No newline at end of file
+}
+
+// 结构化返回值前缀（与 cn_common::value::JSON_VALUE_PREFIX 保持一致）
+// 以该前缀开头的返回值按JSON解析为原生的 Map/Array 等值，而不是字符串
+const JSON_VALUE_PREFIX: &str = "\u{1}cn-json:";
+
+// 将回调函数的返回值转换为续接函数的参数
+// Map 和数组带上结构化前缀，库可以把它们与内容恰好是JSON的字符串区分开
+pub fn convert_callback_result_arg(value: &Value) -> String {
+    match value {
+        Value::Array(_) | Value::Map(_) => format!("{}{}", JSON_VALUE_PREFIX, value_to_json(value)),
+        other => convert_value_to_string_arg(other),
+    }
+}
+
+// 将Value转换为JSON值，用于以JSON形式向库传递数组和映射
+fn value_to_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::Int(i) => serde_json::Value::from(*i),
+        Value::Long(l) => serde_json::Value::from(*l),
+        Value::Float(f) => serde_json::Number::from_f64(*f)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        Value::Bool(b) => serde_json::Value::Bool(*b),
+        Value::String(s) => serde_json::Value::String(s.clone()),
+        Value::Array(arr) => serde_json::Value::Array(arr.iter().map(value_to_json).collect()),
+        Value::Map(map) => serde_json::Value::Object(
+            map.iter().map(|(k, v)| (k.clone(), value_to_json(v))).collect()
+        ),
+        Value::None => serde_json::Value::Null,
+        other => serde_json::Value::String(convert_value_to_string_arg(other)),
+    }
+}
+
+// 将JSON值转换为Value：对象转换为Map，数组转换为Array，null转换为None
+// 超出i64范围的整数转换为字符串，避免转为浮点数后丢失精度
+fn json_to_value(json: serde_json::Value) -> Value {
+    match json {
+        serde_json::Value::Null => Value::None,
+        serde_json::Value::Bool(b) => Value::Bool(b),
+        serde_json::Value::Number(n) => {
+            match n.as_i64() {
+                Some(i) => match i32::try_from(i) {
+                    Ok(i) => Value::Int(i),
+                    Err(_) => Value::Long(i),
+                },
+                None if n.is_u64() => Value::String(n.to_string()),
+                None => Value::Float(n.as_f64().unwrap_or(f64::NAN)),
+            }
+        },
+        serde_json::Value::String(s) => Value::String(s),
+        serde_json::Value::Array(items) => Value::Array(items.into_iter().map(json_to_value).collect()),
+        serde_json::Value::Object(obj) => Value::Map(
+            obj.into_iter().map(|(k, v)| (k, json_to_value(v))).collect()
+        ),
+    }
+}
+
+// 回调请求前缀（与 cn_common::value::CALLBACK_PREFIX 保持一致）
+// 以该前缀开头的返回值表示库请求调用脚本函数，格式为 前缀 + 续接函数 + \u{1} + 脚本函数 + \u{1} + JSON参数数组
+const CALLBACK_PREFIX: &str = "\u{1}cn-call:";
+
+// 回调异常前缀（与 cn_common::value::CALLBACK_THROW_PREFIX 保持一致）
+// 回调函数抛出未捕获的异常时，续接函数收到 前缀 + 异常信息
+pub const CALLBACK_THROW_PREFIX: &str = "\u{1}cn-throw:";
+
+// 续接函数名前缀（与 cn_common::value::CONTINUATION_PREFIX 保持一致）
+const CONTINUATION_PREFIX: &str = "\u{1}cn-resume:";
+
+// 保存回调的函数的标记前缀（与 cn_common::value::RETAINS_CALLBACKS_PREFIX 保持一致）
+const RETAINS_CALLBACKS_PREFIX: &str = "\u{1}cn-retain:";
+
+// 传给库函数的Lambda，retained 表示库保存了它（如路由处理函数），需要在之后的调用中回调
+struct LibraryLambda {
+    lambda: LambdaFunctionPointerInstance,
+    retained: bool,
+}
+
+thread_local! {
+    // 传给库函数的Lambda，键是Lambda创建时分配的编号；库以 *fn(lambda#编号) 的形式引用，回调时按编号取回
+    // 未被保存的Lambda在最外层的库函数调用结束后由 release_library_lambdas 释放
+    static LIBRARY_LAMBDAS: RefCell<HashMap<usize, LibraryLambda>> = RefCell::new(HashMap::new());
+}
+
+// 登记传给库函数的Lambda并返回其编号，同一个Lambda重复传入时只登记一次
+fn register_library_lambda(lambda: &LambdaFunctionPointerInstance) -> usize {
+    LIBRARY_LAMBDAS.with(|lambdas| {
+        lambdas.borrow_mut().entry(lambda.lambda_id).or_insert_with(|| LibraryLambda { lambda: lambda.clone(), retained: false });
+    });
+    lambda.lambda_id
+}
+
+// 按回调请求中的函数名（lambda#编号）取回登记过的Lambda
+pub fn library_lambda(function: &str) -> Option<LambdaFunctionPointerInstance> {
+    let id = function.strip_prefix("lambda#")?.parse::<usize>().ok()?;
+    LIBRARY_LAMBDAS.with(|lambdas| lambdas.borrow().get(&id).map(|entry| entry.lambda.clone()))
+}
+
+// 调用会保存回调的库函数时，保留参数中引用的Lambda
+// 参数: lib_name, func_name, 已转换为字符串的参数
+pub fn retain_library_callbacks(lib_name: &str, func_name: &str, args: &[String]) {
+    let retains = RETAINING_FUNCTIONS.get(lib_name).is_some_and(|functions| functions.contains(func_name));
+    if !retains {
+        return;
+    }
+    LIBRARY_LAMBDAS.with(|lambdas| {
+        let mut lambdas = lambdas.borrow_mut();
+        for arg in args {
+            for (pos, marker) in arg.match_indices("*fn(lambda#") {
+                let digits: String = arg[pos + marker.len()..].chars().take_while(|c| c.is_ascii_digit()).collect();
+                if let Some(entry) = digits.parse::<usize>().ok().and_then(|id| lambdas.get_mut(&id)) {
+                    entry.retained = true;
+                }
+            }
+        }
+    });
+}
+
+// 释放没有被库保存的Lambda，在最外层的库函数调用结束后调用
+pub fn release_library_lambdas() {
+    LIBRARY_LAMBDAS.with(|lambdas| lambdas.borrow_mut().retain(|_, entry| entry.retained));
+}
+
+// 库函数请求的一次脚本函数回调
+pub struct LibraryCallback {
+    pub function: String,
+    pub args: Vec<Value>,
+    pub resume: String,
+}
+
+// 解析库函数返回的回调请求，不是回调请求时返回 None
+pub fn parse_library_callback(result: &str) -> Option<LibraryCallback> {
+    let mut parts = result.strip_prefix(CALLBACK_PREFIX)?.splitn(3, '\u{1}');
+    let resume = parts.next()?.to_string();
+    let function = parts.next()?;
+    let args = match serde_json::from_str::<serde_json::Value>(parts.next()?) {
+        Ok(serde_json::Value::Array(items)) => items.into_iter().map(json_to_value).collect(),
+        _ => return None,
+    };
+
+    // 函数指针以 *fn(name) 的形式传给库，函数引用以 function_ref(name) 的形式传给库
+    let function = function.strip_prefix("*fn(")
+        .or_else(|| function.strip_prefix("function_ref("))
+        .and_then(|name| name.strip_suffix(')'))
+        .unwrap_or(function)
+        .to_string();
+
+    Some(LibraryCallback { function, args, resume })
+}
+
+// 将库函数返回的字符串转换为适当的值类型
+pub fn convert_library_result(result: String) -> Value {
+    if let Some(json) = result.strip_prefix(JSON_VALUE_PREFIX) {
+        return match serde_json::from_str::<serde_json::Value>(json) {
+            Ok(parsed) => json_to_value(parsed),
+            Err(_) => Value::String(json.to_string()),
+        };
+    }
+
+    if let Ok(int_val) = result.parse::<i32>() {
+        Value::Int(int_val)
+    } else if let Ok(float_val) = result.parse::<f64>() {
+        Value::Float(float_val)
+    } else if result == "true" {
+        Value::Bool(true)
+    } else if result == "false" {
+        Value::Bool(false)
+    } else {
+        Value::String(result)
+    }
+}
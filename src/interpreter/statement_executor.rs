@@ -22,7 +22,7 @@ impl<'a> StatementExecutor for Interpreter<'a> {
             return ExecutionResult::Error(timeout_msg);
         }
 
-        match statement {
+        let result = match statement {
             Statement::Return(expr) => {
                 // 返回语句，计算表达式值并返回
                 let value = if let Some(expr) = expr {
@@ -34,7 +34,11 @@ impl<'a> StatementExecutor for Interpreter<'a> {
             },
             Statement::VariableDeclaration(name, declared_type, expr) => {
                 let mut value = self.evaluate_expression(&expr);
-                
+                // 初始化表达式中调用的函数抛出了异常，不再声明变量
+                if let Some(exception) = self.pending_exception.take() {
+                    return ExecutionResult::Throw(exception);
+                }
+
                 // 如果声明的类型是 Auto，则不进行类型检查（弱类型）
                 if !matches!(declared_type, Type::Auto) {
                     // 进行强类型检查，包括自动类型转换
@@ -145,6 +149,9 @@ impl<'a> StatementExecutor for Interpreter<'a> {
             Statement::ConstantDeclaration(name, typ, expr) => {
                 // 计算常量值
                 let value = self.evaluate_expression(&expr);
+                if let Some(exception) = self.pending_exception.take() {
+                    return ExecutionResult::Throw(exception);
+                }
                 
                 // 检查类型是否匹配
                 let type_matches = match (&typ, &value) {
@@ -177,6 +184,9 @@ impl<'a> StatementExecutor for Interpreter<'a> {
                 }
                 
                 let mut value = self.evaluate_expression(&expr);
+                if let Some(exception) = self.pending_exception.take() {
+                    return ExecutionResult::Throw(exception);
+                }
 
                 // 检查变量是否存在
                 let variable_exists = self.local_env.contains_key(&name) || self.global_env.contains_key(&name);
@@ -411,6 +421,12 @@ impl<'a> StatementExecutor for Interpreter<'a> {
                 // 枚举声明在解释器初始化时已经处理，这里不需要额外操作
                 ExecutionResult::Continue
             },
+        };
+
+        // 语句中调用的函数抛出了未捕获的异常，在此继续向上抛出，外层的 try/catch 可以捕获
+        match self.pending_exception.take() {
+            Some(exception) => ExecutionResult::Throw(exception),
+            None => result,
         }
     }
     
@@ -435,6 +451,11 @@ impl<'a> StatementExecutor for Interpreter<'a> {
                 },
                 ExecutionResult::Throw(value) => {
                     self.namespace_import_stack.pop();
+                    if self.library_callback_depth > 0 {
+                        // 交给调用处的语句继续抛出，最终由 call_library_callback 交给库处理
+                        self.pending_exception = Some(value);
+                        return Value::None;
+                    }
                     panic!("未捕获的异常: {:?}", value);
                 },
                 ExecutionResult::Error(msg) => {
@@ -491,6 +512,9 @@ impl<'a> Interpreter<'a> {
     fn execute_switch_statement(&mut self, expr: Expression, cases: Vec<SwitchCase>, default_block: Option<Vec<Statement>>, switch_type: SwitchType) -> ExecutionResult {
         // 计算 switch 表达式的值
         let switch_value = self.evaluate_expression(&expr);
+        if self.pending_exception.is_some() {
+            return ExecutionResult::None;
+        }
         // debug_println(&format!("Switch value: {:?}", switch_value));
         
         let mut matched = false;
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use crate::ast::{Parameter, Expression, Statement};
 
 /// 指针操作错误类型
@@ -185,6 +186,15 @@ pub struct LambdaFunctionPointerInstance {
     pub lambda_body: Option<Box<crate::ast::Statement>>, // Lambda函数体
     pub lambda_params: Vec<crate::ast::Parameter>, // 完整的参数信息（包含名称）
     pub closure_env: std::collections::HashMap<String, Value>, // 闭包环境
+    pub lambda_id: usize, // 创建时分配的编号，复制出的实例共用同一编号
+}
+
+// 下一个Lambda编号
+static NEXT_LAMBDA_ID: AtomicUsize = AtomicUsize::new(0);
+
+// 为新创建的Lambda分配编号
+pub fn next_lambda_id() -> usize {
+    NEXT_LAMBDA_ID.fetch_add(1, Ordering::Relaxed)
 }
 
 impl PartialEq for LambdaFunctionPointerInstance {
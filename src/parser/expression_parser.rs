@@ -741,6 +741,14 @@ impl<'a> ExpressionParser for ParserBase<'a> {
                         let array_expr = Expression::Variable(name);
                         let mut result = Expression::ArrayAccess(Box::new(array_expr), Box::new(index_expr));
 
+                        // 连续索引访问，例如 data["items"][0]
+                        while self.peek() == Some(&"[".to_string()) {
+                            self.consume(); // 消费 "["
+                            let next_index = self.parse_expression()?;
+                            self.expect("]")?;
+                            result = Expression::ArrayAccess(Box::new(result), Box::new(next_index));
+                        }
+
                         // 检查是否有后续的函数调用
                         if self.peek() == Some(&"(".to_string()) {
                             self.consume(); // 消费 "("